@@ -10,33 +10,20 @@ fn main() -> anyhow::Result<()> {
     let mut canvas = Canvas::new(1000);
 
     // Draw face
-    canvas.draw_circle(
-        Vec2::ZERO,
-        1.0,
-        None,
-        Some(Color::from_hex("#fecb00").unwrap()),
-    );
+    canvas.draw_circle(Vec2::ZERO, 1.0, None, Some(Color::from_hex("#fecb00")?));
 
     // Draw eyes
     canvas.draw_line(
         (-0.5, 0.25),
         (-0.5, 0.0),
-        Some(Stroke {
-            color: Color::black(),
-            width: 0.2,
-            line_end: LineEnd::Round,
-        }),
+        Some(Stroke::new(Color::black(), 0.2, LineEnd::Round)),
         None,
     );
 
     canvas.draw_line(
         (0.5, 0.25),
         (0.5, 0.0),
-        Some(Stroke {
-            color: Color::black(),
-            width: 0.2,
-            line_end: LineEnd::Round,
-        }),
+        Some(Stroke::new(Color::black(), 0.2, LineEnd::Round)),
         None,
     );
 
@@ -50,7 +37,7 @@ fn main() -> anyhow::Result<()> {
     );
 
     // Save to png
-    let png = canvas.render(SkiaRenderer::new(UVec2::splat(1000), None, true, true));
+    let png = canvas.render(SkiaRenderer::new(UVec2::splat(1000), None, true, true)?)?;
     png.save("smile.png")?;
 
     // Save to svg
@@ -60,7 +47,7 @@ fn main() -> anyhow::Result<()> {
         false,
         false,
         32,
-    ));
+    )?)?;
 
     std::fs::write("smile.svg", svg)?;
 
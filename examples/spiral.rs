@@ -35,7 +35,7 @@ fn main() -> anyhow::Result<()> {
             Some(Color::black()),
             true,
             false,
-        ))
+        )?)?
         .save("spiral.png")?;
 
     Ok(())
@@ -0,0 +1,161 @@
+use glam::Vec2;
+
+use crate::{rng::Rng, ScalarField};
+
+/// A grid of trail left behind by agents, which spreads out and fades over time.
+#[deprecated(note = "use `ScalarField`, which the trail of a `Physarum` simulation now is")]
+pub type TrailGrid = ScalarField;
+
+/// One agent of a [Physarum] simulation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Agent {
+    /// Where the agent is, in cells of the trail grid.
+    pub position: Vec2,
+    /// The direction the agent is moving, in radians counter-clockwise from the x axis.
+    pub heading: f32,
+}
+
+/// A slime mold simulation, after Jeff Jones' model of Physarum polycephalum.
+///
+/// Each agent senses the trail ahead of it, to the left, and to the right, turns towards the strongest, moves forward and deposits more trail.
+/// The trail then spreads and fades. Over many [steps](Self::step), agents following each other's trails form branching networks.
+/// The grid wraps around at its edges. The same seed always produces the same simulation.
+#[derive(Debug, Clone)]
+pub struct Physarum {
+    /// The agents, which may be moved or added to between steps.
+    pub agents: Vec<Agent>,
+    /// The trail the agents follow.
+    pub trail: ScalarField,
+    /// Angle between straight ahead and each side sensor, in radians.
+    pub sensor_angle: f32,
+    /// How far ahead of an agent its sensors are, in cells.
+    pub sensor_distance: f32,
+    /// How far an agent turns towards a stronger trail each step, in radians.
+    pub turn_angle: f32,
+    /// How far an agent moves each step, in cells.
+    pub step_size: f32,
+    /// How much trail an agent leaves behind each step.
+    pub deposit: f32,
+    /// How much the trail spreads into neighbouring cells each step, from 0.0 to 1.0.
+    pub diffusion: f32,
+    /// How much of the trail fades away each step, from 0.0 to 1.0.
+    pub decay: f32,
+    rng: Rng,
+}
+
+impl Physarum {
+    /// Create a new [Physarum] simulation on a grid `width` cells across and `height` cells up,
+    /// with `agents` agents scattered at random positions and headings.
+    pub fn new(width: usize, height: usize, agents: usize, seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        let agents = (0..agents)
+            .map(|_| Agent {
+                position: Vec2::new(rng.range(0.0, width as f32), rng.range(0.0, height as f32)),
+                heading: rng.range(0.0, std::f32::consts::TAU),
+            })
+            .collect();
+
+        Self {
+            agents,
+            trail: ScalarField::new(width, height),
+            sensor_angle: 45f32.to_radians(),
+            sensor_distance: 9.0,
+            turn_angle: 45f32.to_radians(),
+            step_size: 1.0,
+            deposit: 5.0,
+            diffusion: 1.0,
+            decay: 0.1,
+            rng,
+        }
+    }
+
+    /// Advance the simulation by one step: every agent senses, turns, moves and deposits, then the trail spreads and fades.
+    pub fn step(&mut self) {
+        let size = Vec2::new(self.trail.width as f32, self.trail.height as f32);
+        // Tiny negative values can wrap around to exactly the size, which is just off the grid.
+        let wrap = |value: f32, size: f32| {
+            let value = value.rem_euclid(size);
+            if value >= size {
+                0.0
+            } else {
+                value
+            }
+        };
+
+        for agent in &mut self.agents {
+            let sense = |angle: f32| {
+                let direction = Vec2::new(angle.cos(), angle.sin());
+                self.trail
+                    .get(agent.position + direction * self.sensor_distance)
+            };
+            let left = sense(agent.heading + self.sensor_angle);
+            let ahead = sense(agent.heading);
+            let right = sense(agent.heading - self.sensor_angle);
+
+            if ahead >= left && ahead >= right {
+                // Keep going straight.
+            } else if left > ahead && right > ahead {
+                let turn = if self.rng.next_f32() < 0.5 { 1.0 } else { -1.0 };
+                agent.heading += turn * self.turn_angle;
+            } else if left > right {
+                agent.heading += self.turn_angle;
+            } else {
+                agent.heading -= self.turn_angle;
+            }
+
+            let direction = Vec2::new(agent.heading.cos(), agent.heading.sin());
+            let position = agent.position + direction * self.step_size;
+            agent.position = Vec2::new(wrap(position.x, size.x), wrap(position.y, size.y));
+            self.trail.add(agent.position, self.deposit);
+        }
+
+        self.trail.diffuse(self.diffusion, self.decay);
+    }
+
+    /// Advance the simulation by `steps` steps.
+    pub fn run(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "tiny_skia_renderer")]
+    use crate::{Color, Gradient};
+
+    /// Verify that agents stay on the grid, and that their trail gathers into bright paths while the rest fades.
+    #[test]
+    fn physarum_trails() {
+        let mut simulation = Physarum::new(64, 48, 400, 1);
+        simulation.run(60);
+
+        assert!(simulation.agents.iter().all(|agent| {
+            (0.0..64.0).contains(&agent.position.x) && (0.0..48.0).contains(&agent.position.y)
+        }));
+
+        let max = simulation.trail.max();
+        let values = simulation.trail.values();
+        let mean = values.iter().sum::<f32>() / values.len() as f32;
+        assert!(max > 4.0 * mean);
+
+        #[cfg(feature = "tiny_skia_renderer")]
+        {
+            let heatmap = simulation
+                .trail
+                .heatmap(&Gradient::new(Color::black(), Color::white()));
+            assert_eq!(heatmap.dimensions(), (64, 48));
+
+            let shapes = simulation.trail.to_shapes(0.5, Color::black());
+            assert!(!shapes.is_empty());
+            assert!(shapes
+                .iter()
+                .flat_map(|shape| shape.paths().flatten())
+                .all(|point| point.cmpge(Vec2::ZERO).all()
+                    && point.cmple(Vec2::new(64.0, 48.0)).all()));
+        }
+    }
+}
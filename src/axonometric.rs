@@ -0,0 +1,274 @@
+use glam::{Vec2, Vec3};
+
+use crate::{Color, Shape, Stroke};
+
+/// A parallel projection of 3D points onto the canvas, given by where each 3D axis points on the page.
+///
+/// The x and y axes lie on the ground and the z axis points up. Every projection is seen from in front (negative y) and above,
+/// so the faces towards `-y` and `+z` are the ones in view.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Axonometric {
+    /// Where one unit along the x axis lands on the canvas.
+    pub x_axis: Vec2,
+    /// Where one unit along the y axis lands on the canvas.
+    pub y_axis: Vec2,
+    /// Where one unit along the z axis lands on the canvas.
+    pub z_axis: Vec2,
+}
+
+impl Axonometric {
+    /// Create a new [Axonometric] projection from where each axis lands on the canvas.
+    pub fn new(x_axis: Vec2, y_axis: Vec2, z_axis: Vec2) -> Self {
+        Self {
+            x_axis,
+            y_axis,
+            z_axis,
+        }
+    }
+
+    /// The isometric projection, with the ground axes 30° either side of horizontal and every axis at full length.
+    pub fn isometric() -> Self {
+        let angle = 30f32.to_radians();
+        Self::new(
+            Vec2::new(angle.cos(), -angle.sin()),
+            Vec2::new(angle.cos(), angle.sin()),
+            Vec2::Y,
+        )
+    }
+
+    /// The dimetric projection common in pixel art, with the ground axes rising one unit for every two across.
+    pub fn dimetric() -> Self {
+        let angle = 0.5f32.atan();
+        Self::new(
+            Vec2::new(angle.cos(), -angle.sin()),
+            Vec2::new(angle.cos(), angle.sin()),
+            Vec2::Y,
+        )
+    }
+
+    /// An oblique projection, where the front (x and z) is drawn flat and true to size, and depth recedes at `angle` radians from horizontal, scaled by `depth_scale`.
+    pub fn oblique(angle: f32, depth_scale: f32) -> Self {
+        Self::new(
+            Vec2::X,
+            Vec2::new(angle.cos(), angle.sin()) * depth_scale,
+            Vec2::Y,
+        )
+    }
+
+    /// The cabinet projection: an [oblique](Self::oblique) projection with depth at 45° and half length.
+    pub fn cabinet() -> Self {
+        Self::oblique(45f32.to_radians(), 0.5)
+    }
+
+    /// The cavalier projection: an [oblique](Self::oblique) projection with depth at 45° and full length.
+    pub fn cavalier() -> Self {
+        Self::oblique(45f32.to_radians(), 1.0)
+    }
+
+    /// Project a 3D point onto the canvas.
+    pub fn project(&self, point: Vec3) -> Vec2 {
+        self.x_axis * point.x + self.y_axis * point.y + self.z_axis * point.z
+    }
+
+    /// The direction in 3D that points towards the viewer. Every point along it projects onto the same spot.
+    pub fn toward_viewer(&self) -> Vec3 {
+        let across = Vec3::new(self.x_axis.x, self.y_axis.x, self.z_axis.x);
+        let up = Vec3::new(self.x_axis.y, self.y_axis.y, self.z_axis.y);
+        let direction = across.cross(up).normalize_or_zero();
+
+        if direction.z < 0.0 {
+            -direction
+        } else {
+            direction
+        }
+    }
+
+    /// Project `solids` into filled shapes, ordered from back to front, with the faces pointing away from the viewer left out.
+    ///
+    /// Faces are shaded by how directly they face the light, and sorted by the depth of their centers,
+    /// which is right for separate boxes and prisms but can fail when faces interlock.
+    pub fn shapes(
+        &self,
+        solids: &[Solid],
+        shading: &Shading,
+        stroke: Option<Stroke>,
+    ) -> Vec<Shape> {
+        let toward_viewer = self.toward_viewer();
+        let light = shading.light.normalize_or_zero();
+
+        let mut faces: Vec<(f32, Shape)> = solids
+            .iter()
+            .flat_map(|solid| solid.faces.iter().map(move |face| (solid, face)))
+            .filter_map(|(solid, face)| {
+                let normal = face_normal(face);
+                if face.len() < 3 || normal.dot(toward_viewer) <= 1e-6 {
+                    return None;
+                }
+
+                let center =
+                    face.iter().fold(Vec3::ZERO, |sum, &point| sum + point) / face.len() as f32;
+                let brightness =
+                    shading.ambient + (1.0 - shading.ambient) * normal.dot(light).max(0.0);
+                let fill = Color::new(
+                    solid.fill.r() * brightness,
+                    solid.fill.g() * brightness,
+                    solid.fill.b() * brightness,
+                    solid.fill.a(),
+                );
+
+                let mut points: Vec<Vec2> = face.iter().map(|&point| self.project(point)).collect();
+                points.push(points[0]);
+                let shape = Shape {
+                    points,
+                    stroke,
+                    fill: Some(fill),
+                    ..Default::default()
+                };
+                Some((center.dot(toward_viewer), shape))
+            })
+            .collect();
+
+        faces.sort_by(|a, b| a.0.total_cmp(&b.0));
+        faces.into_iter().map(|(_, shape)| shape).collect()
+    }
+}
+
+/// A closed 3D solid made of flat faces, such as a box or a prism, to draw with an [Axonometric] projection.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Solid {
+    /// The corners of each face, counter-clockwise when seen from outside the solid.
+    pub faces: Vec<Vec<Vec3>>,
+    /// The color of a face that looks straight at the light.
+    pub fill: Color,
+}
+
+impl Solid {
+    /// A box with its lowest corner at `min`, extending `size` along each axis.
+    pub fn cuboid(min: Vec3, size: Vec3, fill: Color) -> Self {
+        let base = [
+            min.truncate(),
+            min.truncate() + Vec2::new(size.x, 0.0),
+            min.truncate() + size.truncate(),
+            min.truncate() + Vec2::new(0.0, size.y),
+        ];
+        Self::prism(&base, min.z, size.z, fill)
+    }
+
+    /// A polygon on the ground, lifted to `bottom` and extruded straight up by `height`, such as a building from its footprint.
+    ///
+    /// The base may be given in either winding, and may be closed or open.
+    pub fn prism(base: &[Vec2], bottom: f32, height: f32, fill: Color) -> Self {
+        let mut base = base.to_vec();
+        if base.len() > 1 && base.first() == base.last() {
+            base.pop();
+        }
+
+        let area: f32 = base
+            .iter()
+            .zip(base.iter().cycle().skip(1))
+            .map(|(a, b)| a.perp_dot(*b))
+            .sum();
+        if area < 0.0 {
+            base.reverse();
+        }
+
+        let top = bottom + height;
+        let mut faces = vec![
+            base.iter()
+                .rev()
+                .map(|point| point.extend(bottom))
+                .collect(),
+            base.iter().map(|point| point.extend(top)).collect(),
+        ];
+        for (a, b) in base.iter().zip(base.iter().cycle().skip(1)) {
+            faces.push(vec![
+                a.extend(bottom),
+                b.extend(bottom),
+                b.extend(top),
+                a.extend(top),
+            ]);
+        }
+
+        Self { faces, fill }
+    }
+}
+
+/// How the faces of [Solids](Solid) are lit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Shading {
+    /// The direction towards the light.
+    pub light: Vec3,
+    /// How bright a face turned away from the light is, from 0.0 (black) to 1.0 (no shading).
+    pub ambient: f32,
+}
+
+impl Shading {
+    /// Create a new [Shading].
+    pub fn new(light: Vec3, ambient: f32) -> Self {
+        Self { light, ambient }
+    }
+}
+
+impl Default for Shading {
+    /// Light from above, slightly to the left and front, so that the top, front and side of a box are each a different shade.
+    fn default() -> Self {
+        Self::new(Vec3::new(-0.4, -0.6, 1.0), 0.4)
+    }
+}
+
+/// The unit normal of a flat face, by Newell's method, pointing out of the side the corners wind counter-clockwise around.
+pub(crate) fn face_normal(face: &[Vec3]) -> Vec3 {
+    face.iter()
+        .zip(face.iter().cycle().skip(1))
+        .map(|(a, b)| {
+            Vec3::new(
+                (a.y - b.y) * (a.z + b.z),
+                (a.z - b.z) * (a.x + b.x),
+                (a.x - b.x) * (a.y + b.y),
+            )
+        })
+        .fold(Vec3::ZERO, |sum, normal| sum + normal)
+        .normalize_or_zero()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that a box shows exactly its top, front and right faces, each a different shade, with the top drawn last.
+    #[test]
+    fn cuboid_faces() {
+        let projections = [
+            Axonometric::isometric(),
+            Axonometric::dimetric(),
+            Axonometric::cabinet(),
+        ];
+        let cube = Solid::cuboid(Vec3::ZERO, Vec3::ONE, Color::white());
+
+        for projection in &projections {
+            assert!(projection.toward_viewer().y < 0.0);
+            assert_eq!(
+                projection.project(Vec3::new(1.0, 2.0, 3.0)),
+                projection.x_axis + projection.y_axis * 2.0 + projection.z_axis * 3.0
+            );
+
+            let shapes = projection.shapes(std::slice::from_ref(&cube), &Shading::default(), None);
+            assert_eq!(shapes.len(), 3);
+
+            let mut shades: Vec<f32> = shapes.iter().map(|shape| shape.fill.unwrap().r()).collect();
+            shades.dedup();
+            assert_eq!(shades.len(), 3);
+        }
+
+        // Boxes further back are drawn first.
+        let far = Solid::cuboid(Vec3::new(0.0, 3.0, 0.0), Vec3::ONE, Color::black());
+        let shapes = Axonometric::isometric().shapes(&[cube, far], &Shading::default(), None);
+        assert_eq!(shapes.len(), 6);
+        assert!(shapes[..3]
+            .iter()
+            .all(|shape| shape.fill.unwrap().r() == 0.0));
+    }
+}
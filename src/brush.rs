@@ -0,0 +1,127 @@
+use glam::{Mat2, Vec2};
+
+use crate::{
+    geometry::arc_lengths, math::portable_sin_cos, rng::Rng, BlendMode, Color, IntoPoints, Stroke,
+};
+
+/// A stamp that is repeated along a path to imitate natural media, like a pencil, a marker or a sponge.
+///
+/// Draw with it using [Canvas::draw_brush_stroke](crate::Canvas::draw_brush_stroke).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Brush {
+    /// Points that make up the stamp, centered on the origin and pointing along the positive x axis.
+    pub stamp: Vec<Vec2>,
+    /// The stroke along each stamp.
+    pub stroke: Option<Stroke>,
+    /// The area filled inside each stamp.
+    pub fill: Option<Color>,
+    /// Distance between stamps along the path.
+    pub spacing: f32,
+    /// How far each stamp may randomly move away from the path.
+    pub position_jitter: f32,
+    /// How far each stamp may randomly rotate, in radians.
+    pub rotation_jitter: f32,
+    /// How much each stamp may randomly grow or shrink, as a fraction of its size.
+    pub scale_jitter: f32,
+    /// Whether stamps rotate to follow the direction of the path.
+    pub follow_tangent: bool,
+    /// How each stamp is composited with what is underneath it.
+    pub blend_mode: BlendMode,
+    /// Seed for the random jitter. The same seed always produces the same stroke.
+    pub seed: u64,
+}
+
+impl Brush {
+    /// Create a new [Brush] with no jitter, that follows the path's direction.
+    pub fn new<C: IntoPoints>(
+        stamp: C,
+        stroke: Option<Stroke>,
+        fill: Option<Color>,
+        spacing: f32,
+    ) -> Self {
+        Self {
+            stamp: stamp.into_points(),
+            stroke,
+            fill,
+            spacing,
+            position_jitter: 0.0,
+            rotation_jitter: 0.0,
+            scale_jitter: 0.0,
+            follow_tangent: true,
+            blend_mode: BlendMode::Normal,
+            seed: 0,
+        }
+    }
+
+    /// Place the stamp along a path, returning each stamp's points.
+    ///
+    /// Stamps are placed every [spacing](Self::spacing) along the path, starting at the first point.
+    pub(crate) fn stamps(&self, path: &[Vec2]) -> Vec<Vec<Vec2>> {
+        if path.len() < 2 || self.stamp.is_empty() || self.spacing.is_nan() || self.spacing <= 0.0 {
+            return Vec::new();
+        }
+
+        let distances = arc_lengths(path);
+        let total = distances[distances.len() - 1];
+        let mut rng = Rng::new(self.seed);
+        let mut stamps = Vec::new();
+
+        let mut segment = 0;
+        let mut distance = 0.0;
+        while distance <= total {
+            while segment < path.len() - 2 && distances[segment + 1] < distance {
+                segment += 1;
+            }
+
+            let (start, end) = (path[segment], path[segment + 1]);
+            let length = distances[segment + 1] - distances[segment];
+            let t = if length > 0.0 {
+                (distance - distances[segment]) / length
+            } else {
+                0.0
+            };
+            let position = start.lerp(end, t);
+
+            // Rotations are built without the platform's trigonometry, so stamps land the same everywhere.
+            let (sin, cos) =
+                portable_sin_cos(rng.range(-self.rotation_jitter, self.rotation_jitter));
+            let mut rotation = Mat2::from_cols(Vec2::new(cos, sin), Vec2::new(-sin, cos));
+            if self.follow_tangent {
+                let direction = (end - start).normalize_or_zero();
+                if direction != Vec2::ZERO {
+                    rotation = Mat2::from_cols(direction, direction.perp()) * rotation;
+                }
+            }
+            let scale = 1.0 + rng.range(-self.scale_jitter, self.scale_jitter);
+            let position = position + rng.in_circle(self.position_jitter);
+
+            let transform = rotation * scale;
+            stamps.push(
+                self.stamp
+                    .iter()
+                    .map(|point| position + transform.mul_vec2(*point))
+                    .collect(),
+            );
+
+            distance += self.spacing;
+        }
+
+        stamps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that stamps are spaced evenly and rotate to follow the path.
+    #[test]
+    fn stamps_follow_path() {
+        let brush = Brush::new(vec![Vec2::ZERO, Vec2::X], None, Some(Color::black()), 0.5);
+        let stamps = brush.stamps(&[Vec2::ZERO, Vec2::Y]);
+
+        assert_eq!(stamps.len(), 3);
+        assert!(stamps[1][0].abs_diff_eq(Vec2::new(0.0, 0.5), 1e-6));
+        assert!(stamps[1][1].abs_diff_eq(Vec2::new(0.0, 1.5), 1e-6));
+    }
+}
@@ -1,14 +1,34 @@
-use std::f32::consts::PI;
-
-use crate::{color::Color, PathBuilder};
-use glam::{Mat2, Vec2};
-
-use retain_mut::RetainMut;
+use std::{borrow::Cow, f32::consts::PI, time::Instant};
+
+use crate::{
+    axonometric::{Axonometric, Shading, Solid},
+    brush::Brush,
+    color::Color,
+    diff::CanvasDiff,
+    geometry::{
+        arc_lengths, clip, clip_outside, contains_point, distort, hatch_paths, offset_polygon,
+        optimize_travel, triangulate, variable_width_outline, warp, ClipRegion, Distortion, Hatch,
+        Warp, WidthProfile,
+    },
+    lod::decimate,
+    math::portable_sin_cos,
+    packing::ShapePlacement,
+    recording::{DrawCall, Recording, Reveal},
+    tiling::{PlacedTile, Tiling},
+    validation::{validate_shape, ValidationIssue},
+    CustomGeometry, Error, Gradient, GradientFill, IntoPoint, IntoPoints, PathBuilder, RenderStats,
+    Symmetry,
+};
+use glam::{Affine2, Mat2, UVec2, Vec2};
 
 /// A polygonal shape with a stroke and fill.
 /// 
 /// Nothing will be drawn if there are 1 or fewer points.
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Every field after [fill](Self::fill) was added later, so a `Shape { points, stroke, fill }` literal no longer compiles.
+/// End literals with `..Default::default()` to leave the rest at their defaults, which keeps working as fields are added.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Shape {
     /// Points that make up the shape.
     /// If you want the outline of the shape to be complete, the start and end points must be the same.
@@ -17,6 +37,29 @@ pub struct Shape {
     pub stroke: Option<Stroke>,
     /// The area filled inside the points.
     pub fill: Option<Color>,
+    /// Additional paths that are part of the same shape, such as the hole in a donut.
+    ///
+    /// Each subpath is stroked like [points](Self::points), and filled together with it according to [fill_rule](Self::fill_rule).
+    pub subpaths: Vec<Vec<Vec2>>,
+    /// How overlapping paths and self-intersections decide what is inside the shape.
+    pub fill_rule: FillRule,
+    /// How the shape is composited with what has already been drawn.
+    pub blend_mode: BlendMode,
+    /// A gradient painted over the filled area, by renderers that support it.
+    ///
+    /// Only used when the shape also has a [fill](Self::fill), which other renderers draw instead.
+    pub gradient_fill: Option<GradientFill>,
+    /// A hyperlink followed when the shape is clicked, for renderers with interactive output such as SVG.
+    pub link: Option<String>,
+    /// Text shown when hovering over the shape, for renderers with interactive output such as SVG.
+    pub tooltip: Option<String>,
+    /// A name for a group of shapes, such as the spot color ink they print with in a [Separation](crate::Separation).
+    pub tag: Option<String>,
+    /// A [CustomShape](crate::CustomShape) the shape's paths come from, drawn with [Canvas::draw_custom_shape] and not yet [flattened](Self::flatten).
+    ///
+    /// Custom shapes are not serialized, so flatten them first to keep them.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub custom: Option<CustomGeometry>,
 }
 
 impl Shape {
@@ -29,14 +72,177 @@ impl Shape {
         }
     }
 
-    /// Checks if the shape contains more than 1 point.
+    /// Checks if the shape contains more than 1 point, or has [custom](Self::custom) geometry to flatten into them.
     pub fn is_drawable(&self) -> bool{
-        self.points.len() > 1
+        self.points.len() > 1 || self.custom.is_some()
+    }
+
+    /// Replace the shape's paths with those of its [custom](Self::custom) geometry, if it has any.
+    ///
+    /// The first path becomes the [points](Self::points), and the rest the [subpaths](Self::subpaths).
+    pub fn flatten(&mut self) {
+        if let Some(custom) = self.custom.take() {
+            let mut paths = custom.paths().into_iter();
+            self.points = paths.next().unwrap_or_default();
+            self.subpaths = paths.collect();
+        }
+    }
+
+    /// The shape with any [custom](Self::custom) geometry [flattened](Self::flatten), or the shape itself if it has none.
+    ///
+    /// Anything that walks a shape's [paths](Self::paths) should flatten it first, since custom shapes have no points of their own.
+    pub fn flattened(&self) -> Cow<'_, Shape> {
+        if self.custom.is_none() {
+            return Cow::Borrowed(self);
+        }
+
+        let mut flattened = self.clone();
+        flattened.flatten();
+        Cow::Owned(flattened)
+    }
+
+    /// Transform every point in the shape, keeping [custom](Self::custom) geometry unflattened.
+    pub fn transform(&mut self, transform: Affine2) {
+        match &mut self.custom {
+            Some(custom) => {
+                custom.transform = transform * custom.transform;
+                if let Some(gradient_fill) = &mut self.gradient_fill {
+                    gradient_fill.start = transform.transform_point2(gradient_fill.start);
+                    gradient_fill.end = transform.transform_point2(gradient_fill.end);
+                }
+            }
+            None => self.map_points(|point| transform.transform_point2(point)),
+        }
+    }
+
+    /// What the shape is, borrowing its geometry, for matching on.
+    pub fn kind(&self) -> ShapeKind<'_> {
+        if let Some(custom) = &self.custom {
+            ShapeKind::Custom(custom)
+        } else if !self.subpaths.is_empty() {
+            ShapeKind::Compound {
+                outline: &self.points,
+                subpaths: &self.subpaths,
+            }
+        } else if self.is_polygon() {
+            ShapeKind::Polygon(&self.points)
+        } else {
+            ShapeKind::Polyline(&self.points)
+        }
+    }
+
+    /// Iterate over every path in the shape: [points](Self::points), followed by each of the [subpaths](Self::subpaths).
+    pub fn paths(&self) -> impl Iterator<Item = &[Vec2]> {
+        std::iter::once(self.points.as_slice()).chain(self.subpaths.iter().map(Vec::as_slice))
+    }
+
+    /// The total number of points in the shape, including subpaths.
+    pub fn point_count(&self) -> usize {
+        self.paths().map(<[Vec2]>::len).sum()
+    }
+
+    /// Checks if the shape is a polygon with holes, as drawn by [Canvas::draw_polygon_with_holes].
+    ///
+    /// The holes are the shape's [subpaths](Self::subpaths).
+    pub fn has_holes(&self) -> bool {
+        self.is_polygon() && !self.subpaths.is_empty() && self.fill_rule == FillRule::EvenOdd
+    }
+
+    /// Split the filled area of the shape into triangles, treating each of the [subpaths](Self::subpaths) as a hole.
+    ///
+    /// See [triangulate] for the details.
+    pub fn triangles(&self) -> Vec<[Vec2; 3]> {
+        if self.custom.is_some() {
+            return self.flattened().triangles();
+        }
+
+        let holes: Vec<&[Vec2]> = self.subpaths.iter().map(Vec::as_slice).collect();
+        let vertices: Vec<Vec2> = self.paths().flatten().copied().collect();
+
+        triangulate(&self.points, &holes)
+            .into_iter()
+            .map(|triangle| triangle.map(|i| vertices[i]))
+            .collect()
+    }
+
+    /// The smallest axis-aligned box holding every point of the shape, including subpaths, as `(min, max)`.
+    ///
+    /// Returns `None` if the shape has no points.
+    pub fn bounds(&self) -> Option<(Vec2, Vec2)> {
+        if self.custom.is_some() {
+            return self.flattened().bounds();
+        }
+
+        let mut points = self.paths().flatten();
+        let first = *points.next()?;
+
+        Some(points.fold((first, first), |(min, max), point| {
+            (min.min(*point), max.max(*point))
+        }))
+    }
+
+    /// Replace every point in the shape, including subpaths and the ends of any [gradient_fill](Self::gradient_fill), with the result of `f`.
+    ///
+    /// Any [custom](Self::custom) geometry is [flattened](Self::flatten) first.
+    pub fn map_points<F: FnMut(Vec2) -> Vec2>(&mut self, mut f: F) {
+        self.flatten();
+
+        for point in self
+            .points
+            .iter_mut()
+            .chain(self.subpaths.iter_mut().flatten())
+        {
+            *point = f(*point);
+        }
+
+        if let Some(gradient_fill) = &mut self.gradient_fill {
+            gradient_fill.start = f(gradient_fill.start);
+            gradient_fill.end = f(gradient_fill.end);
+        }
     }
 }
 
+/// What a [Shape] is, as given by [Shape::kind], with its geometry borrowed from it.
+///
+/// Its style, such as [stroke](Shape::stroke) and [fill](Shape::fill), is read from the shape itself.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShapeKind<'a> {
+    /// An open line through its points.
+    Polyline(&'a [Vec2]),
+    /// A closed outline, whose first and last points are the same.
+    Polygon(&'a [Vec2]),
+    /// Several paths filled together, such as a polygon with holes.
+    Compound {
+        /// The first path, the shape's [points](Shape::points).
+        outline: &'a [Vec2],
+        /// The rest of the paths, the shape's [subpaths](Shape::subpaths).
+        subpaths: &'a [Vec<Vec2>],
+    },
+    /// A [CustomShape](crate::CustomShape) not yet [flattened](Shape::flatten) into points.
+    Custom(&'a CustomGeometry),
+}
+
+/// How a [Shape] with overlapping paths or self-intersections decides which areas are inside it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FillRule {
+    /// An area is inside if the paths around it wind around it a different number of times clockwise and counter-clockwise.
+    ///
+    /// Overlapping paths that wind the same way are filled. To cut a hole, wind the hole's path the opposite way.
+    #[default]
+    NonZero,
+    /// An area is inside if it is surrounded by an odd number of paths.
+    ///
+    /// Any path inside another cuts a hole, regardless of winding.
+    EvenOdd,
+}
+
 /// A structure that describes a line stroke.
+///
+/// The [alignment](Self::alignment) field was added after the others, so a `Stroke { color, width, line_end }` literal
+/// no longer compiles. Build strokes with [Stroke::new] instead, which keeps working as fields are added.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Stroke {
     /// Color of the stroke
     pub color: Color,
@@ -44,22 +250,66 @@ pub struct Stroke {
     pub width: f32,
     /// How each end of the line terminates (a.k.a line cap).
     pub line_end: LineEnd,
+    /// Where the stroke sits relative to the outline of a closed shape.
+    pub alignment: StrokeAlignment,
 }
 
 impl Stroke {
-    /// Create a new [Stroke]
+    /// Create a new [Stroke], centered on the outline.
     #[inline]
     pub fn new(color: Color, width: f32, line_end: LineEnd) -> Self {
         Self {
             color,
             width,
             line_end,
+            alignment: StrokeAlignment::Center,
         }
     }
+
+    /// Modify the alignment, consuming the parent.
+    #[inline]
+    pub fn with_alignment(mut self, alignment: StrokeAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+}
+
+/// The stroke and fill a [Canvas] gives shapes drawn with neither, set with [set_style](Canvas::set_style) or [with_style](Canvas::with_style).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Style {
+    /// The stroke given to shapes drawn without a stroke or fill, if any.
+    pub stroke: Option<Stroke>,
+    /// The fill given to shapes drawn without a stroke or fill, if any.
+    pub fill: Option<Color>,
+}
+
+impl Style {
+    /// Create a new [Style].
+    #[inline]
+    pub fn new(stroke: Option<Stroke>, fill: Option<Color>) -> Self {
+        Self { stroke, fill }
+    }
+}
+
+/// Where a [Stroke] sits relative to the outline of a closed shape.
+///
+/// Open shapes (polylines) are always stroked along their center.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StrokeAlignment {
+    /// The stroke straddles the outline.
+    #[default]
+    Center,
+    /// The stroke sits entirely inside the outline, so the shape does not grow.
+    Inside,
+    /// The stroke sits entirely outside the outline, so the fill is not covered.
+    Outside,
 }
 
 /// How to end [stroked](Stroke) line.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LineEnd {
     /// Line continues past the final point and ends with a square.
     Butt,
@@ -67,6 +317,27 @@ pub enum LineEnd {
     Round,
 }
 
+/// How a [Shape] is composited with what has already been drawn underneath it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BlendMode {
+    /// The shape is painted over what is underneath.
+    #[default]
+    Normal,
+    /// Colors are multiplied, which always darkens. Like overlapping ink.
+    Multiply,
+    /// Inverted colors are multiplied, which always lightens. Like overlapping light.
+    Screen,
+    /// Multiplies dark areas and screens light areas underneath.
+    Overlay,
+    /// Keeps the darker of the two colors.
+    Darken,
+    /// Keeps the lighter of the two colors.
+    Lighten,
+    /// Colors are added together.
+    Add,
+}
+
 /// A renderer for [Canvas].
 ///
 /// If you want to implement your own rendering backend,
@@ -75,9 +346,9 @@ pub trait Renderer {
     /// The intended format the renderer will output.
     type Output;
     /// Render a shape. Provided coordinates will be in Camera Space (from the perspective of the camera).
-    fn render(&mut self, shape: &Shape);
+    fn render(&mut self, shape: &Shape) -> Result<(), Error>;
     /// Finalize the render.
-    fn finalize(self) -> Self::Output;
+    fn finalize(self) -> Result<Self::Output, Error>;
 }
 
 /// A canvas that can be used with many backends.
@@ -96,6 +367,14 @@ pub struct Canvas {
     translation: Vec2,
     to_camera_matrix: Mat2,
     to_world_matrix: Mat2,
+    validate_on_render: bool,
+    deterministic: bool,
+    symmetry: Vec<Affine2>,
+    link: Option<String>,
+    tooltip: Option<String>,
+    tag: Option<String>,
+    recording: Option<Recording>,
+    style: Style,
     shapes: Vec<Shape>,
 }
 
@@ -108,6 +387,14 @@ impl Default for Canvas {
             translation: Vec2::ZERO,
             to_camera_matrix: Mat2::IDENTITY,
             to_world_matrix: Mat2::IDENTITY,
+            validate_on_render: false,
+            deterministic: false,
+            symmetry: Vec::new(),
+            link: None,
+            tooltip: None,
+            tag: None,
+            recording: None,
+            style: Style::default(),
             shapes: Vec::new(),
         }
     }
@@ -124,34 +411,236 @@ impl Canvas {
             translation: Vec2::ZERO,
             to_camera_matrix: Mat2::IDENTITY,
             to_world_matrix: Mat2::IDENTITY,
+            validate_on_render: false,
+            deterministic: false,
+            symmetry: Vec::new(),
+            link: None,
+            tooltip: None,
+            tag: None,
+            recording: None,
+            style: Style::default(),
             shapes: Vec::new(),
         }
     }
 
     /// Render the canvas using a renderer of your choice.
-    pub fn render<R: Renderer>(&self, mut renderer: R) -> R::Output {
+    pub fn render<R: Renderer>(&self, mut renderer: R) -> Result<R::Output, Error> {
+        self.validate_for_render()?;
+
         for shape in &self.shapes {
-            let mut transformed_shape = shape.clone();
+            for shape in align_stroke(self.shape_to_camera_space(shape)) {
+                renderer.render(&shape)?;
+            }
+        }
+
+        renderer.finalize()
+    }
+
+    /// Render the canvas using a renderer of your choice, collecting [RenderStats] along the way.
+    ///
+    /// Shapes that are not [drawable](Shape::is_drawable) are skipped and counted in [RenderStats::shapes_skipped].
+    /// Timing each phase relies on [Instant](std::time::Instant), which isn't available on `wasm32-unknown-unknown`, so use [render](Self::render) there.
+    pub fn render_with_stats<R: Renderer>(
+        &self,
+        mut renderer: R,
+    ) -> Result<(R::Output, RenderStats), Error> {
+        self.validate_for_render()?;
+
+        let mut stats = RenderStats::default();
+
+        for shape in &self.shapes {
+            if !shape.is_drawable() {
+                stats.shapes_skipped += 1;
+                continue;
+            }
+
+            let start = Instant::now();
+            let shape = shape.flattened();
+            stats.tessellation_time += start.elapsed();
+
+            let start = Instant::now();
+            let transformed_shape = self.shape_to_camera_space(&shape);
+            stats.transform_time += start.elapsed();
+
+            let start = Instant::now();
+            let shapes = align_stroke(transformed_shape);
+            stats.tessellation_time += start.elapsed();
+
+            let start = Instant::now();
+            for shape in shapes {
+                stats.points_rendered += shape.point_count();
+                renderer.render(&shape)?;
+            }
+            stats.raster_time += start.elapsed();
+
+            stats.shapes_rendered += 1;
+        }
+
+        let start = Instant::now();
+        let output = renderer.finalize()?;
+        stats.finalize_time = start.elapsed();
+
+        Ok((output, stats))
+    }
+
+    /// Check the canvas before rendering it, if [enabled](Self::set_validate_on_render).
+    fn validate_for_render(&self) -> Result<(), Error> {
+        if self.validate_on_render {
+            let issues = self.validate();
+            if !issues.is_empty() {
+                return Err(Error::InvalidGeometry(issues));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Copy a shape into Camera Space, scaling its stroke with the zoom.
+    fn shape_to_camera_space(&self, shape: &Shape) -> Shape {
+        let mut transformed_shape = shape.clone();
+
+        transformed_shape.map_points(|point| self.to_camera_space(point));
+
+        if let Some(stroke) = &mut transformed_shape.stroke {
+            stroke.width *= self.zoom;
+        }
+
+        transformed_shape
+    }
+
+    /// Render only the part of the canvas inside a rectangle of World Space, ignoring the camera.
+    ///
+    /// The rectangle between the corners `min` and `max` fills an image of `pixel_size`, which must be the size the renderer was created with, using `preserve_height`.
+    /// Shapes entirely outside the rectangle are skipped, so rendering tiles of a huge scene only costs as much as the shapes in each tile.
+    ///
+    /// Returns [Error::InvalidSize] if `pixel_size` has no width or height,
+    /// and [Error::InvalidRegion] if the rectangle has no area, isn't finite, or is more than half a pixel off the aspect ratio of `pixel_size`.
+    /// Like [render](Self::render), returns [Error::InvalidGeometry] if [validation](Self::set_validate_on_render) is enabled and fails.
+    pub fn render_region<R: Renderer>(
+        &self,
+        min: Vec2,
+        max: Vec2,
+        pixel_size: UVec2,
+        mut renderer: R,
+    ) -> Result<R::Output, Error> {
+        if pixel_size.x == 0 || pixel_size.y == 0 {
+            return Err(Error::InvalidSize {
+                width: pixel_size.x,
+                height: pixel_size.y,
+            });
+        }
 
-            for point in transformed_shape.points.iter_mut() {
-                *point = self.to_camera_space(*point);
+        let (min, max) = (min.min(max), min.max(max));
+        let size = max - min;
+        // How many pixels wide the rectangle is once it fills the height of the image.
+        let width = size.x / size.y * pixel_size.y as f32;
+        if !(size.x > 0.0 && size.y > 0.0 && size.is_finite() && width.is_finite())
+            || (width - pixel_size.x as f32).abs() > 0.5
+        {
+            return Err(Error::InvalidRegion { min, max });
+        }
+
+        self.validate_for_render()?;
+
+        let center = (min + max) / 2.0;
+        let scale = 2.0 / size.y;
+
+        for shape in &self.shapes {
+            let (shape_min, shape_max) = match shape.bounds() {
+                Some(bounds) if shape.is_drawable() => bounds,
+                _ => continue,
+            };
+
+            let margin = shape.stroke.map_or(0.0, |stroke| stroke.width);
+            if shape_max.x + margin < min.x
+                || shape_max.y + margin < min.y
+                || shape_min.x - margin > max.x
+                || shape_min.y - margin > max.y
+            {
+                continue;
             }
 
+            let mut transformed_shape = shape.clone();
+            transformed_shape.map_points(|point| (point - center) * scale);
+
             if let Some(stroke) = &mut transformed_shape.stroke {
-                stroke.width *= self.zoom;
+                stroke.width *= scale;
             }
 
-            renderer.render(&transformed_shape);
+            for shape in align_stroke(transformed_shape) {
+                renderer.render(&shape)?;
+            }
         }
 
         renderer.finalize()
     }
 
+    /// Check every shape on the canvas for degenerate geometry.
+    ///
+    /// Detects non-finite coordinates, zero-width strokes, self-intersecting polygons and empty paths.
+    /// Self-intersection checks are quadratic in the number of points per shape, so this can be slow for large scenes.
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+
+        for (index, shape) in self.shapes.iter().enumerate() {
+            validate_shape(index, shape, &mut issues);
+        }
+
+        issues
+    }
+
+    /// Whether [render](Self::render) should [validate](Self::validate) the canvas first.
+    ///
+    /// When enabled, rendering a canvas with degenerate geometry returns [Error::InvalidGeometry] instead of producing corrupt output.
+    pub fn set_validate_on_render(&mut self, validate_on_render: bool) {
+        self.validate_on_render = validate_on_render;
+    }
+
     /// Returns a [Vec] of all the [Shapes](Shape) drawn on the canvas.
     pub fn to_raw(self) -> Vec<Shape> {
         self.shapes
     }
 
+    /// Compare the shapes on this canvas with those on `other`, treating this canvas as the old version.
+    ///
+    /// See [CanvasDiff] for how shapes are matched, and [CanvasDiff::draw_overlay] to see the differences.
+    pub fn diff(&self, other: &Canvas) -> CanvasDiff {
+        CanvasDiff::new(&self.shapes, &other.shapes)
+    }
+
+    /// Build a pyramid of simplified copies of this canvas, for rendering quickly while zoomed out.
+    ///
+    /// Level `0` suits pixels `pixel_size` wide in World Space, and each level after it suits pixels twice as wide as the last.
+    /// On each level, shapes smaller than a pixel are merged into one rectangle per quadtree cell a pixel wide, in their average color, and every other shape is simplified to within half a pixel.
+    pub fn level_of_detail(&self, levels: usize, pixel_size: f32) -> Vec<Canvas> {
+        (0..levels)
+            .map(|level| {
+                let pixel = pixel_size * 2f32.powi(level as i32);
+                self.with_shapes(decimate(&self.shapes, pixel))
+            })
+            .collect()
+    }
+
+    /// A copy of the canvas, with the same camera and settings, holding `shapes` instead of its own and not recording.
+    pub(crate) fn with_shapes(&self, shapes: Vec<Shape>) -> Canvas {
+        Canvas {
+            points_per_unit: self.points_per_unit,
+            zoom: self.zoom,
+            translation: self.translation,
+            to_camera_matrix: self.to_camera_matrix,
+            to_world_matrix: self.to_world_matrix,
+            validate_on_render: self.validate_on_render,
+            deterministic: self.deterministic,
+            symmetry: self.symmetry.clone(),
+            link: self.link.clone(),
+            tooltip: self.tooltip.clone(),
+            tag: self.tag.clone(),
+            recording: None,
+            style: self.style,
+            shapes,
+        }
+    }
+
     /// Returns a slice of all the [Shapes](Shape) drawn on the canvas.
     pub fn as_raw(&self) -> &[Shape] {
         self.shapes.as_slice()
@@ -162,23 +651,40 @@ impl Canvas {
         self.shapes.as_mut_slice()
     }
 
+    /// Iterate over every [Shape] drawn on the canvas, from the bottom up.
+    ///
+    /// Match on each shape's [kind](Shape::kind) to tell what it is, such as to gather statistics or write a custom exporter.
+    pub fn shapes(&self) -> std::slice::Iter<'_, Shape> {
+        self.shapes.iter()
+    }
+
+    /// Iterate mutably over every [Shape] drawn on the canvas, from the bottom up.
+    pub fn shapes_mut(&mut self) -> std::slice::IterMut<'_, Shape> {
+        self.shapes.iter_mut()
+    }
+
     /// Rotate the camera counter-clockwise.
     pub fn rotate_camera(&mut self, radians: f32) {
-        let rotate_mat = Mat2::from_angle(radians);
+        self.record(|| DrawCall::RotateCamera(radians));
+        let (sin, cos) = self.sin_cos(radians);
+        let rotate_mat = Mat2::from_cols(Vec2::new(cos, sin), Vec2::new(-sin, cos));
         self.to_camera_matrix = rotate_mat.mul_mat2(&self.to_camera_matrix);
         self.to_world_matrix = self.to_camera_matrix.inverse();
     }
 
     /// Moves the camera by a certain amount. This is effected by zoom.
-    /// 
+    ///
     /// For example, if the zoom is set to `1/100` and the camera is moved by `(1.0, 1.0)`, it will actually be moving (100.0, 100.0).
-    pub fn move_camera<P: Into<Vec2>>(&mut self, translation: P) {
-        self.translation -= translation.into();
+    pub fn move_camera<P: IntoPoint>(&mut self, translation: P) {
+        let translation = translation.into_point();
+        self.record(|| DrawCall::MoveCamera(translation));
+        self.translation -= translation;
         self.translation = -self.translation;
     }
 
     /// Zoom camera
     pub fn zoom_camera(&mut self, zoom: f32) {
+        self.record(|| DrawCall::ZoomCamera(zoom));
         self.to_camera_matrix *= zoom;
         self.to_world_matrix = self.to_camera_matrix.inverse();
         self.zoom *= zoom;
@@ -186,160 +692,680 @@ impl Canvas {
 
     /// Clears the canvas
     pub fn clear(&mut self) {
+        self.record(|| DrawCall::Clear);
         self.shapes.clear();
     }
 
+    /// Replace the fill of every shape on the canvas with [Hatch] lines, for drawing with a pen plotter.
+    ///
+    /// The lines are stroked in the fill's color, `pen_width` wide, and keep the shape's tag, link and tooltip.
+    /// Lines across a [gradient fill](Shape::gradient_fill) take the gradient's color at their middle.
+    /// Outlines are kept, drawn over the hatching.
+    pub fn hatch_fills(&mut self, hatch: &Hatch, pen_width: f32) {
+        self.record(|| DrawCall::HatchFills {
+            hatch: *hatch,
+            pen_width,
+        });
+
+        for mut shape in std::mem::take(&mut self.shapes) {
+            shape.flatten();
+            let fill = match (shape.fill, &shape.gradient_fill) {
+                (Some(fill), _) => fill,
+                (None, Some(gradient_fill)) => gradient_fill.gradient.sample(0.5),
+                (None, None) => {
+                    self.shapes.push(shape);
+                    continue;
+                }
+            };
+
+            let paths: Vec<&[Vec2]> = shape.paths().collect();
+
+            for line in hatch_paths(&paths, shape.fill_rule, hatch) {
+                let color = match &shape.gradient_fill {
+                    Some(gradient_fill) => {
+                        let middle = line.iter().sum::<Vec2>() / line.len() as f32;
+                        gradient_fill.sample(middle)
+                    }
+                    None => fill,
+                };
+
+                self.shapes.push(Shape {
+                    points: line,
+                    stroke: Some(Stroke::new(color, pen_width, LineEnd::Round)),
+                    blend_mode: shape.blend_mode,
+                    link: shape.link.clone(),
+                    tooltip: shape.tooltip.clone(),
+                    tag: shape.tag.clone(),
+                    ..Default::default()
+                });
+            }
+
+            if shape.stroke.is_some() {
+                self.shapes.push(Shape {
+                    fill: None,
+                    gradient_fill: None,
+                    ..shape
+                });
+            }
+        }
+    }
+
+    /// Reorder, reverse and join open strokes to shorten the distance a pen plotter travels between them.
+    ///
+    /// Only runs of consecutive unfilled polylines are touched, so nothing moves above or below a filled shape.
+    /// Within each run, strokes drawn with the same pen are optimized together with [optimize_travel], and pens are kept in the order they first appear.
+    pub fn optimize_pen_travel(&mut self, merge_tolerance: f32) {
+        self.record(|| DrawCall::OptimizePenTravel { merge_tolerance });
+
+        let is_open_stroke = |shape: &Shape| {
+            shape.custom.is_none()
+                && shape.fill.is_none()
+                && shape.stroke.is_some()
+                && shape.subpaths.is_empty()
+                && !shape.is_polygon()
+        };
+
+        let mut shapes = std::mem::take(&mut self.shapes).into_iter().peekable();
+        while let Some(shape) = shapes.next() {
+            if !is_open_stroke(&shape) {
+                self.shapes.push(shape);
+                continue;
+            }
+
+            let mut pens: Vec<(Shape, Vec<Vec<Vec2>>)> = Vec::new();
+            let mut next = Some(shape);
+            while let Some(mut shape) = next {
+                let points = std::mem::take(&mut shape.points);
+                match pens.iter_mut().find(|(pen, _)| *pen == shape) {
+                    Some((_, paths)) => paths.push(points),
+                    None => pens.push((shape, vec![points])),
+                }
+                next = shapes.next_if(is_open_stroke);
+            }
+
+            for (pen, paths) in pens {
+                for points in optimize_travel(paths, merge_tolerance) {
+                    self.shapes.push(Shape {
+                        points,
+                        ..pen.clone()
+                    });
+                }
+            }
+        }
+    }
+
+    /// Flatten the canvas into line art, cutting away every stroke hidden behind an opaque fill drawn after it.
+    ///
+    /// Only strokes remain, each path as its own open polyline, so the result plots the way it renders.
+    /// Fills still hide what is underneath them, but are not drawn themselves. To plot them, use [hatch_fills](Self::hatch_fills) first.
+    pub fn remove_hidden_lines(&mut self) {
+        self.record(|| DrawCall::RemoveHiddenLines);
+
+        let mut shapes = std::mem::take(&mut self.shapes);
+        shapes.iter_mut().for_each(Shape::flatten);
+        let mut occluders: Vec<&Shape> = Vec::new();
+
+        // Walk from the top down, so every shape is clipped against everything drawn over it.
+        for shape in shapes.iter().rev() {
+            if let Some(stroke) = shape.stroke {
+                let mut visible = Vec::new();
+                for path in shape.paths() {
+                    let mut pieces = vec![path.to_vec()];
+                    for occluder in &occluders {
+                        let paths: Vec<&[Vec2]> = occluder.paths().collect();
+                        pieces = pieces
+                            .iter()
+                            .flat_map(|piece| clip_outside(piece, &paths, occluder.fill_rule))
+                            .collect();
+                    }
+                    visible.extend(pieces);
+                }
+
+                self.shapes
+                    .extend(visible.into_iter().rev().map(|points| Shape {
+                        points,
+                        stroke: Some(stroke),
+                        blend_mode: shape.blend_mode,
+                        ..Default::default()
+                    }));
+            }
+
+            let is_opaque = shape.fill.is_some_and(|fill| fill.a() >= 1.0);
+            if is_opaque && shape.blend_mode == BlendMode::Normal && shape.is_drawable() {
+                occluders.push(shape);
+            }
+        }
+
+        self.shapes.reverse();
+    }
+
+    /// Permanently trim every shape on the canvas to `region`, in World Space, such as cutting the artboard out before export.
+    ///
+    /// Unlike render-time clipping, the geometry outside `region` is gone. See [clip] for how fills and strokes are cut.
+    pub fn clip_to(&mut self, region: &ClipRegion) {
+        self.record(|| DrawCall::ClipTo(region.clone()));
+
+        self.shapes = std::mem::take(&mut self.shapes)
+            .iter()
+            .flat_map(|shape| clip(shape, region))
+            .collect();
+    }
+
+    /// Permanently bend every shape on the canvas with a lens-like [Distortion], in World Space, such as magnifying the inset of a map.
+    ///
+    /// Edges are subdivided until they are within `tolerance` of the true curve. See [distort] for details.
+    pub fn distort(&mut self, distortion: &Distortion, tolerance: f32) {
+        self.record(|| DrawCall::Distort {
+            distortion: *distortion,
+            tolerance,
+        });
+
+        for shape in &mut self.shapes {
+            *shape = distort(shape, distortion, tolerance);
+        }
+    }
+
+    /// Permanently warp every shape on the canvas from the unit square onto a quad, in World Space, such as laying a drawing onto a tilted plane.
+    ///
+    /// Edges are subdivided until they are within `tolerance` of the true curve. See [warp] for details.
+    pub fn warp(&mut self, warp: &Warp, tolerance: f32) {
+        self.record(|| DrawCall::Warp {
+            warp: *warp,
+            tolerance,
+        });
+
+        for shape in &mut self.shapes {
+            *shape = self::warp(shape, warp, tolerance);
+        }
+    }
+
+    /// Bake every shape on the canvas down to plain points, for exporters that cannot express transforms, such as G-code or DXF polylines.
+    ///
+    /// [Custom shapes](Shape::custom) are flattened through their transforms, and closed strokes aligned [inside or outside](StrokeAlignment)
+    /// are offset into centered strokes, split from their fills. Symmetry and instancing already draw every copy as a shape of its own.
+    /// Shapes stay in World Space, so the camera is still applied when rendering. Call [bake_camera](Self::bake_camera) as well to bake it in too.
+    pub fn flatten(&mut self) {
+        self.record(|| DrawCall::Flatten);
+
+        self.shapes = std::mem::take(&mut self.shapes)
+            .into_iter()
+            .flat_map(|mut shape| {
+                shape.flatten();
+                align_stroke(shape)
+            })
+            .map(|mut shape| {
+                if let Some(stroke) = &mut shape.stroke {
+                    stroke.alignment = StrokeAlignment::Center;
+                }
+                shape
+            })
+            .collect();
+    }
+
+    /// Move every shape on the canvas into Camera Space, scaling strokes with the zoom, then reset the camera.
+    ///
+    /// The canvas renders just the same, but exporters that ignore the camera see the shapes as it framed them.
+    /// [Custom shapes](Shape::custom) are flattened along the way.
+    pub fn bake_camera(&mut self) {
+        self.record(|| DrawCall::BakeCamera);
+
+        self.shapes = self
+            .shapes
+            .iter()
+            .map(|shape| self.shape_to_camera_space(shape))
+            .collect();
+
+        self.zoom = 1.0;
+        self.translation = Vec2::ZERO;
+        self.to_camera_matrix = Mat2::IDENTITY;
+        self.to_world_matrix = Mat2::IDENTITY;
+    }
+
+    /// Keep only the shapes on the canvas for which `predicate` returns `true`, such as dropping everything too small to see.
+    pub fn filter_shapes<F: FnMut(&Shape) -> bool>(&mut self, predicate: F) {
+        self.shapes.retain(predicate);
+        self.record_edit();
+    }
+
+    /// Replace every shape on the canvas with what `f` makes of it, such as recoloring every stroke.
+    ///
+    /// Shapes left with one or fewer points are discarded.
+    pub fn map_shapes<F: FnMut(Shape) -> Shape>(&mut self, f: F) {
+        self.shapes = std::mem::take(&mut self.shapes)
+            .into_iter()
+            .map(f)
+            .filter(Shape::is_drawable)
+            .collect();
+        self.record_edit();
+    }
+
     /// Draw a shape onto the canvas, projected from the camera.
     ///
     /// If a shape as one or fewer points, it will be discarded.
-    pub fn draw_shape<C: Into<Vec<Vec2>>>(
+    pub fn draw_shape<C: IntoPoints>(
         &mut self,
         points: C,
         stroke: Option<Stroke>,
         fill: Option<Color>,
     ) {
-        let mut points: Vec<Vec2> = points.into();
+        let points = points.into_points();
+        self.begin_call(|| DrawCall::Shape {
+            points: points.clone(),
+            stroke,
+            fill,
+            absolute: false,
+        });
+
+        let points = self.project_points(points);
 
         if points.len() <= 1 {
+            self.end_call();
             return;
         }
 
-        let mut last_point = Vec2::ZERO * f32::INFINITY;
-        RetainMut::retain_mut(&mut points, |point| {
-            let r = last_point != *point;
-            last_point = *point;
-            *point = self.to_world_space(last_point);
-            r
-        });
-
         stroke.map(|mut v| {
             v.width /= self.zoom;
             v
         });
 
-        self.shapes.push(Shape {
+        self.push_shape(Shape {
             points,
             stroke,
             fill,
-        })
+            ..Default::default()
+        });
+        self.end_call();
     }
 
     /// Draw a shape directly onto the canvas.
     ///
     /// If a shape as one or fewer points, it will be discarded.
-    pub fn draw_shape_absolute<C: Into<Vec<Vec2>>>(
+    pub fn draw_shape_absolute<C: IntoPoints>(
         &mut self,
         points: C,
         stroke: Option<Stroke>,
         fill: Option<Color>,
     ) {
-        let mut points: Vec<Vec2> = points.into();
+        let points = points.into_points();
+        self.begin_call(|| DrawCall::Shape {
+            points: points.clone(),
+            stroke,
+            fill,
+            absolute: true,
+        });
+
+        let points = dedup_points(points);
 
         if points.len() <= 1 {
+            self.end_call();
             return;
         }
 
-        let mut last_point = Vec2::ZERO * f32::INFINITY;
-        points.retain(|point| {
-            let r = last_point != *point;
-            last_point = *point;
-            r
-        });
-
-        self.shapes.push(Shape {
+        self.push_shape(Shape {
             points,
             stroke,
             fill,
-        })
+            ..Default::default()
+        });
+        self.end_call();
     }
 
-    /// Draw a rectangle onto the canvas, projected from the camera.
-    pub fn draw_rect<P: Into<Vec2>>(
+    /// Draw a shape filled with a [GradientFill] onto the canvas, projected from the camera.
+    ///
+    /// The ends of the gradient are projected too. Renderers without gradient support fill the shape with the middle of the gradient.
+    pub fn draw_gradient_shape<C: IntoPoints>(
         &mut self,
-        top_left: P,
-        bottom_right: P,
+        points: C,
         stroke: Option<Stroke>,
-        fill: Option<Color>,
+        gradient_fill: GradientFill,
     ) {
-        let top_left = top_left.into();
-        let bottom_right = bottom_right.into();
-
-        self.draw_shape(
-            vec![
-                top_left,
-                Vec2::new(bottom_right.x, top_left.y),
-                bottom_right,
-                Vec2::new(top_left.x, bottom_right.y),
-                top_left,
-            ],
-            stroke,
-            fill,
-        )
+        let points = self.project_points(points.into_points());
+        let gradient_fill = GradientFill {
+            start: self.to_world_space(gradient_fill.start),
+            end: self.to_world_space(gradient_fill.end),
+            ..gradient_fill
+        };
+
+        self.push_gradient_shape(points, stroke, gradient_fill);
     }
 
-    /// Draw a rectangle directly onto the canvas.
-    pub fn draw_rect_absolute<P: Into<Vec2>>(
+    /// Draw a shape filled with a [GradientFill] directly onto the canvas.
+    ///
+    /// Renderers without gradient support fill the shape with the middle of the gradient.
+    pub fn draw_gradient_shape_absolute<C: IntoPoints>(
         &mut self,
-        top_left: P,
-        bottom_right: P,
+        points: C,
         stroke: Option<Stroke>,
-        fill: Option<Color>,
+        gradient_fill: GradientFill,
     ) {
-        let top_left = top_left.into();
-        let bottom_right = bottom_right.into();
+        let points = dedup_points(points.into_points());
 
-        self.draw_shape_absolute(
-            vec![
-                top_left,
-                Vec2::new(bottom_right.x, top_left.y),
-                bottom_right,
-                Vec2::new(top_left.x, bottom_right.y),
-                top_left,
-            ],
-            stroke,
-            fill,
-        )
+        self.push_gradient_shape(points, stroke, gradient_fill);
     }
 
-    /// Draws a regular polygon onto the canvas, projected from the camera.
-    ///
-    /// Rotation is in radians.
-    /// Will panic if `sides` < 3.
-    pub fn draw_regular_polygon<P: Into<Vec2>>(
+    fn push_gradient_shape(
         &mut self,
-        center: P,
-        sides: usize,
-        radius: f32,
-        rotation: f32,
+        points: Vec<Vec2>,
         stroke: Option<Stroke>,
-        fill: Option<Color>,
+        gradient_fill: GradientFill,
     ) {
-        if sides < 3 {
-            panic!("There must be at least 3 sides in a regular polygon.")
+        if points.len() <= 1 {
+            return;
+        }
+
+        self.push_shape(Shape {
+            points,
+            stroke,
+            fill: Some(gradient_fill.gradient.sample(0.5)),
+            gradient_fill: Some(gradient_fill),
+            ..Default::default()
+        });
+    }
+
+    /// Add an already-built [Shape] directly onto the canvas, such as one converted from another geometry library.
+    ///
+    /// If a shape has one or fewer points, it will be discarded.
+    pub fn draw_raw_shape<S: Into<Shape>>(&mut self, shape: S) {
+        let shape = shape.into();
+
+        if shape.is_drawable() {
+            self.push_shape(shape);
+        }
+    }
+
+    /// Draw a shape made of several paths onto the canvas, projected from the camera.
+    ///
+    /// The first path is the shape's outline, and the rest become its [subpaths](Shape::subpaths).
+    /// Paths with one or fewer points are discarded.
+    pub fn draw_compound_shape<I, C>(
+        &mut self,
+        paths: I,
+        stroke: Option<Stroke>,
+        fill: Option<Color>,
+        fill_rule: FillRule,
+    ) where
+        I: IntoIterator<Item = C>,
+        C: IntoPoints,
+    {
+        let paths: Vec<Vec<Vec2>> = paths.into_iter().map(IntoPoints::into_points).collect();
+        self.begin_call(|| DrawCall::CompoundShape {
+            paths: paths.clone(),
+            stroke,
+            fill,
+            fill_rule,
+            absolute: false,
+        });
+
+        let paths = paths
+            .into_iter()
+            .map(|path| self.project_points(path))
+            .collect();
+
+        self.push_compound_shape(paths, stroke, fill, fill_rule);
+        self.end_call();
+    }
+
+    /// Draw a shape made of several paths directly onto the canvas.
+    ///
+    /// The first path is the shape's outline, and the rest become its [subpaths](Shape::subpaths).
+    /// Paths with one or fewer points are discarded.
+    pub fn draw_compound_shape_absolute<I, C>(
+        &mut self,
+        paths: I,
+        stroke: Option<Stroke>,
+        fill: Option<Color>,
+        fill_rule: FillRule,
+    ) where
+        I: IntoIterator<Item = C>,
+        C: IntoPoints,
+    {
+        let paths: Vec<Vec<Vec2>> = paths.into_iter().map(IntoPoints::into_points).collect();
+        self.begin_call(|| DrawCall::CompoundShape {
+            paths: paths.clone(),
+            stroke,
+            fill,
+            fill_rule,
+            absolute: true,
+        });
+
+        let paths = paths.into_iter().map(dedup_points).collect();
+
+        self.push_compound_shape(paths, stroke, fill, fill_rule);
+        self.end_call();
+    }
+
+    fn push_compound_shape(
+        &mut self,
+        paths: Vec<Vec<Vec2>>,
+        stroke: Option<Stroke>,
+        fill: Option<Color>,
+        fill_rule: FillRule,
+    ) {
+        let mut paths = paths.into_iter().filter(|path| path.len() > 1);
+
+        if let Some(points) = paths.next() {
+            self.push_shape(Shape {
+                points,
+                subpaths: paths.collect(),
+                stroke,
+                fill,
+                fill_rule,
+                ..Default::default()
+            })
+        }
+    }
+
+    /// Add a shape, along with a copy for every transform in the current [Symmetry].
+    fn push_shape(&mut self, mut shape: Shape) {
+        self.record(|| DrawCall::RawShape(shape.clone()));
+
+        if shape.link.is_none() {
+            shape.link = self.link.clone();
+        }
+        if shape.tooltip.is_none() {
+            shape.tooltip = self.tooltip.clone();
+        }
+        if shape.tag.is_none() {
+            shape.tag = self.tag.clone();
+        }
+        if shape.stroke.is_none() && shape.fill.is_none() {
+            shape.stroke = self.style.stroke;
+            shape.fill = self.style.fill;
+        }
+
+        if self.symmetry.is_empty() {
+            self.shapes.push(shape);
+            return;
+        }
+
+        for transform in &self.symmetry {
+            let mut copy = shape.clone();
+            copy.transform(*transform);
+            self.shapes.push(copy);
+        }
+    }
+
+    /// Remove consecutive duplicate points and project them from the camera into World Space.
+    fn project_points(&self, points: Vec<Vec2>) -> Vec<Vec2> {
+        dedup_points(points)
+            .into_iter()
+            .map(|point| self.to_world_space(point))
+            .collect()
+    }
+
+    /// Draw a polygon with holes cut out of it onto the canvas, projected from the camera.
+    ///
+    /// Rings are closed automatically, and filled with [FillRule::EvenOdd], so the winding of each ring does not matter.
+    /// Like a lake with islands, a hole can contain further rings drawn as separate polygons.
+    pub fn draw_polygon_with_holes<C, H>(
+        &mut self,
+        outer: C,
+        holes: H,
+        stroke: Option<Stroke>,
+        fill: Option<Color>,
+    ) where
+        C: IntoPoints,
+        H: IntoIterator,
+        H::Item: IntoPoints,
+    {
+        let outer = outer.into_points();
+        let holes: Vec<Vec<Vec2>> = holes.into_iter().map(IntoPoints::into_points).collect();
+        self.begin_call(|| DrawCall::PolygonWithHoles {
+            outer: outer.clone(),
+            holes: holes.clone(),
+            stroke,
+            fill,
+            absolute: false,
+        });
+
+        let rings = std::iter::once(outer).chain(holes).map(close_ring);
+
+        self.draw_compound_shape(rings, stroke, fill, FillRule::EvenOdd);
+        self.end_call();
+    }
+
+    /// Draw a polygon with holes cut out of it directly onto the canvas.
+    ///
+    /// Rings are closed automatically, and filled with [FillRule::EvenOdd], so the winding of each ring does not matter.
+    /// Like a lake with islands, a hole can contain further rings drawn as separate polygons.
+    pub fn draw_polygon_with_holes_absolute<C, H>(
+        &mut self,
+        outer: C,
+        holes: H,
+        stroke: Option<Stroke>,
+        fill: Option<Color>,
+    ) where
+        C: IntoPoints,
+        H: IntoIterator,
+        H::Item: IntoPoints,
+    {
+        let outer = outer.into_points();
+        let holes: Vec<Vec<Vec2>> = holes.into_iter().map(IntoPoints::into_points).collect();
+        self.begin_call(|| DrawCall::PolygonWithHoles {
+            outer: outer.clone(),
+            holes: holes.clone(),
+            stroke,
+            fill,
+            absolute: true,
+        });
+
+        let rings = std::iter::once(outer).chain(holes).map(close_ring);
+
+        self.draw_compound_shape_absolute(rings, stroke, fill, FillRule::EvenOdd);
+        self.end_call();
+    }
+
+    /// Draw a rectangle onto the canvas, projected from the camera.
+    pub fn draw_rect<P: IntoPoint>(
+        &mut self,
+        top_left: P,
+        bottom_right: P,
+        stroke: Option<Stroke>,
+        fill: Option<Color>,
+    ) {
+        let top_left = top_left.into_point();
+        let bottom_right = bottom_right.into_point();
+        self.begin_call(|| DrawCall::Rect {
+            top_left,
+            bottom_right,
+            stroke,
+            fill,
+            absolute: false,
+        });
+
+        self.draw_shape(
+            vec![
+                top_left,
+                Vec2::new(bottom_right.x, top_left.y),
+                bottom_right,
+                Vec2::new(top_left.x, bottom_right.y),
+                top_left,
+            ],
+            stroke,
+            fill,
+        );
+        self.end_call();
+    }
+
+    /// Draw a rectangle directly onto the canvas.
+    pub fn draw_rect_absolute<P: IntoPoint>(
+        &mut self,
+        top_left: P,
+        bottom_right: P,
+        stroke: Option<Stroke>,
+        fill: Option<Color>,
+    ) {
+        let top_left = top_left.into_point();
+        let bottom_right = bottom_right.into_point();
+        self.begin_call(|| DrawCall::Rect {
+            top_left,
+            bottom_right,
+            stroke,
+            fill,
+            absolute: true,
+        });
+
+        self.draw_shape_absolute(
+            vec![
+                top_left,
+                Vec2::new(bottom_right.x, top_left.y),
+                bottom_right,
+                Vec2::new(top_left.x, bottom_right.y),
+                top_left,
+            ],
+            stroke,
+            fill,
+        );
+        self.end_call();
+    }
+
+    /// Draws a regular polygon onto the canvas, projected from the camera.
+    ///
+    /// Rotation is in radians.
+    /// Will panic if `sides` < 3.
+    pub fn draw_regular_polygon<P: IntoPoint>(
+        &mut self,
+        center: P,
+        sides: usize,
+        radius: f32,
+        rotation: f32,
+        stroke: Option<Stroke>,
+        fill: Option<Color>,
+    ) {
+        if sides < 3 {
+            panic!("There must be at least 3 sides in a regular polygon.")
         }
 
-        let center = center.into();
+        let center = center.into_point();
+        self.begin_call(|| DrawCall::RegularPolygon {
+            center,
+            sides,
+            radius,
+            rotation,
+            stroke,
+            fill,
+            absolute: false,
+        });
 
         let mut points = Vec::with_capacity(sides + 1);
 
         for n in 0..sides {
-            points.push(Vec2::new(
-                radius * (2.0 * PI * n as f32 / sides as f32 + rotation).cos() + center.x,
-                radius * (2.0 * PI * n as f32 / sides as f32 + rotation).sin() + center.y,
-            ))
+            let (sin, cos) = self.sin_cos(2.0 * PI * n as f32 / sides as f32 + rotation);
+            points.push(Vec2::new(radius * cos + center.x, radius * sin + center.y))
         }
 
         // Connect first and last points to complete polygon.
         points.push(points[0]);
 
-        self.draw_shape(points, stroke, fill)
+        self.draw_shape(points, stroke, fill);
+        self.end_call();
     }
 
     /// Draws a regular polygon directly onto the canvas.
     ///
     /// Rotation is in radians.
     /// Will panic if `sides` < 3.
-    pub fn draw_regular_polygon_absolute<P: Into<Vec2>>(
+    pub fn draw_regular_polygon_absolute<P: IntoPoint>(
         &mut self,
         center: P,
         sides: usize,
@@ -352,61 +1378,87 @@ impl Canvas {
             panic!("There must be at least 3 sides in a regular polygon.")
         }
 
-        let center = center.into();
+        let center = center.into_point();
+        self.begin_call(|| DrawCall::RegularPolygon {
+            center,
+            sides,
+            radius,
+            rotation,
+            stroke,
+            fill,
+            absolute: true,
+        });
 
         let mut points = Vec::with_capacity(sides + 1);
 
         for n in 0..sides {
-            points.push(Vec2::new(
-                radius * (2.0 * PI * n as f32 / sides as f32 + rotation).cos() + center.x,
-                radius * (2.0 * PI * n as f32 / sides as f32 + rotation).sin() + center.y,
-            ))
+            let (sin, cos) = self.sin_cos(2.0 * PI * n as f32 / sides as f32 + rotation);
+            points.push(Vec2::new(radius * cos + center.x, radius * sin + center.y))
         }
 
         // Connect first and last points to complete polygon.
         points.push(points[0]);
 
-        self.draw_shape_absolute(points, stroke, fill)
+        self.draw_shape_absolute(points, stroke, fill);
+        self.end_call();
     }
 
     /// Draws a circle onto the canvas, projected from the camera.
     /// This is a wrapper over [draw_regular_polygon](Self::draw_regular_polygon).
     /// If you want high-quality circles, use that function directly or adjust [points_per_unit](Self::points_per_unit) to fit your needs.
-    pub fn draw_circle<P: Into<Vec2>>(
+    pub fn draw_circle<P: IntoPoint>(
         &mut self,
         center: P,
         radius: f32,
         stroke: Option<Stroke>,
         fill: Option<Color>,
     ) {
-        let center = center.into();
+        let center = center.into_point();
+        self.begin_call(|| DrawCall::Circle {
+            center,
+            radius,
+            stroke,
+            fill,
+            absolute: false,
+        });
+
         let circumference = 2.0 * PI * radius;
         let sides = (circumference * self.points_per_unit as f32) as usize;
         if sides > 2 {
             self.draw_regular_polygon(center, sides, radius, 0.0, stroke, fill);
         }
+        self.end_call();
     }
 
     /// Draws a circle directly onto the canvas.
     /// This is a wrapper over [draw_regular_polygon_absolute](Self::draw_regular_polygon_absolute).
     /// If you want high-quality circles, use that function directly or adjust [points_per_unit](Self::points_per_unit) to fit your needs.
-    pub fn draw_circle_absolute<P: Into<Vec2>>(
+    pub fn draw_circle_absolute<P: IntoPoint>(
         &mut self,
         center: P,
         radius: f32,
         stroke: Option<Stroke>,
         fill: Option<Color>,
     ) {
-        let center = center.into();
+        let center = center.into_point();
+        self.begin_call(|| DrawCall::Circle {
+            center,
+            radius,
+            stroke,
+            fill,
+            absolute: true,
+        });
+
         let circumference = 2.0 * PI * radius;
         let sides = (circumference * self.points_per_unit as f32) as usize;
         if sides > 2 {
-            self.draw_regular_polygon(center, sides, radius, 0.0, stroke, fill);
+            self.draw_regular_polygon_absolute(center, sides, radius, 0.0, stroke, fill);
         }
+        self.end_call();
     }
 
     /// Draw a triangle onto the canvas, projected from the camera.
-    pub fn draw_triangle<P: Into<Vec2>>(
+    pub fn draw_triangle<P: IntoPoint>(
         &mut self,
         p0: P,
         p1: P,
@@ -414,11 +1466,20 @@ impl Canvas {
         stroke: Option<Stroke>,
         fill: Option<Color>,
     ) {
-        self.draw_shape(vec![p0.into(), p1.into(), p2.into()], stroke, fill);
+        let points = [p0.into_point(), p1.into_point(), p2.into_point()];
+        self.begin_call(|| DrawCall::Triangle {
+            points,
+            stroke,
+            fill,
+            absolute: false,
+        });
+
+        self.draw_shape(points.to_vec(), stroke, fill);
+        self.end_call();
     }
 
     /// Draw a triangle directly onto the canvas.
-    pub fn draw_triangle_absolute<P: Into<Vec2>>(
+    pub fn draw_triangle_absolute<P: IntoPoint>(
         &mut self,
         p0: P,
         p1: P,
@@ -426,11 +1487,20 @@ impl Canvas {
         stroke: Option<Stroke>,
         fill: Option<Color>,
     ) {
-        self.draw_shape_absolute(vec![p0.into(), p1.into(), p2.into()], stroke, fill);
+        let points = [p0.into_point(), p1.into_point(), p2.into_point()];
+        self.begin_call(|| DrawCall::Triangle {
+            points,
+            stroke,
+            fill,
+            absolute: true,
+        });
+
+        self.draw_shape_absolute(points.to_vec(), stroke, fill);
+        self.end_call();
     }
 
     /// Draw a quad onto the canvas, projected from the camera.
-    pub fn draw_quad<P: Into<Vec2>>(
+    pub fn draw_quad<P: IntoPoint>(
         &mut self,
         p0: P,
         p1: P,
@@ -439,15 +1509,25 @@ impl Canvas {
         stroke: Option<Stroke>,
         fill: Option<Color>,
     ) {
-        self.draw_shape(
-            vec![p0.into(), p1.into(), p2.into(), p3.into()],
+        let points = [
+            p0.into_point(),
+            p1.into_point(),
+            p2.into_point(),
+            p3.into_point(),
+        ];
+        self.begin_call(|| DrawCall::Quad {
+            points,
             stroke,
             fill,
-        );
+            absolute: false,
+        });
+
+        self.draw_shape(points.to_vec(), stroke, fill);
+        self.end_call();
     }
 
     /// Draw a quad directly onto the canvas.
-    pub fn draw_quad_absolute<P: Into<Vec2>>(
+    pub fn draw_quad_absolute<P: IntoPoint>(
         &mut self,
         p0: P,
         p1: P,
@@ -456,11 +1536,21 @@ impl Canvas {
         stroke: Option<Stroke>,
         fill: Option<Color>,
     ) {
-        self.draw_shape_absolute(
-            vec![p0.into(), p1.into(), p2.into(), p3.into()],
+        let points = [
+            p0.into_point(),
+            p1.into_point(),
+            p2.into_point(),
+            p3.into_point(),
+        ];
+        self.begin_call(|| DrawCall::Quad {
+            points,
             stroke,
             fill,
-        );
+            absolute: true,
+        });
+
+        self.draw_shape_absolute(points.to_vec(), stroke, fill);
+        self.end_call();
     }
 
     /// Create and draw a path onto the canvas, projected from the camera.
@@ -484,7 +1574,7 @@ impl Canvas {
     }
 
     /// Draw a quadratic bezier curve onto the canvas, projected from the camera.
-    pub fn draw_quadratic_bezier<P: Into<Vec2>>(
+    pub fn draw_quadratic_bezier<P: IntoPoint>(
         &mut self,
         start_point: P,
         control_point: P,
@@ -492,14 +1582,29 @@ impl Canvas {
         stroke: Option<Stroke>,
         fill: Option<Color>,
     ) {
+        let (start_point, control_point, end_point) = (
+            start_point.into_point(),
+            control_point.into_point(),
+            end_point.into_point(),
+        );
+        self.begin_call(|| DrawCall::QuadraticBezier {
+            start_point,
+            control_point,
+            end_point,
+            stroke,
+            fill,
+            absolute: false,
+        });
+
         self.draw_path(stroke, fill, |path| {
-            path.move_to(start_point.into())
-                .quadratic_bezier_to(end_point.into(), control_point.into())
+            path.move_to(start_point)
+                .quadratic_bezier_to(end_point, control_point)
         });
+        self.end_call();
     }
 
     /// Draw a quadratic bezier curve directly onto the canvas..
-    pub fn draw_quadratic_bezier_absolute<P: Into<Vec2>>(
+    pub fn draw_quadratic_bezier_absolute<P: IntoPoint>(
         &mut self,
         start_point: P,
         control_point: P,
@@ -507,14 +1612,29 @@ impl Canvas {
         stroke: Option<Stroke>,
         fill: Option<Color>,
     ) {
+        let (start_point, control_point, end_point) = (
+            start_point.into_point(),
+            control_point.into_point(),
+            end_point.into_point(),
+        );
+        self.begin_call(|| DrawCall::QuadraticBezier {
+            start_point,
+            control_point,
+            end_point,
+            stroke,
+            fill,
+            absolute: true,
+        });
+
         self.draw_path_absolute(stroke, fill, |path| {
-            path.move_to(start_point.into())
-                .quadratic_bezier_to(end_point.into(), control_point.into())
+            path.move_to(start_point)
+                .quadratic_bezier_to(end_point, control_point)
         });
+        self.end_call();
     }
 
     /// Draw a cubic bezier curve onto the canvas, projected from the camera.
-    pub fn draw_cubic_bezier<P: Into<Vec2>>(
+    pub fn draw_cubic_bezier<P: IntoPoint>(
         &mut self,
         start_point: P,
         control_point_0: P,
@@ -523,17 +1643,28 @@ impl Canvas {
         stroke: Option<Stroke>,
         fill: Option<Color>,
     ) {
+        let (start_point, end_point) = (start_point.into_point(), end_point.into_point());
+        let (control_point_0, control_point_1) =
+            (control_point_0.into_point(), control_point_1.into_point());
+        self.begin_call(|| DrawCall::CubicBezier {
+            start_point,
+            control_point_0,
+            control_point_1,
+            end_point,
+            stroke,
+            fill,
+            absolute: false,
+        });
+
         self.draw_path(stroke, fill, |path| {
-            path.move_to(start_point.into()).cubic_bezier_to(
-                end_point.into(),
-                control_point_0.into(),
-                control_point_1.into(),
-            )
+            path.move_to(start_point)
+                .cubic_bezier_to(end_point, control_point_0, control_point_1)
         });
+        self.end_call();
     }
 
     /// Draw a cubic bezier curve directly onto the canvas.
-    pub fn draw_cubic_bezier_absolute<P: Into<Vec2>>(
+    pub fn draw_cubic_bezier_absolute<P: IntoPoint>(
         &mut self,
         start_point: P,
         control_point_0: P,
@@ -542,90 +1673,1028 @@ impl Canvas {
         stroke: Option<Stroke>,
         fill: Option<Color>,
     ) {
+        let (start_point, end_point) = (start_point.into_point(), end_point.into_point());
+        let (control_point_0, control_point_1) =
+            (control_point_0.into_point(), control_point_1.into_point());
+        self.begin_call(|| DrawCall::CubicBezier {
+            start_point,
+            control_point_0,
+            control_point_1,
+            end_point,
+            stroke,
+            fill,
+            absolute: true,
+        });
+
         self.draw_path_absolute(stroke, fill, |path| {
-            path.move_to(start_point.into()).cubic_bezier_to(
-                end_point.into(),
-                control_point_0.into(),
-                control_point_1.into(),
-            )
+            path.move_to(start_point)
+                .cubic_bezier_to(end_point, control_point_0, control_point_1)
         });
+        self.end_call();
     }
 
     /// Draw a straight line onto the canvas, projected from the camera.
-    pub fn draw_line<P: Into<Vec2>>(
+    pub fn draw_line<P: IntoPoint>(
         &mut self,
         p0: P,
         p1: P,
         stroke: Option<Stroke>,
         fill: Option<Color>,
     ) {
-        self.draw_shape(vec![p0.into(), p1.into()], stroke, fill);
+        let (p0, p1) = (p0.into_point(), p1.into_point());
+        self.begin_call(|| DrawCall::Line {
+            p0,
+            p1,
+            stroke,
+            fill,
+            absolute: false,
+        });
+
+        self.draw_shape(vec![p0, p1], stroke, fill);
+        self.end_call();
     }
 
     /// Draw a straight line directly onto the canvas.
-    pub fn draw_line_absolute<P: Into<Vec2>>(
+    pub fn draw_line_absolute<P: IntoPoint>(
         &mut self,
         p0: P,
         p1: P,
         stroke: Option<Stroke>,
         fill: Option<Color>,
     ) {
-        self.draw_shape_absolute(vec![p0.into(), p1.into()], stroke, fill);
+        let (p0, p1) = (p0.into_point(), p1.into_point());
+        self.begin_call(|| DrawCall::Line {
+            p0,
+            p1,
+            stroke,
+            fill,
+            absolute: true,
+        });
+
+        self.draw_shape_absolute(vec![p0, p1], stroke, fill);
+        self.end_call();
     }
 
     /// Draw a line made of several segments onto the canvas, projected from the camera.
-    pub fn draw_polyline<C: Into<Vec<Vec2>>>(&mut self, points: C, stroke: Stroke) {
+    pub fn draw_polyline<C: IntoPoints>(&mut self, points: C, stroke: Stroke) {
+        let points = points.into_points();
+        self.begin_call(|| DrawCall::Polyline {
+            points: points.clone(),
+            stroke,
+            absolute: false,
+        });
+
         self.draw_shape(points, Some(stroke), None);
+        self.end_call();
     }
 
     /// Draw a line made of several segments directly onto the canvas.
-    pub fn draw_polyline_absolute<C: Into<Vec<Vec2>>>(&mut self, points: C, stroke: Stroke) {
-        self.draw_shape_absolute(points, Some(stroke), None);
-    }
-
-    /// Draw a solid shape made of several sides onto the canvas, projected from the camera.
-    pub fn draw_polygon<C: Into<Vec<Vec2>>>(&mut self, points: C, fill: Color) {
-        self.draw_shape(points, None, Some(fill));
-    }
+    pub fn draw_polyline_absolute<C: IntoPoints>(&mut self, points: C, stroke: Stroke) {
+        let points = points.into_points();
+        self.begin_call(|| DrawCall::Polyline {
+            points: points.clone(),
+            stroke,
+            absolute: true,
+        });
 
-    /// Draw a solid shape made of several sides directly onto the canvas.
-    pub fn draw_polygon_absolute<C: Into<Vec<Vec2>>>(&mut self, points: C, fill: Color) {
-        self.draw_shape_absolute(points, None, Some(fill));
+        self.draw_shape_absolute(points, Some(stroke), None);
+        self.end_call();
     }
 
-    /// Transform any given point from world space to camera space.
-    /// Allows to scale to a given resolution width.
-    pub fn to_camera_space<P: Into<Vec2>>(&self, point: P) -> Vec2 {
-        self.to_camera_matrix.mul_vec2(point.into() - self.translation)
+    /// Draw a line made of several segments onto the canvas, projected from the camera, with a color that changes along its length.
+    ///
+    /// The [Gradient] is sampled by arc length, from `0.0` at the first point to `1.0` at the last.
+    /// The line is split into one shape per point, so the color is as detailed as the points are dense.
+    pub fn draw_gradient_polyline<C: IntoPoints>(
+        &mut self,
+        points: C,
+        gradient: &Gradient,
+        width: f32,
+        line_end: LineEnd,
+    ) {
+        for (piece, color, end) in gradient_pieces(points.into_points(), gradient, line_end) {
+            self.draw_shape(piece, Some(Stroke::new(color, width, end)), None);
+        }
     }
 
-    /// Transform any given point from camera space to world space.
-    pub fn to_world_space<P: Into<Vec2>>(&self, point: P) -> Vec2 {
-        self.to_world_matrix.mul_vec2(point.into()) + self.translation
+    /// Draw a line made of several segments directly onto the canvas, with a color that changes along its length.
+    ///
+    /// The [Gradient] is sampled by arc length, from `0.0` at the first point to `1.0` at the last.
+    /// The line is split into one shape per point, so the color is as detailed as the points are dense.
+    pub fn draw_gradient_polyline_absolute<C: IntoPoints>(
+        &mut self,
+        points: C,
+        gradient: &Gradient,
+        width: f32,
+        line_end: LineEnd,
+    ) {
+        for (piece, color, end) in gradient_pieces(points.into_points(), gradient, line_end) {
+            self.draw_shape_absolute(piece, Some(Stroke::new(color, width, end)), None);
+        }
     }
 
-    /// Get the canvas' points per unit.
+    /// Draw a line whose width changes along its length onto the canvas, projected from the camera.
     ///
-    /// This is essentially how detailed it will generate certain kinds of geometry (bezier curves, circles).
-    pub fn points_per_unit(&self) -> usize {
-        self.points_per_unit
+    /// `widths` can be a list of per-point widths or a function of the distance along the line (see [WidthProfile]).
+    /// The line is expanded into a filled outline, so it has flat ends and is filled with `color`.
+    pub fn draw_variable_width_polyline<C: IntoPoints, W: WidthProfile>(
+        &mut self,
+        points: C,
+        widths: W,
+        color: Color,
+    ) {
+        let outline = variable_width_outline(&points.into_points(), &widths);
+        self.draw_shape(outline, None, Some(color));
     }
 
-    /// Set the canvas' points per unit.
+    /// Draw a line whose width changes along its length directly onto the canvas.
     ///
-    /// This is essentially how detailed it will generate certain kinds of geometry (bezier curves, circles).
-    pub fn set_points_per_unit(&mut self, points_per_unit: usize) {
-        self.points_per_unit = points_per_unit;
+    /// `widths` can be a list of per-point widths or a function of the distance along the line (see [WidthProfile]).
+    /// The line is expanded into a filled outline, so it has flat ends and is filled with `color`.
+    pub fn draw_variable_width_polyline_absolute<C: IntoPoints, W: WidthProfile>(
+        &mut self,
+        points: C,
+        widths: W,
+        color: Color,
+    ) {
+        let outline = variable_width_outline(&points.into_points(), &widths);
+        self.draw_shape_absolute(outline, None, Some(color));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Draw a [Brush] stroke along a path onto the canvas, projected from the camera.
+    pub fn draw_brush_stroke<C: IntoPoints>(&mut self, path: C, brush: &Brush) {
+        let first_new = self.shapes.len();
+        self.enter_call();
 
-    const EPSILON: f32 = 0.001;
+        for stamp in brush.stamps(&path.into_points()) {
+            self.draw_shape(stamp, brush.stroke, brush.fill);
+        }
 
-    /// Assert that two [Vec2] are within [EPSILON] of each other.
+        for shape in &mut self.shapes[first_new..] {
+            shape.blend_mode = brush.blend_mode;
+        }
+
+        // The stamps are random, so record the shapes they made rather than the call.
+        if self.end_call() {
+            let shapes = self.shapes[first_new..].to_vec();
+            self.record(|| DrawCall::Shapes(shapes));
+        }
+    }
+
+    /// Draw a [Brush] stroke along a path directly onto the canvas.
+    pub fn draw_brush_stroke_absolute<C: IntoPoints>(&mut self, path: C, brush: &Brush) {
+        let first_new = self.shapes.len();
+        self.enter_call();
+
+        for stamp in brush.stamps(&path.into_points()) {
+            self.draw_shape_absolute(stamp, brush.stroke, brush.fill);
+        }
+
+        for shape in &mut self.shapes[first_new..] {
+            shape.blend_mode = brush.blend_mode;
+        }
+
+        // The stamps are random, so record the shapes they made rather than the call.
+        if self.end_call() {
+            let shapes = self.shapes[first_new..].to_vec();
+            self.record(|| DrawCall::Shapes(shapes));
+        }
+    }
+
+    /// Draw a copy of `shape` for each transform onto the canvas, projected from the camera.
+    ///
+    /// The points of `shape` are in its own local space, and each transform places a copy of it in front of the camera.
+    /// Stroke widths are not scaled by the transforms.
+    pub fn draw_instances<I: IntoIterator<Item = Affine2>>(
+        &mut self,
+        shape: &Shape,
+        transforms: I,
+    ) {
+        let transforms: Vec<Affine2> = transforms.into_iter().collect();
+        self.begin_call(|| DrawCall::Instances {
+            shape: shape.clone(),
+            transforms: transforms.clone(),
+            absolute: false,
+        });
+
+        for transform in transforms {
+            let mut instance = shape.clone();
+            instance.map_points(|point| self.to_world_space(transform.transform_point2(point)));
+            self.draw_raw_shape(instance);
+        }
+        self.end_call();
+    }
+
+    /// Draw a copy of `shape` for each transform directly onto the canvas.
+    ///
+    /// The points of `shape` are in its own local space, and each transform places a copy of it in World Space.
+    /// Stroke widths are not scaled by the transforms.
+    pub fn draw_instances_absolute<I: IntoIterator<Item = Affine2>>(
+        &mut self,
+        shape: &Shape,
+        transforms: I,
+    ) {
+        let transforms: Vec<Affine2> = transforms.into_iter().collect();
+        self.begin_call(|| DrawCall::Instances {
+            shape: shape.clone(),
+            transforms: transforms.clone(),
+            absolute: true,
+        });
+
+        for transform in transforms {
+            let mut instance = shape.clone();
+            instance.map_points(|point| transform.transform_point2(point));
+            self.draw_raw_shape(instance);
+        }
+        self.end_call();
+    }
+
+    /// Draw a copy of `shape` at a random spot between `min` and `max` in World Space, where it does not touch anything already on the canvas.
+    ///
+    /// The points of `shape` are in its own local space. Returns the transform that placed it, or `None` if [ShapePlacement] found no free spot, in which case nothing is drawn.
+    /// Calling this repeatedly builds up a packed composition.
+    pub fn place_shape(
+        &mut self,
+        shape: &Shape,
+        min: Vec2,
+        max: Vec2,
+        placement: &ShapePlacement,
+    ) -> Option<Affine2> {
+        // Try new transforms each time, even with the same placement.
+        let placement = ShapePlacement {
+            seed: placement.seed ^ self.shapes.len() as u64,
+            ..*placement
+        };
+        let transform = placement.find(shape, &self.shapes, min, max)?;
+
+        self.draw_instances_absolute(shape, [transform]);
+        Some(transform)
+    }
+
+    /// Fill a grid of square cells `cell_size` wide, with its bottom left corner at `min` in World Space, with tiles from `tiling`.
+    ///
+    /// Returns where each tile went, or `None` if [Tiling::layout] could not fit the tiles together, in which case nothing is drawn.
+    pub fn draw_tiling(
+        &mut self,
+        tiling: &Tiling,
+        min: Vec2,
+        cell_size: f32,
+        columns: usize,
+        rows: usize,
+    ) -> Option<Vec<PlacedTile>> {
+        let placed = tiling.layout(columns, rows)?;
+        self.draw_placed_tiles(tiling, &placed, min, cell_size);
+        Some(placed)
+    }
+
+    /// Draw tiles from `tiling` where they were placed, such as by [Tiling::collapse], onto a grid of square cells `cell_size` wide,
+    /// with its bottom left corner at `min` in World Space.
+    pub fn draw_placed_tiles(
+        &mut self,
+        tiling: &Tiling,
+        placed: &[PlacedTile],
+        min: Vec2,
+        cell_size: f32,
+    ) {
+        for (index, tile) in tiling.tiles.iter().enumerate() {
+            let transforms: Vec<Affine2> = placed
+                .iter()
+                .filter(|placed| placed.tile == index)
+                .map(|placed| placed.transform(min, cell_size))
+                .collect();
+
+            for shape in &tile.shapes {
+                self.draw_instances_absolute(shape, transforms.iter().copied());
+            }
+        }
+    }
+
+    /// Draw `solids` with an [Axonometric] projection in World Space, back to front and shaded. See [Axonometric::shapes].
+    pub fn draw_solids(
+        &mut self,
+        projection: &Axonometric,
+        solids: &[Solid],
+        shading: &Shading,
+        stroke: Option<Stroke>,
+    ) {
+        for shape in projection.shapes(solids, shading, stroke) {
+            self.draw_raw_shape(shape);
+        }
+    }
+
+    /// Draw a solid shape made of several sides onto the canvas, projected from the camera.
+    pub fn draw_polygon<C: IntoPoints>(&mut self, points: C, fill: Color) {
+        let points = points.into_points();
+        self.begin_call(|| DrawCall::Polygon {
+            points: points.clone(),
+            fill,
+            absolute: false,
+        });
+
+        self.draw_shape(points, None, Some(fill));
+        self.end_call();
+    }
+
+    /// Draw a solid shape made of several sides directly onto the canvas.
+    pub fn draw_polygon_absolute<C: IntoPoints>(&mut self, points: C, fill: Color) {
+        let points = points.into_points();
+        self.begin_call(|| DrawCall::Polygon {
+            points: points.clone(),
+            fill,
+            absolute: true,
+        });
+
+        self.draw_shape_absolute(points, None, Some(fill));
+        self.end_call();
+    }
+
+    /// Transform any given point from world space to camera space.
+    /// Allows to scale to a given resolution width.
+    pub fn to_camera_space<P: IntoPoint>(&self, point: P) -> Vec2 {
+        self.to_camera_matrix
+            .mul_vec2(point.into_point() - self.translation)
+    }
+
+    /// Transform any given point from camera space to world space.
+    pub fn to_world_space<P: IntoPoint>(&self, point: P) -> Vec2 {
+        self.to_world_matrix.mul_vec2(point.into_point()) + self.translation
+    }
+
+    /// The transform from camera space to world space, as applied by [to_world_space](Self::to_world_space).
+    pub(crate) fn to_world_transform(&self) -> Affine2 {
+        Affine2::from_mat2_translation(self.to_world_matrix, self.translation)
+    }
+
+    /// Get the canvas' points per unit.
+    ///
+    /// This is essentially how detailed it will generate certain kinds of geometry (bezier curves, circles).
+    pub fn points_per_unit(&self) -> usize {
+        self.points_per_unit
+    }
+
+    /// Set the canvas' points per unit.
+    ///
+    /// This is essentially how detailed it will generate certain kinds of geometry (bezier curves, circles).
+    pub fn set_points_per_unit(&mut self, points_per_unit: usize) {
+        self.record(|| DrawCall::SetPointsPerUnit(points_per_unit));
+        self.points_per_unit = points_per_unit;
+    }
+
+    /// Replicate every shape drawn from now on under a [Symmetry], or stop replicating them with `None`.
+    ///
+    /// The symmetry is centered on the origin of World Space, regardless of the camera.
+    pub fn set_symmetry(&mut self, symmetry: Option<Symmetry>) {
+        let transforms = match symmetry {
+            Some(symmetry) => symmetry.transforms(|radians| self.sin_cos(radians)),
+            None => Vec::new(),
+        };
+        self.replace_symmetry(transforms);
+    }
+
+    /// Replicate every shape drawn inside `f` under a [Symmetry].
+    ///
+    /// Symmetries nest: inside another [with_symmetry](Self::with_symmetry) or [set_symmetry](Self::set_symmetry), each copy is replicated again under the outer one.
+    pub fn with_symmetry<F: FnOnce(&mut Self)>(&mut self, symmetry: Symmetry, f: F) {
+        let inner = symmetry.transforms(|radians| self.sin_cos(radians));
+        let outer = if self.symmetry.is_empty() {
+            vec![Affine2::IDENTITY]
+        } else {
+            self.symmetry.clone()
+        };
+
+        let nested = outer
+            .iter()
+            .flat_map(|outer| inner.iter().map(move |inner| *outer * *inner))
+            .collect();
+        let previous = self.replace_symmetry(nested);
+
+        f(self);
+
+        self.replace_symmetry(previous);
+    }
+
+    /// Link every shape drawn inside `f` to `link`, unless it already has one. See [Shape::link].
+    pub fn with_link<S: Into<String>, F: FnOnce(&mut Self)>(&mut self, link: S, f: F) {
+        let previous = self.replace_link(Some(link.into()));
+
+        f(self);
+
+        self.replace_link(previous);
+    }
+
+    /// Give every shape drawn inside `f` a `tooltip`, unless it already has one. See [Shape::tooltip].
+    pub fn with_tooltip<S: Into<String>, F: FnOnce(&mut Self)>(&mut self, tooltip: S, f: F) {
+        let previous = self.replace_tooltip(Some(tooltip.into()));
+
+        f(self);
+
+        self.replace_tooltip(previous);
+    }
+
+    /// Tag every shape drawn inside `f` with `tag`, unless it already has one. See [Shape::tag].
+    pub fn with_tag<S: Into<String>, F: FnOnce(&mut Self)>(&mut self, tag: S, f: F) {
+        let previous = self.replace_tag(Some(tag.into()));
+
+        f(self);
+
+        self.replace_tag(previous);
+    }
+
+    /// Get the [Style] given to shapes drawn with neither a stroke nor a fill.
+    pub fn style(&self) -> Style {
+        self.style
+    }
+
+    /// Give every shape drawn from now on with neither a stroke nor a fill the stroke and fill of `style`,
+    /// instead of leaving it invisible.
+    ///
+    /// Pass `None` for both the stroke and fill of a draw call to inherit the style, and `Some` for either to override it.
+    pub fn set_style(&mut self, style: Style) {
+        self.replace_style(style);
+    }
+
+    /// Give every shape drawn inside `f` with neither a stroke nor a fill the stroke and fill of `style`. See [set_style](Self::set_style).
+    ///
+    /// Styles cascade: whichever of the stroke and fill `style` leaves as `None` are kept from the style outside.
+    pub fn with_style<F: FnOnce(&mut Self)>(&mut self, style: Style, f: F) {
+        let cascaded = Style {
+            stroke: style.stroke.or(self.style.stroke),
+            fill: style.fill.or(self.style.fill),
+        };
+        let previous = self.replace_style(cascaded);
+
+        f(self);
+
+        self.replace_style(previous);
+    }
+
+    /// Swap in a new style for drawn shapes, returning the old one.
+    fn replace_style(&mut self, style: Style) -> Style {
+        self.record(|| DrawCall::Style(style));
+        std::mem::replace(&mut self.style, style)
+    }
+
+    /// Swap in a new link for drawn shapes, returning the old one.
+    fn replace_link(&mut self, link: Option<String>) -> Option<String> {
+        self.record(|| DrawCall::Link(link.clone()));
+        std::mem::replace(&mut self.link, link)
+    }
+
+    /// Swap in a new tooltip for drawn shapes, returning the old one.
+    fn replace_tooltip(&mut self, tooltip: Option<String>) -> Option<String> {
+        self.record(|| DrawCall::Tooltip(tooltip.clone()));
+        std::mem::replace(&mut self.tooltip, tooltip)
+    }
+
+    /// Swap in a new tag for drawn shapes, returning the old one.
+    fn replace_tag(&mut self, tag: Option<String>) -> Option<String> {
+        self.record(|| DrawCall::Tag(tag.clone()));
+        std::mem::replace(&mut self.tag, tag)
+    }
+
+    /// Swap in new symmetry transforms, returning the old ones.
+    fn replace_symmetry(&mut self, transforms: Vec<Affine2>) -> Vec<Affine2> {
+        self.record(|| DrawCall::Symmetry(transforms.clone()));
+        std::mem::replace(&mut self.symmetry, transforms)
+    }
+
+    /// Start recording every call made on the canvas, discarding anything recorded before.
+    ///
+    /// See [DrawCall] for what is recorded.
+    pub fn start_recording(&mut self) {
+        self.recording = Some(Recording::default());
+    }
+
+    /// Stop recording, returning every call made since [start_recording](Self::start_recording).
+    pub fn stop_recording(&mut self) -> Vec<DrawCall> {
+        self.recording
+            .take()
+            .map(|recording| recording.calls)
+            .unwrap_or_default()
+    }
+
+    /// Make each recorded call on this canvas, in order.
+    ///
+    /// Replaying onto a new canvas with the same [points_per_unit](Self::points_per_unit) rebuilds the recorded one exactly.
+    /// Replay only part of a recording to see the canvas as it was partway through.
+    pub fn replay<'a, I: IntoIterator<Item = &'a DrawCall>>(&mut self, calls: I) {
+        for call in calls {
+            match call.clone() {
+                DrawCall::Shape {
+                    points,
+                    stroke,
+                    fill,
+                    absolute,
+                } => {
+                    if absolute {
+                        self.draw_shape_absolute(points, stroke, fill)
+                    } else {
+                        self.draw_shape(points, stroke, fill)
+                    }
+                }
+                DrawCall::CompoundShape {
+                    paths,
+                    stroke,
+                    fill,
+                    fill_rule,
+                    absolute,
+                } => {
+                    if absolute {
+                        self.draw_compound_shape_absolute(paths, stroke, fill, fill_rule)
+                    } else {
+                        self.draw_compound_shape(paths, stroke, fill, fill_rule)
+                    }
+                }
+                DrawCall::PolygonWithHoles {
+                    outer,
+                    holes,
+                    stroke,
+                    fill,
+                    absolute,
+                } => {
+                    if absolute {
+                        self.draw_polygon_with_holes_absolute(outer, holes, stroke, fill)
+                    } else {
+                        self.draw_polygon_with_holes(outer, holes, stroke, fill)
+                    }
+                }
+                DrawCall::Rect {
+                    top_left,
+                    bottom_right,
+                    stroke,
+                    fill,
+                    absolute,
+                } => {
+                    if absolute {
+                        self.draw_rect_absolute(top_left, bottom_right, stroke, fill)
+                    } else {
+                        self.draw_rect(top_left, bottom_right, stroke, fill)
+                    }
+                }
+                DrawCall::RegularPolygon {
+                    center,
+                    sides,
+                    radius,
+                    rotation,
+                    stroke,
+                    fill,
+                    absolute,
+                } => {
+                    if absolute {
+                        self.draw_regular_polygon_absolute(
+                            center, sides, radius, rotation, stroke, fill,
+                        )
+                    } else {
+                        self.draw_regular_polygon(center, sides, radius, rotation, stroke, fill)
+                    }
+                }
+                DrawCall::Circle {
+                    center,
+                    radius,
+                    stroke,
+                    fill,
+                    absolute,
+                } => {
+                    if absolute {
+                        self.draw_circle_absolute(center, radius, stroke, fill)
+                    } else {
+                        self.draw_circle(center, radius, stroke, fill)
+                    }
+                }
+                DrawCall::Triangle {
+                    points: [p0, p1, p2],
+                    stroke,
+                    fill,
+                    absolute,
+                } => {
+                    if absolute {
+                        self.draw_triangle_absolute(p0, p1, p2, stroke, fill)
+                    } else {
+                        self.draw_triangle(p0, p1, p2, stroke, fill)
+                    }
+                }
+                DrawCall::Quad {
+                    points: [p0, p1, p2, p3],
+                    stroke,
+                    fill,
+                    absolute,
+                } => {
+                    if absolute {
+                        self.draw_quad_absolute(p0, p1, p2, p3, stroke, fill)
+                    } else {
+                        self.draw_quad(p0, p1, p2, p3, stroke, fill)
+                    }
+                }
+                DrawCall::QuadraticBezier {
+                    start_point,
+                    control_point,
+                    end_point,
+                    stroke,
+                    fill,
+                    absolute,
+                } => {
+                    if absolute {
+                        self.draw_quadratic_bezier_absolute(
+                            start_point,
+                            control_point,
+                            end_point,
+                            stroke,
+                            fill,
+                        )
+                    } else {
+                        self.draw_quadratic_bezier(
+                            start_point,
+                            control_point,
+                            end_point,
+                            stroke,
+                            fill,
+                        )
+                    }
+                }
+                DrawCall::CubicBezier {
+                    start_point,
+                    control_point_0,
+                    control_point_1,
+                    end_point,
+                    stroke,
+                    fill,
+                    absolute,
+                } => {
+                    if absolute {
+                        self.draw_cubic_bezier_absolute(
+                            start_point,
+                            control_point_0,
+                            control_point_1,
+                            end_point,
+                            stroke,
+                            fill,
+                        )
+                    } else {
+                        self.draw_cubic_bezier(
+                            start_point,
+                            control_point_0,
+                            control_point_1,
+                            end_point,
+                            stroke,
+                            fill,
+                        )
+                    }
+                }
+                DrawCall::Line {
+                    p0,
+                    p1,
+                    stroke,
+                    fill,
+                    absolute,
+                } => {
+                    if absolute {
+                        self.draw_line_absolute(p0, p1, stroke, fill)
+                    } else {
+                        self.draw_line(p0, p1, stroke, fill)
+                    }
+                }
+                DrawCall::Polyline {
+                    points,
+                    stroke,
+                    absolute,
+                } => {
+                    if absolute {
+                        self.draw_polyline_absolute(points, stroke)
+                    } else {
+                        self.draw_polyline(points, stroke)
+                    }
+                }
+                DrawCall::Polygon {
+                    points,
+                    fill,
+                    absolute,
+                } => {
+                    if absolute {
+                        self.draw_polygon_absolute(points, fill)
+                    } else {
+                        self.draw_polygon(points, fill)
+                    }
+                }
+                DrawCall::Instances {
+                    shape,
+                    transforms,
+                    absolute,
+                } => {
+                    if absolute {
+                        self.draw_instances_absolute(&shape, transforms)
+                    } else {
+                        self.draw_instances(&shape, transforms)
+                    }
+                }
+                DrawCall::RawShape(shape) => self.draw_raw_shape(shape),
+                DrawCall::Shapes(shapes) => {
+                    self.record(|| DrawCall::Shapes(shapes.clone()));
+                    self.shapes.extend(shapes);
+                }
+                DrawCall::RotateCamera(radians) => self.rotate_camera(radians),
+                DrawCall::MoveCamera(translation) => self.move_camera(translation),
+                DrawCall::ZoomCamera(zoom) => self.zoom_camera(zoom),
+                DrawCall::SetPointsPerUnit(points_per_unit) => {
+                    self.set_points_per_unit(points_per_unit)
+                }
+                DrawCall::SetDeterministic(deterministic) => self.set_deterministic(deterministic),
+                DrawCall::Symmetry(transforms) => {
+                    self.replace_symmetry(transforms);
+                }
+                DrawCall::Link(link) => {
+                    self.replace_link(link);
+                }
+                DrawCall::Tooltip(tooltip) => {
+                    self.replace_tooltip(tooltip);
+                }
+                DrawCall::Tag(tag) => {
+                    self.replace_tag(tag);
+                }
+                DrawCall::Style(style) => {
+                    self.replace_style(style);
+                }
+                DrawCall::Clear => self.clear(),
+                DrawCall::HatchFills { hatch, pen_width } => self.hatch_fills(&hatch, pen_width),
+                DrawCall::OptimizePenTravel { merge_tolerance } => {
+                    self.optimize_pen_travel(merge_tolerance)
+                }
+                DrawCall::RemoveHiddenLines => self.remove_hidden_lines(),
+                DrawCall::Flatten => self.flatten(),
+                DrawCall::BakeCamera => self.bake_camera(),
+                DrawCall::ClipTo(region) => self.clip_to(&region),
+                DrawCall::Distort {
+                    distortion,
+                    tolerance,
+                } => self.distort(&distortion, tolerance),
+                DrawCall::Warp { warp, tolerance } => self.warp(&warp, tolerance),
+            }
+        }
+    }
+
+    /// Render an animation of `calls` being [replayed](Self::replay) onto a copy of this canvas, with shapes appearing in the order they were drawn.
+    ///
+    /// Every new shape gets an equal share of the `frames`, and is revealed according to `reveal`. The last frame shows the finished canvas.
+    /// Calls that change shapes already on the canvas, such as [clear](Self::clear) or [hatch_fills](Self::hatch_fills), take effect all at once.
+    /// `renderer` is called to create a fresh renderer for each frame.
+    pub fn render_build_animation<R, F>(
+        &self,
+        calls: &[DrawCall],
+        frames: usize,
+        reveal: Reveal,
+        mut renderer: F,
+    ) -> Result<Vec<R::Output>, Error>
+    where
+        R: Renderer,
+        F: FnMut() -> Result<R, Error>,
+    {
+        let mut canvas = self.clone();
+        canvas.recording = None;
+
+        // The shapes before each call that changes existing shapes, and at the end.
+        // Each step reveals one shape of a stage, on top of every shape before it.
+        let mut stages: Vec<Vec<Shape>> = Vec::new();
+        let mut steps: Vec<(usize, usize)> = Vec::new();
+
+        for call in calls {
+            let changes_shapes = matches!(
+                call,
+                DrawCall::Clear
+                    | DrawCall::HatchFills { .. }
+                    | DrawCall::OptimizePenTravel { .. }
+                    | DrawCall::RemoveHiddenLines
+                    | DrawCall::Flatten
+                    | DrawCall::BakeCamera
+                    | DrawCall::ClipTo(_)
+                    | DrawCall::Distort { .. }
+                    | DrawCall::Warp { .. }
+            );
+            if changes_shapes {
+                stages.push(canvas.shapes.clone());
+            }
+
+            let before = canvas.shapes.len();
+            canvas.replay([call]);
+            let stage = stages.len();
+
+            if changes_shapes {
+                steps.push((stage, canvas.shapes.len()));
+            } else {
+                steps.extend((before..canvas.shapes.len()).map(|index| (stage, index)));
+            }
+        }
+        stages.push(std::mem::take(&mut canvas.shapes));
+
+        let mut rendered = Vec::with_capacity(frames);
+        for frame in 0..frames {
+            let position = (frame + 1) * steps.len();
+            let step = position / frames;
+            let progress = (position % frames) as f32 / frames as f32;
+
+            canvas.shapes = match steps.get(step) {
+                Some(&(stage, index)) => {
+                    let shapes = &stages[stage];
+                    let mut visible = shapes[..index.min(shapes.len())].to_vec();
+                    visible.extend(
+                        shapes
+                            .get(index)
+                            .and_then(|shape| reveal.partial(shape, progress)),
+                    );
+                    visible
+                }
+                None => stages[stages.len() - 1].clone(),
+            };
+
+            rendered.push(canvas.render(renderer()?)?);
+        }
+
+        Ok(rendered)
+    }
+
+    /// Whether the canvas generates bit-identical geometry on every platform.
+    pub fn is_deterministic(&self) -> bool {
+        self.deterministic
+    }
+
+    /// Set whether the canvas generates bit-identical geometry on every platform.
+    ///
+    /// The platform's math library may disagree in the last bit of trigonometric functions,
+    /// which can shift tessellated circles and rotated cameras enough to change a few pixels.
+    /// When enabled, the canvas uses its own portable implementation instead. This is slightly slower.
+    /// Brush strokes, hatch fills and halftones always use the portable implementation, whether or not this is enabled.
+    ///
+    /// Shapes are always rendered in the order they were drawn, and rendering is single-threaded,
+    /// so this is all that is needed for golden image tests to pass identically across platforms when using the [SvgRenderer](crate::renderers::SvgRenderer).
+    /// Raster backends may still differ if their own rasterization is platform-dependent.
+    pub fn set_deterministic(&mut self, deterministic: bool) {
+        self.record(|| DrawCall::SetDeterministic(deterministic));
+        self.deterministic = deterministic;
+    }
+
+    /// Add a call to the recording, if there is one, unless it was made by another call being recorded.
+    fn record<F: FnOnce() -> DrawCall>(&mut self, call: F) {
+        if let Some(recording) = &mut self.recording {
+            if recording.depth == 0 {
+                recording.calls.push(call());
+            }
+        }
+    }
+
+    /// Record an edit to the shapes on the canvas that cannot be replayed from its arguments, such as a closure, as the shapes it left.
+    fn record_edit(&mut self) {
+        if self.recording.is_some() {
+            let shapes = self.shapes.clone();
+            self.record(|| DrawCall::Clear);
+            self.record(|| DrawCall::Shapes(shapes));
+        }
+    }
+
+    /// [Record](Self::record) a call, then [enter](Self::enter_call) it.
+    fn begin_call<F: FnOnce() -> DrawCall>(&mut self, call: F) {
+        self.record(call);
+        self.enter_call();
+    }
+
+    /// Stop recording the calls made by the current call, until it [ends](Self::end_call).
+    fn enter_call(&mut self) {
+        if let Some(recording) = &mut self.recording {
+            recording.depth += 1;
+        }
+    }
+
+    /// Finish the current call. Returns `true` if calls are being recorded again.
+    fn end_call(&mut self) -> bool {
+        match &mut self.recording {
+            Some(recording) => {
+                recording.depth -= 1;
+                recording.depth == 0
+            }
+            None => false,
+        }
+    }
+
+    /// Sine and cosine, respecting [set_deterministic](Self::set_deterministic).
+    pub(crate) fn sin_cos(&self, radians: f32) -> (f32, f32) {
+        if self.deterministic {
+            portable_sin_cos(radians)
+        } else {
+            radians.sin_cos()
+        }
+    }
+}
+
+/// Make sure a ring's first and last points are the same.
+fn close_ring(mut ring: Vec<Vec2>) -> Vec<Vec2> {
+    if let (Some(first), Some(last)) = (ring.first(), ring.last()) {
+        if first != last {
+            ring.push(*first);
+        }
+    }
+    ring
+}
+
+/// Remove consecutive duplicate points.
+fn dedup_points(mut points: Vec<Vec2>) -> Vec<Vec2> {
+    points.dedup();
+    points
+}
+
+/// Apply a closed shape's [StrokeAlignment] by splitting it into a fill and an offset stroke.
+///
+/// The fill is returned first, so the stroke is drawn on top of it.
+fn align_stroke(shape: Shape) -> Vec<Shape> {
+    let distance = match shape.stroke {
+        Some(stroke) if shape.is_polygon() => match stroke.alignment {
+            StrokeAlignment::Center => return vec![shape],
+            StrokeAlignment::Inside => -stroke.width / 2.0,
+            StrokeAlignment::Outside => stroke.width / 2.0,
+        },
+        _ => return vec![shape],
+    };
+
+    // A subpath inside the outline is a hole, so its stroke moves the opposite way.
+    let subpaths = shape
+        .subpaths
+        .iter()
+        .map(|subpath| match subpath.first() {
+            Some(first) if contains_point(&shape.points, *first) => {
+                offset_polygon(subpath, -distance)
+            }
+            _ => offset_polygon(subpath, distance),
+        })
+        .collect();
+
+    let stroke = Shape {
+        points: offset_polygon(&shape.points, distance),
+        subpaths,
+        fill: None,
+        ..shape.clone()
+    };
+
+    if shape.fill.is_some() {
+        let fill = Shape {
+            stroke: None,
+            ..shape
+        };
+        vec![fill, stroke]
+    } else {
+        vec![stroke]
+    }
+}
+
+/// Split a polyline into one piece per point, each colored by the gradient at that point's arc length.
+///
+/// Pieces meet at the midpoints of the original segments, where the line is straight, so butt ends join seamlessly.
+/// Only the outermost ends of the line use `line_end`.
+fn gradient_pieces(
+    points: Vec<Vec2>,
+    gradient: &Gradient,
+    line_end: LineEnd,
+) -> Vec<(Vec<Vec2>, Color, LineEnd)> {
+    if points.len() <= 1 {
+        return Vec::new();
+    }
+
+    let distances = arc_lengths(&points);
+    let total = distances[distances.len() - 1];
+
+    let last = points.len() - 1;
+    let midpoint = |i: usize| (points[i] + points[i + 1]) / 2.0;
+
+    (0..points.len())
+        .map(|i| {
+            let mut piece = Vec::with_capacity(3);
+            piece.push(if i == 0 { points[0] } else { midpoint(i - 1) });
+            piece.push(points[i]);
+            if i < last {
+                piece.push(midpoint(i));
+            }
+
+            let t = if total > 0.0 {
+                distances[i] / total
+            } else {
+                0.0
+            };
+            let end = if i == 0 || i == last {
+                line_end
+            } else {
+                LineEnd::Butt
+            };
+
+            (piece, gradient.sample(t), end)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        custom_shape::custom_capsule, primitives::Capsule, renderers::SvgRenderer, IssueKind,
+    };
+
+    const EPSILON: f32 = 0.001;
+
+    /// Assert that two [Vec2] are within [EPSILON] of each other.
     #[inline]
     fn assert_vec2_eq<P: Into<Vec2>>(a: P, b: P) {
         let a: Vec2 = a.into();
@@ -765,6 +2834,20 @@ mod tests {
         assert_vec2_eq(canvas.to_world_space(Vec2::new(1.0, -1.0)), Vec2::new(0.5, -0.5));
     }
 
+    /// Verify that absolute circles are drawn around the given center, ignoring the camera.
+    #[test]
+    fn absolute_circle_ignores_camera() {
+        let mut canvas = Canvas::new(10);
+
+        canvas.move_camera(Vec2::ONE);
+        canvas.zoom_camera(2.0);
+        canvas.draw_circle_absolute((3.0, 4.0), 1.0, None, Some(Color::black()));
+
+        for point in &canvas.as_raw()[0].points {
+            assert!((point.distance(Vec2::new(3.0, 4.0)) - 1.0).abs() < EPSILON);
+        }
+    }
+
     /// Verify that a fully moved, rotated, and zoomed camera correctly transforms points when converting to camera space.
     #[test]
     fn full_transform_world_camera() {
@@ -796,4 +2879,469 @@ mod tests {
         assert_vec2_eq(canvas.to_world_space(Vec2::new(-1.0, 1.0)), Vec2::new(1.5,1.5));
         assert_vec2_eq(canvas.to_world_space(Vec2::new(1.0, -1.0)), Vec2::new(0.5, 0.5));
     }
+
+    /// A renderer that only counts how many shapes it was handed.
+    struct CountingRenderer(usize);
+
+    impl Renderer for CountingRenderer {
+        type Output = usize;
+
+        fn render(&mut self, _shape: &Shape) -> Result<(), Error> {
+            self.0 += 1;
+            Ok(())
+        }
+
+        fn finalize(self) -> Result<Self::Output, Error> {
+            Ok(self.0)
+        }
+    }
+
+    /// Verify that render statistics count rendered and skipped shapes.
+    #[test]
+    fn render_stats_counts_shapes() {
+        let mut canvas = Canvas::default();
+
+        canvas.draw_rect((-1.0, -1.0), (1.0, 1.0), None, Some(Color::black()));
+        canvas.draw_line((0.0, 0.0), (1.0, 1.0), None, Some(Color::black()));
+        canvas.as_raw_mut()[1].points.truncate(1);
+
+        let (rendered, stats) = canvas.render_with_stats(CountingRenderer(0)).unwrap();
+
+        assert_eq!(rendered, 1);
+        assert_eq!(stats.shapes_rendered, 1);
+        // Plain rendering leaves undrawable shapes to the renderer.
+        assert_eq!(canvas.render(CountingRenderer(0)).unwrap(), 2);
+        assert_eq!(stats.shapes_skipped, 1);
+        assert_eq!(stats.points_rendered, 5);
+    }
+
+    /// Verify that rendering a region skips shapes outside of it, and maps it to Camera Space.
+    #[test]
+    fn render_region_culls_shapes() {
+        let mut canvas = Canvas::default();
+
+        canvas.draw_rect((0.0, 0.0), (1.0, 1.0), None, Some(Color::black()));
+        canvas.draw_rect((10.0, 10.0), (11.0, 11.0), None, Some(Color::black()));
+        canvas.draw_line(
+            (2.05, 0.0),
+            (3.0, 0.0),
+            Some(Stroke::new(Color::black(), 0.1, LineEnd::Butt)),
+            None,
+        );
+
+        let pixels = UVec2::splat(100);
+        let rendered = canvas
+            .render_region(Vec2::ZERO, Vec2::new(2.0, 2.0), pixels, CountingRenderer(0))
+            .unwrap();
+        assert_eq!(rendered, 2);
+
+        // Corners can be given in either order.
+        let rendered = canvas
+            .render_region(
+                Vec2::new(13.0, 13.0),
+                Vec2::new(9.0, 9.0),
+                pixels,
+                CountingRenderer(0),
+            )
+            .unwrap();
+        assert_eq!(rendered, 1);
+
+        for (max, pixels) in [
+            (Vec2::new(2.0, 0.0), pixels),
+            (Vec2::new(0.0, 2.0), pixels),
+            (Vec2::splat(f32::NAN), pixels),
+            (Vec2::new(2.0, 1.0), pixels),
+            (Vec2::new(2.0, 2.0), UVec2::new(101, 100)),
+        ] {
+            assert!(matches!(
+                canvas.render_region(Vec2::ZERO, max, pixels, CountingRenderer(0)),
+                Err(Error::InvalidRegion { .. })
+            ));
+        }
+        assert_eq!(
+            canvas.render_region(
+                Vec2::ZERO,
+                Vec2::ONE,
+                UVec2::new(0, 100),
+                CountingRenderer(0)
+            ),
+            Err(Error::InvalidSize {
+                width: 0,
+                height: 100
+            })
+        );
+
+        canvas.set_validate_on_render(true);
+        canvas.as_raw_mut()[0].points[1].x = f32::NAN;
+        assert!(matches!(
+            canvas.render_region(Vec2::ZERO, Vec2::ONE, pixels, CountingRenderer(0)),
+            Err(Error::InvalidGeometry(_))
+        ));
+    }
+
+    /// Verify that validation reports degenerate shapes by index.
+    #[test]
+    fn validate_degenerate_geometry() {
+        let mut canvas = Canvas::default();
+
+        canvas.draw_rect((-1.0, -1.0), (1.0, 1.0), None, Some(Color::black()));
+        canvas.draw_shape(
+            vec![
+                Vec2::new(0.0, 0.0),
+                Vec2::new(1.0, 1.0),
+                Vec2::new(1.0, 0.0),
+                Vec2::new(0.0, 1.0),
+                Vec2::new(0.0, 0.0),
+            ],
+            Some(Stroke::new(Color::black(), 0.0, LineEnd::Butt)),
+            None,
+        );
+        canvas.draw_line((0.0, f32::NAN), (1.0, 1.0), None, None);
+
+        let issues = canvas.validate();
+
+        assert_eq!(
+            issues,
+            vec![
+                ValidationIssue {
+                    shape_index: 1,
+                    kind: IssueKind::ZeroWidthStroke
+                },
+                ValidationIssue {
+                    shape_index: 1,
+                    kind: IssueKind::SelfIntersection {
+                        first_edge: 0,
+                        second_edge: 2
+                    }
+                },
+                ValidationIssue {
+                    shape_index: 2,
+                    kind: IssueKind::NonFiniteCoordinate { point_index: 0 }
+                },
+            ]
+        );
+
+        canvas.set_validate_on_render(true);
+        assert_eq!(
+            canvas.render(CountingRenderer(0)),
+            Err(Error::InvalidGeometry(issues))
+        );
+    }
+
+    /// Verify that compound shapes keep their subpaths, discarding degenerate ones.
+    #[test]
+    fn compound_shape_subpaths() {
+        let mut canvas = Canvas::default();
+        canvas.move_camera((1.0, 0.0));
+
+        canvas.draw_compound_shape(
+            vec![
+                vec![Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::ZERO],
+                vec![Vec2::Y],
+                vec![Vec2::ZERO, Vec2::Y],
+            ],
+            None,
+            Some(Color::black()),
+            FillRule::EvenOdd,
+        );
+
+        let shape = &canvas.as_raw()[0];
+        assert_eq!(shape.fill_rule, FillRule::EvenOdd);
+        assert_eq!(shape.points[0], Vec2::new(1.0, 0.0));
+        assert_eq!(shape.subpaths, vec![vec![Vec2::X, Vec2::new(1.0, 1.0)]]);
+        assert_eq!(shape.point_count(), 6);
+    }
+
+    /// Verify that polygons with holes close their rings and fill with even-odd.
+    #[test]
+    fn polygon_with_holes() {
+        let mut canvas = Canvas::default();
+
+        canvas.draw_polygon_with_holes(
+            vec![Vec2::ZERO, Vec2::X * 4.0, Vec2::ONE * 4.0, Vec2::Y * 4.0],
+            [vec![Vec2::ONE, Vec2::new(2.0, 1.0), Vec2::ONE * 2.0]],
+            None,
+            Some(Color::black()),
+        );
+
+        let shape = &canvas.as_raw()[0];
+        assert!(shape.has_holes());
+        assert_eq!(shape.points.len(), 5);
+        assert_eq!(shape.subpaths[0].len(), 4);
+    }
+
+    /// Verify that scoped symmetries replicate shapes, nest, and stop at the end of the scope.
+    #[test]
+    fn scoped_symmetry() {
+        let mut canvas = Canvas::default();
+        let line = [Vec2::new(1.0, 0.0), Vec2::new(2.0, 0.0)];
+        let stroke = Some(Stroke::new(Color::black(), 0.1, LineEnd::Butt));
+
+        canvas.with_symmetry(Symmetry::Mirror(Vec2::Y), |canvas| {
+            canvas.draw_shape(line, stroke, None);
+
+            canvas.with_symmetry(Symmetry::Radial(2), |canvas| {
+                canvas.draw_shape(line, stroke, None);
+            });
+        });
+        canvas.draw_shape(line, stroke, None);
+
+        let shapes = canvas.as_raw();
+        assert_eq!(shapes.len(), 2 + 4 + 1);
+        assert_eq!(shapes[1].points, vec![-Vec2::X, Vec2::new(-2.0, 0.0)]);
+        assert_eq!(shapes[6].points, line.to_vec());
+    }
+
+    /// Verify that shapes drawn with neither a stroke nor a fill inherit the canvas's style, and scoped styles cascade.
+    #[test]
+    fn scoped_style() {
+        let mut canvas = Canvas::default();
+        let square = [Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y, Vec2::ZERO];
+        let stroke = Stroke::new(Color::black(), 0.1, LineEnd::Butt);
+
+        canvas.draw_shape(square, None, None);
+        canvas.set_style(Style::new(Some(stroke), None));
+        canvas.with_style(Style::new(None, Some(Color::red())), |canvas| {
+            canvas.draw_shape(square, None, None);
+            canvas.draw_shape(square, None, Some(Color::blue()));
+        });
+        canvas.draw_shape(square, None, None);
+
+        let shapes = canvas.as_raw();
+        assert_eq!((shapes[0].stroke, shapes[0].fill), (None, None));
+        assert_eq!(
+            (shapes[1].stroke, shapes[1].fill),
+            (Some(stroke), Some(Color::red()))
+        );
+        assert_eq!(
+            (shapes[2].stroke, shapes[2].fill),
+            (None, Some(Color::blue()))
+        );
+        assert_eq!((shapes[3].stroke, shapes[3].fill), (Some(stroke), None));
+        assert_eq!(canvas.style(), Style::new(Some(stroke), None));
+    }
+
+    /// Verify that a recording holds only the outermost calls, and replays into the same shapes.
+    #[test]
+    fn record_and_replay() {
+        let mut canvas = Canvas::default();
+        let stroke = Some(Stroke::new(Color::black(), 0.1, LineEnd::Butt));
+
+        canvas.start_recording();
+        canvas.move_camera(Vec2::ONE);
+        canvas.with_symmetry(Symmetry::Radial(3), |canvas| {
+            canvas.draw_rect(Vec2::ZERO, Vec2::ONE, stroke, None);
+        });
+        canvas.draw_circle(Vec2::ZERO, 0.5, None, Some(Color::red()));
+        let calls = canvas.stop_recording();
+
+        assert_eq!(calls.len(), 5);
+        assert_eq!(calls[0], DrawCall::MoveCamera(Vec2::ONE));
+        assert!(matches!(calls[2], DrawCall::Rect { .. }));
+        assert_eq!(calls[3], DrawCall::Symmetry(Vec::new()));
+
+        let mut replayed = Canvas::default();
+        replayed.replay(&calls);
+        assert_eq!(replayed.as_raw(), canvas.as_raw());
+    }
+
+    /// Verify that shapes can be filtered and mapped after drawing, and the edits replay.
+    #[test]
+    fn filter_and_map_shapes() {
+        let mut canvas = Canvas::default();
+        let stroke = Some(Stroke::new(Color::black(), 0.1, LineEnd::Butt));
+
+        canvas.start_recording();
+        canvas.draw_rect(Vec2::ZERO, Vec2::ONE, stroke, None);
+        canvas.draw_rect(Vec2::ZERO, Vec2::splat(0.01), stroke, None);
+        canvas.draw_rect(Vec2::ZERO, Vec2::ONE, None, Some(Color::red()));
+
+        canvas.filter_shapes(|shape| {
+            shape
+                .bounds()
+                .is_some_and(|(min, max)| (max - min).min_element() > 0.1)
+        });
+        canvas.map_shapes(|mut shape| {
+            if let Some(stroke) = &mut shape.stroke {
+                stroke.color = Color::blue();
+            }
+            shape
+        });
+        let calls = canvas.stop_recording();
+
+        let shapes = canvas.as_raw();
+        assert_eq!(shapes.len(), 2);
+        assert_eq!(shapes[0].stroke.unwrap().color, Color::blue());
+        assert_eq!(shapes[1].fill, Some(Color::red()));
+
+        let mut replayed = Canvas::default();
+        replayed.replay(&calls);
+        assert_eq!(replayed.as_raw(), canvas.as_raw());
+    }
+
+    /// Verify that shapes can be iterated over and matched by kind.
+    #[test]
+    fn match_shape_kinds() {
+        let mut canvas = Canvas::default();
+        let stroke = Stroke::new(Color::black(), 0.1, LineEnd::Butt);
+        let square = [Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y, Vec2::ZERO];
+
+        canvas.draw_polyline([Vec2::ZERO, Vec2::ONE], stroke);
+        canvas.draw_polygon(square, Color::red());
+        let hole = square.map(|point| point * 0.5);
+        canvas.draw_polygon_with_holes(square, [hole], None, Some(Color::red()));
+
+        let kinds: Vec<&str> = canvas
+            .shapes()
+            .map(|shape| match shape.kind() {
+                ShapeKind::Polyline(_) => "polyline",
+                ShapeKind::Polygon(points) if points.len() == 5 => "square",
+                ShapeKind::Polygon(_) => "polygon",
+                ShapeKind::Compound { subpaths, .. } if subpaths.len() == 1 => "donut",
+                ShapeKind::Compound { .. } => "compound",
+                ShapeKind::Custom(_) => "custom",
+            })
+            .collect();
+        assert_eq!(kinds, ["polyline", "square", "donut"]);
+
+        for shape in canvas.shapes_mut() {
+            shape.fill = None;
+        }
+        assert!(canvas.shapes().all(|shape| shape.fill.is_none()));
+    }
+
+    /// Verify that flattening bakes custom shapes and stroke alignment into plain shapes that render the same way.
+    #[test]
+    fn flatten_canvas() {
+        let mut canvas = Canvas::new(16);
+        let stroke = Stroke::new(Color::black(), 0.1, LineEnd::Butt);
+        let square = [Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y, Vec2::ZERO];
+
+        canvas.move_camera(Vec2::new(0.5, 0.0));
+        canvas.with_symmetry(Symmetry::Mirror(Vec2::Y), |canvas| {
+            let capsule = Capsule::new(Vec2::ZERO, Vec2::X, 0.25);
+            canvas.draw_custom_shape(capsule, Some(stroke), None);
+        });
+        canvas.draw_shape(
+            square,
+            Some(stroke.with_alignment(StrokeAlignment::Inside)),
+            Some(Color::red()),
+        );
+
+        let mut flattened = canvas.clone();
+        flattened.flatten();
+
+        let shapes = flattened.as_raw();
+        assert_eq!(shapes.len(), 4);
+        assert!(shapes.iter().all(|shape| shape.custom.is_none()));
+        assert!(shapes
+            .iter()
+            .filter_map(|shape| shape.stroke)
+            .all(|stroke| stroke.alignment == StrokeAlignment::Center));
+        assert_eq!(shapes[2].fill, Some(Color::red()));
+        assert_eq!(shapes[3].points[0], Vec2::new(0.55, 0.05));
+
+        let render = |canvas: &Canvas| {
+            let renderer = SvgRenderer::new(Vec2::splat(100.0), None, false, true, 16).unwrap();
+            canvas.render(renderer).unwrap()
+        };
+        assert_eq!(render(&flattened), render(&canvas));
+
+        let mut baked = flattened.clone();
+        baked.bake_camera();
+        assert_eq!(baked.to_camera_space(Vec2::ONE), Vec2::ONE);
+        assert!(baked.as_raw()[3].points[0].abs_diff_eq(Vec2::new(0.05, 0.05), 1e-6));
+        assert_eq!(render(&baked), render(&canvas));
+    }
+
+    /// Verify that custom shapes are flattened before validation, rather than reported as empty.
+    #[test]
+    fn validate_custom_shape() {
+        let mut canvas = Canvas::default();
+        canvas.draw_raw_shape(custom_capsule());
+        assert!(canvas.validate().is_empty());
+    }
+
+    /// Verify that custom shapes are flattened before their fills are hatched.
+    #[test]
+    fn hatch_custom_shape() {
+        let mut canvas = Canvas::default();
+        canvas.draw_raw_shape(custom_capsule());
+        let mut flattened = canvas.clone();
+        flattened.flatten();
+
+        let hatch = Hatch::new(0.0, 0.25);
+        canvas.hatch_fills(&hatch, 0.05);
+        flattened.hatch_fills(&hatch, 0.05);
+        assert_eq!(canvas.as_raw(), flattened.as_raw());
+        assert!(canvas.as_raw().len() > 2);
+    }
+
+    /// Verify that hatching keeps a shape's tag, link and tooltip, and follows gradient fills even without a plain fill.
+    #[test]
+    fn hatch_tagged_and_gradient_fills() {
+        let square = [(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0), (0.0, 0.0)];
+        let mut canvas = Canvas::default();
+        canvas.with_tag("Red", |canvas| canvas.draw_polygon(square, Color::red()));
+        canvas.as_raw_mut()[0].link = Some("https://example.com".to_string());
+        canvas.draw_raw_shape(Shape {
+            points: square.iter().map(|&(x, y)| Vec2::new(x, y)).collect(),
+            gradient_fill: Some(GradientFill::new(
+                Gradient::new(Color::black(), Color::white()),
+                Vec2::ZERO,
+                Vec2::new(0.0, 4.0),
+            )),
+            ..Default::default()
+        });
+
+        canvas.hatch_fills(&Hatch::new(0.0, 1.0), 0.05);
+        let shapes = canvas.as_raw();
+        assert_eq!(shapes.len(), 8);
+        for line in &shapes[..4] {
+            assert_eq!(line.tag.as_deref(), Some("Red"));
+            assert_eq!(line.link.as_deref(), Some("https://example.com"));
+            assert_eq!(line.stroke.unwrap().color, Color::red());
+        }
+
+        let colors: Vec<Color> = shapes[4..]
+            .iter()
+            .map(|line| line.stroke.unwrap().color)
+            .collect();
+        assert_eq!(colors[0], Color::new(0.125, 0.125, 0.125, 1.0));
+        assert_eq!(colors[3], Color::new(0.875, 0.875, 0.875, 1.0));
+        assert!(shapes[4..]
+            .iter()
+            .all(|line| line.fill.is_none() && line.gradient_fill.is_none()));
+    }
+
+    /// Verify that custom shapes are flattened before hidden lines are removed, and hide what is under them.
+    #[test]
+    fn remove_lines_hidden_by_custom_shape() {
+        let mut canvas = Canvas::default();
+        let stroke = Stroke::new(Color::black(), 0.1, LineEnd::Butt);
+        canvas.draw_polyline([(5.0, -5.0), (5.0, 5.0)], stroke);
+        canvas.draw_raw_shape(custom_capsule());
+        let mut flattened = canvas.clone();
+        flattened.flatten();
+
+        canvas.remove_hidden_lines();
+        flattened.remove_hidden_lines();
+        assert_eq!(canvas.as_raw(), flattened.as_raw());
+        // The line is cut where the capsule covers it, and the capsule's own outline remains.
+        assert!(canvas.as_raw().len() > 2);
+    }
+
+    /// Verify that stroked custom shapes are left alone when optimizing pen travel, rather than taken for empty polylines.
+    #[test]
+    fn optimize_travel_around_custom_shape() {
+        let mut canvas = Canvas::default();
+        let custom = Shape {
+            fill: None,
+            ..custom_capsule()
+        };
+        canvas.draw_raw_shape(custom.clone());
+
+        canvas.optimize_pen_travel(0.01);
+        assert_eq!(canvas.as_raw(), [custom]);
+    }
 }
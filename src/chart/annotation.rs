@@ -0,0 +1,361 @@
+use glam::Vec2;
+
+use super::{Axes, ChartText};
+use crate::{Canvas, Color, Stroke};
+
+/// A label set off from a data point, with a leader line from the label back to the point.
+///
+/// The label is measured and laid out as [ChartText].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Callout {
+    /// The data point the callout points at.
+    pub target: Vec2,
+    /// Where the middle of the label sits, relative to the target on the canvas.
+    pub offset: Vec2,
+    /// The label's text.
+    pub text: String,
+    /// The stroke the leader line is drawn with.
+    pub stroke: Stroke,
+    /// Space left between the label and the end of the leader line.
+    pub gap: f32,
+    /// The size of the arrowhead at the target, if any.
+    pub arrow: Option<f32>,
+}
+
+impl Callout {
+    /// Create a new [Callout] without an arrowhead, leaving a gap of twice the stroke's width around the label.
+    pub fn new(target: Vec2, offset: Vec2, text: impl Into<String>, stroke: Stroke) -> Self {
+        Self {
+            target,
+            offset,
+            text: text.into(),
+            stroke,
+            gap: stroke.width * 2.0,
+            arrow: None,
+        }
+    }
+
+    /// Set the space left between the label and the leader line, consuming the parent.
+    pub fn with_gap(mut self, gap: f32) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Put an arrowhead of the given size at the target, consuming the parent.
+    pub fn with_arrow(mut self, size: f32) -> Self {
+        self.arrow = Some(size);
+        self
+    }
+
+    /// Lay out the label and its leader line, with the target mapped through `axes`.
+    ///
+    /// `measure` gives the width and height of the label's text.
+    pub fn layout<M: Fn(&str) -> Vec2>(&self, axes: &Axes, measure: M) -> CalloutLayout {
+        let target = axes.map(self.target);
+        let size = measure(&self.text);
+        let center = target + self.offset;
+        let half = size / 2.0 + Vec2::splat(self.gap);
+
+        // The leader runs from where the line to the target leaves the padded label, unless the target is inside it.
+        let to_target = target - center;
+        let reach = (half / to_target.abs()).min_element();
+        let (leader, head) = if reach < 1.0 {
+            let start = center + to_target * reach;
+            let direction = (target - start).normalize_or_zero();
+            let head = self
+                .arrow
+                .map(|size| {
+                    let base = target - direction * size;
+                    let side = direction.perp() * size * 0.3;
+                    vec![target, base + side, base - side, target]
+                })
+                .unwrap_or_default();
+            (vec![start, target], head)
+        } else {
+            (Vec::new(), Vec::new())
+        };
+
+        CalloutLayout {
+            leader,
+            head,
+            text: ChartText {
+                text: self.text.clone(),
+                position: center,
+                size,
+            },
+        }
+    }
+}
+
+/// Where a [Callout] was laid out.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CalloutLayout {
+    /// The leader line, from the label to the target, or nothing if the label covers the target.
+    pub leader: Vec<Vec2>,
+    /// The closed arrowhead at the target, if there is one.
+    pub head: Vec<Vec2>,
+    /// The label.
+    pub text: ChartText,
+}
+
+/// A curly brace spanning a range of the data, such as to group a run of bars or mark a period, with an optional label at its tip.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Brace {
+    /// The data point the brace starts at.
+    pub from: Vec2,
+    /// The data point the brace ends at.
+    pub to: Vec2,
+    /// How far the tip stands out from the line between the ends, on the canvas.
+    /// Positive depths bulge to the left looking from `from` to `to`, and negative depths to the right.
+    pub depth: f32,
+    /// The label beyond the tip, if any.
+    pub text: Option<String>,
+    /// The stroke the brace is drawn with.
+    pub stroke: Stroke,
+    /// Space left between the tip and the label.
+    pub gap: f32,
+}
+
+impl Brace {
+    /// Create a new, unlabelled [Brace], leaving a gap of a quarter of its depth before any label.
+    pub fn new(from: Vec2, to: Vec2, depth: f32, stroke: Stroke) -> Self {
+        Self {
+            from,
+            to,
+            depth,
+            text: None,
+            stroke,
+            gap: depth.abs() / 4.0,
+        }
+    }
+
+    /// Set the label beyond the tip, consuming the parent.
+    pub fn with_text(mut self, text: impl Into<String>) -> Self {
+        self.text = Some(text.into());
+        self
+    }
+
+    /// Lay out the brace's curve and label, with its ends mapped through `axes`.
+    ///
+    /// `measure` gives the width and height of the label's text.
+    pub fn layout<M: Fn(&str) -> Vec2>(&self, axes: &Axes, measure: M) -> BraceLayout {
+        let (from, to) = (axes.map(self.from), axes.map(self.to));
+        let along = (to - from).normalize_or_zero();
+        let out = along.perp() * self.depth.signum();
+        let middle = (from + to) / 2.0;
+        let tip = middle + out * self.depth.abs();
+
+        // Each half curls out of its end, runs level at half the depth, then curls out again to the tip.
+        let curl = (self.depth.abs() / 2.0).min(from.distance(to) / 4.0);
+        let shoulder = out * curl;
+        let mut points = vec![from];
+        points.extend(quadratic(
+            from,
+            from + shoulder,
+            from + along * curl + shoulder,
+        ));
+        points.extend(quadratic(
+            middle - along * curl + shoulder,
+            middle + shoulder,
+            tip,
+        ));
+        points.extend(quadratic(
+            tip,
+            middle + shoulder,
+            middle + along * curl + shoulder,
+        ));
+        points.extend(quadratic(to - along * curl + shoulder, to + shoulder, to));
+        points.dedup();
+
+        let text = self.text.as_ref().map(|text| {
+            let size = measure(text);
+            // Far enough past the tip that the label's box clears it, whichever way the brace faces.
+            let clearance = (size / 2.0 * out.abs()).length();
+            ChartText {
+                text: text.clone(),
+                position: tip + out * (self.gap + clearance),
+                size,
+            }
+        });
+
+        BraceLayout { points, text }
+    }
+}
+
+/// Points along a quadratic Bézier curve from `start` to `end`, pulled towards `control`, including `start` and `end`.
+fn quadratic(start: Vec2, control: Vec2, end: Vec2) -> Vec<Vec2> {
+    const SEGMENTS: usize = 8;
+    (0..=SEGMENTS)
+        .map(|i| {
+            let t = i as f32 / SEGMENTS as f32;
+            start * (1.0 - t) * (1.0 - t) + control * 2.0 * t * (1.0 - t) + end * t * t
+        })
+        .collect()
+}
+
+/// Where a [Brace] was laid out.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BraceLayout {
+    /// The brace, from one end through the tip to the other.
+    pub points: Vec<Vec2>,
+    /// The label beyond the tip, if there is one.
+    pub text: Option<ChartText>,
+}
+
+/// A shaded band across the plot area between two data values, such as to highlight a period or a target range.
+///
+/// A band between x values runs the full height of the plot area, and a band between y values its full width.
+/// A band between both is just the rectangle they make.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Band {
+    /// The x values the band runs between, or `None` to span the plot area.
+    pub x: Option<(f32, f32)>,
+    /// The y values the band runs between, or `None` to span the plot area.
+    pub y: Option<(f32, f32)>,
+    /// The color the band is filled with, usually translucent so the data shows through.
+    pub fill: Color,
+    /// The stroke the band is outlined with, if any.
+    pub stroke: Option<Stroke>,
+}
+
+impl Band {
+    /// Create a new, unoutlined [Band] running up the plot area between two x values.
+    pub fn vertical(from: f32, to: f32, fill: Color) -> Self {
+        Self {
+            x: Some((from, to)),
+            y: None,
+            fill,
+            stroke: None,
+        }
+    }
+
+    /// Create a new, unoutlined [Band] running across the plot area between two y values.
+    pub fn horizontal(from: f32, to: f32, fill: Color) -> Self {
+        Self {
+            x: None,
+            y: Some((from, to)),
+            fill,
+            stroke: None,
+        }
+    }
+
+    /// Set the stroke the band is outlined with, consuming the parent.
+    pub fn with_stroke(mut self, stroke: Stroke) -> Self {
+        self.stroke = Some(stroke);
+        self
+    }
+
+    /// The bottom left and top right corners of the band on the canvas, clipped to the plot area of `axes`.
+    pub fn rectangle(&self, axes: &Axes) -> (Vec2, Vec2) {
+        let span = |values: Option<(f32, f32)>, map: &dyn Fn(f32) -> f32, min: f32, max: f32| {
+            let (low, high) = (min.min(max), min.max(max));
+            match values {
+                Some((from, to)) => {
+                    let (from, to) = (map(from).clamp(low, high), map(to).clamp(low, high));
+                    (from.min(to), from.max(to))
+                }
+                None => (low, high),
+            }
+        };
+        let (left, right) = span(self.x, &|x| axes.map_x(x), axes.min.x, axes.max.x);
+        let (bottom, top) = span(self.y, &|y| axes.map_y(y), axes.min.y, axes.max.y);
+        (Vec2::new(left, bottom), Vec2::new(right, top))
+    }
+}
+
+impl Canvas {
+    /// Draw a laid out callout's leader line and arrowhead in World Space. The label is left to the caller, as laid out in [CalloutLayout::text].
+    pub fn draw_callout(&mut self, callout: &Callout, layout: &CalloutLayout) {
+        if !layout.leader.is_empty() {
+            self.draw_polyline_absolute(layout.leader.clone(), callout.stroke);
+        }
+        if !layout.head.is_empty() {
+            self.draw_shape_absolute(layout.head.clone(), None, Some(callout.stroke.color));
+        }
+    }
+
+    /// Draw a laid out brace in World Space. The label is left to the caller, as laid out in [BraceLayout::text].
+    pub fn draw_brace(&mut self, brace: &Brace, layout: &BraceLayout) {
+        self.draw_polyline_absolute(layout.points.clone(), brace.stroke);
+    }
+
+    /// Draw a band in World Space across the plot area of `axes`, usually before the data so it sits behind.
+    pub fn draw_band(&mut self, axes: &Axes, band: &Band) {
+        let (min, max) = band.rectangle(axes);
+        self.draw_shape_absolute(
+            vec![
+                min,
+                Vec2::new(max.x, min.y),
+                max,
+                Vec2::new(min.x, max.y),
+                min,
+            ],
+            band.stroke,
+            Some(band.fill),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{chart::Axis, LineEnd};
+
+    /// Verify that callouts lead back to their targets, braces bulge to their tips, and bands stay in the plot area.
+    #[test]
+    fn annotate() {
+        let axes = Axes::new(
+            Vec2::ZERO,
+            Vec2::new(100.0, 100.0),
+            Axis::new((0.0, 10.0)),
+            Axis::new((0.0, 10.0)),
+        );
+        let measure = |text: &str| Vec2::new(text.len() as f32 * 2.0, 2.0);
+        let stroke = Stroke::new(Color::black(), 0.5, LineEnd::Butt);
+
+        let callout =
+            Callout::new(Vec2::new(5.0, 5.0), Vec2::new(20.0, 0.0), "peak", stroke).with_arrow(2.0);
+        let layout = callout.layout(&axes, measure);
+        assert_eq!(layout.text.position, Vec2::new(70.0, 50.0));
+        assert_eq!(
+            layout.leader,
+            [Vec2::new(65.0, 50.0), Vec2::new(50.0, 50.0)]
+        );
+        assert_eq!(layout.head[1], Vec2::new(52.0, 49.4));
+
+        let covered = Callout::new(Vec2::new(5.0, 5.0), Vec2::ZERO, "peak", stroke);
+        assert!(covered.layout(&axes, measure).leader.is_empty());
+
+        // Below a range along the x axis, bulging down.
+        let brace =
+            Brace::new(Vec2::new(2.0, 0.0), Vec2::new(6.0, 0.0), -8.0, stroke).with_text("growth");
+        let layout = brace.layout(&axes, measure);
+        assert_eq!(layout.points[0], Vec2::new(20.0, 0.0));
+        assert!(layout.points[layout.points.len() - 1].abs_diff_eq(Vec2::new(60.0, 0.0), 1e-4));
+        assert!(layout.points.contains(&Vec2::new(40.0, -8.0)));
+        assert!(layout.points.iter().all(|point| point.y >= -8.0));
+        assert_eq!(layout.text.unwrap().position, Vec2::new(40.0, -11.0));
+
+        let band = Band::vertical(8.0, 12.0, Color::black());
+        assert_eq!(
+            band.rectangle(&axes),
+            (Vec2::new(80.0, 0.0), Vec2::new(100.0, 100.0))
+        );
+        let band = Band::horizontal(5.0, 2.5, Color::black());
+        assert_eq!(
+            band.rectangle(&axes),
+            (Vec2::new(0.0, 25.0), Vec2::new(100.0, 50.0))
+        );
+
+        let mut canvas = Canvas::new(1);
+        canvas.draw_band(&axes, &band);
+        canvas.draw_callout(&callout, &callout.layout(&axes, measure));
+        canvas.draw_brace(&brace, &brace.layout(&axes, measure));
+        assert_eq!(canvas.as_raw().len(), 4);
+    }
+}
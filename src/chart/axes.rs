@@ -0,0 +1,474 @@
+use glam::Vec2;
+
+use super::{format_tick, nice_ticks, time::time_ticks, ChartText};
+use crate::{Canvas, Color, LineEnd, Stroke};
+
+/// How an [Axis] spaces out the values along it.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AxisScale {
+    /// Equal steps in value take equal space.
+    #[default]
+    Linear,
+    /// Equal ratios take equal space, ticked at powers of the base. Only positive values can be shown.
+    ///
+    /// Falls back to [Linear](Self::Linear) unless the base is finite and greater than one.
+    Log(f32),
+    /// Linear near zero and logarithmic beyond the threshold either side, for values that cross zero but span
+    /// many orders of magnitude.
+    ///
+    /// Falls back to [Linear](Self::Linear) unless the threshold is finite and greater than zero.
+    Symlog(f32),
+    /// Values are seconds after a time, itself in seconds since the Unix epoch, ticked on round times
+    /// such as whole hours, days, months or years in UTC.
+    Time(f64),
+}
+
+impl AxisScale {
+    /// The scale, with a [Log](Self::Log) or [Symlog](Self::Symlog) scale that can't be drawn replaced by [Linear](Self::Linear).
+    fn validated(self) -> Self {
+        match self {
+            AxisScale::Log(base) if !(base > 1.0 && base.is_finite()) => AxisScale::Linear,
+            AxisScale::Symlog(threshold) if !(threshold > 0.0 && threshold.is_finite()) => {
+                AxisScale::Linear
+            }
+            scale => scale,
+        }
+    }
+
+    /// Where `value` is along the scale, before stretching over the axis.
+    fn transform(&self, value: f32) -> f32 {
+        match self.validated() {
+            AxisScale::Linear | AxisScale::Time(_) => value,
+            AxisScale::Log(base) => value.max(f32::MIN_POSITIVE).ln() / base.ln(),
+            AxisScale::Symlog(threshold) => value.signum() * (value.abs() / threshold).ln_1p(),
+        }
+    }
+}
+
+/// One axis of a chart: the range of data values it spans, and how it is ticked.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Axis {
+    /// The data values at the start and end of the axis.
+    pub domain: (f32, f32),
+    /// How the values are spaced out along the axis.
+    pub scale: AxisScale,
+    /// About how many ticks to mark along the axis.
+    pub ticks: usize,
+    /// Writes the label of each tick, in place of the scale's own labels.
+    /// It is given the tick's value, or for a [Time](AxisScale::Time) scale, its time in seconds since the Unix epoch.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub formatter: Option<fn(f64) -> String>,
+}
+
+impl Axis {
+    /// Create a new linear [Axis] spanning `domain`, with about five ticks.
+    pub fn new(domain: (f32, f32)) -> Self {
+        Self {
+            domain,
+            scale: AxisScale::Linear,
+            ticks: 5,
+            formatter: None,
+        }
+    }
+
+    /// Create a new linear [Axis] spanning every one of `values`, such as the values in every panel of a [FacetGrid](super::FacetGrid),
+    /// so they share one scale. `NaN`s are left out.
+    pub fn fit<I: IntoIterator<Item = f32>>(values: I) -> Self {
+        let (low, high) = values
+            .into_iter()
+            .filter(|value| !value.is_nan())
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(low, high), value| {
+                (low.min(value), high.max(value))
+            });
+        if low <= high {
+            Self::new((low, high))
+        } else {
+            Self::new((0.0, 1.0))
+        }
+    }
+
+    /// Set how the values are spaced out along the axis, consuming the parent.
+    pub fn with_scale(mut self, scale: AxisScale) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Set about how many ticks to mark along the axis, consuming the parent.
+    pub fn with_ticks(mut self, ticks: usize) -> Self {
+        self.ticks = ticks;
+        self
+    }
+
+    /// Set the function that writes the label of each tick, consuming the parent.
+    pub fn with_formatter(mut self, formatter: fn(f64) -> String) -> Self {
+        self.formatter = Some(formatter);
+        self
+    }
+
+    /// How far along the axis `value` is, from `0.0` at the start of the domain to `1.0` at the end.
+    pub fn fraction(&self, value: f32) -> f32 {
+        let (low, high) = (
+            self.scale.transform(self.domain.0),
+            self.scale.transform(self.domain.1),
+        );
+        if high == low {
+            return 0.5;
+        }
+        (self.scale.transform(value) - low) / (high - low)
+    }
+
+    /// The values to tick, each with its label.
+    pub fn tick_labels(&self) -> Vec<(f32, String)> {
+        let (low, high) = (
+            self.domain.0.min(self.domain.1),
+            self.domain.0.max(self.domain.1),
+        );
+
+        let ticks: Vec<(f32, String)> = match self.scale.validated() {
+            AxisScale::Linear => linear_ticks(low, high, self.ticks),
+            AxisScale::Log(base) => log_ticks(low, high, self.ticks, base),
+            AxisScale::Symlog(threshold) => symlog_ticks(low, high, self.ticks, threshold),
+            AxisScale::Time(origin) => {
+                time_ticks(origin + low as f64, origin + high as f64, self.ticks)
+                    .into_iter()
+                    .map(|(time, label)| ((time - origin) as f32, label))
+                    .collect()
+            }
+        };
+
+        match self.formatter {
+            Some(formatter) => {
+                let origin = match self.scale {
+                    AxisScale::Time(origin) => origin,
+                    _ => 0.0,
+                };
+                ticks
+                    .into_iter()
+                    .map(|(value, _)| (value, formatter(origin + value as f64)))
+                    .collect()
+            }
+            None => ticks,
+        }
+    }
+}
+
+impl PartialEq for Axis {
+    fn eq(&self, other: &Self) -> bool {
+        let formatters_eq = match (self.formatter, other.formatter) {
+            (Some(a), Some(b)) => std::ptr::fn_addr_eq(a, b),
+            (a, b) => a.is_none() && b.is_none(),
+        };
+        self.domain == other.domain
+            && self.scale == other.scale
+            && self.ticks == other.ticks
+            && formatters_eq
+    }
+}
+
+/// Round ticks, evenly spaced.
+fn linear_ticks(low: f32, high: f32, count: usize) -> Vec<(f32, String)> {
+    let values = nice_ticks(low, high, count);
+    let step = match values.as_slice() {
+        [first, second, ..] => second - first,
+        _ => high - low,
+    };
+    values
+        .into_iter()
+        .map(|value| (value, format_tick(value, step)))
+        .collect()
+}
+
+/// Ticks at powers of `base`, skipping some when there are too many, or round linear ticks when there are too few.
+fn log_ticks(low: f32, high: f32, count: usize, base: f32) -> Vec<(f32, String)> {
+    let low = low.max(f32::MIN_POSITIVE);
+    if !high.is_finite() {
+        return linear_ticks(low, high, count);
+    }
+    let (first, last) = (
+        (low.ln() / base.ln() - 1e-4).ceil() as i32,
+        (high.ln() / base.ln() + 1e-4).floor() as i32,
+    );
+    if last - first < 1 {
+        return linear_ticks(low, high, count);
+    }
+
+    let stride = ((last - first + 1) as usize).div_ceil(count.max(1)).max(1);
+    (first..=last)
+        .step_by(stride)
+        .map(|power| {
+            let value = base.powi(power);
+            let label = if base == 10.0 {
+                match power {
+                    power if power.abs() >= 4 => format!("1e{}", power),
+                    power if power < 0 => format!("{:.*}", -power as usize, value),
+                    _ => format!("{:.0}", value),
+                }
+            } else {
+                format!("{}", value)
+            };
+            (value, label)
+        })
+        .collect()
+}
+
+/// Ticks at zero and at the threshold times powers of ten either side, skipping some when there are too many.
+fn symlog_ticks(low: f32, high: f32, count: usize, threshold: f32) -> Vec<(f32, String)> {
+    let reach = low.abs().max(high.abs()) / threshold;
+    if !reach.is_finite() {
+        return linear_ticks(low, high, count);
+    }
+    let powers = if reach >= 1.0 {
+        reach.log10().floor() as i32
+    } else {
+        -1
+    };
+    let stride = ((powers + 1) as usize * 2 + 1)
+        .div_ceil(count.max(1))
+        .max(1);
+
+    let mut values = vec![0.0];
+    for power in (0..=powers).step_by(stride) {
+        let value = threshold * 10f32.powi(power);
+        values.push(value);
+        values.push(-value);
+    }
+    values.retain(|value| (low..=high).contains(value));
+    values.sort_by(f32::total_cmp);
+
+    values
+        .into_iter()
+        .map(|value| (value, format_tick(value, value.abs().max(threshold))))
+        .collect()
+}
+
+/// A pair of axes framing a plot area, mapping data values onto the canvas.
+///
+/// The x axis runs along the bottom of the area and the y axis up its left side.
+/// Tick labels are measured and laid out as [ChartText].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Axes {
+    /// The bottom left corner of the plot area.
+    pub min: Vec2,
+    /// The top right corner of the plot area.
+    pub max: Vec2,
+    /// The horizontal axis.
+    pub x: Axis,
+    /// The vertical axis.
+    pub y: Axis,
+    /// The stroke the axis lines and ticks are drawn with.
+    pub stroke: Stroke,
+    /// The stroke of grid lines across the plot area at each tick, if any.
+    pub grid: Option<Stroke>,
+    /// How far ticks reach out from the axis lines.
+    pub tick_length: f32,
+    /// Space between the ends of the ticks and their labels.
+    pub gap: f32,
+}
+
+impl Axes {
+    /// Create new [Axes] over the plot area from `min` to `max`, in thin black lines without a grid.
+    pub fn new(min: Vec2, max: Vec2, x: Axis, y: Axis) -> Self {
+        let size = (max - min).abs().min_element();
+        Self {
+            min,
+            max,
+            x,
+            y,
+            stroke: Stroke::new(Color::black(), size / 300.0, LineEnd::Butt),
+            grid: None,
+            tick_length: size / 80.0,
+            gap: size / 120.0,
+        }
+    }
+
+    /// Set the stroke of grid lines across the plot area, consuming the parent.
+    pub fn with_grid(mut self, grid: Stroke) -> Self {
+        self.grid = Some(grid);
+        self
+    }
+
+    /// Map an x data value to a horizontal position on the canvas.
+    pub fn map_x(&self, x: f32) -> f32 {
+        self.min.x + (self.max.x - self.min.x) * self.x.fraction(x)
+    }
+
+    /// Map a y data value to a vertical position on the canvas.
+    pub fn map_y(&self, y: f32) -> f32 {
+        self.min.y + (self.max.y - self.min.y) * self.y.fraction(y)
+    }
+
+    /// Map a data point to a point on the canvas.
+    pub fn map(&self, point: Vec2) -> Vec2 {
+        Vec2::new(self.map_x(point.x), self.map_y(point.y))
+    }
+
+    /// Lay out the axis lines, ticks, grid and tick labels.
+    ///
+    /// `measure` gives the width and height of a label's text.
+    pub fn layout<M: Fn(&str) -> Vec2>(&self, measure: M) -> AxesLayout {
+        let (min, max) = (self.min, self.max);
+        let mut ticks = Vec::new();
+        let mut grid = Vec::new();
+        let mut texts = Vec::new();
+
+        for (value, text) in self.x.tick_labels() {
+            let x = self.map_x(value);
+            let base = Vec2::new(x, min.y);
+            let tip = base - Vec2::Y * self.tick_length;
+            let size = measure(&text);
+            ticks.push((base, tip));
+            grid.push((base, Vec2::new(x, max.y)));
+            texts.push(ChartText {
+                text,
+                position: tip - Vec2::Y * (self.gap + size.y / 2.0),
+                size,
+            });
+        }
+
+        for (value, text) in self.y.tick_labels() {
+            let y = self.map_y(value);
+            let base = Vec2::new(min.x, y);
+            let tip = base - Vec2::X * self.tick_length;
+            let size = measure(&text);
+            ticks.push((base, tip));
+            grid.push((base, Vec2::new(max.x, y)));
+            texts.push(ChartText {
+                text,
+                position: tip - Vec2::X * (self.gap + size.x / 2.0),
+                size,
+            });
+        }
+
+        AxesLayout {
+            lines: vec![
+                (min, Vec2::new(max.x, min.y)),
+                (min, Vec2::new(min.x, max.y)),
+            ],
+            ticks,
+            grid,
+            texts,
+        }
+    }
+}
+
+/// Where [Axes] were laid out.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AxesLayout {
+    /// The x axis line along the bottom of the plot area, then the y axis line up its left side.
+    pub lines: Vec<(Vec2, Vec2)>,
+    /// Each tick, from the axis line out: the x axis's first, then the y axis's.
+    pub ticks: Vec<(Vec2, Vec2)>,
+    /// A grid line across the plot area at each tick, in the same order as the ticks.
+    pub grid: Vec<(Vec2, Vec2)>,
+    /// Each tick's label, in the same order as the ticks.
+    pub texts: Vec<ChartText>,
+}
+
+impl Canvas {
+    /// Draw laid out axes in World Space: the grid, if any, then the axis lines and ticks. The labels are left to the caller, as laid out in [AxesLayout::texts].
+    pub fn draw_axes(&mut self, axes: &Axes, layout: &AxesLayout) {
+        if let Some(grid) = axes.grid {
+            for &(from, to) in &layout.grid {
+                self.draw_polyline_absolute(vec![from, to], grid);
+            }
+        }
+        for &(from, to) in layout.lines.iter().chain(&layout.ticks) {
+            self.draw_polyline_absolute(vec![from, to], axes.stroke);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that data values map onto the plot area, and ticks are labelled beside the axis lines.
+    #[test]
+    fn map_and_tick() {
+        let axes = Axes::new(
+            Vec2::new(10.0, 10.0),
+            Vec2::new(110.0, 60.0),
+            Axis::new((0.0, 10.0)),
+            Axis::new((-1.0, 1.0)).with_ticks(2),
+        );
+        assert_eq!(axes.map(Vec2::new(5.0, 0.0)), Vec2::new(60.0, 35.0));
+        assert_eq!(axes.map(Vec2::new(10.0, 1.0)), axes.max);
+
+        let layout = axes.layout(|text| Vec2::new(text.len() as f32, 1.0));
+        let labels: Vec<&str> = layout.texts.iter().map(|text| text.text.as_str()).collect();
+        assert_eq!(labels, ["0", "2", "4", "6", "8", "10", "-1", "0", "1"]);
+        assert!(layout.texts[0].position.y < axes.min.y);
+        assert!(layout.texts[6].position.x < axes.min.x);
+        assert_eq!(layout.ticks[7].0, Vec2::new(10.0, 35.0));
+
+        let mut canvas = Canvas::new(1);
+        canvas.draw_axes(&axes.clone().with_grid(axes.stroke), &layout);
+        assert_eq!(canvas.as_raw().len(), 9 * 2 + 2);
+    }
+
+    /// Verify that log, symlog and time scales space values and ticks out their own way, and formatters relabel ticks.
+    #[test]
+    fn scales() {
+        let labels = |axis: &Axis| -> Vec<String> {
+            axis.tick_labels()
+                .into_iter()
+                .map(|(_, label)| label)
+                .collect()
+        };
+
+        let log = Axis::new((0.01, 1e5)).with_scale(AxisScale::Log(10.0));
+        assert!((log.fraction(1.0) - 2.0 / 7.0).abs() < 1e-5);
+        assert_eq!(labels(&log), ["0.01", "1", "100", "1e4"]);
+        assert_eq!(
+            labels(&log.clone().with_ticks(10)),
+            ["0.01", "0.1", "1", "10", "100", "1000", "1e4", "1e5"]
+        );
+
+        let symlog = Axis::new((-1000.0, 1000.0)).with_scale(AxisScale::Symlog(1.0));
+        assert_eq!(symlog.fraction(0.0), 0.5);
+        assert!(symlog.fraction(10.0) > 0.65);
+        assert_eq!(
+            labels(&symlog.with_ticks(10)),
+            ["-1000", "-100", "-10", "-1", "0", "1", "10", "100", "1000"]
+        );
+
+        // From midnight on the first of March, 2024, for two days.
+        let time = Axis::new((0.0, 172800.0)).with_scale(AxisScale::Time(1709251200.0));
+        assert_eq!(
+            labels(&time.clone().with_ticks(2)),
+            ["2024-03-01", "2024-03-02", "2024-03-03"]
+        );
+        assert_eq!(labels(&time.clone().with_ticks(4))[1], "12:00");
+
+        let formatted = time
+            .with_ticks(2)
+            .with_formatter(|time| format!("{}", time / 86400.0));
+        assert_eq!(labels(&formatted), ["19783", "19784", "19785"]);
+    }
+
+    /// Verify that log and symlog scales without a usable base or threshold fall back to linear ones.
+    #[test]
+    fn invalid_scales() {
+        let linear = Axis::new((0.0, 10.0));
+        for scale in [
+            AxisScale::Log(1.0),
+            AxisScale::Log(0.5),
+            AxisScale::Log(f32::NAN),
+            AxisScale::Symlog(0.0),
+            AxisScale::Symlog(-1.0),
+        ] {
+            let axis = linear.clone().with_scale(scale);
+            assert_eq!(axis.tick_labels(), linear.tick_labels());
+            assert_eq!(axis.fraction(5.0), 0.5);
+        }
+
+        let tiny = Axis::new((-1.0, 1.0)).with_scale(AxisScale::Symlog(1e-45));
+        assert!(!tiny.tick_labels().is_empty());
+        let huge = Axis::new((1.0, f32::INFINITY)).with_scale(AxisScale::Log(10.0));
+        assert!(!huge.tick_labels().is_empty());
+    }
+}
@@ -0,0 +1,234 @@
+use super::{format_tick, nice_ticks, quantile, Anchor, Colorbar, Legend, Swatch};
+use crate::{Canvas, Color, Gradient, Shape};
+
+/// How a [Choropleth] groups values into classes that share a color.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Classes {
+    /// No classes: every value gets its own color along the gradient.
+    #[default]
+    Continuous,
+    /// This many classes, each covering an equal share of the range of the values.
+    EqualInterval(usize),
+    /// This many classes, each holding about as many of the values.
+    Quantile(usize),
+}
+
+/// Colors for a choropleth map: shapes, such as regions, filled by the value each stands for.
+///
+/// Build one from every value on the map, then color each shape with [color](Self::color), such as
+/// from the style callback when drawing GeoJSON, or fill a list of shapes at once with
+/// [draw_choropleth](Canvas::draw_choropleth). [legend](Self::legend) explains the colors.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Choropleth {
+    /// The colors, from the smallest value to the largest.
+    pub gradient: Gradient,
+    /// The smallest and largest values.
+    pub domain: (f32, f32),
+    /// The boundaries between classes, from the smallest value to the largest, or empty for continuous colors.
+    pub breaks: Vec<f32>,
+    /// The color of shapes without a value, given as `NaN`.
+    pub missing: Color,
+}
+
+impl Choropleth {
+    /// Create a new [Choropleth] for `values`, grouped into `classes`, with missing values in light gray.
+    pub fn new(gradient: Gradient, values: &[f32], classes: Classes) -> Self {
+        let mut sorted: Vec<f32> = values.iter().copied().filter(|v| v.is_finite()).collect();
+        sorted.sort_by(f32::total_cmp);
+        let domain = match (sorted.first(), sorted.last()) {
+            (Some(&min), Some(&max)) => (min, max),
+            _ => (0.0, 0.0),
+        };
+
+        let breaks = match classes {
+            Classes::Continuous => Vec::new(),
+            Classes::EqualInterval(count) => {
+                let count = count.max(1);
+                (0..=count)
+                    .map(|i| domain.0 + (domain.1 - domain.0) * i as f32 / count as f32)
+                    .collect()
+            }
+            Classes::Quantile(count) => {
+                let count = count.max(1);
+                (0..=count)
+                    .map(|i| quantile(&sorted, i as f32 / count as f32))
+                    .map(|value| if value.is_nan() { 0.0 } else { value })
+                    .collect()
+            }
+        };
+
+        Self {
+            gradient,
+            domain,
+            breaks,
+            missing: Color::new(0.8, 0.8, 0.8, 1.0),
+        }
+    }
+
+    /// Set the color of shapes without a value, consuming the parent.
+    pub fn with_missing(mut self, missing: Color) -> Self {
+        self.missing = missing;
+        self
+    }
+
+    /// How many classes the values are grouped into, or zero for continuous colors.
+    pub fn class_count(&self) -> usize {
+        self.breaks.len().saturating_sub(1)
+    }
+
+    /// The class `value` falls into, or `None` for continuous colors or a missing value.
+    ///
+    /// Values beyond the smallest or largest fall into the first or last class.
+    pub fn class(&self, value: f32) -> Option<usize> {
+        let count = self.class_count();
+        if count == 0 || value.is_nan() {
+            return None;
+        }
+        Some(
+            self.breaks[1..count]
+                .iter()
+                .filter(|b| value >= **b)
+                .count(),
+        )
+    }
+
+    /// The color of a shape standing for `value`.
+    pub fn color(&self, value: f32) -> Color {
+        if value.is_nan() {
+            return self.missing;
+        }
+
+        match self.class(value) {
+            Some(class) => self.class_color(class),
+            None => {
+                let (min, max) = self.domain;
+                let t = if max > min {
+                    (value - min) / (max - min)
+                } else {
+                    0.5
+                };
+                self.gradient.sample(t)
+            }
+        }
+    }
+
+    /// The color of every value in class `class`, spread evenly along the gradient.
+    fn class_color(&self, class: usize) -> Color {
+        let count = self.class_count();
+        let t = if count > 1 {
+            class as f32 / (count - 1) as f32
+        } else {
+            0.5
+        };
+        self.gradient.sample(t)
+    }
+
+    /// A [Legend] with a swatch for each class, labelled with the range of values it covers.
+    ///
+    /// For continuous colors, there's a swatch for each of about five round values instead.
+    pub fn legend(&self, anchor: Anchor, swatch_size: f32) -> Legend {
+        let legend = Legend::new(anchor, swatch_size);
+        let (min, max) = self.domain;
+
+        if self.breaks.is_empty() {
+            let values = nice_ticks(min, max, 5);
+            let step = step(&values);
+            return values.iter().fold(legend, |legend, &value| {
+                legend.with_entry(format_tick(value, step), Swatch::Fill(self.color(value)))
+            });
+        }
+
+        let step = step(&self.breaks);
+        self.breaks
+            .windows(2)
+            .enumerate()
+            .fold(legend, |legend, (class, bounds)| {
+                let label = format!(
+                    "{} – {}",
+                    format_tick(bounds[0], step),
+                    format_tick(bounds[1], step)
+                );
+                legend.with_entry(label, Swatch::Fill(self.class_color(class)))
+            })
+    }
+
+    /// A [Colorbar] spanning the values, for continuous colors.
+    pub fn colorbar(&self, anchor: Anchor, length: f32, thickness: f32) -> Colorbar {
+        Colorbar::new(
+            self.gradient.clone(),
+            self.domain,
+            anchor,
+            length,
+            thickness,
+        )
+    }
+}
+
+/// The smallest gap between neighbouring values, which sets how many decimals their labels need.
+fn step(values: &[f32]) -> f32 {
+    values
+        .windows(2)
+        .map(|pair| pair[1] - pair[0])
+        .filter(|gap| *gap > 0.0)
+        .fold(f32::INFINITY, f32::min)
+        .min(1.0)
+}
+
+impl Canvas {
+    /// Draw each shape filled with the color of its value, such as the regions of a choropleth map.
+    ///
+    /// Shapes keep their own stroke, and are drawn as they are, without the camera.
+    pub fn draw_choropleth(&mut self, shapes: &[Shape], values: &[f32], choropleth: &Choropleth) {
+        for (shape, &value) in shapes.iter().zip(values) {
+            let mut shape = shape.clone();
+            shape.fill = Some(choropleth.color(value));
+            self.draw_raw_shape(shape);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Vec2;
+
+    /// Verify that values are classed by equal intervals and quantiles, and the legend names each class.
+    #[test]
+    fn classify_values() {
+        let gradient = Gradient::new(Color::white(), Color::black());
+        let values = [0.0, 1.0, 2.0, 3.0, 10.0, f32::NAN];
+
+        let equal = Choropleth::new(gradient.clone(), &values, Classes::EqualInterval(2));
+        assert_eq!(equal.breaks, [0.0, 5.0, 10.0]);
+        assert_eq!(equal.class(3.0), Some(0));
+        assert_eq!(equal.class(10.0), Some(1));
+        assert_eq!(equal.color(1.0), Color::white());
+        assert_eq!(equal.color(f32::NAN), equal.missing);
+
+        let quantiles = Choropleth::new(gradient.clone(), &values, Classes::Quantile(2));
+        assert_eq!(quantiles.breaks, [0.0, 2.0, 10.0]);
+        assert_eq!(quantiles.class(3.0), Some(1));
+
+        let labels: Vec<String> = quantiles
+            .legend(Anchor::TopRight, 1.0)
+            .entries
+            .into_iter()
+            .map(|entry| entry.label)
+            .collect();
+        assert_eq!(labels, ["0 – 2", "2 – 10"]);
+
+        let continuous = Choropleth::new(gradient, &values, Classes::Continuous);
+        assert_eq!(continuous.class(3.0), None);
+        assert_eq!(continuous.color(5.0), Color::new(0.5, 0.5, 0.5, 1.0));
+
+        let square = Shape {
+            points: vec![Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::ZERO],
+            ..Default::default()
+        };
+        let mut canvas = Canvas::new(1);
+        canvas.draw_choropleth(&[square.clone(), square], &[0.0, 10.0], &equal);
+        assert_eq!(canvas.as_raw()[1].fill, Some(Color::black()));
+    }
+}
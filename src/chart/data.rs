@@ -0,0 +1,249 @@
+use std::borrow::Borrow;
+
+use glam::Vec2;
+
+use super::Candle;
+
+/// A table of text fields with named columns, such as the records read from a CSV file, ready to pull chart data out of.
+///
+/// Rows can come from any iterator of records whose fields are strings, so the `StringRecord`s read by a `csv::Reader`
+/// can be passed in by reference, without `barium` depending on `csv`.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Table {
+    /// The name of each column.
+    pub headers: Vec<String>,
+    /// The fields of each row, in the same order as the headers.
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Create a new [Table] from column names and records.
+    pub fn new<H, S, I, R>(headers: H, records: I) -> Self
+    where
+        H: IntoIterator<Item = S>,
+        S: AsRef<str>,
+        I: IntoIterator<Item = R>,
+        R: IntoIterator,
+        R::Item: AsRef<str>,
+    {
+        Self {
+            headers: headers
+                .into_iter()
+                .map(|header| header.as_ref().to_string())
+                .collect(),
+            rows: records
+                .into_iter()
+                .map(|record| {
+                    record
+                        .into_iter()
+                        .map(|field| field.as_ref().to_string())
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    /// Create a new [Table] from records, taking the column names from the first.
+    pub fn with_header_row<I, R>(records: I) -> Self
+    where
+        I: IntoIterator<Item = R>,
+        R: IntoIterator,
+        R::Item: AsRef<str>,
+    {
+        let mut records = records.into_iter();
+        let headers: Vec<String> = records
+            .next()
+            .map(|record| {
+                record
+                    .into_iter()
+                    .map(|field| field.as_ref().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self::new(headers, records)
+    }
+
+    /// The index of the column named `name`, if there is one.
+    pub fn column(&self, name: &str) -> Option<usize> {
+        self.headers.iter().position(|header| header == name)
+    }
+
+    /// Every field in the column named `name`, or `None` if there is no such column. Short rows give empty fields.
+    pub fn strings(&self, name: &str) -> Option<Vec<&str>> {
+        let column = self.column(name)?;
+        Some(
+            self.rows
+                .iter()
+                .map(|row| row.get(column).map(String::as_str).unwrap_or(""))
+                .collect(),
+        )
+    }
+
+    /// Every field in the column named `name` read as a number, or `None` if there is no such column.
+    /// Fields that aren't numbers are read as `NaN`, which the statistics helpers leave out.
+    pub fn numbers(&self, name: &str) -> Option<Vec<f32>> {
+        Some(
+            self.strings(name)?
+                .into_iter()
+                .map(|field| field.trim().parse().unwrap_or(f32::NAN))
+                .collect(),
+        )
+    }
+
+    /// A point for each row, from the columns named `x` and `y`, or `None` if either column is missing.
+    /// Rows where either field isn't a number are left out.
+    pub fn points(&self, x: &str, y: &str) -> Option<Vec<Vec2>> {
+        let (xs, ys) = (self.numbers(x)?, self.numbers(y)?);
+        Some(
+            xs.into_iter()
+                .zip(ys)
+                .map(|(x, y)| Vec2::new(x, y))
+                .filter(|point| !point.is_nan())
+                .collect(),
+        )
+    }
+
+    /// A [Candle] for each row, from the named columns, or `None` if any column is missing.
+    /// Times are read in seconds since the Unix epoch. Rows where any field isn't a number are left out.
+    pub fn candles(
+        &self,
+        time: &str,
+        open: &str,
+        high: &str,
+        low: &str,
+        close: &str,
+    ) -> Option<Vec<Candle>> {
+        let times = self.strings(time)?;
+        let (opens, highs, lows, closes) = (
+            self.numbers(open)?,
+            self.numbers(high)?,
+            self.numbers(low)?,
+            self.numbers(close)?,
+        );
+        Some(
+            times
+                .into_iter()
+                .enumerate()
+                .filter_map(|(i, time)| {
+                    let candle = Candle::new(
+                        time.trim().parse().ok()?,
+                        opens[i],
+                        highs[i],
+                        lows[i],
+                        closes[i],
+                    );
+                    let prices = [candle.open, candle.high, candle.low, candle.close];
+                    (!prices.iter().any(|price| price.is_nan())).then_some(candle)
+                })
+                .collect(),
+        )
+    }
+
+    /// Split the rows into groups by the field in the column named `key`, in the order each group first appears,
+    /// or `None` if there is no such column.
+    pub fn group_by(&self, key: &str) -> Option<Vec<(String, Table)>> {
+        let column = self.column(key)?;
+        let mut groups: Vec<(String, Table)> = Vec::new();
+        for row in &self.rows {
+            let value = row.get(column).cloned().unwrap_or_default();
+            match groups.iter_mut().find(|(group, _)| *group == value) {
+                Some((_, table)) => table.rows.push(row.clone()),
+                None => groups.push((
+                    value,
+                    Table {
+                        headers: self.headers.clone(),
+                        rows: vec![row.clone()],
+                    },
+                )),
+            }
+        }
+        Some(groups)
+    }
+}
+
+/// A point from the first two values of each row, such as the `rows()` of an `n × 2` `ndarray` array.
+/// Rows with fewer than two values are left out.
+pub fn rows_to_points<I, R>(rows: I) -> Vec<Vec2>
+where
+    I: IntoIterator<Item = R>,
+    R: IntoIterator,
+    R::Item: Borrow<f32>,
+{
+    rows.into_iter()
+        .filter_map(|row| {
+            let mut values = row.into_iter();
+            let x = *values.next()?.borrow();
+            let y = *values.next()?.borrow();
+            Some(Vec2::new(x, y))
+        })
+        .collect()
+}
+
+/// A point from each pair of values in two columns, such as two `column()`s of an `ndarray` array, or two `Vec`s.
+pub fn columns_to_points<X, Y>(xs: X, ys: Y) -> Vec<Vec2>
+where
+    X: IntoIterator,
+    X::Item: Borrow<f32>,
+    Y: IntoIterator,
+    Y::Item: Borrow<f32>,
+{
+    xs.into_iter()
+        .zip(ys)
+        .map(|(x, y)| Vec2::new(*x.borrow(), *y.borrow()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that records read into columns of numbers, points, candles and groups, and numeric rows read into points.
+    #[test]
+    fn read_records() {
+        let records = vec![
+            vec!["time", "region", "open", "high", "low", "close"],
+            vec!["0", "north", "1", "3", "0.5", "2"],
+            vec!["86400", "south", "2", "2.5", "1", "1.5"],
+            vec!["172800", "north", "1.5", "n/a", "1", "2"],
+        ];
+        let table = Table::with_header_row(records);
+        assert_eq!(table.headers.len(), 6);
+        assert_eq!(table.rows.len(), 3);
+        assert_eq!(table.column("low"), Some(4));
+        assert!(table.numbers("missing").is_none());
+
+        let highs = table.numbers("high").unwrap();
+        assert_eq!(highs[..2], [3.0, 2.5]);
+        assert!(highs[2].is_nan());
+        assert_eq!(
+            table.points("open", "close").unwrap(),
+            [
+                Vec2::new(1.0, 2.0),
+                Vec2::new(2.0, 1.5),
+                Vec2::new(1.5, 2.0)
+            ]
+        );
+
+        let candles = table
+            .candles("time", "open", "high", "low", "close")
+            .unwrap();
+        assert_eq!(candles.len(), 2);
+        assert_eq!(candles[1], Candle::new(86400.0, 2.0, 2.5, 1.0, 1.5));
+
+        let groups = table.group_by("region").unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].0, "north");
+        assert_eq!(groups[0].1.rows.len(), 2);
+
+        let array = [[1.0f32, 2.0], [3.0, 4.0]];
+        assert_eq!(
+            rows_to_points(array.iter().map(|row| row.iter())),
+            [Vec2::new(1.0, 2.0), Vec2::new(3.0, 4.0)]
+        );
+        assert_eq!(
+            columns_to_points([1.0f32, 3.0].iter(), vec![2.0f32, 4.0]),
+            rows_to_points(array)
+        );
+    }
+}
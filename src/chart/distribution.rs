@@ -0,0 +1,331 @@
+use glam::Vec2;
+
+use super::{quantile, Axes, Kde};
+use crate::{Canvas, Color, Stroke};
+
+/// Which way a chart's categories run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Orientation {
+    /// Categories along the x axis, with values up the y axis.
+    #[default]
+    Vertical,
+    /// Categories up the y axis, with values along the x axis.
+    Horizontal,
+}
+
+impl Orientation {
+    /// The data point at `position` among the categories and `value` along the values.
+    pub fn point(&self, position: f32, value: f32) -> Vec2 {
+        match self {
+            Orientation::Vertical => Vec2::new(position, value),
+            Orientation::Horizontal => Vec2::new(value, position),
+        }
+    }
+}
+
+/// The numbers a box-and-whisker plot shows for a sample.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Summary {
+    /// The lowest value within reach of the lower whisker.
+    pub low: f32,
+    /// The first quartile, where the box starts.
+    pub first_quartile: f32,
+    /// The median.
+    pub median: f32,
+    /// The third quartile, where the box ends.
+    pub third_quartile: f32,
+    /// The highest value within reach of the upper whisker.
+    pub high: f32,
+    /// Values beyond the reach of either whisker.
+    pub outliers: Vec<f32>,
+}
+
+impl Summary {
+    /// Summarize `values`, with the whiskers reaching up to `whisker` times the interquartile range past the box.
+    ///
+    /// `NaN`s are left out. Returns `None` when there are no values left.
+    pub fn new(values: &[f32], whisker: f32) -> Option<Self> {
+        let mut sorted: Vec<f32> = values.iter().copied().filter(|v| !v.is_nan()).collect();
+        sorted.sort_by(f32::total_cmp);
+        if sorted.is_empty() {
+            return None;
+        }
+
+        let first_quartile = quantile(&sorted, 0.25);
+        let third_quartile = quantile(&sorted, 0.75);
+        let reach = (third_quartile - first_quartile) * whisker;
+        let (lowest, highest) = (first_quartile - reach, third_quartile + reach);
+        let within = |value: &&f32| **value >= lowest && **value <= highest;
+
+        Some(Self {
+            low: *sorted.iter().find(within).unwrap_or(&first_quartile),
+            first_quartile,
+            median: quantile(&sorted, 0.5),
+            third_quartile,
+            high: *sorted.iter().rev().find(within).unwrap_or(&third_quartile),
+            outliers: sorted
+                .iter()
+                .copied()
+                .filter(|value| !within(&value))
+                .collect(),
+        })
+    }
+}
+
+/// How a [DistributionPlot] shows each sample.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DistributionKind {
+    /// A box from the first to the third quartile, split at the median, with whiskers and outliers.
+    #[default]
+    Box,
+    /// A mirrored density curve, with lines across at the quartiles and median.
+    Violin,
+}
+
+/// One series of samples on a [DistributionPlot], one sample per category.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DistributionSeries {
+    /// The sample for each category. Category `i` is centered on `i` along the category axis.
+    pub samples: Vec<Vec<f32>>,
+    /// The stroke the outlines, lines and outliers are drawn with.
+    pub stroke: Stroke,
+    /// The color the boxes or violins are filled with, if any.
+    pub fill: Option<Color>,
+}
+
+/// Box-and-whisker or violin plots of one or more series, drawn on [Axes].
+///
+/// Category `i` is centered on `i` along the category axis, so an axis domain of `(-0.5, n - 0.5)` fits `n` categories.
+/// When there are several series, they are grouped side by side within each category.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DistributionPlot {
+    /// How each sample is shown.
+    pub kind: DistributionKind,
+    /// The series, grouped side by side in order.
+    pub series: Vec<DistributionSeries>,
+    /// Which way the categories run.
+    pub orientation: Orientation,
+    /// How much of each category's slot the group takes up, from 0 to 1.
+    pub width: f32,
+    /// How far box whiskers reach past the box, in interquartile ranges.
+    pub whisker: f32,
+    /// The radius of the circle drawn at each outlier.
+    pub outlier_radius: f32,
+}
+
+impl DistributionPlot {
+    /// Create a new, empty, upright [DistributionPlot], with Tukey's whiskers of one and a half interquartile ranges.
+    pub fn new(kind: DistributionKind) -> Self {
+        Self {
+            kind,
+            series: Vec::new(),
+            orientation: Orientation::Vertical,
+            width: 0.8,
+            whisker: 1.5,
+            outlier_radius: 1.0,
+        }
+    }
+
+    /// Add a series to the [DistributionPlot], consuming the parent.
+    pub fn with_series(
+        mut self,
+        samples: Vec<Vec<f32>>,
+        stroke: Stroke,
+        fill: Option<Color>,
+    ) -> Self {
+        self.series.push(DistributionSeries {
+            samples,
+            stroke,
+            fill,
+        });
+        self
+    }
+
+    /// Set which way the categories run, consuming the parent.
+    pub fn with_orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Lay out every sample of every series, mapped through `axes`.
+    pub fn layout(&self, axes: &Axes) -> Vec<DistributionLayout> {
+        let slot = self.width / self.series.len().max(1) as f32;
+        // Leave a little space between neighbouring series in a group.
+        let half = slot / 2.0 * 0.9;
+        let map = |position: f32, value: f32| axes.map(self.orientation.point(position, value));
+
+        let mut layouts = Vec::new();
+        for (series_index, series) in self.series.iter().enumerate() {
+            for (category, sample) in series.samples.iter().enumerate() {
+                let Some(summary) = Summary::new(sample, self.whisker) else {
+                    continue;
+                };
+                let center =
+                    category as f32 - self.width / 2.0 + slot * (series_index as f32 + 0.5);
+                let across =
+                    |value: f32, half: f32| (map(center - half, value), map(center + half, value));
+
+                let (outline, lines, outliers) = match self.kind {
+                    DistributionKind::Box => {
+                        let (q1, q3) = (summary.first_quartile, summary.third_quartile);
+                        let outline = vec![
+                            map(center - half, q1),
+                            map(center + half, q1),
+                            map(center + half, q3),
+                            map(center - half, q3),
+                            map(center - half, q1),
+                        ];
+                        let lines = vec![
+                            across(summary.median, half),
+                            (map(center, q1), map(center, summary.low)),
+                            (map(center, q3), map(center, summary.high)),
+                            across(summary.low, half / 2.0),
+                            across(summary.high, half / 2.0),
+                        ];
+                        let outliers = summary
+                            .outliers
+                            .iter()
+                            .map(|&value| map(center, value))
+                            .collect();
+                        (outline, lines, outliers)
+                    }
+                    DistributionKind::Violin => {
+                        let kde = Kde::new(sample);
+                        let (from, to) = (kde.values[0], kde.values[kde.values.len() - 1]);
+                        let curve = kde.curve(from, to, 64);
+                        let peak = curve.iter().fold(0.0f32, |peak, point| peak.max(point.y));
+                        let scale = if peak > 0.0 { half / peak } else { 0.0 };
+
+                        let mut outline: Vec<Vec2> = curve
+                            .iter()
+                            .map(|point| map(center + point.y * scale, point.x))
+                            .collect();
+                        outline.extend(
+                            curve
+                                .iter()
+                                .rev()
+                                .map(|point| map(center - point.y * scale, point.x)),
+                        );
+                        outline.push(outline[0]);
+
+                        let lines = [
+                            summary.first_quartile,
+                            summary.median,
+                            summary.third_quartile,
+                        ]
+                        .into_iter()
+                        .map(|value| across(value, kde.density(value) * scale))
+                        .collect();
+                        (outline, lines, Vec::new())
+                    }
+                };
+
+                layouts.push(DistributionLayout {
+                    series: series_index,
+                    category,
+                    summary,
+                    outline,
+                    lines,
+                    outliers,
+                });
+            }
+        }
+
+        layouts
+    }
+}
+
+/// Where one sample of a [DistributionPlot] was laid out.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DistributionLayout {
+    /// Which series the sample belongs to.
+    pub series: usize,
+    /// Which category the sample belongs to.
+    pub category: usize,
+    /// The numbers summarizing the sample.
+    pub summary: Summary,
+    /// The closed outline of the box or violin.
+    pub outline: Vec<Vec2>,
+    /// For a box, the median, then the two whiskers, then their caps. For a violin, the quartiles and median across it.
+    pub lines: Vec<(Vec2, Vec2)>,
+    /// The center of each outlier's circle.
+    pub outliers: Vec<Vec2>,
+}
+
+impl Canvas {
+    /// Draw a laid out box or violin plot in World Space: each outline, then the lines and outliers on top.
+    pub fn draw_distributions(&mut self, plot: &DistributionPlot, layouts: &[DistributionLayout]) {
+        for layout in layouts {
+            let series = &plot.series[layout.series];
+            self.draw_shape_absolute(layout.outline.clone(), Some(series.stroke), series.fill);
+            for &(from, to) in &layout.lines {
+                self.draw_polyline_absolute(vec![from, to], series.stroke);
+            }
+            for &outlier in &layout.outliers {
+                self.draw_circle_absolute(outlier, plot.outlier_radius, Some(series.stroke), None);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{chart::Axis, LineEnd};
+
+    /// Verify that boxes span the quartiles with outliers set apart, and grouped violins sit side by side within their category.
+    #[test]
+    fn box_and_violin() {
+        let sample: Vec<f32> = (1..=9).map(|i| i as f32).chain([30.0]).collect();
+        let summary = Summary::new(&sample, 1.5).unwrap();
+        assert_eq!(summary.median, 5.5);
+        assert_eq!(
+            (summary.first_quartile, summary.third_quartile),
+            (3.25, 7.75)
+        );
+        assert_eq!((summary.low, summary.high), (1.0, 9.0));
+        assert_eq!(summary.outliers, vec![30.0]);
+
+        let axes = Axes::new(
+            Vec2::ZERO,
+            Vec2::new(100.0, 100.0),
+            Axis::new((-0.5, 1.5)),
+            Axis::new((0.0, 40.0)),
+        );
+        let stroke = Stroke::new(Color::black(), 0.5, LineEnd::Butt);
+        let plot = DistributionPlot::new(DistributionKind::Box).with_series(
+            vec![sample.clone(), vec![]],
+            stroke,
+            None,
+        );
+        let layouts = plot.layout(&axes);
+        assert_eq!(layouts.len(), 1);
+        let layout = &layouts[0];
+        assert!(layout.outline[0].abs_diff_eq(Vec2::new(25.0 - 18.0, 3.25 * 2.5), 1e-3));
+        assert!(layout.outliers[0].abs_diff_eq(Vec2::new(25.0, 75.0), 1e-3));
+
+        let horizontal = plot.clone().with_orientation(Orientation::Horizontal);
+        let turned = Axes::new(axes.min, axes.max, axes.y.clone(), axes.x.clone());
+        assert!(horizontal.layout(&turned)[0].outliers[0].abs_diff_eq(Vec2::new(75.0, 25.0), 1e-3));
+
+        let violins = DistributionPlot::new(DistributionKind::Violin)
+            .with_series(vec![sample.clone()], stroke, None)
+            .with_series(vec![sample], stroke, None)
+            .with_orientation(Orientation::Horizontal);
+        let layouts = violins.layout(&turned);
+        let (left, right) = (&layouts[0], &layouts[1]);
+        assert!(left.outline.iter().all(|point| point.y <= 25.0 + 1e-3));
+        assert!(right.outline.iter().all(|point| point.y >= 25.0 - 1e-3));
+        assert_eq!(left.lines.len(), 3);
+
+        let mut canvas = Canvas::new(4);
+        canvas.draw_distributions(&plot, &plot.layout(&axes));
+        assert_eq!(canvas.as_raw().len(), 1 + 5 + 1);
+    }
+}
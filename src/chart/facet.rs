@@ -0,0 +1,244 @@
+use glam::Vec2;
+
+use super::{Axes, AxesLayout, Axis, ChartText};
+use crate::Canvas;
+
+/// Split `items` into groups by `key`, in the order each group first appears, such as to give each group a panel of a [FacetGrid].
+pub fn partition<T, K, I, F>(items: I, key: F) -> Vec<(K, Vec<T>)>
+where
+    I: IntoIterator<Item = T>,
+    K: PartialEq,
+    F: Fn(&T) -> K,
+{
+    let mut groups: Vec<(K, Vec<T>)> = Vec::new();
+    for item in items {
+        let item_key = key(&item);
+        match groups.iter_mut().find(|(group, _)| *group == item_key) {
+            Some((_, members)) => members.push(item),
+            None => groups.push((item_key, vec![item])),
+        }
+    }
+    groups
+}
+
+/// Small multiples: a grid of panels, one per group of the data, each with its own [Axes] on the same scales,
+/// so the groups can be compared at a glance.
+///
+/// Panels fill the grid row by row from the top left. Only the panels on the outer edges label their ticks,
+/// since the scales are shared. To give the grid a single legend, lay one out around the whole grid, from
+/// [FacetLayout::min] to [FacetLayout::max].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FacetGrid {
+    /// The bottom left corner of the grid.
+    pub min: Vec2,
+    /// The top right corner of the grid.
+    pub max: Vec2,
+    /// How many panels go side by side in each row.
+    pub columns: usize,
+    /// Space between neighbouring panels, across and up, such as for tick labels.
+    pub gap: Vec2,
+    /// Space above each panel for its title.
+    pub title_height: f32,
+}
+
+impl FacetGrid {
+    /// Create a new [FacetGrid], with gaps and titles a twentieth of its size.
+    pub fn new(min: Vec2, max: Vec2, columns: usize) -> Self {
+        let size = (max - min).abs().min_element();
+        Self {
+            min,
+            max,
+            columns,
+            gap: Vec2::splat(size / 20.0),
+            title_height: size / 20.0,
+        }
+    }
+
+    /// Set the space between neighbouring panels, consuming the parent.
+    pub fn with_gap(mut self, gap: Vec2) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Set the space above each panel for its title, consuming the parent.
+    pub fn with_title_height(mut self, title_height: f32) -> Self {
+        self.title_height = title_height;
+        self
+    }
+
+    /// The bottom left and top right corners of the plot area of each of `count` panels.
+    pub fn panels(&self, count: usize) -> Vec<(Vec2, Vec2)> {
+        let columns = self.columns.max(1);
+        let rows = count.div_ceil(columns).max(1);
+        let cell = Vec2::new(
+            (self.max.x - self.min.x - self.gap.x * (columns - 1) as f32) / columns as f32,
+            (self.max.y - self.min.y - self.gap.y * (rows - 1) as f32) / rows as f32,
+        );
+
+        (0..count)
+            .map(|i| {
+                let (row, column) = (i / columns, i % columns);
+                // Rows run down from the top of the grid.
+                let top = self.max.y - (cell.y + self.gap.y) * row as f32;
+                let left = self.min.x + (cell.x + self.gap.x) * column as f32;
+                (
+                    Vec2::new(left, top - cell.y),
+                    Vec2::new(left + cell.x, top - self.title_height),
+                )
+            })
+            .collect()
+    }
+
+    /// Lay out one panel per title, each with axes on the shared `x` and `y` scales.
+    ///
+    /// `measure` gives the width and height of a title's or tick label's text.
+    pub fn layout<S, M>(&self, titles: &[S], x: &Axis, y: &Axis, measure: M) -> FacetLayout
+    where
+        S: AsRef<str>,
+        M: Fn(&str) -> Vec2,
+    {
+        let columns = self.columns.max(1);
+        let count = titles.len();
+        let x_ticks = x.tick_labels().len();
+
+        let facets = titles
+            .iter()
+            .zip(self.panels(count))
+            .enumerate()
+            .map(|(i, (title, (min, max)))| {
+                let axes = Axes::new(min, max, x.clone(), y.clone());
+                let mut layout = axes.layout(&measure);
+
+                // Only label the x axis where there's no panel below, and the y axis in the first column.
+                let bottom = i + columns >= count;
+                let left = i % columns == 0;
+                let mut index = 0;
+                layout.texts.retain(|_| {
+                    let keep = if index < x_ticks { bottom } else { left };
+                    index += 1;
+                    keep
+                });
+
+                let text = title.as_ref().to_string();
+                let size = measure(&text);
+                Facet {
+                    title: ChartText {
+                        position: Vec2::new((min.x + max.x) / 2.0, max.y + self.title_height / 2.0),
+                        text,
+                        size,
+                    },
+                    axes,
+                    layout,
+                }
+            })
+            .collect();
+
+        FacetLayout {
+            min: self.min,
+            max: self.max,
+            facets,
+        }
+    }
+}
+
+/// One panel of a laid out [FacetGrid].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Facet {
+    /// The panel's title, centered above it.
+    pub title: ChartText,
+    /// The panel's axes, to draw its group's data through.
+    pub axes: Axes,
+    /// Where the panel's axes were laid out.
+    pub layout: AxesLayout,
+}
+
+/// Where a [FacetGrid] was laid out.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FacetLayout {
+    /// The bottom left corner of the whole grid.
+    pub min: Vec2,
+    /// The top right corner of the whole grid.
+    pub max: Vec2,
+    /// Each panel, in the same order as the titles.
+    pub facets: Vec<Facet>,
+}
+
+impl Canvas {
+    /// Draw a laid out grid of panels in World Space: for each panel, its axes, then whatever `draw` draws through them.
+    ///
+    /// `draw` is given the index of the panel and its axes. The titles and tick labels are left to the caller,
+    /// as laid out in each [Facet].
+    pub fn draw_facets<F: FnMut(&mut Canvas, usize, &Axes)>(
+        &mut self,
+        layout: &FacetLayout,
+        mut draw: F,
+    ) {
+        for (i, facet) in layout.facets.iter().enumerate() {
+            self.draw_axes(&facet.axes, &facet.layout);
+            draw(self, i, &facet.axes);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{chart::Bar, Color};
+
+    /// Verify that groups get panels row by row on shared scales, with only the outer panels labelled.
+    #[test]
+    fn facet_groups() {
+        let data = [("a", 1.0), ("b", 4.0), ("a", 2.0), ("c", 3.0)];
+        let groups = partition(data, |(key, _)| *key);
+        let titles: Vec<&str> = groups.iter().map(|(key, _)| *key).collect();
+        assert_eq!(titles, ["a", "b", "c"]);
+        assert_eq!(groups[0].1.len(), 2);
+
+        let x = Axis::new((0.0, 1.0));
+        let y = Axis::fit(data.iter().map(|(_, value)| *value));
+        assert_eq!(y.domain, (1.0, 4.0));
+
+        let grid = FacetGrid::new(Vec2::ZERO, Vec2::new(210.0, 210.0), 2)
+            .with_gap(Vec2::splat(10.0))
+            .with_title_height(5.0);
+        let layout = grid.layout(&titles, &x, &y, |text| Vec2::new(text.len() as f32, 1.0));
+        let panels: Vec<(Vec2, Vec2)> = layout
+            .facets
+            .iter()
+            .map(|facet| (facet.axes.min, facet.axes.max))
+            .collect();
+        assert_eq!(
+            panels,
+            [
+                (Vec2::new(0.0, 110.0), Vec2::new(100.0, 205.0)),
+                (Vec2::new(110.0, 110.0), Vec2::new(210.0, 205.0)),
+                (Vec2::new(0.0, 0.0), Vec2::new(100.0, 95.0)),
+            ]
+        );
+        assert_eq!(layout.facets[0].title.position, Vec2::new(50.0, 207.5));
+
+        // The top right panel has a panel below it and one to its left, so it has no labels.
+        let x_ticks = x.tick_labels().len();
+        let y_ticks = y.tick_labels().len();
+        assert_eq!(layout.facets[0].layout.texts.len(), y_ticks);
+        assert_eq!(layout.facets[1].layout.texts.len(), x_ticks);
+        assert_eq!(layout.facets[2].layout.texts.len(), x_ticks + y_ticks);
+
+        let mut canvas = Canvas::new(1);
+        let mut drawn = Vec::new();
+        canvas.draw_facets(&layout, |canvas, i, axes| {
+            let total: f32 = groups[i].1.iter().map(|(_, value)| value).sum();
+            canvas.draw_bars(
+                axes,
+                &[Bar::new(0.25, 0.75, total)],
+                None,
+                Some(Color::black()),
+            );
+            drawn.push(i);
+        });
+        assert_eq!(drawn, [0, 1, 2]);
+    }
+}
@@ -0,0 +1,277 @@
+use glam::Vec2;
+
+use super::{Axes, Axis, AxisScale};
+use crate::{Canvas, Color, LineEnd, Stroke};
+
+/// One period of trading: when it started, and the prices it opened, peaked, bottomed and closed at.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Candle {
+    /// When the period started, in seconds since the Unix epoch.
+    pub time: f64,
+    /// The first price.
+    pub open: f32,
+    /// The highest price.
+    pub high: f32,
+    /// The lowest price.
+    pub low: f32,
+    /// The last price.
+    pub close: f32,
+}
+
+impl Candle {
+    /// Create a new [Candle].
+    pub fn new(time: f64, open: f32, high: f32, low: f32, close: f32) -> Self {
+        Self {
+            time,
+            open,
+            high,
+            low,
+            close,
+        }
+    }
+
+    /// Whether the price closed at or above where it opened.
+    pub fn is_up(&self) -> bool {
+        self.close >= self.open
+    }
+}
+
+/// How a [CandleChart] draws each [Candle].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CandleStyle {
+    /// A filled body from the open to the close, with a wick from the low to the high.
+    #[default]
+    Candlestick,
+    /// A line from the low to the high, with a tick left at the open and a tick right at the close.
+    Ohlc,
+}
+
+/// A financial chart of [Candles](Candle), colored by whether each period closed up or down.
+///
+/// Along the x axis, times are measured in seconds since the first candle, which keeps them precise in an `f32`.
+/// Use [x_axis](Self::x_axis) for an axis that fits every candle, ticked on round dates and times.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CandleChart {
+    /// The candles, in order of time.
+    pub candles: Vec<Candle>,
+    /// How each candle is drawn.
+    pub style: CandleStyle,
+    /// The color of periods that closed up.
+    pub up: Color,
+    /// The color of periods that closed down.
+    pub down: Color,
+    /// The width of the wicks and lines.
+    pub line_width: f32,
+    /// How much of the space between neighbouring candles each one takes up, from 0 to 1.
+    pub width: f32,
+    /// Whether candles are spaced evenly by their order, closing the gaps where there was no trading, such as weekends.
+    /// The x axis then counts candles rather than seconds.
+    pub skip_gaps: bool,
+}
+
+impl CandleChart {
+    /// Create a new [CandleChart] in green and red, sorting the candles by time.
+    pub fn new(mut candles: Vec<Candle>, style: CandleStyle) -> Self {
+        candles.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Self {
+            candles,
+            style,
+            up: Color::new(0.15, 0.65, 0.35, 1.0),
+            down: Color::new(0.85, 0.2, 0.2, 1.0),
+            line_width: 1.0,
+            width: 0.7,
+            skip_gaps: false,
+        }
+    }
+
+    /// Set the colors of periods that closed up and down, consuming the parent.
+    pub fn with_colors(mut self, up: Color, down: Color) -> Self {
+        self.up = up;
+        self.down = down;
+        self
+    }
+
+    /// Set whether candles are spaced evenly by their order, consuming the parent.
+    pub fn with_skip_gaps(mut self, skip_gaps: bool) -> Self {
+        self.skip_gaps = skip_gaps;
+        self
+    }
+
+    /// Where the candle at `index` is along the x axis.
+    pub fn x(&self, index: usize) -> f32 {
+        if self.skip_gaps {
+            index as f32
+        } else {
+            (self.candles[index].time - self.candles[0].time) as f32
+        }
+    }
+
+    /// The shortest distance between neighbouring candles along the x axis, which sets how wide they are.
+    pub fn spacing(&self) -> f32 {
+        let spacing = (1..self.candles.len())
+            .map(|i| self.x(i) - self.x(i - 1))
+            .filter(|gap| *gap > 0.0)
+            .fold(f32::INFINITY, f32::min);
+        if spacing.is_finite() {
+            spacing
+        } else {
+            1.0
+        }
+    }
+
+    /// An x axis spanning every candle, with half a spacing to spare at either end.
+    ///
+    /// Unless gaps are skipped, it has a [Time](AxisScale::Time) scale starting at the first candle.
+    pub fn x_axis(&self) -> Axis {
+        let margin = self.spacing() / 2.0;
+        let last = self.candles.len().saturating_sub(1);
+        let end = if self.candles.is_empty() {
+            0.0
+        } else {
+            self.x(last)
+        };
+        let axis = Axis::new((-margin, end + margin));
+        match self.candles.first() {
+            Some(first) if !self.skip_gaps => axis.with_scale(AxisScale::Time(first.time)),
+            _ => axis,
+        }
+    }
+
+    /// A y axis spanning every price.
+    pub fn y_axis(&self) -> Axis {
+        Axis::fit(
+            self.candles
+                .iter()
+                .flat_map(|candle| [candle.low, candle.high]),
+        )
+    }
+
+    /// Lay out every candle, mapped through `axes`.
+    pub fn layout(&self, axes: &Axes) -> Vec<CandleLayout> {
+        let half = self.spacing() * self.width / 2.0;
+
+        self.candles
+            .iter()
+            .enumerate()
+            .map(|(i, candle)| {
+                let x = self.x(i);
+                let map = |x: f32, y: f32| axes.map(Vec2::new(x, y));
+                let color = if candle.is_up() { self.up } else { self.down };
+
+                match self.style {
+                    CandleStyle::Candlestick => {
+                        let (bottom, top) =
+                            (candle.open.min(candle.close), candle.open.max(candle.close));
+                        CandleLayout {
+                            color,
+                            body: Some(vec![
+                                map(x - half, bottom),
+                                map(x + half, bottom),
+                                map(x + half, top),
+                                map(x - half, top),
+                                map(x - half, bottom),
+                            ]),
+                            lines: vec![
+                                (map(x, candle.low), map(x, bottom)),
+                                (map(x, top), map(x, candle.high)),
+                            ],
+                        }
+                    }
+                    CandleStyle::Ohlc => CandleLayout {
+                        color,
+                        body: None,
+                        lines: vec![
+                            (map(x, candle.low), map(x, candle.high)),
+                            (map(x - half, candle.open), map(x, candle.open)),
+                            (map(x, candle.close), map(x + half, candle.close)),
+                        ],
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+/// Where one [Candle] of a [CandleChart] was laid out.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CandleLayout {
+    /// The color of the candle, by whether it closed up or down.
+    pub color: Color,
+    /// The closed outline of the candle's body, if it has one.
+    pub body: Option<Vec<Vec2>>,
+    /// The wicks of a candlestick, or the line and ticks of an OHLC bar.
+    pub lines: Vec<(Vec2, Vec2)>,
+}
+
+impl Canvas {
+    /// Draw a laid out candle chart in World Space, each candle in its color.
+    pub fn draw_candles(&mut self, chart: &CandleChart, layouts: &[CandleLayout]) {
+        for layout in layouts {
+            let stroke = Stroke::new(layout.color, chart.line_width, LineEnd::Butt);
+            for &(from, to) in &layout.lines {
+                self.draw_polyline_absolute(vec![from, to], stroke);
+            }
+            if let Some(body) = &layout.body {
+                self.draw_shape_absolute(body.clone(), Some(stroke), Some(layout.color));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that candles are spaced by time, colored by direction, and close up gaps when asked.
+    #[test]
+    fn lay_out_candles() {
+        let day = 86400.0;
+        let start = 1_700_000_000.0;
+        let candles = vec![
+            Candle::new(start + day * 3.0, 12.0, 14.0, 9.0, 10.0),
+            Candle::new(start, 10.0, 13.0, 8.0, 12.0),
+            Candle::new(start + day, 12.0, 12.5, 11.0, 12.0),
+        ];
+        let chart = CandleChart::new(candles, CandleStyle::Candlestick);
+        assert_eq!(chart.spacing(), day as f32);
+        assert_eq!(chart.x_axis().domain, (-day as f32 / 2.0, day as f32 * 3.5));
+        assert_eq!(chart.y_axis().domain, (8.0, 14.0));
+
+        let axes = Axes::new(
+            Vec2::ZERO,
+            Vec2::new(80.0, 60.0),
+            chart.x_axis(),
+            chart.y_axis(),
+        );
+        let layouts = chart.layout(&axes);
+        assert_eq!(layouts[0].color, chart.up);
+        assert_eq!(layouts[1].color, chart.up);
+        assert_eq!(layouts[2].color, chart.down);
+        let body = layouts[0].body.as_ref().unwrap();
+        assert!(body[0].abs_diff_eq(Vec2::new(10.0 - 7.0, 20.0), 1e-3));
+        assert!(layouts[2].lines[1]
+            .1
+            .abs_diff_eq(Vec2::new(70.0, 60.0), 1e-3));
+
+        let bars = CandleChart::new(chart.candles.clone(), CandleStyle::Ohlc).with_skip_gaps(true);
+        assert_eq!(bars.x_axis().domain, (-0.5, 2.5));
+        let layouts = bars.layout(&Axes::new(
+            Vec2::ZERO,
+            Vec2::new(30.0, 60.0),
+            bars.x_axis(),
+            bars.y_axis(),
+        ));
+        assert!(layouts[2].lines[0]
+            .0
+            .abs_diff_eq(Vec2::new(25.0, 10.0), 1e-3));
+        assert!(layouts.iter().all(|layout| layout.body.is_none()));
+
+        let mut canvas = Canvas::new(1);
+        canvas.draw_candles(&bars, &layouts);
+        assert_eq!(canvas.as_raw().len(), 9);
+    }
+}
@@ -0,0 +1,459 @@
+use glam::Vec2;
+
+use super::{format_tick, nice_ticks, Anchor, ChartText};
+use crate::{Canvas, Color, Gradient, GradientFill, LineEnd, Stroke};
+
+/// The small sample drawn beside a [Legend] label, showing what the label stands for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Swatch {
+    /// A filled square, as for bars and areas.
+    Fill(Color),
+    /// A line across the swatch, as for line series.
+    Line(Stroke),
+    /// A filled dot, as for scatter plots.
+    Marker(Color),
+}
+
+/// One row of a [Legend].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LegendEntry {
+    /// The text beside the swatch.
+    pub label: String,
+    /// What the label stands for.
+    pub swatch: Swatch,
+}
+
+/// A legend: a box of swatches, each beside a label, in rows and columns.
+///
+/// Labels are measured and laid out as [ChartText].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Legend {
+    /// The entries, filled in row by row.
+    pub entries: Vec<LegendEntry>,
+    /// Where the legend is placed around the plot area.
+    pub anchor: Anchor,
+    /// How many entries go side by side in each row. `1` makes a single column.
+    pub columns: usize,
+    /// The width and height of each swatch.
+    pub swatch_size: f32,
+    /// Space between a swatch and its label, and between neighbouring entries.
+    pub gap: f32,
+    /// Space between the entries and the edge of the box.
+    pub padding: f32,
+    /// Space between the box and the edge of the plot area.
+    pub margin: f32,
+    /// The color the box is filled with, if any.
+    pub background: Option<Color>,
+    /// The stroke around the box, if any.
+    pub border: Option<Stroke>,
+}
+
+impl Legend {
+    /// Create a new, empty [Legend] in a single column on a white box, spaced by half the swatch size.
+    pub fn new(anchor: Anchor, swatch_size: f32) -> Self {
+        Self {
+            entries: Vec::new(),
+            anchor,
+            columns: 1,
+            swatch_size,
+            gap: swatch_size / 2.0,
+            padding: swatch_size / 2.0,
+            margin: swatch_size / 2.0,
+            background: Some(Color::white()),
+            border: None,
+        }
+    }
+
+    /// Add an entry to the [Legend], consuming the parent.
+    pub fn with_entry(mut self, label: impl Into<String>, swatch: Swatch) -> Self {
+        self.entries.push(LegendEntry {
+            label: label.into(),
+            swatch,
+        });
+        self
+    }
+
+    /// Set how many entries go side by side in each row, consuming the parent.
+    pub fn with_columns(mut self, columns: usize) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// Set how the box is filled and outlined, consuming the parent.
+    pub fn with_background(mut self, background: Option<Color>, border: Option<Stroke>) -> Self {
+        self.background = background;
+        self.border = border;
+        self
+    }
+
+    /// Lay out the legend beside or within the plot area from `area_min` to `area_max`.
+    ///
+    /// `measure` gives the width and height of a label's text.
+    pub fn layout<M: Fn(&str) -> Vec2>(
+        &self,
+        area_min: Vec2,
+        area_max: Vec2,
+        measure: M,
+    ) -> LegendLayout {
+        let columns = self.columns.max(1);
+        let rows = self.entries.len().div_ceil(columns);
+        let sizes: Vec<Vec2> = self
+            .entries
+            .iter()
+            .map(|entry| measure(&entry.label))
+            .collect();
+
+        let mut widths = vec![0.0f32; columns];
+        let mut heights = vec![0.0f32; rows];
+        for (i, size) in sizes.iter().enumerate() {
+            let (row, column) = (i / columns, i % columns);
+            widths[column] = widths[column].max(self.swatch_size + self.gap + size.x);
+            heights[row] = heights[row].max(self.swatch_size.max(size.y));
+        }
+
+        let gaps = |count: usize| count.saturating_sub(1) as f32 * self.gap;
+        let inner = Vec2::new(
+            widths.iter().sum::<f32>() + gaps(columns.min(self.entries.len())),
+            heights.iter().sum::<f32>() + gaps(rows),
+        );
+        let size = if self.entries.is_empty() {
+            Vec2::ZERO
+        } else {
+            inner + self.padding * 2.0
+        };
+        let min = self.anchor.place(area_min, area_max, size, self.margin);
+        let max = min + size;
+
+        let mut swatches = Vec::with_capacity(self.entries.len());
+        let mut texts = Vec::with_capacity(self.entries.len());
+        for (i, (entry, size)) in self.entries.iter().zip(&sizes).enumerate() {
+            let (row, column) = (i / columns, i % columns);
+            let left = min.x
+                + self.padding
+                + widths[..column].iter().sum::<f32>()
+                + column as f32 * self.gap;
+            // Rows run down from the top of the box.
+            let middle = max.y
+                - self.padding
+                - heights[..row].iter().sum::<f32>()
+                - row as f32 * self.gap
+                - heights[row] / 2.0;
+
+            let half = self.swatch_size / 2.0;
+            swatches.push((
+                Vec2::new(left, middle - half),
+                Vec2::new(left + self.swatch_size, middle + half),
+            ));
+            texts.push(ChartText {
+                text: entry.label.clone(),
+                position: Vec2::new(left + self.swatch_size + self.gap + size.x / 2.0, middle),
+                size: *size,
+            });
+        }
+
+        LegendLayout {
+            min,
+            max,
+            swatches,
+            texts,
+        }
+    }
+}
+
+/// Where a [Legend] was laid out.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LegendLayout {
+    /// The bottom left corner of the box.
+    pub min: Vec2,
+    /// The top right corner of the box.
+    pub max: Vec2,
+    /// The bottom left and top right corners of each entry's swatch, in the same order as the entries.
+    pub swatches: Vec<(Vec2, Vec2)>,
+    /// Each entry's label, in the same order as the entries.
+    pub texts: Vec<ChartText>,
+}
+
+/// A colorbar: a strip painted with a [Gradient], with ticks and labels for the values its colors stand for.
+///
+/// Labels are measured and laid out as [ChartText].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Colorbar {
+    /// The colors, from the low end of the strip to the high end.
+    pub gradient: Gradient,
+    /// The values at the low and high ends of the strip.
+    pub domain: (f32, f32),
+    /// Where the colorbar is placed around the plot area.
+    pub anchor: Anchor,
+    /// How long the strip is.
+    pub length: f32,
+    /// How thick the strip is.
+    pub thickness: f32,
+    /// Whether the strip runs up, with labels to its right, or across, with labels below it.
+    pub vertical: bool,
+    /// About how many ticks to mark along the strip.
+    pub ticks: usize,
+    /// How far ticks reach out from the strip.
+    pub tick_length: f32,
+    /// Space between the ends of the ticks and their labels.
+    pub gap: f32,
+    /// Space between the colorbar and the edge of the plot area.
+    pub margin: f32,
+    /// The stroke around the strip and along the ticks.
+    pub stroke: Stroke,
+}
+
+impl Colorbar {
+    /// Create a new upright [Colorbar] with about five ticks, outlined in black.
+    pub fn new(
+        gradient: Gradient,
+        domain: (f32, f32),
+        anchor: Anchor,
+        length: f32,
+        thickness: f32,
+    ) -> Self {
+        Self {
+            gradient,
+            domain,
+            anchor,
+            length,
+            thickness,
+            vertical: true,
+            ticks: 5,
+            tick_length: thickness / 4.0,
+            gap: thickness / 4.0,
+            margin: thickness,
+            stroke: Stroke::new(Color::black(), thickness / 20.0, LineEnd::Butt),
+        }
+    }
+
+    /// Set whether the strip runs up or across, consuming the parent.
+    pub fn with_vertical(mut self, vertical: bool) -> Self {
+        self.vertical = vertical;
+        self
+    }
+
+    /// Set about how many ticks to mark along the strip, consuming the parent.
+    pub fn with_ticks(mut self, ticks: usize) -> Self {
+        self.ticks = ticks;
+        self
+    }
+
+    /// Lay out the colorbar beside or within the plot area from `area_min` to `area_max`.
+    ///
+    /// `measure` gives the width and height of a label's text.
+    pub fn layout<M: Fn(&str) -> Vec2>(
+        &self,
+        area_min: Vec2,
+        area_max: Vec2,
+        measure: M,
+    ) -> ColorbarLayout {
+        let (low, high) = self.domain;
+        let values = nice_ticks(low, high, self.ticks);
+        let step = match values.as_slice() {
+            [first, second, ..] => second - first,
+            _ => (high - low).abs(),
+        };
+        let labels: Vec<(f32, String, Vec2)> = values
+            .into_iter()
+            .map(|value| {
+                let text = format_tick(value, step);
+                let size = measure(&text);
+                (value, text, size)
+            })
+            .collect();
+        let largest = labels
+            .iter()
+            .fold(Vec2::ZERO, |largest, (_, _, size)| largest.max(*size));
+
+        // The strip runs along `along`, and the ticks and labels stick out along `across`.
+        let (along, across) = if self.vertical {
+            (Vec2::Y, Vec2::X)
+        } else {
+            (Vec2::X, -Vec2::Y)
+        };
+        let reach = self.thickness + self.tick_length + self.gap + largest.dot(across.abs());
+        let overhang = largest.dot(along) / 2.0;
+        let size = along * (self.length + overhang * 2.0) + across.abs() * reach;
+
+        let min = self.anchor.place(area_min, area_max, size, self.margin);
+        let start = if self.vertical {
+            min + along * overhang
+        } else {
+            min + along * overhang + Vec2::Y * (size.y - self.thickness)
+        };
+        let bar_min = start;
+        let bar_max = start + along * self.length + across.abs() * self.thickness;
+        let side = if self.vertical {
+            start + across * self.thickness
+        } else {
+            start
+        };
+
+        let mut ticks = Vec::with_capacity(labels.len());
+        let mut texts = Vec::with_capacity(labels.len());
+        for (value, text, label_size) in labels {
+            let t = if high != low {
+                (value - low) / (high - low)
+            } else {
+                0.5
+            };
+            let base = side + along * self.length * t;
+            let tip = base + across * self.tick_length;
+            ticks.push((base, tip));
+            texts.push(ChartText {
+                text,
+                position: tip + across * (self.gap + label_size.dot(across.abs()) / 2.0),
+                size: label_size,
+            });
+        }
+
+        ColorbarLayout {
+            min,
+            max: min + size,
+            bar_min,
+            bar_max,
+            start,
+            end: start + along * self.length,
+            ticks,
+            texts,
+        }
+    }
+}
+
+/// Where a [Colorbar] was laid out.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColorbarLayout {
+    /// The bottom left corner of the whole colorbar, labels included.
+    pub min: Vec2,
+    /// The top right corner of the whole colorbar, labels included.
+    pub max: Vec2,
+    /// The bottom left corner of the strip.
+    pub bar_min: Vec2,
+    /// The top right corner of the strip.
+    pub bar_max: Vec2,
+    /// Where the low end of the gradient is painted.
+    pub start: Vec2,
+    /// Where the high end of the gradient is painted.
+    pub end: Vec2,
+    /// Each tick, from the edge of the strip out.
+    pub ticks: Vec<(Vec2, Vec2)>,
+    /// Each tick's label, in the same order as the ticks.
+    pub texts: Vec<ChartText>,
+}
+
+impl Canvas {
+    /// Draw a laid out legend in World Space: its box, then each swatch. The labels are left to the caller, as laid out in [LegendLayout::texts].
+    pub fn draw_legend(&mut self, legend: &Legend, layout: &LegendLayout) {
+        if legend.background.is_some() || legend.border.is_some() {
+            self.draw_shape_absolute(
+                rectangle(layout.min, layout.max),
+                legend.border,
+                legend.background,
+            );
+        }
+
+        for (entry, &(min, max)) in legend.entries.iter().zip(&layout.swatches) {
+            let middle = (min + max) / 2.0;
+            match entry.swatch {
+                Swatch::Fill(color) => {
+                    self.draw_shape_absolute(rectangle(min, max), None, Some(color))
+                }
+                Swatch::Line(stroke) => self.draw_polyline_absolute(
+                    vec![Vec2::new(min.x, middle.y), Vec2::new(max.x, middle.y)],
+                    stroke,
+                ),
+                Swatch::Marker(color) => {
+                    self.draw_circle_absolute(middle, (max.x - min.x) / 3.0, None, Some(color))
+                }
+            }
+        }
+    }
+
+    /// Draw a laid out colorbar in World Space: the strip painted with its gradient, then the ticks. The labels are left to the caller, as laid out in [ColorbarLayout::texts].
+    pub fn draw_colorbar(&mut self, colorbar: &Colorbar, layout: &ColorbarLayout) {
+        self.draw_gradient_shape_absolute(
+            rectangle(layout.bar_min, layout.bar_max),
+            Some(colorbar.stroke),
+            GradientFill::new(colorbar.gradient.clone(), layout.start, layout.end),
+        );
+
+        for &(base, tip) in &layout.ticks {
+            self.draw_polyline_absolute(vec![base, tip], colorbar.stroke);
+        }
+    }
+}
+
+/// The closed outline of the rectangle from `min` to `max`.
+fn rectangle(min: Vec2, max: Vec2) -> Vec<Vec2> {
+    vec![
+        min,
+        Vec2::new(max.x, min.y),
+        max,
+        Vec2::new(min.x, max.y),
+        min,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that legend entries fill rows within their box, and colorbar ticks and labels line up along the strip.
+    #[test]
+    fn legend_and_colorbar() {
+        let measure = |text: &str| Vec2::new(text.len() as f32, 1.0);
+        let (area_min, area_max) = (Vec2::ZERO, Vec2::new(100.0, 50.0));
+
+        let legend = Legend::new(Anchor::TopRight, 1.0)
+            .with_entry("one", Swatch::Fill(Color::black()))
+            .with_entry("three", Swatch::Marker(Color::black()))
+            .with_entry(
+                "two",
+                Swatch::Line(Stroke::new(Color::black(), 0.1, LineEnd::Butt)),
+            )
+            .with_columns(2);
+        let layout = legend.layout(area_min, area_max, measure);
+
+        // Two columns, as wide as their widest entries, and two rows.
+        assert_eq!(layout.max, area_max - 0.5);
+        assert_eq!(layout.max - layout.min, Vec2::new(12.5, 3.5));
+        assert_eq!(
+            layout.texts[2].position,
+            Vec2::new(layout.min.x + 3.5, layout.min.y + 1.0)
+        );
+        assert!(layout.swatches[1].0.x > layout.texts[0].position.x + 1.5);
+        assert_eq!(layout.swatches[0].1.y, layout.max.y - 0.5);
+
+        let mut canvas = Canvas::new(10);
+        canvas.draw_legend(&legend, &layout);
+        assert_eq!(canvas.as_raw().len(), 4);
+
+        let gradient = Gradient::new(Color::black(), Color::white());
+        let colorbar = Colorbar::new(gradient, (0.0, 1.0), Anchor::OutsideRight, 40.0, 4.0);
+        let layout = colorbar.layout(area_min, area_max, measure);
+        assert_eq!(layout.texts.len(), 6);
+        assert_eq!(layout.texts[1].text, "0.2");
+        assert_eq!(layout.bar_max.y - layout.bar_min.y, 40.0);
+        assert!(layout.bar_min.x >= area_max.x);
+        assert_eq!(
+            layout.ticks[0].0,
+            Vec2::new(layout.bar_max.x, layout.start.y)
+        );
+        assert_eq!(layout.ticks[5].0.y, layout.end.y);
+        assert!(layout.texts[5].position.x > layout.ticks[5].1.x);
+
+        let colorbar = colorbar.with_vertical(false);
+        let layout = colorbar.layout(area_min, area_max, measure);
+        assert_eq!(layout.bar_max.x - layout.bar_min.x, 40.0);
+        assert!(layout.texts[0].position.y < layout.ticks[0].1.y);
+
+        canvas.draw_colorbar(&colorbar, &layout);
+        assert_eq!(canvas.as_raw().len(), 4 + 1 + 6);
+    }
+}
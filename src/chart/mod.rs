@@ -0,0 +1,190 @@
+mod annotation;
+mod axes;
+mod choropleth;
+mod data;
+mod distribution;
+mod facet;
+mod financial;
+mod legend;
+mod polar;
+mod scale;
+mod series;
+mod stats;
+mod time;
+
+pub use annotation::{Band, Brace, BraceLayout, Callout, CalloutLayout};
+pub use axes::{Axes, AxesLayout, Axis, AxisScale};
+pub use choropleth::{Choropleth, Classes};
+pub use data::{columns_to_points, rows_to_points, Table};
+pub use distribution::{
+    DistributionKind, DistributionLayout, DistributionPlot, DistributionSeries, Orientation,
+    Summary,
+};
+pub use facet::{partition, Facet, FacetGrid, FacetLayout};
+pub use financial::{Candle, CandleChart, CandleLayout, CandleStyle};
+pub use legend::{Colorbar, ColorbarLayout, Legend, LegendEntry, LegendLayout, Swatch};
+pub use polar::{Polar, PolarAxes, PolarAxesLayout, Radar, RadarLayout, RadarSeries};
+pub use scale::{BandScale, LinearScale, LogScale, OrdinalScale, Scale, SqrtScale};
+pub use series::Bar;
+pub use stats::{histogram, quantile, Bin, BinRule, Kde, MAX_BINS};
+
+use glam::Vec2;
+
+/// Where a box, such as a [Legend] or [Colorbar], is placed around a plot area.
+///
+/// The inside anchors keep the box within the area, a margin in from its edges.
+/// The outside anchors put the box a margin beyond one side, centered along it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Anchor {
+    /// Inside, in the top left corner.
+    TopLeft,
+    /// Inside, in the middle of the top edge.
+    Top,
+    /// Inside, in the top right corner.
+    #[default]
+    TopRight,
+    /// Inside, in the middle of the left edge.
+    Left,
+    /// Inside, in the middle of the area.
+    Center,
+    /// Inside, in the middle of the right edge.
+    Right,
+    /// Inside, in the bottom left corner.
+    BottomLeft,
+    /// Inside, in the middle of the bottom edge.
+    Bottom,
+    /// Inside, in the bottom right corner.
+    BottomRight,
+    /// Outside, above the top edge.
+    OutsideTop,
+    /// Outside, past the right edge.
+    OutsideRight,
+    /// Outside, below the bottom edge.
+    OutsideBottom,
+    /// Outside, past the left edge.
+    OutsideLeft,
+}
+
+impl Anchor {
+    /// Place a box of `size` around the plot area from `area_min` to `area_max`, returning the box's bottom left corner.
+    pub fn place(&self, area_min: Vec2, area_max: Vec2, size: Vec2, margin: f32) -> Vec2 {
+        let middle = (area_min + area_max - size) / 2.0;
+        let low = area_min + margin;
+        let high = area_max - size - margin;
+
+        match self {
+            Anchor::TopLeft => Vec2::new(low.x, high.y),
+            Anchor::Top => Vec2::new(middle.x, high.y),
+            Anchor::TopRight => high,
+            Anchor::Left => Vec2::new(low.x, middle.y),
+            Anchor::Center => middle,
+            Anchor::Right => Vec2::new(high.x, middle.y),
+            Anchor::BottomLeft => low,
+            Anchor::Bottom => Vec2::new(middle.x, low.y),
+            Anchor::BottomRight => Vec2::new(high.x, low.y),
+            Anchor::OutsideTop => Vec2::new(middle.x, area_max.y + margin),
+            Anchor::OutsideRight => Vec2::new(area_max.x + margin, middle.y),
+            Anchor::OutsideBottom => Vec2::new(middle.x, area_min.y - size.y - margin),
+            Anchor::OutsideLeft => Vec2::new(area_min.x - size.x - margin, middle.y),
+        }
+    }
+}
+
+/// Text to write on a chart, such as a legend label or tick label.
+///
+/// There's no text rendering in `barium`. Anything that places text takes a function giving the width and height of a string,
+/// lays the text out around those sizes, and hands it back to be drawn with whatever draws the text, such as SVG `<text>` or a font rasterizer.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChartText {
+    /// The text.
+    pub text: String,
+    /// The middle of the text.
+    pub position: Vec2,
+    /// The width and height of the text, as measured.
+    pub size: Vec2,
+}
+
+/// Pick round tick values between `min` and `max`, about `count` of them, spaced 1, 2 or 5 times a power of ten apart.
+pub fn nice_ticks(min: f32, max: f32, count: usize) -> Vec<f32> {
+    let (min, max) = (min.min(max), min.max(max));
+    if max <= min || count == 0 || !(max - min).is_finite() {
+        return vec![min];
+    }
+
+    let step = nice_step((max - min) / count as f32);
+    let first = (min / step - 1e-4).ceil() as i64;
+    let last = (max / step + 1e-4).floor() as i64;
+    (first..=last).map(|i| i as f32 * step).collect()
+}
+
+/// Round `step` to the nearest of 1, 2 or 5 times a power of ten.
+pub(crate) fn nice_step(step: f32) -> f32 {
+    let power = 10f32.powf(step.log10().floor());
+    let fraction = step / power;
+    let nice = if fraction < 1.5 {
+        1.0
+    } else if fraction < 3.5 {
+        2.0
+    } else if fraction < 7.5 {
+        5.0
+    } else {
+        10.0
+    };
+    nice * power
+}
+
+/// Write `value` with just enough decimal places to tell apart ticks `step` apart.
+pub(crate) fn format_tick(value: f32, step: f32) -> String {
+    let decimals = if step > 0.0 {
+        (-step.log10().floor()).max(0.0) as usize
+    } else {
+        0
+    };
+    let text = format!("{:.*}", decimals, value);
+    // Avoid writing "-0" for values that round to zero.
+    if text
+        .trim_start_matches('-')
+        .chars()
+        .all(|c| c == '0' || c == '.')
+    {
+        text.trim_start_matches('-').to_string()
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that ticks land on round values within the range, and anchors place boxes inside or beside the area.
+    #[test]
+    fn ticks_and_anchors() {
+        assert_eq!(nice_ticks(0.0, 1.0, 5), vec![0.0, 0.2, 0.4, 0.6, 0.8, 1.0]);
+        assert_eq!(nice_ticks(-3.0, 47.0, 5), vec![0.0, 10.0, 20.0, 30.0, 40.0]);
+        assert_eq!(nice_ticks(2.0, 2.0, 5), vec![2.0]);
+        assert_eq!(format_tick(0.2 * 3.0, 0.2), "0.6");
+        assert_eq!(format_tick(-0.01, 0.1), "0.0");
+        assert_eq!(format_tick(40.0, 10.0), "40");
+
+        let (min, max, size) = (Vec2::ZERO, Vec2::new(100.0, 50.0), Vec2::new(20.0, 10.0));
+        assert_eq!(
+            Anchor::TopRight.place(min, max, size, 2.0),
+            Vec2::new(78.0, 38.0)
+        );
+        assert_eq!(
+            Anchor::BottomLeft.place(min, max, size, 2.0),
+            Vec2::new(2.0, 2.0)
+        );
+        assert_eq!(
+            Anchor::OutsideRight.place(min, max, size, 2.0),
+            Vec2::new(102.0, 20.0)
+        );
+        assert_eq!(
+            Anchor::OutsideBottom.place(min, max, size, 2.0),
+            Vec2::new(40.0, -12.0)
+        );
+    }
+}
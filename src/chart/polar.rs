@@ -0,0 +1,423 @@
+use std::f32::consts::TAU;
+
+use glam::Vec2;
+
+use super::{format_tick, nice_ticks, ChartText};
+use crate::{Canvas, Color, LineEnd, Stroke};
+
+/// Polar coordinates on a canvas: a radius `r` and an angle `theta`, mapped around a center.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Polar {
+    /// Where `r` is at the low end of the domain.
+    pub center: Vec2,
+    /// How far from the center the high end of the domain is.
+    pub radius: f32,
+    /// The values of `r` at the center and at the rim.
+    pub domain: (f32, f32),
+    /// The direction `theta = 0` points in, in radians counter-clockwise from the x axis.
+    pub start_angle: f32,
+    /// Whether `theta` grows clockwise, as on a compass or a radar chart, rather than counter-clockwise.
+    pub clockwise: bool,
+}
+
+impl Polar {
+    /// Create a new [Polar] mapping with `theta = 0` along the x axis, growing counter-clockwise.
+    pub fn new(center: Vec2, radius: f32, domain: (f32, f32)) -> Self {
+        Self {
+            center,
+            radius,
+            domain,
+            start_angle: 0.0,
+            clockwise: false,
+        }
+    }
+
+    /// Set the direction `theta = 0` points in, consuming the parent.
+    pub fn with_start_angle(mut self, start_angle: f32) -> Self {
+        self.start_angle = start_angle;
+        self
+    }
+
+    /// Set whether `theta` grows clockwise, consuming the parent.
+    pub fn with_clockwise(mut self, clockwise: bool) -> Self {
+        self.clockwise = clockwise;
+        self
+    }
+
+    /// The angle on the canvas, counter-clockwise from the x axis, that `theta` points in.
+    pub fn angle(&self, theta: f32) -> f32 {
+        if self.clockwise {
+            self.start_angle - theta
+        } else {
+            self.start_angle + theta
+        }
+    }
+
+    /// The distance from the center that `r` is mapped to.
+    pub fn distance(&self, r: f32) -> f32 {
+        let (low, high) = self.domain;
+        if high == low {
+            return 0.0;
+        }
+        (r - low) / (high - low) * self.radius
+    }
+
+    /// Map `r` and `theta` to a point on the canvas.
+    pub fn point(&self, r: f32, theta: f32) -> Vec2 {
+        let angle = self.angle(theta);
+        self.center + Vec2::new(angle.cos(), angle.sin()) * self.distance(r)
+    }
+
+    /// Map a point on the canvas back to `r` and `theta`, with `theta` from `0` up to a full turn.
+    pub fn invert(&self, point: Vec2) -> (f32, f32) {
+        let offset = point - self.center;
+        let (low, high) = self.domain;
+        let r = low + offset.length() / self.radius * (high - low);
+        let angle = offset.y.atan2(offset.x) - self.start_angle;
+        let theta = if self.clockwise { -angle } else { angle };
+        (r, theta.rem_euclid(TAU))
+    }
+
+    /// Points along the arc at `r` from `theta_from` to `theta_to`, with a point every few degrees.
+    pub fn arc(&self, r: f32, theta_from: f32, theta_to: f32) -> Vec<Vec2> {
+        let steps = ((theta_to - theta_from).abs() / (TAU / 96.0))
+            .ceil()
+            .max(1.0) as usize;
+        (0..=steps)
+            .map(|i| {
+                self.point(
+                    r,
+                    theta_from + (theta_to - theta_from) * i as f32 / steps as f32,
+                )
+            })
+            .collect()
+    }
+}
+
+/// The grid and axes of a polar chart: rings at round values of `r`, and spokes out from the center at even angles.
+///
+/// Labels are measured and laid out as [ChartText].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PolarAxes {
+    /// The mapping the grid is drawn for.
+    pub polar: Polar,
+    /// About how many rings to draw between the center and the rim. The rim is always drawn.
+    pub rings: usize,
+    /// How many spokes to draw, evenly spaced around a full turn starting at `theta = 0`.
+    pub spokes: usize,
+    /// The label at the end of each spoke. When empty, spokes are labelled with their angle in degrees.
+    pub labels: Vec<String>,
+    /// Whether the rings are drawn as polygons through the spokes, as on a radar chart, rather than as circles.
+    pub polygonal: bool,
+    /// The stroke the rings and spokes are drawn with.
+    pub stroke: Stroke,
+    /// Space between the grid and its labels.
+    pub gap: f32,
+}
+
+impl PolarAxes {
+    /// Create new [PolarAxes] with about four circular rings and eight spokes, in thin gray lines.
+    pub fn new(polar: Polar) -> Self {
+        Self {
+            polar,
+            rings: 4,
+            spokes: 8,
+            labels: Vec::new(),
+            polygonal: false,
+            stroke: Stroke::new(
+                Color::new(0.6, 0.6, 0.6, 1.0),
+                polar.radius / 200.0,
+                LineEnd::Butt,
+            ),
+            gap: polar.radius / 40.0,
+        }
+    }
+
+    /// Set about how many rings to draw, consuming the parent.
+    pub fn with_rings(mut self, rings: usize) -> Self {
+        self.rings = rings;
+        self
+    }
+
+    /// Set how many spokes to draw, consuming the parent.
+    pub fn with_spokes(mut self, spokes: usize) -> Self {
+        self.spokes = spokes;
+        self
+    }
+
+    /// Set the label at the end of each spoke, and draw one spoke per label, consuming the parent.
+    pub fn with_labels<S: Into<String>, I: IntoIterator<Item = S>>(mut self, labels: I) -> Self {
+        self.labels = labels.into_iter().map(Into::into).collect();
+        self.spokes = self.labels.len();
+        self
+    }
+
+    /// Set whether the rings are drawn as polygons through the spokes, consuming the parent.
+    pub fn with_polygonal(mut self, polygonal: bool) -> Self {
+        self.polygonal = polygonal;
+        self
+    }
+
+    /// The value of `theta` along each spoke.
+    pub fn spoke_thetas(&self) -> Vec<f32> {
+        (0..self.spokes)
+            .map(|i| TAU * i as f32 / self.spokes as f32)
+            .collect()
+    }
+
+    /// Lay out the rings, spokes and labels.
+    ///
+    /// `measure` gives the width and height of a label's text.
+    pub fn layout<M: Fn(&str) -> Vec2>(&self, measure: M) -> PolarAxesLayout {
+        let polar = &self.polar;
+        let (low, high) = polar.domain;
+        let values = nice_ticks(low, high, self.rings);
+        let step = match values.as_slice() {
+            [first, second, ..] => second - first,
+            _ => (high - low).abs(),
+        };
+        let mut values: Vec<f32> = values
+            .into_iter()
+            .filter(|&value| polar.distance(value) > 1e-4 * polar.radius)
+            .filter(|&value| polar.distance(value) < polar.radius * (1.0 - 1e-4))
+            .collect();
+        values.push(high);
+
+        let thetas = self.spoke_thetas();
+        let mut rings = Vec::with_capacity(values.len());
+        let mut texts = Vec::with_capacity(values.len() + thetas.len());
+        for &value in &values {
+            rings.push(if self.polygonal && thetas.len() >= 3 {
+                let mut points: Vec<Vec2> = thetas
+                    .iter()
+                    .map(|&theta| polar.point(value, theta))
+                    .collect();
+                points.push(points[0]);
+                points
+            } else {
+                polar.arc(value, 0.0, TAU)
+            });
+
+            // Ring labels sit just to the side of the first spoke, away from the way theta grows.
+            let text = format_tick(value, step);
+            let size = measure(&text);
+            let angle = polar.angle(0.0);
+            let along = Vec2::new(angle.cos(), angle.sin());
+            let side = if polar.clockwise {
+                along.perp()
+            } else {
+                -along.perp()
+            };
+            texts.push(ChartText {
+                position: polar.point(value, 0.0) + side * (self.gap + extent(size, side)),
+                text,
+                size,
+            });
+        }
+
+        let mut spokes = Vec::with_capacity(thetas.len());
+        for (i, &theta) in thetas.iter().enumerate() {
+            let tip = polar.point(high, theta);
+            spokes.push((polar.center, tip));
+
+            let text = match self.labels.get(i) {
+                Some(label) => label.clone(),
+                None => format!("{}°", format_tick(theta.to_degrees(), 1.0)),
+            };
+            let size = measure(&text);
+            let angle = polar.angle(theta);
+            let out = Vec2::new(angle.cos(), angle.sin());
+            texts.push(ChartText {
+                position: tip + out * (self.gap + extent(size, out)),
+                text,
+                size,
+            });
+        }
+
+        PolarAxesLayout {
+            rings,
+            spokes,
+            texts,
+        }
+    }
+}
+
+/// Half the distance across a box of `size` in `direction`.
+fn extent(size: Vec2, direction: Vec2) -> f32 {
+    size.dot(direction.abs()) / 2.0
+}
+
+/// Where [PolarAxes] were laid out.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PolarAxesLayout {
+    /// The closed outline of each ring, from the center out.
+    pub rings: Vec<Vec<Vec2>>,
+    /// Each spoke, from the center to the rim.
+    pub spokes: Vec<(Vec2, Vec2)>,
+    /// The label of each ring, then the label of each spoke.
+    pub texts: Vec<ChartText>,
+}
+
+/// One set of values on a [Radar] chart, drawn as a closed polygon.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RadarSeries {
+    /// One value per spoke, in the same order as the labels.
+    pub values: Vec<f32>,
+    /// The stroke around the polygon.
+    pub stroke: Stroke,
+    /// The color the polygon is filled with, if any. Usually see-through, so series beneath still show.
+    pub fill: Option<Color>,
+}
+
+/// A radar (or spider) chart: one spoke per quantity, starting straight up and going clockwise,
+/// with each series drawn as a polygon through its values.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Radar {
+    /// The grid, with one spoke per quantity.
+    pub axes: PolarAxes,
+    /// The series, drawn in order.
+    pub series: Vec<RadarSeries>,
+}
+
+impl Radar {
+    /// Create a new, empty [Radar] chart with one spoke per label, and values from zero to `max` at the rim.
+    pub fn new<S: Into<String>, I: IntoIterator<Item = S>>(
+        center: Vec2,
+        radius: f32,
+        labels: I,
+        max: f32,
+    ) -> Self {
+        let polar = Polar::new(center, radius, (0.0, max))
+            .with_start_angle(TAU / 4.0)
+            .with_clockwise(true);
+
+        Self {
+            axes: PolarAxes::new(polar)
+                .with_labels(labels)
+                .with_polygonal(true),
+            series: Vec::new(),
+        }
+    }
+
+    /// Add a series to the [Radar] chart, consuming the parent.
+    pub fn with_series(mut self, values: Vec<f32>, stroke: Stroke, fill: Option<Color>) -> Self {
+        self.series.push(RadarSeries {
+            values,
+            stroke,
+            fill,
+        });
+        self
+    }
+
+    /// Lay out the grid and the polygon of each series.
+    ///
+    /// `measure` gives the width and height of a label's text.
+    pub fn layout<M: Fn(&str) -> Vec2>(&self, measure: M) -> RadarLayout {
+        let thetas = self.axes.spoke_thetas();
+        let polygons = self
+            .series
+            .iter()
+            .map(|series| {
+                let mut points: Vec<Vec2> = thetas
+                    .iter()
+                    .zip(&series.values)
+                    .map(|(&theta, &value)| self.axes.polar.point(value, theta))
+                    .collect();
+                if let Some(&first) = points.first() {
+                    points.push(first);
+                }
+                points
+            })
+            .collect();
+
+        RadarLayout {
+            axes: self.axes.layout(measure),
+            polygons,
+        }
+    }
+}
+
+/// Where a [Radar] chart was laid out.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RadarLayout {
+    /// The grid, with its labels.
+    pub axes: PolarAxesLayout,
+    /// The closed polygon of each series, in the same order as the series.
+    pub polygons: Vec<Vec<Vec2>>,
+}
+
+impl Canvas {
+    /// Draw laid out polar axes in World Space: the rings, then the spokes. The labels are left to the caller, as laid out in [PolarAxesLayout::texts].
+    pub fn draw_polar_axes(&mut self, axes: &PolarAxes, layout: &PolarAxesLayout) {
+        for ring in &layout.rings {
+            self.draw_polyline_absolute(ring.clone(), axes.stroke);
+        }
+        for &(center, tip) in &layout.spokes {
+            self.draw_polyline_absolute(vec![center, tip], axes.stroke);
+        }
+    }
+
+    /// Draw a laid out radar chart in World Space: the grid, then each series on top. The labels are left to the caller, as laid out in [PolarAxesLayout::texts].
+    pub fn draw_radar(&mut self, radar: &Radar, layout: &RadarLayout) {
+        self.draw_polar_axes(&radar.axes, &layout.axes);
+        for (series, polygon) in radar.series.iter().zip(&layout.polygons) {
+            self.draw_shape_absolute(polygon.clone(), Some(series.stroke), series.fill);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that polar points map there and back, and radar polygons reach out along their spokes.
+    #[test]
+    fn polar_and_radar() {
+        let polar = Polar::new(Vec2::new(10.0, 10.0), 5.0, (0.0, 100.0));
+        assert!(polar
+            .point(100.0, 0.0)
+            .abs_diff_eq(Vec2::new(15.0, 10.0), 1e-4));
+        assert!(polar
+            .point(50.0, TAU / 4.0)
+            .abs_diff_eq(Vec2::new(10.0, 12.5), 1e-4));
+        let (r, theta) = polar.invert(Vec2::new(10.0, 7.5));
+        assert!((r - 50.0).abs() < 1e-3 && (theta - TAU * 0.75).abs() < 1e-4);
+
+        let axes = PolarAxes::new(polar).layout(|text| Vec2::new(text.len() as f32, 1.0));
+        assert_eq!(axes.rings.len(), 5);
+        assert_eq!(axes.spokes.len(), 8);
+        assert_eq!(axes.texts[0].text, "20");
+        assert_eq!(axes.texts[6].text, "45°");
+
+        let stroke = Stroke::new(Color::black(), 0.1, LineEnd::Butt);
+        let radar = Radar::new(Vec2::ZERO, 10.0, ["a", "b", "c", "d"], 10.0).with_series(
+            vec![10.0, 5.0, 10.0, 0.0],
+            stroke,
+            None,
+        );
+        let layout = radar.layout(|text| Vec2::new(text.len() as f32, 1.0));
+
+        // The first spoke points up, and the rest go clockwise.
+        let polygon = &layout.polygons[0];
+        assert_eq!(polygon.len(), 5);
+        assert!(polygon[0].abs_diff_eq(Vec2::new(0.0, 10.0), 1e-4));
+        assert!(polygon[1].abs_diff_eq(Vec2::new(5.0, 0.0), 1e-4));
+        assert!(polygon[2].abs_diff_eq(Vec2::new(0.0, -10.0), 1e-4));
+        assert!(polygon[3].abs_diff_eq(Vec2::ZERO, 1e-4));
+        assert_eq!(layout.axes.rings[0].len(), 5);
+        assert!(layout.axes.texts.iter().any(|text| text.text == "d"));
+
+        let mut canvas = Canvas::new(1);
+        canvas.draw_radar(&radar, &layout);
+        assert_eq!(
+            canvas.as_raw().len(),
+            layout.axes.rings.len() + layout.axes.spokes.len() + 1
+        );
+    }
+}
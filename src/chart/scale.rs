@@ -0,0 +1,294 @@
+use crate::{Color, Gradient};
+
+/// A mapping from a continuous domain of data values onto a range, such as positions, sizes or, through a [Gradient], colors.
+pub trait Scale {
+    /// How far along the domain `value` is, from `0.0` at its start to `1.0` at its end.
+    fn fraction(&self, value: f32) -> f32;
+
+    /// The values the start and end of the domain are mapped to.
+    fn range(&self) -> (f32, f32);
+
+    /// Map `value` into the range.
+    fn map(&self, value: f32) -> f32 {
+        let (start, end) = self.range();
+        start + (end - start) * self.fraction(value)
+    }
+
+    /// Map `value` to a color along `gradient`, from its first color at the start of the domain to its last at the end.
+    fn color(&self, value: f32, gradient: &Gradient) -> Color {
+        gradient.sample(self.fraction(value).clamp(0.0, 1.0))
+    }
+}
+
+/// How far `value` is from `start` to `end`, clamped to the domain if `clamp` is set.
+fn fraction(value: f32, start: f32, end: f32, clamp: bool) -> f32 {
+    if end == start {
+        return 0.5;
+    }
+    let fraction = (value - start) / (end - start);
+    if clamp {
+        fraction.clamp(0.0, 1.0)
+    } else {
+        fraction
+    }
+}
+
+/// A [Scale] where equal steps in value make equal steps in the range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LinearScale {
+    /// The data values at the start and end of the scale.
+    pub domain: (f32, f32),
+    /// The values the start and end of the domain are mapped to.
+    pub range: (f32, f32),
+    /// Whether values outside the domain are held at its ends, rather than mapped beyond the range.
+    pub clamp: bool,
+}
+
+impl LinearScale {
+    /// Create a new [LinearScale], without clamping.
+    pub fn new(domain: (f32, f32), range: (f32, f32)) -> Self {
+        Self {
+            domain,
+            range,
+            clamp: false,
+        }
+    }
+
+    /// Set whether values outside the domain are held at its ends, consuming the parent.
+    pub fn with_clamp(mut self, clamp: bool) -> Self {
+        self.clamp = clamp;
+        self
+    }
+
+    /// Map a value in the range back into the domain.
+    pub fn invert(&self, mapped: f32) -> f32 {
+        let t = fraction(mapped, self.range.0, self.range.1, self.clamp);
+        self.domain.0 + (self.domain.1 - self.domain.0) * t
+    }
+}
+
+impl Scale for LinearScale {
+    fn fraction(&self, value: f32) -> f32 {
+        fraction(value, self.domain.0, self.domain.1, self.clamp)
+    }
+
+    fn range(&self) -> (f32, f32) {
+        self.range
+    }
+}
+
+/// A [Scale] where equal ratios of value make equal steps in the range, for data spanning orders of magnitude.
+/// Only positive values can be mapped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LogScale {
+    /// The data values at the start and end of the scale.
+    pub domain: (f32, f32),
+    /// The values the start and end of the domain are mapped to.
+    pub range: (f32, f32),
+    /// Whether values outside the domain are held at its ends, rather than mapped beyond the range.
+    pub clamp: bool,
+}
+
+impl LogScale {
+    /// Create a new [LogScale], without clamping.
+    pub fn new(domain: (f32, f32), range: (f32, f32)) -> Self {
+        Self {
+            domain,
+            range,
+            clamp: false,
+        }
+    }
+
+    /// Set whether values outside the domain are held at its ends, consuming the parent.
+    pub fn with_clamp(mut self, clamp: bool) -> Self {
+        self.clamp = clamp;
+        self
+    }
+}
+
+impl Scale for LogScale {
+    fn fraction(&self, value: f32) -> f32 {
+        let log = |value: f32| value.max(f32::MIN_POSITIVE).ln();
+        fraction(
+            log(value),
+            log(self.domain.0),
+            log(self.domain.1),
+            self.clamp,
+        )
+    }
+
+    fn range(&self) -> (f32, f32) {
+        self.range
+    }
+}
+
+/// A [Scale] through the square root of the value, so that a circle's area, rather than its radius, grows with the value.
+///
+/// The domain starts at zero, so zero maps to the start of the range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SqrtScale {
+    /// The largest data value, mapped to the end of the range.
+    pub max: f32,
+    /// The values zero and `max` are mapped to.
+    pub range: (f32, f32),
+}
+
+impl SqrtScale {
+    /// Create a new [SqrtScale].
+    pub fn new(max: f32, range: (f32, f32)) -> Self {
+        Self { max, range }
+    }
+}
+
+impl Scale for SqrtScale {
+    fn fraction(&self, value: f32) -> f32 {
+        fraction(value.max(0.0).sqrt(), 0.0, self.max.sqrt(), false)
+    }
+
+    fn range(&self) -> (f32, f32) {
+        self.range
+    }
+}
+
+/// A mapping from categories to a list of outputs, such as a palette of colors or a set of marker shapes.
+///
+/// Categories are given outputs in order, starting again from the first when they run out.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OrdinalScale<T> {
+    /// The categories, in order.
+    pub domain: Vec<String>,
+    /// The outputs, in the same order as the categories.
+    pub range: Vec<T>,
+}
+
+impl<T: Clone> OrdinalScale<T> {
+    /// Create a new [OrdinalScale].
+    pub fn new<S: Into<String>, I: IntoIterator<Item = S>>(domain: I, range: Vec<T>) -> Self {
+        Self {
+            domain: domain.into_iter().map(Into::into).collect(),
+            range,
+        }
+    }
+
+    /// Create a new [OrdinalScale] over every distinct category in `values`, in the order they first appear.
+    pub fn from_values<S: AsRef<str>, I: IntoIterator<Item = S>>(values: I, range: Vec<T>) -> Self {
+        let mut domain: Vec<String> = Vec::new();
+        for value in values {
+            if !domain.iter().any(|category| category == value.as_ref()) {
+                domain.push(value.as_ref().to_string());
+            }
+        }
+        Self { domain, range }
+    }
+
+    /// The output for `category`, or `None` if it isn't in the domain or there are no outputs.
+    pub fn map(&self, category: &str) -> Option<T> {
+        let index = self.domain.iter().position(|c| c == category)?;
+        if self.range.is_empty() {
+            return None;
+        }
+        Some(self.range[index % self.range.len()].clone())
+    }
+}
+
+/// A mapping from categories to evenly spaced bands along a range, such as the bars of a bar chart.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BandScale {
+    /// The categories, in order along the range.
+    pub domain: Vec<String>,
+    /// The span the bands and the padding around them fill.
+    pub range: (f32, f32),
+    /// The space between neighbouring bands, as a share of the step from one band to the next, from 0 to 1.
+    pub padding_inner: f32,
+    /// The space before the first band and after the last, in steps.
+    pub padding_outer: f32,
+}
+
+impl BandScale {
+    /// Create a new [BandScale] without padding.
+    pub fn new<S: Into<String>, I: IntoIterator<Item = S>>(domain: I, range: (f32, f32)) -> Self {
+        Self {
+            domain: domain.into_iter().map(Into::into).collect(),
+            range,
+            padding_inner: 0.0,
+            padding_outer: 0.0,
+        }
+    }
+
+    /// Set the space between bands and at either end, consuming the parent.
+    pub fn with_padding(mut self, inner: f32, outer: f32) -> Self {
+        self.padding_inner = inner;
+        self.padding_outer = outer;
+        self
+    }
+
+    /// The distance from the start of one band to the start of the next.
+    pub fn step(&self) -> f32 {
+        let count = self.domain.len() as f32;
+        let slots = (count - self.padding_inner + self.padding_outer * 2.0).max(1.0);
+        (self.range.1 - self.range.0) / slots
+    }
+
+    /// The width of each band.
+    pub fn bandwidth(&self) -> f32 {
+        self.step() * (1.0 - self.padding_inner)
+    }
+
+    /// Where the band for `category` starts, or `None` if it isn't in the domain.
+    pub fn map(&self, category: &str) -> Option<f32> {
+        let index = self.domain.iter().position(|c| c == category)?;
+        Some(self.range.0 + self.step() * (self.padding_outer + index as f32))
+    }
+
+    /// The middle of the band for `category`, or `None` if it isn't in the domain.
+    pub fn center(&self, category: &str) -> Option<f32> {
+        self.map(category)
+            .map(|start| start + self.bandwidth() / 2.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that each scale maps its domain onto its range, and categories onto outputs and bands.
+    #[test]
+    fn map_through_scales() {
+        let linear = LinearScale::new((0.0, 10.0), (100.0, 200.0));
+        assert_eq!(linear.map(2.5), 125.0);
+        assert_eq!(linear.map(20.0), 300.0);
+        assert_eq!(linear.with_clamp(true).map(20.0), 200.0);
+        assert_eq!(linear.invert(150.0), 5.0);
+
+        let log = LogScale::new((1.0, 1000.0), (0.0, 3.0));
+        assert!((log.map(100.0) - 2.0).abs() < 1e-5);
+
+        // A population four times as large gets a circle twice as wide.
+        let radius = SqrtScale::new(400.0, (0.0, 20.0));
+        assert_eq!(radius.map(100.0), 10.0);
+        assert_eq!(radius.map(400.0), 20.0);
+
+        let gradient = Gradient::new(Color::black(), Color::white());
+        assert_eq!(linear.color(10.0, &gradient), Color::white());
+        assert_eq!(linear.color(-5.0, &gradient), Color::black());
+
+        let palette = vec![Color::black(), Color::white()];
+        let regions = OrdinalScale::from_values(["north", "south", "north", "east"], palette);
+        assert_eq!(regions.domain, ["north", "south", "east"]);
+        assert_eq!(regions.map("east"), Some(Color::black()));
+        assert_eq!(regions.map("south"), Some(Color::white()));
+        assert_eq!(regions.map("west"), None);
+
+        let bands = BandScale::new(["a", "b", "c", "d"], (0.0, 100.0)).with_padding(0.2, 0.1);
+        assert!((bands.step() - 25.0).abs() < 1e-4);
+        assert!((bands.bandwidth() - 20.0).abs() < 1e-4);
+        assert!((bands.map("a").unwrap() - 2.5).abs() < 1e-4);
+        assert!((bands.center("d").unwrap() - 87.5).abs() < 1e-4);
+        assert_eq!(bands.map("e"), None);
+    }
+}
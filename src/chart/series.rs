@@ -0,0 +1,144 @@
+use glam::Vec2;
+
+use super::{Axes, Bin};
+use crate::{Canvas, Color, Stroke};
+
+/// One bar of a bar series, in data values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bar {
+    /// Where the bar starts along the x axis.
+    pub start: f32,
+    /// Where the bar ends along the x axis.
+    pub end: f32,
+    /// The height of the bar, up from zero.
+    pub value: f32,
+}
+
+impl Bar {
+    /// Create a new [Bar].
+    pub fn new(start: f32, end: f32, value: f32) -> Self {
+        Self { start, end, value }
+    }
+}
+
+impl Bin {
+    /// The bar showing this bin, as tall as its count, or as its density if `density` is set.
+    pub fn bar(&self, density: bool) -> Bar {
+        Bar::new(
+            self.start,
+            self.end,
+            if density {
+                self.density
+            } else {
+                self.count as f32
+            },
+        )
+    }
+}
+
+/// Where zero on the y axis is on the canvas, kept within the plot area, for bars and areas to rise from.
+fn baseline(axes: &Axes) -> f32 {
+    let (low, high) = (axes.min.y.min(axes.max.y), axes.min.y.max(axes.max.y));
+    axes.map_y(0.0).clamp(low, high)
+}
+
+impl Canvas {
+    /// Draw a series of bars in World Space, mapped through `axes`, each rising from zero on the y axis.
+    pub fn draw_bars(
+        &mut self,
+        axes: &Axes,
+        bars: &[Bar],
+        stroke: Option<Stroke>,
+        fill: Option<Color>,
+    ) {
+        let base = baseline(axes);
+        for bar in bars {
+            let (left, right) = (axes.map_x(bar.start), axes.map_x(bar.end));
+            let top = axes.map_y(bar.value);
+            self.draw_shape_absolute(
+                vec![
+                    Vec2::new(left, base),
+                    Vec2::new(right, base),
+                    Vec2::new(right, top),
+                    Vec2::new(left, top),
+                    Vec2::new(left, base),
+                ],
+                stroke,
+                fill,
+            );
+        }
+    }
+
+    /// Draw a curve through data `points` in World Space, mapped through `axes`, such as a [Kde] curve.
+    ///
+    /// With a `fill`, the area between the curve and zero on the y axis is filled first.
+    /// With a `stroke`, the curve is drawn on top.
+    ///
+    /// [Kde]: super::Kde
+    pub fn draw_area(
+        &mut self,
+        axes: &Axes,
+        points: &[Vec2],
+        stroke: Option<Stroke>,
+        fill: Option<Color>,
+    ) {
+        let (Some(first), Some(last)) = (points.first(), points.last()) else {
+            return;
+        };
+        let curve: Vec<Vec2> = points.iter().map(|&point| axes.map(point)).collect();
+
+        if fill.is_some() {
+            let base = baseline(axes);
+            let mut area = curve.clone();
+            area.push(Vec2::new(axes.map_x(last.x), base));
+            area.push(Vec2::new(axes.map_x(first.x), base));
+            area.push(curve[0]);
+            self.draw_shape_absolute(area, None, fill);
+        }
+        if let Some(stroke) = stroke {
+            self.draw_polyline_absolute(curve, stroke);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        chart::{histogram, Axis, BinRule, Kde},
+        LineEnd,
+    };
+
+    /// Verify that histogram bars rise from the x axis to their counts, and density areas close along the baseline.
+    #[test]
+    fn draw_histogram() {
+        let values = [1.0, 2.0, 2.5, 3.0, 3.0, 3.5, 4.0, 6.0];
+        let bins = histogram(&values, BinRule::Width(1.0));
+        let bars: Vec<Bar> = bins.iter().map(|bin| bin.bar(false)).collect();
+        assert_eq!(bars[2], Bar::new(3.0, 4.0, 3.0));
+
+        let axes = Axes::new(
+            Vec2::ZERO,
+            Vec2::new(100.0, 100.0),
+            Axis::new((0.0, 10.0)),
+            Axis::new((0.0, 5.0)),
+        );
+        let mut canvas = Canvas::new(1);
+        canvas.draw_bars(&axes, &bars, None, Some(Color::black()));
+        assert_eq!(canvas.as_raw().len(), bars.len());
+        let points = &canvas.as_raw()[2].points;
+        assert!(points[0].abs_diff_eq(Vec2::new(30.0, 0.0), 1e-4));
+        assert!(points[2].abs_diff_eq(Vec2::new(40.0, 60.0), 1e-4));
+
+        let kde = Kde::new(&values);
+        let (from, to) = kde.extent();
+        let curve = kde.curve(from, to, 50);
+        let stroke = Stroke::new(Color::black(), 0.5, LineEnd::Butt);
+        canvas.draw_area(&axes, &curve, Some(stroke), Some(Color::white()));
+        let raw = canvas.as_raw();
+        assert_eq!(raw.len(), bars.len() + 2);
+        assert!(raw[bars.len()].points.iter().all(|point| point.y >= 0.0));
+        assert!(raw[bars.len() + 1].fill.is_none());
+    }
+}
@@ -0,0 +1,258 @@
+use std::f32::consts::TAU;
+
+use glam::Vec2;
+
+use super::nice_step;
+
+/// The value below which a share `q` (from 0 to 1) of `sorted` falls, interpolating between neighbouring values.
+///
+/// `sorted` must be in ascending order. Returns `NaN` when it is empty.
+pub fn quantile(sorted: &[f32], q: f32) -> f32 {
+    if sorted.is_empty() {
+        return f32::NAN;
+    }
+    let position = q.clamp(0.0, 1.0) * (sorted.len() - 1) as f32;
+    let below = position.floor() as usize;
+    let above = (below + 1).min(sorted.len() - 1);
+    sorted[below] + (sorted[above] - sorted[below]) * (position - below as f32)
+}
+
+/// The mean and standard deviation of `values`.
+fn mean_and_deviation(values: &[f32]) -> (f32, f32) {
+    let count = values.len().max(1) as f32;
+    let mean = values.iter().sum::<f32>() / count;
+    let variance = values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f32>()
+        / (values.len().max(2) - 1) as f32;
+    (mean, variance.sqrt())
+}
+
+/// A copy of `values` in ascending order, without any `NaN`s or infinities.
+fn sorted(values: &[f32]) -> Vec<f32> {
+    let mut sorted: Vec<f32> = values.iter().copied().filter(|v| v.is_finite()).collect();
+    sorted.sort_by(f32::total_cmp);
+    sorted
+}
+
+/// How [histogram] picks the width of its bins.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BinRule {
+    /// Sturges' rule, `log2(n) + 1` bins. Best for small, roughly normal samples.
+    Sturges,
+    /// Scott's rule, from the standard deviation. Best for roughly normal samples.
+    Scott,
+    /// The Freedman-Diaconis rule, from the interquartile range. Holds up against outliers.
+    #[default]
+    FreedmanDiaconis,
+    /// This many bins, evenly spanning the values.
+    Count(usize),
+    /// Bins of this width, starting at a multiple of it.
+    Width(f32),
+}
+
+/// One bin of a [histogram].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bin {
+    /// The lowest value counted in the bin.
+    pub start: f32,
+    /// The value where the next bin starts. Only the last bin counts values equal to it.
+    pub end: f32,
+    /// How many values fall in the bin.
+    pub count: usize,
+    /// The count as a probability density, so the areas of all the bins add up to one.
+    pub density: f32,
+}
+
+/// The most bins [histogram] makes.
+pub const MAX_BINS: usize = 10_000;
+
+/// Count `values` into evenly spaced bins, with the width picked by `rule`.
+///
+/// Rules that pick a width round it to 1, 2 or 5 times a power of ten, and the bins start at a multiple of it.
+/// Makes at most [MAX_BINS] bins, widening them if needed. `NaN`s and infinities are left out.
+pub fn histogram(values: &[f32], rule: BinRule) -> Vec<Bin> {
+    let sorted = sorted(values);
+    let (Some(&low), Some(&high)) = (sorted.first(), sorted.last()) else {
+        return Vec::new();
+    };
+    let count = sorted.len() as f32;
+    let span = high - low;
+
+    let width = match rule {
+        BinRule::Count(bins) => {
+            if span > 0.0 {
+                span / bins.clamp(1, MAX_BINS) as f32
+            } else {
+                1.0
+            }
+        }
+        BinRule::Width(width) => width,
+        BinRule::Sturges => nice_step(span / (count.log2().ceil() + 1.0)),
+        BinRule::Scott => nice_step(3.49 * mean_and_deviation(&sorted).1 / count.cbrt()),
+        BinRule::FreedmanDiaconis => {
+            let spread = quantile(&sorted, 0.75) - quantile(&sorted, 0.25);
+            nice_step(2.0 * spread / count.cbrt())
+        }
+    };
+    // Fall back to a single bin when the rule can't pick a width, such as for identical values.
+    let width = if width.is_finite() && width > 0.0 {
+        // Rounding the start down to a multiple of the width can add one more bin.
+        width.max(span / (MAX_BINS - 1) as f32)
+    } else {
+        span.max(1.0)
+    };
+
+    let (start, bins) = match rule {
+        BinRule::Count(bins) if span > 0.0 => (low, bins.clamp(1, MAX_BINS)),
+        _ => {
+            let start = (low / width).floor() * width;
+            // A value on the end of the last bin is counted in it, rather than starting a bin of its own.
+            let bins = ((high - start) / width).ceil().max(1.0) as usize;
+            (start, bins)
+        }
+    };
+
+    let mut counts = vec![0; bins];
+    for value in sorted {
+        let index = (((value - start) / width).floor().max(0.0) as usize).min(bins - 1);
+        counts[index] += 1;
+    }
+
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(i, bin_count)| Bin {
+            start: start + width * i as f32,
+            end: start + width * (i + 1) as f32,
+            count: bin_count,
+            density: bin_count as f32 / (count * width),
+        })
+        .collect()
+}
+
+/// A Gaussian kernel density estimate: a smooth curve of how densely packed a sample's values are.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kde {
+    /// The sample, in ascending order.
+    pub values: Vec<f32>,
+    /// The standard deviation of the kernel around each value. Wider gives a smoother curve.
+    pub bandwidth: f32,
+}
+
+impl Kde {
+    /// Create a new [Kde] of `values`, with the bandwidth picked by Silverman's rule of thumb. `NaN`s and infinities are left out.
+    pub fn new(values: &[f32]) -> Self {
+        let values = sorted(values);
+        let deviation = mean_and_deviation(&values).1;
+        let spread = (quantile(&values, 0.75) - quantile(&values, 0.25)) / 1.34;
+        let scale = if spread > 0.0 {
+            deviation.min(spread)
+        } else {
+            deviation
+        };
+        let bandwidth = 0.9 * scale * (values.len().max(1) as f32).powf(-0.2);
+
+        Self {
+            values,
+            bandwidth: if bandwidth > 0.0 { bandwidth } else { 1.0 },
+        }
+    }
+
+    /// Set the standard deviation of the kernel, consuming the parent.
+    pub fn with_bandwidth(mut self, bandwidth: f32) -> Self {
+        self.bandwidth = bandwidth;
+        self
+    }
+
+    /// The estimated probability density at `x`.
+    pub fn density(&self, x: f32) -> f32 {
+        if self.values.is_empty() {
+            return 0.0;
+        }
+        // Kernels more than a few bandwidths away add nothing worth counting.
+        let reach = self.bandwidth * 4.0;
+        let from = self.values.partition_point(|&value| value < x - reach);
+        let to = self.values.partition_point(|&value| value <= x + reach);
+        let sum: f32 = self.values[from..to]
+            .iter()
+            .map(|value| (-0.5 * ((x - value) / self.bandwidth).powi(2)).exp())
+            .sum();
+        sum / (self.values.len() as f32 * self.bandwidth * TAU.sqrt())
+    }
+
+    /// The density at `samples` evenly spaced values from `from` to `to`, as points of `x` and density.
+    pub fn curve(&self, from: f32, to: f32, samples: usize) -> Vec<Vec2> {
+        let samples = samples.max(2);
+        (0..samples)
+            .map(|i| {
+                let x = from + (to - from) * i as f32 / (samples - 1) as f32;
+                Vec2::new(x, self.density(x))
+            })
+            .collect()
+    }
+
+    /// The range of values the curve is worth drawing over: the sample, plus three bandwidths either side.
+    pub fn extent(&self) -> (f32, f32) {
+        match (self.values.first(), self.values.last()) {
+            (Some(low), Some(high)) => (low - self.bandwidth * 3.0, high + self.bandwidth * 3.0),
+            _ => (0.0, 0.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that bins cover every value with round edges, and the density estimate integrates to about one.
+    #[test]
+    fn bin_and_estimate() {
+        assert_eq!(quantile(&[1.0, 2.0, 3.0, 4.0], 0.5), 2.5);
+
+        let values: Vec<f32> = (0..200).map(|i| ((i * 37) % 100) as f32 / 10.0).collect();
+        for rule in [
+            BinRule::Sturges,
+            BinRule::Scott,
+            BinRule::FreedmanDiaconis,
+            BinRule::Count(7),
+            BinRule::Width(0.5),
+        ] {
+            let bins = histogram(&values, rule);
+            assert_eq!(bins.iter().map(|bin| bin.count).sum::<usize>(), 200);
+            assert!(bins[0].start <= 0.0 && bins[bins.len() - 1].end >= 9.9);
+            let area: f32 = bins
+                .iter()
+                .map(|bin| bin.density * (bin.end - bin.start))
+                .sum();
+            assert!((area - 1.0).abs() < 1e-4);
+        }
+        assert_eq!(histogram(&values, BinRule::Count(7)).len(), 7);
+        assert_eq!(histogram(&values, BinRule::Width(0.5)).len(), 20);
+        assert_eq!(histogram(&[3.0, 3.0], BinRule::Scott).len(), 1);
+
+        let kde = Kde::new(&values);
+        let (from, to) = kde.extent();
+        let curve = kde.curve(from, to, 500);
+        let step = (to - from) / 499.0;
+        let area: f32 = curve.iter().map(|point| point.y * step).sum();
+        assert!((area - 1.0).abs() < 0.01);
+        assert!(kde.density(5.0) > kde.density(12.0));
+    }
+
+    /// Verify that tiny widths and huge counts are capped, and infinities are left out.
+    #[test]
+    fn bounded_bins() {
+        let values = [0.0, 1.0, f32::INFINITY, f32::NEG_INFINITY, f32::NAN];
+        for rule in [BinRule::Width(1e-9), BinRule::Count(usize::MAX)] {
+            let bins = histogram(&values, rule);
+            assert!(!bins.is_empty() && bins.len() <= MAX_BINS);
+            assert_eq!(bins.iter().map(|bin| bin.count).sum::<usize>(), 2);
+            assert!(bins.iter().all(|bin| bin.density.is_finite()));
+        }
+    }
+}
@@ -0,0 +1,236 @@
+const MINUTE: f64 = 60.0;
+const HOUR: f64 = 60.0 * MINUTE;
+const DAY: f64 = 24.0 * HOUR;
+
+/// A spacing between time ticks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Interval {
+    /// A fixed number of seconds, aligned to multiples of it since the epoch.
+    Seconds(f64),
+    /// Whole weeks, starting on Mondays.
+    Weeks,
+    /// Calendar months, starting on months that are a multiple of this many into the year.
+    Months(i64),
+    /// Calendar years, starting on years that are a multiple of this many.
+    Years(i64),
+}
+
+impl Interval {
+    /// About how many seconds the interval lasts, for picking one.
+    fn length(&self) -> f64 {
+        match self {
+            Interval::Seconds(seconds) => *seconds,
+            Interval::Weeks => DAY * 7.0,
+            Interval::Months(months) => DAY * 30.44 * *months as f64,
+            Interval::Years(years) => DAY * 365.25 * *years as f64,
+        }
+    }
+}
+
+/// The intervals tried, shortest first.
+const INTERVALS: [Interval; 24] = [
+    Interval::Seconds(1.0),
+    Interval::Seconds(2.0),
+    Interval::Seconds(5.0),
+    Interval::Seconds(10.0),
+    Interval::Seconds(15.0),
+    Interval::Seconds(30.0),
+    Interval::Seconds(MINUTE),
+    Interval::Seconds(MINUTE * 2.0),
+    Interval::Seconds(MINUTE * 5.0),
+    Interval::Seconds(MINUTE * 10.0),
+    Interval::Seconds(MINUTE * 15.0),
+    Interval::Seconds(MINUTE * 30.0),
+    Interval::Seconds(HOUR),
+    Interval::Seconds(HOUR * 2.0),
+    Interval::Seconds(HOUR * 3.0),
+    Interval::Seconds(HOUR * 6.0),
+    Interval::Seconds(HOUR * 12.0),
+    Interval::Seconds(DAY),
+    Interval::Seconds(DAY * 2.0),
+    Interval::Weeks,
+    Interval::Months(1),
+    Interval::Months(2),
+    Interval::Months(3),
+    Interval::Months(6),
+];
+
+/// Pick about `count` ticks on round times between `start` and `end`, in seconds since the Unix epoch,
+/// each with a label as precise as the spacing calls for.
+///
+/// Times are in UTC. Ticks fall on whole seconds, minutes, hours, days, Mondays, months or years.
+pub(crate) fn time_ticks(start: f64, end: f64, count: usize) -> Vec<(f64, String)> {
+    let (start, end) = (start.min(end), start.max(end));
+    if !(end - start).is_finite() {
+        return Vec::new();
+    }
+
+    let wanted = (end - start) / count.max(1) as f64;
+    let interval = INTERVALS
+        .iter()
+        .copied()
+        .find(|interval| interval.length() >= wanted)
+        .unwrap_or_else(|| {
+            let years = (wanted / Interval::Years(1).length()).max(1.0);
+            Interval::Years(super::nice_step(years as f32).round().max(1.0) as i64)
+        });
+
+    let times: Vec<f64> = match interval {
+        Interval::Seconds(step) => {
+            let first = (start / step).ceil() as i64;
+            let last = (end / step).floor() as i64;
+            (first..=last).map(|i| i as f64 * step).collect()
+        }
+        Interval::Weeks => {
+            // The epoch fell on a Thursday, so Mondays are four days after a multiple of seven.
+            let monday = DAY * 4.0;
+            let step = DAY * 7.0;
+            let first = ((start - monday) / step).ceil() as i64;
+            let last = ((end - monday) / step).floor() as i64;
+            (first..=last).map(|i| monday + i as f64 * step).collect()
+        }
+        Interval::Months(step) | Interval::Years(step) => {
+            let step = if let Interval::Years(_) = interval {
+                step * 12
+            } else {
+                step
+            };
+            let (year, month, _) = civil_from_days((start / DAY).floor() as i64);
+            let mut index = (year * 12 + month - 1).div_euclid(step) * step;
+            let mut times = Vec::new();
+            loop {
+                let time =
+                    days_from_civil(index.div_euclid(12), index.rem_euclid(12) + 1, 1) as f64 * DAY;
+                if time > end {
+                    break times;
+                }
+                if time >= start {
+                    times.push(time);
+                }
+                index += step;
+            }
+        }
+    };
+
+    times
+        .into_iter()
+        .map(|time| (time, format_time(time, interval)))
+        .collect()
+}
+
+/// Write `time` as precisely as ticks `interval` apart call for.
+fn format_time(time: f64, interval: Interval) -> String {
+    // Round to the second before splitting off the day, so a time just before midnight carries into the next day.
+    let time = time.round() as i64;
+    let (days, seconds) = (time.div_euclid(DAY as i64), time.rem_euclid(DAY as i64));
+    let (year, month, day) = civil_from_days(days);
+    let (hour, minute, second) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    match interval {
+        Interval::Years(_) => format!("{}", year),
+        Interval::Months(_) => format!("{}-{:02}", year, month),
+        Interval::Seconds(step) if step < MINUTE => {
+            format!("{:02}:{:02}:{:02}", hour, minute, second)
+        }
+        // Ticks within a day show the date at midnight, so it's clear when the day changes.
+        Interval::Seconds(step) if step < DAY && seconds != 0 => {
+            format!("{:02}:{:02}", hour, minute)
+        }
+        _ => format!("{}-{:02}-{:02}", year, month, day),
+    }
+}
+
+/// The number of days since the Unix epoch of a date in the proleptic Gregorian calendar.
+///
+/// From Howard Hinnant's `days_from_civil`.
+pub(crate) fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// The year, month and day of a number of days since the Unix epoch, in the proleptic Gregorian calendar.
+///
+/// From Howard Hinnant's `civil_from_days`.
+pub(crate) fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that dates round-trip, and time ticks fall on round times with labels to match their spacing.
+    #[test]
+    fn ticks_on_round_times() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2024, 3, 1), 19783);
+        assert_eq!(civil_from_days(19783), (2024, 3, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+
+        let day = |year, month, date| days_from_civil(year, month, date) as f64 * DAY;
+        let labels = |start, end, count| -> Vec<String> {
+            time_ticks(start, end, count)
+                .into_iter()
+                .map(|(_, label)| label)
+                .collect()
+        };
+
+        assert_eq!(
+            labels(day(2023, 11, 20), day(2024, 4, 10), 5),
+            ["2023-12", "2024-01", "2024-02", "2024-03", "2024-04"]
+        );
+        assert_eq!(
+            labels(
+                day(2024, 3, 4) + HOUR * 15.5,
+                day(2024, 3, 5) + HOUR * 10.0,
+                4
+            ),
+            ["18:00", "2024-03-05", "06:00"]
+        );
+        assert_eq!(
+            labels(day(1990, 6, 1), day(2024, 1, 1), 4),
+            ["2000", "2010", "2020"]
+        );
+        assert_eq!(
+            labels(day(2024, 3, 1), day(2024, 3, 29), 4),
+            ["2024-03-04", "2024-03-11", "2024-03-18", "2024-03-25"]
+        );
+        assert_eq!(labels(30.0, 50.0, 2), ["00:00:30", "00:00:40", "00:00:50"]);
+    }
+
+    /// Verify that a time rounding up to midnight is written as the next day, not as 24:00.
+    #[test]
+    fn round_into_next_day() {
+        let midnight = days_from_civil(2024, 3, 5) as f64 * DAY;
+        assert_eq!(
+            format_time(midnight - 0.2, Interval::Seconds(HOUR)),
+            "2024-03-05"
+        );
+        assert_eq!(
+            format_time(midnight - 0.2, Interval::Seconds(1.0)),
+            "00:00:00"
+        );
+        assert_eq!(
+            format_time(midnight - 0.6, Interval::Seconds(1.0)),
+            "23:59:59"
+        );
+    }
+}
@@ -0,0 +1,782 @@
+use glam::Vec2;
+
+use crate::{Color, Error, Shape};
+
+/// How much of a [QrCode] can be damaged or covered while it still scans. More error correction makes the code larger.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ErrorCorrection {
+    /// About 7% of the code can be recovered.
+    Low,
+    /// About 15% of the code can be recovered.
+    #[default]
+    Medium,
+    /// About 25% of the code can be recovered.
+    Quartile,
+    /// About 30% of the code can be recovered, enough to put a small logo over the middle.
+    High,
+}
+
+impl ErrorCorrection {
+    /// The two bits that stand for the level in the format information.
+    fn format_bits(self) -> u32 {
+        match self {
+            ErrorCorrection::Low => 1,
+            ErrorCorrection::Medium => 0,
+            ErrorCorrection::Quartile => 3,
+            ErrorCorrection::High => 2,
+        }
+    }
+
+    /// The row of the block tables for the level.
+    fn index(self) -> usize {
+        match self {
+            ErrorCorrection::Low => 0,
+            ErrorCorrection::Medium => 1,
+            ErrorCorrection::Quartile => 2,
+            ErrorCorrection::High => 3,
+        }
+    }
+}
+
+/// Error correction codewords in each block, by level and version.
+const ECC_CODEWORDS_PER_BLOCK: [[u8; 41]; 4] = [
+    [
+        0, 7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28, 28, 28,
+        30, 30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28,
+        28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28,
+    ],
+    [
+        0, 13, 22, 18, 26, 18, 24, 18, 22, 20, 24, 28, 26, 24, 20, 30, 24, 28, 28, 26, 30, 28, 30,
+        30, 30, 30, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        0, 17, 28, 22, 16, 22, 28, 26, 26, 24, 28, 24, 28, 22, 24, 24, 30, 28, 28, 26, 28, 30, 24,
+        30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+];
+
+/// The number of error correction blocks, by level and version.
+const ERROR_CORRECTION_BLOCKS: [[u8; 41]; 4] = [
+    [
+        0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12, 12, 12, 13,
+        14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25,
+    ],
+    [
+        0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21,
+        23, 25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49,
+    ],
+    [
+        0, 1, 1, 2, 2, 4, 4, 6, 6, 8, 8, 8, 10, 12, 16, 12, 17, 16, 18, 21, 20, 23, 23, 25, 27, 29,
+        34, 34, 35, 38, 40, 43, 45, 48, 51, 53, 56, 59, 62, 65, 68,
+    ],
+    [
+        0, 1, 1, 2, 4, 4, 4, 5, 6, 8, 8, 11, 11, 16, 16, 18, 16, 19, 21, 25, 25, 25, 34, 30, 32,
+        35, 37, 40, 42, 45, 48, 51, 54, 57, 60, 63, 66, 70, 74, 77, 81,
+    ],
+];
+
+/// A QR code, as a square grid of dark and light modules.
+///
+/// Data is stored as bytes, in the smallest version (size) that fits, with whichever of the eight masks scores best.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QrCode {
+    version: usize,
+    size: usize,
+    modules: Vec<bool>,
+}
+
+impl QrCode {
+    /// Encode `data`, such as the bytes of a URL, at the given level of error correction.
+    ///
+    /// Returns [Error::DataTooLong] if it doesn't fit in the largest QR code.
+    pub fn new(data: impl AsRef<[u8]>, error_correction: ErrorCorrection) -> Result<Self, Error> {
+        let data = data.as_ref();
+        let level = error_correction.index();
+
+        let (version, capacity) = (1..=40)
+            .map(|version| (version, data_codewords(version, error_correction) * 8))
+            .find(|&(version, capacity)| {
+                let count_bits = if version <= 9 { 8 } else { 16 };
+                4 + count_bits + data.len() * 8 <= capacity
+            })
+            .ok_or(Error::DataTooLong)?;
+
+        // Byte mode, then the length and the data.
+        let mut bits = Vec::with_capacity(capacity);
+        push_bits(&mut bits, 0b0100, 4);
+        push_bits(
+            &mut bits,
+            data.len() as u32,
+            if version <= 9 { 8 } else { 16 },
+        );
+        for &byte in data {
+            push_bits(&mut bits, byte as u32, 8);
+        }
+
+        // End with a terminator, then pad to the capacity.
+        let terminator = (capacity - bits.len()).min(4);
+        push_bits(&mut bits, 0, terminator);
+        let partial = (8 - bits.len() % 8) % 8;
+        push_bits(&mut bits, 0, partial);
+        for pad in [0xEC, 0x11].into_iter().cycle() {
+            if bits.len() >= capacity {
+                break;
+            }
+            push_bits(&mut bits, pad, 8);
+        }
+
+        let codewords: Vec<u8> = bits
+            .chunks(8)
+            .map(|byte| byte.iter().fold(0, |sum, &bit| sum << 1 | bit as u8))
+            .collect();
+
+        // Split into blocks, add error correction to each, then interleave them.
+        let blocks = ERROR_CORRECTION_BLOCKS[level][version] as usize;
+        let ecc_length = ECC_CODEWORDS_PER_BLOCK[level][version] as usize;
+        let raw = raw_data_modules(version) / 8;
+        let short_blocks = blocks - raw % blocks;
+        let short_length = raw / blocks;
+        let divisor = reed_solomon_divisor(ecc_length);
+
+        let mut split = Vec::with_capacity(blocks);
+        let mut start = 0;
+        for block in 0..blocks {
+            let length = short_length - ecc_length + usize::from(block >= short_blocks);
+            let data = &codewords[start..start + length];
+            start += length;
+            split.push((data, reed_solomon_remainder(data, &divisor)));
+        }
+
+        let mut interleaved = Vec::with_capacity(raw);
+        for i in 0..short_length + 1 - ecc_length {
+            interleaved.extend(split.iter().filter_map(|(data, _)| data.get(i)));
+        }
+        for i in 0..ecc_length {
+            interleaved.extend(split.iter().map(|(_, ecc)| ecc[i]));
+        }
+
+        let mut grid = Grid::new(version);
+        grid.draw_function_patterns(error_correction);
+        grid.draw_codewords(&interleaved);
+
+        let mask = (0..8)
+            .min_by_key(|&mask| {
+                grid.apply_mask(mask);
+                grid.draw_format(error_correction, mask);
+                let penalty = grid.penalty();
+                grid.apply_mask(mask);
+                penalty
+            })
+            .unwrap_or(0);
+        grid.apply_mask(mask);
+        grid.draw_format(error_correction, mask);
+
+        Ok(Self {
+            version,
+            size: grid.size,
+            modules: grid.modules,
+        })
+    }
+
+    /// The version of the code, from 1 to 40, which sets its size.
+    pub fn version(&self) -> usize {
+        self.version
+    }
+
+    /// The number of modules along each side.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Checks if the module in column `x` and row `y`, counting from the top left, is dark. Modules outside the code are light.
+    pub fn module(&self, x: usize, y: usize) -> bool {
+        x < self.size && y < self.size && self.modules[y * self.size + x]
+    }
+
+    /// The dark modules as filled rectangles, with each run along a row merged into one.
+    ///
+    /// Each module is `module_size` wide, and the bottom left corner of the code is at `origin`.
+    /// Scanners need a light margin of four modules around the code.
+    pub fn shapes(&self, origin: Vec2, module_size: f32, color: Color) -> Vec<Shape> {
+        let mut shapes = Vec::new();
+        for y in 0..self.size {
+            let row = &self.modules[y * self.size..(y + 1) * self.size];
+            let bottom = origin.y + (self.size - 1 - y) as f32 * module_size;
+            for (start, end) in dark_runs(row) {
+                shapes.push(rectangle(
+                    Vec2::new(origin.x + start as f32 * module_size, bottom),
+                    Vec2::new(origin.x + end as f32 * module_size, bottom + module_size),
+                    color,
+                ));
+            }
+        }
+        shapes
+    }
+}
+
+/// A QR code being built, with a note of which modules belong to the fixed patterns rather than the data.
+struct Grid {
+    version: usize,
+    size: usize,
+    modules: Vec<bool>,
+    function: Vec<bool>,
+}
+
+impl Grid {
+    /// An empty grid for a version.
+    fn new(version: usize) -> Self {
+        let size = version * 4 + 17;
+        Self {
+            version,
+            size,
+            modules: vec![false; size * size],
+            function: vec![false; size * size],
+        }
+    }
+
+    /// Set a module that belongs to a fixed pattern.
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y * self.size + x] = dark;
+        self.function[y * self.size + x] = true;
+    }
+
+    /// Draw the timing, finder and alignment patterns, and the version and format information.
+    fn draw_function_patterns(&mut self, error_correction: ErrorCorrection) {
+        for i in 0..self.size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+
+        let far = self.size - 4;
+        for (x, y) in [(3, 3), (far, 3), (3, far)] {
+            for dy in -4..=4isize {
+                for dx in -4..=4isize {
+                    let (px, py) = (x as isize + dx, y as isize + dy);
+                    if (0..self.size as isize).contains(&px)
+                        && (0..self.size as isize).contains(&py)
+                    {
+                        let distance = dx.abs().max(dy.abs());
+                        self.set_function(px as usize, py as usize, distance != 2 && distance != 4);
+                    }
+                }
+            }
+        }
+
+        let positions = alignment_positions(self.version);
+        let last = positions.len().saturating_sub(1);
+        for (i, &x) in positions.iter().enumerate() {
+            for (j, &y) in positions.iter().enumerate() {
+                // Skip the three that would overlap the finder patterns.
+                if (i == 0 && (j == 0 || j == last)) || (i == last && j == 0) {
+                    continue;
+                }
+                for dy in -2..=2isize {
+                    for dx in -2..=2isize {
+                        let dark = dx.abs().max(dy.abs()) != 1;
+                        self.set_function(
+                            (x as isize + dx) as usize,
+                            (y as isize + dy) as usize,
+                            dark,
+                        );
+                    }
+                }
+            }
+        }
+
+        // Reserve the format information, which is drawn for real once the mask is chosen.
+        self.draw_format(error_correction, 0);
+
+        if self.version >= 7 {
+            let bits = version_bits(self.version);
+            for i in 0..18 {
+                let dark = bits >> i & 1 != 0;
+                let (a, b) = (self.size - 11 + i % 3, i / 3);
+                self.set_function(a, b, dark);
+                self.set_function(b, a, dark);
+            }
+        }
+    }
+
+    /// Draw both copies of the format information, which tells scanners the error correction level and mask.
+    fn draw_format(&mut self, error_correction: ErrorCorrection, mask: u32) {
+        let bits = format_bits(error_correction, mask);
+        let bit = |i: usize| bits >> i & 1 != 0;
+
+        for i in 0..6 {
+            self.set_function(8, i, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i));
+        }
+
+        for i in 0..8 {
+            self.set_function(self.size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, self.size - 15 + i, bit(i));
+        }
+        self.set_function(8, self.size - 8, true);
+    }
+
+    /// Fill the modules that aren't part of a fixed pattern with `codewords`, in pairs of columns zigzagging up and down from the right.
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        let mut index = 0;
+        let mut right = self.size - 1;
+        loop {
+            // Skip the vertical timing pattern.
+            if right == 6 {
+                right = 5;
+            }
+            for vertical in 0..self.size {
+                for x in [right, right - 1] {
+                    let upward = (right + 1) & 2 == 0;
+                    let y = if upward {
+                        self.size - 1 - vertical
+                    } else {
+                        vertical
+                    };
+                    if !self.function[y * self.size + x] && index < codewords.len() * 8 {
+                        self.modules[y * self.size + x] =
+                            codewords[index / 8] >> (7 - index % 8) & 1 != 0;
+                        index += 1;
+                    }
+                }
+            }
+            if right < 3 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    /// Flip the data modules chosen by one of the eight masks. Applying a mask twice undoes it.
+    fn apply_mask(&mut self, mask: u32) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let flip = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                let index = y * self.size + x;
+                if flip && !self.function[index] {
+                    self.modules[index] = !self.modules[index];
+                }
+            }
+        }
+    }
+
+    /// How hard the code is to scan: long runs, blocks of one color, patterns that look like finders, and unbalanced color all count against it.
+    fn penalty(&self) -> u32 {
+        let size = self.size;
+        let module = |x: usize, y: usize| self.modules[y * size + x];
+
+        let mut penalty = 0;
+        for i in 0..size {
+            let row: Vec<bool> = (0..size).map(|x| module(x, i)).collect();
+            let column: Vec<bool> = (0..size).map(|y| module(i, y)).collect();
+            penalty += line_penalty(&row) + line_penalty(&column);
+        }
+
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let color = module(x, y);
+                if module(x + 1, y) == color
+                    && module(x, y + 1) == color
+                    && module(x + 1, y + 1) == color
+                {
+                    penalty += 3;
+                }
+            }
+        }
+
+        let total = size * size;
+        let dark = self.modules.iter().filter(|&&dark| dark).count();
+        let deviation = (dark * 20).abs_diff(total * 10).div_ceil(total);
+        penalty + deviation.saturating_sub(1) as u32 * 10
+    }
+}
+
+/// The penalty for runs of five or more modules of one color in a row or column, and for patterns that look like finders.
+fn line_penalty(line: &[bool]) -> u32 {
+    const FINDER: [bool; 11] = [
+        true, false, true, true, true, false, true, false, false, false, false,
+    ];
+
+    let mut penalty = 0;
+    let mut run = 0;
+    for (i, &dark) in line.iter().enumerate() {
+        if i > 0 && dark == line[i - 1] {
+            run += 1;
+        } else {
+            if run >= 5 {
+                penalty += run - 2;
+            }
+            run = 1;
+        }
+    }
+    if run >= 5 {
+        penalty += run - 2;
+    }
+
+    // The area around the code counts as light.
+    let padded: Vec<bool> = [false; 4]
+        .into_iter()
+        .chain(line.iter().copied())
+        .chain([false; 4])
+        .collect();
+    let mut reversed = FINDER;
+    reversed.reverse();
+    for window in padded.windows(11) {
+        if window == FINDER || window == reversed {
+            penalty += 40;
+        }
+    }
+    penalty
+}
+
+/// The centers of the alignment patterns along each axis.
+fn alignment_positions(version: usize) -> Vec<usize> {
+    if version == 1 {
+        return Vec::new();
+    }
+    let count = version / 7 + 2;
+    let step = (version * 8 + count * 3 + 5) / (count * 4 - 4) * 2;
+    let size = version * 4 + 17;
+
+    let mut positions: Vec<usize> = (0..count - 1).map(|i| size - 7 - i * step).collect();
+    positions.push(6);
+    positions.reverse();
+    positions
+}
+
+/// The number of modules left for data and error correction once the fixed patterns are drawn.
+fn raw_data_modules(version: usize) -> usize {
+    let mut modules = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let count = version / 7 + 2;
+        modules -= (25 * count - 10) * count - 55;
+        if version >= 7 {
+            modules -= 36;
+        }
+    }
+    modules
+}
+
+/// The number of codewords of data a version holds at a level of error correction.
+fn data_codewords(version: usize, error_correction: ErrorCorrection) -> usize {
+    let level = error_correction.index();
+    raw_data_modules(version) / 8
+        - ECC_CODEWORDS_PER_BLOCK[level][version] as usize
+            * ERROR_CORRECTION_BLOCKS[level][version] as usize
+}
+
+/// The 15 bits of format information, with their own error correction.
+fn format_bits(error_correction: ErrorCorrection, mask: u32) -> u32 {
+    let data = error_correction.format_bits() << 3 | mask;
+    let mut remainder = data;
+    for _ in 0..10 {
+        remainder = (remainder << 1) ^ ((remainder >> 9) * 0x537);
+    }
+    (data << 10 | remainder) ^ 0x5412
+}
+
+/// The 18 bits of version information, with their own error correction.
+fn version_bits(version: usize) -> u32 {
+    let mut remainder = version as u32;
+    for _ in 0..12 {
+        remainder = (remainder << 1) ^ ((remainder >> 11) * 0x1F25);
+    }
+    (version as u32) << 12 | remainder
+}
+
+/// Append the lowest `count` bits of `value`, most significant first.
+fn push_bits(bits: &mut Vec<bool>, value: u32, count: usize) {
+    bits.extend((0..count).rev().map(|i| value >> i & 1 != 0));
+}
+
+/// The generator polynomial for Reed-Solomon codes with `degree` error correction codewords, leaving out the leading term.
+fn reed_solomon_divisor(degree: usize) -> Vec<u8> {
+    let mut divisor = vec![0; degree];
+    divisor[degree - 1] = 1;
+
+    let mut root = 1;
+    for _ in 0..degree {
+        for j in 0..degree {
+            divisor[j] = gf_multiply(divisor[j], root);
+            if j + 1 < degree {
+                divisor[j] ^= divisor[j + 1];
+            }
+        }
+        root = gf_multiply(root, 0x02);
+    }
+    divisor
+}
+
+/// The Reed-Solomon error correction codewords for `data`.
+fn reed_solomon_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut remainder = vec![0; divisor.len()];
+    for &byte in data {
+        let factor = byte ^ remainder.remove(0);
+        remainder.push(0);
+        for (value, &coefficient) in remainder.iter_mut().zip(divisor) {
+            *value ^= gf_multiply(coefficient, factor);
+        }
+    }
+    remainder
+}
+
+/// Multiply in the Galois field GF(2⁸) that QR codes use.
+fn gf_multiply(x: u8, y: u8) -> u8 {
+    let mut product: u16 = 0;
+    for i in (0..8).rev() {
+        product = (product << 1) ^ ((product >> 7) * 0x11D);
+        product ^= (y as u16 >> i & 1) * x as u16;
+    }
+    product as u8
+}
+
+/// Bar and space widths of each Code 128 symbol, starting with a bar. The last is the stop symbol.
+const CODE128_PATTERNS: [&str; 107] = [
+    "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312", "132212",
+    "221213", "221312", "231212", "112232", "122132", "122231", "113222", "123122", "123221",
+    "223211", "221132", "221231", "213212", "223112", "312131", "311222", "321122", "321221",
+    "312212", "322112", "322211", "212123", "212321", "232121", "111323", "131123", "131321",
+    "112313", "132113", "132311", "211313", "231113", "231311", "112133", "112331", "132131",
+    "113123", "113321", "133121", "313121", "211331", "231131", "213113", "213311", "213131",
+    "311123", "311321", "331121", "312113", "312311", "332111", "314111", "221411", "431111",
+    "111224", "111422", "121124", "121421", "141122", "141221", "112214", "112412", "122114",
+    "122411", "142112", "142211", "241211", "221114", "413111", "241112", "134111", "111242",
+    "121142", "121241", "114212", "124112", "124211", "411212", "421112", "421211", "212141",
+    "214121", "412121", "111143", "111341", "131141", "114113", "114311", "411113", "411311",
+    "113141", "114131", "311141", "411131", "211412", "211214", "211232", "2331112",
+];
+
+/// The Code 128 symbol that starts code set B.
+const CODE128_START_B: usize = 104;
+
+/// The seven-module patterns of each digit on the left of an EAN-13 code with odd parity. Even parity is these reversed and inverted,
+/// and the right half uses these inverted.
+const EAN_DIGITS: [u8; 10] = [
+    0b0001101, 0b0011001, 0b0010011, 0b0111101, 0b0100011, 0b0110001, 0b0101111, 0b0111011,
+    0b0110111, 0b0001011,
+];
+
+/// Which digits of the left half use even parity, one bit per digit from the left, chosen by the first digit.
+const EAN_PARITY: [u8; 10] = [
+    0b000000, 0b001011, 0b001101, 0b001110, 0b010011, 0b011001, 0b011100, 0b010101, 0b010110,
+    0b011010,
+];
+
+/// A one-dimensional barcode, as a row of dark and light modules of equal width.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Barcode {
+    modules: Vec<bool>,
+}
+
+impl Barcode {
+    /// Encode `text` as a Code 128 barcode, using code set B.
+    ///
+    /// Returns [Error::InvalidBarcode] if the text has characters outside printable ASCII.
+    pub fn code128(text: &str) -> Result<Self, Error> {
+        let mut symbols = vec![CODE128_START_B];
+        for character in text.chars() {
+            if !(' '..='\u{7f}').contains(&character) {
+                return Err(Error::InvalidBarcode(text.to_string()));
+            }
+            symbols.push(character as usize - 32);
+        }
+
+        let checksum = symbols
+            .iter()
+            .enumerate()
+            .map(|(i, &symbol)| i.max(1) * symbol)
+            .sum::<usize>()
+            % 103;
+        symbols.push(checksum);
+        symbols.push(CODE128_PATTERNS.len() - 1);
+
+        let mut modules = Vec::new();
+        for symbol in symbols {
+            for (i, width) in CODE128_PATTERNS[symbol].bytes().enumerate() {
+                let dark = i % 2 == 0;
+                modules.extend(std::iter::repeat_n(dark, (width - b'0') as usize));
+            }
+        }
+        Ok(Self { modules })
+    }
+
+    /// Encode an EAN-13 barcode from its digits, as on retail products.
+    ///
+    /// Give either the first 12 digits, and the check digit is worked out, or all 13, and the check digit is checked.
+    /// Returns [Error::InvalidBarcode] for anything else.
+    pub fn ean13(digits: &str) -> Result<Self, Error> {
+        let invalid = || Error::InvalidBarcode(digits.to_string());
+        let mut values: Vec<u8> = digits
+            .chars()
+            .map(|digit| digit.to_digit(10).map(|value| value as u8))
+            .collect::<Option<_>>()
+            .ok_or_else(invalid)?;
+        if values.len() != 12 && values.len() != 13 {
+            return Err(invalid());
+        }
+
+        let sum: u32 = values[..12]
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| value as u32 * if i % 2 == 0 { 1 } else { 3 })
+            .sum();
+        let check = ((10 - sum % 10) % 10) as u8;
+        match values.get(12) {
+            Some(&given) if given != check => return Err(invalid()),
+            Some(_) => {}
+            None => values.push(check),
+        }
+
+        let mut modules = Vec::with_capacity(95);
+        let mut push = |pattern: u8, count: usize| {
+            modules.extend((0..count).rev().map(|i| pattern >> i & 1 != 0));
+        };
+
+        push(0b101, 3);
+        let parity = EAN_PARITY[values[0] as usize];
+        for (i, &value) in values[1..7].iter().enumerate() {
+            let odd = EAN_DIGITS[value as usize];
+            if parity >> (5 - i) & 1 != 0 {
+                push((!odd & 0x7f).reverse_bits() >> 1, 7);
+            } else {
+                push(odd, 7);
+            }
+        }
+        push(0b01010, 5);
+        for &value in &values[7..] {
+            push(!EAN_DIGITS[value as usize] & 0x7f, 7);
+        }
+        push(0b101, 3);
+
+        Ok(Self { modules })
+    }
+
+    /// Each module from left to right, `true` where it is dark.
+    pub fn modules(&self) -> &[bool] {
+        &self.modules
+    }
+
+    /// The bars as filled rectangles, each module `module_width` wide and `height` tall, with the bottom left corner at `origin`.
+    ///
+    /// Scanners need a light margin on either side, of ten modules for Code 128 and eleven for EAN-13.
+    pub fn shapes(&self, origin: Vec2, module_width: f32, height: f32, color: Color) -> Vec<Shape> {
+        dark_runs(&self.modules)
+            .map(|(start, end)| {
+                rectangle(
+                    origin + Vec2::new(start as f32 * module_width, 0.0),
+                    origin + Vec2::new(end as f32 * module_width, height),
+                    color,
+                )
+            })
+            .collect()
+    }
+}
+
+/// The start and end of each run of dark modules.
+fn dark_runs(modules: &[bool]) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let mut start = None;
+    (0..=modules.len()).filter_map(move |i| {
+        match (modules.get(i).copied().unwrap_or(false), start) {
+            (true, None) => {
+                start = Some(i);
+                None
+            }
+            (false, Some(run)) => {
+                start = None;
+                Some((run, i))
+            }
+            _ => None,
+        }
+    })
+}
+
+/// A filled rectangle from `min` to `max`.
+fn rectangle(min: Vec2, max: Vec2, color: Color) -> Shape {
+    Shape {
+        points: vec![
+            min,
+            Vec2::new(max.x, min.y),
+            max,
+            Vec2::new(min.x, max.y),
+            min,
+        ],
+        fill: Some(color),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify the error correction and format information against published examples, and the layout of each kind of code.
+    #[test]
+    fn encode_codes() {
+        // "HELLO WORLD" at version 1-Q, from Thonky's QR code tutorial.
+        let data = [32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236];
+        let ecc = reed_solomon_remainder(&data, &reed_solomon_divisor(13));
+        assert_eq!(
+            ecc,
+            [168, 72, 22, 82, 217, 54, 156, 0, 46, 15, 180, 122, 16]
+        );
+        assert_eq!(format_bits(ErrorCorrection::Medium, 0), 0b101010000010010);
+        assert_eq!(format_bits(ErrorCorrection::Low, 4), 0b110011000101111);
+        assert_eq!(version_bits(7), 0b000111110010010100);
+        assert_eq!(alignment_positions(32), [6, 34, 60, 86, 112, 138]);
+        assert_eq!(data_codewords(1, ErrorCorrection::Low), 19);
+        assert_eq!(data_codewords(40, ErrorCorrection::High), 1276);
+
+        let code = QrCode::new("https://example.com", ErrorCorrection::Medium).unwrap();
+        assert_eq!((code.version(), code.size()), (2, 25));
+        // The finder pattern's ring, and the dark module beside the lower format information.
+        assert!((0..7).all(|i| code.module(i, 0) && code.module(0, i) && code.module(24, i)));
+        assert!(!code.module(1, 1) && code.module(3, 3));
+        assert!(code.module(8, 17));
+
+        let long = QrCode::new(vec![b'a'; 1000], ErrorCorrection::Low).unwrap();
+        assert_eq!(long.size(), long.version() * 4 + 17);
+        assert!(long.version() > 20);
+        assert_eq!(
+            QrCode::new(vec![0; 3000], ErrorCorrection::Low),
+            Err(Error::DataTooLong)
+        );
+        assert!(!code.shapes(Vec2::ZERO, 1.0, Color::black()).is_empty());
+
+        assert!(CODE128_PATTERNS[..106].iter().all(|pattern| pattern
+            .bytes()
+            .map(|width| (width - b'0') as usize)
+            .sum::<usize>()
+            == 11));
+        let barcode = Barcode::code128("barium").unwrap();
+        assert_eq!(barcode.modules().len(), 11 * 9 + 2);
+        assert!(Barcode::code128("é").is_err());
+
+        // The example from Wikipedia's EAN-13 article.
+        let ean = Barcode::ean13("400638133393").unwrap();
+        assert_eq!(ean, Barcode::ean13("4006381333931").unwrap());
+        assert!(Barcode::ean13("4006381333932").is_err());
+        assert_eq!(ean.modules().len(), 95);
+        let bars = ean.shapes(Vec2::ZERO, 1.0, 20.0, Color::black());
+        assert_eq!(bars.len(), 30);
+    }
+}
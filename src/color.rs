@@ -1,13 +1,13 @@
-use std::{
-    num::ParseIntError,
-    ops::{Add, Div, Mul, Rem, Sub},
-};
+use std::ops::{Add, Div, Mul, Rem, Sub};
 
 use glam::Vec4;
 use image::{Rgb, Rgba};
 
+use crate::Error;
+
 /// A structure that represents an RGBA color. All values are [f32] from 0.0..=1.0.
 #[derive(Default, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Color {
     inner: Vec4,
 }
@@ -143,15 +143,27 @@ impl Color {
 
     /// Creates a [Color] from HSV values.
     ///
-    /// Note: all inputs must be of range 0..1.
+    /// All inputs must be of range 0..=1, otherwise [Error::HsvOutOfRange] is returned.
+    /// See [from_hsv_clamped](Self::from_hsv_clamped) for a variant that cannot fail.
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Result<Self, Error> {
+        let range = 0.0..=1.0;
+        if !range.contains(&hue) || !range.contains(&saturation) || !range.contains(&value) {
+            return Err(Error::HsvOutOfRange);
+        }
+
+        Ok(Self::hsv_unchecked(hue, saturation, value))
+    }
+
+    /// Creates a [Color] from HSV values, clamping each input to 0..=1.
     ///
+    /// `NaN` inputs are treated as `0.0`.
+    pub fn from_hsv_clamped(hue: f32, saturation: f32, value: f32) -> Self {
+        Self::hsv_unchecked(clamp_unit(hue), clamp_unit(saturation), clamp_unit(value))
+    }
+
     /// Implementation from [Rosetta Code](https://rosettacode.org/wiki/Color_wheel#Rust).
     #[allow(clippy::many_single_char_names)]
-    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Self {
-        if hue + saturation + value > 3.0 {
-            panic!("All HSV values must be below 1.0.");
-        }
-
+    fn hsv_unchecked(hue: f32, saturation: f32, value: f32) -> Self {
         let hp = hue / (1.0 / 6.0);
         let c = saturation * value;
         let x = c * (1.0 - (hp % 2.0 - 1.0).abs());
@@ -210,30 +222,211 @@ impl Color {
     ///
     /// The hex *can* include `#` or `0x` at the beginning, but it is not required.
     /// If the alpha channel is not included, it will default to 1.0
-    pub fn from_hex(hex: &str) -> Result<Self, ParseIntError> {
-        let mut start_index = if hex.starts_with('#') {
-            1
-        } else if hex.starts_with("0x") {
-            2
-        } else {
-            0
+    pub fn from_hex(hex: &str) -> Result<Self, Error> {
+        let digits = hex
+            .strip_prefix('#')
+            .or_else(|| hex.strip_prefix("0x"))
+            .unwrap_or(hex);
+
+        if !(digits.len() == 6 || digits.len() == 8) || !digits.is_ascii() {
+            return Err(Error::InvalidHex(hex.to_string()));
+        }
+
+        let channel = |index: usize| {
+            u8::from_str_radix(&digits[index..index + 2], 16)
+                .map(|v| v as f32 / 255.0)
+                .map_err(|_| Error::InvalidHex(hex.to_string()))
         };
 
-        let r = u8::from_str_radix(&hex[start_index..start_index + 2], 16)? as f32 / 255.0;
-        start_index += 2;
-        let g = u8::from_str_radix(&hex[start_index..start_index + 2], 16)? as f32 / 255.0;
-        start_index += 2;
-        let b = u8::from_str_radix(&hex[start_index..start_index + 2], 16)? as f32 / 255.0;
+        let a = if digits.len() == 8 { channel(6)? } else { 1.0 };
+
+        Ok(Self::new(channel(0)?, channel(2)?, channel(4)?, a))
+    }
+
+    /// Clamp every channel to 0..=1.
+    ///
+    /// `NaN` channels become `0.0`.
+    pub fn clamped(&self) -> Self {
+        Self::new(
+            clamp_unit(self.r()),
+            clamp_unit(self.g()),
+            clamp_unit(self.b()),
+            clamp_unit(self.a()),
+        )
+    }
 
-        start_index += 2;
+    /// Checks if every channel is a finite number.
+    #[inline]
+    pub fn is_finite(&self) -> bool {
+        self.inner.is_finite()
+    }
 
-        if start_index >= hex.len() {
-            return Ok(Self::new(r, g, b, 1.0));
+    /// The relative luminance of the color, from 0 for black to 1 for white, as defined by WCAG. Alpha is ignored.
+    pub fn relative_luminance(&self) -> f32 {
+        let color = self.clamped();
+
+        0.2126 * srgb_to_linear(color.r())
+            + 0.7152 * srgb_to_linear(color.g())
+            + 0.0722 * srgb_to_linear(color.b())
+    }
+
+    /// The WCAG contrast ratio between two colors, from 1 for identical luminance to 21 for black on white.
+    ///
+    /// WCAG asks for at least 4.5 between body text and its background, and 3 for large text and chart elements.
+    pub fn contrast_ratio(&self, other: &Color) -> f32 {
+        let (a, b) = (self.relative_luminance(), other.relative_luminance());
+
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    /// Convert to the Oklab perceptual color space, as `[lightness, a, b]`. Alpha is ignored.
+    ///
+    /// Equal distances in Oklab look roughly equally different, unlike in RGB or HSV.
+    pub fn to_oklab(&self) -> [f32; 3] {
+        let color = self.clamped();
+        let (r, g, b) = (
+            srgb_to_linear(color.r()),
+            srgb_to_linear(color.g()),
+            srgb_to_linear(color.b()),
+        );
+
+        let l = (0.41222146 * r + 0.53633255 * g + 0.051445995 * b).cbrt();
+        let m = (0.2119035 * r + 0.6806995 * g + 0.10739696 * b).cbrt();
+        let s = (0.08830246 * r + 0.28171885 * g + 0.6299787 * b).cbrt();
+
+        [
+            0.21045426 * l + 0.7936178 * m - 0.004072047 * s,
+            1.9779985 * l - 2.4285922 * m + 0.4505937 * s,
+            0.025904037 * l + 0.78277177 * m - 0.80867577 * s,
+        ]
+    }
+
+    /// Creates an opaque [Color] from the Oklab perceptual color space. See [to_oklab](Self::to_oklab).
+    ///
+    /// Colors outside of sRGB are clamped.
+    pub fn from_oklab(lightness: f32, a: f32, b: f32) -> Self {
+        let l = (lightness + 0.39633778 * a + 0.21580376 * b).powi(3);
+        let m = (lightness - 0.105561346 * a - 0.06385417 * b).powi(3);
+        let s = (lightness - 0.08948418 * a - 1.2914855 * b).powi(3);
+
+        Self::new(
+            linear_to_srgb(4.0767417 * l - 3.3077116 * m + 0.23096994 * s),
+            linear_to_srgb(-1.268438 * l + 2.6097574 * m - 0.34131938 * s),
+            linear_to_srgb(-0.0041960864 * l - 0.7034186 * m + 1.7076147 * s),
+            1.0,
+        )
+        .clamped()
+    }
+
+    /// How different two colors look, as the distance between them in [Oklab](Self::to_oklab). Alpha is ignored.
+    ///
+    /// Colors closer than about 0.02 are hard to tell apart.
+    pub fn perceptual_distance(&self, other: &Color) -> f32 {
+        let (a, b) = (self.to_oklab(), other.to_oklab());
+
+        a.iter()
+            .zip(b)
+            .map(|(a, b)| (a - b).powi(2))
+            .sum::<f32>()
+            .sqrt()
+    }
+
+    /// Creates an opaque [Color] from the cyan, magenta, yellow and black inks used in print, each from 0 to 1.
+    ///
+    /// Inputs are clamped to 0..=1. This is the inverse of [to_cmyk](Self::to_cmyk) with any [CmykProfile] that does not limit ink.
+    pub fn from_cmyk(cyan: f32, magenta: f32, yellow: f32, black: f32) -> Self {
+        let white = 1.0 - clamp_unit(black);
+
+        Self::new(
+            (1.0 - clamp_unit(cyan)) * white,
+            (1.0 - clamp_unit(magenta)) * white,
+            (1.0 - clamp_unit(yellow)) * white,
+            1.0,
+        )
+    }
+
+    /// Get the cyan, magenta, yellow and black inks that print this color, each from 0 to 1, ignoring alpha.
+    pub fn to_cmyk(&self, profile: &CmykProfile) -> [f32; 4] {
+        let color = self.clamped();
+        let darkest = 1.0 - color.r().max(color.g()).max(color.b());
+        let black = darkest * clamp_unit(profile.black_generation);
+
+        if black >= 1.0 {
+            return [0.0, 0.0, 0.0, 1.0];
+        }
+
+        let mut inks = [color.r(), color.g(), color.b()]
+            .map(|channel| (1.0 - channel - black) / (1.0 - black));
+
+        // Take ink away from cyan, magenta and yellow evenly, leaving black to keep the color dark.
+        let total = inks.iter().sum::<f32>() + black;
+        let limit = profile.total_ink_limit.max(black);
+        if total > limit {
+            let scale = (limit - black) / (total - black);
+            inks = inks.map(|ink| ink * scale);
         }
 
-        let a = u8::from_str_radix(&hex[start_index..start_index + 2], 16)? as f32 / 255.0;
+        [inks[0], inks[1], inks[2], black]
+    }
+}
+
+/// How a [Color] is separated into print inks by [Color::to_cmyk].
+///
+/// The default replaces all of the gray in a color with black ink, and does not limit ink.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CmykProfile {
+    /// How much of the gray shared by cyan, magenta and yellow is printed with black ink instead, from 0 to 1.
+    ///
+    /// Also known as gray component replacement. Lower values give richer blacks, at the cost of more ink.
+    pub black_generation: f32,
+    /// The most ink allowed on any spot, as the sum of all four inks, from 0 to 4.
+    ///
+    /// Presses commonly limit this to around 3.0, or 300%, so the paper is not soaked.
+    pub total_ink_limit: f32,
+}
+
+impl CmykProfile {
+    /// Creates a new [CmykProfile].
+    pub fn new(black_generation: f32, total_ink_limit: f32) -> Self {
+        Self {
+            black_generation,
+            total_ink_limit,
+        }
+    }
+}
+
+impl Default for CmykProfile {
+    fn default() -> Self {
+        Self::new(1.0, 4.0)
+    }
+}
+
+/// Undo the sRGB transfer curve, turning a channel into linear light.
+pub(crate) fn srgb_to_linear(v: f32) -> f32 {
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Apply the sRGB transfer curve to a channel of linear light.
+pub(crate) fn linear_to_srgb(v: f32) -> f32 {
+    if v <= 0.0031308 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    }
+}
 
-        Ok(Self::new(r, g, b, a))
+/// Clamp a value to 0..=1, mapping `NaN` to `0.0`.
+#[inline]
+fn clamp_unit(v: f32) -> f32 {
+    if v.is_nan() {
+        0.0
+    } else {
+        v.clamp(0.0, 1.0)
     }
 }
 
@@ -306,28 +499,26 @@ impl From<Color> for Rgba<u8> {
 }
 
 #[cfg(feature = "tiny_skia_renderer")]
-impl From<Color> for tiny_skia::Color {
-    fn from(color: Color) -> Self {
-        tiny_skia::Color::from_rgba(
-            color.r().clamp(0.0, 1.0),
-            color.g().clamp(0.0, 1.0),
-            color.b().clamp(0.0, 1.0),
-            color.a().clamp(0.0, 1.0),
-        )
-        .unwrap()
+impl TryFrom<Color> for tiny_skia::Color {
+    type Error = Error;
+
+    fn try_from(color: Color) -> Result<Self, Self::Error> {
+        if !color.is_finite() {
+            return Err(Error::NonFiniteColor);
+        }
+
+        let color = color.clamped();
+        tiny_skia::Color::from_rgba(color.r(), color.g(), color.b(), color.a())
+            .ok_or(Error::NonFiniteColor)
     }
 }
 
 #[cfg(feature = "tiny_skia_renderer")]
-impl From<&Color> for tiny_skia::Color {
-    fn from(color: &Color) -> Self {
-        tiny_skia::Color::from_rgba(
-            color.r().clamp(0.0, 1.0),
-            color.g().clamp(0.0, 1.0),
-            color.b().clamp(0.0, 1.0),
-            color.a().clamp(0.0, 1.0),
-        )
-        .unwrap()
+impl TryFrom<&Color> for tiny_skia::Color {
+    type Error = Error;
+
+    fn try_from(color: &Color) -> Result<Self, Self::Error> {
+        tiny_skia::Color::try_from(*color)
     }
 }
 
@@ -420,3 +611,56 @@ impl Rem<Color> for Color {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that hex strings parse with and without prefixes and alpha.
+    #[test]
+    fn parse_hex() {
+        assert_eq!(Color::from_hex("#ff0000"), Ok(Color::red()));
+        assert_eq!(Color::from_hex("0x00ff00"), Ok(Color::green()));
+        assert_eq!(Color::from_hex("0000ff00"), Ok(Color::blue().with_a(0.0)));
+    }
+
+    /// Verify that malformed hex strings return an error instead of panicking.
+    #[test]
+    fn parse_bad_hex() {
+        for hex in ["", "#", "#fff", "#ff00zz", "#ff00ff0", "#ééé"] {
+            assert_eq!(
+                Color::from_hex(hex),
+                Err(Error::InvalidHex(hex.to_string()))
+            );
+        }
+    }
+
+    /// Verify that out of range HSV values are rejected or clamped.
+    #[test]
+    fn hsv_range() {
+        assert_eq!(Color::from_hsv(1.5, 0.0, 0.0), Err(Error::HsvOutOfRange));
+        assert_eq!(
+            Color::from_hsv(0.0, f32::NAN, 0.0),
+            Err(Error::HsvOutOfRange)
+        );
+        assert_eq!(Color::from_hsv_clamped(0.0, 2.0, 2.0), Color::red());
+    }
+
+    /// Verify that colors survive a round trip through CMYK, and that ink limits hold.
+    #[test]
+    fn cmyk_round_trip() {
+        let color = Color::new(0.2, 0.4, 0.6, 1.0);
+        let [c, m, y, k] = color.to_cmyk(&CmykProfile::default());
+        let round_trip = Color::from_cmyk(c, m, y, k);
+        assert!((round_trip.inner - color.inner).abs().max_element() < 1e-6);
+
+        assert_eq!(
+            Color::red().to_cmyk(&CmykProfile::default()),
+            [0.0, 1.0, 1.0, 0.0]
+        );
+
+        let rich_black = Color::new(0.05, 0.05, 0.05, 1.0).to_cmyk(&CmykProfile::new(0.5, 3.0));
+        assert!(rich_black.iter().sum::<f32>() <= 3.0 + 1e-6);
+        assert!(rich_black[3] < 0.5);
+    }
+}
@@ -0,0 +1,131 @@
+/// The color space that the colors of a render are meant to be shown in.
+///
+/// Tagging an output with a profile does not change its colors. It tells displays and printers how to interpret them,
+/// so colors picked for a wide-gamut display are not squashed into sRGB.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColorProfile {
+    /// The standard color space of the web, and what untagged outputs are assumed to be in.
+    Srgb,
+    /// The wide-gamut color space of most modern Apple and phone displays.
+    DisplayP3,
+    /// Adobe RGB (1998), a wide-gamut color space common in photography and print.
+    AdobeRgb,
+    /// The contents of an ICC profile file, embedded as-is.
+    Icc(Vec<u8>),
+}
+
+impl ColorProfile {
+    /// The PNG metadata describing the profile, for an RGBA image of `width` by `height` pixels.
+    ///
+    /// Presets are written as the chromaticities and gamma of the color space, which is far smaller than an ICC profile.
+    #[cfg(feature = "tiny_skia_renderer")]
+    pub(crate) fn png_info(&self, width: u32, height: u32) -> png::Info<'static> {
+        let mut info = png::Info::with_size(width, height);
+        info.color_type = png::ColorType::Rgba;
+        info.bit_depth = png::BitDepth::Eight;
+
+        let d65 = (0.3127, 0.3290);
+        match self {
+            ColorProfile::Srgb => {
+                info.srgb = Some(png::SrgbRenderingIntent::Perceptual);
+            }
+            ColorProfile::DisplayP3 => {
+                // Display P3 shares the transfer curve of sRGB, which a pure gamma of 2.2 approximates.
+                info.source_gamma = Some(png::ScaledFloat::new(1.0 / 2.2));
+                info.source_chromaticities = Some(png::SourceChromaticities::new(
+                    d65,
+                    (0.680, 0.320),
+                    (0.265, 0.690),
+                    (0.150, 0.060),
+                ));
+            }
+            ColorProfile::AdobeRgb => {
+                info.source_gamma = Some(png::ScaledFloat::new(256.0 / 563.0));
+                info.source_chromaticities = Some(png::SourceChromaticities::new(
+                    d65,
+                    (0.640, 0.330),
+                    (0.210, 0.710),
+                    (0.150, 0.060),
+                ));
+            }
+            ColorProfile::Icc(profile) => {
+                info.icc_profile = Some(profile.clone().into());
+            }
+        }
+
+        info
+    }
+
+    /// The name of the CSS color space for the profile, used with the `color()` function.
+    ///
+    /// ICC profiles are declared with `@color-profile` under the name `--barium`.
+    #[cfg(feature = "svg_renderer")]
+    pub(crate) fn css_name(&self) -> &'static str {
+        match self {
+            ColorProfile::Srgb => "srgb",
+            ColorProfile::DisplayP3 => "display-p3",
+            ColorProfile::AdobeRgb => "a98-rgb",
+            ColorProfile::Icc(_) => "--barium",
+        }
+    }
+}
+
+/// Encode bytes as standard, padded base64, for embedding binary data in text formats.
+#[cfg(feature = "svg_renderer")]
+pub(crate) fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, byte)| {
+            group | ((*byte as u32) << (16 - i * 8))
+        });
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(group >> (18 - i * 6)) as usize & 63] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that base64 pads partial groups.
+    #[cfg(feature = "svg_renderer")]
+    #[test]
+    fn encode_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+
+    /// Verify that PNGs carry the profile they are tagged with.
+    #[cfg(feature = "tiny_skia_renderer")]
+    #[test]
+    fn tag_png() {
+        use crate::{renderers::write_png, RgbaImage};
+
+        let image = RgbaImage::new(2, 2);
+        let profile = vec![1, 2, 3, 4];
+
+        let encoded = write_png(&image, Vec::new(), &ColorProfile::Icc(profile.clone())).unwrap();
+        let reader = png::Decoder::new(encoded.as_slice()).read_info().unwrap();
+        assert_eq!(
+            reader.info().icc_profile.as_deref(),
+            Some(profile.as_slice())
+        );
+
+        let encoded = write_png(&image, Vec::new(), &ColorProfile::DisplayP3).unwrap();
+        let reader = png::Decoder::new(encoded.as_slice()).read_info().unwrap();
+        assert!(reader.info().srgb.is_none());
+        assert!(reader.info().source_chromaticities.is_some());
+    }
+}
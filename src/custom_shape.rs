@@ -0,0 +1,153 @@
+use std::{fmt::Debug, sync::Arc};
+
+use glam::{Affine2, Vec2};
+
+use crate::{Canvas, Color, Shape, Stroke};
+
+/// A shape defined outside of this crate, such as a parametric gear or superellipse, that is only turned into points when it is needed.
+///
+/// Draw one with [Canvas::draw_custom_shape]. The canvas keeps it as it is, along with the transforms it is drawn through,
+/// and flattens it into paths on demand, such as when it is rendered.
+pub trait CustomShape: Debug + Send + Sync {
+    /// The paths that make up the shape, in its own local space.
+    ///
+    /// The first path is the shape's outline, and the rest become its [subpaths](Shape::subpaths).
+    /// Shapes are filled with [FillRule::NonZero](crate::FillRule::NonZero), so wind holes the opposite way to the outline.
+    fn to_paths(&self) -> Vec<Vec<Vec2>>;
+}
+
+/// A [CustomShape] held by a [Shape], along with the transform from its local space into World Space.
+#[derive(Debug, Clone)]
+pub struct CustomGeometry {
+    /// The shape itself, shared between copies such as those made by a [Symmetry](crate::Symmetry).
+    pub shape: Arc<dyn CustomShape>,
+    /// The transform from the shape's local space into World Space.
+    pub transform: Affine2,
+}
+
+impl CustomGeometry {
+    /// Create a new [CustomGeometry], placing `shape` with `transform`.
+    pub fn new<S: CustomShape + 'static>(shape: S, transform: Affine2) -> Self {
+        Self {
+            shape: Arc::new(shape),
+            transform,
+        }
+    }
+
+    /// Flatten the shape into its paths, in World Space.
+    pub fn paths(&self) -> Vec<Vec<Vec2>> {
+        self.shape
+            .to_paths()
+            .into_iter()
+            .map(|path| {
+                path.into_iter()
+                    .map(|point| self.transform.transform_point2(point))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl PartialEq for CustomGeometry {
+    /// Two custom geometries are equal if they share the same shape, placed the same way.
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.shape, &other.shape) && self.transform == other.transform
+    }
+}
+
+impl Canvas {
+    /// Draw a [CustomShape] onto the canvas, projected from the camera.
+    ///
+    /// The shape is kept unflattened until it is rendered or its points are otherwise needed.
+    pub fn draw_custom_shape<S: CustomShape + 'static>(
+        &mut self,
+        shape: S,
+        stroke: Option<Stroke>,
+        fill: Option<Color>,
+    ) {
+        let projection = self.to_world_transform();
+        self.draw_custom_shape_with(CustomGeometry::new(shape, projection), stroke, fill);
+    }
+
+    /// Draw a [CustomShape] directly onto the canvas.
+    ///
+    /// The shape is kept unflattened until it is rendered or its points are otherwise needed.
+    pub fn draw_custom_shape_absolute<S: CustomShape + 'static>(
+        &mut self,
+        shape: S,
+        stroke: Option<Stroke>,
+        fill: Option<Color>,
+    ) {
+        self.draw_custom_shape_with(CustomGeometry::new(shape, Affine2::IDENTITY), stroke, fill);
+    }
+
+    fn draw_custom_shape_with(
+        &mut self,
+        custom: CustomGeometry,
+        stroke: Option<Stroke>,
+        fill: Option<Color>,
+    ) {
+        self.draw_raw_shape(Shape {
+            stroke,
+            fill,
+            custom: Some(custom),
+            ..Default::default()
+        });
+    }
+}
+
+/// A capsule from the origin out to `(10, 0)`, filled and stroked, kept as custom geometry.
+///
+/// For checking that operations on shapes flatten custom geometry before walking its paths.
+#[cfg(test)]
+pub(crate) fn custom_capsule() -> Shape {
+    let capsule = crate::primitives::Capsule::new(Vec2::ZERO, Vec2::new(10.0, 0.0), 1.0);
+    Shape {
+        stroke: Some(Stroke::new(Color::black(), 0.1, crate::LineEnd::Butt)),
+        fill: Some(Color::red()),
+        custom: Some(CustomGeometry::new(capsule, Affine2::IDENTITY)),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{renderers::SvgRenderer, Symmetry};
+
+    #[derive(Debug)]
+    struct Diamond;
+
+    impl CustomShape for Diamond {
+        fn to_paths(&self) -> Vec<Vec<Vec2>> {
+            vec![vec![Vec2::X, Vec2::Y, -Vec2::X, -Vec2::Y, Vec2::X]]
+        }
+    }
+
+    /// Verify that custom shapes are kept unflattened through the camera and symmetry, and flattened when rendered.
+    #[test]
+    fn draw_custom_shape() {
+        let mut canvas = Canvas::new(10);
+        canvas.move_camera((2.0, 0.0));
+        canvas.set_symmetry(Some(Symmetry::Mirror(Vec2::Y)));
+        canvas.draw_custom_shape(Diamond, None, Some(Color::black()));
+
+        let shapes = canvas.as_raw();
+        assert_eq!(shapes.len(), 2);
+        assert!(shapes.iter().all(|shape| shape.points.is_empty()));
+        assert_eq!(
+            shapes[1].bounds(),
+            Some((Vec2::new(-3.0, -1.0), Vec2::new(-1.0, 1.0)))
+        );
+
+        let mut flattened = shapes[0].clone();
+        flattened.flatten();
+        assert_eq!(flattened.points[0], Vec2::new(3.0, 0.0));
+        assert_eq!(flattened.custom, None);
+
+        let renderer = SvgRenderer::new(Vec2::splat(10.0), None, false, true, 16).unwrap();
+        let (_, stats) = canvas.render_with_stats(renderer).unwrap();
+        assert_eq!(stats.shapes_rendered, 2);
+        assert_eq!(stats.points_rendered, 10);
+    }
+}
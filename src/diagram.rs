@@ -0,0 +1,428 @@
+use std::f32::consts::{SQRT_2, TAU};
+
+use glam::Vec2;
+
+use crate::{
+    geometry::{arc_lengths, fillet},
+    graph::{trim_route, Graph, LayeredLayout},
+    routing::{Router, Routing},
+    Canvas, Color, Shape, Stroke,
+};
+
+/// The outline drawn around a [Diagram] node's text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NodeShape {
+    /// A rectangle, as for a step in a process.
+    #[default]
+    Rectangle,
+    /// A rectangle with rounded corners, as for the start or end of a process.
+    Rounded,
+    /// An ellipse.
+    Ellipse,
+    /// A diamond, as for a decision.
+    Diamond,
+}
+
+/// How a [Diagram] node is drawn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodeStyle {
+    /// The outline around the text.
+    pub shape: NodeShape,
+    /// The stroke of the outline.
+    pub stroke: Option<Stroke>,
+    /// The fill inside the outline.
+    pub fill: Option<Color>,
+    /// Space between the text and the outline.
+    pub padding: f32,
+}
+
+impl NodeStyle {
+    /// Create a new [NodeStyle] with an unfilled outline.
+    pub fn new(shape: NodeShape, stroke: Stroke, padding: f32) -> Self {
+        Self {
+            shape,
+            stroke: Some(stroke),
+            fill: None,
+            padding,
+        }
+    }
+
+    /// Set the fill, consuming the parent.
+    pub fn with_fill(mut self, fill: Color) -> Self {
+        self.fill = Some(fill);
+        self
+    }
+}
+
+/// How a [Diagram] edge is drawn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EdgeStyle {
+    /// The stroke of the line.
+    pub stroke: Stroke,
+    /// Length of the arrowhead where the edge meets the node it points to, filled in the stroke's color. No arrowhead is drawn if this is zero.
+    pub arrow_size: f32,
+}
+
+impl EdgeStyle {
+    /// Create a new [EdgeStyle] without an arrowhead.
+    pub fn new(stroke: Stroke) -> Self {
+        Self {
+            stroke,
+            arrow_size: 0.0,
+        }
+    }
+
+    /// Set the length of the arrowhead, consuming the parent.
+    pub fn with_arrows(mut self, arrow_size: f32) -> Self {
+        self.arrow_size = arrow_size;
+        self
+    }
+}
+
+/// A box of text in a [Diagram].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DiagramNode {
+    /// The text written in the node.
+    pub text: String,
+    /// How the node is drawn.
+    pub style: NodeStyle,
+}
+
+/// An arrow from one [Diagram] node to another.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DiagramEdge {
+    /// The node the edge leaves.
+    pub from: usize,
+    /// The node the edge points to.
+    pub to: usize,
+    /// Text written beside the middle of the edge, such as "yes" or "no" after a decision.
+    pub label: Option<String>,
+    /// How the edge is drawn.
+    pub style: EdgeStyle,
+}
+
+/// A flowchart or other diagram of nodes and edges, described in code and laid out automatically, like a small Graphviz.
+///
+/// Nodes are sized to fit their text, ranked into rows down the page with a [LayeredLayout], and joined by edges routed around other nodes with a [Router].
+/// Text is measured by a function given to [layout](Self::layout), as with [chart text](crate::chart::ChartText),
+/// and the laid out [DiagramText] says where to write it.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Diagram {
+    /// Every node, numbered in the order they were added.
+    pub nodes: Vec<DiagramNode>,
+    /// Every edge.
+    pub edges: Vec<DiagramEdge>,
+    /// Space between neighbouring nodes in a row.
+    pub node_gap: f32,
+    /// Space between rows.
+    pub layer_gap: f32,
+    /// The kind of path edges take.
+    pub routing: Routing,
+}
+
+/// Text to write on a laid out [Diagram].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DiagramText {
+    /// The text.
+    pub text: String,
+    /// The middle of the text.
+    pub position: Vec2,
+    /// The width and height of the text, as measured.
+    pub size: Vec2,
+}
+
+/// Where a [Diagram] was laid out.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DiagramLayout {
+    /// The closed outline of each node.
+    pub outlines: Vec<Vec<Vec2>>,
+    /// The path of each edge, from the outline of one node to the outline of the other.
+    pub routes: Vec<Vec<Vec2>>,
+    /// The text of each node, in order, then the label of each edge that has one.
+    pub texts: Vec<DiagramText>,
+}
+
+impl Diagram {
+    /// Create a new, empty [Diagram] with straight edges.
+    pub fn new(node_gap: f32, layer_gap: f32) -> Self {
+        Self {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            node_gap,
+            layer_gap,
+            routing: Routing::Straight,
+        }
+    }
+
+    /// Set the kind of path edges take, consuming the parent.
+    pub fn with_routing(mut self, routing: Routing) -> Self {
+        self.routing = routing;
+        self
+    }
+
+    /// Add a node, returning its number.
+    pub fn node(&mut self, text: impl Into<String>, style: NodeStyle) -> usize {
+        self.nodes.push(DiagramNode {
+            text: text.into(),
+            style,
+        });
+        self.nodes.len() - 1
+    }
+
+    /// Add an edge from one node to another.
+    pub fn edge(&mut self, from: usize, to: usize, style: EdgeStyle) {
+        self.edges.push(DiagramEdge {
+            from,
+            to,
+            label: None,
+            style,
+        });
+    }
+
+    /// Add an edge from one node to another, with a label beside it.
+    pub fn labeled_edge(
+        &mut self,
+        from: usize,
+        to: usize,
+        label: impl Into<String>,
+        style: EdgeStyle,
+    ) {
+        self.edges.push(DiagramEdge {
+            from,
+            to,
+            label: Some(label.into()),
+            style,
+        });
+    }
+
+    /// Lay out the diagram, with `measure` giving the width and height of each piece of text.
+    pub fn layout<M: Fn(&str) -> Vec2>(&self, measure: M) -> DiagramLayout {
+        let text_sizes: Vec<Vec2> = self.nodes.iter().map(|node| measure(&node.text)).collect();
+        let sizes: Vec<Vec2> = self
+            .nodes
+            .iter()
+            .zip(&text_sizes)
+            .map(|(node, &text)| outline_size(node.style.shape, text + node.style.padding * 2.0))
+            .collect();
+        let largest = sizes
+            .iter()
+            .fold(Vec2::ZERO, |largest, &size| largest.max(size));
+
+        let graph = Graph::new(
+            self.nodes.len(),
+            self.edges.iter().map(|edge| (edge.from, edge.to)).collect(),
+        );
+        let layout = LayeredLayout::new(largest.y + self.layer_gap, largest.x + self.node_gap);
+        let positions = graph.layered(&layout).positions;
+
+        let outlines: Vec<Vec<Vec2>> = self
+            .nodes
+            .iter()
+            .zip(positions.iter().zip(&sizes))
+            .map(|(node, (&center, &size))| outline(node.style.shape, center, size))
+            .collect();
+
+        let mut router =
+            Router::new(outlines.clone(), self.node_gap / 4.0).with_routing(self.routing);
+        router.corner_radius = self.node_gap / 2.0;
+        let routes: Vec<Vec<Vec2>> = self
+            .edges
+            .iter()
+            .map(|edge| {
+                if edge.from < outlines.len() && edge.to < outlines.len() && edge.from != edge.to {
+                    router.route(&outlines[edge.from], &outlines[edge.to])
+                } else {
+                    Vec::new()
+                }
+            })
+            .collect();
+
+        let mut texts: Vec<DiagramText> = self
+            .nodes
+            .iter()
+            .zip(positions.iter().zip(text_sizes))
+            .map(|(node, (&position, size))| DiagramText {
+                text: node.text.clone(),
+                position,
+                size,
+            })
+            .collect();
+
+        // Labels sit beside the middle of their edge, clear of the line.
+        for (edge, route) in self.edges.iter().zip(&routes) {
+            let Some(label) = &edge.label else {
+                continue;
+            };
+            let Some((middle, direction)) = halfway(route) else {
+                continue;
+            };
+            let size = measure(label);
+            let normal = direction.perp();
+            let clearance = size.dot(normal.abs()) / 2.0 + edge.style.stroke.width * 2.0;
+            texts.push(DiagramText {
+                text: label.clone(),
+                position: middle + normal * clearance,
+                size,
+            });
+        }
+
+        DiagramLayout {
+            outlines,
+            routes,
+            texts,
+        }
+    }
+}
+
+impl Canvas {
+    /// Draw a laid out diagram in World Space: each edge, then each node on top. The text is left to the caller, as laid out in [DiagramLayout::texts].
+    pub fn draw_diagram(&mut self, diagram: &Diagram, layout: &DiagramLayout) {
+        for (edge, route) in diagram.edges.iter().zip(&layout.routes) {
+            let style = edge.style;
+            let Some(trimmed) = trim_route(route, 0.0, style.arrow_size) else {
+                continue;
+            };
+            self.draw_polyline_absolute(trimmed.clone(), style.stroke);
+
+            if style.arrow_size > 0.0 {
+                let tip = route[route.len() - 1];
+                let base = trimmed[trimmed.len() - 1];
+                let side = (tip - base).normalize_or_zero().perp() * style.arrow_size * 0.4;
+                self.draw_shape_absolute(
+                    vec![tip, base + side, base - side, tip],
+                    None,
+                    Some(style.stroke.color),
+                );
+            }
+        }
+
+        for (node, outline) in diagram.nodes.iter().zip(&layout.outlines) {
+            self.draw_shape_absolute(outline.clone(), node.style.stroke, node.style.fill);
+        }
+    }
+}
+
+/// The size of an outline that fits a box of `size` inside it.
+fn outline_size(shape: NodeShape, size: Vec2) -> Vec2 {
+    match shape {
+        NodeShape::Rectangle | NodeShape::Rounded => size,
+        NodeShape::Ellipse => size * SQRT_2,
+        NodeShape::Diamond => size * 2.0,
+    }
+}
+
+/// The closed outline of a node of `size`, centered on `center`.
+fn outline(shape: NodeShape, center: Vec2, size: Vec2) -> Vec<Vec2> {
+    let half = size / 2.0;
+    let rectangle = vec![
+        center - half,
+        center + Vec2::new(half.x, -half.y),
+        center + half,
+        center + Vec2::new(-half.x, half.y),
+        center - half,
+    ];
+
+    match shape {
+        NodeShape::Rectangle => rectangle,
+        NodeShape::Rounded => {
+            let shape = Shape {
+                points: rectangle,
+                ..Default::default()
+            };
+            fillet(&shape, half.min_element() / 2.0).points
+        }
+        NodeShape::Ellipse => {
+            const STEPS: usize = 48;
+            (0..=STEPS)
+                .map(|i| {
+                    let angle = TAU * i as f32 / STEPS as f32;
+                    center + Vec2::new(angle.cos(), angle.sin()) * half
+                })
+                .collect()
+        }
+        NodeShape::Diamond => vec![
+            center + Vec2::new(0.0, -half.y),
+            center + Vec2::new(half.x, 0.0),
+            center + Vec2::new(0.0, half.y),
+            center + Vec2::new(-half.x, 0.0),
+            center + Vec2::new(0.0, -half.y),
+        ],
+    }
+}
+
+/// The point halfway along a route, and the direction the route runs there.
+fn halfway(route: &[Vec2]) -> Option<(Vec2, Vec2)> {
+    let lengths = arc_lengths(route);
+    let half = lengths.last()? / 2.0;
+    let segment = lengths.windows(2).position(|pair| pair[1] >= half)?;
+
+    let (a, b) = (route[segment], route[segment + 1]);
+    let span = lengths[segment + 1] - lengths[segment];
+    let t = if span > 0.0 {
+        (half - lengths[segment]) / span
+    } else {
+        0.0
+    };
+    Some((a.lerp(b, t), (b - a).normalize_or_zero()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{geometry::contains_point, LineEnd};
+
+    /// Verify that nodes are sized around their text, ranked down the page, and joined by edges between their outlines.
+    #[test]
+    fn flowchart() {
+        let stroke = Stroke::new(Color::black(), 0.1, LineEnd::Butt);
+        let edge_style = EdgeStyle::new(stroke).with_arrows(0.5);
+
+        let mut diagram = Diagram::new(2.0, 2.0);
+        let start = diagram.node("Start", NodeStyle::new(NodeShape::Rounded, stroke, 0.5));
+        let check = diagram.node("Ready?", NodeStyle::new(NodeShape::Diamond, stroke, 0.5));
+        let wait = diagram.node("Wait", NodeStyle::new(NodeShape::Rectangle, stroke, 0.5));
+        let end = diagram.node("End", NodeStyle::new(NodeShape::Ellipse, stroke, 0.5));
+        diagram.edge(start, check, edge_style);
+        diagram.labeled_edge(check, wait, "no", edge_style);
+        diagram.labeled_edge(check, end, "yes", edge_style);
+        diagram.edge(wait, check, edge_style);
+
+        let measure = |text: &str| Vec2::new(text.len() as f32 * 0.6, 1.0);
+        let layout = diagram.layout(measure);
+
+        assert_eq!(layout.texts.len(), 6);
+        assert_eq!(layout.texts[4].text, "no");
+        for (text, outline) in layout.texts.iter().zip(&layout.outlines) {
+            let half = text.size / 2.0;
+            for corner in [text.position - half, text.position + half] {
+                assert!(contains_point(
+                    outline,
+                    corner + (text.position - corner) * 0.01
+                ));
+            }
+        }
+        assert!(layout.texts[start].position.y > layout.texts[check].position.y);
+        assert!(layout.texts[check].position.y > layout.texts[end].position.y);
+
+        for (edge, route) in diagram.edges.iter().zip(&layout.routes) {
+            assert!(route.len() >= 2);
+            assert!(!contains_point(&layout.outlines[edge.from], route[1]));
+            assert!(!contains_point(
+                &layout.outlines[edge.to],
+                route[route.len() - 2]
+            ));
+        }
+
+        let mut canvas = Canvas::new(100);
+        canvas.draw_diagram(&diagram, &layout);
+        assert_eq!(canvas.as_raw().len(), 4 * 2 + 4);
+    }
+}
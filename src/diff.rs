@@ -0,0 +1,181 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use crate::{Canvas, Color, Shape};
+
+/// The differences between the shapes of two canvases, as found by [Canvas::diff].
+///
+/// Shapes are matched regardless of draw order. A shape is [changed](Self::changed) if only its style differs, such as its color or stroke.
+/// Moving a shape at all makes it both [removed](Self::removed) and [added](Self::added).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CanvasDiff {
+    /// Shapes only in the new canvas.
+    pub added: Vec<Shape>,
+    /// Shapes only in the old canvas.
+    pub removed: Vec<Shape>,
+    /// Shapes with the same geometry in both canvases, but a different style, as `(old, new)`.
+    pub changed: Vec<(Shape, Shape)>,
+    /// Shapes that are identical in both canvases.
+    pub unchanged: Vec<Shape>,
+}
+
+impl CanvasDiff {
+    /// Compare the shapes of an old and a new canvas.
+    pub(crate) fn new(old: &[Shape], new: &[Shape]) -> Self {
+        let mut diff = Self::default();
+
+        // Shapes with the same geometry, which are the only candidates for matching.
+        let mut candidates: HashMap<u64, Vec<usize>> = HashMap::new();
+        for (index, shape) in new.iter().enumerate() {
+            candidates
+                .entry(geometry_hash(shape))
+                .or_default()
+                .push(index);
+        }
+
+        let mut matched = vec![false; new.len()];
+        let mut unmatched = Vec::new();
+
+        for shape in old {
+            let same_geometry = candidates.get(&geometry_hash(shape));
+            let identical = same_geometry.and_then(|indices| {
+                indices
+                    .iter()
+                    .copied()
+                    .find(|&index| !matched[index] && new[index] == *shape)
+            });
+
+            match identical {
+                Some(index) => {
+                    matched[index] = true;
+                    diff.unchanged.push(shape.clone());
+                }
+                None => unmatched.push(shape),
+            }
+        }
+
+        for shape in unmatched {
+            let restyled = candidates.get(&geometry_hash(shape)).and_then(|indices| {
+                indices.iter().copied().find(|&index| {
+                    !matched[index]
+                        && new[index].points == shape.points
+                        && new[index].subpaths == shape.subpaths
+                })
+            });
+
+            match restyled {
+                Some(index) => {
+                    matched[index] = true;
+                    diff.changed.push((shape.clone(), new[index].clone()));
+                }
+                None => diff.removed.push(shape.clone()),
+            }
+        }
+
+        diff.added = new
+            .iter()
+            .zip(matched)
+            .filter(|(_, matched)| !matched)
+            .map(|(shape, _)| shape.clone())
+            .collect();
+
+        diff
+    }
+
+    /// Checks if the canvases have exactly the same shapes, ignoring draw order.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Draw the differences onto `canvas`: unchanged shapes faintly in gray, old shapes in red, and new shapes in green.
+    ///
+    /// Changed shapes are drawn twice, once in each color.
+    pub fn draw_overlay(&self, canvas: &mut Canvas) {
+        let gray = Color::new(0.5, 0.5, 0.5, 0.25);
+        let red = Color::red().with_a(0.5);
+        let green = Color::green().with_a(0.5);
+
+        for shape in &self.unchanged {
+            canvas.draw_raw_shape(highlight(shape, gray));
+        }
+        for shape in self
+            .removed
+            .iter()
+            .chain(self.changed.iter().map(|(old, _)| old))
+        {
+            canvas.draw_raw_shape(highlight(shape, red));
+        }
+        for shape in self
+            .added
+            .iter()
+            .chain(self.changed.iter().map(|(_, new)| new))
+        {
+            canvas.draw_raw_shape(highlight(shape, green));
+        }
+    }
+}
+
+/// A copy of `shape`, with its stroke and fill replaced by `color`.
+fn highlight(shape: &Shape, color: Color) -> Shape {
+    let mut shape = shape.clone();
+
+    if let Some(stroke) = &mut shape.stroke {
+        stroke.color = color;
+    }
+    if shape.fill.is_some() {
+        shape.fill = Some(color);
+    }
+
+    shape
+}
+
+/// A hash of every point in a shape, ignoring its style.
+fn geometry_hash(shape: &Shape) -> u64 {
+    let mut hasher = DefaultHasher::new();
+
+    for path in shape.paths() {
+        path.len().hash(&mut hasher);
+        for point in path {
+            point.x.to_bits().hash(&mut hasher);
+            point.y.to_bits().hash(&mut hasher);
+        }
+    }
+
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Vec2;
+
+    /// Verify that shapes are sorted into added, removed, changed and unchanged, regardless of draw order.
+    #[test]
+    fn diff_canvases() {
+        let mut old = Canvas::default();
+        old.draw_circle(Vec2::ZERO, 1.0, None, Some(Color::black()));
+        old.draw_rect(Vec2::ZERO, Vec2::ONE, None, Some(Color::black()));
+        old.draw_line(Vec2::ZERO, Vec2::ONE, None, Some(Color::black()));
+
+        let mut new = Canvas::default();
+        new.draw_rect(Vec2::ZERO, Vec2::ONE, None, Some(Color::blue()));
+        new.draw_circle(Vec2::ZERO, 1.0, None, Some(Color::black()));
+        new.draw_line(Vec2::ZERO, Vec2::X, None, Some(Color::black()));
+
+        let diff = old.diff(&new);
+        assert!(!diff.is_empty());
+        assert_eq!(diff.unchanged, vec![old.as_raw()[0].clone()]);
+        assert_eq!(
+            diff.changed,
+            vec![(old.as_raw()[1].clone(), new.as_raw()[0].clone())]
+        );
+        assert_eq!(diff.removed, vec![old.as_raw()[2].clone()]);
+        assert_eq!(diff.added, vec![new.as_raw()[2].clone()]);
+
+        let mut overlay = Canvas::default();
+        diff.draw_overlay(&mut overlay);
+        assert_eq!(overlay.as_raw().len(), 5);
+        assert!(old.diff(&old).is_empty());
+    }
+}
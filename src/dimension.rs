@@ -0,0 +1,432 @@
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+use glam::Vec2;
+
+use crate::{Canvas, Stroke};
+
+/// A measurement to mark on a technical drawing, such as the width of a part or the angle between two edges.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Dimension {
+    /// The horizontal or vertical distance between two points, whatever the angle between them.
+    Linear {
+        /// The first point measured.
+        from: Vec2,
+        /// The second point measured.
+        to: Vec2,
+        /// Measure the vertical distance rather than the horizontal.
+        vertical: bool,
+        /// How far the dimension line sits past the farthest point, above or to the left when positive, below or to the right when negative.
+        offset: f32,
+    },
+    /// The straight distance between two points, with the dimension line parallel to them.
+    Aligned {
+        /// The first point measured.
+        from: Vec2,
+        /// The second point measured.
+        to: Vec2,
+        /// How far the dimension line sits from the points, to the left when looking from `from` to `to` when positive.
+        offset: f32,
+    },
+    /// The angle counter-clockwise from one direction to another around a center, such as a corner or the end of an arc.
+    Angular {
+        /// The point the angle is measured around.
+        center: Vec2,
+        /// A point along the first direction.
+        from: Vec2,
+        /// A point along the second direction.
+        to: Vec2,
+        /// The radius of the dimension arc.
+        radius: f32,
+    },
+    /// The radius or diameter of a circle or arc.
+    Radial {
+        /// The center of the circle.
+        center: Vec2,
+        /// The radius of the circle.
+        radius: f32,
+        /// The direction the dimension line points, in radians counter-clockwise from the x axis.
+        angle: f32,
+        /// Measure the diameter, with the line running right across the circle.
+        diameter: bool,
+    },
+}
+
+/// How [Dimensions](Dimension) are drawn and their measurements written.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DimensionStyle {
+    /// The stroke of dimension and extension lines. Arrowheads are filled in its color.
+    pub stroke: Stroke,
+    /// Length of each arrowhead.
+    pub arrow_size: f32,
+    /// Space between a measured point and the start of its extension line.
+    pub extension_gap: f32,
+    /// How far extension lines carry on past the dimension line.
+    pub extension_overshoot: f32,
+    /// Distance from the dimension line to the middle of the measurement text.
+    pub text_offset: f32,
+    /// How many units of measurement there are to each unit on the canvas, such as `10.0` to write millimeters on a drawing in centimeters.
+    pub scale: f32,
+    /// Written after each length, such as `" mm"`.
+    pub suffix: String,
+    /// Digits written after the decimal point.
+    pub precision: usize,
+}
+
+impl DimensionStyle {
+    /// Create a new [DimensionStyle], with arrowheads and gaps sized to the stroke, writing lengths in canvas units to two decimal places.
+    pub fn new(stroke: Stroke) -> Self {
+        Self {
+            stroke,
+            arrow_size: stroke.width * 8.0,
+            extension_gap: stroke.width * 3.0,
+            extension_overshoot: stroke.width * 4.0,
+            text_offset: stroke.width * 8.0,
+            scale: 1.0,
+            suffix: String::new(),
+            precision: 2,
+        }
+    }
+
+    /// Set the units lengths are written in, consuming the parent.
+    pub fn with_units(mut self, scale: f32, suffix: impl Into<String>) -> Self {
+        self.scale = scale;
+        self.suffix = suffix.into();
+        self
+    }
+
+    /// Set the digits written after the decimal point, consuming the parent.
+    pub fn with_precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Write a length on the canvas in the style's units, such as `"12.50 mm"`.
+    pub fn format_length(&self, length: f32) -> String {
+        format!("{:.*}{}", self.precision, length * self.scale, self.suffix)
+    }
+
+    /// Write an angle in radians as degrees, such as `"45.0°"`.
+    pub fn format_angle(&self, angle: f32) -> String {
+        format!("{:.*}°", self.precision, angle.to_degrees())
+    }
+}
+
+/// The measurement of a [Dimension], and where to write it. Drawing it is up to the caller, as with [chart text](crate::chart::ChartText).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DimensionText {
+    /// The formatted measurement, such as `"12.50 mm"`, `"R5.00"` or `"45.00°"`.
+    pub text: String,
+    /// The middle of the text.
+    pub position: Vec2,
+    /// The angle of the text's baseline, in radians counter-clockwise from the x axis, turned so that the text never reads upside down.
+    pub angle: f32,
+}
+
+/// The lines, arrowheads and text that make up a drawn [Dimension].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DimensionDrawing {
+    /// Dimension and extension lines, as polylines.
+    pub lines: Vec<Vec<Vec2>>,
+    /// Arrowheads, as closed triangles to fill.
+    pub arrows: Vec<Vec<Vec2>>,
+    /// The measurement and where it goes.
+    pub text: DimensionText,
+}
+
+impl Dimension {
+    /// The measured value: a length in canvas units, or an angle in radians.
+    pub fn measurement(&self) -> f32 {
+        match *self {
+            Dimension::Linear {
+                from, to, vertical, ..
+            } => {
+                if vertical {
+                    (to.y - from.y).abs()
+                } else {
+                    (to.x - from.x).abs()
+                }
+            }
+            Dimension::Aligned { from, to, .. } => from.distance(to),
+            Dimension::Angular {
+                center, from, to, ..
+            } => sweep(center, from, to).1,
+            Dimension::Radial {
+                radius, diameter, ..
+            } => {
+                if diameter {
+                    radius * 2.0
+                } else {
+                    radius
+                }
+            }
+        }
+    }
+
+    /// Work out the lines, arrowheads and text that mark the dimension.
+    pub fn drawing(&self, style: &DimensionStyle) -> DimensionDrawing {
+        match *self {
+            Dimension::Linear {
+                from,
+                to,
+                vertical,
+                offset,
+            } => {
+                let along = if vertical { Vec2::Y } else { Vec2::X };
+                let across = along.perp();
+                let level = if offset >= 0.0 {
+                    from.dot(across).max(to.dot(across)) + offset
+                } else {
+                    from.dot(across).min(to.dot(across)) + offset
+                };
+                self.straight(style, from, to, along, across, level)
+            }
+            Dimension::Aligned { from, to, offset } => {
+                let along = (to - from).normalize_or_zero();
+                let along = if along == Vec2::ZERO { Vec2::X } else { along };
+                let across = along.perp();
+                self.straight(style, from, to, along, across, from.dot(across) + offset)
+            }
+            Dimension::Angular {
+                center,
+                from,
+                to,
+                radius,
+            } => {
+                let (start, angle) = sweep(center, from, to);
+                let steps = (angle / (TAU / 96.0)).ceil().max(1.0) as usize;
+                let arc: Vec<Vec2> = (0..=steps)
+                    .map(|i| center + direction(start + angle * i as f32 / steps as f32) * radius)
+                    .collect();
+
+                let mut lines = Vec::new();
+                for (point, angle) in [(from, start), (to, start + angle)] {
+                    let distance = point.distance(center);
+                    if radius > distance + style.extension_gap {
+                        lines.push(vec![
+                            center + direction(angle) * (distance + style.extension_gap),
+                            center + direction(angle) * (radius + style.extension_overshoot),
+                        ]);
+                    }
+                }
+
+                let end = start + angle;
+                let arrows = vec![
+                    arrowhead(arc[0], -direction(start + FRAC_PI_2), style.arrow_size),
+                    arrowhead(
+                        arc[arc.len() - 1],
+                        direction(end + FRAC_PI_2),
+                        style.arrow_size,
+                    ),
+                ];
+                lines.insert(0, arc);
+
+                let middle = start + angle / 2.0;
+                DimensionDrawing {
+                    lines,
+                    arrows,
+                    text: DimensionText {
+                        text: style.format_angle(angle),
+                        position: center + direction(middle) * (radius + style.text_offset),
+                        angle: upright(middle - FRAC_PI_2),
+                    },
+                }
+            }
+            Dimension::Radial {
+                center,
+                radius,
+                angle,
+                diameter,
+            } => {
+                let outward = direction(angle);
+                let end = center + outward * radius;
+                let (start, arrows, prefix) = if diameter {
+                    let start = center - outward * radius;
+                    let arrows = vec![
+                        arrowhead(start, -outward, style.arrow_size),
+                        arrowhead(end, outward, style.arrow_size),
+                    ];
+                    (start, arrows, "⌀")
+                } else {
+                    (center, vec![arrowhead(end, outward, style.arrow_size)], "R")
+                };
+
+                DimensionDrawing {
+                    lines: vec![vec![start, end]],
+                    arrows,
+                    text: DimensionText {
+                        text: format!("{}{}", prefix, style.format_length(self.measurement())),
+                        position: (start + end) / 2.0 + outward.perp() * style.text_offset,
+                        angle: upright(angle),
+                    },
+                }
+            }
+        }
+    }
+
+    /// A dimension line along `along`, at `level` across it, with extension lines back to the points.
+    fn straight(
+        &self,
+        style: &DimensionStyle,
+        from: Vec2,
+        to: Vec2,
+        along: Vec2,
+        across: Vec2,
+        level: f32,
+    ) -> DimensionDrawing {
+        let start = along * from.dot(along) + across * level;
+        let end = along * to.dot(along) + across * level;
+
+        let mut lines = vec![vec![start, end]];
+        for (point, foot) in [(from, start), (to, end)] {
+            let distance = level - point.dot(across);
+            if distance.abs() > style.extension_gap {
+                let outward = across * distance.signum();
+                lines.push(vec![
+                    point + outward * style.extension_gap,
+                    foot + outward * style.extension_overshoot,
+                ]);
+            }
+        }
+
+        let inward = (end - start).normalize_or_zero();
+        let side = if level >= from.dot(across).min(to.dot(across)) {
+            across
+        } else {
+            -across
+        };
+        DimensionDrawing {
+            lines,
+            arrows: vec![
+                arrowhead(start, -inward, style.arrow_size),
+                arrowhead(end, inward, style.arrow_size),
+            ],
+            text: DimensionText {
+                text: style.format_length(self.measurement()),
+                position: (start + end) / 2.0 + side * style.text_offset,
+                angle: upright(along.y.atan2(along.x)),
+            },
+        }
+    }
+}
+
+impl Canvas {
+    /// Draw the lines and arrowheads of a dimension in World Space, returning the measurement and where to write it.
+    pub fn draw_dimension(
+        &mut self,
+        dimension: &Dimension,
+        style: &DimensionStyle,
+    ) -> DimensionText {
+        let drawing = dimension.drawing(style);
+        for line in drawing.lines {
+            self.draw_polyline_absolute(line, style.stroke);
+        }
+        for arrow in drawing.arrows {
+            self.draw_shape_absolute(arrow, None, Some(style.stroke.color));
+        }
+        drawing.text
+    }
+}
+
+/// The angle of the direction from `center` to `from`, and the angle counter-clockwise from there to the direction to `to`.
+fn sweep(center: Vec2, from: Vec2, to: Vec2) -> (f32, f32) {
+    let start = (from - center).y.atan2((from - center).x);
+    let end = (to - center).y.atan2((to - center).x);
+    (start, (end - start).rem_euclid(TAU))
+}
+
+/// The unit vector at `angle` radians counter-clockwise from the x axis.
+fn direction(angle: f32) -> Vec2 {
+    Vec2::new(angle.cos(), angle.sin())
+}
+
+/// `angle`, turned half a turn if needed so that text along it reads left to right.
+fn upright(angle: f32) -> f32 {
+    let angle = (angle + PI).rem_euclid(TAU) - PI;
+    if angle > FRAC_PI_2 + 1e-4 {
+        angle - PI
+    } else if angle <= -FRAC_PI_2 + 1e-4 {
+        angle + PI
+    } else {
+        angle
+    }
+}
+
+/// A closed triangle with its tip at `tip`, pointing along `direction`.
+fn arrowhead(tip: Vec2, direction: Vec2, size: f32) -> Vec<Vec2> {
+    let base = tip - direction * size;
+    let side = direction.perp() * size * 0.3;
+    vec![tip, base + side, base - side, tip]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Color, LineEnd};
+
+    /// Verify the measurement, formatting and placement of each kind of dimension.
+    #[test]
+    fn dimensions() {
+        let style = DimensionStyle::new(Stroke::new(Color::black(), 0.1, LineEnd::Butt))
+            .with_units(10.0, " mm")
+            .with_precision(1);
+
+        let linear = Dimension::Linear {
+            from: Vec2::new(0.0, 0.0),
+            to: Vec2::new(3.0, 1.0),
+            vertical: false,
+            offset: 2.0,
+        };
+        let drawing = linear.drawing(&style);
+        assert_eq!(drawing.text.text, "30.0 mm");
+        assert_eq!(
+            drawing.lines[0],
+            vec![Vec2::new(0.0, 3.0), Vec2::new(3.0, 3.0)]
+        );
+        assert_eq!(drawing.lines.len(), 3);
+        assert_eq!(drawing.arrows.len(), 2);
+        assert!(drawing.text.position.y > 3.0);
+        assert_eq!(drawing.text.angle, 0.0);
+
+        let aligned = Dimension::Aligned {
+            from: Vec2::new(0.0, 0.0),
+            to: Vec2::new(-3.0, -4.0),
+            offset: 1.0,
+        };
+        let drawing = aligned.drawing(&style);
+        assert_eq!(drawing.text.text, "50.0 mm");
+        assert!(drawing.text.angle.abs() <= FRAC_PI_2);
+        assert!((drawing.lines[0][0].distance(drawing.lines[0][1]) - 5.0).abs() < 1e-4);
+
+        let angular = Dimension::Angular {
+            center: Vec2::ZERO,
+            from: Vec2::new(1.0, 0.0),
+            to: Vec2::new(0.0, 2.0),
+            radius: 3.0,
+        };
+        assert!((angular.measurement() - FRAC_PI_2).abs() < 1e-5);
+        let drawing = angular.drawing(&style);
+        assert_eq!(drawing.text.text, "90.0°");
+        assert!(drawing.lines[0]
+            .iter()
+            .all(|point| (point.length() - 3.0).abs() < 1e-4));
+
+        let radial = Dimension::Radial {
+            center: Vec2::ZERO,
+            radius: 2.5,
+            angle: PI,
+            diameter: true,
+        };
+        let drawing = radial.drawing(&style);
+        assert_eq!(drawing.text.text, "⌀50.0 mm");
+        assert!(drawing.text.angle.abs() < 1e-4);
+
+        let mut canvas = Canvas::new(100);
+        let text = canvas.draw_dimension(&linear, &style);
+        assert_eq!(text.text, "30.0 mm");
+        assert_eq!(canvas.as_raw().len(), 5);
+    }
+}
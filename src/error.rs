@@ -0,0 +1,80 @@
+use std::fmt::{self, Display};
+
+use glam::Vec2;
+
+use crate::ValidationIssue;
+
+/// The error type for fallible `barium` operations.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// A hex color string could not be parsed.
+    InvalidHex(String),
+    /// An HSV component was outside of `0.0..=1.0`.
+    HsvOutOfRange,
+    /// A color contained a channel that was not a finite number.
+    NonFiniteColor,
+    /// A renderer was asked to produce an image with an unusable size.
+    InvalidSize {
+        /// The requested width.
+        width: u32,
+        /// The requested height.
+        height: u32,
+    },
+    /// A region of World Space to [render](crate::Canvas::render_region) had no area, wasn't finite, or didn't match the shape of the image.
+    InvalidRegion {
+        /// The bottom left corner of the region.
+        min: Vec2,
+        /// The top right corner of the region.
+        max: Vec2,
+    },
+    /// A shape could not be turned into a path, usually because it contains non-finite points.
+    InvalidPath,
+    /// Writing to the output failed.
+    Format,
+    /// The canvas failed [validation](crate::Canvas::validate).
+    InvalidGeometry(Vec<ValidationIssue>),
+    /// A Wavefront OBJ file could not be parsed.
+    InvalidObj {
+        /// The line the problem was found on, counting from 1.
+        line: usize,
+    },
+    /// Data was too long to fit in the largest [QrCode](crate::codes::QrCode).
+    DataTooLong,
+    /// Text could not be encoded as a [Barcode](crate::codes::Barcode), because of a character it can't hold or a wrong check digit.
+    InvalidBarcode(String),
+    /// A [Theme](crate::Theme) could not be read, such as from malformed TOML or an unknown base theme.
+    InvalidTheme(String),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidHex(hex) => write!(f, "invalid hex color: {:?}", hex),
+            Error::HsvOutOfRange => write!(f, "all HSV values must be within 0.0..=1.0"),
+            Error::NonFiniteColor => write!(f, "color channels must be finite"),
+            Error::InvalidSize { width, height } => {
+                write!(f, "invalid output size: {}x{}", width, height)
+            }
+            Error::InvalidRegion { min, max } => {
+                write!(f, "invalid region to render: {} to {}", min, max)
+            }
+            Error::InvalidPath => write!(f, "shape could not be converted into a path"),
+            Error::Format => write!(f, "failed to write output"),
+            Error::InvalidGeometry(issues) => {
+                write!(f, "canvas contains {} geometry issue(s)", issues.len())
+            }
+            Error::InvalidObj { line } => write!(f, "invalid OBJ file on line {}", line),
+            Error::DataTooLong => write!(f, "data is too long for a QR code"),
+            Error::InvalidBarcode(text) => write!(f, "cannot encode {:?} as a barcode", text),
+            Error::InvalidTheme(message) => write!(f, "invalid theme: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<fmt::Error> for Error {
+    fn from(_: fmt::Error) -> Self {
+        Error::Format
+    }
+}
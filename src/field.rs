@@ -0,0 +1,148 @@
+use glam::Vec2;
+#[cfg(feature = "tiny_skia_renderer")]
+use image::RgbaImage;
+
+#[cfg(feature = "tiny_skia_renderer")]
+use crate::{raster::Trace, Color, Gradient, Shape};
+
+/// A grid of values, such as the trail of a [Physarum](crate::agents::Physarum) simulation or the chemicals of a
+/// [ReactionDiffusion](crate::reaction::ReactionDiffusion) simulation, which can be turned into a heatmap or traced into shapes.
+///
+/// Cell `(0, 0)` is at the bottom left, and cell `(x, y)` covers the square from `(x, y)` to `(x + 1, y + 1)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScalarField {
+    pub(crate) width: usize,
+    pub(crate) height: usize,
+    pub(crate) values: Vec<f32>,
+}
+
+impl ScalarField {
+    /// Create a [ScalarField] `width` cells across and `height` cells up, filled with zeros.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            values: vec![0.0; width * height],
+        }
+    }
+
+    /// Number of cells across.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Number of cells up.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Every value, row by row from the bottom left.
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+
+    /// The value of the cell containing `position`. The field wraps around at its edges.
+    pub fn get(&self, position: Vec2) -> f32 {
+        self.index(position).map_or(0.0, |index| self.values[index])
+    }
+
+    /// Add to the value of the cell containing `position`.
+    pub fn add(&mut self, position: Vec2, amount: f32) {
+        if let Some(index) = self.index(position) {
+            self.values[index] += amount;
+        }
+    }
+
+    /// Add trail to the cell containing `position`, as [TrailGrid](crate::agents::TrailGrid) did.
+    #[deprecated(note = "use `ScalarField::add`")]
+    pub fn deposit(&mut self, position: Vec2, amount: f32) {
+        self.add(position, amount);
+    }
+
+    /// The largest value in any cell, or zero if every value is negative.
+    pub fn max(&self) -> f32 {
+        self.values.iter().copied().fold(0.0, f32::max)
+    }
+
+    /// Blend every cell towards the average of its 3×3 neighbourhood by `amount`, from 0.0 to 1.0, then multiply it by `1.0 - decay`.
+    pub fn diffuse(&mut self, amount: f32, decay: f32) {
+        let values = &self.values;
+        let blurred: Vec<f32> = (0..self.values.len())
+            .map(|index| {
+                let sum: f32 = self.neighborhood(index).map(|i| values[i]).sum();
+                let value = values[index];
+                (value + (sum / 9.0 - value) * amount) * (1.0 - decay)
+            })
+            .collect();
+
+        self.values = blurred;
+    }
+
+    /// Color each cell by its value, relative to the [largest](Self::max), sampled from `gradient`. Each cell is one pixel.
+    ///
+    /// Requires the `tiny_skia_renderer` feature.
+    #[cfg(feature = "tiny_skia_renderer")]
+    pub fn heatmap(&self, gradient: &Gradient) -> RgbaImage {
+        let max = self.max().max(f32::EPSILON);
+
+        RgbaImage::from_fn(self.width as u32, self.height as u32, |x, y| {
+            // Images count rows from the top.
+            let row = self.height - 1 - y as usize;
+            gradient
+                .sample(self.values[row * self.width + x as usize] / max)
+                .into()
+        })
+    }
+
+    /// Trace the areas where the value is at least `threshold` of the [largest](Self::max) into filled shapes,
+    /// in the same space as the field's cells.
+    ///
+    /// Requires the `tiny_skia_renderer` feature.
+    #[cfg(feature = "tiny_skia_renderer")]
+    pub fn to_shapes(&self, threshold: f32, fill: Color) -> Vec<Shape> {
+        let dark = Gradient::new(Color::white(), Color::black());
+        let mut shapes = Trace::new(1.0 - threshold).trace(&self.heatmap(&dark), fill);
+
+        // Traced shapes are centered on the origin, with their points on the centers of the pixels.
+        let half = Vec2::new(self.width as f32, self.height as f32) / 2.0;
+        for shape in &mut shapes {
+            shape.map_points(|point| point + half);
+        }
+        shapes
+    }
+
+    /// The index of the cell containing `position`, wrapping around the edges.
+    fn index(&self, position: Vec2) -> Option<usize> {
+        if self.values.is_empty() || !position.is_finite() {
+            return None;
+        }
+
+        let x = (position.x.floor() as i64).rem_euclid(self.width as i64) as usize;
+        let y = (position.y.floor() as i64).rem_euclid(self.height as i64) as usize;
+        Some(y * self.width + x)
+    }
+
+    /// The indices of the 3×3 block of cells around the cell at `index`, wrapping around the edges. The center cell comes last.
+    pub(crate) fn neighborhood(&self, index: usize) -> impl Iterator<Item = usize> {
+        let (width, height) = (self.width as isize, self.height as isize);
+        let (x, y) = (index as isize % width, index as isize / width);
+
+        [
+            (-1, -1),
+            (0, -1),
+            (1, -1),
+            (-1, 0),
+            (1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+            (0, 0),
+        ]
+        .into_iter()
+        .map(move |(dx, dy)| {
+            let x = (x + dx).rem_euclid(width);
+            let y = (y + dy).rem_euclid(height);
+            (y * width + x) as usize
+        })
+    }
+}
@@ -0,0 +1,439 @@
+use glam::Vec2;
+
+use crate::{
+    geometry::annular_sector,
+    graph::{acyclic_edges, longest_path_layers, Graph},
+    Canvas, Color,
+};
+
+/// An amount flowing from one node to another, such as energy from a source to a use, or people moving between regions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Flow {
+    /// The node the flow leaves.
+    pub from: usize,
+    /// The node the flow enters.
+    pub to: usize,
+    /// How much flows. The thickness of its ribbon is in proportion.
+    pub value: f32,
+}
+
+impl Flow {
+    /// Create a new [Flow].
+    pub fn new(from: usize, to: usize, value: f32) -> Self {
+        Self { from, to, value }
+    }
+}
+
+/// A filled band joining two nodes of a diagram.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ribbon {
+    /// The node the ribbon leaves.
+    pub from: usize,
+    /// The node the ribbon enters.
+    pub to: usize,
+    /// The closed outline of the ribbon.
+    pub points: Vec<Vec2>,
+}
+
+/// Layout for Sankey diagrams, where nodes stand in columns and flows between them are ribbons as thick as their value.
+///
+/// Columns follow the direction of the flows, with nodes that nothing flows out of in the last column.
+/// Flows that would loop back to an earlier column are left out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sankey {
+    /// Width and height of the diagram, with its bottom left corner at the origin.
+    pub size: Vec2,
+    /// Width of each node's bar.
+    pub node_width: f32,
+    /// Space between nodes in the same column.
+    pub node_gap: f32,
+}
+
+/// Where [Sankey] put the parts of a diagram.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SankeyLayout {
+    /// The bottom left and top right corners of each node's bar.
+    pub nodes: Vec<(Vec2, Vec2)>,
+    /// A ribbon for each flow that was laid out, in the order of the flows.
+    pub ribbons: Vec<Ribbon>,
+}
+
+impl Sankey {
+    /// Create a new [Sankey] layout.
+    pub fn new(size: Vec2, node_width: f32, node_gap: f32) -> Self {
+        Self {
+            size,
+            node_width,
+            node_gap,
+        }
+    }
+
+    /// Lay out `nodes` nodes and the flows between them.
+    pub fn layout(&self, nodes: usize, flows: &[Flow]) -> SankeyLayout {
+        let graph = Graph::new(
+            nodes,
+            flows.iter().map(|flow| (flow.from, flow.to)).collect(),
+        );
+        let mut columns = longest_path_layers(nodes, &acyclic_edges(&graph));
+
+        let forward: Vec<&Flow> = flows
+            .iter()
+            .filter(|flow| {
+                flow.from < nodes && flow.to < nodes && columns[flow.from] < columns[flow.to]
+            })
+            .collect();
+
+        // Nodes that nothing flows out of end up in the last column.
+        let last = columns.iter().copied().max().unwrap_or(0);
+        for (node, column) in columns.iter_mut().enumerate() {
+            if !forward.iter().any(|flow| flow.from == node) {
+                *column = last;
+            }
+        }
+
+        let mut incoming = vec![0.0; nodes];
+        let mut outgoing = vec![0.0; nodes];
+        for flow in &forward {
+            outgoing[flow.from] += flow.value.max(0.0);
+            incoming[flow.to] += flow.value.max(0.0);
+        }
+        let values: Vec<f32> = incoming
+            .iter()
+            .zip(&outgoing)
+            .map(|(a, b)| a.max(*b))
+            .collect();
+
+        // One scale for every column, so that the fullest column fills the height.
+        let mut stacks: Vec<Vec<usize>> = vec![Vec::new(); last + 1];
+        for (node, &column) in columns.iter().enumerate() {
+            stacks[column].push(node);
+        }
+        let scale = stacks
+            .iter()
+            .filter(|stack| !stack.is_empty())
+            .map(|stack| {
+                let total: f32 = stack.iter().map(|&node| values[node]).sum();
+                let room = self.size.y - self.node_gap * (stack.len() - 1) as f32;
+                room.max(0.0) / total.max(f32::EPSILON)
+            })
+            .fold(f32::INFINITY, f32::min);
+        let scale = if scale.is_finite() { scale } else { 0.0 };
+
+        let spacing = (self.size.x - self.node_width) / last.max(1) as f32;
+        let mut boxes = vec![(Vec2::ZERO, Vec2::ZERO); nodes];
+        for (column, stack) in stacks.iter().enumerate() {
+            let height = stack.iter().map(|&node| values[node] * scale).sum::<f32>()
+                + self.node_gap * stack.len().saturating_sub(1) as f32;
+            let mut top = (self.size.y + height) / 2.0;
+            let x = column as f32 * spacing;
+
+            for &node in stack {
+                let bottom = top - values[node] * scale;
+                boxes[node] = (Vec2::new(x, bottom), Vec2::new(x + self.node_width, top));
+                top = bottom - self.node_gap;
+            }
+        }
+
+        // Stack flows down each side of a node, in the order of the nodes at their other ends, so that ribbons don't cross needlessly.
+        let middle = |node: usize| (boxes[node].0.y + boxes[node].1.y) / 2.0;
+        let mut leaving: Vec<usize> = (0..forward.len()).collect();
+        leaving.sort_by(|&a, &b| middle(forward[b].to).total_cmp(&middle(forward[a].to)));
+        let mut entering: Vec<usize> = (0..forward.len()).collect();
+        entering.sort_by(|&a, &b| middle(forward[b].from).total_cmp(&middle(forward[a].from)));
+
+        let mut starts = vec![(Vec2::ZERO, Vec2::ZERO); forward.len()];
+        let mut tops: Vec<f32> = boxes.iter().map(|(_, max)| max.y).collect();
+        for &index in &leaving {
+            let flow = forward[index];
+            let (top, x) = (tops[flow.from], boxes[flow.from].1.x);
+            tops[flow.from] -= flow.value.max(0.0) * scale;
+            starts[index] = (Vec2::new(x, top), Vec2::new(x, tops[flow.from]));
+        }
+
+        let mut ends = vec![(Vec2::ZERO, Vec2::ZERO); forward.len()];
+        let mut tops: Vec<f32> = boxes.iter().map(|(_, max)| max.y).collect();
+        for &index in &entering {
+            let flow = forward[index];
+            let (top, x) = (tops[flow.to], boxes[flow.to].0.x);
+            tops[flow.to] -= flow.value.max(0.0) * scale;
+            ends[index] = (Vec2::new(x, top), Vec2::new(x, tops[flow.to]));
+        }
+
+        let ribbons = forward
+            .iter()
+            .zip(starts.into_iter().zip(ends))
+            .map(|(flow, (start, end))| Ribbon {
+                from: flow.from,
+                to: flow.to,
+                points: ribbon(start, end),
+            })
+            .collect();
+
+        SankeyLayout {
+            nodes: boxes,
+            ribbons,
+        }
+    }
+}
+
+/// The closed outline of a band that leaves one vertical edge and curves smoothly across to another,
+/// as in a [Sankey] diagram. Each edge is given as its top and bottom points.
+pub fn ribbon(start: (Vec2, Vec2), end: (Vec2, Vec2)) -> Vec<Vec2> {
+    const STEPS: usize = 24;
+    let curve = |from: Vec2, to: Vec2| {
+        let middle = (from.x + to.x) / 2.0;
+        let (a, b) = (Vec2::new(middle, from.y), Vec2::new(middle, to.y));
+        (0..=STEPS).map(move |i| cubic(from, a, b, to, i as f32 / STEPS as f32))
+    };
+
+    let mut points: Vec<Vec2> = curve(start.0, end.0).collect();
+    points.extend(curve(end.1, start.1));
+    points.push(start.0);
+    points
+}
+
+/// Layout for chord diagrams, where groups sit around a circle and the flows between them are ribbons through the middle.
+///
+/// Each group's arc is as long as the total of its row of the matrix. Within it, the flow to each other group has its own stretch,
+/// and the ribbon between two groups joins the stretch for each direction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Chord {
+    /// The center of the circle.
+    pub center: Vec2,
+    /// The radius where ribbons meet the groups.
+    pub radius: f32,
+    /// How thick each group's arc is, outward from the radius.
+    pub thickness: f32,
+    /// Space between neighbouring groups, in radians.
+    pub gap: f32,
+}
+
+/// Where [Chord] put the parts of a diagram.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChordLayout {
+    /// The start and end angle of each group, in radians counter-clockwise from the x axis.
+    pub angles: Vec<(f32, f32)>,
+    /// The closed outline of each group's arc.
+    pub groups: Vec<Vec<Vec2>>,
+    /// A ribbon for each pair of groups with anything flowing between them, from the lower group to the higher, or a group to itself.
+    pub ribbons: Vec<Ribbon>,
+}
+
+impl Chord {
+    /// Create a new [Chord] layout, with arcs a twentieth of the radius thick and small gaps between them.
+    pub fn new(center: Vec2, radius: f32) -> Self {
+        Self {
+            center,
+            radius,
+            thickness: radius / 20.0,
+            gap: 0.04,
+        }
+    }
+
+    /// Lay out the flows in a square matrix, where `matrix[i][j]` flows from group `i` to group `j`.
+    pub fn layout(&self, matrix: &[Vec<f32>]) -> ChordLayout {
+        let count = matrix.len();
+        let value = |i: usize, j: usize| {
+            matrix[i]
+                .get(j)
+                .copied()
+                .filter(|value| *value > 0.0)
+                .unwrap_or(0.0)
+        };
+
+        let total: f32 = (0..count)
+            .flat_map(|i| (0..count).map(move |j| (i, j)))
+            .map(|(i, j)| value(i, j))
+            .sum();
+        let room = (std::f32::consts::TAU - self.gap * count as f32).max(0.0);
+        let scale = if total > 0.0 { room / total } else { 0.0 };
+
+        let mut angles = Vec::with_capacity(count);
+        let mut stretches = vec![vec![(0.0, 0.0); count]; count];
+        let mut angle = 0.0;
+        for (i, row) in stretches.iter_mut().enumerate() {
+            let start = angle;
+            for (j, stretch) in row.iter_mut().enumerate() {
+                let end = angle + value(i, j) * scale;
+                *stretch = (angle, end);
+                angle = end;
+            }
+            angles.push((start, angle));
+            angle += self.gap;
+        }
+
+        let groups = angles
+            .iter()
+            .map(|&(start, end)| {
+                annular_sector(
+                    self.center,
+                    self.radius,
+                    self.radius + self.thickness,
+                    start,
+                    end,
+                )
+            })
+            .collect();
+
+        let mut ribbons = Vec::new();
+        for (i, j) in (0..count).flat_map(|i| (i..count).map(move |j| (i, j))) {
+            if value(i, j) + value(j, i) <= 0.0 {
+                continue;
+            }
+
+            let (source, target) = (stretches[i][j], stretches[j][i]);
+            let mut points = self.arc(source.0, source.1);
+            points.extend(self.through_center(source.1, target.0));
+            points.extend(self.arc(target.0, target.1));
+            points.extend(self.through_center(target.1, source.0));
+            points.push(points[0]);
+            ribbons.push(Ribbon {
+                from: i,
+                to: j,
+                points,
+            });
+        }
+
+        ChordLayout {
+            angles,
+            groups,
+            ribbons,
+        }
+    }
+
+    /// Points along the circle from one angle to another.
+    fn arc(&self, start: f32, end: f32) -> Vec<Vec2> {
+        let steps = ((end - start).abs() / (std::f32::consts::TAU / 96.0))
+            .ceil()
+            .max(1.0) as usize;
+
+        (0..=steps)
+            .map(|i| {
+                let angle = start + (end - start) * i as f32 / steps as f32;
+                self.center + Vec2::new(angle.cos(), angle.sin()) * self.radius
+            })
+            .collect()
+    }
+
+    /// Points along a curve from one angle on the circle to another, pulled towards the center, leaving out both ends.
+    fn through_center(&self, start: f32, end: f32) -> impl Iterator<Item = Vec2> + '_ {
+        const STEPS: usize = 24;
+        let from = self.center + Vec2::new(start.cos(), start.sin()) * self.radius;
+        let to = self.center + Vec2::new(end.cos(), end.sin()) * self.radius;
+
+        (1..STEPS).map(move |i| {
+            let t = i as f32 / STEPS as f32;
+            let u = 1.0 - t;
+            from * u * u + self.center * 2.0 * u * t + to * t * t
+        })
+    }
+}
+
+impl Canvas {
+    /// Draw a laid out Sankey diagram in World Space: the ribbons, then each node's bar on top.
+    ///
+    /// Nodes take their colors from `fills` in turn, and each ribbon takes the color of the node it leaves, at `ribbon_opacity`.
+    pub fn draw_sankey(&mut self, layout: &SankeyLayout, fills: &[Color], ribbon_opacity: f32) {
+        if fills.is_empty() {
+            return;
+        }
+
+        for ribbon in &layout.ribbons {
+            let fill = faded(fills[ribbon.from % fills.len()], ribbon_opacity);
+            self.draw_shape_absolute(ribbon.points.clone(), None, Some(fill));
+        }
+        for (node, &(min, max)) in layout.nodes.iter().enumerate() {
+            let corners = vec![
+                min,
+                Vec2::new(max.x, min.y),
+                max,
+                Vec2::new(min.x, max.y),
+                min,
+            ];
+            self.draw_shape_absolute(corners, None, Some(fills[node % fills.len()]));
+        }
+    }
+
+    /// Draw a laid out chord diagram in World Space: the ribbons, then each group's arc.
+    ///
+    /// Groups take their colors from `fills` in turn, and each ribbon takes the color of its first group, at `ribbon_opacity`.
+    pub fn draw_chord(&mut self, layout: &ChordLayout, fills: &[Color], ribbon_opacity: f32) {
+        if fills.is_empty() {
+            return;
+        }
+
+        for ribbon in &layout.ribbons {
+            let fill = faded(fills[ribbon.from % fills.len()], ribbon_opacity);
+            self.draw_shape_absolute(ribbon.points.clone(), None, Some(fill));
+        }
+        for (group, points) in layout.groups.iter().enumerate() {
+            self.draw_shape_absolute(points.clone(), None, Some(fills[group % fills.len()]));
+        }
+    }
+}
+
+/// `color`, with its alpha multiplied by `opacity`.
+fn faded(color: Color, opacity: f32) -> Color {
+    Color::new(color.r(), color.g(), color.b(), color.a() * opacity)
+}
+
+/// A point along a cubic Bézier curve.
+fn cubic(a: Vec2, b: Vec2, c: Vec2, d: Vec2, t: f32) -> Vec2 {
+    let u = 1.0 - t;
+    a * u * u * u + b * 3.0 * u * u * t + c * 3.0 * u * t * t + d * t * t * t
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::signed_area;
+
+    /// Verify that ribbons are as thick as their flows at both ends, and that chord groups share out the circle by their totals.
+    #[test]
+    fn sankey_and_chord() {
+        // Two sources feeding a middle node, which splits into two uses, plus a flow looping back.
+        let flows = [
+            Flow::new(0, 2, 3.0),
+            Flow::new(1, 2, 1.0),
+            Flow::new(2, 3, 2.0),
+            Flow::new(2, 4, 2.0),
+            Flow::new(4, 0, 1.0),
+        ];
+        let sankey = Sankey::new(Vec2::new(10.0, 5.0), 1.0, 1.0);
+        let layout = sankey.layout(5, &flows);
+
+        assert_eq!(layout.ribbons.len(), 4);
+        let height = |node: usize| layout.nodes[node].1.y - layout.nodes[node].0.y;
+        assert!((height(0) - 3.0 * height(1)).abs() < 1e-4);
+        assert!((height(2) - (height(0) + height(1))).abs() < 1e-4);
+        assert_eq!(layout.nodes[0].0.x, 0.0);
+        assert_eq!(layout.nodes[3].1.x, 10.0);
+        assert!(layout
+            .nodes
+            .iter()
+            .all(|(min, max)| min.y >= -1e-4 && max.y <= 5.0 + 1e-4));
+
+        // The first ribbon is as thick as the first node where it leaves, and as the top of the middle node where it arrives.
+        let first = &layout.ribbons[0].points;
+        assert!((first[0].y - first[first.len() - 2].y - height(0)).abs() < 1e-4);
+        assert_eq!(first[24].x, layout.nodes[2].0.x);
+
+        let chord = Chord::new(Vec2::ZERO, 1.0);
+        let matrix = vec![
+            vec![0.0, 2.0, 1.0],
+            vec![2.0, 0.0, 0.0],
+            vec![1.0, 0.0, 0.0],
+        ];
+        let layout = chord.layout(&matrix);
+        let span = |(start, end): (f32, f32)| end - start;
+        assert!((span(layout.angles[0]) - 3.0 * span(layout.angles[2])).abs() < 1e-4);
+        assert_eq!(layout.ribbons.len(), 2);
+        assert!(layout.groups.iter().all(|group| signed_area(group) > 0.0));
+    }
+}
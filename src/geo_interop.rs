@@ -0,0 +1,120 @@
+use ::geo_types::{Coord, CoordFloat, LineString, MultiLineString, MultiPolygon, Polygon};
+use glam::Vec2;
+
+use crate::{FillRule, Shape};
+
+// Conversions between barium's shapes and the geometry types used by the `geo` ecosystem.
+// Shapes converted from `geo_types` have no stroke or fill yet, so set one before handing them to `Canvas::draw_raw_shape`.
+
+impl<T: CoordFloat> From<LineString<T>> for Shape {
+    fn from(line: LineString<T>) -> Self {
+        Self {
+            points: to_points(&line),
+            ..Default::default()
+        }
+    }
+}
+
+impl<T: CoordFloat> From<MultiLineString<T>> for Shape {
+    fn from(lines: MultiLineString<T>) -> Self {
+        compound_shape(lines.iter().map(to_points), FillRule::NonZero)
+    }
+}
+
+impl<T: CoordFloat> From<Polygon<T>> for Shape {
+    fn from(polygon: Polygon<T>) -> Self {
+        compound_shape(polygon_rings(&polygon), FillRule::EvenOdd)
+    }
+}
+
+impl<T: CoordFloat> From<MultiPolygon<T>> for Shape {
+    fn from(polygons: MultiPolygon<T>) -> Self {
+        compound_shape(polygons.iter().flat_map(polygon_rings), FillRule::EvenOdd)
+    }
+}
+
+impl<T: CoordFloat> From<&Shape> for LineString<T> {
+    /// Only the outline of the shape is kept, not its subpaths.
+    fn from(shape: &Shape) -> Self {
+        to_line_string(&shape.points)
+    }
+}
+
+impl<T: CoordFloat> From<&Shape> for Polygon<T> {
+    /// The outline of the shape becomes the exterior, and its subpaths the interiors. Rings are closed automatically.
+    fn from(shape: &Shape) -> Self {
+        Polygon::new(
+            to_line_string(&shape.points),
+            shape
+                .subpaths
+                .iter()
+                .map(|path| to_line_string(path))
+                .collect(),
+        )
+    }
+}
+
+fn compound_shape<I: Iterator<Item = Vec<Vec2>>>(paths: I, fill_rule: FillRule) -> Shape {
+    let mut paths = paths.filter(|path| !path.is_empty());
+
+    Shape {
+        points: paths.next().unwrap_or_default(),
+        subpaths: paths.collect(),
+        fill_rule,
+        ..Default::default()
+    }
+}
+
+fn polygon_rings<T: CoordFloat>(polygon: &Polygon<T>) -> impl Iterator<Item = Vec<Vec2>> + '_ {
+    std::iter::once(polygon.exterior())
+        .chain(polygon.interiors())
+        .map(to_points)
+}
+
+fn to_points<T: CoordFloat>(line: &LineString<T>) -> Vec<Vec2> {
+    line.coords().map(|&coord| to_vec2(coord)).collect()
+}
+
+fn to_vec2<T: CoordFloat>(coord: Coord<T>) -> Vec2 {
+    Vec2::new(
+        coord.x.to_f32().unwrap_or(f32::NAN),
+        coord.y.to_f32().unwrap_or(f32::NAN),
+    )
+}
+
+fn to_line_string<T: CoordFloat>(points: &[Vec2]) -> LineString<T> {
+    points
+        .iter()
+        .map(|point| Coord {
+            x: T::from(point.x).unwrap_or_else(T::nan),
+            y: T::from(point.y).unwrap_or_else(T::nan),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::geo_types::{coord, polygon};
+
+    /// Verify that polygons with holes survive a round trip through a [Shape].
+    #[test]
+    fn polygon_round_trip() {
+        let polygon: Polygon<f64> = polygon!(
+            exterior: [(x: 0.0, y: 0.0), (x: 4.0, y: 0.0), (x: 4.0, y: 4.0), (x: 0.0, y: 4.0)],
+            interiors: [[(x: 1.0, y: 1.0), (x: 2.0, y: 1.0), (x: 2.0, y: 2.0)]],
+        );
+
+        let shape = Shape::from(polygon.clone());
+        assert!(shape.is_polygon());
+        assert_eq!(shape.subpaths.len(), 1);
+        assert_eq!(shape.fill_rule, FillRule::EvenOdd);
+        assert_eq!(shape.points[2], Vec2::new(4.0, 4.0));
+
+        assert_eq!(Polygon::<f64>::from(&shape), polygon);
+        assert_eq!(
+            LineString::<f32>::from(&shape).0[1],
+            coord! { x: 4.0, y: 0.0 }
+        );
+    }
+}
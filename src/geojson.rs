@@ -0,0 +1,299 @@
+use ::geojson::{Position, Value};
+use glam::Vec2;
+
+use crate::{
+    projection::{project_line, Projection},
+    Canvas, Color, Stroke,
+};
+
+pub use ::geojson::{Feature, GeoJson, Geometry};
+
+/// How to draw the geometry of a single GeoJSON [Feature].
+///
+/// Lines are only ever stroked. Polygons and points use both the stroke and the fill.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GeoJsonStyle {
+    /// Outline for polygons and points, and the line itself for line strings.
+    pub stroke: Option<Stroke>,
+    /// Fill for polygons and points.
+    pub fill: Option<Color>,
+    /// Radius of the circle drawn for each point, in World Space units. Points are skipped if this is zero.
+    pub point_radius: f32,
+    /// How far lines and polygon edges may stray from the curves the projection bends them into before points are added, in World Space units.
+    /// Points are never added if this is zero. See [project_line].
+    pub tolerance: f32,
+}
+
+impl GeoJsonStyle {
+    /// Create a new [GeoJsonStyle] that does not draw points.
+    pub fn new(stroke: Option<Stroke>, fill: Option<Color>) -> Self {
+        Self {
+            stroke,
+            fill,
+            point_radius: 0.0,
+            tolerance: 0.0,
+        }
+    }
+
+    /// Set the radius of the circle drawn for each point, consuming the parent.
+    pub fn with_point_radius(mut self, point_radius: f32) -> Self {
+        self.point_radius = point_radius;
+        self
+    }
+
+    /// Set how closely lines and polygon edges follow the curves of the projection, consuming the parent.
+    pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+}
+
+impl Canvas {
+    /// Draw every geometry in a GeoJSON document onto the canvas, projected from the camera.
+    ///
+    /// Coordinates are flattened with `projection` first. `style` is called once per [Feature], or with `None` for geometry that is not part of one.
+    pub fn draw_geojson<P, S>(&mut self, geojson: &GeoJson, projection: &P, style: S)
+    where
+        P: Projection + ?Sized,
+        S: FnMut(Option<&Feature>) -> GeoJsonStyle,
+    {
+        self.draw_geojson_inner(geojson, projection, style, false);
+    }
+
+    /// Draw every geometry in a GeoJSON document directly onto the canvas.
+    ///
+    /// Coordinates are flattened with `projection` first. `style` is called once per [Feature], or with `None` for geometry that is not part of one.
+    pub fn draw_geojson_absolute<P, S>(&mut self, geojson: &GeoJson, projection: &P, style: S)
+    where
+        P: Projection + ?Sized,
+        S: FnMut(Option<&Feature>) -> GeoJsonStyle,
+    {
+        self.draw_geojson_inner(geojson, projection, style, true);
+    }
+
+    fn draw_geojson_inner<P, S>(
+        &mut self,
+        geojson: &GeoJson,
+        projection: &P,
+        mut style: S,
+        absolute: bool,
+    ) where
+        P: Projection + ?Sized,
+        S: FnMut(Option<&Feature>) -> GeoJsonStyle,
+    {
+        let mut draw_feature = |canvas: &mut Self, feature: &Feature| {
+            if let Some(geometry) = &feature.geometry {
+                let style = style(Some(feature));
+                canvas.draw_geojson_value(&geometry.value, projection, &style, absolute);
+            }
+        };
+
+        match geojson {
+            GeoJson::Geometry(geometry) => {
+                let style = style(None);
+                self.draw_geojson_value(&geometry.value, projection, &style, absolute);
+            }
+            GeoJson::Feature(feature) => draw_feature(self, feature),
+            GeoJson::FeatureCollection(collection) => {
+                for feature in &collection.features {
+                    draw_feature(self, feature);
+                }
+            }
+        }
+    }
+
+    fn draw_geojson_value<P: Projection + ?Sized>(
+        &mut self,
+        value: &Value,
+        projection: &P,
+        style: &GeoJsonStyle,
+        absolute: bool,
+    ) {
+        match value {
+            Value::Point(position) => {
+                self.draw_geojson_point(position, projection, style, absolute)
+            }
+            Value::MultiPoint(positions) => {
+                for position in positions {
+                    self.draw_geojson_point(position, projection, style, absolute);
+                }
+            }
+            Value::LineString(line) => self.draw_geojson_line(line, projection, style, absolute),
+            Value::MultiLineString(lines) => {
+                for line in lines {
+                    self.draw_geojson_line(line, projection, style, absolute);
+                }
+            }
+            Value::Polygon(rings) => self.draw_geojson_polygon(rings, projection, style, absolute),
+            Value::MultiPolygon(polygons) => {
+                for rings in polygons {
+                    self.draw_geojson_polygon(rings, projection, style, absolute);
+                }
+            }
+            Value::GeometryCollection(geometries) => {
+                for geometry in geometries {
+                    self.draw_geojson_value(&geometry.value, projection, style, absolute);
+                }
+            }
+        }
+    }
+
+    fn draw_geojson_point<P: Projection + ?Sized>(
+        &mut self,
+        position: &Position,
+        projection: &P,
+        style: &GeoJsonStyle,
+        absolute: bool,
+    ) {
+        let center = match project_position(position, projection) {
+            Some(center) if style.point_radius > 0.0 => center,
+            _ => return,
+        };
+
+        if absolute {
+            self.draw_circle_absolute(center, style.point_radius, style.stroke, style.fill);
+        } else {
+            self.draw_circle(center, style.point_radius, style.stroke, style.fill);
+        }
+    }
+
+    fn draw_geojson_line<P: Projection + ?Sized>(
+        &mut self,
+        line: &[Position],
+        projection: &P,
+        style: &GeoJsonStyle,
+        absolute: bool,
+    ) {
+        let stroke = match style.stroke {
+            Some(stroke) => stroke,
+            None => return,
+        };
+        // Lines that pass out of view, such as behind a globe, are drawn in pieces.
+        for points in project_positions(line, projection, style.tolerance) {
+            if absolute {
+                self.draw_polyline_absolute(points, stroke);
+            } else {
+                self.draw_polyline(points, stroke);
+            }
+        }
+    }
+
+    fn draw_geojson_polygon<P: Projection + ?Sized>(
+        &mut self,
+        rings: &[Vec<Position>],
+        projection: &P,
+        style: &GeoJsonStyle,
+        absolute: bool,
+    ) {
+        let (outer, holes) = match rings.split_first() {
+            Some(split) => split,
+            None => return,
+        };
+        // Rings that pass out of view are joined straight across the gap.
+        let project_ring = |ring| -> Vec<Vec2> {
+            project_positions(ring, projection, style.tolerance)
+                .into_iter()
+                .flatten()
+                .collect()
+        };
+        let outer = project_ring(outer);
+        let holes = holes.iter().map(|ring| project_ring(ring));
+
+        if absolute {
+            self.draw_polygon_with_holes_absolute(outer, holes, style.stroke, style.fill);
+        } else {
+            self.draw_polygon_with_holes(outer, holes, style.stroke, style.fill);
+        }
+    }
+}
+
+/// Project a GeoJSON position, ignoring any altitude. Returns `None` if it has fewer than two coordinates or is out of view.
+fn project_position<P: Projection + ?Sized>(position: &Position, projection: &P) -> Option<Vec2> {
+    match position.as_slice() {
+        [longitude, latitude, ..] if projection.is_visible(*longitude, *latitude) => {
+            Some(projection.project(*longitude, *latitude))
+        }
+        _ => None,
+    }
+}
+
+/// Project a run of GeoJSON positions into the pieces that are in view. See [project_line].
+fn project_positions<P: Projection + ?Sized>(
+    positions: &[Position],
+    projection: &P,
+    tolerance: f32,
+) -> Vec<Vec<Vec2>> {
+    let line: Vec<(f64, f64)> = positions
+        .iter()
+        .filter_map(|position| match position.as_slice() {
+            [longitude, latitude, ..] => Some((*longitude, *latitude)),
+            _ => None,
+        })
+        .collect();
+
+    project_line(projection, &line, tolerance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{projection::Equirectangular, FillRule, LineEnd};
+
+    const DOCUMENT: &str = r#"{
+        "type": "FeatureCollection",
+        "features": [
+            {
+                "type": "Feature",
+                "properties": { "name": "pond" },
+                "geometry": {
+                    "type": "Polygon",
+                    "coordinates": [
+                        [[-90, -45], [90, -45], [90, 45], [-90, 45], [-90, -45]],
+                        [[-45, -9], [45, -9], [45, 9], [-45, 9], [-45, -9]]
+                    ]
+                }
+            },
+            {
+                "type": "Feature",
+                "properties": { "name": "river" },
+                "geometry": { "type": "LineString", "coordinates": [[0, 0], [18, 9, 100]] }
+            },
+            {
+                "type": "Feature",
+                "properties": null,
+                "geometry": { "type": "MultiPoint", "coordinates": [[0, 0], [36, 18]] }
+            }
+        ]
+    }"#;
+
+    /// Verify that features become projected shapes, with holes, lines, and points handled separately.
+    #[test]
+    fn draw_feature_collection() {
+        let geojson: GeoJson = DOCUMENT.parse().unwrap();
+        let stroke = Stroke::new(Color::black(), 0.01, LineEnd::Butt);
+
+        let mut canvas = Canvas::new(100);
+        canvas.draw_geojson(&geojson, &Equirectangular::default(), |feature| {
+            let style = GeoJsonStyle::new(Some(stroke), Some(Color::white()));
+
+            match feature.and_then(|feature| feature.property("name")) {
+                Some(_) => style,
+                None => style.with_point_radius(0.05),
+            }
+        });
+
+        let shapes = canvas.as_raw();
+        assert_eq!(shapes.len(), 4);
+
+        let pond = &shapes[0];
+        assert_eq!(pond.fill_rule, FillRule::EvenOdd);
+        assert_eq!(pond.subpaths.len(), 1);
+        assert_eq!(pond.points[1], Vec2::new(0.5, -0.25));
+
+        let river = &shapes[1];
+        assert_eq!(river.fill, None);
+        assert_eq!(river.points, vec![Vec2::ZERO, Vec2::new(0.1, 0.05)]);
+
+        assert!(shapes[2..].iter().all(|point| point.is_polygon()));
+    }
+}
@@ -0,0 +1,308 @@
+use glam::Vec2;
+
+use super::{occlusion::clip_inside, signed_area};
+use crate::{FillRule, Shape};
+
+/// An area that shapes are trimmed to by [clip].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ClipRegion {
+    /// An axis-aligned rectangle between two opposite corners, such as the artboard.
+    Rect {
+        /// One corner of the rectangle.
+        min: Vec2,
+        /// The opposite corner.
+        max: Vec2,
+    },
+    /// The inside of a polygon. It may be closed (first and last points equal) or not.
+    ///
+    /// Fills are clipped exactly when the polygon is convex. Concave polygons can leave zero-width slivers along their edges.
+    Polygon(Vec<Vec2>),
+}
+
+impl ClipRegion {
+    /// The corners of the region, counter-clockwise and closed, so that the last edge is included.
+    fn ring(&self) -> Vec<Vec2> {
+        let mut ring = match self {
+            ClipRegion::Rect { min, max } => {
+                let (min, max) = (min.min(*max), min.max(*max));
+                vec![min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)]
+            }
+            ClipRegion::Polygon(points) => {
+                let mut ring = points.clone();
+                if ring.len() > 1 && ring[0] == ring[ring.len() - 1] {
+                    ring.pop();
+                }
+                ring
+            }
+        };
+
+        if signed_area(&ring) < 0.0 {
+            ring.reverse();
+        }
+        if let Some(first) = ring.first().copied() {
+            ring.push(first);
+        }
+        ring
+    }
+}
+
+/// Trim a shape to the inside of `region`, permanently removing everything outside it.
+///
+/// Fills are clipped with the Sutherland-Hodgman algorithm. Strokes are clipped separately, with the Liang-Barsky algorithm for rectangles,
+/// so that the new edges along the border of the region are filled but not stroked.
+/// A shape with both a fill and a stroke therefore becomes a filled shape followed by one stroked shape per visible piece of its outline.
+/// Shapes entirely inside a rectangle are returned unchanged, and shapes entirely outside are dropped.
+pub fn clip(subject: &Shape, region: &ClipRegion) -> Vec<Shape> {
+    let subject: &Shape = &subject.flattened();
+    if let ClipRegion::Rect { min, max } = region {
+        let (min, max) = (min.min(*max), min.max(*max));
+        let inside = subject
+            .paths()
+            .flatten()
+            .all(|point| point.cmpge(min).all() && point.cmple(max).all());
+        if inside {
+            return vec![subject.clone()];
+        }
+    }
+
+    let ring = region.ring();
+    let mut clipped = Vec::new();
+
+    if subject.fill.is_some() {
+        let mut paths = subject
+            .paths()
+            .map(|path| sutherland_hodgman(path, &ring))
+            .filter(|path| path.len() >= 3);
+
+        if let Some(points) = paths.next() {
+            clipped.push(Shape {
+                points,
+                subpaths: paths.collect(),
+                stroke: None,
+                ..subject.clone()
+            });
+        }
+    }
+
+    if subject.stroke.is_some() {
+        for path in subject.paths().filter(|path| path.len() > 1) {
+            let pieces = match region {
+                ClipRegion::Rect { min, max } => liang_barsky(path, min.min(*max), min.max(*max)),
+                ClipRegion::Polygon(_) => clip_inside(path, &[&ring], FillRule::NonZero),
+            };
+
+            clipped.extend(join_around(path, pieces).into_iter().map(|points| Shape {
+                points,
+                subpaths: Vec::new(),
+                fill: None,
+                gradient_fill: None,
+                ..subject.clone()
+            }));
+        }
+    }
+
+    clipped
+}
+
+/// Clip the area of a path to a convex, counter-clockwise, closed ring. The result is closed.
+fn sutherland_hodgman(path: &[Vec2], ring: &[Vec2]) -> Vec<Vec2> {
+    let mut output: Vec<Vec2> = path.to_vec();
+    if output.len() > 1 && output[0] == output[output.len() - 1] {
+        output.pop();
+    }
+
+    for edge in ring.windows(2) {
+        let (a, b) = (edge[0], edge[1]);
+        let inside = |point: Vec2| (b - a).perp_dot(point - a) >= 0.0;
+
+        let input = std::mem::take(&mut output);
+        for (i, &current) in input.iter().enumerate() {
+            let previous = input[(i + input.len() - 1) % input.len()];
+
+            match (inside(previous), inside(current)) {
+                (true, true) => output.push(current),
+                (true, false) => output.push(crossing(previous, current, a, b)),
+                (false, true) => {
+                    output.push(crossing(previous, current, a, b));
+                    output.push(current);
+                }
+                (false, false) => {}
+            }
+        }
+    }
+
+    if let Some(first) = output.first().copied() {
+        output.push(first);
+    }
+    output
+}
+
+/// Where the segment from `p` to `q` crosses the infinite line through `a` and `b`.
+fn crossing(p: Vec2, q: Vec2, a: Vec2, b: Vec2) -> Vec2 {
+    let edge = b - a;
+    let t = edge.perp_dot(a - p) / edge.perp_dot(q - p);
+    p.lerp(q, t)
+}
+
+/// Clip a polyline to a rectangle, one segment at a time, joining segments that stay connected.
+fn liang_barsky(polyline: &[Vec2], min: Vec2, max: Vec2) -> Vec<Vec<Vec2>> {
+    let mut pieces: Vec<Vec<Vec2>> = Vec::new();
+    let mut current: Vec<Vec2> = Vec::new();
+
+    for pair in polyline.windows(2) {
+        let (start, end) = match clip_segment(pair[0], pair[1], min, max) {
+            Some(segment) => segment,
+            None => continue,
+        };
+
+        if current.last() != Some(&start) {
+            if current.len() > 1 {
+                pieces.push(std::mem::take(&mut current));
+            }
+            current = vec![start];
+        }
+        current.push(end);
+    }
+
+    if current.len() > 1 {
+        pieces.push(current);
+    }
+
+    pieces
+}
+
+/// The part of the segment from `a` to `b` inside the rectangle from `min` to `max`, using the Liang-Barsky algorithm.
+pub(crate) fn clip_segment(a: Vec2, b: Vec2, min: Vec2, max: Vec2) -> Option<(Vec2, Vec2)> {
+    let delta = b - a;
+
+    // Each boundary limits how far along the segment it can enter or leave the rectangle.
+    let boundaries = [
+        (-delta.x, a.x - min.x),
+        (delta.x, max.x - a.x),
+        (-delta.y, a.y - min.y),
+        (delta.y, max.y - a.y),
+    ];
+
+    let (mut enter, mut leave) = (0.0f32, 1.0f32);
+    let visible = boundaries.iter().all(|&(p, q)| {
+        if p == 0.0 {
+            return q >= 0.0;
+        }
+
+        let t = q / p;
+        if p < 0.0 {
+            enter = enter.max(t);
+        } else {
+            leave = leave.min(t);
+        }
+        enter <= leave
+    });
+
+    visible.then(|| (a + delta * enter, a + delta * leave))
+}
+
+/// Join the last piece of a clipped closed path onto its first, if the path was only cut apart where it starts and ends.
+fn join_around(path: &[Vec2], mut pieces: Vec<Vec<Vec2>>) -> Vec<Vec<Vec2>> {
+    let closed = path.len() >= 3 && path[0] == path[path.len() - 1];
+    if !closed || pieces.len() < 2 {
+        return pieces;
+    }
+
+    let starts = pieces[0].first() == Some(&path[0]);
+    let ends = pieces[pieces.len() - 1].last() == Some(&path[path.len() - 1]);
+    if starts && ends {
+        let first = pieces.remove(0);
+        if let Some(last) = pieces.last_mut() {
+            last.extend(first.into_iter().skip(1));
+        }
+    }
+
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{custom_shape::custom_capsule, Color, LineEnd, Stroke};
+
+    /// Verify that fills and strokes are trimmed to rectangles and polygons, without stroking the cut edges.
+    #[test]
+    fn clip_to_regions() {
+        let shape = Shape {
+            points: vec![
+                Vec2::new(-1.0, -1.0),
+                Vec2::new(1.0, -1.0),
+                Vec2::new(1.0, 1.0),
+                Vec2::new(-1.0, 1.0),
+                Vec2::new(-1.0, -1.0),
+            ],
+            stroke: Some(Stroke::new(Color::black(), 0.1, LineEnd::Butt)),
+            fill: Some(Color::red()),
+            ..Default::default()
+        };
+
+        let artboard = ClipRegion::Rect {
+            min: Vec2::ZERO,
+            max: Vec2::splat(2.0),
+        };
+        let clipped = clip(&shape, &artboard);
+        assert_eq!(clipped.len(), 2);
+        assert!((signed_area(&clipped[0].points) - 1.0).abs() < 1e-6);
+        assert!(clipped[0].stroke.is_none());
+        assert_eq!(
+            clipped[1].points,
+            vec![Vec2::new(1.0, 0.0), Vec2::ONE, Vec2::new(0.0, 1.0)]
+        );
+        assert!(clipped[1].fill.is_none());
+
+        let everything = ClipRegion::Rect {
+            min: Vec2::splat(-2.0),
+            max: Vec2::splat(2.0),
+        };
+        assert_eq!(clip(&shape, &everything), vec![shape.clone()]);
+
+        // The outline of the square starts inside, so the pieces either side of its start are rejoined.
+        let left = ClipRegion::Rect {
+            min: Vec2::splat(-2.0),
+            max: Vec2::new(0.5, 2.0),
+        };
+        assert_eq!(
+            clip(&shape, &left)[1].points,
+            vec![
+                Vec2::new(0.5, 1.0),
+                Vec2::new(-1.0, 1.0),
+                Vec2::new(-1.0, -1.0),
+                Vec2::new(0.5, -1.0)
+            ]
+        );
+
+        // A diamond clips the corners off the square, leaving the middle of each side.
+        let diamond = ClipRegion::Polygon(vec![
+            Vec2::new(1.5, 0.0),
+            Vec2::new(0.0, 1.5),
+            Vec2::new(-1.5, 0.0),
+            Vec2::new(0.0, -1.5),
+        ]);
+        let clipped = clip(&shape, &diamond);
+        assert_eq!(clipped.len(), 5);
+        assert!((signed_area(&clipped[0].points) - 3.5).abs() < 1e-5);
+        assert!(clipped[1..].iter().all(|piece| piece.points.len() == 2));
+    }
+
+    /// Verify that custom shapes are flattened before clipping, rather than passed through as if inside.
+    #[test]
+    fn clip_custom_shape() {
+        let custom = custom_capsule();
+        let region = ClipRegion::Rect {
+            min: Vec2::splat(-1.0),
+            max: Vec2::splat(1.0),
+        };
+
+        let clipped = clip(&custom, &region);
+        assert_eq!(clipped, clip(&custom.flattened(), &region));
+        assert!(clipped
+            .iter()
+            .all(|piece| { piece.custom.is_none() && piece.bounds().unwrap().1.x <= 1.0 + 1e-6 }));
+    }
+}
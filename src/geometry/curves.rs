@@ -0,0 +1,205 @@
+use glam::Vec2;
+
+use super::occlusion::clip_inside;
+use crate::Shape;
+
+/// A curve that winds through every part of an area as its order increases, drawn in one continuous line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SpaceFillingCurve {
+    /// Visits a grid of `2^order` by `2^order` cells, turning at right angles.
+    Hilbert,
+    /// Visits a grid of `3^order` by `3^order` cells, turning at right angles.
+    Peano,
+    /// The flowsnake, winding through a rough hexagon at multiples of 60°.
+    Gosper,
+}
+
+impl SpaceFillingCurve {
+    /// The L-system that draws the curve: its axiom, rewriting rules, which symbols step forward, and the angle of each turn.
+    fn l_system(
+        &self,
+    ) -> (
+        &'static str,
+        &'static [(char, &'static str)],
+        &'static str,
+        f32,
+    ) {
+        match self {
+            SpaceFillingCurve::Hilbert => (
+                "A",
+                &[('A', "+BF-AFA-FB+"), ('B', "-AF+BFB+FA-")],
+                "F",
+                90.0,
+            ),
+            SpaceFillingCurve::Peano => (
+                "X",
+                &[
+                    ('X', "XFYFX+F+YFXFY-F-XFYFX"),
+                    ('Y', "YFXFY-F-XFYFX+F+YFXFY"),
+                ],
+                "F",
+                90.0,
+            ),
+            SpaceFillingCurve::Gosper => (
+                "A",
+                &[('A', "A-B--B+A++AA+B-"), ('B', "+A-BB--B-A++A+B")],
+                "AB",
+                60.0,
+            ),
+        }
+    }
+}
+
+/// A space-filling curve of the given order, as a single polyline fitted inside the rectangle from `min` to `max`.
+///
+/// The Hilbert and Peano curves are stretched to fill the rectangle, with half a grid cell of margin around the edges.
+/// The Gosper curve keeps its proportions and is centered. Each order multiplies the number of points by four, nine or seven respectively.
+pub fn space_filling_curve(
+    curve: SpaceFillingCurve,
+    order: u32,
+    min: Vec2,
+    max: Vec2,
+) -> Vec<Vec2> {
+    let (axiom, rules, forward, angle) = curve.l_system();
+    let turn = angle.to_radians();
+
+    let mut points = vec![Vec2::ZERO];
+    let mut heading = 0i32;
+    for symbol in axiom.chars() {
+        expand(symbol, order, rules, &mut |symbol| match symbol {
+            '+' => heading += 1,
+            '-' => heading -= 1,
+            symbol if forward.contains(symbol) => {
+                let angle = heading as f32 * turn;
+                let last = points[points.len() - 1];
+                points.push(last + Vec2::new(angle.cos(), angle.sin()));
+            }
+            _ => {}
+        });
+    }
+
+    // Right angles add up rounding error, so snap the grid curves back onto whole steps.
+    if angle == 90.0 {
+        points.iter_mut().for_each(|point| *point = point.round());
+    }
+
+    let low = points
+        .iter()
+        .copied()
+        .fold(Vec2::splat(f32::INFINITY), Vec2::min);
+    let high = points
+        .iter()
+        .copied()
+        .fold(Vec2::splat(f32::NEG_INFINITY), Vec2::max);
+    let (min, max) = (min.min(max), min.max(max));
+
+    let (scale, offset) = match curve {
+        SpaceFillingCurve::Hilbert | SpaceFillingCurve::Peano => {
+            let scale = (max - min) / (high - low + 1.0);
+            (scale, min + (Vec2::splat(0.5) - low) * scale)
+        }
+        SpaceFillingCurve::Gosper => {
+            let extent = (high - low).max(Vec2::splat(f32::EPSILON));
+            let scale = Vec2::splat(((max - min) / extent).min_element());
+            (scale, (min + max) / 2.0 - (low + high) / 2.0 * scale)
+        }
+    };
+
+    points
+        .into_iter()
+        .map(|point| point * scale + offset)
+        .collect()
+}
+
+/// A space-filling curve of the given order, fitted to the bounds of `boundary` and trimmed to its inside.
+///
+/// Trimming cuts the curve into several polylines wherever it wanders out across the edge of the shape, which happens for almost any shape
+/// but a rectangle. The pieces are returned in order along the curve.
+pub fn space_filling_curve_in(
+    curve: SpaceFillingCurve,
+    order: u32,
+    boundary: &Shape,
+) -> Vec<Vec<Vec2>> {
+    let Some((min, max)) = boundary.bounds() else {
+        return Vec::new();
+    };
+
+    let boundary: &Shape = &boundary.flattened();
+    let paths: Vec<&[Vec2]> = boundary.paths().collect();
+    clip_inside(
+        &space_filling_curve(curve, order, min, max),
+        &paths,
+        boundary.fill_rule,
+    )
+}
+
+/// Rewrite `symbol` by `rules` to the given depth, handing each symbol of the result to `draw` in order.
+fn expand(symbol: char, depth: u32, rules: &[(char, &str)], draw: &mut impl FnMut(char)) {
+    let rule = rules.iter().find(|(from, _)| *from == symbol);
+
+    match rule {
+        Some((_, to)) if depth > 0 => {
+            for symbol in to.chars() {
+                expand(symbol, depth - 1, rules, draw);
+            }
+        }
+        _ => draw(symbol),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::custom_shape::custom_capsule;
+
+    /// Verify that each curve visits the expected number of points, stays inside its rectangle and never jumps.
+    #[test]
+    fn space_filling_curves() {
+        let (min, max) = (Vec2::ZERO, Vec2::new(8.0, 4.0));
+
+        let hilbert = space_filling_curve(SpaceFillingCurve::Hilbert, 3, min, max);
+        assert_eq!(hilbert.len(), 64);
+        assert!(hilbert.contains(&Vec2::new(0.5, 0.25)));
+
+        let peano = space_filling_curve(SpaceFillingCurve::Peano, 2, min, max);
+        assert_eq!(peano.len(), 81);
+
+        let gosper = space_filling_curve(SpaceFillingCurve::Gosper, 2, min, max);
+        assert_eq!(gosper.len(), 50);
+
+        for curve in [&hilbert, &peano, &gosper] {
+            assert!(curve
+                .iter()
+                .all(|point| point.cmpge(min - 1e-4).all() && point.cmple(max + 1e-4).all()));
+
+            let steps: Vec<f32> = curve
+                .windows(2)
+                .map(|pair| pair[0].distance(pair[1]))
+                .collect();
+            let longest = steps.iter().copied().fold(0.0, f32::max);
+            assert!(longest < 1.5);
+        }
+
+        let square = Shape {
+            points: vec![Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y],
+            ..Default::default()
+        };
+        let inside = space_filling_curve_in(SpaceFillingCurve::Hilbert, 2, &square);
+        assert_eq!(inside.len(), 1);
+        assert_eq!(inside[0].len(), 16);
+    }
+
+    /// Verify that custom shapes are flattened before curves are trimmed to them.
+    #[test]
+    fn curve_in_custom_shape() {
+        let custom = custom_capsule();
+
+        let pieces = space_filling_curve_in(SpaceFillingCurve::Hilbert, 4, &custom);
+        assert_eq!(
+            pieces,
+            space_filling_curve_in(SpaceFillingCurve::Hilbert, 4, &custom.flattened())
+        );
+        assert!(!pieces.is_empty());
+    }
+}
@@ -0,0 +1,213 @@
+use glam::{Mat2, Vec2};
+
+use super::distance_to_segment;
+use crate::Shape;
+
+/// A lens-like distortion of the space inside a circle. Points outside the circle are left where they are.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Distortion {
+    /// Magnify the middle of the circle and squash its edges, like a fisheye lens.
+    Fisheye {
+        /// Center of the lens.
+        center: Vec2,
+        /// Radius of the lens.
+        radius: f32,
+        /// How strongly the middle is magnified. Zero leaves points unchanged.
+        strength: f32,
+    },
+    /// Shrink the middle of the circle and stretch its edges. The opposite of [Fisheye](Self::Fisheye) with the same strength.
+    Pinch {
+        /// Center of the pinch.
+        center: Vec2,
+        /// Radius of the pinch.
+        radius: f32,
+        /// How strongly the middle is shrunk. Zero leaves points unchanged.
+        strength: f32,
+    },
+    /// Twist the circle around its center, most at the center and fading to nothing at its edge.
+    Swirl {
+        /// Center of the swirl.
+        center: Vec2,
+        /// Radius of the swirl.
+        radius: f32,
+        /// How far the center is turned, in radians counter-clockwise.
+        angle: f32,
+    },
+    /// Wrap the circle around the front of a sphere, bulging it towards the viewer.
+    Spherize {
+        /// Center of the sphere.
+        center: Vec2,
+        /// Radius of the sphere.
+        radius: f32,
+        /// How much of the effect to apply, from 0.0 (none) to 1.0 (a full sphere).
+        strength: f32,
+    },
+}
+
+impl Distortion {
+    /// Where the distortion moves `point`.
+    pub fn apply(&self, point: Vec2) -> Vec2 {
+        let (center, radius) = match *self {
+            Distortion::Fisheye { center, radius, .. }
+            | Distortion::Pinch { center, radius, .. }
+            | Distortion::Swirl { center, radius, .. }
+            | Distortion::Spherize { center, radius, .. } => (center, radius),
+        };
+
+        let offset = point - center;
+        let distance = offset.length() / radius;
+        if !(distance > 0.0 && distance < 1.0) {
+            return point;
+        }
+
+        // Each lens moves points nearer or further from the center, keeping the center and the edge of the circle in place.
+        let moved = match *self {
+            Distortion::Fisheye { strength, .. } => {
+                (strength + 1.0) * distance / (strength * distance + 1.0)
+            }
+            Distortion::Pinch { strength, .. } => distance / (strength + 1.0 - strength * distance),
+            Distortion::Spherize { strength, .. } => {
+                let sphere = (distance * std::f32::consts::FRAC_PI_2).sin();
+                distance + (sphere - distance) * strength.clamp(0.0, 1.0)
+            }
+            Distortion::Swirl { angle, .. } => {
+                let turn = angle * (1.0 - distance).powi(2);
+                return center + Mat2::from_angle(turn) * offset;
+            }
+        };
+
+        center + offset * (moved / distance)
+    }
+}
+
+/// Distort a shape, adding points along its edges so that straight lines bend smoothly.
+///
+/// Edges are split in half until the distorted midpoint of every piece is within `tolerance` of the straight edge between its distorted ends.
+/// A [gradient fill](Shape::gradient_fill) moves with the distortion, but stays a straight gradient.
+pub fn distort(shape: &Shape, distortion: &Distortion, tolerance: f32) -> Shape {
+    map_subdivided(shape, |point| distortion.apply(point), tolerance)
+}
+
+/// Move every point of a shape with `f`, splitting edges until each piece bends by no more than `tolerance`.
+pub(crate) fn map_subdivided<F: Fn(Vec2) -> Vec2>(shape: &Shape, f: F, tolerance: f32) -> Shape {
+    let shape: &Shape = &shape.flattened();
+    let tolerance = tolerance.max(1e-6);
+    let map_path = |path: &[Vec2]| -> Vec<Vec2> {
+        let mut mapped = Vec::with_capacity(path.len());
+        if let Some(&first) = path.first() {
+            mapped.push(f(first));
+        }
+        for pair in path.windows(2) {
+            subdivide(pair[0], pair[1], &f, tolerance, 12, &mut mapped);
+        }
+        mapped
+    };
+
+    let mut mapped = Shape {
+        points: map_path(&shape.points),
+        subpaths: shape.subpaths.iter().map(|path| map_path(path)).collect(),
+        ..shape.clone()
+    };
+
+    if let Some(gradient) = &mut mapped.gradient_fill {
+        gradient.start = f(gradient.start);
+        gradient.end = f(gradient.end);
+    }
+    mapped
+}
+
+/// Push the mapped points along the edge from `a` to `b`, after `a`, splitting the edge until it is flat enough or `depth` runs out.
+fn subdivide<F: Fn(Vec2) -> Vec2>(
+    a: Vec2,
+    b: Vec2,
+    f: &F,
+    tolerance: f32,
+    depth: u32,
+    mapped: &mut Vec<Vec2>,
+) {
+    let middle = a.lerp(b, 0.5);
+    let (start, end) = (f(a), f(b));
+
+    if depth > 0 && distance_to_segment(f(middle), start, end) > tolerance {
+        subdivide(a, middle, f, tolerance, depth - 1, mapped);
+        subdivide(middle, b, f, tolerance, depth - 1, mapped);
+    } else {
+        mapped.push(end);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::custom_shape::custom_capsule;
+
+    /// Verify that distortions keep the center and rim of their circle in place, and that bent edges gain points.
+    #[test]
+    fn lens_distortions() {
+        let center = Vec2::new(1.0, 2.0);
+        let distortions = [
+            Distortion::Fisheye {
+                center,
+                radius: 2.0,
+                strength: 2.0,
+            },
+            Distortion::Pinch {
+                center,
+                radius: 2.0,
+                strength: 2.0,
+            },
+            Distortion::Swirl {
+                center,
+                radius: 2.0,
+                angle: 1.0,
+            },
+            Distortion::Spherize {
+                center,
+                radius: 2.0,
+                strength: 1.0,
+            },
+        ];
+
+        for distortion in &distortions {
+            assert_eq!(distortion.apply(center), center);
+            assert_eq!(
+                distortion.apply(center + Vec2::new(3.0, 0.0)),
+                center + Vec2::new(3.0, 0.0)
+            );
+            assert!(distortion
+                .apply(center + Vec2::new(0.0, 2.0))
+                .abs_diff_eq(center + Vec2::new(0.0, 2.0), 1e-5));
+        }
+
+        let near = center + Vec2::new(0.5, 0.0);
+        assert!(distortions[0].apply(near).x > near.x);
+        assert!(distortions[1].apply(near).x < near.x);
+        assert!(distortions[1]
+            .apply(distortions[0].apply(near))
+            .abs_diff_eq(near, 1e-5));
+
+        let line = Shape {
+            points: vec![center - Vec2::new(1.5, 0.5), center + Vec2::new(1.5, -0.5)],
+            ..Default::default()
+        };
+        let bent = distort(&line, &distortions[2], 0.01);
+        assert!(bent.points.len() > 8);
+        assert_eq!(bent.points[0], distortions[2].apply(line.points[0]));
+    }
+
+    /// Verify that custom shapes are flattened before they are distorted.
+    #[test]
+    fn distort_custom_shape() {
+        let custom = custom_capsule();
+        let distortion = Distortion::Swirl {
+            center: Vec2::ZERO,
+            radius: 5.0,
+            angle: 1.0,
+        };
+
+        let bent = distort(&custom, &distortion, 0.01);
+        assert_eq!(bent, distort(&custom.flattened(), &distortion, 0.01));
+        assert!(bent.custom.is_none() && !bent.points.is_empty());
+    }
+}
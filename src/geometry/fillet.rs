@@ -0,0 +1,154 @@
+use std::f32::consts::PI;
+
+use glam::{Mat2, Vec2};
+
+use crate::Shape;
+
+/// The largest angle, in radians, covered by a single segment of a rounded corner.
+const MAX_ARC_STEP: f32 = PI / 16.0;
+
+/// Round off the sharp corners of every path in `shape` with arcs of `radius`.
+///
+/// Every corner of a closed path is rounded, but the ends of an open path are left in place.
+/// Where edges are too short to fit the full radius, the arc shrinks so that it uses at most half of each edge.
+pub fn fillet(shape: &Shape, radius: f32) -> Shape {
+    let shape: &Shape = &shape.flattened();
+    let mut paths = shape.paths().map(|path| fillet_path(path, radius));
+
+    Shape {
+        points: paths.next().unwrap_or_default(),
+        subpaths: paths.collect(),
+        ..shape.clone()
+    }
+}
+
+/// Round off the corners of a single path, open or closed.
+fn fillet_path(points: &[Vec2], radius: f32) -> Vec<Vec2> {
+    let closed = points.len() >= 4 && points[0] == points[points.len() - 1];
+    if radius <= 0.0 || points.len() < 3 || (points.len() == 3 && points[0] == points[2]) {
+        return points.to_vec();
+    }
+
+    let ring = if closed {
+        &points[..points.len() - 1]
+    } else {
+        points
+    };
+    let count = ring.len();
+
+    let mut rounded = Vec::with_capacity(count * 4);
+    for i in 0..count {
+        let corner = if closed {
+            Some((ring[(i + count - 1) % count], ring[(i + 1) % count]))
+        } else if i > 0 && i < count - 1 {
+            Some((ring[i - 1], ring[i + 1]))
+        } else {
+            None
+        };
+
+        match corner {
+            Some((before, after)) => round_corner(&mut rounded, before, ring[i], after, radius),
+            None => rounded.push(ring[i]),
+        }
+    }
+
+    if closed {
+        rounded.push(rounded[0]);
+    }
+
+    rounded
+}
+
+/// Push the arc that replaces the corner at `point`, between the edges to `before` and `after`.
+fn round_corner(rounded: &mut Vec<Vec2>, before: Vec2, point: Vec2, after: Vec2, radius: f32) {
+    let (to_before, to_after) = (before - point, after - point);
+    let (u, v) = (to_before.normalize_or_zero(), to_after.normalize_or_zero());
+
+    // The angle inside the corner. Straight and folded-back corners cannot be rounded.
+    let angle = u.dot(v).clamp(-1.0, 1.0).acos();
+    if u == Vec2::ZERO || v == Vec2::ZERO || angle < 1e-4 || PI - angle < 1e-4 {
+        rounded.push(point);
+        return;
+    }
+
+    // How far along each edge the arc meets it, kept to half of the shorter edge.
+    let half_tan = (angle / 2.0).tan();
+    let distance = (radius / half_tan)
+        .min(to_before.length() / 2.0)
+        .min(to_after.length() / 2.0);
+    let radius = distance * half_tan;
+
+    let center = point + (u + v).normalize() * (radius / (angle / 2.0).sin());
+    let start = point + u * distance - center;
+    let end = point + v * distance - center;
+    let sweep = start.perp_dot(end).atan2(start.dot(end));
+
+    let steps = (sweep.abs() / MAX_ARC_STEP).ceil().max(1.0) as usize;
+    for step in 0..=steps {
+        let angle = sweep * step as f32 / steps as f32;
+        rounded.push(center + Mat2::from_angle(angle) * start);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::custom_shape::custom_capsule;
+
+    /// Verify that square corners become arcs, and that the radius shrinks to fit short edges.
+    #[test]
+    fn fillet_square() {
+        let square = Shape {
+            points: vec![
+                Vec2::new(-1.0, -1.0),
+                Vec2::new(1.0, -1.0),
+                Vec2::new(1.0, 1.0),
+                Vec2::new(-1.0, 1.0),
+                Vec2::new(-1.0, -1.0),
+            ],
+            ..Default::default()
+        };
+
+        let rounded = fillet(&square, 0.5);
+        assert_eq!(rounded.points.first(), rounded.points.last());
+        assert!(rounded.points.len() > 5);
+        assert!(rounded
+            .points
+            .iter()
+            .all(|point| point.abs().max_element() <= 1.0 + 1e-6));
+        assert!(rounded
+            .points
+            .iter()
+            .any(|point| point.abs_diff_eq(Vec2::new(1.0, -0.5), 1e-6)));
+        assert!(rounded.points.iter().all(|point| {
+            let corner = point.abs() - Vec2::splat(0.5);
+            corner.min_element() <= 1e-6 || corner.length() <= 0.5 + 1e-5
+        }));
+
+        // A radius of 10 is clamped to 1, turning the square into a circle.
+        let circle = fillet(&square, 10.0);
+        assert!(circle
+            .points
+            .iter()
+            .all(|point| (point.length() - 1.0).abs() < 1e-5));
+
+        // The ends of open paths stay put.
+        let line = Shape {
+            points: vec![Vec2::ZERO, Vec2::X, Vec2::ONE],
+            ..Default::default()
+        };
+        let rounded = fillet(&line, 0.25);
+        assert_eq!(rounded.points.first(), Some(&Vec2::ZERO));
+        assert_eq!(rounded.points.last(), Some(&Vec2::ONE));
+    }
+
+    /// Verify that custom shapes are flattened before their corners are rounded.
+    #[test]
+    fn fillet_custom_shape() {
+        let custom = custom_capsule();
+
+        let rounded = fillet(&custom, 0.1);
+        assert_eq!(rounded, fillet(&custom.flattened(), 0.1));
+        assert!(!rounded.points.is_empty());
+    }
+}
@@ -0,0 +1,355 @@
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+use glam::{DMat3, DVec2, DVec3, Vec2};
+
+/// A straight line fit through points, from [fit_line].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LineFit {
+    /// Where the points start along the line.
+    pub start: Vec2,
+    /// Where the points end along the line.
+    pub end: Vec2,
+    /// The root mean square distance from the points to the line.
+    pub error: f32,
+}
+
+impl LineFit {
+    /// The ends of the line, ready to draw.
+    pub fn points(&self) -> Vec<Vec2> {
+        vec![self.start, self.end]
+    }
+}
+
+/// A circle fit through points, from [fit_circle].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CircleFit {
+    /// The center of the circle.
+    pub center: Vec2,
+    /// The radius of the circle.
+    pub radius: f32,
+    /// The root mean square distance from the points to the circle.
+    pub error: f32,
+}
+
+impl CircleFit {
+    /// The outline of the circle as a closed polygon of `segments` sides, ready to draw.
+    pub fn points(&self, segments: usize) -> Vec<Vec2> {
+        ellipse_points(self.center, Vec2::splat(self.radius), 0.0, segments)
+    }
+}
+
+/// An ellipse fit through points, from [fit_ellipse].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EllipseFit {
+    /// The center of the ellipse.
+    pub center: Vec2,
+    /// The radius of the ellipse along its rotated x axis, then its rotated y axis. The first is never the shorter.
+    pub radii: Vec2,
+    /// How far the ellipse's longer axis is rotated counter-clockwise from the x axis, in radians, between -π/2 and π/2.
+    pub rotation: f32,
+    /// The root mean square distance from the points to the ellipse, measured towards its center.
+    pub error: f32,
+}
+
+impl EllipseFit {
+    /// The outline of the ellipse as a closed polygon of `segments` sides, ready to draw.
+    pub fn points(&self, segments: usize) -> Vec<Vec2> {
+        ellipse_points(self.center, self.radii, self.rotation, segments)
+    }
+}
+
+/// The straight line closest to every point, measuring distance straight across the line rather than vertically.
+///
+/// The line runs as far as the points do. Returns `None` with fewer than two distinct points.
+pub fn fit_line(points: &[Vec2]) -> Option<LineFit> {
+    let (mean, points) = centered(points)?;
+
+    let (xx, xy, yy) = points.iter().fold((0.0, 0.0, 0.0), |(xx, xy, yy), p| {
+        (xx + p.x * p.x, xy + p.x * p.y, yy + p.y * p.y)
+    });
+    if xx + yy == 0.0 {
+        return None;
+    }
+
+    // The line runs along the direction the points spread out the most.
+    let angle = 0.5 * (2.0 * xy).atan2(xx - yy);
+    let direction = DVec2::new(angle.cos(), angle.sin());
+    let (min, max, squares) =
+        points
+            .iter()
+            .fold((f64::MAX, f64::MIN, 0.0), |(min, max, squares), point| {
+                let along = point.dot(direction);
+                let across = point.perp_dot(direction);
+                (min.min(along), max.max(along), squares + across * across)
+            });
+
+    Some(LineFit {
+        start: (mean + direction * min).as_vec2(),
+        end: (mean + direction * max).as_vec2(),
+        error: (squares / points.len() as f64).sqrt() as f32,
+    })
+}
+
+/// The circle closest to every point, such as to find the curve of a measured arc.
+///
+/// Returns `None` with fewer than three points, or if they all lie on a line.
+pub fn fit_circle(points: &[Vec2]) -> Option<CircleFit> {
+    if points.len() < 3 {
+        return None;
+    }
+    let (mean, points) = centered(points)?;
+
+    // Solve for the circle x² + y² + dx + ey + f = 0 closest to the points, by least squares.
+    let (mut normal, mut target) = (DMat3::ZERO, DVec3::ZERO);
+    for point in &points {
+        let row = DVec3::new(point.x, point.y, 1.0);
+        normal += outer(row, row);
+        target -= row * point.length_squared();
+    }
+    if normal.determinant().abs() < 1e-12 {
+        return None;
+    }
+    let solution = normal.inverse().mul_vec3(target);
+
+    let center = DVec2::new(-solution.x, -solution.y) / 2.0;
+    let radius = (center.length_squared() - solution.z).sqrt();
+    let squares: f64 = points
+        .iter()
+        .map(|point| (point.distance(center) - radius).powi(2))
+        .sum();
+
+    Some(CircleFit {
+        center: (mean + center).as_vec2(),
+        radius: radius as f32,
+        error: (squares / points.len() as f64).sqrt() as f32,
+    })
+}
+
+/// The ellipse closest to every point, such as to find the outline of a traced blob.
+///
+/// Uses Fitzgibbon's direct least squares fit, which always gives an ellipse rather than another conic.
+/// Returns `None` with fewer than five points, or if they do not describe an ellipse, such as if they all lie on a line.
+pub fn fit_ellipse(points: &[Vec2]) -> Option<EllipseFit> {
+    if points.len() < 5 {
+        return None;
+    }
+    let (mean, points) = centered(points)?;
+    // Scale the points to around a unit in size, to keep the squares and products well conditioned.
+    let scale = (points
+        .iter()
+        .map(|point| point.length_squared())
+        .sum::<f64>()
+        / points.len() as f64)
+        .sqrt();
+    let points: Vec<DVec2> = points.iter().map(|point| *point / scale).collect();
+
+    // Halíř and Flusser's numerically stable form: split the conic ax² + bxy + cy² + dx + ey + f = 0
+    // into its quadratic and linear parts, and solve for the quadratic part as an eigenvector.
+    let (mut quadratic, mut mixed, mut linear) = (DMat3::ZERO, DMat3::ZERO, DMat3::ZERO);
+    for point in &points {
+        let squares = DVec3::new(point.x * point.x, point.x * point.y, point.y * point.y);
+        let line = DVec3::new(point.x, point.y, 1.0);
+        quadratic += outer(squares, squares);
+        mixed += outer(squares, line);
+        linear += outer(line, line);
+    }
+    if linear.determinant().abs() < 1e-12 {
+        return None;
+    }
+    let to_linear = -(linear.inverse() * mixed.transpose());
+    let reduced = quadratic + mixed * to_linear;
+    // Premultiply by the inverse of the constraint 4ac - b² = 1.
+    let constraint = DMat3::from_cols(
+        DVec3::new(0.0, 0.0, 0.5),
+        DVec3::new(0.0, -1.0, 0.0),
+        DVec3::new(0.5, 0.0, 0.0),
+    );
+    let system = constraint * reduced;
+
+    let conic = eigenvectors(system)
+        .into_iter()
+        .find(|v| 4.0 * v.x * v.z - v.y * v.y > 0.0)?;
+    // Either sign describes the same conic, so pick the one that is negative inside the ellipse.
+    let conic = if conic.x < 0.0 { -conic } else { conic };
+    let rest = to_linear.mul_vec3(conic);
+    let (a, b, c) = (conic.x, conic.y, conic.z);
+    let (d, e, f) = (rest.x, rest.y, rest.z);
+
+    // Move the conic to its center, then read the radii off its principal axes.
+    let discriminant = b * b - 4.0 * a * c;
+    let center = DVec2::new(2.0 * c * d - b * e, 2.0 * a * e - b * d) / discriminant;
+    let at_center = a * center.x * center.x
+        + b * center.x * center.y
+        + c * center.y * center.y
+        + d * center.x
+        + e * center.y
+        + f;
+    let spread = (((a - c) / 2.0).powi(2) + (b / 2.0).powi(2)).sqrt();
+    let (steep, shallow) = ((a + c) / 2.0 + spread, (a + c) / 2.0 - spread);
+    // The form curves most steeply across the shorter radius, so turn a quarter from there to lie along the longer one.
+    let rotation = 0.5 * b.atan2(a - c) + FRAC_PI_2;
+    let rotation = if rotation > FRAC_PI_2 {
+        rotation - PI
+    } else {
+        rotation
+    };
+    let radii = DVec2::new((-at_center / shallow).sqrt(), (-at_center / steep).sqrt());
+    if !radii.is_finite() {
+        return None;
+    }
+
+    let (sin, cos) = rotation.sin_cos();
+    let squares: f64 = points
+        .iter()
+        .map(|point| {
+            let offset = *point - center;
+            let local = DVec2::new(
+                offset.dot(DVec2::new(cos, sin)),
+                offset.perp_dot(DVec2::new(cos, sin)),
+            );
+            let length = (local / radii).length();
+            (offset.length() * (1.0 - 1.0 / length)).powi(2)
+        })
+        .sum();
+
+    Some(EllipseFit {
+        center: (mean + center * scale).as_vec2(),
+        radii: (radii * scale).as_vec2(),
+        rotation: rotation as f32,
+        error: ((squares / points.len() as f64).sqrt() * scale) as f32,
+    })
+}
+
+/// The mean of `points`, and the points moved so the mean is at the origin, in `f64` to keep precision through the sums.
+fn centered(points: &[Vec2]) -> Option<(DVec2, Vec<DVec2>)> {
+    if points.len() < 2 {
+        return None;
+    }
+    let points: Vec<DVec2> = points.iter().map(|point| point.as_dvec2()).collect();
+    let mean = points.iter().fold(DVec2::ZERO, |sum, point| sum + *point) / points.len() as f64;
+    Some((mean, points.iter().map(|point| *point - mean).collect()))
+}
+
+/// The matrix `a bᵀ`.
+fn outer(a: DVec3, b: DVec3) -> DMat3 {
+    DMat3::from_cols(a * b.x, a * b.y, a * b.z)
+}
+
+/// The eigenvectors of `matrix` with real eigenvalues.
+fn eigenvectors(matrix: DMat3) -> Vec<DVec3> {
+    let rows = matrix.transpose();
+    let (x, y, z) = (rows.x_axis, rows.y_axis, rows.z_axis);
+
+    // The characteristic polynomial λ³ - tλ² + mλ - det, from the trace and the principal minors.
+    let trace = x.x + y.y + z.z;
+    let minors = x.x * y.y - x.y * y.x + x.x * z.z - x.z * z.x + y.y * z.z - y.z * z.y;
+    let roots = cubic_roots(-trace, minors, -matrix.determinant());
+
+    roots
+        .into_iter()
+        .filter_map(|root| {
+            let (x, y, z) = (
+                x - DVec3::X * root,
+                y - DVec3::Y * root,
+                z - DVec3::Z * root,
+            );
+            // The eigenvector is perpendicular to every row of the singular matrix, so take the best conditioned cross product.
+            [x.cross(y), y.cross(z), z.cross(x)]
+                .into_iter()
+                .max_by(|a, b| a.length_squared().total_cmp(&b.length_squared()))
+                .filter(|vector| vector.length_squared() > 0.0)
+        })
+        .collect()
+}
+
+/// The real roots of `x³ + a x² + b x + c`.
+fn cubic_roots(a: f64, b: f64, c: f64) -> Vec<f64> {
+    // Substitute x = t - a/3 to get the depressed cubic t³ + pt + q.
+    let p = b - a * a / 3.0;
+    let q = 2.0 * a * a * a / 27.0 - a * b / 3.0 + c;
+    let shift = -a / 3.0;
+    let discriminant = (q / 2.0).powi(2) + (p / 3.0).powi(3);
+
+    if discriminant > 0.0 {
+        let root = discriminant.sqrt();
+        vec![(-q / 2.0 + root).cbrt() + (-q / 2.0 - root).cbrt() + shift]
+    } else if p == 0.0 {
+        vec![shift]
+    } else {
+        // Three real roots, by the trigonometric method.
+        let radius = 2.0 * (-p / 3.0).sqrt();
+        let angle = (3.0 * q / (p * radius)).clamp(-1.0, 1.0).acos() / 3.0;
+        (0..3)
+            .map(|k| radius * (angle - TAU * k as f64 / 3.0).cos() + shift)
+            .collect()
+    }
+}
+
+/// The outline of an ellipse as a closed polygon.
+fn ellipse_points(center: Vec2, radii: Vec2, rotation: f32, segments: usize) -> Vec<Vec2> {
+    let segments = segments.max(3);
+    let axis = Vec2::new(rotation.cos(), rotation.sin());
+    (0..=segments)
+        .map(|i| {
+            let angle = std::f32::consts::TAU * (i % segments) as f32 / segments as f32;
+            let local = Vec2::new(angle.cos(), angle.sin()) * radii;
+            center + axis * local.x + axis.perp() * local.y
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that lines, circles and ellipses are recovered from noisy points on them.
+    #[test]
+    fn fit_shapes() {
+        // A deterministic wobble, standing in for measurement noise.
+        let noise = |i: usize| 0.01 * ((i * 7919) % 13) as f32 / 13.0 - 0.005;
+
+        let line: Vec<Vec2> = (0..20)
+            .map(|i| Vec2::new(i as f32, 2.0 * i as f32 + 1.0) + Vec2::splat(noise(i)))
+            .collect();
+        let fit = fit_line(&line).unwrap();
+        assert!(fit.start.abs_diff_eq(Vec2::new(0.0, 1.0), 0.02));
+        assert!(fit.end.abs_diff_eq(Vec2::new(19.0, 39.0), 0.02));
+        assert!(fit.error < 0.01);
+
+        let ellipse = EllipseFit {
+            center: Vec2::new(3.0, -2.0),
+            radii: Vec2::new(4.0, 1.5),
+            rotation: 0.5,
+            error: 0.0,
+        };
+        let points: Vec<Vec2> = ellipse.points(40)[..40]
+            .iter()
+            .enumerate()
+            .map(|(i, point)| *point + Vec2::splat(noise(i)))
+            .collect();
+        let fit = fit_ellipse(&points).unwrap();
+        assert!(fit.center.abs_diff_eq(ellipse.center, 0.01));
+        assert!(fit.radii.abs_diff_eq(ellipse.radii, 0.01));
+        assert!((fit.rotation - ellipse.rotation).abs() < 0.01);
+        assert!(fit.error < 0.01);
+
+        // Only a quarter of the circle is given, as from a measured arc.
+        let arc: Vec<Vec2> = CircleFit {
+            center: Vec2::new(-1.0, 5.0),
+            radius: 2.0,
+            error: 0.0,
+        }
+        .points(40)[..11]
+            .to_vec();
+        let fit = fit_circle(&arc).unwrap();
+        assert!(fit.center.abs_diff_eq(Vec2::new(-1.0, 5.0), 1e-3));
+        assert!((fit.radius - 2.0).abs() < 1e-3);
+
+        let straight: Vec<Vec2> = (0..10).map(|i| Vec2::splat(i as f32)).collect();
+        assert_eq!(fit_circle(&straight), None);
+        assert_eq!(fit_ellipse(&straight), None);
+    }
+}
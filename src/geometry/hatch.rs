@@ -0,0 +1,274 @@
+use glam::{Mat2, Vec2};
+
+use super::{clip_outside, contains_point, offset_polygon, signed_area};
+use crate::{math::portable_sin_cos, FillRule};
+
+/// The arrangement of lines a [Hatch] fills shapes with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HatchPattern {
+    /// Parallel lines at the hatch angle.
+    #[default]
+    Parallel,
+    /// Two sets of parallel lines, at the hatch angle and perpendicular to it.
+    Cross,
+    /// Rings that follow the outline inwards, each `spacing` inside the last. The angle is ignored.
+    ///
+    /// Holes keep their outline, but are not followed outwards. Rings are cut where they cross a hole.
+    Contour,
+}
+
+/// How to turn a filled area into lines that a pen plotter can draw.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hatch {
+    /// Angle of the lines, in radians counter-clockwise from the x axis.
+    pub angle: f32,
+    /// Distance between neighbouring lines.
+    pub spacing: f32,
+    /// The arrangement of the lines.
+    pub pattern: HatchPattern,
+}
+
+impl Hatch {
+    /// Create a new [Hatch] of parallel lines.
+    pub fn new(angle: f32, spacing: f32) -> Self {
+        Self {
+            angle,
+            spacing,
+            pattern: HatchPattern::Parallel,
+        }
+    }
+
+    /// Modify the pattern, consuming the parent.
+    pub fn with_pattern(mut self, pattern: HatchPattern) -> Self {
+        self.pattern = pattern;
+        self
+    }
+}
+
+/// Fill the area inside a set of closed paths with hatching lines.
+///
+/// `fill_rule` decides which areas are inside, just like for a filled [Shape](crate::Shape).
+/// Every returned line is an open polyline, and neighbouring parallel lines alternate direction to keep pen travel short.
+pub fn hatch_paths(paths: &[&[Vec2]], fill_rule: FillRule, hatch: &Hatch) -> Vec<Vec<Vec2>> {
+    if hatch.spacing.is_nan() || hatch.spacing <= 0.0 {
+        return Vec::new();
+    }
+
+    match hatch.pattern {
+        HatchPattern::Parallel => scan_lines(paths, fill_rule, hatch.angle, hatch.spacing),
+        HatchPattern::Cross => {
+            let mut lines = scan_lines(paths, fill_rule, hatch.angle, hatch.spacing);
+            let perpendicular = hatch.angle + std::f32::consts::FRAC_PI_2;
+            lines.extend(scan_lines(paths, fill_rule, perpendicular, hatch.spacing));
+            lines
+        }
+        HatchPattern::Contour => contours(paths, hatch.spacing),
+    }
+}
+
+/// Lines across the area at `angle`, found by scanning horizontal lines through the rotated paths.
+fn scan_lines(paths: &[&[Vec2]], fill_rule: FillRule, angle: f32, spacing: f32) -> Vec<Vec<Vec2>> {
+    let (sin, cos) = portable_sin_cos(angle);
+    let to_scan = Mat2::from_cols(Vec2::new(cos, -sin), Vec2::new(sin, cos));
+    let from_scan = to_scan.transpose();
+
+    let edges: Vec<(Vec2, Vec2)> = paths
+        .iter()
+        .filter(|path| path.len() > 1)
+        .flat_map(|path| {
+            (0..path.len()).map(move |i| {
+                let a = to_scan * path[i];
+                let b = to_scan * path[(i + 1) % path.len()];
+                (a, b)
+            })
+        })
+        .collect();
+
+    let (min_y, max_y) = edges
+        .iter()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), (a, _)| {
+            (min.min(a.y), max.max(a.y))
+        });
+
+    let mut lines = Vec::new();
+    let mut crossings: Vec<(f32, i32)> = Vec::new();
+    let mut y = min_y + spacing / 2.0;
+
+    while y < max_y {
+        crossings.clear();
+        for &(a, b) in &edges {
+            if (a.y <= y) != (b.y <= y) {
+                let x = a.x + (y - a.y) / (b.y - a.y) * (b.x - a.x);
+                crossings.push((x, if b.y > a.y { 1 } else { -1 }));
+            }
+        }
+        crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut winding = 0;
+        let mut start = None;
+        for &(x, direction) in &crossings {
+            let was_inside = is_inside(winding, fill_rule);
+            winding += direction;
+
+            match (was_inside, is_inside(winding, fill_rule)) {
+                (false, true) => start = Some(x),
+                (true, false) => {
+                    if let Some(start) = start.take() {
+                        let mut line =
+                            vec![from_scan * Vec2::new(start, y), from_scan * Vec2::new(x, y)];
+                        if lines.len() % 2 == 1 {
+                            line.reverse();
+                        }
+                        lines.push(line);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        y += spacing;
+    }
+
+    lines
+}
+
+/// Checks if an area with the given winding number is filled under `fill_rule`.
+pub(super) fn is_inside(winding: i32, fill_rule: FillRule) -> bool {
+    match fill_rule {
+        FillRule::NonZero => winding != 0,
+        FillRule::EvenOdd => winding % 2 != 0,
+    }
+}
+
+/// Rings that shrink inwards from each outer path until they collapse, cut where they cross a hole.
+fn contours(paths: &[&[Vec2]], spacing: f32) -> Vec<Vec<Vec2>> {
+    // Close every path, and tell holes apart by how many other paths they sit inside.
+    let closed: Vec<(Vec<Vec2>, bool)> = paths
+        .iter()
+        .enumerate()
+        .filter(|(_, path)| path.len() >= 3)
+        .map(|(i, path)| {
+            let mut ring = path.to_vec();
+            if ring[0] != ring[ring.len() - 1] {
+                ring.push(ring[0]);
+            }
+
+            let depth = paths
+                .iter()
+                .enumerate()
+                .filter(|&(j, other)| j != i && other.len() > 2 && contains_point(other, path[0]))
+                .count();
+            (ring, depth % 2 == 1)
+        })
+        .collect();
+    let holes: Vec<&[Vec2]> = closed
+        .iter()
+        .filter(|(_, is_hole)| *is_hole)
+        .map(|(ring, _)| ring.as_slice())
+        .collect();
+
+    let mut rings = Vec::new();
+    for (ring, is_hole) in &closed {
+        rings.push(ring.clone());
+
+        if *is_hole {
+            continue;
+        }
+
+        // Stop once the ring turns itself inside out, or becomes too small to draw another line in.
+        let area = signed_area(ring);
+        for step in 1.. {
+            let inset = offset_polygon(ring, -spacing * step as f32);
+            let inset_area = signed_area(&inset);
+            if inset_area.signum() != area.signum() || inset_area.abs() < spacing * spacing {
+                break;
+            }
+
+            if holes.is_empty() {
+                rings.push(inset);
+            } else {
+                rings.extend(clip_outside(&inset, &holes, FillRule::NonZero));
+            }
+        }
+    }
+
+    rings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that parallel hatching covers a square with evenly spaced lines, skipping its hole.
+    #[test]
+    fn hatch_square_with_hole() {
+        let outer = [
+            Vec2::ZERO,
+            Vec2::new(4.0, 0.0),
+            Vec2::new(4.0, 4.0),
+            Vec2::new(0.0, 4.0),
+        ];
+        let hole = [
+            Vec2::ONE,
+            Vec2::new(3.0, 1.0),
+            Vec2::new(3.0, 3.0),
+            Vec2::new(1.0, 3.0),
+        ];
+
+        let lines = hatch_paths(&[&outer, &hole], FillRule::EvenOdd, &Hatch::new(0.0, 1.0));
+
+        // Rows at y = 0.5 and 3.5 cross the whole square, rows at 1.5 and 2.5 are split by the hole.
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[0], vec![Vec2::new(0.0, 0.5), Vec2::new(4.0, 0.5)]);
+        assert_eq!(lines[1], vec![Vec2::new(1.0, 1.5), Vec2::new(0.0, 1.5)]);
+
+        let cross = hatch_paths(
+            &[&outer],
+            FillRule::NonZero,
+            &Hatch::new(0.0, 1.0).with_pattern(HatchPattern::Cross),
+        );
+        assert_eq!(cross.len(), 8);
+
+        let contour = hatch_paths(
+            &[&outer],
+            FillRule::NonZero,
+            &Hatch::new(0.0, 0.5).with_pattern(HatchPattern::Contour),
+        );
+        assert_eq!(contour.len(), 4);
+    }
+
+    /// Verify that contour rings are cut where they cross a hole.
+    #[test]
+    fn contours_skip_holes() {
+        let outer = [
+            Vec2::ZERO,
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, 4.0),
+            Vec2::new(0.0, 4.0),
+        ];
+        let hole = [
+            Vec2::new(4.0, 0.5),
+            Vec2::new(6.0, 0.5),
+            Vec2::new(6.0, 1.5),
+            Vec2::new(4.0, 1.5),
+        ];
+
+        let contour = hatch_paths(
+            &[&outer, &hole],
+            FillRule::EvenOdd,
+            &Hatch::new(0.0, 1.0).with_pattern(HatchPattern::Contour),
+        );
+
+        // The outline, the hole, and the ring at y = 1 split in two by the hole.
+        assert_eq!(contour.len(), 4);
+        let in_hole =
+            |point: Vec2| point.x > 4.0 && point.x < 6.0 && point.y > 0.5 && point.y < 1.5;
+        for line in &contour {
+            assert!(line
+                .windows(2)
+                .all(|pair| !in_hole(pair[0].lerp(pair[1], 0.5))));
+        }
+    }
+}
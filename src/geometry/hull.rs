@@ -0,0 +1,120 @@
+use glam::Vec2;
+
+use crate::Shape;
+
+/// The smallest convex polygon containing every point, using Andrew's monotone chain algorithm.
+///
+/// The hull winds counter-clockwise and is closed, with its first point repeated at the end. Points along its edges are left out.
+/// The returned shape has no stroke or fill. Fewer than three distinct points give a shape with those points, which is not drawable as an area.
+pub fn convex_hull(points: &[Vec2]) -> Shape {
+    let mut sorted: Vec<Vec2> = points.iter().copied().filter(|p| p.is_finite()).collect();
+    sorted.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    sorted.dedup();
+
+    if sorted.len() < 3 {
+        return Shape {
+            points: sorted,
+            ..Default::default()
+        };
+    }
+
+    // Build the lower hull from left to right, then the upper hull back from right to left, dropping any point that does not turn left.
+    // The upper hull ends on the first point, closing the polygon.
+    let mut hull: Vec<Vec2> = Vec::with_capacity(sorted.len() + 1);
+    for &point in &sorted {
+        push_turning_left(&mut hull, point, 2);
+    }
+    let floor = hull.len() + 1;
+    for &point in sorted.iter().rev().skip(1) {
+        push_turning_left(&mut hull, point, floor);
+    }
+
+    Shape {
+        points: hull,
+        ..Default::default()
+    }
+}
+
+/// Push `point` onto a hull, first removing points that would not turn left while the hull has at least `floor` of them.
+fn push_turning_left(hull: &mut Vec<Vec2>, point: Vec2, floor: usize) {
+    while hull.len() >= floor {
+        let (a, b) = (hull[hull.len() - 2], hull[hull.len() - 1]);
+        if (b - a).perp_dot(point - a) > 0.0 {
+            break;
+        }
+        hull.pop();
+    }
+    hull.push(point);
+}
+
+/// The Minkowski sum of two shapes: the area covered by sliding `b` around every point of `a`.
+///
+/// Both shapes are treated as their [convex hulls](convex_hull), so the result is exact for convex shapes and covers concave ones.
+/// Sliding a small circle around a shape grows it with rounded corners, and two shapes overlap exactly when the sum of one with the other, mirrored, contains the origin.
+pub fn minkowski_sum(a: &Shape, b: &Shape) -> Shape {
+    let (a, b): (&Shape, &Shape) = (&a.flattened(), &b.flattened());
+    let sums: Vec<Vec2> = a
+        .paths()
+        .flatten()
+        .flat_map(|p| b.paths().flatten().map(move |q| *p + *q))
+        .collect();
+
+    convex_hull(&sums)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::custom_shape::custom_capsule;
+
+    /// Verify that hulls drop inner points, and that the sum of two squares is a larger square.
+    #[test]
+    fn hull_and_sum() {
+        let points = [
+            Vec2::ZERO,
+            Vec2::new(2.0, 0.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(0.0, 2.0),
+            Vec2::new(1.0, 0.0),
+        ];
+
+        assert_eq!(
+            convex_hull(&points).points,
+            vec![
+                Vec2::ZERO,
+                Vec2::new(2.0, 0.0),
+                Vec2::new(2.0, 2.0),
+                Vec2::new(0.0, 2.0),
+                Vec2::ZERO,
+            ]
+        );
+
+        let square = convex_hull(&[Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y]);
+        let sum = minkowski_sum(&square, &square);
+        assert_eq!(
+            sum.points,
+            vec![
+                Vec2::ZERO,
+                Vec2::new(2.0, 0.0),
+                Vec2::new(2.0, 2.0),
+                Vec2::new(0.0, 2.0),
+                Vec2::ZERO,
+            ]
+        );
+    }
+
+    /// Verify that custom shapes are flattened before they are summed.
+    #[test]
+    fn minkowski_sum_custom_shape() {
+        let custom = custom_capsule();
+        let square = Shape {
+            points: vec![Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y, Vec2::ZERO],
+            ..Default::default()
+        };
+
+        let sum = minkowski_sum(&custom, &square);
+        assert_eq!(sum, minkowski_sum(&custom.flattened(), &square));
+        assert_eq!(sum.bounds().unwrap().1, Vec2::new(12.0, 2.0));
+    }
+}
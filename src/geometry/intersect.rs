@@ -0,0 +1,219 @@
+use glam::Vec2;
+
+use crate::Shape;
+
+/// A point where the outlines of two different shapes meet, as found by [all_intersections].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Intersection {
+    /// Where the outlines meet.
+    pub point: Vec2,
+    /// Index of the first shape.
+    pub first: usize,
+    /// Index of the second shape, which is always greater than [first](Self::first).
+    pub second: usize,
+}
+
+/// Where the segment from `a0` to `a1` meets the segment from `b0` to `b1`, if it does.
+///
+/// Segments that only touch at an end count as meeting. If the segments overlap along a line, the end of the overlap nearest `a0` is returned.
+pub fn segment_intersection(a0: Vec2, a1: Vec2, b0: Vec2, b1: Vec2) -> Option<Vec2> {
+    let (r, s) = (a1 - a0, b1 - b0);
+    let length_squared = r.length_squared();
+    if length_squared == 0.0 || s.length_squared() == 0.0 {
+        return None;
+    }
+
+    let denominator = r.perp_dot(s);
+    if denominator == 0.0 {
+        // Parallel segments only meet if they lie on the same line and overlap along it.
+        if (b0 - a0).perp_dot(r) != 0.0 {
+            return None;
+        }
+
+        let t0 = (b0 - a0).dot(r) / length_squared;
+        let t1 = (b1 - a0).dot(r) / length_squared;
+        let (start, end) = (t0.min(t1).max(0.0), t0.max(t1).min(1.0));
+        return (start <= end).then(|| a0 + r * start);
+    }
+
+    let t = (b0 - a0).perp_dot(s) / denominator;
+    let u = (b0 - a0).perp_dot(r) / denominator;
+    ((0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)).then(|| a0 + r * t)
+}
+
+/// Checks if two segments properly cross each other. Touching endpoints and collinear overlaps do not count.
+pub(crate) fn segments_cross(a0: Vec2, a1: Vec2, b0: Vec2, b1: Vec2) -> bool {
+    let d0 = (a1 - a0).perp_dot(b0 - a0);
+    let d1 = (a1 - a0).perp_dot(b1 - a0);
+    let d2 = (b1 - b0).perp_dot(a0 - b0);
+    let d3 = (b1 - b0).perp_dot(a1 - b0);
+
+    d0 * d1 < 0.0 && d2 * d3 < 0.0
+}
+
+/// Every point where the outline of `a` meets the outline of `b`, including their subpaths.
+///
+/// Closed paths include their closing edge only if their first point is repeated at the end. Each point is reported once.
+pub fn intersections(a: &Shape, b: &Shape) -> Vec<Vec2> {
+    let (a, b): (&Shape, &Shape) = (&a.flattened(), &b.flattened());
+    let mut both = segments(a, 0);
+    both.extend(segments(b, 1));
+
+    sweep(both)
+        .into_iter()
+        .map(|intersection| intersection.point)
+        .collect()
+}
+
+/// Every point where the outlines of two different shapes in `shapes` meet, found with a sweep line.
+///
+/// Only pairs of segments whose extents overlap along the x axis are compared, which is far faster than testing every pair when shapes are spread out.
+/// Points where a shape meets itself are not included. The result is sorted by the indices of the shapes.
+pub fn all_intersections(shapes: &[Shape]) -> Vec<Intersection> {
+    let segments = shapes
+        .iter()
+        .enumerate()
+        .flat_map(|(index, shape)| segments(shape, index))
+        .collect();
+
+    sweep(segments)
+}
+
+/// A segment of an outline, and the index of the shape it belongs to.
+struct Segment {
+    shape: usize,
+    start: Vec2,
+    end: Vec2,
+}
+
+impl Segment {
+    fn min(&self) -> Vec2 {
+        self.start.min(self.end)
+    }
+
+    fn max(&self) -> Vec2 {
+        self.start.max(self.end)
+    }
+}
+
+/// Every segment of every path in `shape`.
+fn segments(shape: &Shape, index: usize) -> Vec<Segment> {
+    shape
+        .paths()
+        .flat_map(|path| path.windows(2))
+        .map(|pair| Segment {
+            shape: index,
+            start: pair[0],
+            end: pair[1],
+        })
+        .collect()
+}
+
+/// Find where segments of different shapes meet, sweeping a vertical line from left to right.
+fn sweep(mut segments: Vec<Segment>) -> Vec<Intersection> {
+    segments.sort_by(|a, b| a.min().x.total_cmp(&b.min().x));
+
+    let mut found = Vec::new();
+    let mut active: Vec<&Segment> = Vec::new();
+
+    for segment in &segments {
+        let (min, max) = (segment.min(), segment.max());
+        active.retain(|other| other.max().x >= min.x);
+
+        for other in &active {
+            let overlaps = other.min().y <= max.y && other.max().y >= min.y;
+            if other.shape == segment.shape || !overlaps {
+                continue;
+            }
+
+            if let Some(point) =
+                segment_intersection(segment.start, segment.end, other.start, other.end)
+            {
+                found.push(Intersection {
+                    point,
+                    first: segment.shape.min(other.shape),
+                    second: segment.shape.max(other.shape),
+                });
+            }
+        }
+
+        active.push(segment);
+    }
+
+    // Points shared by two consecutive segments are found by both of them.
+    found.sort_by(|a, b| {
+        (a.first, a.second)
+            .cmp(&(b.first, b.second))
+            .then(a.point.x.total_cmp(&b.point.x))
+            .then(a.point.y.total_cmp(&b.point.y))
+    });
+    found.dedup();
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::custom_shape::custom_capsule;
+
+    /// Verify that crossings, touches and overlaps are found between shapes, but not within one.
+    #[test]
+    fn find_intersections() {
+        assert_eq!(
+            segment_intersection(Vec2::ZERO, Vec2::ONE, Vec2::Y, Vec2::X),
+            Some(Vec2::splat(0.5))
+        );
+        assert_eq!(
+            segment_intersection(
+                Vec2::ZERO,
+                Vec2::X,
+                Vec2::new(0.5, 0.0),
+                Vec2::new(2.0, 0.0)
+            ),
+            Some(Vec2::new(0.5, 0.0))
+        );
+        assert_eq!(
+            segment_intersection(Vec2::ZERO, Vec2::X, Vec2::Y, Vec2::ONE),
+            None
+        );
+
+        let square = Shape {
+            points: vec![Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y, Vec2::ZERO],
+            ..Default::default()
+        };
+        let line = Shape {
+            points: vec![Vec2::new(-1.0, 0.5), Vec2::new(2.0, 0.5)],
+            ..Default::default()
+        };
+        let far = Shape {
+            points: vec![Vec2::new(5.0, 5.0), Vec2::new(6.0, 6.0)],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            intersections(&square, &line),
+            vec![Vec2::new(0.0, 0.5), Vec2::new(1.0, 0.5)]
+        );
+        assert!(intersections(&square, &far).is_empty());
+
+        let found = all_intersections(&[line, far, square]);
+        assert_eq!(found.len(), 2);
+        assert!(found
+            .iter()
+            .all(|intersection| (intersection.first, intersection.second) == (0, 2)));
+    }
+
+    /// Verify that custom shapes are flattened before looking for crossings.
+    #[test]
+    fn intersect_custom_shape() {
+        let custom = custom_capsule();
+        let line = Shape {
+            points: vec![Vec2::new(5.0, -5.0), Vec2::new(5.0, 5.0)],
+            ..Default::default()
+        };
+
+        let crossings = intersections(&custom, &line);
+        assert_eq!(crossings, intersections(&custom.flattened(), &line));
+        assert_eq!(crossings.len(), 2);
+    }
+}
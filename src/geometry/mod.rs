@@ -0,0 +1,141 @@
+mod clip;
+mod curves;
+mod distort;
+mod fillet;
+mod fit;
+mod hatch;
+mod hull;
+mod intersect;
+mod occlusion;
+mod offset;
+mod outline;
+mod scatter;
+mod simplify;
+mod smooth;
+mod stroke;
+mod travel;
+mod triangulate;
+mod warp;
+
+pub(crate) use clip::clip_segment;
+pub use clip::{clip, ClipRegion};
+pub use curves::{space_filling_curve, space_filling_curve_in, SpaceFillingCurve};
+pub use distort::{distort, Distortion};
+pub use fillet::fillet;
+pub use fit::{fit_circle, fit_ellipse, fit_line, CircleFit, EllipseFit, LineFit};
+pub use hatch::{hatch_paths, Hatch, HatchPattern};
+pub use hull::{convex_hull, minkowski_sum};
+pub(crate) use intersect::segments_cross;
+pub use intersect::{all_intersections, intersections, segment_intersection, Intersection};
+pub use occlusion::clip_outside;
+pub use offset::offset_polygon;
+pub use outline::{variable_width_outline, WidthProfile};
+pub use scatter::{scatter_along_path, scatter_in_polygon};
+pub use simplify::simplify_polyline;
+pub use smooth::{chaikin_smooth, laplacian_smooth};
+pub use stroke::stroke_to_path;
+pub use travel::{optimize_travel, travel_distance};
+pub use triangulate::triangulate;
+pub use warp::{warp, Projective, Warp};
+
+use glam::Vec2;
+
+/// The cumulative distance along a polyline at each of its points.
+///
+/// The first entry is always `0.0`, and the last is the total length.
+pub fn arc_lengths(points: &[Vec2]) -> Vec<f32> {
+    let mut distances = Vec::with_capacity(points.len());
+    let mut total = 0.0;
+
+    if !points.is_empty() {
+        distances.push(0.0);
+    }
+
+    for pair in points.windows(2) {
+        total += pair[0].distance(pair[1]);
+        distances.push(total);
+    }
+
+    distances
+}
+
+/// The signed area of a polygon, using the shoelace formula.
+///
+/// Positive if the points wind counter-clockwise (with the y axis pointing up), negative if clockwise.
+/// The polygon may be closed (first and last points equal) or not.
+pub fn signed_area(points: &[Vec2]) -> f32 {
+    if points.len() < 3 {
+        return 0.0;
+    }
+
+    let mut area = 0.0;
+    for (i, a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        area += a.perp_dot(b);
+    }
+
+    area / 2.0
+}
+
+/// Checks if a point lies inside a polygon, using the even-odd rule.
+///
+/// The polygon may be closed (first and last points equal) or not. Points exactly on an edge may be considered either inside or outside.
+pub fn contains_point(polygon: &[Vec2], point: Vec2) -> bool {
+    let mut inside = false;
+
+    for (i, a) in polygon.iter().enumerate() {
+        let b = polygon[(i + 1) % polygon.len()];
+
+        if (a.y > point.y) != (b.y > point.y) {
+            let x = a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x);
+            if point.x < x {
+                inside = !inside;
+            }
+        }
+    }
+
+    inside
+}
+
+/// The shortest distance from a point to the line segment between `a` and `b`.
+pub fn distance_to_segment(point: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let along = b - a;
+    let t = if along.length_squared() > 0.0 {
+        ((point - a).dot(along) / along.length_squared()).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    point.distance(a + along * t)
+}
+
+/// A closed ring segment between two radii and two angles, in radians counter-clockwise from the x axis, such as a slice of a donut chart.
+///
+/// With an inner radius of zero it is a pie slice. The curved edges get a point every few degrees.
+pub fn annular_sector(
+    center: Vec2,
+    inner_radius: f32,
+    outer_radius: f32,
+    start_angle: f32,
+    end_angle: f32,
+) -> Vec<Vec2> {
+    let steps = ((end_angle - start_angle).abs() / (std::f32::consts::TAU / 96.0))
+        .ceil()
+        .max(1.0) as usize;
+    let arc = |radius: f32| {
+        (0..=steps).map(move |i| {
+            let angle = start_angle + (end_angle - start_angle) * i as f32 / steps as f32;
+            center + Vec2::new(angle.cos(), angle.sin()) * radius
+        })
+    };
+
+    let mut points: Vec<Vec2> = arc(outer_radius).collect();
+    if inner_radius > 0.0 {
+        let inner: Vec<Vec2> = arc(inner_radius).collect();
+        points.extend(inner.into_iter().rev());
+    } else {
+        points.push(center);
+    }
+    points.push(points[0]);
+    points
+}
@@ -0,0 +1,141 @@
+use glam::Vec2;
+
+use super::hatch::is_inside;
+use crate::FillRule;
+
+/// Cut away the parts of a polyline that fall inside a filled area, keeping the parts that stay visible.
+///
+/// `paths` and `fill_rule` describe the area just like a filled [Shape](crate::Shape). The visible parts are returned in order, each as an open polyline.
+pub fn clip_outside(polyline: &[Vec2], paths: &[&[Vec2]], fill_rule: FillRule) -> Vec<Vec<Vec2>> {
+    split_by_area(polyline, paths, fill_rule, false)
+}
+
+/// Cut away the parts of a polyline that fall outside a filled area, keeping the parts inside it.
+///
+/// The opposite of [clip_outside].
+pub(crate) fn clip_inside(
+    polyline: &[Vec2],
+    paths: &[&[Vec2]],
+    fill_rule: FillRule,
+) -> Vec<Vec<Vec2>> {
+    split_by_area(polyline, paths, fill_rule, true)
+}
+
+/// Split a polyline where it crosses the outline of an area, keeping the pieces inside it if `keep_inside` is `true`, or outside it otherwise.
+fn split_by_area(
+    polyline: &[Vec2],
+    paths: &[&[Vec2]],
+    fill_rule: FillRule,
+    keep_inside: bool,
+) -> Vec<Vec<Vec2>> {
+    let edges: Vec<(Vec2, Vec2)> = paths
+        .iter()
+        .filter(|path| path.len() > 1)
+        .flat_map(|path| (0..path.len()).map(move |i| (path[i], path[(i + 1) % path.len()])))
+        .collect();
+
+    let mut pieces = Vec::new();
+    let mut current: Vec<Vec2> = Vec::new();
+    let mut cuts = Vec::new();
+
+    for pair in polyline.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+
+        // Split the segment wherever it crosses the area's outline, so each piece is entirely inside or outside.
+        cuts.clear();
+        cuts.extend([0.0, 1.0]);
+        cuts.extend(edges.iter().filter_map(|&(c, d)| intersection(a, b, c, d)));
+        cuts.sort_by(f32::total_cmp);
+        cuts.dedup();
+
+        for span in cuts.windows(2) {
+            let (start, end) = (a.lerp(b, span[0]), a.lerp(b, span[1]));
+            if start == end {
+                continue;
+            }
+
+            let middle = start.lerp(end, 0.5);
+            if is_inside(winding_number(&edges, middle), fill_rule) != keep_inside {
+                if current.len() > 1 {
+                    pieces.push(std::mem::take(&mut current));
+                }
+                current.clear();
+                continue;
+            }
+
+            if current.last() != Some(&start) {
+                if current.len() > 1 {
+                    pieces.push(std::mem::take(&mut current));
+                }
+                current = vec![start];
+            }
+            current.push(end);
+        }
+    }
+
+    if current.len() > 1 {
+        pieces.push(current);
+    }
+
+    pieces
+}
+
+/// How far along `a` to `b` the segment crosses `c` to `d`, if it does.
+fn intersection(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> Option<f32> {
+    let (r, s) = (b - a, d - c);
+    let denominator = r.perp_dot(s);
+    if denominator == 0.0 {
+        return None;
+    }
+
+    let t = (c - a).perp_dot(s) / denominator;
+    let u = (c - a).perp_dot(r) / denominator;
+    ((0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)).then_some(t)
+}
+
+/// How many times the edges wind counter-clockwise around a point, minus how many times they wind clockwise.
+fn winding_number(edges: &[(Vec2, Vec2)], point: Vec2) -> i32 {
+    edges
+        .iter()
+        .map(|&(a, b)| {
+            let side = (b - a).perp_dot(point - a);
+            if a.y <= point.y && b.y > point.y && side > 0.0 {
+                1
+            } else if a.y > point.y && b.y <= point.y && side < 0.0 {
+                -1
+            } else {
+                0
+            }
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that a line passing behind a square is split around it.
+    #[test]
+    fn clip_line_behind_square() {
+        let square = [
+            Vec2::new(1.0, -1.0),
+            Vec2::new(2.0, -1.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(1.0, 1.0),
+        ];
+        let line = [Vec2::ZERO, Vec2::new(3.0, 0.0), Vec2::new(3.0, 1.0)];
+
+        let pieces = clip_outside(&line, &[&square], FillRule::NonZero);
+        assert_eq!(
+            pieces,
+            vec![
+                vec![Vec2::ZERO, Vec2::new(1.0, 0.0)],
+                vec![
+                    Vec2::new(2.0, 0.0),
+                    Vec2::new(3.0, 0.0),
+                    Vec2::new(3.0, 1.0)
+                ],
+            ]
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use glam::Vec2;
+
+use super::{outline::miter_normal, signed_area};
+
+/// Grow or shrink a closed polygon by moving each edge outwards by `distance`.
+///
+/// Negative distances shrink the polygon. Corners are mitered, and very sharp corners are clipped.
+/// The polygon may be closed (first and last points equal) or not, and the result is closed the same way.
+/// Shrinking by more than the polygon's inner radius produces an inverted, self-intersecting polygon.
+pub fn offset_polygon(points: &[Vec2], distance: f32) -> Vec<Vec2> {
+    let closed = points.len() > 1 && points[0] == points[points.len() - 1];
+    let ring = if closed {
+        &points[..points.len() - 1]
+    } else {
+        points
+    };
+
+    if ring.len() < 3 {
+        return points.to_vec();
+    }
+
+    // The left-hand side of a counter-clockwise polygon is its inside.
+    let outwards = if signed_area(ring) > 0.0 {
+        -distance
+    } else {
+        distance
+    };
+
+    let count = ring.len();
+    let mut offset: Vec<Vec2> = (0..count)
+        .map(|i| {
+            let before = ring[(i + count - 1) % count];
+            let after = ring[(i + 1) % count];
+            ring[i] + miter_normal(Some(before), ring[i], Some(after)) * outwards
+        })
+        .collect();
+
+    if closed {
+        offset.push(offset[0]);
+    }
+
+    offset
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that squares grow and shrink the same way regardless of winding.
+    #[test]
+    fn offset_square() {
+        let square = [
+            Vec2::new(-1.0, -1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(-1.0, -1.0),
+        ];
+        let reversed: Vec<Vec2> = square.iter().rev().copied().collect();
+
+        for points in [&square[..], &reversed[..]] {
+            let grown = offset_polygon(points, 0.5);
+            let shrunk = offset_polygon(points, -0.5);
+
+            assert_eq!(grown.len(), 5);
+            assert_eq!(grown[0], grown[4]);
+            assert!(grown
+                .iter()
+                .all(|p| p.abs().abs_diff_eq(Vec2::splat(1.5), 1e-6)));
+            assert!(shrunk
+                .iter()
+                .all(|p| p.abs().abs_diff_eq(Vec2::splat(0.5), 1e-6)));
+        }
+    }
+}
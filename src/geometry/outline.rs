@@ -0,0 +1,135 @@
+use glam::Vec2;
+
+use super::arc_lengths;
+
+/// The width of a line at each of its points.
+///
+/// Implemented for slices and [Vec]s of per-point widths, and for functions that take
+/// the distance along the line (from `0.0` at the start to `1.0` at the end) and return a width.
+pub trait WidthProfile {
+    /// Get the width at the point with the given index, which lies `t` of the way along the line.
+    fn width_at(&self, index: usize, t: f32) -> f32;
+}
+
+impl WidthProfile for [f32] {
+    /// Missing widths repeat the last one.
+    fn width_at(&self, index: usize, _t: f32) -> f32 {
+        self.get(index)
+            .or_else(|| self.last())
+            .copied()
+            .unwrap_or(0.0)
+    }
+}
+
+impl WidthProfile for Vec<f32> {
+    fn width_at(&self, index: usize, t: f32) -> f32 {
+        self.as_slice().width_at(index, t)
+    }
+}
+
+impl<F: Fn(f32) -> f32> WidthProfile for F {
+    fn width_at(&self, _index: usize, t: f32) -> f32 {
+        self(t)
+    }
+}
+
+/// Joins sharper than this are clipped, so the outline does not spike out to infinity.
+const MITER_LIMIT: f32 = 4.0;
+
+/// Expand a polyline into the outline of a stroke whose width varies along its length.
+///
+/// The returned polygon is closed, goes up the left side of the line and back down the right side, and has flat ends.
+/// It can be filled with the nonzero rule even where the outline overlaps itself on sharp turns.
+/// Returns an empty [Vec] if there are fewer than two points.
+pub fn variable_width_outline<W: WidthProfile + ?Sized>(points: &[Vec2], widths: &W) -> Vec<Vec2> {
+    if points.len() < 2 {
+        return Vec::new();
+    }
+
+    let distances = arc_lengths(points);
+    let total = distances[distances.len() - 1];
+
+    let mut left = Vec::with_capacity(points.len());
+    let mut right = Vec::with_capacity(points.len());
+
+    for (i, point) in points.iter().enumerate() {
+        let t = if total > 0.0 {
+            distances[i] / total
+        } else {
+            0.0
+        };
+        let half_width = widths.width_at(i, t).max(0.0) / 2.0;
+
+        let offset = join_normal(points, i) * half_width;
+        left.push(*point + offset);
+        right.push(*point - offset);
+    }
+
+    let mut outline = left;
+    outline.extend(right.into_iter().rev());
+    outline.push(outline[0]);
+    outline
+}
+
+/// The left-hand normal at a point of an open polyline, scaled so that offsetting by it keeps parallel edges at a constant distance.
+fn join_normal(points: &[Vec2], i: usize) -> Vec2 {
+    let before = i.checked_sub(1).map(|j| points[j]);
+    miter_normal(before, points[i], points.get(i + 1).copied())
+}
+
+/// The left-hand normal at `point`, between the segment coming from `before` and the one going to `after`.
+///
+/// It is scaled so that offsetting by it keeps both segments at a constant distance, up to a miter limit.
+pub(crate) fn miter_normal(before: Option<Vec2>, point: Vec2, after: Option<Vec2>) -> Vec2 {
+    let before = before.and_then(|before| segment_normal(before, point));
+    let after = after.and_then(|after| segment_normal(point, after));
+
+    match (before, after) {
+        (Some(before), Some(after)) => {
+            let bisector = (before + after).normalize_or_zero();
+            if bisector == Vec2::ZERO {
+                before
+            } else {
+                bisector / bisector.dot(before).max(1.0 / MITER_LIMIT)
+            }
+        }
+        (Some(normal), None) | (None, Some(normal)) => normal,
+        (None, None) => Vec2::ZERO,
+    }
+}
+
+/// The unit left-hand normal of a segment, or [None] if the segment has no length.
+fn segment_normal(a: Vec2, b: Vec2) -> Option<Vec2> {
+    let direction = (b - a).normalize_or_zero();
+    if direction == Vec2::ZERO {
+        None
+    } else {
+        Some(direction.perp())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that a straight line expands into a rectangle, and a function profile tapers it.
+    #[test]
+    fn straight_line_outline() {
+        let points = [Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0)];
+
+        assert_eq!(
+            variable_width_outline(&points, &vec![2.0, 2.0]),
+            vec![
+                Vec2::new(0.0, 1.0),
+                Vec2::new(2.0, 1.0),
+                Vec2::new(2.0, -1.0),
+                Vec2::new(0.0, -1.0),
+                Vec2::new(0.0, 1.0),
+            ]
+        );
+
+        let tapered = variable_width_outline(&points, &|t: f32| 2.0 * (1.0 - t));
+        assert_eq!(tapered[1], Vec2::new(2.0, 0.0));
+        assert_eq!(tapered[2], Vec2::new(2.0, 0.0));
+    }
+}
@@ -0,0 +1,111 @@
+use glam::{Affine2, Mat2, Vec2};
+
+use super::{arc_lengths, contains_point};
+use crate::rng::Rng;
+
+/// Spread `count` transforms evenly along a path, by arc length, each rotated to follow the path's direction.
+///
+/// `jitter` nudges each position along the path by up to that fraction of the spacing between copies, from `0.0` (perfectly even) to `1.0`.
+/// The result can be handed straight to [Canvas::draw_instances](crate::Canvas::draw_instances).
+pub fn scatter_along_path(path: &[Vec2], count: usize, jitter: f32, seed: u64) -> Vec<Affine2> {
+    let lengths = arc_lengths(path);
+    let total = match lengths.last() {
+        Some(&total) if total > 0.0 => total,
+        _ => return Vec::new(),
+    };
+
+    let mut rng = Rng::new(seed);
+    let spacing = total / count as f32;
+    let jitter = jitter.clamp(0.0, 1.0) * spacing;
+
+    (0..count)
+        .map(|i| {
+            let distance = (i as f32 + 0.5) * spacing + rng.range(-0.5, 0.5) * jitter;
+            let distance = distance.clamp(0.0, total);
+
+            // The segment this distance falls on, skipping any that have no length.
+            let segment = lengths
+                .partition_point(|&length| length < distance)
+                .clamp(1, lengths.len() - 1);
+            let (start, end) = (path[segment - 1], path[segment]);
+            let span = lengths[segment] - lengths[segment - 1];
+            let t = if span > 0.0 {
+                (distance - lengths[segment - 1]) / span
+            } else {
+                0.0
+            };
+
+            let direction = (end - start).try_normalize().unwrap_or(Vec2::X);
+            Affine2::from_mat2_translation(
+                Mat2::from_cols(direction, direction.perp()),
+                start.lerp(end, t),
+            )
+        })
+        .collect()
+}
+
+/// Scatter up to `count` transforms uniformly at random inside a closed polygon, using rejection sampling.
+///
+/// The transforms only translate. Fewer than `count` may be returned if the polygon covers very little of its bounding box.
+/// The result can be handed straight to [Canvas::draw_instances](crate::Canvas::draw_instances).
+pub fn scatter_in_polygon(polygon: &[Vec2], count: usize, seed: u64) -> Vec<Affine2> {
+    if polygon.len() < 3 {
+        return Vec::new();
+    }
+
+    let min = polygon
+        .iter()
+        .copied()
+        .fold(Vec2::splat(f32::INFINITY), Vec2::min);
+    let max = polygon
+        .iter()
+        .copied()
+        .fold(Vec2::splat(f32::NEG_INFINITY), Vec2::max);
+
+    let mut rng = Rng::new(seed);
+    let mut transforms = Vec::with_capacity(count);
+
+    for _ in 0..count.saturating_mul(100) {
+        if transforms.len() == count {
+            break;
+        }
+
+        let point = Vec2::new(rng.range(min.x, max.x), rng.range(min.y, max.y));
+        if contains_point(polygon, point) {
+            transforms.push(Affine2::from_translation(point));
+        }
+    }
+
+    transforms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that copies along a path are evenly spaced and follow its direction.
+    #[test]
+    fn scatter_evenly() {
+        let path = [Vec2::ZERO, Vec2::new(2.0, 0.0), Vec2::new(2.0, 2.0)];
+        let transforms = scatter_along_path(&path, 4, 0.0, 0);
+
+        let positions: Vec<Vec2> = transforms.iter().map(|t| t.translation).collect();
+        assert_eq!(
+            positions,
+            vec![
+                Vec2::new(0.5, 0.0),
+                Vec2::new(1.5, 0.0),
+                Vec2::new(2.0, 0.5),
+                Vec2::new(2.0, 1.5)
+            ]
+        );
+        assert_eq!(transforms[3].transform_vector2(Vec2::X), Vec2::Y);
+
+        let triangle = [Vec2::ZERO, Vec2::new(1.0, 0.0), Vec2::new(0.0, 1.0)];
+        let inside = scatter_in_polygon(&triangle, 50, 7);
+        assert_eq!(inside.len(), 50);
+        assert!(inside
+            .iter()
+            .all(|t| t.translation.x + t.translation.y <= 1.0));
+    }
+}
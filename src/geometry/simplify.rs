@@ -0,0 +1,63 @@
+use glam::Vec2;
+
+use super::distance_to_segment;
+
+/// Remove points from a polyline that stray less than `tolerance` from the line through their neighbours, using the Ramer-Douglas-Peucker algorithm.
+///
+/// The first and last points are always kept, so closed paths stay closed.
+pub fn simplify_polyline(points: &[Vec2], tolerance: f32) -> Vec<Vec2> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+
+    let mut spans = vec![(0, points.len() - 1)];
+    while let Some((start, end)) = spans.pop() {
+        let farthest = (start + 1..end)
+            .map(|i| {
+                let distance = distance_to_segment(points[i], points[start], points[end]);
+                (i, distance)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        if let Some((i, distance)) = farthest {
+            if distance > tolerance {
+                keep[i] = true;
+                spans.push((start, i));
+                spans.push((i, end));
+            }
+        }
+    }
+
+    points
+        .iter()
+        .zip(keep)
+        .filter_map(|(&point, keep)| keep.then_some(point))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that points close to a straight run are dropped, and corners are kept.
+    #[test]
+    fn simplify_keeps_corners() {
+        let points = [
+            Vec2::ZERO,
+            Vec2::new(1.0, 0.05),
+            Vec2::new(2.0, -0.05),
+            Vec2::new(3.0, 0.0),
+            Vec2::new(3.0, 3.0),
+        ];
+
+        assert_eq!(
+            simplify_polyline(&points, 0.1),
+            vec![Vec2::ZERO, Vec2::new(3.0, 0.0), Vec2::new(3.0, 3.0)]
+        );
+        assert_eq!(simplify_polyline(&points, 0.01), points.to_vec());
+    }
+}
@@ -0,0 +1,130 @@
+use glam::Vec2;
+
+/// Smooth a polyline by repeatedly cutting each of its corners, using Chaikin's algorithm.
+///
+/// Every iteration replaces each segment with points a quarter and three quarters of the way along it, roughly doubling the number of points.
+/// If `closed` is `true`, the line wraps around and the result is closed, with its first point repeated at the end.
+/// Otherwise, the first and last points are kept in place.
+pub fn chaikin_smooth(points: &[Vec2], iterations: usize, closed: bool) -> Vec<Vec2> {
+    let mut ring = open_ring(points, closed);
+    if ring.len() < 3 {
+        return points.to_vec();
+    }
+
+    for _ in 0..iterations {
+        let segments = if closed { ring.len() } else { ring.len() - 1 };
+
+        let mut cut = Vec::with_capacity(segments * 2 + 2);
+        if !closed {
+            cut.push(ring[0]);
+        }
+        for i in 0..segments {
+            let (a, b) = (ring[i], ring[(i + 1) % ring.len()]);
+            cut.push(a.lerp(b, 0.25));
+            cut.push(a.lerp(b, 0.75));
+        }
+        if !closed {
+            // The first and last segments only lose their inner halves, so the ends stay put.
+            cut.remove(1);
+            cut.pop();
+            cut.push(ring[ring.len() - 1]);
+        }
+
+        ring = cut;
+    }
+
+    close_ring(ring, closed)
+}
+
+/// Smooth a polyline by repeatedly moving each point towards the middle of its neighbours, using Laplacian smoothing.
+///
+/// `strength` is how far each point moves per iteration, from 0 (not at all) to 1 (all the way to the midpoint).
+/// Unlike [chaikin_smooth], the number of points never changes, but the line shrinks slightly with every iteration.
+/// If `closed` is `true`, the line wraps around and the result is closed, with its first point repeated at the end.
+/// Otherwise, the first and last points are kept in place.
+pub fn laplacian_smooth(
+    points: &[Vec2],
+    iterations: usize,
+    strength: f32,
+    closed: bool,
+) -> Vec<Vec2> {
+    let mut ring = open_ring(points, closed);
+    if ring.len() < 3 {
+        return points.to_vec();
+    }
+
+    let count = ring.len();
+    for _ in 0..iterations {
+        ring = (0..count)
+            .map(|i| {
+                if !closed && (i == 0 || i == count - 1) {
+                    return ring[i];
+                }
+
+                let middle = (ring[(i + count - 1) % count] + ring[(i + 1) % count]) / 2.0;
+                ring[i].lerp(middle, strength)
+            })
+            .collect();
+    }
+
+    close_ring(ring, closed)
+}
+
+/// The points of a polyline, without the repeated first point of a closed one.
+fn open_ring(points: &[Vec2], closed: bool) -> Vec<Vec2> {
+    if closed && points.len() > 1 && points[0] == points[points.len() - 1] {
+        points[..points.len() - 1].to_vec()
+    } else {
+        points.to_vec()
+    }
+}
+
+/// Repeat the first point at the end if the polyline is closed.
+fn close_ring(mut ring: Vec<Vec2>, closed: bool) -> Vec<Vec2> {
+    if closed {
+        ring.push(ring[0]);
+    }
+    ring
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that both smoothings keep the ends of open lines, and close closed ones.
+    #[test]
+    fn smooth_lines() {
+        let open = [Vec2::ZERO, Vec2::X, Vec2::ONE];
+
+        let cut = chaikin_smooth(&open, 1, false);
+        assert_eq!(
+            cut,
+            vec![
+                Vec2::ZERO,
+                Vec2::new(0.75, 0.0),
+                Vec2::new(1.0, 0.25),
+                Vec2::ONE
+            ]
+        );
+
+        let relaxed = laplacian_smooth(&open, 1, 1.0, false);
+        assert_eq!(relaxed, vec![Vec2::ZERO, Vec2::new(0.5, 0.5), Vec2::ONE]);
+
+        let square = [
+            Vec2::new(-1.0, -1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(-1.0, -1.0),
+        ];
+
+        let cut = chaikin_smooth(&square, 2, true);
+        assert_eq!(cut.len(), 17);
+        assert_eq!(cut.first(), cut.last());
+        assert!(cut.iter().all(|point| point.length() < 2.0f32.sqrt()));
+
+        let relaxed = laplacian_smooth(&square, 3, 0.5, true);
+        assert_eq!(relaxed.len(), 5);
+        assert_eq!(relaxed.first(), relaxed.last());
+    }
+}
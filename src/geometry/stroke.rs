@@ -0,0 +1,149 @@
+use std::f32::consts::PI;
+
+use glam::{Mat2, Vec2};
+
+use super::{offset_polygon, outline::miter_normal, signed_area};
+use crate::{FillRule, LineEnd, Shape, Stroke};
+
+/// Number of segments in each round cap.
+const CAP_SEGMENTS: usize = 12;
+
+/// Convert the stroke of every path in `shape` into the filled polygon it covers.
+///
+/// Open paths become a closed outline around the line, with ends shaped by [Stroke::line_end].
+/// Closed paths become a ring, with the outline outside and the inside as a hole.
+/// The stroke is centered on each path, the way renderers draw it, so [Stroke::alignment] is ignored.
+///
+/// The returned shape is filled with the stroke's color using [FillRule::NonZero], and has no stroke of its own.
+/// Corners are mitered, so it covers slightly more than a round-joined stroke would.
+pub fn stroke_to_path(shape: &Shape, stroke: Stroke) -> Shape {
+    let shape: &Shape = &shape.flattened();
+    let half_width = stroke.width.max(0.0) / 2.0;
+
+    let mut outlines = Vec::new();
+    for path in shape.paths().filter(|path| path.len() > 1) {
+        if path.len() >= 3 && path[0] == path[path.len() - 1] {
+            outlines.push(wind(offset_polygon(path, half_width), true));
+            outlines.push(wind(offset_polygon(path, -half_width), false));
+        } else {
+            outlines.push(wind(open_outline(path, half_width, stroke.line_end), true));
+        }
+    }
+
+    let mut outlines = outlines.into_iter();
+    Shape {
+        points: outlines.next().unwrap_or_default(),
+        subpaths: outlines.collect(),
+        stroke: None,
+        fill: Some(stroke.color),
+        fill_rule: FillRule::NonZero,
+        gradient_fill: None,
+        ..shape.clone()
+    }
+}
+
+/// The closed outline of an open polyline, up its left side and back down its right.
+fn open_outline(points: &[Vec2], half_width: f32, line_end: LineEnd) -> Vec<Vec2> {
+    let count = points.len();
+    let offsets: Vec<Vec2> = (0..count)
+        .map(|i| {
+            let before = i.checked_sub(1).map(|j| points[j]);
+            miter_normal(before, points[i], points.get(i + 1).copied()) * half_width
+        })
+        .collect();
+
+    let mut outline: Vec<Vec2> = points
+        .iter()
+        .zip(&offsets)
+        .map(|(point, offset)| *point + *offset)
+        .collect();
+
+    if line_end == LineEnd::Round {
+        outline.extend(cap(points[count - 1], offsets[count - 1]));
+    }
+
+    outline.extend(
+        points
+            .iter()
+            .zip(&offsets)
+            .rev()
+            .map(|(point, offset)| *point - *offset),
+    );
+
+    if line_end == LineEnd::Round {
+        outline.extend(cap(points[0], -offsets[0]));
+    }
+
+    outline.push(outline[0]);
+    outline
+}
+
+/// The points of a half circle around `center`, turning clockwise from `center + offset` to `center - offset`, excluding both ends.
+fn cap(center: Vec2, offset: Vec2) -> impl Iterator<Item = Vec2> {
+    (1..CAP_SEGMENTS).map(move |i| {
+        let angle = -PI * i as f32 / CAP_SEGMENTS as f32;
+        center + Mat2::from_angle(angle) * offset
+    })
+}
+
+/// Reverse a closed polygon if needed, so it winds counter-clockwise when `counter_clockwise` is `true`, and clockwise otherwise.
+fn wind(mut points: Vec<Vec2>, counter_clockwise: bool) -> Vec<Vec2> {
+    if (signed_area(&points) > 0.0) != counter_clockwise {
+        points.reverse();
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{custom_shape::custom_capsule, geometry::contains_point, Color};
+
+    /// Verify that open lines expand into capped outlines, and closed shapes into rings.
+    #[test]
+    fn outline_strokes() {
+        let stroke = Stroke::new(Color::red(), 1.0, LineEnd::Butt);
+        let line = Shape {
+            points: vec![Vec2::ZERO, Vec2::new(2.0, 0.0)],
+            stroke: Some(stroke),
+            ..Default::default()
+        };
+
+        let outline = stroke_to_path(&line, stroke);
+        assert_eq!(outline.fill, Some(Color::red()));
+        assert!(outline.stroke.is_none());
+        assert!((signed_area(&outline.points) - 2.0).abs() < 1e-6);
+
+        let round = stroke_to_path(&line, Stroke::new(Color::red(), 1.0, LineEnd::Round));
+        assert!(contains_point(&round.points, Vec2::new(-0.4, 0.0)));
+        assert!(contains_point(&round.points, Vec2::new(2.4, 0.0)));
+
+        let square = Shape {
+            points: vec![
+                Vec2::new(-1.0, -1.0),
+                Vec2::new(1.0, -1.0),
+                Vec2::new(1.0, 1.0),
+                Vec2::new(-1.0, 1.0),
+                Vec2::new(-1.0, -1.0),
+            ],
+            ..Default::default()
+        };
+
+        let ring = stroke_to_path(&square, stroke);
+        assert_eq!(ring.subpaths.len(), 1);
+        assert!(signed_area(&ring.points) > 0.0);
+        assert!(signed_area(&ring.subpaths[0]) < 0.0);
+        assert!((signed_area(&ring.points) + signed_area(&ring.subpaths[0]) - 8.0).abs() < 1e-5);
+    }
+
+    /// Verify that custom shapes are flattened before their stroke is outlined.
+    #[test]
+    fn stroke_custom_shape() {
+        let custom = custom_capsule();
+        let stroke = custom.stroke.unwrap();
+
+        let outline = stroke_to_path(&custom, stroke);
+        assert_eq!(outline, stroke_to_path(&custom.flattened(), stroke));
+        assert!(!outline.points.is_empty());
+    }
+}
@@ -0,0 +1,148 @@
+use glam::Vec2;
+
+/// Reorder and reverse open paths to shorten the distance a pen travels between them, then join paths whose ends meet.
+///
+/// The first path stays first and keeps its direction. A greedy nearest-neighbour tour is improved with 2-opt until no reversal helps.
+/// Consecutive paths are merged when the end of one is within `merge_tolerance` of the start of the next.
+pub fn optimize_travel(paths: Vec<Vec<Vec2>>, merge_tolerance: f32) -> Vec<Vec<Vec2>> {
+    let paths: Vec<Vec<Vec2>> = paths.into_iter().filter(|path| !path.is_empty()).collect();
+    if paths.is_empty() {
+        return paths;
+    }
+
+    let mut order = nearest_neighbour(&paths);
+    two_opt(&paths, &mut order);
+
+    let mut paths: Vec<Option<Vec<Vec2>>> = paths.into_iter().map(Some).collect();
+    let mut merged: Vec<Vec<Vec2>> = Vec::new();
+
+    for (index, reversed) in order {
+        let mut path = paths[index].take().unwrap_or_default();
+        if reversed {
+            path.reverse();
+        }
+
+        match merged.last_mut() {
+            Some(last) if last[last.len() - 1].distance(path[0]) <= merge_tolerance => {
+                last.extend_from_slice(&path[1..]);
+            }
+            _ => merged.push(path),
+        }
+    }
+
+    merged
+}
+
+/// The total distance travelled between the end of each path and the start of the next, with the pen raised.
+pub fn travel_distance(paths: &[Vec<Vec2>]) -> f32 {
+    paths
+        .windows(2)
+        .filter(|pair| !pair[0].is_empty() && !pair[1].is_empty())
+        .map(|pair| pair[0][pair[0].len() - 1].distance(pair[1][0]))
+        .sum()
+}
+
+/// The start and end of a path, as it would be drawn.
+fn ends(paths: &[Vec<Vec2>], (index, reversed): (usize, bool)) -> (Vec2, Vec2) {
+    let path = &paths[index];
+    let (first, last) = (path[0], path[path.len() - 1]);
+    if reversed {
+        (last, first)
+    } else {
+        (first, last)
+    }
+}
+
+/// Visit every path by repeatedly moving to the closest unvisited end.
+fn nearest_neighbour(paths: &[Vec<Vec2>]) -> Vec<(usize, bool)> {
+    let mut visited = vec![false; paths.len()];
+    let mut order = vec![(0, false)];
+    visited[0] = true;
+
+    for _ in 1..paths.len() {
+        let (_, position) = ends(paths, order[order.len() - 1]);
+        let mut best = (0, false);
+        let mut best_distance = f32::INFINITY;
+
+        for (index, path) in paths.iter().enumerate() {
+            if visited[index] {
+                continue;
+            }
+
+            for (reversed, start) in [(false, path[0]), (true, path[path.len() - 1])] {
+                let distance = position.distance_squared(start);
+                if distance < best_distance {
+                    best = (index, reversed);
+                    best_distance = distance;
+                }
+            }
+        }
+
+        visited[best.0] = true;
+        order.push(best);
+    }
+
+    order
+}
+
+/// Reverse runs of the tour, flipping each path in them, while doing so shortens the travel.
+fn two_opt(paths: &[Vec<Vec2>], order: &mut [(usize, bool)]) {
+    // Each pass is quadratic, so give up on very long tours before they take forever.
+    const MAX_PASSES: usize = 64;
+
+    for _ in 0..MAX_PASSES {
+        let mut improved = false;
+
+        for i in 1..order.len() {
+            for j in i + 1..order.len() {
+                let (start_i, _) = ends(paths, order[i]);
+                let (_, end_j) = ends(paths, order[j]);
+                let (_, before) = ends(paths, order[i - 1]);
+                let after = order.get(j + 1).map(|&next| ends(paths, next).0);
+
+                let mut current = before.distance(start_i);
+                let mut reversed = before.distance(end_j);
+                if let Some(after) = after {
+                    current += end_j.distance(after);
+                    reversed += start_i.distance(after);
+                }
+
+                if reversed < current - 1e-6 {
+                    order[i..=j].reverse();
+                    for step in &mut order[i..=j] {
+                        step.1 = !step.1;
+                    }
+                    improved = true;
+                }
+            }
+        }
+
+        if !improved {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that scattered segments of one line are reordered, reversed, and joined back into a single path.
+    #[test]
+    fn optimize_joins_segments() {
+        let paths = vec![
+            vec![Vec2::ZERO, Vec2::new(1.0, 0.0)],
+            vec![Vec2::new(3.0, 0.0), Vec2::new(4.0, 0.0)],
+            vec![Vec2::new(2.0, 0.0), Vec2::new(1.0, 0.0)],
+            vec![Vec2::new(2.0, 0.0), Vec2::new(3.0, 0.0)],
+        ];
+        assert_eq!(travel_distance(&paths), 5.0);
+
+        let optimized = optimize_travel(paths, 1e-3);
+        assert_eq!(
+            optimized,
+            vec![(0..5).map(|x| Vec2::new(x as f32, 0.0)).collect::<Vec<_>>()]
+        );
+        assert_eq!(travel_distance(&optimized), 0.0);
+    }
+}
@@ -0,0 +1,190 @@
+use glam::Vec2;
+
+use super::intersect::segments_cross;
+use super::signed_area;
+
+/// Split a polygon, minus any holes inside it, into triangles by ear clipping.
+///
+/// Each triangle is three indices into the outline's points followed by every hole's points, in the order given, and winds counter-clockwise.
+/// Paths may be closed (first and last points equal) or not. Holes must lie inside the outline and not overlap each other.
+pub fn triangulate(outline: &[Vec2], holes: &[&[Vec2]]) -> Vec<[usize; 3]> {
+    let vertices: Vec<Vec2> = outline
+        .iter()
+        .chain(holes.iter().copied().flatten())
+        .copied()
+        .collect();
+
+    let mut start = 0;
+    let mut rings = Vec::new();
+    for path in std::iter::once(outline).chain(holes.iter().copied()) {
+        let mut ring: Vec<usize> = (start..start + open_len(path)).collect();
+        start += path.len();
+
+        // The outline winds counter-clockwise and holes clockwise, so bridged rings join into one counter-clockwise polygon.
+        let counter_clockwise = rings.is_empty();
+        if (signed_area(path) > 0.0) != counter_clockwise {
+            ring.reverse();
+        }
+        rings.push(ring);
+    }
+
+    let mut polygon = rings.remove(0);
+    if polygon.len() < 3 {
+        return Vec::new();
+    }
+
+    // Bridge the holes in from right to left, so each bridge can reach the outline without crossing a hole still waiting.
+    let rightmost = |ring: &[usize]| {
+        ring.iter()
+            .copied()
+            .max_by(|&a, &b| vertices[a].x.total_cmp(&vertices[b].x))
+    };
+    rings.retain(|ring| ring.len() >= 3);
+    rings.sort_by(|a, b| {
+        let (a, b) = (rightmost(a).unwrap(), rightmost(b).unwrap());
+        vertices[b].x.total_cmp(&vertices[a].x)
+    });
+
+    while !rings.is_empty() {
+        let hole = rings.remove(0);
+        bridge(&vertices, &mut polygon, &hole, &rings);
+    }
+
+    ear_clip(&vertices, polygon)
+}
+
+/// The number of points in a path, leaving out the closing point if it repeats the first.
+fn open_len(path: &[Vec2]) -> usize {
+    match path {
+        [first, .., last] if first == last => path.len() - 1,
+        _ => path.len(),
+    }
+}
+
+/// Splice a hole into the polygon, through a pair of edges joining the hole's rightmost point to the closest point of the polygon it can see.
+fn bridge(vertices: &[Vec2], polygon: &mut Vec<usize>, hole: &[usize], others: &[Vec<usize>]) {
+    let (start, &from) = hole
+        .iter()
+        .enumerate()
+        .max_by(|a, b| vertices[*a.1].x.total_cmp(&vertices[*b.1].x))
+        .unwrap();
+    let origin = vertices[from];
+
+    let edges = |ring: &[usize]| {
+        (0..ring.len())
+            .map(|i| (vertices[ring[i]], vertices[ring[(i + 1) % ring.len()]]))
+            .collect::<Vec<_>>()
+    };
+    let blocking: Vec<(Vec2, Vec2)> = std::iter::once(&polygon[..])
+        .chain(std::iter::once(hole))
+        .chain(others.iter().map(Vec::as_slice))
+        .flat_map(edges)
+        .collect();
+
+    let mut candidates: Vec<usize> = (0..polygon.len()).collect();
+    candidates.sort_by(|&a, &b| {
+        let (a, b) = (vertices[polygon[a]], vertices[polygon[b]]);
+        origin
+            .distance_squared(a)
+            .total_cmp(&origin.distance_squared(b))
+    });
+
+    let target = candidates
+        .iter()
+        .copied()
+        .find(|&i| {
+            let point = vertices[polygon[i]];
+            !blocking
+                .iter()
+                .any(|&(a, b)| segments_cross(origin, point, a, b))
+        })
+        .unwrap_or(candidates[0]);
+
+    let around_hole = (0..=hole.len()).map(|i| hole[(start + i) % hole.len()]);
+    let spliced: Vec<usize> = around_hole
+        .chain(std::iter::once(polygon[target]))
+        .collect();
+    polygon.splice(target + 1..target + 1, spliced);
+}
+
+/// Repeatedly cut off a convex corner with no other point inside it, until only one triangle remains.
+fn ear_clip(vertices: &[Vec2], mut polygon: Vec<usize>) -> Vec<[usize; 3]> {
+    let mut triangles = Vec::with_capacity(polygon.len().saturating_sub(2));
+    let mut i = 0;
+    let mut since_last_ear = 0;
+
+    while polygon.len() > 3 {
+        let n = polygon.len();
+        let (previous, current, next) = (
+            polygon[(i + n - 1) % n],
+            polygon[i % n],
+            polygon[(i + 1) % n],
+        );
+        let (a, b, c) = (vertices[previous], vertices[current], vertices[next]);
+
+        let convex = (b - a).perp_dot(c - b) > 0.0;
+        let empty = convex
+            && polygon.iter().all(|&other| {
+                let point = vertices[other];
+                point == a || point == b || point == c || !strictly_inside(point, a, b, c)
+            });
+
+        // A polygon with no ears left is degenerate, so cut corners anyway rather than stopping short.
+        if empty || since_last_ear > n {
+            triangles.push([previous, current, next]);
+            polygon.remove(i % n);
+            since_last_ear = 0;
+        } else {
+            i += 1;
+            since_last_ear += 1;
+        }
+        i %= polygon.len();
+    }
+
+    if let [a, b, c] = polygon[..] {
+        triangles.push([a, b, c]);
+    }
+
+    triangles
+}
+
+fn strictly_inside(point: Vec2, a: Vec2, b: Vec2, c: Vec2) -> bool {
+    (b - a).perp_dot(point - a) > 0.0
+        && (c - b).perp_dot(point - b) > 0.0
+        && (a - c).perp_dot(point - c) > 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that a square with a square hole is covered by triangles whose area adds up to the area between them.
+    #[test]
+    fn triangulate_square_with_hole() {
+        let outer = [
+            Vec2::ZERO,
+            Vec2::new(4.0, 0.0),
+            Vec2::new(4.0, 4.0),
+            Vec2::new(0.0, 4.0),
+            Vec2::ZERO,
+        ];
+        let hole = [
+            Vec2::ONE,
+            Vec2::new(3.0, 1.0),
+            Vec2::new(3.0, 3.0),
+            Vec2::new(1.0, 3.0),
+        ];
+
+        let triangles = triangulate(&outer, &[&hole]);
+        let vertices: Vec<Vec2> = outer.iter().chain(&hole).copied().collect();
+
+        assert_eq!(triangles.len(), 8);
+        let area: f32 = triangles
+            .iter()
+            .map(|triangle| signed_area(&triangle.map(|i| vertices[i])))
+            .inspect(|&area| assert!(area > 0.0))
+            .sum();
+        assert_eq!(area, 12.0);
+        assert!(triangles.iter().flatten().all(|&i| i != 4));
+    }
+}
@@ -0,0 +1,270 @@
+#[cfg(feature = "tiny_skia_renderer")]
+use glam::Vec3;
+use glam::{Mat3, Vec2};
+#[cfg(feature = "tiny_skia_renderer")]
+use image::{Rgba, RgbaImage};
+
+use super::distort::map_subdivided;
+use crate::Shape;
+
+/// The corners of the unit square, in the order quads are given: `(0, 0)`, `(1, 0)`, `(1, 1)`, then `(0, 1)`.
+const UNIT_SQUARE: [Vec2; 4] = [Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y];
+
+/// A perspective (projective) transform, which keeps straight lines straight but not parallel lines parallel.
+///
+/// Useful for laying a flat drawing onto a plane seen at an angle, such as a floor, a wall or a cast shadow.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Projective {
+    matrix: Mat3,
+}
+
+impl Projective {
+    /// The transform that leaves every point where it is.
+    pub const IDENTITY: Self = Self {
+        matrix: Mat3::IDENTITY,
+    };
+
+    /// Create a [Projective] transform from its matrix, which maps `(x, y, 1)` to homogeneous coordinates.
+    pub fn from_matrix(matrix: Mat3) -> Self {
+        Self { matrix }
+    }
+
+    /// The transform that maps the unit square onto `quad`, with corners in the order `(0, 0)`, `(1, 0)`, `(1, 1)`, `(0, 1)`.
+    ///
+    /// Returns [None] if three of the corners lie on a line.
+    pub fn from_quad(quad: [Vec2; 4]) -> Option<Self> {
+        let [p0, p1, p2, p3] = quad;
+        let (d1, d2, d3) = (p1 - p2, p3 - p2, p0 - p1 + p2 - p3);
+
+        let det = d1.perp_dot(d2);
+        if det.abs() <= f32::EPSILON {
+            return None;
+        }
+        let g = d3.perp_dot(d2) / det;
+        let h = d1.perp_dot(d3) / det;
+
+        let matrix = Mat3::from_cols(
+            (p1 - p0 + g * p1).extend(g),
+            (p3 - p0 + h * p3).extend(h),
+            p0.extend(1.0),
+        );
+        if matrix.determinant().abs() <= f32::EPSILON {
+            return None;
+        }
+        Self::from_matrix(matrix).checked()
+    }
+
+    /// The transform that maps each corner of the quad `from` onto the same corner of `to`.
+    ///
+    /// Returns [None] if either quad is degenerate.
+    pub fn between(from: [Vec2; 4], to: [Vec2; 4]) -> Option<Self> {
+        let from = Self::from_quad(from)?.inverse()?;
+        let to = Self::from_quad(to)?;
+        Some(from.then(&to))
+    }
+
+    /// The matrix of the transform.
+    pub fn matrix(&self) -> Mat3 {
+        self.matrix
+    }
+
+    /// Where the transform moves `point`. Points on the horizon go off to infinity.
+    pub fn apply(&self, point: Vec2) -> Vec2 {
+        let projected = self.matrix * point.extend(1.0);
+        projected.truncate() / projected.z
+    }
+
+    /// The transform that undoes this one, or [None] if it flattens the plane onto a line.
+    pub fn inverse(&self) -> Option<Self> {
+        if self.matrix.determinant().abs() <= f32::EPSILON {
+            return None;
+        }
+        Self::from_matrix(self.matrix.inverse()).checked()
+    }
+
+    /// The transform that applies this one, then `next`.
+    pub fn then(&self, next: &Self) -> Self {
+        Self::from_matrix(next.matrix * self.matrix)
+    }
+
+    /// Return [None] if any part of the matrix is not finite.
+    fn checked(self) -> Option<Self> {
+        self.matrix.is_finite().then_some(self)
+    }
+}
+
+/// A way of mapping the unit square onto an arbitrary quad, bending whatever is drawn inside it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Warp {
+    /// Map with perspective, as if the square were a plane tilted away from the viewer.
+    Perspective(Projective),
+    /// Map by interpolating between the corners of a quad, with corners in the order `(0, 0)`, `(1, 0)`, `(1, 1)`, `(0, 1)`.
+    ///
+    /// Lines along the square's axes stay straight, but diagonals curve, like a sheet of paper taped down at its corners.
+    Bilinear([Vec2; 4]),
+}
+
+impl Warp {
+    /// A [Perspective](Self::Perspective) warp of the unit square onto `quad`, or [None] if three of its corners lie on a line.
+    pub fn perspective(quad: [Vec2; 4]) -> Option<Self> {
+        Projective::from_quad(quad).map(Self::Perspective)
+    }
+
+    /// Where the warp moves `point`.
+    pub fn apply(&self, point: Vec2) -> Vec2 {
+        match self {
+            Warp::Perspective(projective) => projective.apply(point),
+            Warp::Bilinear([p0, p1, p2, p3]) => {
+                let bottom = p0.lerp(*p1, point.x);
+                let top = p3.lerp(*p2, point.x);
+                bottom.lerp(top, point.y)
+            }
+        }
+    }
+
+    /// The point that the warp moves to `point`, or [None] if there isn't one.
+    pub fn inverse_apply(&self, point: Vec2) -> Option<Vec2> {
+        match self {
+            Warp::Perspective(projective) => {
+                Some(projective.inverse()?.apply(point)).filter(|point| point.is_finite())
+            }
+            Warp::Bilinear([p0, p1, p2, p3]) => {
+                // Newton's method, starting from the middle of the square.
+                let mut guess = Vec2::splat(0.5);
+                for _ in 0..16 {
+                    let error = self.apply(guess) - point;
+                    let along_x = (*p1 - *p0).lerp(*p2 - *p3, guess.y);
+                    let along_y = (*p3 - *p0).lerp(*p2 - *p1, guess.x);
+
+                    let det = along_x.perp_dot(along_y);
+                    if det.abs() <= f32::EPSILON {
+                        return None;
+                    }
+                    guess -= Vec2::new(error.perp_dot(along_y), along_x.perp_dot(error)) / det;
+                }
+
+                let scale = UNIT_SQUARE
+                    .iter()
+                    .map(|&corner| self.apply(corner).distance(point))
+                    .fold(1.0, f32::max);
+                (guess.is_finite() && self.apply(guess).distance(point) <= scale * 1e-4)
+                    .then_some(guess)
+            }
+        }
+    }
+
+    /// Resample `image` so that it fills the warped quad, in a new image `width` by `height` pixels.
+    ///
+    /// Both the unit square and the quad are measured in pixels, from the top left corner:
+    /// the square spans the whole of `image`, and the quad is placed in the new image. Pixels outside the quad are transparent.
+    ///
+    /// Requires the `tiny_skia_renderer` feature.
+    #[cfg(feature = "tiny_skia_renderer")]
+    pub fn warp_image(&self, image: &RgbaImage, width: u32, height: u32) -> RgbaImage {
+        let size = Vec2::new(image.width() as f32, image.height() as f32);
+
+        RgbaImage::from_fn(width, height, |x, y| {
+            let pixel = Vec2::new(x as f32, y as f32) + 0.5;
+            match self.inverse_apply(pixel) {
+                Some(source) if source.cmpge(Vec2::ZERO).all() && source.cmple(Vec2::ONE).all() => {
+                    sample(image, source * size - 0.5)
+                }
+                _ => Rgba([0, 0, 0, 0]),
+            }
+        })
+    }
+}
+
+/// Blend the four pixels around `position`, in pixels from the top left corner, clamping to the edges of the image.
+#[cfg(feature = "tiny_skia_renderer")]
+fn sample(image: &RgbaImage, position: Vec2) -> Rgba<u8> {
+    let max = Vec2::new(image.width() as f32 - 1.0, image.height() as f32 - 1.0);
+    let position = position.clamp(Vec2::ZERO, max.max(Vec2::ZERO));
+    let (floor, fraction) = (position.floor(), position - position.floor());
+
+    let pixel = |dx: f32, dy: f32| {
+        let corner = (floor + Vec2::new(dx, dy)).min(max);
+        let [r, g, b, a] = image.get_pixel(corner.x as u32, corner.y as u32).0;
+        Vec3::new(r as f32, g as f32, b as f32).extend(a as f32)
+    };
+    let top = pixel(0.0, 0.0).lerp(pixel(1.0, 0.0), fraction.x);
+    let bottom = pixel(0.0, 1.0).lerp(pixel(1.0, 1.0), fraction.x);
+    let blended = top.lerp(bottom, fraction.y).round();
+
+    Rgba([
+        blended.x as u8,
+        blended.y as u8,
+        blended.z as u8,
+        blended.w as u8,
+    ])
+}
+
+/// Warp a shape drawn in the unit square onto a quad, adding points along its edges so that lines the warp bends stay smooth.
+///
+/// Edges are split in half until the warped midpoint of every piece is within `tolerance` of the straight edge between its warped ends.
+/// A [gradient fill](Shape::gradient_fill) moves with the warp, but stays a straight gradient.
+pub fn warp(shape: &Shape, warp: &Warp, tolerance: f32) -> Shape {
+    map_subdivided(shape, |point| warp.apply(point), tolerance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::custom_shape::custom_capsule;
+
+    /// Verify that both warps carry the unit square onto their quad and back, and that only bilinear warps bend lines.
+    #[test]
+    fn quad_warps() {
+        let quad = [
+            Vec2::new(1.0, 1.0),
+            Vec2::new(5.0, 0.0),
+            Vec2::new(4.0, 3.0),
+            Vec2::new(2.0, 4.0),
+        ];
+        let warps = [Warp::perspective(quad).unwrap(), Warp::Bilinear(quad)];
+
+        for warp in &warps {
+            for (corner, target) in UNIT_SQUARE.iter().zip(quad) {
+                assert!(warp.apply(*corner).abs_diff_eq(target, 1e-4));
+            }
+
+            let point = Vec2::new(0.3, 0.8);
+            let back = warp.inverse_apply(warp.apply(point)).unwrap();
+            assert!(back.abs_diff_eq(point, 1e-4));
+        }
+
+        let diagonal = Shape {
+            points: vec![Vec2::ZERO, Vec2::ONE],
+            ..Default::default()
+        };
+        assert_eq!(warp(&diagonal, &warps[0], 0.01).points.len(), 2);
+        assert!(warp(&diagonal, &warps[1], 0.01).points.len() > 2);
+
+        let flat = [Vec2::ZERO, Vec2::X, Vec2::new(2.0, 0.0), Vec2::Y];
+        assert!(Projective::from_quad(flat).is_none());
+
+        let square = UNIT_SQUARE.map(|corner| corner * 2.0);
+        let between = Projective::between(square, quad).unwrap();
+        assert!(between
+            .apply(Vec2::new(2.0, 2.0))
+            .abs_diff_eq(quad[2], 1e-4));
+    }
+
+    /// Verify that custom shapes are flattened before they are warped.
+    #[test]
+    fn warp_custom_shape() {
+        let custom = custom_capsule();
+        let bilinear = Warp::Bilinear([
+            Vec2::ZERO,
+            Vec2::new(2.0, 0.0),
+            Vec2::new(3.0, 2.0),
+            Vec2::new(0.0, 1.0),
+        ]);
+
+        let warped = warp(&custom, &bilinear, 0.01);
+        assert_eq!(warped, warp(&custom.flattened(), &bilinear, 0.01));
+        assert!(warped.custom.is_none() && !warped.points.is_empty());
+    }
+}
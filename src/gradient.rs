@@ -0,0 +1,209 @@
+use glam::Vec2;
+
+use crate::Color;
+
+/// A smooth transition between several [Colors](Color).
+///
+/// Each color stop sits at a position from 0.0 to 1.0. [Sampling](Self::sample) between two stops linearly interpolates their colors.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Gradient {
+    stops: Vec<(f32, Color)>,
+}
+
+impl Gradient {
+    /// Create a new [Gradient] that transitions from `start` to `end`.
+    pub fn new(start: Color, end: Color) -> Self {
+        Self {
+            stops: vec![(0.0, start), (1.0, end)],
+        }
+    }
+
+    /// Create a new [Gradient] from a list of `(position, color)` stops.
+    ///
+    /// Stops do not need to be sorted. Positions are clamped to 0..=1.
+    pub fn from_stops<I: IntoIterator<Item = (f32, Color)>>(stops: I) -> Self {
+        let mut gradient = Self { stops: Vec::new() };
+
+        for (position, color) in stops {
+            gradient = gradient.with_stop(position, color);
+        }
+
+        gradient
+    }
+
+    /// Add a color stop, consuming the parent.
+    pub fn with_stop(mut self, position: f32, color: Color) -> Self {
+        let position = if position.is_nan() {
+            0.0
+        } else {
+            position.clamp(0.0, 1.0)
+        };
+
+        // Insert after any stops at the same position to keep hard transitions in order.
+        let index = self.stops.partition_point(|(p, _)| *p <= position);
+        self.stops.insert(index, (position, color));
+        self
+    }
+
+    /// Get the color stops, sorted by position.
+    pub fn stops(&self) -> &[(f32, Color)] {
+        &self.stops
+    }
+
+    /// Get the color at `t`, from 0.0 to 1.0.
+    ///
+    /// Values of `t` outside that range take the color of the nearest end.
+    /// An empty gradient is always [transparent](Color::transparent).
+    pub fn sample(&self, t: f32) -> Color {
+        let (first, last) = match (self.stops.first(), self.stops.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return Color::transparent(),
+        };
+
+        if t.is_nan() || t <= first.0 {
+            return first.1;
+        }
+        if t >= last.0 {
+            return last.1;
+        }
+
+        let index = self.stops.partition_point(|(p, _)| *p <= t);
+        let (p0, c0) = self.stops[index - 1];
+        let (p1, c1) = self.stops[index];
+
+        let f = (t - p0) / (p1 - p0);
+        c0 + (c1 - c0) * f
+    }
+}
+
+/// A [Gradient] painted in a straight line across a filled [Shape](crate::Shape), with noise to hide banding.
+///
+/// Set on [Shape::gradient_fill](crate::Shape::gradient_fill), or draw with [Canvas::draw_gradient_shape](crate::Canvas::draw_gradient_shape).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GradientFill {
+    /// The colors, from `start` to `end`.
+    pub gradient: Gradient,
+    /// Where the gradient begins. Before it, the first color continues.
+    pub start: Vec2,
+    /// Where the gradient ends. After it, the last color continues.
+    pub end: Vec2,
+    /// Strength of blue noise added to every pixel to break up banding, in steps of an 8-bit channel. `1.0` is usually enough.
+    pub dither: f32,
+    /// Strength of film-like grain added to every pixel, from 0 to 1. Unlike [dither](Self::dither), it is meant to be seen.
+    pub grain: f32,
+}
+
+impl GradientFill {
+    /// Create a new [GradientFill] from `start` to `end`, dithered just enough to hide banding.
+    pub fn new(gradient: Gradient, start: Vec2, end: Vec2) -> Self {
+        Self {
+            gradient,
+            start,
+            end,
+            dither: 1.0,
+            grain: 0.0,
+        }
+    }
+
+    /// Get the color at `point`, before any noise is added.
+    pub fn sample(&self, point: Vec2) -> Color {
+        let direction = self.end - self.start;
+        let length_squared = direction.length_squared();
+        if length_squared == 0.0 {
+            return self.gradient.sample(0.0);
+        }
+
+        self.gradient
+            .sample((point - self.start).dot(direction) / length_squared)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that sampling interpolates between stops and clamps at the ends.
+    #[test]
+    fn sample_stops() {
+        let gradient = Gradient::new(Color::black(), Color::white()).with_stop(0.5, Color::red());
+
+        assert_eq!(gradient.sample(-1.0), Color::black());
+        assert_eq!(gradient.sample(0.25), Color::new(0.5, 0.0, 0.0, 1.0));
+        assert_eq!(gradient.sample(0.5), Color::red());
+        assert_eq!(gradient.sample(0.75), Color::new(1.0, 0.5, 0.5, 1.0));
+        assert_eq!(gradient.sample(2.0), Color::white());
+    }
+
+    /// Verify that gradient fills are painted across the shape, and that grain varies neighboring pixels.
+    #[cfg(feature = "tiny_skia_renderer")]
+    #[test]
+    fn raster_gradient_fill() {
+        use crate::{renderers::SkiaRenderer, Canvas};
+        use glam::UVec2;
+
+        let fill = GradientFill::new(
+            Gradient::new(Color::black(), Color::white()),
+            Vec2::new(-1.0, 0.0),
+            Vec2::new(1.0, 0.0),
+        );
+        let square = [
+            Vec2::new(-1.0, -1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(-1.0, -1.0),
+        ];
+
+        let mut canvas = Canvas::new(16);
+        canvas.draw_gradient_shape(square, None, fill.clone());
+        assert_eq!(
+            canvas.as_raw()[0].fill,
+            Some(Color::new(0.5, 0.5, 0.5, 1.0))
+        );
+
+        let renderer = SkiaRenderer::new(UVec2::splat(16), None, false, true).unwrap();
+        let image = canvas.render(renderer).unwrap();
+        let row: Vec<u8> = (0..16).map(|x| image.get_pixel(x, 8)[0]).collect();
+        assert!(row[0] < 12 && row[15] > 243);
+        assert!(row.windows(2).all(|pair| pair[0] < pair[1]));
+
+        let mut canvas = Canvas::new(16);
+        canvas.draw_gradient_shape(square, None, GradientFill { grain: 0.5, ..fill });
+        let renderer = SkiaRenderer::new(UVec2::splat(16), None, false, true).unwrap();
+        let image = canvas.render(renderer).unwrap();
+        let row: Vec<u8> = (0..16).map(|x| image.get_pixel(x, 8)[0]).collect();
+        assert!(row.windows(2).any(|pair| pair[0] > pair[1]));
+    }
+
+    /// Verify that a gradient fill far larger than the image is only rasterized where it is visible.
+    #[cfg(feature = "tiny_skia_renderer")]
+    #[test]
+    fn raster_huge_gradient_fill() {
+        use crate::{renderers::SkiaRenderer, Canvas};
+        use glam::UVec2;
+
+        let fill = GradientFill::new(
+            Gradient::new(Color::black(), Color::white()),
+            Vec2::new(-1.0, 0.0),
+            Vec2::new(1.0, 0.0),
+        );
+        let size = 1e5;
+        let square = [
+            Vec2::new(-size, -size),
+            Vec2::new(size, -size),
+            Vec2::new(size, size),
+            Vec2::new(-size, size),
+            Vec2::new(-size, -size),
+        ];
+
+        let mut canvas = Canvas::new(16);
+        canvas.draw_gradient_shape(square, None, fill);
+        let renderer = SkiaRenderer::new(UVec2::splat(16), None, false, true).unwrap();
+        let image = canvas.render(renderer).unwrap();
+        let row: Vec<u8> = (0..16).map(|x| image.get_pixel(x, 8)[0]).collect();
+        assert!(row[0] < 12 && row[15] > 243);
+        assert!(row.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
@@ -0,0 +1,807 @@
+use glam::Vec2;
+
+use crate::{
+    labels::{Label, LabelPlacement, PlacedLabel},
+    rng::Rng,
+    Canvas, Color, Stroke,
+};
+
+/// A graph of nodes, numbered from zero, joined by directed edges, such as the modules of a program and their dependencies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Graph {
+    /// How many nodes there are.
+    pub nodes: usize,
+    /// Each edge, from one node to another.
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl Graph {
+    /// Create a new [Graph] of `nodes` nodes. Edges to nodes that don't exist are ignored.
+    pub fn new(nodes: usize, edges: Vec<(usize, usize)>) -> Self {
+        Self { nodes, edges }
+    }
+
+    /// Lay the graph out with a [ForceLayout].
+    pub fn force_directed(&self, layout: &ForceLayout) -> GraphLayout {
+        layout.layout(self)
+    }
+
+    /// Lay the graph out with a [LayeredLayout].
+    pub fn layered(&self, layout: &LayeredLayout) -> GraphLayout {
+        layout.layout(self)
+    }
+
+    /// The edges between nodes that exist, without loops from a node to itself.
+    fn valid_edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.edges
+            .iter()
+            .copied()
+            .filter(|&(from, to)| from < self.nodes && to < self.nodes && from != to)
+    }
+}
+
+/// Where a [Graph] was laid out.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GraphLayout {
+    /// The center of each node.
+    pub positions: Vec<Vec2>,
+    /// The path of each edge, in the same order as [Graph::edges], from the center of one node to the center of the other.
+    /// Loops from a node to itself, and edges to nodes that don't exist, have no points.
+    pub routes: Vec<Vec<Vec2>>,
+}
+
+impl GraphLayout {
+    /// Place a label of each size next to its node, without covering other labels or nodes. See [LabelPlacement].
+    ///
+    /// The placement's gap should be at least the radius of the nodes, so that labels clear them.
+    pub fn labels(&self, sizes: &[Vec2], placement: &LabelPlacement) -> Vec<Option<PlacedLabel>> {
+        let labels: Vec<Label> = self
+            .positions
+            .iter()
+            .zip(sizes)
+            .map(|(&anchor, &size)| Label::new(anchor, size))
+            .collect();
+
+        placement.place(&labels)
+    }
+}
+
+/// A force-directed layout, after Fruchterman and Reingold, which pulls joined nodes together and pushes every node apart from the rest.
+///
+/// Nodes start at random and settle over many iterations into a layout where clusters and symmetry show.
+/// The same seed always produces the same layout.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ForceLayout {
+    /// Width and height of the area the nodes are laid out in, centered on the origin.
+    pub size: Vec2,
+    /// How many times to move the nodes.
+    pub iterations: usize,
+    /// Seed for the starting positions.
+    pub seed: u64,
+}
+
+impl ForceLayout {
+    /// Create a new [ForceLayout] filling an area of `size`, with 300 iterations.
+    pub fn new(size: Vec2) -> Self {
+        Self {
+            size,
+            iterations: 300,
+            seed: 0,
+        }
+    }
+
+    /// Lay out `graph`.
+    pub fn layout(&self, graph: &Graph) -> GraphLayout {
+        let mut rng = Rng::new(self.seed);
+        let half = self.size / 2.0;
+        let mut positions: Vec<Vec2> = (0..graph.nodes)
+            .map(|_| Vec2::new(rng.range(-half.x, half.x), rng.range(-half.y, half.y)))
+            .collect();
+
+        // The ideal distance between nodes, if they were spread evenly over the area.
+        let ideal = (self.size.x * self.size.y / graph.nodes.max(1) as f32)
+            .sqrt()
+            .max(f32::EPSILON);
+        let edges: Vec<(usize, usize)> = graph.valid_edges().collect();
+
+        for iteration in 0..self.iterations {
+            let mut moves = vec![Vec2::ZERO; graph.nodes];
+
+            for (i, &position) in positions.iter().enumerate() {
+                for (j, &other) in positions.iter().enumerate().skip(i + 1) {
+                    let mut away = position - other;
+                    if away.length_squared() < 1e-12 {
+                        // Nodes on top of each other are nudged apart in a fixed direction.
+                        away = Vec2::new(1e-3, 0.0);
+                    }
+                    let push = away.normalize() * ideal * ideal / away.length();
+                    moves[i] += push;
+                    moves[j] -= push;
+                }
+            }
+
+            for &(from, to) in &edges {
+                let apart = positions[to] - positions[from];
+                let pull = apart * apart.length() / ideal;
+                moves[from] += pull;
+                moves[to] -= pull;
+            }
+
+            // Cool down over time, so the layout settles instead of jittering.
+            let temperature =
+                half.max_element() / 10.0 * (1.0 - iteration as f32 / self.iterations as f32);
+            for (position, step) in positions.iter_mut().zip(moves) {
+                *position = (*position + step.clamp_length_max(temperature)).clamp(-half, half);
+            }
+        }
+
+        let routes = straight_routes(graph, &positions);
+        GraphLayout { positions, routes }
+    }
+}
+
+/// A layered layout, after Sugiyama, which ranks nodes into rows so that edges point down the page, such as for dependency diagrams.
+///
+/// Cycles are broken by reversing edges, nodes are ranked by the longest path to them, and each row is reordered to untangle crossing edges.
+/// Edges that skip over rows are routed through the rows in between.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LayeredLayout {
+    /// Distance between rows, downwards from the first row at `y = 0`.
+    pub layer_spacing: f32,
+    /// Distance between neighbouring nodes in a row, each centered on `x = 0`.
+    pub node_spacing: f32,
+    /// How many times to sweep down and back up the rows, reordering them to reduce crossings.
+    pub sweeps: usize,
+}
+
+impl LayeredLayout {
+    /// Create a new [LayeredLayout], with four sweeps to reduce crossings.
+    pub fn new(layer_spacing: f32, node_spacing: f32) -> Self {
+        Self {
+            layer_spacing,
+            node_spacing,
+            sweeps: 4,
+        }
+    }
+
+    /// Lay out `graph`.
+    pub fn layout(&self, graph: &Graph) -> GraphLayout {
+        let edges = acyclic_edges(graph);
+        let layers = longest_path_layers(graph.nodes, &edges);
+
+        // Break edges that skip rows into chains through extra, invisible nodes, one per row.
+        let mut node_layers = layers.clone();
+        let mut chains: Vec<Vec<usize>> = Vec::with_capacity(edges.len());
+        let mut links = Vec::new();
+        for &(from, to) in &edges {
+            let mut chain = vec![from];
+            for layer in layers[from] + 1..layers[to] {
+                node_layers.push(layer);
+                chain.push(node_layers.len() - 1);
+            }
+            chain.push(to);
+            links.extend(chain.windows(2).map(|pair| (pair[0], pair[1])));
+            chains.push(chain);
+        }
+
+        let depth = node_layers.iter().max().map_or(0, |max| max + 1);
+        let mut rows: Vec<Vec<usize>> = vec![Vec::new(); depth];
+        for (node, &layer) in node_layers.iter().enumerate() {
+            rows[layer].push(node);
+        }
+
+        // Order each row by the average position of its neighbours in the row before, sweeping down then up.
+        let mut order = vec![0.0; node_layers.len()];
+        let place = |rows: &[Vec<usize>], order: &mut Vec<f32>| {
+            for row in rows {
+                for (index, &node) in row.iter().enumerate() {
+                    order[node] = index as f32;
+                }
+            }
+        };
+        place(&rows, &mut order);
+        for _ in 0..self.sweeps {
+            for downward in [true, false] {
+                let layers: Vec<usize> = if downward {
+                    (1..depth).collect()
+                } else {
+                    (0..depth.saturating_sub(1)).rev().collect()
+                };
+
+                for layer in layers {
+                    let barycenter = |node: usize| {
+                        let neighbors: Vec<f32> = links
+                            .iter()
+                            .filter_map(|&(from, to)| match downward {
+                                true if to == node => Some(order[from]),
+                                false if from == node => Some(order[to]),
+                                _ => None,
+                            })
+                            .collect();
+                        if neighbors.is_empty() {
+                            order[node]
+                        } else {
+                            neighbors.iter().sum::<f32>() / neighbors.len() as f32
+                        }
+                    };
+
+                    let mut keyed: Vec<(f32, usize)> = rows[layer]
+                        .iter()
+                        .map(|&node| (barycenter(node), node))
+                        .collect();
+                    keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+                    rows[layer] = keyed.into_iter().map(|(_, node)| node).collect();
+                    place(&rows[layer..=layer], &mut order);
+                }
+            }
+        }
+
+        let mut positions = vec![Vec2::ZERO; node_layers.len()];
+        for (layer, row) in rows.iter().enumerate() {
+            let width = row.len().saturating_sub(1) as f32 * self.node_spacing;
+            for (index, &node) in row.iter().enumerate() {
+                positions[node] = Vec2::new(
+                    index as f32 * self.node_spacing - width / 2.0,
+                    -(layer as f32) * self.layer_spacing,
+                );
+            }
+        }
+
+        // Route each edge through its chain, in the edge's own direction.
+        let mut chains = chains.into_iter();
+        let routes = graph
+            .edges
+            .iter()
+            .map(|&(from, to)| {
+                if from >= graph.nodes || to >= graph.nodes || from == to {
+                    return Vec::new();
+                }
+                let chain = chains.next().unwrap_or_default();
+                let mut route: Vec<Vec2> = chain.iter().map(|&node| positions[node]).collect();
+                if chain.first() != Some(&from) {
+                    route.reverse();
+                }
+                route
+            })
+            .collect();
+
+        positions.truncate(graph.nodes);
+        GraphLayout { positions, routes }
+    }
+}
+
+/// A tree, or several, given by the parent of each node, such as an org chart or a directory listing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tree {
+    /// The parent of each node, or [None] for a root. Children are ordered by their own index.
+    pub parents: Vec<Option<usize>>,
+}
+
+impl Tree {
+    /// Create a new [Tree] from the parent of each node. Parents that don't exist, or that would form a cycle, make a node a root.
+    pub fn new(parents: Vec<Option<usize>>) -> Self {
+        Self { parents }
+    }
+
+    /// Each edge of the tree, from parent to child, in the order of the children. This is the order of a [GraphLayout]'s routes.
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        let (_, parents) = self.children();
+        parents
+            .iter()
+            .enumerate()
+            .filter_map(|(child, parent)| parent.map(|parent| (parent, child)))
+            .collect()
+    }
+
+    /// Lay out the tree with a [TreeLayout].
+    pub fn layout(&self, layout: &TreeLayout) -> GraphLayout {
+        layout.layout(self)
+    }
+
+    /// The children of each node, with an extra node at the end holding every root, and the parent of each node once cycles are broken.
+    pub(crate) fn children(&self) -> (Vec<Vec<usize>>, Vec<Option<usize>>) {
+        let count = self.parents.len();
+        let mut parents: Vec<Option<usize>> = self
+            .parents
+            .iter()
+            .enumerate()
+            .map(|(node, parent)| parent.filter(|&parent| parent < count && parent != node))
+            .collect();
+
+        // Walk up from each node, cutting the tree at the first node seen twice.
+        for start in 0..count {
+            let mut seen = vec![start];
+            let mut node = start;
+            while let Some(parent) = parents[node] {
+                if seen.contains(&parent) {
+                    parents[node] = None;
+                    break;
+                }
+                seen.push(parent);
+                node = parent;
+            }
+        }
+
+        let mut children = vec![Vec::new(); count + 1];
+        for (child, parent) in parents.iter().enumerate() {
+            children[parent.unwrap_or(count)].push(child);
+        }
+        (children, parents)
+    }
+}
+
+/// How to draw the edge from a parent to a child in a [TreeLayout].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Connector {
+    /// A straight line.
+    #[default]
+    Straight,
+    /// Down from the parent, across, then down to the child, like an org chart.
+    Elbow,
+    /// A smooth S-shaped curve from parent to child.
+    Curved,
+}
+
+/// A tidy tree layout, after Reingold and Tilford, which centers each parent over its children and packs subtrees as close as they fit.
+///
+/// Roots are at the top at `y = 0`, each level further down the page, and separate trees sit side by side.
+/// A [radial](Self::radial) layout wraps the same arrangement around a circle, with the roots at the center.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TreeLayout {
+    /// Distance between levels of the tree, down the page or out from the center.
+    pub level_spacing: f32,
+    /// The smallest distance between neighbouring nodes on the same level.
+    pub sibling_spacing: f32,
+    /// How edges are drawn.
+    pub connector: Connector,
+    /// Wrap the tree around a circle, with the roots at the center and leaves around the outside.
+    pub radial: bool,
+    /// Put every leaf on the deepest level, as in a dendrogram of a phylogenetic tree.
+    pub align_leaves: bool,
+}
+
+impl TreeLayout {
+    /// Create a new [TreeLayout] going down the page, with straight connectors.
+    pub fn new(level_spacing: f32, sibling_spacing: f32) -> Self {
+        Self {
+            level_spacing,
+            sibling_spacing,
+            connector: Connector::Straight,
+            radial: false,
+            align_leaves: false,
+        }
+    }
+
+    /// Lay out `tree`.
+    pub fn layout(&self, tree: &Tree) -> GraphLayout {
+        let (children, _) = tree.children();
+        let count = tree.parents.len();
+
+        // Lay out the roots as children of an extra node, one level above them.
+        let subtree = tidy_subtree(count, &children, self.sibling_spacing).nodes;
+        let mut places = vec![Vec2::ZERO; count];
+        let mut depths = vec![0; count + 1];
+        let mut stack = vec![count];
+        while let Some(node) = stack.pop() {
+            for &child in &children[node] {
+                depths[child] = depths[node] + 1;
+                stack.push(child);
+            }
+        }
+        let deepest = depths.iter().copied().max().unwrap_or(0);
+        for &(node, x) in &subtree {
+            if node < count {
+                let depth = if self.align_leaves && children[node].is_empty() {
+                    deepest
+                } else {
+                    depths[node]
+                };
+                places[node] = Vec2::new(x, (depth - 1) as f32);
+            }
+        }
+
+        // Routes are found in the tidy layout, with x across and levels down, then placed.
+        let (min_x, max_x) = places
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), place| {
+                (min.min(place.x), max.max(place.x))
+            });
+        let place = |point: Vec2| {
+            if self.radial {
+                let span = (max_x - min_x + self.sibling_spacing).max(f32::EPSILON);
+                let angle = std::f32::consts::TAU * (point.x - min_x) / span;
+                Vec2::new(angle.cos(), angle.sin()) * (point.y + 1.0) * self.level_spacing
+            } else {
+                Vec2::new(point.x, -point.y * self.level_spacing)
+            }
+        };
+
+        let routes = tree
+            .edges()
+            .into_iter()
+            .map(|(parent, child)| {
+                let (from, to) = (places[parent], places[child]);
+                let middle = (from.y + to.y) / 2.0;
+                let route = match self.connector {
+                    Connector::Straight => vec![from, to],
+                    Connector::Elbow => {
+                        let across = Vec2::new(to.x, middle);
+                        let turn = Vec2::new(from.x, middle);
+                        // Curve the crossing piece along its circle when radial.
+                        let steps = if self.radial { 16 } else { 1 };
+                        let mut route = vec![from];
+                        route.extend(
+                            (0..=steps).map(|i| turn.lerp(across, i as f32 / steps as f32)),
+                        );
+                        route.push(to);
+                        route
+                    }
+                    Connector::Curved => {
+                        let (a, b) = (Vec2::new(from.x, middle), Vec2::new(to.x, middle));
+                        (0..=16)
+                            .map(|i| {
+                                let t = i as f32 / 16.0;
+                                let u = 1.0 - t;
+                                from * u * u * u
+                                    + a * 3.0 * u * u * t
+                                    + b * 3.0 * u * t * t
+                                    + to * t * t * t
+                            })
+                            .collect()
+                    }
+                };
+                route.into_iter().map(place).collect()
+            })
+            .collect();
+
+        GraphLayout {
+            positions: places.into_iter().map(place).collect(),
+            routes,
+        }
+    }
+}
+
+/// A laid out part of a tree, with its top node at `x = 0`.
+struct Subtree {
+    /// The position across of every node in it, the top node first.
+    nodes: Vec<(usize, f32)>,
+    /// The leftmost and rightmost position at each level, starting with the top node's.
+    contour: Vec<(f32, f32)>,
+}
+
+/// Lay out the subtree under `node`.
+fn tidy_subtree(node: usize, children: &[Vec<usize>], spacing: f32) -> Subtree {
+    let mut nodes: Vec<(usize, f32)> = Vec::new();
+    let mut contour: Vec<(f32, f32)> = Vec::new();
+    let mut offsets = Vec::new();
+
+    for &child in &children[node] {
+        let Subtree {
+            nodes: child_nodes,
+            contour: child_contour,
+        } = tidy_subtree(child, children, spacing);
+
+        // Push the new subtree right until it clears everything to its left on every level.
+        let offset = contour
+            .iter()
+            .zip(&child_contour)
+            .map(|(&(_, right), &(left, _))| right - left + spacing)
+            .fold(0.0, f32::max);
+
+        nodes.extend(child_nodes.into_iter().map(|(node, x)| (node, x + offset)));
+        for (level, &(left, right)) in child_contour.iter().enumerate() {
+            let (left, right) = (left + offset, right + offset);
+            match contour.get_mut(level) {
+                Some(existing) => *existing = (existing.0.min(left), existing.1.max(right)),
+                None => contour.push((left, right)),
+            }
+        }
+        offsets.push(offset);
+    }
+
+    // Center the parent over its first and last children.
+    let center = match (offsets.first(), offsets.last()) {
+        (Some(first), Some(last)) => (first + last) / 2.0,
+        _ => 0.0,
+    };
+    let mut placed = vec![(node, 0.0)];
+    placed.extend(nodes.into_iter().map(|(node, x)| (node, x - center)));
+
+    let mut levels = vec![(0.0, 0.0)];
+    levels.extend(
+        contour
+            .into_iter()
+            .map(|(left, right)| (left - center, right - center)),
+    );
+    Subtree {
+        nodes: placed,
+        contour: levels,
+    }
+}
+
+/// How to draw a [GraphLayout] onto a [Canvas].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GraphStyle {
+    /// Radius of the circle drawn for each node. Edges stop at its edge.
+    pub node_radius: f32,
+    /// Outline of each node.
+    pub node_stroke: Option<Stroke>,
+    /// Fill of each node.
+    pub node_fill: Option<Color>,
+    /// The line of each edge.
+    pub edge_stroke: Stroke,
+    /// Length of the arrowhead at the end of each edge, filled in the edge's color. No arrowheads are drawn if this is zero.
+    pub arrow_size: f32,
+}
+
+impl GraphStyle {
+    /// Create a new [GraphStyle], with nodes outlined like the edges and no arrowheads.
+    pub fn new(node_radius: f32, edge_stroke: Stroke) -> Self {
+        Self {
+            node_radius,
+            node_stroke: Some(edge_stroke),
+            node_fill: None,
+            edge_stroke,
+            arrow_size: 0.0,
+        }
+    }
+
+    /// Set the length of arrowheads, consuming the parent.
+    pub fn with_arrows(mut self, arrow_size: f32) -> Self {
+        self.arrow_size = arrow_size;
+        self
+    }
+}
+
+impl Canvas {
+    /// Draw a laid out graph in World Space: each edge, trimmed to stop at the nodes, then each node on top.
+    pub fn draw_graph(&mut self, layout: &GraphLayout, style: &GraphStyle) {
+        for route in &layout.routes {
+            let Some(route) = trim_route(
+                route,
+                style.node_radius,
+                style.node_radius + style.arrow_size,
+            ) else {
+                continue;
+            };
+
+            let end = route[route.len() - 1];
+            let direction = (end - route[route.len() - 2]).normalize_or_zero();
+            self.draw_polyline_absolute(route, style.edge_stroke);
+
+            if style.arrow_size > 0.0 {
+                let tip = end + direction * style.arrow_size;
+                let side = direction.perp() * style.arrow_size * 0.4;
+                self.draw_shape_absolute(
+                    vec![tip, end + side, end - side, tip],
+                    None,
+                    Some(style.edge_stroke.color),
+                );
+            }
+        }
+
+        if style.node_stroke.is_some() || style.node_fill.is_some() {
+            for &position in &layout.positions {
+                self.draw_circle_absolute(
+                    position,
+                    style.node_radius,
+                    style.node_stroke,
+                    style.node_fill,
+                );
+            }
+        }
+    }
+}
+
+/// Straight routes from node to node.
+fn straight_routes(graph: &Graph, positions: &[Vec2]) -> Vec<Vec<Vec2>> {
+    graph
+        .edges
+        .iter()
+        .map(|&(from, to)| {
+            if from < graph.nodes && to < graph.nodes && from != to {
+                vec![positions[from], positions[to]]
+            } else {
+                Vec::new()
+            }
+        })
+        .collect()
+}
+
+/// The valid edges of `graph`, with some turned around so that there are no cycles.
+pub(crate) fn acyclic_edges(graph: &Graph) -> Vec<(usize, usize)> {
+    let edges: Vec<(usize, usize)> = graph.valid_edges().collect();
+
+    // Depth-first search, turning around every edge back to a node still being explored.
+    let mut state = vec![0u8; graph.nodes];
+    let mut reversed = vec![false; edges.len()];
+    for root in 0..graph.nodes {
+        if state[root] != 0 {
+            continue;
+        }
+        let mut stack = vec![(root, 0)];
+        state[root] = 1;
+        while let Some(&mut (node, ref mut next)) = stack.last_mut() {
+            match edges
+                .iter()
+                .enumerate()
+                .skip(*next)
+                .find(|(_, edge)| edge.0 == node)
+            {
+                Some((index, &(_, to))) => {
+                    *next = index + 1;
+                    match state[to] {
+                        0 => {
+                            state[to] = 1;
+                            stack.push((to, 0));
+                        }
+                        1 => reversed[index] = true,
+                        _ => {}
+                    }
+                }
+                None => {
+                    state[node] = 2;
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    edges
+        .into_iter()
+        .zip(reversed)
+        .map(|((from, to), reversed)| if reversed { (to, from) } else { (from, to) })
+        .collect()
+}
+
+/// Rank each node one below the lowest node pointing to it. `edges` must have no cycles.
+pub(crate) fn longest_path_layers(nodes: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let mut layers = vec![0; nodes];
+    for node in topological_order(nodes, edges) {
+        for &(from, to) in edges {
+            if from == node {
+                layers[to] = layers[to].max(layers[node] + 1);
+            }
+        }
+    }
+    layers
+}
+
+/// The nodes in an order where every edge points forwards. `edges` must have no cycles.
+fn topological_order(nodes: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let mut incoming = vec![0; nodes];
+    for &(_, to) in edges {
+        incoming[to] += 1;
+    }
+
+    let mut ready: Vec<usize> = (0..nodes).filter(|&node| incoming[node] == 0).collect();
+    let mut order = Vec::with_capacity(nodes);
+    while let Some(node) = ready.pop() {
+        order.push(node);
+        for &(from, to) in edges {
+            if from == node {
+                incoming[to] -= 1;
+                if incoming[to] == 0 {
+                    ready.push(to);
+                }
+            }
+        }
+    }
+    order
+}
+
+/// Shorten a route by `start` at its beginning and `end` at its end, or [None] if nothing would be left.
+pub(crate) fn trim_route(route: &[Vec2], start: f32, end: f32) -> Option<Vec<Vec2>> {
+    if route.len() < 2 {
+        return None;
+    }
+    let mut route = route.to_vec();
+
+    let first = route[0] + (route[1] - route[0]).normalize_or_zero() * start;
+    let last = route.len() - 1;
+    let final_point = route[last] + (route[last - 1] - route[last]).normalize_or_zero() * end;
+
+    if route.len() == 2 && (final_point - first).dot(route[1] - route[0]) <= 0.0 {
+        return None;
+    }
+    route[0] = first;
+    route[last] = final_point;
+    Some(route)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LineEnd;
+
+    /// Verify that layered layouts rank nodes down the page through cycles, and that force-directed layouts keep nodes apart.
+    #[test]
+    fn graph_layouts() {
+        // A diamond with a shortcut from top to bottom, and a cycle back up.
+        let graph = Graph::new(
+            4,
+            vec![(0, 1), (0, 2), (1, 3), (2, 3), (0, 3), (3, 0), (1, 1)],
+        );
+
+        let layered = graph.layered(&LayeredLayout::new(2.0, 1.0));
+        let rows: Vec<f32> = layered
+            .positions
+            .iter()
+            .map(|position| position.y)
+            .collect();
+        assert_eq!(rows, vec![0.0, -2.0, -2.0, -4.0]);
+
+        // The shortcut bends through the middle row, and the cycle's edge still points from 3 to 0.
+        assert_eq!(layered.routes[4].len(), 3);
+        assert_eq!(layered.routes[5].first(), Some(&layered.positions[3]));
+        assert_eq!(layered.routes[5].last(), Some(&layered.positions[0]));
+        assert!(layered.routes[6].is_empty());
+
+        let force = graph.force_directed(&ForceLayout::new(Vec2::splat(10.0)));
+        for (i, a) in force.positions.iter().enumerate() {
+            assert!(a.abs().cmple(Vec2::splat(5.0)).all());
+            for b in &force.positions[i + 1..] {
+                assert!(a.distance(*b) > 1.0);
+            }
+        }
+
+        let mut canvas = Canvas::new(100);
+        let stroke = Stroke::new(Color::black(), 0.05, LineEnd::Butt);
+        canvas.draw_graph(&layered, &GraphStyle::new(0.3, stroke).with_arrows(0.2));
+        // Six edges with arrowheads, and four nodes.
+        assert_eq!(canvas.as_raw().len(), 16);
+    }
+
+    /// Verify that tidy trees center parents over their children without overlaps, and that radial trees put levels on rings.
+    #[test]
+    fn tree_layouts() {
+        //      0
+        //    / | \
+        //   1  2  3
+        //  / \     \
+        // 4   5     6
+        let tree = Tree::new(vec![
+            None,
+            Some(0),
+            Some(0),
+            Some(0),
+            Some(1),
+            Some(1),
+            Some(3),
+        ]);
+
+        let tidy = tree.layout(&TreeLayout::new(2.0, 1.0));
+        let positions = &tidy.positions;
+        assert_eq!(positions[0], Vec2::ZERO);
+        assert_eq!(positions[4].y, -4.0);
+        assert!((positions[1].x - (positions[4].x + positions[5].x) / 2.0).abs() < 1e-6);
+        assert!((positions[0].x - (positions[1].x + positions[3].x) / 2.0).abs() < 1e-6);
+        for (i, a) in positions.iter().enumerate() {
+            for b in &positions[i + 1..] {
+                assert!(a.y != b.y || (a.x - b.x).abs() >= 1.0 - 1e-6);
+            }
+        }
+        assert_eq!(tidy.routes.len(), 6);
+
+        let mut layout = TreeLayout::new(2.0, 1.0);
+        layout.radial = true;
+        layout.connector = Connector::Curved;
+        let radial = tree.layout(&layout);
+        assert!((radial.positions[4].length() - 6.0).abs() < 1e-4);
+        assert!((radial.positions[2].length() - 4.0).abs() < 1e-4);
+        assert!(radial.routes.iter().all(|route| route.len() == 17));
+
+        // A cycle is cut, leaving one of its nodes as a root.
+        let cycle = Tree::new(vec![Some(1), Some(0)]);
+        assert_eq!(cycle.edges().len(), 1);
+        assert_eq!(cycle.layout(&TreeLayout::new(1.0, 1.0)).routes.len(), 1);
+    }
+}
@@ -0,0 +1,154 @@
+use glam::Vec2;
+
+use crate::{rng::Rng, spatial::SpatialIndex, Shape};
+
+/// A differential growth simulation of a closed curve, which folds into organic, coral-like forms as it grows.
+///
+/// Each step, every node is pulled towards the midpoint of its neighbours, pushed away from any other nearby node of the curve,
+/// and edges that grow too long are split in two. With more nodes crowding into the same space, the curve buckles and meanders.
+/// The same seed always produces the same growth.
+#[derive(Debug, Clone)]
+pub struct DifferentialGrowth {
+    /// The nodes of the curve, in order. The last node connects back to the first.
+    pub nodes: Vec<Vec2>,
+    /// Edges longer than this are split in two.
+    pub max_edge_length: f32,
+    /// How close another node must be to push a node away.
+    pub repulsion_radius: f32,
+    /// How strongly nearby nodes push each other apart, from 0.0 to 1.0.
+    pub repulsion: f32,
+    /// How strongly each node is pulled towards the midpoint of its neighbours, from 0.0 to 1.0, which keeps the curve smooth.
+    pub attraction: f32,
+    /// Edges stop being split once the curve has this many nodes.
+    pub max_nodes: usize,
+    rng: Rng,
+}
+
+impl DifferentialGrowth {
+    /// Create a new [DifferentialGrowth] from the points of a closed curve, such as a small circle.
+    ///
+    /// Edges are split when longer than `max_edge_length`, and nodes repel each other within twice that distance.
+    pub fn new(points: Vec<Vec2>, max_edge_length: f32, seed: u64) -> Self {
+        let mut nodes = points;
+        if nodes.len() > 1 && nodes[0] == nodes[nodes.len() - 1] {
+            nodes.pop();
+        }
+
+        Self {
+            nodes,
+            max_edge_length,
+            repulsion_radius: max_edge_length * 2.0,
+            repulsion: 0.5,
+            attraction: 0.2,
+            max_nodes: 10_000,
+            rng: Rng::new(seed),
+        }
+    }
+
+    /// Advance the simulation by one step.
+    pub fn step(&mut self) {
+        let count = self.nodes.len();
+        if count < 3 {
+            return;
+        }
+
+        let radius = self.repulsion_radius.max(f32::EPSILON);
+        let mut index = SpatialIndex::new(radius);
+        for (i, &node) in self.nodes.iter().enumerate() {
+            index.insert(i, node, node);
+        }
+
+        let moved: Vec<Vec2> = (0..count)
+            .map(|i| {
+                let node = self.nodes[i];
+                let previous = self.nodes[(i + count - 1) % count];
+                let next = self.nodes[(i + 1) % count];
+                let mut offset = ((previous + next) / 2.0 - node) * self.attraction;
+
+                // Neighbours are kept in line by attraction instead, so that edges only lengthen as the curve is crowded.
+                for j in index.query(node - radius, node + radius) {
+                    let away = node - self.nodes[j];
+                    let distance = away.length();
+                    let neighbor = j == (i + 1) % count || j == (i + count - 1) % count;
+                    if j != i && !neighbor && distance > 0.0 && distance < radius {
+                        offset += away / distance * (radius - distance) * self.repulsion / 2.0;
+                    }
+                }
+
+                // Limit how far a crowded node can jump, which keeps the simulation stable.
+                node + offset.clamp_length_max(self.max_edge_length / 4.0)
+            })
+            .collect();
+
+        self.nodes = Vec::with_capacity(count * 2);
+        for i in 0..count {
+            let (node, next) = (moved[i], moved[(i + 1) % count]);
+            self.nodes.push(node);
+
+            let length = node.distance(next);
+            if length > self.max_edge_length && self.nodes.len() + (count - i) < self.max_nodes {
+                // Nudge the new node off the line, so that growth can break symmetry.
+                let nudge = self.rng.in_circle(length * 0.01);
+                self.nodes.push(node.lerp(next, 0.5) + nudge);
+            }
+        }
+    }
+
+    /// Advance the simulation by `steps` steps, returning the curve after each of them.
+    pub fn grow(&mut self, steps: usize) -> Vec<Shape> {
+        (0..steps)
+            .map(|_| {
+                self.step();
+                self.shape()
+            })
+            .collect()
+    }
+
+    /// The curve as a closed shape, with no stroke or fill.
+    pub fn shape(&self) -> Shape {
+        let mut points = self.nodes.clone();
+        if let Some(&first) = self.nodes.first() {
+            points.push(first);
+        }
+
+        Shape {
+            points,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::arc_lengths;
+
+    /// Verify that the curve gains nodes and length as it grows, without nodes bunching up.
+    #[test]
+    fn differential_growth() {
+        let circle: Vec<Vec2> = (0..24)
+            .map(|i| {
+                let angle = std::f32::consts::TAU * i as f32 / 24.0;
+                Vec2::new(angle.cos(), angle.sin()) * 4.0
+            })
+            .collect();
+
+        let mut growth = DifferentialGrowth::new(circle, 1.0, 5);
+        let shapes = growth.grow(80);
+        assert_eq!(shapes.len(), 80);
+
+        let length = |shape: &Shape| arc_lengths(&shape.points).last().copied().unwrap_or(0.0);
+        assert!(length(&shapes[79]) > 1.5 * length(&shapes[0]));
+        assert!(growth.nodes.len() > 48);
+        assert_eq!(shapes[79].points.first(), shapes[79].points.last());
+
+        let count = growth.nodes.len();
+        for i in 0..count {
+            for j in i + 2..count {
+                if (i, j) != (0, count - 1) {
+                    assert!(growth.nodes[i].distance(growth.nodes[j]) > 0.2);
+                }
+            }
+        }
+    }
+}
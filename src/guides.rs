@@ -0,0 +1,261 @@
+use glam::{Mat2, Vec2};
+
+use crate::{
+    geometry::clip_segment,
+    hex::{Hex, HexLayout, HexOrientation},
+    Canvas, Stroke,
+};
+
+/// A regular grid to lay a composition out on, with helpers to snap points to it and draw it as guide lines.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Grid {
+    /// Axis-aligned rectangular cells. Points snap to the corners of the cells.
+    Rectangular {
+        /// A corner shared by four cells.
+        origin: Vec2,
+        /// Width and height of each cell.
+        spacing: Vec2,
+    },
+    /// Pointy-topped hexagonal cells. Points snap to the centers of the cells.
+    Hexagonal {
+        /// The center of one cell.
+        origin: Vec2,
+        /// Distance from the center of a cell to each of its corners.
+        size: f32,
+    },
+    /// A triangular lattice of vertical lines and lines at ±30°, as used for isometric drawings. Points snap to where the lines cross.
+    Isometric {
+        /// A point where lines cross.
+        origin: Vec2,
+        /// Distance between neighbouring crossings.
+        spacing: f32,
+    },
+}
+
+impl Grid {
+    /// Create a rectangular [Grid] of square cells `spacing` wide, with a corner at the origin.
+    pub fn rectangular(spacing: f32) -> Self {
+        Self::Rectangular {
+            origin: Vec2::ZERO,
+            spacing: Vec2::splat(spacing),
+        }
+    }
+
+    /// Create a hexagonal [Grid] of cells `size` from center to corner, with a cell centered on the origin.
+    pub fn hexagonal(size: f32) -> Self {
+        Self::Hexagonal {
+            origin: Vec2::ZERO,
+            size,
+        }
+    }
+
+    /// Create an isometric [Grid] with crossings `spacing` apart, one of them on the origin.
+    pub fn isometric(spacing: f32) -> Self {
+        Self::Isometric {
+            origin: Vec2::ZERO,
+            spacing,
+        }
+    }
+
+    /// Move the grid so that it lines up with `origin`.
+    pub fn with_origin(mut self, new_origin: Vec2) -> Self {
+        match &mut self {
+            Self::Rectangular { origin, .. }
+            | Self::Hexagonal { origin, .. }
+            | Self::Isometric { origin, .. } => *origin = new_origin,
+        }
+        self
+    }
+
+    /// The nearest grid point to `point`.
+    pub fn snap_to_grid(&self, point: Vec2) -> Vec2 {
+        match *self {
+            Self::Rectangular { origin, spacing } => {
+                origin + ((point - origin) / spacing).round() * spacing
+            }
+            Self::Hexagonal { size, origin } => {
+                let layout = HexLayout::new(HexOrientation::Pointy, size).with_origin(origin);
+                layout.hex_to_world(layout.world_to_hex(point))
+            }
+            Self::Isometric { origin, spacing } => {
+                let basis = Mat2::from_cols(
+                    Vec2::new(spacing * 30f32.to_radians().cos(), spacing / 2.0),
+                    Vec2::new(0.0, spacing),
+                );
+                let local = basis.inverse() * (point - origin);
+
+                // The nearest crossing is one of the corners of the parallelogram around it.
+                let floor = local.floor();
+                [Vec2::ZERO, Vec2::X, Vec2::Y, Vec2::ONE]
+                    .into_iter()
+                    .map(|corner| origin + basis * (floor + corner))
+                    .min_by(|a, b| {
+                        a.distance_squared(point)
+                            .total_cmp(&b.distance_squared(point))
+                    })
+                    .unwrap_or(point)
+            }
+        }
+    }
+
+    /// Draw the grid over the area from `min` to `max` onto `canvas`.
+    ///
+    /// Guides are usually drawn onto a canvas of their own, so that they can be rendered under or over the artwork while laying it out,
+    /// and left out of the final render.
+    /// Nothing is drawn unless the spacing or size of the grid is positive and finite.
+    pub fn draw_guides(&self, canvas: &mut Canvas, min: Vec2, max: Vec2, stroke: Stroke) {
+        let (min, max) = (min.min(max), min.max(max));
+
+        let positive = |value: f32| value > 0.0 && value.is_finite();
+        let valid = match *self {
+            Self::Rectangular { spacing, .. } => positive(spacing.x) && positive(spacing.y),
+            Self::Hexagonal { size, .. } => positive(size),
+            Self::Isometric { spacing, .. } => positive(spacing),
+        };
+        if !valid {
+            return;
+        }
+
+        match *self {
+            Self::Rectangular { origin, spacing } => {
+                let first = ((min - origin) / spacing).ceil();
+                let last = ((max - origin) / spacing).floor();
+
+                for i in first.x as i32..=last.x as i32 {
+                    let x = origin.x + i as f32 * spacing.x;
+                    canvas.draw_line_absolute(
+                        Vec2::new(x, min.y),
+                        Vec2::new(x, max.y),
+                        Some(stroke),
+                        None,
+                    );
+                }
+                for i in first.y as i32..=last.y as i32 {
+                    let y = origin.y + i as f32 * spacing.y;
+                    canvas.draw_line_absolute(
+                        Vec2::new(min.x, y),
+                        Vec2::new(max.x, y),
+                        Some(stroke),
+                        None,
+                    );
+                }
+            }
+            Self::Hexagonal { origin, size } => {
+                let layout = HexLayout::new(HexOrientation::Pointy, size).with_origin(origin);
+                let first = layout.world_to_hex(min).to_offset(HexOrientation::Pointy);
+                let last = layout.world_to_hex(max).to_offset(HexOrientation::Pointy);
+
+                for row in first.1 - 1..=last.1 + 1 {
+                    for column in first.0 - 1..=last.0 + 1 {
+                        let hex = Hex::from_offset(column, row, HexOrientation::Pointy);
+                        let outline = layout.polygon(hex);
+                        let inside = outline
+                            .iter()
+                            .all(|point| point.cmpge(min).all() && point.cmple(max).all());
+                        if inside {
+                            canvas.draw_shape_absolute(outline, Some(stroke), None);
+                        }
+                    }
+                }
+            }
+            Self::Isometric { origin, spacing } => {
+                let step = spacing * 30f32.to_radians().cos();
+                let slope = 30f32.to_radians().tan();
+                let corners = [min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)];
+
+                for i in ((min.x - origin.x) / step).ceil() as i32
+                    ..=((max.x - origin.x) / step).floor() as i32
+                {
+                    let x = origin.x + i as f32 * step;
+                    canvas.draw_line_absolute(
+                        Vec2::new(x, min.y),
+                        Vec2::new(x, max.y),
+                        Some(stroke),
+                        None,
+                    );
+                }
+
+                // Lines at ±30° through the crossings, identified by where they meet the vertical line through the origin.
+                for direction in [slope, -slope] {
+                    let offsets = corners.iter().map(|corner| {
+                        (corner.y - origin.y - (corner.x - origin.x) * direction) / spacing
+                    });
+                    let first = offsets.clone().fold(f32::INFINITY, f32::min).ceil() as i32;
+                    let last = offsets.fold(f32::NEG_INFINITY, f32::max).floor() as i32;
+
+                    for i in first..=last {
+                        let y = |x: f32| origin.y + i as f32 * spacing + (x - origin.x) * direction;
+                        let (start, end) = (Vec2::new(min.x, y(min.x)), Vec2::new(max.x, y(max.x)));
+                        if let Some((start, end)) = clip_segment(start, end, min, max) {
+                            canvas.draw_line_absolute(start, end, Some(stroke), None);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Color, LineEnd};
+
+    /// Verify that points snap to the nearest corner, hex center or crossing, and that guides are drawn inside the area.
+    #[test]
+    fn snap_and_draw_guides() {
+        let rectangular = Grid::rectangular(10.0).with_origin(Vec2::new(5.0, 0.0));
+        assert_eq!(
+            rectangular.snap_to_grid(Vec2::new(13.0, 4.0)),
+            Vec2::new(15.0, 0.0)
+        );
+
+        let hexagonal = Grid::hexagonal(1.0);
+        let neighbour = Vec2::new(3f32.sqrt() / 2.0, 1.5);
+        assert!(hexagonal
+            .snap_to_grid(neighbour + Vec2::new(0.3, -0.4))
+            .abs_diff_eq(neighbour, 1e-5));
+        assert_eq!(hexagonal.snap_to_grid(Vec2::new(0.4, 0.3)), Vec2::ZERO);
+
+        let isometric = Grid::isometric(2.0);
+        assert!(isometric
+            .snap_to_grid(Vec2::new(1.6, 1.2))
+            .abs_diff_eq(Vec2::new(3f32.sqrt(), 1.0), 1e-5));
+
+        let stroke = Stroke::new(Color::black(), 0.05, LineEnd::Butt);
+        for grid in [rectangular, hexagonal, isometric] {
+            let mut canvas = Canvas::new(10);
+            grid.draw_guides(&mut canvas, Vec2::ZERO, Vec2::splat(10.0), stroke);
+
+            let shapes = canvas.as_raw();
+            assert!(!shapes.is_empty());
+            assert!(shapes
+                .iter()
+                .flat_map(|shape| shape.points.iter())
+                .all(|point| {
+                    point.cmpge(Vec2::splat(-1e-4)).all()
+                        && point.cmple(Vec2::splat(10.0 + 1e-4)).all()
+                }));
+        }
+    }
+
+    /// Verify that grids without a positive spacing draw nothing.
+    #[test]
+    fn draw_degenerate_guides() {
+        let stroke = Stroke::new(Color::black(), 0.05, LineEnd::Butt);
+        for grid in [
+            Grid::rectangular(0.0),
+            Grid::Rectangular {
+                origin: Vec2::ZERO,
+                spacing: Vec2::new(1.0, -1.0),
+            },
+            Grid::hexagonal(0.0),
+            Grid::isometric(f32::NAN),
+        ] {
+            let mut canvas = Canvas::new(10);
+            grid.draw_guides(&mut canvas, Vec2::ZERO, Vec2::splat(10.0), stroke);
+            assert!(canvas.as_raw().is_empty());
+        }
+    }
+}
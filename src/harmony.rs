@@ -0,0 +1,84 @@
+use std::f32::consts::PI;
+
+use crate::Color;
+
+/// Companion colors for palette design, found by turning the hue in Oklch so every companion looks as bright and as saturated as the original.
+///
+/// Companions outside of sRGB are clamped, so very saturated colors can drift slightly.
+impl Color {
+    /// The same color with its hue turned counter-clockwise by `radians` around the Oklab color wheel, keeping alpha.
+    pub fn rotate_hue(&self, radians: f32) -> Color {
+        let [lightness, a, b] = self.to_oklab();
+        let (sin, cos) = radians.sin_cos();
+
+        Color::from_oklab(lightness, a * cos - b * sin, a * sin + b * cos).with_a(self.a())
+    }
+
+    /// The color opposite on the color wheel.
+    pub fn complementary(&self) -> Color {
+        self.rotate_hue(PI)
+    }
+
+    /// The two colors a third of the way around the color wheel either side.
+    pub fn triadic(&self) -> [Color; 2] {
+        [
+            self.rotate_hue(2.0 * PI / 3.0),
+            self.rotate_hue(-2.0 * PI / 3.0),
+        ]
+    }
+
+    /// The two neighbouring colors, `spread` radians around the color wheel either side.
+    ///
+    /// Around `PI / 6.0` gives a calm palette.
+    pub fn analogous(&self, spread: f32) -> [Color; 2] {
+        [self.rotate_hue(spread), self.rotate_hue(-spread)]
+    }
+
+    /// The two colors either side of the complementary color, `spread` radians away from it.
+    pub fn split_complementary(&self, spread: f32) -> [Color; 2] {
+        [self.rotate_hue(PI - spread), self.rotate_hue(PI + spread)]
+    }
+
+    /// The three colors that make a square with this one on the color wheel.
+    pub fn tetradic(&self) -> [Color; 3] {
+        [
+            self.rotate_hue(PI / 2.0),
+            self.rotate_hue(PI),
+            self.rotate_hue(3.0 * PI / 2.0),
+        ]
+    }
+
+    /// The color in `palette` that looks closest to this one, by [perceptual distance](Self::perceptual_distance).
+    ///
+    /// Returns `None` if the palette is empty.
+    pub fn nearest_in(&self, palette: &[Color]) -> Option<Color> {
+        palette.iter().copied().min_by(|a, b| {
+            self.perceptual_distance(a)
+                .total_cmp(&self.perceptual_distance(b))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that companions keep their lightness, and that snapping picks the closest palette color.
+    #[test]
+    fn harmonies() {
+        let color = Color::new(0.2, 0.5, 0.7, 0.5);
+        let [lightness, ..] = color.to_oklab();
+
+        for companion in color.triadic().into_iter().chain(color.tetradic()) {
+            assert!((companion.to_oklab()[0] - lightness).abs() < 0.02);
+            assert_eq!(companion.a(), 0.5);
+        }
+
+        let round_trip = color.complementary().complementary();
+        assert!(round_trip.perceptual_distance(&color) < 1e-3);
+
+        let palette = [Color::black(), Color::white(), Color::blue()];
+        assert_eq!(color.nearest_in(&palette), Some(Color::blue()));
+        assert_eq!(color.nearest_in(&[]), None);
+    }
+}
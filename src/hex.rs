@@ -0,0 +1,225 @@
+use glam::{Mat2, Vec2};
+
+/// A cell of a hexagonal grid, in axial coordinates.
+///
+/// Axial coordinates are two of the three cube coordinates `q + r + s = 0`, which keep distances and directions simple.
+/// Use [HexLayout] to find where a cell is drawn, and [Hex::to_offset] to convert to the row and column of a rectangular map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hex {
+    /// The column-like axis.
+    pub q: i32,
+    /// The row-like axis.
+    pub r: i32,
+}
+
+impl Hex {
+    /// The six directions to neighbouring cells, counter-clockwise starting from `+q`.
+    pub const DIRECTIONS: [Hex; 6] = [
+        Hex { q: 1, r: 0 },
+        Hex { q: 1, r: -1 },
+        Hex { q: 0, r: -1 },
+        Hex { q: -1, r: 0 },
+        Hex { q: -1, r: 1 },
+        Hex { q: 0, r: 1 },
+    ];
+
+    /// Create a new [Hex] from axial coordinates.
+    pub fn new(q: i32, r: i32) -> Self {
+        Self { q, r }
+    }
+
+    /// The third cube coordinate, so that `q + r + s = 0`.
+    pub fn s(&self) -> i32 {
+        -self.q - self.r
+    }
+
+    /// The neighbouring cell in one of the six [DIRECTIONS](Self::DIRECTIONS), wrapping around past 5.
+    pub fn neighbor(&self, direction: usize) -> Hex {
+        let step = Self::DIRECTIONS[direction % 6];
+        Hex::new(self.q + step.q, self.r + step.r)
+    }
+
+    /// All six neighbouring cells, counter-clockwise starting from `+q`.
+    pub fn neighbors(&self) -> [Hex; 6] {
+        [0, 1, 2, 3, 4, 5].map(|direction| self.neighbor(direction))
+    }
+
+    /// The number of steps between two cells.
+    pub fn distance(&self, other: Hex) -> i32 {
+        let (dq, dr) = (self.q - other.q, self.r - other.r);
+        (dq.abs() + dr.abs() + (dq + dr).abs()) / 2
+    }
+
+    /// Every cell exactly `radius` steps away, going once around counter-clockwise. A radius of zero gives just this cell.
+    pub fn ring(&self, radius: u32) -> impl Iterator<Item = Hex> {
+        let radius = radius as i32;
+        let start = Hex::new(self.q - radius, self.r + radius);
+        let sides = if radius == 0 { 1 } else { 6 };
+
+        (0..sides)
+            .flat_map(move |side| (0..radius.max(1)).map(move |step| (side, step)))
+            .scan(start, |cell, (side, _)| {
+                let current = *cell;
+                *cell = cell.neighbor(side);
+                Some(current)
+            })
+    }
+
+    /// Every cell within `radius` steps, spiralling out ring by ring from this one.
+    pub fn range(&self, radius: u32) -> impl Iterator<Item = Hex> {
+        let center = *self;
+        (0..=radius).flat_map(move |ring| center.ring(ring))
+    }
+
+    /// The column and row of this cell in a rectangular map, where every other row (for pointy-topped cells)
+    /// or column (for flat-topped cells) is shoved half a cell over.
+    pub fn to_offset(&self, orientation: HexOrientation) -> (i32, i32) {
+        match orientation {
+            HexOrientation::Pointy => (self.q + (self.r - (self.r & 1)) / 2, self.r),
+            HexOrientation::Flat => (self.q, self.r + (self.q - (self.q & 1)) / 2),
+        }
+    }
+
+    /// The cell at a column and row of a rectangular map. The inverse of [to_offset](Self::to_offset).
+    pub fn from_offset(column: i32, row: i32, orientation: HexOrientation) -> Self {
+        match orientation {
+            HexOrientation::Pointy => Hex::new(column - (row - (row & 1)) / 2, row),
+            HexOrientation::Flat => Hex::new(column, row - (column - (column & 1)) / 2),
+        }
+    }
+
+    /// Round fractional axial coordinates to the cell containing them.
+    fn round(q: f32, r: f32) -> Self {
+        let s = -q - r;
+        let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+        let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+
+        // Rounding each coordinate separately can break q + r + s = 0, so recompute whichever was furthest off.
+        if dq > dr && dq > ds {
+            rq = -rr - rs;
+        } else if dr > ds {
+            rr = -rq - rs;
+        }
+
+        Hex::new(rq as i32, rr as i32)
+    }
+}
+
+/// Which way up the cells of a hexagonal grid are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HexOrientation {
+    /// A corner at the top of each cell, so cells form horizontal rows.
+    Pointy,
+    /// A flat edge at the top of each cell, so cells form vertical columns.
+    Flat,
+}
+
+/// How the cells of a hexagonal grid map onto the canvas.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HexLayout {
+    /// Which way up the cells are drawn.
+    pub orientation: HexOrientation,
+    /// Distance from the center of a cell to each of its corners.
+    pub size: f32,
+    /// Where the center of the cell at `(0, 0)` is drawn.
+    pub origin: Vec2,
+}
+
+impl HexLayout {
+    /// Create a new [HexLayout] of cells `size` from center to corner, centered on the origin.
+    pub fn new(orientation: HexOrientation, size: f32) -> Self {
+        Self {
+            orientation,
+            size,
+            origin: Vec2::ZERO,
+        }
+    }
+
+    /// Move the grid so that the cell at `(0, 0)` is centered on `origin`.
+    pub fn with_origin(mut self, origin: Vec2) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// The center of a cell on the canvas.
+    pub fn hex_to_world(&self, hex: Hex) -> Vec2 {
+        self.origin + self.basis() * Vec2::new(hex.q as f32, hex.r as f32)
+    }
+
+    /// The cell containing a point on the canvas.
+    pub fn world_to_hex(&self, point: Vec2) -> Hex {
+        let axial = self.basis().inverse() * (point - self.origin);
+        Hex::round(axial.x, axial.y)
+    }
+
+    /// The six corners of a cell, counter-clockwise.
+    pub fn corners(&self, hex: Hex) -> [Vec2; 6] {
+        let center = self.hex_to_world(hex);
+        let start = match self.orientation {
+            HexOrientation::Pointy => 30f32,
+            HexOrientation::Flat => 0.0,
+        };
+
+        [0, 1, 2, 3, 4, 5].map(|i| {
+            center + Mat2::from_angle((start + 60.0 * i as f32).to_radians()) * Vec2::X * self.size
+        })
+    }
+
+    /// The outline of a cell as a closed polygon, ready to pass to [Canvas::draw_shape](crate::Canvas::draw_shape).
+    pub fn polygon(&self, hex: Hex) -> Vec<Vec2> {
+        let corners = self.corners(hex);
+        corners.iter().chain(&corners[..1]).copied().collect()
+    }
+
+    /// The two vectors from a cell's center to its `+q` and `+r` neighbours, as the columns of a matrix.
+    fn basis(&self) -> Mat2 {
+        let root = 3f32.sqrt();
+        let basis = match self.orientation {
+            HexOrientation::Pointy => {
+                Mat2::from_cols(Vec2::new(root, 0.0), Vec2::new(root / 2.0, 1.5))
+            }
+            HexOrientation::Flat => {
+                Mat2::from_cols(Vec2::new(1.5, root / 2.0), Vec2::new(0.0, root))
+            }
+        };
+        basis * self.size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify ring and range sizes, offset round trips, and that points map back to the cell they were drawn in.
+    #[test]
+    fn hex_coordinates() {
+        let center = Hex::new(2, -1);
+        assert_eq!(center.ring(0).collect::<Vec<_>>(), vec![center]);
+
+        let ring: Vec<Hex> = center.ring(2).collect();
+        assert_eq!(ring.len(), 12);
+        assert!(ring.iter().all(|hex| hex.distance(center) == 2));
+        assert_eq!(center.range(2).count(), 19);
+
+        for orientation in [HexOrientation::Pointy, HexOrientation::Flat] {
+            let layout = HexLayout::new(orientation, 2.0).with_origin(Vec2::new(5.0, 3.0));
+
+            for hex in center.range(3) {
+                let (column, row) = hex.to_offset(orientation);
+                assert_eq!(Hex::from_offset(column, row, orientation), hex);
+
+                let middle = layout.hex_to_world(hex);
+                assert_eq!(layout.world_to_hex(middle + Vec2::new(0.8, -0.9)), hex);
+
+                let polygon = layout.polygon(hex);
+                assert_eq!(polygon.len(), 7);
+                assert!(polygon
+                    .iter()
+                    .all(|corner| (corner.distance(middle) - 2.0).abs() < 1e-4));
+            }
+        }
+    }
+}
@@ -0,0 +1,366 @@
+use glam::Vec2;
+
+use crate::{geometry::annular_sector, graph::Tree};
+
+/// A rectangle [Treemap] gave to a node.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cell {
+    /// The node of the tree this is for.
+    pub node: usize,
+    /// How many ancestors the node has.
+    pub depth: usize,
+    /// The bottom left corner.
+    pub min: Vec2,
+    /// The top right corner.
+    pub max: Vec2,
+}
+
+impl Cell {
+    /// The center of the rectangle, where a label would go.
+    pub fn center(&self) -> Vec2 {
+        (self.min + self.max) / 2.0
+    }
+
+    /// The width and height of the rectangle.
+    pub fn size(&self) -> Vec2 {
+        self.max - self.min
+    }
+
+    /// The closed outline of the rectangle.
+    pub fn points(&self) -> Vec<Vec2> {
+        vec![
+            self.min,
+            Vec2::new(self.max.x, self.min.y),
+            self.max,
+            Vec2::new(self.min.x, self.max.y),
+            self.min,
+        ]
+    }
+}
+
+/// A squarified treemap, which shows a [Tree] as nested rectangles with areas in proportion to their values.
+///
+/// Siblings are laid out in rows, largest first, choosing each row to keep the rectangles as close to square as it can, after Bruls, Huizing and van Wijk.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Treemap {
+    /// The bottom left corner of the whole map.
+    pub min: Vec2,
+    /// The top right corner of the whole map.
+    pub max: Vec2,
+    /// Space left between a node's edges and its children, so that every level of the tree stays visible.
+    pub padding: f32,
+}
+
+impl Treemap {
+    /// Create a new [Treemap] filling the rectangle from `min` to `max`, without padding.
+    pub fn new(min: Vec2, max: Vec2) -> Self {
+        Self {
+            min,
+            max,
+            padding: 0.0,
+        }
+    }
+
+    /// Set the padding, consuming the parent.
+    pub fn with_padding(mut self, padding: f32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Lay out `tree`, where each node counts for its own entry in `values` plus everything below it.
+    ///
+    /// Returns a cell for every node with a positive total, parents before their children, so they can be drawn in order.
+    pub fn layout(&self, tree: &Tree, values: &[f32]) -> Vec<Cell> {
+        let (children, _) = tree.children();
+        let totals = totals(&children, values);
+        let root = children.len() - 1;
+
+        let mut cells = Vec::new();
+        let mut stack = vec![(root, 0, self.min, self.max)];
+        while let Some((node, depth, min, max)) = stack.pop() {
+            let (min, max) = if node == root {
+                (min, max)
+            } else {
+                cells.push(Cell {
+                    node,
+                    depth,
+                    min,
+                    max,
+                });
+                let inset = Vec2::splat(self.padding).min((max - min) / 2.0);
+                (min + inset, max - inset)
+            };
+
+            let mut items: Vec<(usize, f32)> = children[node]
+                .iter()
+                .map(|&child| (child, totals[child]))
+                .filter(|(_, total)| *total > 0.0)
+                .collect();
+            items.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+            let depth = if node == root { 0 } else { depth + 1 };
+            for (child, min, max) in squarify(&items, totals[node], min, max).into_iter().rev() {
+                stack.push((child, depth, min, max));
+            }
+        }
+        cells
+    }
+}
+
+/// Lay out `items`, sorted largest first, as rows within the rectangle from `min` to `max`, which stands for `total`.
+fn squarify(items: &[(usize, f32)], total: f32, min: Vec2, max: Vec2) -> Vec<(usize, Vec2, Vec2)> {
+    let mut placed = Vec::with_capacity(items.len());
+    if total <= 0.0 {
+        return placed;
+    }
+    let scale = (max - min).x * (max - min).y / total;
+    let (mut min, mut max) = (min, max);
+
+    let mut rest = items;
+    while !rest.is_empty() {
+        let size = max - min;
+        let side = size.x.min(size.y);
+
+        // The worst aspect ratio of a row of items summing to `sum`, between `smallest` and `largest`.
+        let worst = |sum: f32, smallest: f32, largest: f32| {
+            let (area, side) = (sum * scale, side * side);
+            if area <= 0.0 || smallest <= 0.0 {
+                return f32::INFINITY;
+            }
+            (side * largest * scale / (area * area)).max(area * area / (side * smallest * scale))
+        };
+
+        let mut count = 1;
+        let mut sum = rest[0].1;
+        while count < rest.len() {
+            let next = sum + rest[count].1;
+            if worst(next, rest[count].1, rest[0].1) > worst(sum, rest[count - 1].1, rest[0].1) {
+                break;
+            }
+            sum = next;
+            count += 1;
+        }
+
+        let (row, remaining) = rest.split_at(count);
+        let thickness = if side > 0.0 { sum * scale / side } else { 0.0 };
+        if size.x >= size.y {
+            // A column down the left side.
+            let mut top = max.y;
+            for &(item, value) in row {
+                let height = if sum > 0.0 { size.y * value / sum } else { 0.0 };
+                placed.push((
+                    item,
+                    Vec2::new(min.x, top - height),
+                    Vec2::new(min.x + thickness, top),
+                ));
+                top -= height;
+            }
+            min.x = (min.x + thickness).min(max.x);
+        } else {
+            // A row along the top.
+            let mut left = min.x;
+            for &(item, value) in row {
+                let width = if sum > 0.0 { size.x * value / sum } else { 0.0 };
+                placed.push((
+                    item,
+                    Vec2::new(left, max.y - thickness),
+                    Vec2::new(left + width, max.y),
+                ));
+                left += width;
+            }
+            max.y = (max.y - thickness).max(min.y);
+        }
+        rest = remaining;
+    }
+    placed
+}
+
+/// A ring segment [Sunburst] gave to a node.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sector {
+    /// The node of the tree this is for.
+    pub node: usize,
+    /// How many ancestors the node has, which is also its ring, counting out from the middle.
+    pub depth: usize,
+    /// The center of the sunburst.
+    pub center: Vec2,
+    /// Distance from the center to the inner edge.
+    pub inner_radius: f32,
+    /// Distance from the center to the outer edge.
+    pub outer_radius: f32,
+    /// The angle the sector starts at, in radians counter-clockwise from the x axis.
+    pub start_angle: f32,
+    /// The angle the sector ends at.
+    pub end_angle: f32,
+}
+
+impl Sector {
+    /// The point midway through the sector, both around and across, where a label would go.
+    pub fn middle(&self) -> Vec2 {
+        let angle = (self.start_angle + self.end_angle) / 2.0;
+        let radius = (self.inner_radius + self.outer_radius) / 2.0;
+        self.center + Vec2::new(angle.cos(), angle.sin()) * radius
+    }
+
+    /// The closed outline of the sector.
+    pub fn points(&self) -> Vec<Vec2> {
+        annular_sector(
+            self.center,
+            self.inner_radius,
+            self.outer_radius,
+            self.start_angle,
+            self.end_angle,
+        )
+    }
+}
+
+/// A sunburst, or radial partition, which shows a [Tree] as rings: roots on the innermost ring, and each child
+/// further out, spanning its share of its parent's angle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sunburst {
+    /// The center of the rings.
+    pub center: Vec2,
+    /// Radius of the hole in the middle. With no hole, the roots are pie slices.
+    pub inner_radius: f32,
+    /// How wide each ring is.
+    pub ring_width: f32,
+    /// The angle the first root starts at, in radians counter-clockwise from the x axis.
+    pub start_angle: f32,
+}
+
+impl Sunburst {
+    /// Create a new [Sunburst], starting from the top and going counter-clockwise.
+    pub fn new(center: Vec2, inner_radius: f32, ring_width: f32) -> Self {
+        Self {
+            center,
+            inner_radius,
+            ring_width,
+            start_angle: std::f32::consts::FRAC_PI_2,
+        }
+    }
+
+    /// Lay out `tree`, where each node counts for its own entry in `values` plus everything below it.
+    ///
+    /// Returns a sector for every node with a positive total, parents before their children.
+    /// A node's own value is left as an empty gap beyond its children.
+    pub fn layout(&self, tree: &Tree, values: &[f32]) -> Vec<Sector> {
+        let (children, _) = tree.children();
+        let totals = totals(&children, values);
+        let root = children.len() - 1;
+        let scale = if totals[root] > 0.0 {
+            std::f32::consts::TAU / totals[root]
+        } else {
+            0.0
+        };
+
+        let mut sectors = Vec::new();
+        let mut stack = vec![(root, 0, self.start_angle)];
+        while let Some((node, depth, start)) = stack.pop() {
+            let depth = if node == root {
+                0
+            } else {
+                let inner_radius = self.inner_radius + self.ring_width * depth as f32;
+                sectors.push(Sector {
+                    node,
+                    depth,
+                    center: self.center,
+                    inner_radius,
+                    outer_radius: inner_radius + self.ring_width,
+                    start_angle: start,
+                    end_angle: start + totals[node] * scale,
+                });
+                depth + 1
+            };
+
+            let mut angle = start;
+            let mut next = Vec::new();
+            for &child in &children[node] {
+                if totals[child] > 0.0 {
+                    next.push((child, depth, angle));
+                    angle += totals[child] * scale;
+                }
+            }
+            stack.extend(next.into_iter().rev());
+        }
+        sectors
+    }
+}
+
+/// The total of each node and everything below it, with the extra root of [Tree::children] at the end. Negative values count as zero.
+fn totals(children: &[Vec<usize>], values: &[f32]) -> Vec<f32> {
+    let root = children.len() - 1;
+    let mut totals: Vec<f32> = (0..children.len())
+        .map(|node| {
+            if node == root {
+                0.0
+            } else {
+                values.get(node).copied().unwrap_or(0.0).max(0.0)
+            }
+        })
+        .collect();
+
+    // Visit every node before its parent by reversing a depth-first order.
+    let mut order = vec![root];
+    let mut index = 0;
+    while index < order.len() {
+        order.extend(&children[order[index]]);
+        index += 1;
+    }
+    for &node in order.iter().rev() {
+        let sum: f32 = children[node].iter().map(|&child| totals[child]).sum();
+        totals[node] += sum;
+    }
+    totals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that treemap cells and sunburst sectors are sized by value and nest inside their parents.
+    #[test]
+    fn treemap_and_sunburst() {
+        // One root with three children, the last of which has two leaves.
+        let tree = Tree::new(vec![None, Some(0), Some(0), Some(0), Some(3), Some(3)]);
+        let values = [0.0, 6.0, 2.0, 0.0, 3.0, 1.0];
+
+        let cells = Treemap::new(Vec2::ZERO, Vec2::new(6.0, 2.0)).layout(&tree, &values);
+        assert_eq!(cells.len(), 6);
+        assert_eq!(cells[0].node, 0);
+        let area = |node: usize| {
+            let cell = cells.iter().find(|cell| cell.node == node).unwrap();
+            cell.size().x * cell.size().y
+        };
+        for (node, value) in values.iter().enumerate().skip(1) {
+            if *value > 0.0 {
+                assert!((area(node) - value).abs() < 1e-4);
+            }
+        }
+        assert!((area(3) - 4.0).abs() < 1e-4);
+
+        let padded = Treemap::new(Vec2::ZERO, Vec2::new(6.0, 2.0))
+            .with_padding(0.1)
+            .layout(&tree, &values);
+        let parent = padded.iter().find(|cell| cell.node == 3).unwrap();
+        for leaf in padded.iter().filter(|cell| cell.node >= 4) {
+            assert_eq!(leaf.depth, 2);
+            assert!(leaf.min.cmpge(parent.min + 0.1 - 1e-4).all());
+            assert!(leaf.max.cmple(parent.max - 0.1 + 1e-4).all());
+        }
+
+        let sectors = Sunburst::new(Vec2::ZERO, 1.0, 1.0).layout(&tree, &values);
+        assert_eq!(sectors.len(), 6);
+        let span = |sector: &Sector| sector.end_angle - sector.start_angle;
+        assert!((span(&sectors[0]) - std::f32::consts::TAU).abs() < 1e-4);
+        let parent = sectors.iter().find(|sector| sector.node == 3).unwrap();
+        let leaves: Vec<&Sector> = sectors.iter().filter(|sector| sector.node >= 4).collect();
+        assert!((leaves[0].start_angle - parent.start_angle).abs() < 1e-4);
+        assert!((leaves[1].end_angle - parent.end_angle).abs() < 1e-4);
+        assert!((span(leaves[0]) - 3.0 * span(leaves[1])).abs() < 1e-4);
+        assert_eq!(leaves[0].inner_radius, 3.0);
+    }
+}
@@ -0,0 +1,165 @@
+use glam::Vec2;
+
+/// A label to place next to a point, such as the name of a city on a map or a point in a scatterplot.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Label {
+    /// The point being labeled.
+    pub anchor: Vec2,
+    /// Width and height of the label's box, as measured from its text.
+    pub size: Vec2,
+}
+
+impl Label {
+    /// Create a new [Label] of `size` for the point at `anchor`.
+    pub fn new(anchor: Vec2, size: Vec2) -> Self {
+        Self { anchor, size }
+    }
+}
+
+/// Where [LabelPlacement] put a [Label].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlacedLabel {
+    /// The bottom left corner of the label's box.
+    pub min: Vec2,
+    /// The top right corner of the label's box.
+    pub max: Vec2,
+    /// A line from the anchor to the nearest point on the box, if the label had to be moved away from its anchor.
+    pub leader: Option<(Vec2, Vec2)>,
+}
+
+impl PlacedLabel {
+    /// The center of the label's box.
+    pub fn center(&self) -> Vec2 {
+        (self.min + self.max) / 2.0
+    }
+
+    /// Checks if the label's box overlaps another box, leaving `padding` between them.
+    fn overlaps(&self, min: Vec2, max: Vec2, padding: f32) -> bool {
+        self.min.cmplt(max + padding).all() && self.max.cmpgt(min - padding).all()
+    }
+}
+
+/// Greedy placement of labels next to their anchors without overlaps.
+///
+/// Labels are placed in order, so put the most important first. Each tries the eight positions around its anchor, starting top right,
+/// then rings of positions further and further away, connected back to the anchor with a leader line.
+/// Labels never cover each other or any anchor. A label that fits nowhere is left out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LabelPlacement {
+    /// Space between a label and its anchor, and between neighbouring labels.
+    pub gap: f32,
+    /// How far a label may be moved from its anchor, measured from the nearest edge of its box.
+    pub max_distance: f32,
+    /// How many rings of positions to try between [gap](Self::gap) and [max_distance](Self::max_distance).
+    pub rings: usize,
+}
+
+impl LabelPlacement {
+    /// Create a new [LabelPlacement], trying four rings of positions out to `max_distance`.
+    pub fn new(gap: f32, max_distance: f32) -> Self {
+        Self {
+            gap,
+            max_distance,
+            rings: 4,
+        }
+    }
+
+    /// Place every label, returning where each went in the same order as `labels`.
+    pub fn place(&self, labels: &[Label]) -> Vec<Option<PlacedLabel>> {
+        let mut placed: Vec<Option<PlacedLabel>> = Vec::with_capacity(labels.len());
+
+        for label in labels {
+            let position = self.candidates(label).find(|candidate| {
+                let covers_anchor = labels
+                    .iter()
+                    .any(|other| candidate.overlaps(other.anchor, other.anchor, self.gap / 2.0));
+                let covers_label = placed
+                    .iter()
+                    .flatten()
+                    .any(|other| candidate.overlaps(other.min, other.max, self.gap));
+
+                !covers_anchor && !covers_label
+            });
+
+            placed.push(position);
+        }
+
+        placed
+    }
+
+    /// Every position to try for a label, in order of preference.
+    fn candidates<'a>(&'a self, label: &'a Label) -> impl Iterator<Item = PlacedLabel> + 'a {
+        // Directions scaled so their largest component is 1, which keeps the box clear of the anchor along that axis.
+        let preferred = [
+            Vec2::new(1.0, 1.0),
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(-1.0, -1.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(0.0, 1.0),
+            Vec2::new(-1.0, 0.0),
+            Vec2::new(0.0, -1.0),
+        ];
+        const RING_DIRECTIONS: usize = 16;
+
+        let nearby = preferred
+            .into_iter()
+            .map(move |direction| (direction, self.gap));
+        let rings = (1..=self.rings).flat_map(move |ring| {
+            let distance =
+                self.gap + (self.max_distance - self.gap) * ring as f32 / self.rings as f32;
+
+            (0..RING_DIRECTIONS).map(move |i| {
+                let angle = std::f32::consts::TAU * i as f32 / RING_DIRECTIONS as f32;
+                let direction = Vec2::new(angle.cos(), angle.sin());
+                (direction / direction.abs().max_element(), distance)
+            })
+        });
+
+        nearby.chain(rings).map(move |(direction, distance)| {
+            let half = label.size / 2.0;
+            let center = label.anchor + direction * (Vec2::splat(distance) + half);
+            let (min, max) = (center - half, center + half);
+
+            let leader = (distance > self.gap).then(|| {
+                let nearest = label.anchor.clamp(min, max);
+                (label.anchor, nearest)
+            });
+
+            PlacedLabel { min, max, leader }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that crowded labels spread out without overlapping, and that displaced labels get leader lines.
+    #[test]
+    fn place_crowded_labels() {
+        let size = Vec2::new(1.0, 0.5);
+        let labels: Vec<Label> = (0..12)
+            .map(|i| Label::new(Vec2::new(i as f32 * 0.2, 0.0), size))
+            .collect();
+
+        let placement = LabelPlacement::new(0.1, 3.0);
+        let placed = placement.place(&labels);
+
+        let first = placed[0].unwrap();
+        assert!(first.min.abs_diff_eq(Vec2::splat(0.1), 1e-6));
+        assert!(first.leader.is_none());
+
+        let placed: Vec<PlacedLabel> = placed.into_iter().flatten().collect();
+        assert!(placed.len() > 6);
+        assert!(placed.iter().any(|label| label.leader.is_some()));
+        for (i, a) in placed.iter().enumerate() {
+            for b in &placed[i + 1..] {
+                assert!(!a.overlaps(b.min, b.max, 0.0));
+            }
+            for label in &labels {
+                assert!(!a.overlaps(label.anchor, label.anchor, 0.0));
+            }
+        }
+    }
+}
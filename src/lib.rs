@@ -7,9 +7,94 @@
 #![deny(warnings)]
 #![deny(missing_docs)]
 
+mod brush;
 mod canvas;
 mod color;
+mod color_profile;
+mod custom_shape;
+mod diff;
+mod error;
+mod field;
+#[cfg(feature = "geo_types")]
+mod geo_interop;
+mod gradient;
+mod harmony;
+mod lod;
+mod math;
 mod path_builder;
+mod point;
+mod recording;
+mod rng;
+mod separation;
+mod shape_builder;
+mod spatial;
+mod stats;
+mod symmetry;
+mod theme;
+mod validation;
+mod vision;
+
+/// Agent simulations, such as slime molds, that leave trails to turn into shapes or images.
+pub mod agents;
+/// Pseudo-3D drawing with isometric, dimetric and oblique projections, for boxes, prisms and other "2.5D" art.
+pub mod axonometric;
+/// Chart building blocks: scales, axes, legends and colorbars, annotations such as callouts, braces and highlight bands, series such as histograms, density curves, box plots, violin plots and candlesticks, polar and radar charts, choropleth colors, and facet grids of small multiples.
+pub mod chart;
+/// QR codes and barcodes, as filled shapes ready to draw or plot.
+pub mod codes;
+/// Flowcharts and other diagrams of text boxes and arrows, laid out and routed automatically.
+pub mod diagram;
+/// Dimensions for technical drawings, with extension lines, arrowheads and formatted measurements.
+pub mod dimension;
+/// Sankey and chord diagrams, with ribbons as thick as the flows they show.
+pub mod flow;
+/// Drawing GeoJSON documents, such as maps, onto a [Canvas].
+///
+/// Longitude/latitude coordinates are flattened with a [Projection](projection::Projection).
+/// Requires the `geojson` feature.
+#[cfg(feature = "geojson")]
+pub mod geojson;
+/// Geometric operations on points and shapes.
+///
+/// These work on plain lists of points, so they can be used before drawing onto a [Canvas], or on shapes taken back out of one.
+pub mod geometry;
+/// Laying out and drawing node and edge graphs and trees, such as dependency diagrams and org charts.
+pub mod graph;
+/// Differential growth of curves into organic, folded forms.
+pub mod growth;
+/// Rectangular, hexagonal and isometric grids for laying out compositions, with snapping and guide lines.
+pub mod guides;
+/// Coordinates, layout and outlines for hexagonal grids, such as hex maps and board-game diagrams.
+pub mod hex;
+/// Treemaps and sunbursts, which show the values in a [Tree](graph::Tree) as nested rectangles or rings.
+pub mod hierarchy;
+/// Placing labels next to the points they describe, without overlapping each other.
+pub mod labels;
+/// Generating mazes on square and hexagonal grids, as walls ready to plot.
+pub mod maze;
+/// 3D meshes drawn through a camera as line art, with hidden lines removed, such as for plotting.
+pub mod mesh;
+/// Packing circles and rectangles into a space without overlaps.
+pub mod packing;
+/// Effects applied to a finished raster image, such as vignettes, film grain, and bloom.
+///
+/// Chain effects together with a [PostProcess](post::PostProcess), and run it over the output of a [SkiaRenderer](renderers::SkiaRenderer).
+/// Requires the `tiny_skia_renderer` feature.
+#[cfg(feature = "tiny_skia_renderer")]
+pub mod post;
+/// Parametric shapes common in icons and mechanical sketches, such as squircles, gears and capsules.
+///
+/// Each is a [CustomShape], drawn with [Canvas::draw_custom_shape].
+pub mod primitives;
+/// Ways of flattening longitude/latitude coordinates onto a [Canvas].
+pub mod projection;
+/// Turning raster images into vector shapes, by tracing their outlines or rebuilding their tones from dots and lines.
+///
+/// Requires the `tiny_skia_renderer` feature.
+#[cfg(feature = "tiny_skia_renderer")]
+pub mod raster;
+/// Gray-Scott reaction-diffusion, growing organic patterns to trace into shapes.
+pub mod reaction;
 /**
  * A collection of backend renderers
  *
@@ -19,9 +104,40 @@ mod path_builder;
  * This module contains several basic renderers for everyday use. They also serve as referance if you want to implement your own renderer.
  */
 pub mod renderers;
+/// Routing connectors between shapes around obstacles, for node-and-wire diagrams.
+pub mod routing;
+/// Helpers for writing rendering regression tests.
+///
+/// Render a figure, then compare it against a known-good "golden" image with [assert_matches_golden](testing::assert_matches_golden).
+/// Requires the `test_utils` feature.
+#[cfg(feature = "test_utils")]
+pub mod testing;
+/// Filling grids with Truchet and Wang tiles that match along their edges.
+pub mod tiling;
+/// Word clouds, placing words sized by weight along a spiral without overlapping.
+pub mod word_cloud;
 
-pub use canvas::{Canvas, LineEnd, Renderer, Shape, Stroke};
-pub use color::Color;
-pub use glam::{Mat2, UVec2, Vec2};
+pub use brush::Brush;
+pub use canvas::{
+    BlendMode, Canvas, FillRule, LineEnd, Renderer, Shape, ShapeKind, Stroke, StrokeAlignment,
+    Style,
+};
+pub use color::{CmykProfile, Color};
+pub use color_profile::ColorProfile;
+pub use custom_shape::{CustomGeometry, CustomShape};
+pub use diff::CanvasDiff;
+pub use error::Error;
+pub use field::ScalarField;
+pub use glam::{Affine2, DVec2, Mat2, Mat3, UVec2, Vec2, Vec3};
+pub use gradient::{Gradient, GradientFill};
 pub use image::RgbaImage;
 pub use path_builder::PathBuilder;
+pub use point::{IntoPoint, IntoPoints, LocalFrame};
+pub use recording::{DrawCall, Reveal};
+pub use separation::{Ink, Plate, Separation};
+pub use shape_builder::ShapeBuilder;
+pub use stats::RenderStats;
+pub use symmetry::{Symmetry, Wallpaper, WallpaperGroup};
+pub use theme::{AxisStyle, Font, Theme};
+pub use validation::{IssueKind, ValidationIssue};
+pub use vision::ColorVisionDeficiency;
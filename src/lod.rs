@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use glam::Vec2;
+
+use crate::{geometry::simplify_polyline, Color, Shape};
+
+/// Shapes smaller than a pixel that fall in the same quadtree cell.
+struct Cluster {
+    /// Index in the output of the shape standing in for the cluster.
+    index: usize,
+    min: Vec2,
+    max: Vec2,
+    color: [f32; 4],
+    count: usize,
+}
+
+/// Merge shapes smaller than `pixel` into one rectangle per cell of a grid `pixel` wide, and simplify the rest to within half a pixel.
+///
+/// The rectangle of each cell covers every shape merged into it, in their average color, and is drawn where the first of them was.
+pub(crate) fn decimate(shapes: &[Shape], pixel: f32) -> Vec<Shape> {
+    let mut output: Vec<Shape> = Vec::new();
+    let mut clusters: HashMap<(i64, i64), Cluster> = HashMap::new();
+
+    for shape in shapes {
+        let shape: &Shape = &shape.flattened();
+        let (min, max) = match shape.bounds() {
+            Some(bounds) if shape.is_drawable() => bounds,
+            _ => continue,
+        };
+        let size = max - min;
+
+        if size.max_element() >= pixel {
+            let mut paths = shape
+                .paths()
+                .map(|path| simplify_polyline(path, pixel * 0.5));
+            output.push(Shape {
+                points: paths.next().unwrap_or_default(),
+                subpaths: paths.collect(),
+                ..shape.clone()
+            });
+            continue;
+        }
+
+        let color = match shape
+            .fill
+            .or_else(|| shape.stroke.map(|stroke| stroke.color))
+        {
+            Some(color) => color,
+            None => continue,
+        };
+
+        let center = (min + max) / 2.0;
+        let cell = (
+            (center.x / pixel).floor() as i64,
+            (center.y / pixel).floor() as i64,
+        );
+        let cluster = clusters.entry(cell).or_insert_with(|| {
+            output.push(Shape::default());
+            Cluster {
+                index: output.len() - 1,
+                min,
+                max,
+                color: [0.0; 4],
+                count: 0,
+            }
+        });
+
+        cluster.min = cluster.min.min(min);
+        cluster.max = cluster.max.max(max);
+        for (sum, channel) in
+            cluster
+                .color
+                .iter_mut()
+                .zip([color.r(), color.g(), color.b(), color.a()])
+        {
+            *sum += channel;
+        }
+        cluster.count += 1;
+    }
+
+    for cluster in clusters.into_values() {
+        // Keep the rectangle at least a pixel wide, so it does not vanish.
+        let center = (cluster.min + cluster.max) / 2.0;
+        let half_size = ((cluster.max - cluster.min) / 2.0).max(Vec2::splat(pixel / 2.0));
+        let (min, max) = (center - half_size, center + half_size);
+
+        let [r, g, b, a] = cluster.color.map(|sum| sum / cluster.count as f32);
+        output[cluster.index] = Shape {
+            points: vec![
+                min,
+                Vec2::new(max.x, min.y),
+                max,
+                Vec2::new(min.x, max.y),
+                min,
+            ],
+            fill: Some(Color::new(r, g, b, a)),
+            ..Default::default()
+        };
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{custom_shape::custom_capsule, Canvas};
+
+    /// Verify that tiny shapes merge into one rectangle per cell, while large shapes are only simplified.
+    #[test]
+    fn merge_tiny_shapes() {
+        let mut canvas = Canvas::default();
+        canvas.draw_circle(Vec2::ZERO, 4.0, None, Some(Color::blue()));
+        for i in 0..10 {
+            let center = Vec2::new(0.1 + i as f32 * 0.05, 0.1);
+            canvas.draw_circle(center, 0.01, None, Some(Color::red()));
+        }
+        canvas.draw_circle(Vec2::new(5.5, 0.5), 0.01, None, Some(Color::green()));
+
+        let levels = canvas.level_of_detail(2, 1.0);
+        let shapes = levels[0].as_raw();
+
+        assert_eq!(shapes.len(), 3);
+        assert!(shapes[0].points.len() < canvas.as_raw()[0].points.len());
+        assert_eq!(shapes[1].fill, Some(Color::red()));
+        assert_eq!(shapes[1].points.len(), 5);
+        assert_eq!(shapes[2].fill, Some(Color::green()));
+        assert_eq!(levels[1].as_raw().len(), 3);
+    }
+
+    /// Verify that custom shapes are flattened before they are simplified.
+    #[test]
+    fn decimate_custom_shape() {
+        let custom = custom_capsule();
+
+        let decimated = decimate(std::slice::from_ref(&custom), 0.5);
+        assert_eq!(decimated, decimate(&[custom.flattened().into_owned()], 0.5));
+        assert!(decimated[0].custom.is_none() && !decimated[0].points.is_empty());
+    }
+}
@@ -0,0 +1,68 @@
+use std::f64::consts::{FRAC_PI_2, PI};
+
+/// Computes the sine and cosine of `x` using only basic arithmetic.
+///
+/// Unlike [f32::sin_cos], which defers to the platform's math library, this produces bit-identical results on every platform.
+/// Used when a [Canvas](crate::Canvas) is [deterministic](crate::Canvas::set_deterministic),
+/// and always by brushes, hatching, halftones and random points in a circle, which are seeded to come out the same everywhere.
+pub(crate) fn portable_sin_cos(x: f32) -> (f32, f32) {
+    let x = x as f64;
+
+    // Reduce into -PI..=PI, then into -PI/4..=PI/4 plus a quadrant.
+    let x = x - (x / (2.0 * PI)).round() * (2.0 * PI);
+    let quadrant = (x / FRAC_PI_2).round();
+    let r = x - quadrant * FRAC_PI_2;
+
+    let (sin, cos) = (taylor_sin(r), taylor_cos(r));
+
+    let (sin, cos) = match quadrant as i64 {
+        0 => (sin, cos),
+        1 => (cos, -sin),
+        -1 => (-cos, sin),
+        _ => (-sin, -cos),
+    };
+
+    (sin as f32, cos as f32)
+}
+
+/// Taylor series of sine, accurate to well below `f32` precision within -PI/4..=PI/4.
+fn taylor_sin(x: f64) -> f64 {
+    let x2 = x * x;
+    let mut term = x;
+    let mut sum = x;
+    for n in 1..=8 {
+        term *= -x2 / ((2 * n) * (2 * n + 1)) as f64;
+        sum += term;
+    }
+    sum
+}
+
+/// Taylor series of cosine, accurate to well below `f32` precision within -PI/4..=PI/4.
+fn taylor_cos(x: f64) -> f64 {
+    let x2 = x * x;
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    for n in 1..=8 {
+        term *= -x2 / ((2 * n - 1) * (2 * n)) as f64;
+        sum += term;
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that the portable implementation agrees with the platform implementation.
+    #[test]
+    fn portable_sin_cos_matches_std() {
+        for i in -2000..2000 {
+            let x = i as f32 * 0.01;
+            let (sin, cos) = portable_sin_cos(x);
+            let (std_sin, std_cos) = (x as f64).sin_cos();
+
+            assert!((sin as f64 - std_sin).abs() < 1e-6, "sin({})", x);
+            assert!((cos as f64 - std_cos).abs() < 1e-6, "cos({})", x);
+        }
+    }
+}
@@ -0,0 +1,435 @@
+use std::collections::HashMap;
+
+use glam::Vec2;
+
+use crate::{
+    hex::{Hex, HexLayout},
+    rng::Rng,
+};
+
+/// The cells a [Maze] is carved out of.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MazeGrid {
+    /// A rectangle of square cells.
+    Rectangular {
+        /// The bottom left corner of the maze.
+        min: Vec2,
+        /// Width and height of each cell.
+        cell_size: f32,
+        /// Number of cells across.
+        columns: usize,
+        /// Number of cells up.
+        rows: usize,
+    },
+    /// A large hexagon of hexagonal cells.
+    Hexagonal {
+        /// Where each cell is drawn. The maze is centered on the cell at `(0, 0)`.
+        layout: HexLayout,
+        /// How many rings of cells surround the center cell.
+        radius: u32,
+    },
+}
+
+/// How a [Maze] is carved, each giving it a different character. Every algorithm produces a perfect maze, with exactly one route between any two cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MazeAlgorithm {
+    /// A random walk that backs up when it gets stuck. Makes long, winding corridors with few dead ends.
+    RecursiveBacktracker,
+    /// Knocks down walls in a random order, unless they separate cells that are already connected. Makes many short dead ends.
+    Kruskal,
+    /// Joins loop-erased random walks together. Picks evenly from every possible maze, so it has no bias towards any texture.
+    Wilson,
+}
+
+/// A maze carved out of a grid of cells, ready to plot.
+///
+/// The same seed always produces the same maze.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Maze {
+    /// The cells to carve the maze out of.
+    pub grid: MazeGrid,
+    /// How to carve it.
+    pub algorithm: MazeAlgorithm,
+    /// Seed for the random choices of the algorithm.
+    pub seed: u64,
+}
+
+/// A wall between a cell and its neighbour, or the edge of the grid.
+struct Wall {
+    cells: (usize, Option<usize>),
+    start: Vec2,
+    end: Vec2,
+}
+
+impl Maze {
+    /// Create a new [Maze].
+    pub fn new(grid: MazeGrid, algorithm: MazeAlgorithm) -> Self {
+        Self {
+            grid,
+            algorithm,
+            seed: 0,
+        }
+    }
+
+    /// The walls left standing, including the outer boundary, joined into as few polylines as possible so that they plot without lifting the pen more than needed.
+    pub fn walls(&self) -> Vec<Vec<Vec2>> {
+        let (_, walls) = self.cells();
+        let open = self.carve(&walls);
+
+        let standing: Vec<(Vec2, Vec2)> = walls
+            .iter()
+            .zip(&open)
+            .filter(|(_, open)| !**open)
+            .map(|(wall, _)| (wall.start, wall.end))
+            .collect();
+
+        chain(&standing)
+    }
+
+    /// The passages between the centers of neighbouring cells that are joined, which draw the maze as a tree of paths instead of walls.
+    pub fn passages(&self) -> Vec<(Vec2, Vec2)> {
+        let (centers, walls) = self.cells();
+        let open = self.carve(&walls);
+
+        walls
+            .iter()
+            .zip(&open)
+            .filter(|(_, open)| **open)
+            .filter_map(|(wall, _)| Some((centers[wall.cells.0], centers[wall.cells.1?])))
+            .collect()
+    }
+
+    /// The center of every cell, and every wall of the grid before any are knocked down.
+    fn cells(&self) -> (Vec<Vec2>, Vec<Wall>) {
+        let mut walls = Vec::new();
+
+        let centers = match self.grid {
+            MazeGrid::Rectangular {
+                min,
+                cell_size,
+                columns,
+                rows,
+            } => {
+                let corner = |column: usize, row: usize| {
+                    min + Vec2::new(column as f32, row as f32) * cell_size
+                };
+                let index = |column: usize, row: usize| row * columns + column;
+
+                for row in 0..rows {
+                    for column in 0..columns {
+                        let cell = index(column, row);
+                        walls.push(Wall {
+                            cells: (cell, (column + 1 < columns).then(|| cell + 1)),
+                            start: corner(column + 1, row),
+                            end: corner(column + 1, row + 1),
+                        });
+                        walls.push(Wall {
+                            cells: (cell, (row + 1 < rows).then(|| cell + columns)),
+                            start: corner(column, row + 1),
+                            end: corner(column + 1, row + 1),
+                        });
+                        if column == 0 {
+                            walls.push(Wall {
+                                cells: (cell, None),
+                                start: corner(0, row),
+                                end: corner(0, row + 1),
+                            });
+                        }
+                        if row == 0 {
+                            walls.push(Wall {
+                                cells: (cell, None),
+                                start: corner(column, 0),
+                                end: corner(column + 1, 0),
+                            });
+                        }
+                    }
+                }
+
+                (0..rows)
+                    .flat_map(|row| (0..columns).map(move |column| (column, row)))
+                    .map(|(column, row)| corner(column, row) + cell_size / 2.0)
+                    .collect()
+            }
+            MazeGrid::Hexagonal { layout, radius } => {
+                let hexes: Vec<Hex> = Hex::default().range(radius).collect();
+                let indices: HashMap<Hex, usize> =
+                    hexes.iter().enumerate().map(|(i, &hex)| (hex, i)).collect();
+
+                for (cell, hex) in hexes.iter().enumerate() {
+                    for neighbor in hex.neighbors() {
+                        let other = indices.get(&neighbor).copied();
+                        if other.is_some_and(|other| other < cell) {
+                            continue;
+                        }
+
+                        // The shared edge runs between the two corners as far from the neighbour's center as from this one's.
+                        let middle = layout.hex_to_world(neighbor);
+                        let mut shared = layout
+                            .corners(*hex)
+                            .into_iter()
+                            .filter(|corner| corner.distance(middle) < layout.size * 1.01);
+                        if let (Some(start), Some(end)) = (shared.next(), shared.next()) {
+                            walls.push(Wall {
+                                cells: (cell, other),
+                                start,
+                                end,
+                            });
+                        }
+                    }
+                }
+
+                hexes.iter().map(|&hex| layout.hex_to_world(hex)).collect()
+            }
+        };
+
+        (centers, walls)
+    }
+
+    /// Knock down walls to join every cell into one maze, returning whether each wall was knocked down.
+    fn carve(&self, walls: &[Wall]) -> Vec<bool> {
+        let cells = walls
+            .iter()
+            .map(|wall| wall.cells.0.max(wall.cells.1.unwrap_or(0)) + 1)
+            .max()
+            .unwrap_or(0);
+        if cells == 0 {
+            return Vec::new();
+        }
+
+        // The neighbours of each cell, and the wall in between.
+        let mut neighbors: Vec<Vec<(usize, usize)>> = vec![Vec::new(); cells];
+        for (index, wall) in walls.iter().enumerate() {
+            if let (a, Some(b)) = wall.cells {
+                neighbors[a].push((b, index));
+                neighbors[b].push((a, index));
+            }
+        }
+
+        let mut rng = Rng::new(self.seed);
+        let mut pick = |count: usize| (rng.next_u64() % count.max(1) as u64) as usize;
+        let mut open = vec![false; walls.len()];
+
+        match self.algorithm {
+            MazeAlgorithm::RecursiveBacktracker => {
+                let mut visited = vec![false; cells];
+                let mut stack = vec![0];
+                if let Some(first) = visited.first_mut() {
+                    *first = true;
+                }
+
+                while let Some(&cell) = stack.last() {
+                    let unvisited: Vec<&(usize, usize)> = neighbors[cell]
+                        .iter()
+                        .filter(|(other, _)| !visited[*other])
+                        .collect();
+
+                    if unvisited.is_empty() {
+                        stack.pop();
+                    } else {
+                        let &(other, wall) = unvisited[pick(unvisited.len())];
+                        visited[other] = true;
+                        open[wall] = true;
+                        stack.push(other);
+                    }
+                }
+            }
+            MazeAlgorithm::Kruskal => {
+                let mut order: Vec<usize> = (0..walls.len())
+                    .filter(|&wall| walls[wall].cells.1.is_some())
+                    .collect();
+                for i in (1..order.len()).rev() {
+                    order.swap(i, pick(i + 1));
+                }
+
+                let mut parents: Vec<usize> = (0..cells).collect();
+                fn root(parents: &mut [usize], mut cell: usize) -> usize {
+                    while parents[cell] != cell {
+                        parents[cell] = parents[parents[cell]];
+                        cell = parents[cell];
+                    }
+                    cell
+                }
+
+                for wall in order {
+                    if let (a, Some(b)) = walls[wall].cells {
+                        let (a, b) = (root(&mut parents, a), root(&mut parents, b));
+                        if a != b {
+                            parents[a] = b;
+                            open[wall] = true;
+                        }
+                    }
+                }
+            }
+            MazeAlgorithm::Wilson => {
+                let mut in_maze = vec![false; cells];
+                if let Some(first) = in_maze.first_mut() {
+                    *first = true;
+                }
+                // The way each cell of the current walk was last left, which erases any loops as the walk revisits cells.
+                let mut exits: Vec<Option<(usize, usize)>> = vec![None; cells];
+
+                for start in 0..cells {
+                    let mut cell = start;
+                    while !in_maze[cell] {
+                        let exit = neighbors[cell][pick(neighbors[cell].len())];
+                        exits[cell] = Some(exit);
+                        cell = exit.0;
+                    }
+
+                    let mut cell = start;
+                    while !in_maze[cell] {
+                        in_maze[cell] = true;
+                        if let Some((next, wall)) = exits[cell] {
+                            open[wall] = true;
+                            cell = next;
+                        }
+                    }
+                }
+            }
+        }
+
+        open
+    }
+}
+
+/// Join segments that share ends into polylines.
+fn chain(segments: &[(Vec2, Vec2)]) -> Vec<Vec<Vec2>> {
+    let key = |point: Vec2| {
+        (
+            (point.x * 1e4).round() as i64,
+            (point.y * 1e4).round() as i64,
+        )
+    };
+
+    let mut ends: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+    for (index, (start, end)) in segments.iter().enumerate() {
+        ends.entry(key(*start)).or_default().push(index);
+        ends.entry(key(*end)).or_default().push(index);
+    }
+
+    let mut used = vec![false; segments.len()];
+    let mut polylines = Vec::new();
+
+    for first in 0..segments.len() {
+        if used[first] {
+            continue;
+        }
+        used[first] = true;
+
+        let mut polyline = vec![segments[first].0, segments[first].1];
+        // Extend the end of the polyline, then reverse it and extend the other end.
+        for _ in 0..2 {
+            loop {
+                let last = polyline[polyline.len() - 1];
+                let next = ends[&key(last)].iter().copied().find(|&index| !used[index]);
+
+                let Some(next) = next else {
+                    break;
+                };
+                used[next] = true;
+
+                let (start, end) = segments[next];
+                polyline.push(if key(start) == key(last) { end } else { start });
+            }
+            polyline.reverse();
+        }
+
+        polylines.push(polyline);
+    }
+
+    polylines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex::HexOrientation;
+
+    /// Verify that every algorithm joins every cell with exactly one route, on both square and hex grids.
+    #[test]
+    fn perfect_mazes() {
+        let grids = [
+            (
+                MazeGrid::Rectangular {
+                    min: Vec2::ZERO,
+                    cell_size: 1.0,
+                    columns: 6,
+                    rows: 4,
+                },
+                24,
+            ),
+            (
+                MazeGrid::Hexagonal {
+                    layout: HexLayout::new(HexOrientation::Pointy, 1.0),
+                    radius: 2,
+                },
+                19,
+            ),
+        ];
+
+        for (grid, cells) in grids {
+            for algorithm in [
+                MazeAlgorithm::RecursiveBacktracker,
+                MazeAlgorithm::Kruskal,
+                MazeAlgorithm::Wilson,
+            ] {
+                let maze = Maze {
+                    seed: 3,
+                    ..Maze::new(grid, algorithm)
+                };
+
+                // A tree joining every cell has one fewer passage than cells, and no loops.
+                let passages = maze.passages();
+                assert_eq!(passages.len(), cells - 1);
+                let (_, walls) = maze.cells();
+                let open = maze.carve(&walls);
+                let mut parents: Vec<usize> = (0..cells).collect();
+                for (wall, _) in walls.iter().zip(&open).filter(|(_, open)| **open) {
+                    let (mut a, mut b) = (wall.cells.0, wall.cells.1.unwrap());
+                    while parents[a] != a {
+                        a = parents[a];
+                    }
+                    while parents[b] != b {
+                        b = parents[b];
+                    }
+                    assert_ne!(a, b);
+                    parents[a] = b;
+                }
+
+                let length: f32 = maze
+                    .walls()
+                    .iter()
+                    .flat_map(|wall| wall.windows(2))
+                    .map(|pair| pair[0].distance(pair[1]))
+                    .sum();
+                let total: f32 = walls.iter().map(|wall| wall.start.distance(wall.end)).sum();
+                assert!((total - length - (cells - 1) as f32).abs() < 1e-3);
+            }
+        }
+    }
+
+    /// Verify that a grid without any cells makes an empty maze.
+    #[test]
+    fn empty_maze() {
+        for (columns, rows) in [(0, 4), (6, 0), (0, 0)] {
+            let grid = MazeGrid::Rectangular {
+                min: Vec2::ZERO,
+                cell_size: 1.0,
+                columns,
+                rows,
+            };
+            for algorithm in [
+                MazeAlgorithm::RecursiveBacktracker,
+                MazeAlgorithm::Kruskal,
+                MazeAlgorithm::Wilson,
+            ] {
+                let maze = Maze::new(grid, algorithm);
+                assert!(maze.walls().is_empty());
+                assert!(maze.passages().is_empty());
+            }
+        }
+    }
+}
@@ -0,0 +1,608 @@
+use std::collections::HashMap;
+
+use glam::{Mat4, Vec2, Vec3};
+
+#[cfg(feature = "obj")]
+use crate::Error;
+use crate::{
+    axonometric::{face_normal, Shading, Solid},
+    geometry::{clip_outside, signed_area, triangulate},
+    Color, FillRule, Shape, Stroke,
+};
+
+/// A camera that projects 3D points onto a square view, spanning `-1..=1` on both axes, with the z axis pointing up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Camera {
+    matrix: Mat4,
+}
+
+impl Camera {
+    /// Create a [Camera] from a combined view and projection matrix, which maps points in view to `-1..=1` on the x and y axes.
+    pub fn new(matrix: Mat4) -> Self {
+        Self { matrix }
+    }
+
+    /// A camera at `eye` looking at `target` with perspective, seeing `fov_y` radians from the bottom of the view to the top.
+    pub fn perspective(eye: Vec3, target: Vec3, fov_y: f32) -> Self {
+        let near = eye.distance(target) * 1e-3;
+        Self::new(
+            Mat4::perspective_infinite_rh(fov_y, 1.0, near.max(f32::EPSILON))
+                * Mat4::look_at_rh(eye, target, Vec3::Z),
+        )
+    }
+
+    /// A camera at `eye` looking at `target` without perspective, seeing `height` units from the bottom of the view to the top.
+    ///
+    /// Anything within ten thousand units of `eye`, in front or behind, is in view.
+    pub fn orthographic(eye: Vec3, target: Vec3, height: f32) -> Self {
+        let half = height / 2.0;
+        Self::new(
+            Mat4::orthographic_rh(-half, half, -half, half, -1e4, 1e4)
+                * Mat4::look_at_rh(eye, target, Vec3::Z),
+        )
+    }
+
+    /// The combined view and projection matrix.
+    pub fn matrix(&self) -> Mat4 {
+        self.matrix
+    }
+
+    /// Project a point into the view, along with its depth, which grows further from the camera.
+    ///
+    /// Returns [None] for points behind the camera.
+    pub fn project(&self, point: Vec3) -> Option<(Vec2, f32)> {
+        let clip = self.matrix * point.extend(1.0);
+        if clip.w <= f32::EPSILON {
+            return None;
+        }
+
+        let projected = clip.truncate() / clip.w;
+        projected
+            .is_finite()
+            .then(|| (projected.truncate(), projected.z))
+    }
+}
+
+/// A 3D mesh of flat faces that share their corners, ready to draw as line art through a [Camera].
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Mesh {
+    /// The corners of every face.
+    pub vertices: Vec<Vec3>,
+    /// Each face as indices into [vertices](Self::vertices), counter-clockwise when seen from outside the mesh.
+    pub faces: Vec<Vec<usize>>,
+}
+
+impl Mesh {
+    /// Create a new [Mesh] from its vertices and faces.
+    pub fn new(vertices: Vec<Vec3>, faces: Vec<Vec<usize>>) -> Self {
+        Self { vertices, faces }
+    }
+
+    /// Parse a mesh from the text of a Wavefront OBJ file. Only vertices (`v`) and faces (`f`) are read.
+    ///
+    /// Requires the `obj` feature.
+    #[cfg(feature = "obj")]
+    pub fn from_obj(source: &str) -> Result<Self, Error> {
+        let mut mesh = Self::default();
+
+        for (number, line) in source.lines().enumerate() {
+            let invalid = || Error::InvalidObj { line: number + 1 };
+            let mut tokens = line.split('#').next().unwrap_or("").split_whitespace();
+
+            match tokens.next() {
+                Some("v") => {
+                    let coordinates = tokens
+                        .take(3)
+                        .map(|token| token.parse::<f32>().map_err(|_| invalid()))
+                        .collect::<Result<Vec<f32>, Error>>()?;
+                    if coordinates.len() != 3 {
+                        return Err(invalid());
+                    }
+                    mesh.vertices.push(Vec3::from_slice(&coordinates));
+                }
+                Some("f") => {
+                    let face = tokens
+                        .map(|token| {
+                            // Faces may also refer to texture coordinates and normals, as `v/vt/vn`.
+                            let index: i64 = token
+                                .split('/')
+                                .next()
+                                .and_then(|index| index.parse().ok())
+                                .ok_or_else(invalid)?;
+
+                            // Negative indices count back from the latest vertex.
+                            let count = mesh.vertices.len() as i64;
+                            let index = if index < 0 { count + index } else { index - 1 };
+                            if (0..count).contains(&index) {
+                                Ok(index as usize)
+                            } else {
+                                Err(invalid())
+                            }
+                        })
+                        .collect::<Result<Vec<usize>, Error>>()?;
+                    if face.len() < 3 {
+                        return Err(invalid());
+                    }
+                    mesh.faces.push(face);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(mesh)
+    }
+
+    /// Extrude a flat profile straight up from the ground by `height`, capped at both ends, such as a letter or a floor plan.
+    ///
+    /// The profile's [points](Shape::points) are its outline and its [subpaths](Shape::subpaths) are holes, as when filling a shape.
+    /// Either winding works.
+    pub fn extrude(profile: &Shape, height: f32) -> Self {
+        let profile: &Shape = &profile.flattened();
+        let paths: Vec<Vec<Vec2>> = profile
+            .paths()
+            .enumerate()
+            .map(|(index, path)| {
+                let mut path = path.to_vec();
+                if path.len() > 1 && path.first() == path.last() {
+                    path.pop();
+                }
+
+                // Wind the outline counter-clockwise and the holes clockwise, so that every side faces out.
+                let clockwise = signed_area(&path) < 0.0;
+                if clockwise != (index > 0) {
+                    path.reverse();
+                }
+                path
+            })
+            .filter(|path| path.len() > 2)
+            .collect();
+        let Some((outline, holes)) = paths.split_first() else {
+            return Self::default();
+        };
+
+        let flat: Vec<Vec2> = paths.iter().flatten().copied().collect();
+        let count = flat.len();
+        let mut mesh = Self::new(
+            flat.iter()
+                .map(|point| point.extend(0.0))
+                .chain(flat.iter().map(|point| point.extend(height)))
+                .collect(),
+            Vec::new(),
+        );
+
+        let holes: Vec<&[Vec2]> = holes.iter().map(Vec::as_slice).collect();
+        for [a, b, c] in triangulate(outline, &holes) {
+            mesh.faces.push(vec![c, b, a]);
+            mesh.faces.push(vec![a + count, b + count, c + count]);
+        }
+
+        let mut start = 0;
+        for path in &paths {
+            for (a, b) in face_edges(&(start..start + path.len()).collect::<Vec<_>>()) {
+                mesh.faces.push(vec![a, b, b + count, a + count]);
+            }
+            start += path.len();
+        }
+
+        mesh
+    }
+
+    /// Spin a profile a full turn around the z axis, in `segments` steps, such as a vase or a column.
+    ///
+    /// Each path of the profile is in the plane of the radius (x) and height (y). Points on the axis become a single point.
+    /// Paths should run up the outside of the form, or counter-clockwise if closed, so that the faces point out.
+    /// Open paths leave the ends of the surface open.
+    pub fn revolve(profile: &Shape, segments: usize) -> Self {
+        let profile: &Shape = &profile.flattened();
+        let segments = segments.max(3);
+        let mut mesh = Self::default();
+
+        for path in profile.paths() {
+            let closed = path.len() > 2 && path.first() == path.last();
+            let path = if closed {
+                &path[..path.len() - 1]
+            } else {
+                path
+            };
+
+            let rings: Vec<Vec<usize>> = path
+                .iter()
+                .map(|point| {
+                    if point.x.abs() <= f32::EPSILON {
+                        mesh.vertices.push(Vec3::new(0.0, 0.0, point.y));
+                        return vec![mesh.vertices.len() - 1; segments];
+                    }
+
+                    (0..segments)
+                        .map(|step| {
+                            let angle = std::f32::consts::TAU * step as f32 / segments as f32;
+                            mesh.vertices.push(Vec3::new(
+                                point.x * angle.cos(),
+                                point.x * angle.sin(),
+                                point.y,
+                            ));
+                            mesh.vertices.len() - 1
+                        })
+                        .collect()
+                })
+                .collect();
+
+            let pairs = rings.len().saturating_sub(1) + usize::from(closed);
+            for (lower, upper) in rings.iter().zip(rings.iter().cycle().skip(1)).take(pairs) {
+                for step in 0..segments {
+                    let next = (step + 1) % segments;
+                    let mut face = vec![lower[step], lower[next], upper[next], upper[step]];
+
+                    // Faces meeting the axis are triangles.
+                    face.dedup();
+                    if face.first() == face.last() {
+                        face.pop();
+                    }
+                    if face.len() > 2 {
+                        mesh.faces.push(face);
+                    }
+                }
+            }
+        }
+
+        mesh
+    }
+
+    /// Every edge of the mesh, once each, as a pair of vertex indices.
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        let mut edges: Vec<(usize, usize)> = self
+            .faces
+            .iter()
+            .flat_map(|face| face_edges(face))
+            .map(|(a, b)| (a.min(b), a.max(b)))
+            .collect();
+        edges.sort_unstable();
+        edges.dedup();
+        edges
+    }
+
+    /// Every edge of the mesh through `camera`, hidden or not. Edges reaching behind the camera are left out.
+    pub fn wireframe(&self, camera: &Camera) -> Vec<Vec<Vec2>> {
+        let projected = self.project(camera);
+
+        self.edges()
+            .into_iter()
+            .filter_map(|(a, b)| {
+                let point = |index: usize| projected.get(index).copied().flatten();
+                Some(vec![point(a)?.0, point(b)?.0])
+            })
+            .collect()
+    }
+
+    /// The edges of the mesh through `camera` that are not hidden behind its faces, such as for plotting.
+    ///
+    /// Faces turned away from the camera are ignored, so the mesh should be closed, with its faces wound consistently.
+    /// Each visible piece of an edge is returned once, as an open polyline.
+    pub fn hidden_lines(&self, camera: &Camera) -> Vec<Vec<Vec2>> {
+        self.visible_edges(camera, |_| true)
+    }
+
+    /// Like [hidden_lines](Self::hidden_lines), but only the edges that outline the form: silhouettes, where the mesh turns away from the camera,
+    /// open borders, and creases sharper than `crease_angle` radians.
+    ///
+    /// Edges between faces that are nearly flat to each other, such as those splitting up a curved surface or a flat cap, are left out.
+    pub fn outline(&self, camera: &Camera, crease_angle: f32) -> Vec<Vec<Vec2>> {
+        let visible: Vec<bool> = {
+            let mut visible = vec![false; self.faces.len()];
+            for (_, face, _) in self.visible_faces(camera) {
+                visible[face] = true;
+            }
+            visible
+        };
+        // Faces with a corner out of range are skipped, as they are by the faces' visibility.
+        let normals: Vec<Option<Vec3>> = self
+            .faces
+            .iter()
+            .map(|face| {
+                face.iter()
+                    .map(|&index| self.vertices.get(index).copied())
+                    .collect::<Option<Vec<_>>>()
+                    .map(|corners| face_normal(&corners))
+            })
+            .collect();
+
+        let mut neighbors: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        for (index, face) in self.faces.iter().enumerate() {
+            if normals[index].is_none() {
+                continue;
+            }
+            for (a, b) in face_edges(face) {
+                neighbors
+                    .entry((a.min(b), a.max(b)))
+                    .or_default()
+                    .push(index);
+            }
+        }
+
+        let min_cos = crease_angle.cos();
+        self.visible_edges(camera, |edge| match neighbors[&edge].as_slice() {
+            &[first, second] => {
+                visible[first] != visible[second]
+                    || normals[first]
+                        .zip(normals[second])
+                        .is_some_and(|(first, second)| first.dot(second) < min_cos)
+            }
+            _ => true,
+        })
+    }
+
+    /// The visible pieces of every edge for which `keep` returns true, given as a sorted pair of vertex indices.
+    fn visible_edges<F: Fn((usize, usize)) -> bool>(
+        &self,
+        camera: &Camera,
+        keep: F,
+    ) -> Vec<Vec<Vec2>> {
+        let faces = self.visible_faces(camera);
+
+        // Each edge is drawn with the nearest face it belongs to, and hidden only by faces nearer still.
+        let mut owners = HashMap::new();
+        for (order, (_, face, _)) in faces.iter().enumerate() {
+            for (a, b) in face_edges(&self.faces[*face]) {
+                owners.insert((a.min(b), a.max(b)), order);
+            }
+        }
+
+        let projected = self.project(camera);
+        let mut lines = Vec::new();
+        for (order, (_, face, _)) in faces.iter().enumerate() {
+            for (a, b) in face_edges(&self.faces[*face]) {
+                let edge = (a.min(b), a.max(b));
+                if owners.get(&edge) != Some(&order) || !keep(edge) {
+                    continue;
+                }
+
+                let (Some((start, _)), Some((end, _))) = (projected[a], projected[b]) else {
+                    continue;
+                };
+                let (min, max) = (start.min(end), start.max(end));
+
+                let mut pieces = vec![vec![start, end]];
+                for (_, _, polygon) in &faces[order + 1..] {
+                    let (near_min, near_max) = bounds(polygon);
+                    if near_min.cmpgt(max).any() || near_max.cmplt(min).any() {
+                        continue;
+                    }
+                    pieces = pieces
+                        .iter()
+                        .flat_map(|piece| clip_outside(piece, &[polygon], FillRule::NonZero))
+                        .collect();
+                }
+                lines.extend(pieces);
+            }
+        }
+
+        lines
+    }
+
+    /// The faces of the mesh through `camera` as filled shapes, ordered from back to front, with faces turned away from the camera left out.
+    ///
+    /// Faces are shaded like an [Axonometric](crate::axonometric::Axonometric) solid, and sorted by their average depth,
+    /// which can fail when faces interlock. Draw them onto a canvas and call [remove_hidden_lines](crate::Canvas::remove_hidden_lines) for line art instead.
+    pub fn shapes(
+        &self,
+        camera: &Camera,
+        fill: Color,
+        shading: &Shading,
+        stroke: Option<Stroke>,
+    ) -> Vec<Shape> {
+        let light = shading.light.normalize_or_zero();
+
+        self.visible_faces(camera)
+            .into_iter()
+            .map(|(_, face, points)| {
+                let corners: Vec<Vec3> = self.faces[face]
+                    .iter()
+                    .map(|&index| self.vertices[index])
+                    .collect();
+                let brightness = shading.ambient
+                    + (1.0 - shading.ambient) * face_normal(&corners).dot(light).max(0.0);
+
+                Shape {
+                    points,
+                    stroke,
+                    fill: Some(Color::new(
+                        fill.r() * brightness,
+                        fill.g() * brightness,
+                        fill.b() * brightness,
+                        fill.a(),
+                    )),
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+
+    /// Every vertex through `camera`, or [None] for those behind it.
+    fn project(&self, camera: &Camera) -> Vec<Option<(Vec2, f32)>> {
+        self.vertices
+            .iter()
+            .map(|&vertex| camera.project(vertex))
+            .collect()
+    }
+
+    /// The faces wound counter-clockwise on screen, and so facing the camera, from back to front,
+    /// with their average depth, index and closed projected outline.
+    fn visible_faces(&self, camera: &Camera) -> Vec<(f32, usize, Vec<Vec2>)> {
+        let projected = self.project(camera);
+
+        let mut faces: Vec<(f32, usize, Vec<Vec2>)> = self
+            .faces
+            .iter()
+            .enumerate()
+            .filter_map(|(index, face)| {
+                let corners = face
+                    .iter()
+                    .map(|&index| projected.get(index).copied().flatten())
+                    .collect::<Option<Vec<(Vec2, f32)>>>()?;
+
+                let mut polygon: Vec<Vec2> = corners.iter().map(|(point, _)| *point).collect();
+                let area: f32 = face_edges(&polygon).map(|(a, b)| a.perp_dot(b)).sum();
+                if corners.len() < 3 || area <= 0.0 {
+                    return None;
+                }
+
+                polygon.push(polygon[0]);
+                let depth =
+                    corners.iter().map(|(_, depth)| depth).sum::<f32>() / corners.len() as f32;
+                Some((depth, index, polygon))
+            })
+            .collect();
+
+        faces.sort_by(|a, b| b.0.total_cmp(&a.0));
+        faces
+    }
+}
+
+impl From<Solid> for Mesh {
+    /// Join the faces of a [Solid] at their shared corners.
+    fn from(solid: Solid) -> Self {
+        let mut mesh = Self::default();
+
+        for face in &solid.faces {
+            let indices = face
+                .iter()
+                .map(
+                    |corner| match mesh.vertices.iter().position(|vertex| vertex == corner) {
+                        Some(index) => index,
+                        None => {
+                            mesh.vertices.push(*corner);
+                            mesh.vertices.len() - 1
+                        }
+                    },
+                )
+                .collect();
+            mesh.faces.push(indices);
+        }
+
+        mesh
+    }
+}
+
+/// Each consecutive pair of items in a closed loop, including the last back to the first.
+fn face_edges<T: Copy>(face: &[T]) -> impl Iterator<Item = (T, T)> + '_ {
+    face.iter()
+        .copied()
+        .zip(face.iter().copied().cycle().skip(1))
+}
+
+/// The smallest and largest corners of the box around `points`.
+fn bounds(points: &[Vec2]) -> (Vec2, Vec2) {
+    points.iter().fold(
+        (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+        |(min, max), &point| (min.min(point), max.max(point)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::custom_shape::custom_capsule;
+
+    /// Verify that a cube seen from a corner shows three faces and nine of its twelve edges.
+    #[test]
+    fn cube_hidden_lines() {
+        let cube = Mesh::from(Solid::cuboid(Vec3::ZERO, Vec3::ONE, Color::white()));
+        assert_eq!((cube.vertices.len(), cube.edges().len()), (8, 12));
+
+        let cameras = [
+            Camera::perspective(Vec3::new(4.0, -3.0, 5.0), Vec3::splat(0.5), 0.8),
+            Camera::orthographic(Vec3::new(4.0, -3.0, 5.0), Vec3::splat(0.5), 3.0),
+        ];
+        for camera in &cameras {
+            assert_eq!(cube.wireframe(camera).len(), 12);
+            assert_eq!(cube.hidden_lines(camera).len(), 9);
+
+            let shapes = cube.shapes(camera, Color::white(), &Shading::default(), None);
+            assert_eq!(shapes.len(), 3);
+            assert!(shapes
+                .iter()
+                .flat_map(|shape| &shape.points)
+                .all(|point| point.abs().cmple(Vec2::ONE).all()));
+        }
+
+        // Nothing behind a perspective camera is in view.
+        assert!(Camera::perspective(Vec3::ZERO, Vec3::X, 1.0)
+            .project(Vec3::new(-1.0, 0.0, 0.0))
+            .is_none());
+
+        #[cfg(feature = "obj")]
+        {
+            let source = "# A square\nv 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1/1 2/2 3/3 -1\n";
+            let square = Mesh::from_obj(source).unwrap();
+            assert_eq!(square.faces, vec![vec![0, 1, 2, 3]]);
+            assert_eq!(
+                Mesh::from_obj("v 0 0 0\nf 1 2 3\n"),
+                Err(Error::InvalidObj { line: 2 })
+            );
+        }
+    }
+
+    /// Verify that extruded and revolved profiles are closed where expected, and that their outlines skip edges on flat or smooth surfaces.
+    #[test]
+    fn extrude_and_revolve() {
+        let square = Shape {
+            points: vec![Vec2::ZERO, Vec2::Y, Vec2::ONE, Vec2::X, Vec2::ZERO],
+            ..Default::default()
+        };
+        let extruded = Mesh::extrude(&square, 1.0);
+        assert_eq!((extruded.vertices.len(), extruded.faces.len()), (8, 8));
+        assert_eq!(extruded.edges().len(), 14);
+
+        let camera = Camera::orthographic(Vec3::new(4.0, -3.0, 5.0), Vec3::splat(0.5), 3.0);
+        assert_eq!(extruded.outline(&camera, 0.1).len(), 9);
+
+        let ball = Shape {
+            points: vec![
+                Vec2::new(0.0, -1.0),
+                Vec2::new(1.0, 0.0),
+                Vec2::new(0.0, 1.0),
+            ],
+            ..Default::default()
+        };
+        let revolved = Mesh::revolve(&ball, 8);
+        assert_eq!((revolved.vertices.len(), revolved.faces.len()), (10, 16));
+        assert!(revolved.faces.iter().all(|face| face.len() == 3));
+
+        let tube = Shape {
+            points: vec![Vec2::new(1.0, 0.0), Vec2::new(1.0, 2.0)],
+            ..Default::default()
+        };
+        let revolved = Mesh::revolve(&tube, 32);
+        let camera = Camera::orthographic(Vec3::new(0.0, -10.0, 6.0), Vec3::Z, 4.0);
+        let outline = revolved.outline(&camera, 0.5);
+        assert!(!outline.is_empty());
+        assert!(outline.len() < revolved.hidden_lines(&camera).len());
+    }
+
+    /// Verify that custom profiles are flattened before they are extruded or revolved.
+    #[test]
+    fn custom_profiles() {
+        let custom = custom_capsule();
+        let flattened = custom.flattened();
+
+        let extruded = Mesh::extrude(&custom, 1.0);
+        assert_eq!(extruded, Mesh::extrude(&flattened, 1.0));
+        assert!(!extruded.faces.is_empty());
+        assert_eq!(Mesh::revolve(&custom, 8), Mesh::revolve(&flattened, 8));
+    }
+
+    /// Verify that faces with corners out of range are skipped rather than panicking.
+    #[test]
+    fn skip_bad_faces() {
+        let camera = Camera::orthographic(Vec3::new(4.0, -3.0, 5.0), Vec3::splat(0.5), 3.0);
+        let mut cube = Mesh::from(Solid::cuboid(Vec3::ZERO, Vec3::ONE, Color::white()));
+        let expected = cube.outline(&camera, 0.1);
+        cube.faces.push(vec![0, 1, 100]);
+
+        assert_eq!(cube.outline(&camera, 0.1).len(), expected.len());
+        assert_eq!(cube.wireframe(&camera).len(), 12);
+        assert!(!cube.hidden_lines(&camera).is_empty());
+    }
+}
@@ -0,0 +1,395 @@
+use glam::{Affine2, Vec2};
+
+use crate::{
+    geometry::{contains_point, distance_to_segment, segment_intersection},
+    rng::Rng,
+    spatial::SpatialIndex,
+    Shape,
+};
+
+/// A circle placed by [CirclePacking].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PackedCircle {
+    /// Center of the circle.
+    pub center: Vec2,
+    /// Radius of the circle.
+    pub radius: f32,
+}
+
+impl PackedCircle {
+    /// A transform that scales a unit circle, centered on the origin, onto this circle.
+    ///
+    /// Hand these to [Canvas::draw_instances](crate::Canvas::draw_instances) to draw any shape in place of each circle.
+    pub fn transform(&self) -> Affine2 {
+        Affine2::from_scale_angle_translation(Vec2::splat(self.radius), 0.0, self.center)
+    }
+}
+
+/// Iterative circle packing inside a boundary polygon.
+///
+/// Random candidate points inside the boundary each grow the largest circle that fits without touching the boundary or any circle already placed.
+/// The same seed always produces the same packing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CirclePacking {
+    /// Circles smaller than this are discarded.
+    pub min_radius: f32,
+    /// Circles stop growing at this radius.
+    pub max_radius: f32,
+    /// Space left between neighbouring circles, and between circles and the boundary.
+    pub padding: f32,
+    /// How many candidate points to try. More attempts fill gaps more thoroughly.
+    pub attempts: usize,
+    /// Seed for choosing candidate points.
+    pub seed: u64,
+}
+
+impl CirclePacking {
+    /// Create a new [CirclePacking] with no padding, making 1000 attempts.
+    pub fn new(min_radius: f32, max_radius: f32) -> Self {
+        Self {
+            min_radius,
+            max_radius,
+            padding: 0.0,
+            attempts: 1000,
+            seed: 0,
+        }
+    }
+
+    /// Pack circles inside a closed polygon. Circles are returned in the order they were placed.
+    pub fn pack(&self, boundary: &[Vec2]) -> Vec<PackedCircle> {
+        if boundary.len() < 3 {
+            return Vec::new();
+        }
+
+        let min = boundary
+            .iter()
+            .copied()
+            .fold(Vec2::splat(f32::INFINITY), Vec2::min);
+        let max = boundary
+            .iter()
+            .copied()
+            .fold(Vec2::splat(f32::NEG_INFINITY), Vec2::max);
+
+        let mut rng = Rng::new(self.seed);
+        let mut circles: Vec<PackedCircle> = Vec::new();
+
+        for _ in 0..self.attempts {
+            let center = Vec2::new(rng.range(min.x, max.x), rng.range(min.y, max.y));
+            if !contains_point(boundary, center) {
+                continue;
+            }
+
+            let to_boundary = (0..boundary.len())
+                .map(|i| {
+                    distance_to_segment(center, boundary[i], boundary[(i + 1) % boundary.len()])
+                })
+                .fold(f32::INFINITY, f32::min);
+            let to_circles = circles
+                .iter()
+                .map(|circle| center.distance(circle.center) - circle.radius)
+                .fold(f32::INFINITY, f32::min);
+
+            let radius = (to_boundary.min(to_circles) - self.padding).min(self.max_radius);
+            if radius >= self.min_radius && radius > 0.0 {
+                circles.push(PackedCircle { center, radius });
+            }
+        }
+
+        circles
+    }
+}
+
+/// Pack rectangles of the given sizes into shelves, left to right and then bottom to top, inside a region of `bounds`.
+///
+/// Positions are the bottom left corner of each rectangle, with the region's bottom left corner at the origin, in the same order as `sizes`.
+/// Rectangles that do not fit are `None`. Taller rectangles are placed first, which keeps shelves tight.
+pub fn pack_rectangles(sizes: &[Vec2], bounds: Vec2, padding: f32) -> Vec<Option<Vec2>> {
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by(|&a, &b| sizes[b].y.total_cmp(&sizes[a].y));
+
+    let mut positions = vec![None; sizes.len()];
+    let mut cursor = Vec2::ZERO;
+    let mut shelf_height = 0.0;
+
+    for index in order {
+        let size = sizes[index];
+        if size.x > bounds.x {
+            continue;
+        }
+
+        // Start a new shelf if this rectangle would run off the right edge.
+        if cursor.x + size.x > bounds.x {
+            cursor = Vec2::new(0.0, cursor.y + shelf_height + padding);
+            shelf_height = 0.0;
+        }
+
+        if cursor.y + size.y > bounds.y {
+            continue;
+        }
+
+        positions[index] = Some(cursor);
+        cursor.x += size.x + padding;
+        shelf_height = f32::max(shelf_height, size.y);
+    }
+
+    positions
+}
+
+/// Random placement of copies of a shape, rejecting any spot where it would touch shapes already there.
+///
+/// Each attempt picks a random position, and optionally a random rotation and scale, for the shape.
+/// Attempts are checked against existing shapes through a spatial index, so only nearby shapes are compared exactly.
+/// The same seed always tries the same transforms.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShapePlacement {
+    /// How many random transforms to try before giving up.
+    pub attempts: usize,
+    /// The smallest scale to try.
+    pub min_scale: f32,
+    /// The largest scale to try.
+    pub max_scale: f32,
+    /// Whether to try random rotations.
+    pub rotate: bool,
+    /// Space left between the placed shape and existing ones, on top of half of each stroke.
+    pub padding: f32,
+    /// Seed for choosing transforms.
+    pub seed: u64,
+}
+
+impl ShapePlacement {
+    /// Create a new [ShapePlacement] making `attempts` tries, without rotating, scaling, or padding the shape.
+    pub fn new(attempts: usize) -> Self {
+        Self {
+            attempts,
+            min_scale: 1.0,
+            max_scale: 1.0,
+            rotate: false,
+            padding: 0.0,
+            seed: 0,
+        }
+    }
+
+    /// Find a transform that places `shape` entirely within `min` to `max` without touching any of `existing`.
+    ///
+    /// `shape` is in its own local space, and is rotated and scaled around the center of its bounds.
+    /// Returns `None` if every attempt collided.
+    pub fn find(&self, shape: &Shape, existing: &[Shape], min: Vec2, max: Vec2) -> Option<Affine2> {
+        let (local_min, local_max) = shape.bounds()?;
+        let local_center = (local_min + local_max) / 2.0;
+        let radius = (local_max - local_min).length() / 2.0 * self.max_scale.max(self.min_scale);
+
+        let margin = |shape: &Shape| shape.stroke.map_or(0.0, |stroke| stroke.width / 2.0);
+        let mut index = SpatialIndex::new(radius * 2.0 + self.padding);
+        for (i, other) in existing.iter().enumerate() {
+            if let Some((other_min, other_max)) = other.bounds() {
+                let grow = Vec2::splat(margin(other));
+                index.insert(i, other_min - grow, other_max + grow);
+            }
+        }
+
+        let mut rng = Rng::new(self.seed);
+        for _ in 0..self.attempts {
+            let position = Vec2::new(rng.range(min.x, max.x), rng.range(min.y, max.y));
+            let angle = if self.rotate {
+                rng.range(0.0, std::f32::consts::TAU)
+            } else {
+                0.0
+            };
+            let scale = rng.range(self.min_scale, self.max_scale);
+
+            let transform =
+                Affine2::from_scale_angle_translation(Vec2::splat(scale), angle, position)
+                    * Affine2::from_translation(-local_center);
+            let mut candidate = shape.clone();
+            candidate.map_points(|point| transform.transform_point2(point));
+
+            let (candidate_min, candidate_max) = match candidate.bounds() {
+                Some((min, max)) if min.is_finite() && max.is_finite() => (min, max),
+                _ => continue,
+            };
+            let grow = Vec2::splat(margin(&candidate));
+            if (candidate_min - grow).cmplt(min).any() || (candidate_max + grow).cmpgt(max).any() {
+                continue;
+            }
+
+            let reach = Vec2::splat(margin(&candidate) + self.padding);
+            let collides = index
+                .query(candidate_min - reach, candidate_max + reach)
+                .into_iter()
+                .any(|i| {
+                    let clearance = margin(&candidate) + margin(&existing[i]) + self.padding;
+                    shapes_touch(&candidate, &existing[i], clearance)
+                });
+
+            if !collides {
+                return Some(transform);
+            }
+        }
+
+        None
+    }
+}
+
+/// Checks if two shapes come within `clearance` of each other, or one lies inside the other's closed paths.
+fn shapes_touch(a: &Shape, b: &Shape, clearance: f32) -> bool {
+    let (a, b): (&Shape, &Shape) = (&a.flattened(), &b.flattened());
+    let segments = |shape: &Shape| -> Vec<(Vec2, Vec2)> {
+        shape
+            .paths()
+            .flat_map(|path| path.windows(2).map(|pair| (pair[0], pair[1])))
+            .collect()
+    };
+    let (a_segments, b_segments) = (segments(a), segments(b));
+
+    let near = a_segments.iter().any(|&(a0, a1)| {
+        b_segments.iter().any(|&(b0, b1)| {
+            segment_intersection(a0, a1, b0, b1).is_some()
+                || distance_to_segment(a0, b0, b1) < clearance
+                || distance_to_segment(a1, b0, b1) < clearance
+                || distance_to_segment(b0, a0, a1) < clearance
+                || distance_to_segment(b1, a0, a1) < clearance
+        })
+    });
+
+    let inside = |outer: &Shape, inner: &Shape| {
+        let point = match inner.points.first() {
+            Some(point) => *point,
+            None => return false,
+        };
+        outer.fill.is_some()
+            && outer
+                .paths()
+                .any(|path| path.len() >= 3 && contains_point(path, point))
+    };
+
+    near || inside(a, b) || inside(b, a)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::custom_shape::custom_capsule;
+
+    /// Verify that packed circles stay inside the boundary and never overlap.
+    #[test]
+    fn circles_do_not_overlap() {
+        let square = [
+            Vec2::new(-1.0, -1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(-1.0, 1.0),
+        ];
+        let mut packing = CirclePacking::new(0.02, 0.5);
+        packing.padding = 0.01;
+        let circles = packing.pack(&square);
+
+        assert!(circles.len() > 20);
+        for (i, a) in circles.iter().enumerate() {
+            assert!(a.center.abs().max_element() + a.radius <= 1.0);
+            for b in &circles[i + 1..] {
+                assert!(a.center.distance(b.center) >= a.radius + b.radius);
+            }
+        }
+    }
+
+    /// Verify that placed shapes stay inside the region and never touch each other.
+    #[test]
+    fn place_without_collisions() {
+        let square = Shape {
+            points: vec![
+                Vec2::new(-0.5, -0.5),
+                Vec2::new(0.5, -0.5),
+                Vec2::new(0.5, 0.5),
+                Vec2::new(-0.5, 0.5),
+                Vec2::new(-0.5, -0.5),
+            ],
+            fill: Some(crate::Color::black()),
+            ..Default::default()
+        };
+
+        let mut placement = ShapePlacement::new(200);
+        placement.rotate = true;
+        placement.padding = 0.05;
+
+        let mut placed: Vec<Shape> = Vec::new();
+        for seed in 0..10 {
+            placement.seed = seed;
+            if let Some(transform) = placement.find(&square, &placed, Vec2::ZERO, Vec2::splat(4.0))
+            {
+                let mut shape = square.clone();
+                shape.map_points(|point| transform.transform_point2(point));
+                placed.push(shape);
+            }
+        }
+
+        assert!(placed.len() >= 5);
+        for (i, a) in placed.iter().enumerate() {
+            let (min, max) = a.bounds().unwrap();
+            assert!(min.cmpge(Vec2::ZERO).all() && max.cmple(Vec2::splat(4.0)).all());
+            for b in &placed[i + 1..] {
+                assert!(!shapes_touch(a, b, 0.05));
+            }
+        }
+    }
+
+    /// Verify that rectangles fill shelves, and ones that do not fit are left out.
+    #[test]
+    fn shelf_packing() {
+        let sizes = [
+            Vec2::new(2.0, 1.0),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(5.0, 1.0),
+        ];
+        let positions = pack_rectangles(&sizes, Vec2::new(4.0, 3.0), 0.0);
+
+        assert_eq!(
+            positions,
+            vec![
+                Some(Vec2::new(2.0, 0.0)),
+                Some(Vec2::ZERO),
+                Some(Vec2::new(0.0, 2.0)),
+                None
+            ]
+        );
+    }
+
+    /// Verify that custom shapes are flattened before checking whether they touch.
+    #[test]
+    fn custom_shapes_touch() {
+        let custom = custom_capsule();
+        let line = Shape {
+            points: vec![Vec2::new(5.0, 0.5), Vec2::new(5.0, 5.0)],
+            ..Default::default()
+        };
+
+        assert!(shapes_touch(&custom, &line, 0.0));
+        assert!(shapes_touch(&line, &custom, 0.0));
+    }
+
+    /// Verify that a small shape can be placed among huge and non-finite shapes.
+    #[test]
+    fn place_among_huge_shapes() {
+        let line = |start: Vec2, end: Vec2| Shape {
+            points: vec![start, end],
+            stroke: Some(crate::Stroke::new(
+                crate::Color::black(),
+                0.1,
+                crate::LineEnd::Butt,
+            )),
+            ..Default::default()
+        };
+        let existing = [
+            line(Vec2::new(-1e7, 3.0), Vec2::new(1e7, 3.0)),
+            line(Vec2::new(f32::NAN, 0.0), Vec2::new(f32::INFINITY, 1.0)),
+        ];
+        let dot = line(Vec2::ZERO, Vec2::new(0.01, 0.0));
+
+        let transform = ShapePlacement::new(100)
+            .find(&dot, &existing, Vec2::ZERO, Vec2::splat(4.0))
+            .unwrap();
+        let mut placed = dot.clone();
+        placed.map_points(|point| transform.transform_point2(point));
+        assert!(!shapes_touch(&placed, &existing[0], 0.1));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{Canvas, Color, Stroke};
+use crate::{Canvas, Color, FillRule, IntoPoint, Stroke};
 use glam::Vec2;
 
 /// A builder to describe the shape of a path.
@@ -8,6 +8,7 @@ use glam::Vec2;
 #[derive(Clone, Debug)]
 pub struct PathBuilder {
     points_per_unit: usize,
+    fill_rule: FillRule,
     shapes: Vec<Vec<Vec2>>,
     current_shape: Vec<Vec2>,
 }
@@ -16,26 +17,27 @@ impl PathBuilder {
     pub(crate) fn new(points_per_unit: usize) -> Self {
         Self {
             points_per_unit,
+            fill_rule: FillRule::NonZero,
             shapes: Vec::new(),
             current_shape: vec![Vec2::ZERO],
         }
     }
 
     /// Move the "pen" to another part of the canvas without drawing a line.
-    pub fn move_to<P: Into<Vec2>>(mut self, point: P) -> Self {
+    pub fn move_to<P: IntoPoint>(mut self, point: P) -> Self {
         if self.current_shape.len() > 1 {
             self.shapes.push(self.current_shape);
         }
 
         self.current_shape = Vec::new();
-        self.current_shape.push(point.into());
+        self.current_shape.push(point.into_point());
 
         self
     }
 
     /// Draw a straight line to another spot on the canvas.
-    pub fn line_to<P: Into<Vec2>>(mut self, point: P) -> Self {
-        let point = point.into();
+    pub fn line_to<P: IntoPoint>(mut self, point: P) -> Self {
+        let point = point.into_point();
         if self.current_shape[self.current_shape.len() - 1] != point {
             self.current_shape.push(point);
         }
@@ -43,10 +45,10 @@ impl PathBuilder {
     }
 
     /// Draw a quadratic bezier curve to another spot on the canvas.
-    pub fn quadratic_bezier_to<P: Into<Vec2>>(mut self, end_point: P, control_point: P) -> Self {
+    pub fn quadratic_bezier_to<P: IntoPoint>(mut self, end_point: P, control_point: P) -> Self {
         let start_point = self.current_shape[self.current_shape.len() - 1];
-        let end_point = end_point.into();
-        let control_point = control_point.into();
+        let end_point = end_point.into_point();
+        let control_point = control_point.into_point();
 
         let curve_length = start_point.distance(control_point) + control_point.distance(end_point);
         let point_count = curve_length * self.points_per_unit as f32;
@@ -64,16 +66,16 @@ impl PathBuilder {
     }
 
     /// Draw a cubic bezier curve to another spot on the canvas.
-    pub fn cubic_bezier_to<P: Into<Vec2>>(
+    pub fn cubic_bezier_to<P: IntoPoint>(
         mut self,
         end_point: P,
         control_point_0: P,
         control_point_1: P,
     ) -> Self {
         let start_point = self.current_shape[self.current_shape.len() - 1];
-        let end_point = end_point.into();
-        let control_point_0 = control_point_0.into();
-        let control_point_1 = control_point_1.into();
+        let end_point = end_point.into_point();
+        let control_point_0 = control_point_0.into_point();
+        let control_point_1 = control_point_1.into_point();
 
         let curve_length = start_point.distance(control_point_0)
             + control_point_0.distance(control_point_1)
@@ -105,6 +107,14 @@ impl PathBuilder {
         }
     }
 
+    /// Set how overlapping parts of the path are filled.
+    ///
+    /// Use [FillRule::EvenOdd] to cut holes with inner paths regardless of their direction.
+    pub fn fill_rule(mut self, fill_rule: FillRule) -> Self {
+        self.fill_rule = fill_rule;
+        self
+    }
+
     /// Close the path.
     pub fn close(self) -> Self {
         let first_point = self.first_point();
@@ -124,13 +134,12 @@ impl PathBuilder {
 
         // We have to make a seperate shape for the fill to make sure we get the whole thing.
         if let Some(fill) = fill {
-            let mut fill_shape = Vec::with_capacity(raw_shapes.iter().map(|v| v.len()).sum());
-
-            for shape in raw_shapes.iter() {
-                fill_shape.append(&mut shape.clone());
-            }
-
-            destination_canvas.draw_shape(fill_shape, None, Some(fill));
+            destination_canvas.draw_compound_shape(
+                raw_shapes.iter().cloned(),
+                None,
+                Some(fill),
+                self.fill_rule,
+            );
         }
 
         for shape in raw_shapes.drain(..) {
@@ -151,13 +160,12 @@ impl PathBuilder {
 
         // We have to make a seperate shape for the fill to make sure we get the whole thing.
         if let Some(fill) = fill {
-            let mut fill_shape = Vec::with_capacity(raw_shapes.iter().map(|v| v.len()).sum());
-
-            for shape in raw_shapes.iter() {
-                fill_shape.append(&mut shape.clone());
-            }
-
-            destination_canvas.draw_shape_absolute(fill_shape, None, Some(fill));
+            destination_canvas.draw_compound_shape_absolute(
+                raw_shapes.iter().cloned(),
+                None,
+                Some(fill),
+                self.fill_rule,
+            );
         }
 
         for shape in raw_shapes.drain(..) {
@@ -0,0 +1,187 @@
+use glam::{DVec2, IVec2, Vec2};
+
+/// Anything that can be drawn as a point: a [Vec2], a pair of numbers as a tuple or array, or a reference to one.
+///
+/// All of the drawing methods take their points through this trait, so data in `f64` or `i32` can be drawn without
+/// mapping it into [Vec2]s first. Points are narrowed to `f32`, so bring large `f64` coordinates in through a [LocalFrame].
+///
+/// The drawing methods used to take `Into<Vec2>`, so a type of your own that only implements `Into<Vec2>` needs an
+/// `IntoPoint` impl to keep drawing. There's no blanket impl for `Into<Vec2>` types, because it would overlap with the
+/// impls for tuples, arrays and [DVec2] under Rust's coherence rules, since `glam` is free to add more `From` impls for [Vec2].
+pub trait IntoPoint {
+    /// Convert into a [Vec2].
+    fn into_point(self) -> Vec2;
+}
+
+impl IntoPoint for Vec2 {
+    fn into_point(self) -> Vec2 {
+        self
+    }
+}
+
+impl IntoPoint for DVec2 {
+    fn into_point(self) -> Vec2 {
+        self.as_vec2()
+    }
+}
+
+impl IntoPoint for IVec2 {
+    fn into_point(self) -> Vec2 {
+        self.as_vec2()
+    }
+}
+
+macro_rules! impl_into_point {
+    ($($number:ty),*) => {
+        $(
+            impl IntoPoint for ($number, $number) {
+                fn into_point(self) -> Vec2 {
+                    Vec2::new(self.0 as f32, self.1 as f32)
+                }
+            }
+
+            impl IntoPoint for [$number; 2] {
+                fn into_point(self) -> Vec2 {
+                    Vec2::new(self[0] as f32, self[1] as f32)
+                }
+            }
+        )*
+    };
+}
+
+impl_into_point!(f32, f64, i32);
+
+impl<T: IntoPoint + Copy> IntoPoint for &T {
+    fn into_point(self) -> Vec2 {
+        (*self).into_point()
+    }
+}
+
+/// Anything that can be drawn as a list of points: a [Vec], array, slice or iterator of anything that is [IntoPoint].
+pub trait IntoPoints {
+    /// Convert into a list of [Vec2]s.
+    fn into_points(self) -> Vec<Vec2>;
+}
+
+impl<I> IntoPoints for I
+where
+    I: IntoIterator,
+    I::Item: IntoPoint,
+{
+    fn into_points(self) -> Vec<Vec2> {
+        self.into_iter().map(IntoPoint::into_point).collect()
+    }
+}
+
+/// A frame of reference that brings `f64` data, such as scientific measurements, onto a canvas without losing precision.
+///
+/// Converting large coordinates straight to `f32` rounds away their fine detail: near a million, `f32` can only step
+/// by about a tenth. A frame subtracts its `origin` while still in `f64`, so only the small offsets from it are
+/// narrowed. Put the origin in the middle of the region being looked at, such as with [around](Self::around),
+/// then draw the converted points as usual.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LocalFrame {
+    /// The point in the data that lands on the origin of the canvas.
+    pub origin: DVec2,
+    /// How many canvas units one unit of the data covers.
+    pub scale: f64,
+}
+
+impl LocalFrame {
+    /// Create a new [LocalFrame] at `origin`, with the data at its own scale.
+    pub fn new<P: Into<DVec2>>(origin: P) -> Self {
+        Self {
+            origin: origin.into(),
+            scale: 1.0,
+        }
+    }
+
+    /// Create a new [LocalFrame] at the center of the bounds of `points`, or at zero if there are none.
+    pub fn around<I>(points: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<DVec2>,
+    {
+        let bounds = points
+            .into_iter()
+            .map(Into::into)
+            .fold(None, |bounds, point: DVec2| {
+                Some(match bounds {
+                    Some((min, max)) => (point.min(min), point.max(max)),
+                    None => (point, point),
+                })
+            });
+
+        match bounds {
+            Some((min, max)) => Self::new((min + max) / 2.0),
+            None => Self::new(DVec2::ZERO),
+        }
+    }
+
+    /// Set how many canvas units one unit of the data covers, consuming the parent.
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Bring a point of the data onto the canvas.
+    pub fn point<P: Into<DVec2>>(&self, point: P) -> Vec2 {
+        ((point.into() - self.origin) * self.scale).as_vec2()
+    }
+
+    /// Bring a list of points of the data onto the canvas, ready to draw.
+    pub fn points<I>(&self, points: I) -> Vec<Vec2>
+    where
+        I: IntoIterator,
+        I::Item: Into<DVec2>,
+    {
+        points.into_iter().map(|point| self.point(point)).collect()
+    }
+
+    /// Take a point on the canvas back to the data, such as to label it.
+    pub fn unproject(&self, point: Vec2) -> DVec2 {
+        point.as_dvec2() / self.scale + self.origin
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Canvas, Color};
+
+    /// Verify that points in any numeric type, singly or in lists, draw the same as [Vec2]s.
+    #[test]
+    fn mixed_points() {
+        let expected = vec![Vec2::ZERO, Vec2::new(1.0, 2.0), Vec2::new(3.0, 0.0)];
+        let doubles = [(0.0f64, 0.0f64), (1.0, 2.0), (3.0, 0.0)];
+        let integers = vec![[0, 0], [1, 2], [3, 0]];
+
+        assert_eq!(doubles.into_points(), expected);
+        assert_eq!(integers.into_points(), expected);
+        assert_eq!(expected.as_slice().into_points(), expected);
+        assert_eq!(DVec2::new(1.0, 2.0).into_point(), expected[1]);
+
+        let mut canvas = Canvas::new(1);
+        canvas.draw_polygon_absolute(doubles.as_slice(), Color::black());
+        canvas.draw_circle_absolute([1i32, 2], 1.0, None, Some(Color::black()));
+        assert_eq!(canvas.as_raw()[0].points[..3], expected);
+        assert_eq!(canvas.as_raw()[1].points[0], Vec2::new(2.0, 2.0));
+    }
+
+    /// Verify that a frame keeps the fine detail of large coordinates that converting straight to `f32` loses.
+    #[test]
+    fn local_frame() {
+        let data = [(1e7, 5e6), (1e7 + 0.01, 5e6 + 0.02), (1e7 + 0.02, 5e6)];
+        assert_eq!(data[0].into_point(), data[1].into_point());
+
+        let frame = LocalFrame::around(data).with_scale(100.0);
+        assert_eq!(frame.origin, DVec2::new(1e7 + 0.01, 5e6 + 0.01));
+        let points = frame.points(data);
+        assert!(points[0].abs_diff_eq(Vec2::new(-1.0, -1.0), 1e-3));
+        assert!(points[1].abs_diff_eq(Vec2::new(0.0, 1.0), 1e-3));
+        assert!(frame
+            .unproject(points[2])
+            .abs_diff_eq(DVec2::from(data[2]), 1e-6));
+    }
+}
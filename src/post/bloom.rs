@@ -0,0 +1,132 @@
+use image::RgbaImage;
+
+use super::{from_unit, to_unit, Effect};
+
+/// Make bright areas of an image glow, spilling light onto their surroundings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bloom {
+    /// Brightness, from 0.0 to 1.0, above which a pixel starts to glow.
+    pub threshold: f32,
+    /// How far the glow spreads, in pixels.
+    pub radius: u32,
+    /// How strongly the glow is added back onto the image.
+    pub intensity: f32,
+}
+
+impl Bloom {
+    /// Create a new [Bloom].
+    pub fn new(threshold: f32, radius: u32, intensity: f32) -> Self {
+        Self {
+            threshold,
+            radius,
+            intensity,
+        }
+    }
+}
+
+impl Effect for Bloom {
+    fn apply(&self, image: &mut RgbaImage) {
+        let (width, height) = (image.width() as usize, image.height() as usize);
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        // Keep only the light above the threshold.
+        let mut glow: Vec<[f32; 3]> = image
+            .pixels()
+            .map(|pixel| {
+                let [r, g, b, _] = pixel.0.map(to_unit);
+                let luma = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+                let excess = (luma - self.threshold).max(0.0) / luma.max(f32::EPSILON);
+                [r * excess, g * excess, b * excess]
+            })
+            .collect();
+
+        // Three box blurs come close to a gaussian.
+        let box_radius = (self.radius / 3).max(1) as usize;
+        for _ in 0..3 {
+            blur(&mut glow, width, height, box_radius, 1, width);
+            blur(&mut glow, height, width, box_radius, width, 1);
+        }
+
+        for (pixel, glow) in image.pixels_mut().zip(glow) {
+            for (channel, glow) in pixel.0[..3].iter_mut().zip(glow) {
+                *channel = from_unit(to_unit(*channel) + glow * self.intensity);
+            }
+        }
+    }
+}
+
+/// Box blur every line of a buffer in place.
+///
+/// Each of the `lines` lines has `len` pixels, `step` apart, and consecutive lines start `stride` apart.
+fn blur(
+    buffer: &mut [[f32; 3]],
+    len: usize,
+    lines: usize,
+    radius: usize,
+    step: usize,
+    stride: usize,
+) {
+    let mut line = vec![[0.0; 3]; len];
+    let window = (radius * 2 + 1) as f32;
+
+    for start in (0..lines).map(|index| index * stride) {
+        for (i, pixel) in line.iter_mut().enumerate() {
+            *pixel = buffer[start + i * step];
+        }
+
+        // Slide a running sum along the line, clamping at the ends.
+        let at = |i: isize| line[i.clamp(0, len as isize - 1) as usize];
+        let mut sum = [0.0; 3];
+        for i in -(radius as isize)..=radius as isize {
+            add(&mut sum, at(i), 1.0);
+        }
+
+        for i in 0..len {
+            buffer[start + i * step] = sum.map(|channel| channel / window);
+
+            let i = i as isize;
+            add(&mut sum, at(i + radius as isize + 1), 1.0);
+            add(&mut sum, at(i - radius as isize), -1.0);
+        }
+    }
+}
+
+fn add(sum: &mut [f32; 3], pixel: [f32; 3], sign: f32) {
+    for (sum, channel) in sum.iter_mut().zip(pixel) {
+        *sum += channel * sign;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    /// Verify that light spills from a bright spot onto its dark neighbours, but dark images are left alone.
+    #[test]
+    fn bloom_spreads_light() {
+        let mut image = RgbaImage::from_pixel(15, 15, Rgba([0, 0, 0, 255]));
+        image.put_pixel(7, 7, Rgba([255, 255, 255, 255]));
+
+        let bloom = Bloom::new(0.5, 6, 4.0);
+        bloom.apply(&mut image);
+        assert!(image.get_pixel(9, 7)[0] > 0);
+        assert_eq!(image.get_pixel(0, 0)[0], 0);
+
+        let mut dark = RgbaImage::from_pixel(4, 4, Rgba([100, 100, 100, 255]));
+        bloom.apply(&mut dark);
+        assert_eq!(dark.get_pixel(2, 2), &Rgba([100, 100, 100, 255]));
+    }
+
+    /// Verify that empty images are left alone.
+    #[test]
+    fn bloom_empty_image() {
+        for (width, height) in [(0, 0), (0, 5), (5, 0)] {
+            let mut image = RgbaImage::new(width, height);
+            Bloom::new(0.5, 6, 4.0).apply(&mut image);
+            assert_eq!(image.dimensions(), (width, height));
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use image::RgbaImage;
+
+use super::Effect;
+use crate::rng::Rng;
+
+/// Sprinkle monochrome noise over an image, like the grain of photographic film.
+///
+/// The grain is seeded, so the same seed always gives the same image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FilmGrain {
+    /// The largest change, in 8-bit levels, the grain makes to a pixel.
+    pub amount: u8,
+    /// Seed for the random pattern.
+    pub seed: u64,
+}
+
+impl FilmGrain {
+    /// Create a new [FilmGrain].
+    pub fn new(amount: u8, seed: u64) -> Self {
+        Self { amount, seed }
+    }
+}
+
+impl Effect for FilmGrain {
+    fn apply(&self, image: &mut RgbaImage) {
+        let mut rng = Rng::new(self.seed);
+        let amount = self.amount as f32;
+
+        for pixel in image.pixels_mut() {
+            // The same offset on every channel keeps the grain free of color speckles.
+            let noise = rng.range(-amount, amount).round() as i16;
+            for channel in &mut pixel.0[..3] {
+                *channel = (*channel as i16 + noise).clamp(0, 255) as u8;
+            }
+        }
+    }
+}
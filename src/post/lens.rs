@@ -0,0 +1,120 @@
+use image::{Rgba, RgbaImage};
+
+use super::{distance_from_center, from_unit, to_unit, Effect};
+
+/// Darken the edges of an image, drawing the eye to its center.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vignette {
+    /// How dark the corners become, from 0.0 (unchanged) to 1.0 (black).
+    pub strength: f32,
+    /// How far from the center the darkening begins, where 1.0 is a corner.
+    pub radius: f32,
+}
+
+impl Vignette {
+    /// Create a new [Vignette].
+    pub fn new(strength: f32, radius: f32) -> Self {
+        Self { strength, radius }
+    }
+}
+
+impl Effect for Vignette {
+    fn apply(&self, image: &mut RgbaImage) {
+        let radius = self.radius.clamp(0.0, 1.0);
+
+        for y in 0..image.height() {
+            for x in 0..image.width() {
+                let distance = distance_from_center(image, x, y);
+                let t = ((distance - radius) / (1.0 - radius).max(f32::EPSILON)).clamp(0.0, 1.0);
+                let darken = 1.0 - self.strength * t * t * (3.0 - 2.0 * t);
+
+                let pixel = image.get_pixel_mut(x, y);
+                for channel in &mut pixel.0[..3] {
+                    *channel = from_unit(to_unit(*channel) * darken);
+                }
+            }
+        }
+    }
+}
+
+/// Split the red and blue channels apart towards the edges of an image, like a cheap lens.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChromaticAberration {
+    /// How far, in pixels, the red channel is pushed outwards (and the blue channel inwards) at the corners.
+    pub offset: f32,
+}
+
+impl ChromaticAberration {
+    /// Create a new [ChromaticAberration].
+    pub fn new(offset: f32) -> Self {
+        Self { offset }
+    }
+}
+
+impl Effect for ChromaticAberration {
+    fn apply(&self, image: &mut RgbaImage) {
+        let source = image.clone();
+        let center_x = image.width() as f32 / 2.0;
+        let center_y = image.height() as f32 / 2.0;
+        let corner = (center_x * center_x + center_y * center_y).sqrt().max(1.0);
+
+        for y in 0..image.height() {
+            for x in 0..image.width() {
+                let dx = x as f32 + 0.5 - center_x;
+                let dy = y as f32 + 0.5 - center_y;
+
+                // Shift proportionally to the distance from the center, so the middle stays sharp.
+                let shift = self.offset / corner;
+                let red = sample(&source, x as f32 - dx * shift, y as f32 - dy * shift);
+                let blue = sample(&source, x as f32 + dx * shift, y as f32 + dy * shift);
+
+                let pixel = image.get_pixel_mut(x, y);
+                pixel.0[0] = red[0];
+                pixel.0[2] = blue[2];
+            }
+        }
+    }
+}
+
+/// Bilinearly sample an image at a pixel position, clamping to its edges.
+fn sample(image: &RgbaImage, x: f32, y: f32) -> [u8; 4] {
+    let max_x = image.width() as f32 - 1.0;
+    let max_y = image.height() as f32 - 1.0;
+    let (x, y) = (x.clamp(0.0, max_x), y.clamp(0.0, max_y));
+    let (x0, y0) = (x.floor(), y.floor());
+    let (tx, ty) = (x - x0, y - y0);
+
+    let at =
+        |x: f32, y: f32| -> &Rgba<u8> { image.get_pixel(x.min(max_x) as u32, y.min(max_y) as u32) };
+    let corners = [
+        at(x0, y0),
+        at(x0 + 1.0, y0),
+        at(x0, y0 + 1.0),
+        at(x0 + 1.0, y0 + 1.0),
+    ];
+
+    let mut result = [0; 4];
+    for (channel, value) in result.iter_mut().enumerate() {
+        let top = corners[0][channel] as f32 * (1.0 - tx) + corners[1][channel] as f32 * tx;
+        let bottom = corners[2][channel] as f32 * (1.0 - tx) + corners[3][channel] as f32 * tx;
+        *value = (top * (1.0 - ty) + bottom * ty).round() as u8;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that a vignette leaves the center alone and darkens the corners.
+    #[test]
+    fn vignette_darkens_corners() {
+        let mut image = RgbaImage::from_pixel(9, 9, Rgba([200, 200, 200, 255]));
+        Vignette::new(0.5, 0.2).apply(&mut image);
+
+        assert_eq!(image.get_pixel(4, 4), &Rgba([200, 200, 200, 255]));
+        assert!(image.get_pixel(0, 0)[0] < 120);
+        assert_eq!(image.get_pixel(0, 0)[3], 255);
+    }
+}
@@ -0,0 +1,99 @@
+mod bloom;
+mod grain;
+mod lens;
+mod shader;
+mod tone;
+mod vision;
+
+pub use bloom::Bloom;
+pub use grain::FilmGrain;
+pub use lens::{ChromaticAberration, Vignette};
+pub use shader::PixelShader;
+pub use tone::{Curves, Levels};
+
+use image::RgbaImage;
+
+/// An effect that runs over a whole rendered image, such as a [Vignette] or [Bloom].
+pub trait Effect {
+    /// Apply the effect to `image` in place.
+    fn apply(&self, image: &mut RgbaImage);
+}
+
+/// A chain of [Effects](Effect), run in order over a rendered image.
+///
+/// Because the chain is part of the program, the same figure always comes out with the same finish.
+#[derive(Default)]
+pub struct PostProcess {
+    effects: Vec<Box<dyn Effect>>,
+}
+
+impl PostProcess {
+    /// Create a new, empty [PostProcess] chain.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an effect to the end of the chain, consuming the parent.
+    pub fn with_effect<E: Effect + 'static>(mut self, effect: E) -> Self {
+        self.effects.push(Box::new(effect));
+        self
+    }
+
+    /// Run every effect over `image`, in the order they were added.
+    pub fn apply(&self, image: &mut RgbaImage) {
+        for effect in &self.effects {
+            effect.apply(image);
+        }
+    }
+}
+
+impl Effect for PostProcess {
+    fn apply(&self, image: &mut RgbaImage) {
+        PostProcess::apply(self, image);
+    }
+}
+
+/// Convert a channel to `0.0..=1.0`.
+fn to_unit(channel: u8) -> f32 {
+    channel as f32 / 255.0
+}
+
+/// Convert a channel from `0.0..=1.0`, clamping anything outside of it.
+fn from_unit(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// The distance of a pixel from the center of the image, where `1.0` is a corner.
+fn distance_from_center(image: &RgbaImage, x: u32, y: u32) -> f32 {
+    let (width, height) = (image.width() as f32, image.height() as f32);
+    let dx = (x as f32 + 0.5) / width * 2.0 - 1.0;
+    let dy = (y as f32 + 0.5) / height * 2.0 - 1.0;
+
+    (dx * dx + dy * dy).sqrt() / std::f32::consts::SQRT_2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    /// Verify that effects run in the order they were added.
+    #[test]
+    fn effects_run_in_order() {
+        let mut image = RgbaImage::from_pixel(4, 4, Rgba([128, 128, 128, 255]));
+
+        // Crushing the shadows after boosting everything is different from the other way around.
+        PostProcess::new()
+            .with_effect(Levels::new(0.0, 0.5, 1.0))
+            .with_effect(Levels::new(0.5, 1.0, 1.0))
+            .apply(&mut image);
+        assert_eq!(image.get_pixel(0, 0), &Rgba([255, 255, 255, 255]));
+
+        let mut image = RgbaImage::from_pixel(4, 4, Rgba([128, 128, 128, 255]));
+        PostProcess::new()
+            .with_effect(Levels::new(0.5, 1.0, 1.0))
+            .with_effect(Levels::new(0.0, 0.5, 1.0))
+            .apply(&mut image);
+        assert_eq!(image.get_pixel(0, 0), &Rgba([2, 2, 2, 255]));
+    }
+}
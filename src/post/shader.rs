@@ -0,0 +1,86 @@
+use glam::Vec2;
+use image::{Rgba, RgbaImage};
+
+use super::Effect;
+use crate::Color;
+
+/// Run a closure over every pixel of an image, replacing each pixel with its result.
+///
+/// The closure is given the pixel's `x` and `y`, its `uv` coordinates (from `(0, 0)` at the top left to `(1, 1)` at the bottom right, measured to the pixel's center), and its current [Color].
+/// With the `rayon` feature, rows are shaded in parallel.
+pub struct PixelShader<F> {
+    shader: F,
+}
+
+impl<F> PixelShader<F>
+where
+    F: Fn(u32, u32, Vec2, Color) -> Color + Sync,
+{
+    /// Create a new [PixelShader] from a closure.
+    pub fn new(shader: F) -> Self {
+        Self { shader }
+    }
+
+    /// Shade one row of pixels in place.
+    fn shade_row(&self, y: u32, row: &mut [u8], size: Vec2) {
+        for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+            let x = x as u32;
+            let uv = (Vec2::new(x as f32, y as f32) + 0.5) / size;
+            let color = Color::from(Rgba([pixel[0], pixel[1], pixel[2], pixel[3]]));
+
+            let Rgba(shaded) = (self.shader)(x, y, uv, color).into();
+            pixel.copy_from_slice(&shaded);
+        }
+    }
+}
+
+impl<F> Effect for PixelShader<F>
+where
+    F: Fn(u32, u32, Vec2, Color) -> Color + Sync,
+{
+    fn apply(&self, image: &mut RgbaImage) {
+        let size = Vec2::new(image.width() as f32, image.height() as f32);
+        let row_len = image.width() as usize * 4;
+        if row_len == 0 {
+            return;
+        }
+
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+
+            image
+                .par_chunks_mut(row_len)
+                .enumerate()
+                .for_each(|(y, row)| self.shade_row(y as u32, row, size));
+        }
+
+        #[cfg(not(feature = "rayon"))]
+        for (y, row) in image.chunks_mut(row_len).enumerate() {
+            self.shade_row(y as u32, row, size);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that the shader sees each pixel's position and color, and its result is written back.
+    #[test]
+    fn shade_every_pixel() {
+        let mut image = RgbaImage::from_pixel(4, 2, Rgba([0, 0, 255, 255]));
+
+        PixelShader::new(|x, y, uv, color| {
+            assert_eq!(
+                uv,
+                Vec2::new(x as f32 + 0.5, y as f32 + 0.5) / Vec2::new(4.0, 2.0)
+            );
+            Color::new(uv.x, y as f32, color.b(), 1.0)
+        })
+        .apply(&mut image);
+
+        assert_eq!(image.get_pixel(0, 0), &Rgba([31, 0, 255, 255]));
+        assert_eq!(image.get_pixel(3, 1), &Rgba([223, 255, 255, 255]));
+    }
+}
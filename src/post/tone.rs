@@ -0,0 +1,122 @@
+use image::RgbaImage;
+
+use super::{from_unit, to_unit, Effect};
+
+/// Remap the tones of an image: stretch `black..=white` to fill the full range, then bend the midtones with `gamma`.
+///
+/// Alpha is left untouched.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Levels {
+    /// Input level, from 0.0 to 1.0, that becomes pure black.
+    pub black: f32,
+    /// Input level, from 0.0 to 1.0, that becomes pure white.
+    pub white: f32,
+    /// Values above 1.0 brighten the midtones, and values below darken them.
+    pub gamma: f32,
+}
+
+impl Levels {
+    /// Create a new [Levels] effect.
+    pub fn new(black: f32, white: f32, gamma: f32) -> Self {
+        Self {
+            black,
+            white,
+            gamma,
+        }
+    }
+
+    fn map(&self, value: f32) -> f32 {
+        let range = (self.white - self.black).max(f32::EPSILON);
+        let value = ((value - self.black) / range).clamp(0.0, 1.0);
+
+        value.powf(1.0 / self.gamma.max(f32::EPSILON))
+    }
+}
+
+impl Effect for Levels {
+    fn apply(&self, image: &mut RgbaImage) {
+        apply_table(image, &lookup_table(|value| self.map(value)));
+    }
+}
+
+/// Remap the tones of an image along a curve through several `(input, output)` points, each from 0.0 to 1.0.
+///
+/// The curve is straight between points, and flat beyond the first and last. Alpha is left untouched.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Curves {
+    points: Vec<(f32, f32)>,
+}
+
+impl Curves {
+    /// Create a new [Curves] effect. Points do not need to be sorted.
+    ///
+    /// With no points, the image is left unchanged.
+    pub fn new<I: IntoIterator<Item = (f32, f32)>>(points: I) -> Self {
+        let mut points: Vec<_> = points
+            .into_iter()
+            .filter(|(input, output)| input.is_finite() && output.is_finite())
+            .collect();
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        Self { points }
+    }
+
+    fn map(&self, value: f32) -> f32 {
+        let (first, last) = match (self.points.first(), self.points.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return value,
+        };
+
+        if value <= first.0 {
+            return first.1;
+        }
+
+        for pair in self.points.windows(2) {
+            let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+            if value <= x1 {
+                let t = (value - x0) / (x1 - x0).max(f32::EPSILON);
+                return y0 + (y1 - y0) * t;
+            }
+        }
+
+        last.1
+    }
+}
+
+impl Effect for Curves {
+    fn apply(&self, image: &mut RgbaImage) {
+        apply_table(image, &lookup_table(|value| self.map(value)));
+    }
+}
+
+fn lookup_table<F: Fn(f32) -> f32>(f: F) -> [u8; 256] {
+    let mut table = [0; 256];
+    for (value, entry) in table.iter_mut().enumerate() {
+        *entry = from_unit(f(to_unit(value as u8)));
+    }
+
+    table
+}
+
+fn apply_table(image: &mut RgbaImage, table: &[u8; 256]) {
+    for pixel in image.pixels_mut() {
+        for channel in &mut pixel.0[..3] {
+            *channel = table[*channel as usize];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that curves pass through their points and interpolate between them.
+    #[test]
+    fn curves_interpolate() {
+        let curves = Curves::new([(1.0, 0.5), (0.0, 0.0), (0.5, 0.5)]);
+
+        assert_eq!(curves.map(0.25), 0.25);
+        assert_eq!(curves.map(0.75), 0.5);
+        assert_eq!(Curves::new([]).map(0.3), 0.3);
+    }
+}
@@ -0,0 +1,13 @@
+use image::RgbaImage;
+
+use super::Effect;
+use crate::{vision::ColorVisionDeficiency, Color};
+
+/// Simulate how the image looks with a [ColorVisionDeficiency], to check a finished figure for accessibility.
+impl Effect for ColorVisionDeficiency {
+    fn apply(&self, image: &mut RgbaImage) {
+        for pixel in image.pixels_mut() {
+            *pixel = self.simulate(Color::from(*pixel)).into();
+        }
+    }
+}
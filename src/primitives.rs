@@ -0,0 +1,285 @@
+use std::f32::consts::{PI, TAU};
+
+use glam::Vec2;
+
+use crate::CustomShape;
+
+/// A superellipse: the curve `|x / a|^n + |y / b|^n = 1`, between a diamond, an ellipse and a rectangle.
+///
+/// An exponent of 2 is an ellipse, 4 is a squircle, and larger exponents get closer to a rectangle with rounded corners.
+/// Exponents below 2 pinch the sides in towards a diamond at 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Superellipse {
+    /// The center of the shape.
+    pub center: Vec2,
+    /// Half the width and height of the shape.
+    pub radii: Vec2,
+    /// How square the shape is.
+    pub exponent: f32,
+    /// How many points go around the outline.
+    pub segments: usize,
+}
+
+impl Superellipse {
+    /// Create a new [Superellipse], with 64 points around its outline.
+    pub fn new(center: Vec2, radii: Vec2, exponent: f32) -> Self {
+        Self {
+            center,
+            radii,
+            exponent,
+            segments: 64,
+        }
+    }
+
+    /// Create a new squircle: a [Superellipse] with an exponent of 4, as wide as it is tall.
+    pub fn squircle(center: Vec2, radius: f32) -> Self {
+        Self::new(center, Vec2::splat(radius), 4.0)
+    }
+
+    /// Set how many points go around the outline, consuming the parent.
+    pub fn with_segments(mut self, segments: usize) -> Self {
+        self.segments = segments;
+        self
+    }
+}
+
+impl CustomShape for Superellipse {
+    fn to_paths(&self) -> Vec<Vec<Vec2>> {
+        let segments = self.segments.max(4);
+        let power = 2.0 / self.exponent;
+        let mut outline: Vec<Vec2> = (0..segments)
+            .map(|i| {
+                let (sin, cos) = (TAU * i as f32 / segments as f32).sin_cos();
+                let unit = Vec2::new(
+                    cos.signum() * cos.abs().powf(power),
+                    sin.signum() * sin.abs().powf(power),
+                );
+                self.center + unit * self.radii
+            })
+            .collect();
+        outline.push(outline[0]);
+        vec![outline]
+    }
+}
+
+/// A spur gear, with involute teeth like a real one, for mechanical sketches.
+///
+/// The size of the teeth is set by the module: the pitch diameter per tooth. Gears with the same module and pressure angle mesh.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Gear {
+    /// The center of the gear.
+    pub center: Vec2,
+    /// How many teeth go around the gear.
+    pub teeth: usize,
+    /// The pitch diameter per tooth, which sets the size of the teeth.
+    pub module: f32,
+    /// The angle of the teeth's faces where they push against another gear's, in radians.
+    pub pressure_angle: f32,
+    /// The radius of the hole through the middle of the gear, if any.
+    pub bore: Option<f32>,
+    /// How many points go along each face of a tooth.
+    pub segments: usize,
+}
+
+impl Gear {
+    /// Create a new [Gear], with the standard pressure angle of 20 degrees and no bore.
+    pub fn new(center: Vec2, teeth: usize, module: f32) -> Self {
+        Self {
+            center,
+            teeth,
+            module,
+            pressure_angle: 20f32.to_radians(),
+            bore: None,
+            segments: 8,
+        }
+    }
+
+    /// Set the pressure angle, in radians, consuming the parent.
+    pub fn with_pressure_angle(mut self, pressure_angle: f32) -> Self {
+        self.pressure_angle = pressure_angle;
+        self
+    }
+
+    /// Cut a hole through the middle of the gear, consuming the parent.
+    pub fn with_bore(mut self, radius: f32) -> Self {
+        self.bore = Some(radius);
+        self
+    }
+
+    /// Set how many points go along each face of a tooth, consuming the parent.
+    pub fn with_segments(mut self, segments: usize) -> Self {
+        self.segments = segments;
+        self
+    }
+
+    /// The radius where the teeth of meshing gears meet.
+    pub fn pitch_radius(&self) -> f32 {
+        self.module * self.teeth as f32 / 2.0
+    }
+
+    /// The radius of the tips of the teeth.
+    pub fn outer_radius(&self) -> f32 {
+        self.pitch_radius() + self.module
+    }
+
+    /// The radius of the gaps between the teeth.
+    pub fn root_radius(&self) -> f32 {
+        self.pitch_radius() - 1.25 * self.module
+    }
+}
+
+impl CustomShape for Gear {
+    fn to_paths(&self) -> Vec<Vec<Vec2>> {
+        let teeth = self.teeth.max(1);
+        let segments = self.segments.max(1);
+        let outer = self.outer_radius();
+        let root = self.root_radius().max(0.0);
+        let base = self.pitch_radius() * self.pressure_angle.cos();
+        let polar =
+            |radius: f32, angle: f32| self.center + Vec2::new(angle.cos(), angle.sin()) * radius;
+
+        // Each face is an involute of the base circle, rolled out until it reaches the tips.
+        let roll = ((outer / base).powi(2) - 1.0).max(0.0).sqrt();
+        let involute = |t: f32| (base * (1.0 + t * t).sqrt(), t - t.atan());
+        // Half the angle a tooth covers on the base circle, so that it is half the pitch wide at the pitch circle.
+        let involute_angle = self.pressure_angle.tan() - self.pressure_angle;
+        let half_tooth = PI / (2.0 * teeth as f32) + involute_angle;
+
+        let mut outline = Vec::new();
+        for tooth in 0..teeth {
+            let middle = TAU * tooth as f32 / teeth as f32;
+            let (rising, falling) = (middle - half_tooth, middle + half_tooth);
+
+            if root < base {
+                outline.push(polar(root, rising));
+            }
+            for i in 0..=segments {
+                let (radius, angle) = involute(roll * i as f32 / segments as f32);
+                outline.push(polar(radius.max(root), rising + angle));
+            }
+            for i in (0..=segments).rev() {
+                let (radius, angle) = involute(roll * i as f32 / segments as f32);
+                outline.push(polar(radius.max(root), falling - angle));
+            }
+            if root < base {
+                outline.push(polar(root, falling));
+            }
+        }
+        outline.push(outline[0]);
+
+        let mut paths = vec![outline];
+        if let Some(bore) = self.bore {
+            // Wound clockwise, against the outline, so it cuts a hole.
+            let sides = (segments * teeth).max(16);
+            let hole: Vec<Vec2> = (0..=sides)
+                .map(|i| polar(bore, -TAU * i as f32 / sides as f32))
+                .collect();
+            paths.push(hole);
+        }
+        paths
+    }
+}
+
+/// A capsule, or stadium: a line segment thickened into a rectangle with semicircular ends.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Capsule {
+    /// The center of one end.
+    pub start: Vec2,
+    /// The center of the other end.
+    pub end: Vec2,
+    /// Half the width of the capsule, and the radius of its ends.
+    pub radius: f32,
+    /// How many points go around each end.
+    pub segments: usize,
+}
+
+impl Capsule {
+    /// Create a new [Capsule], with 16 points around each end.
+    pub fn new(start: Vec2, end: Vec2, radius: f32) -> Self {
+        Self {
+            start,
+            end,
+            radius,
+            segments: 16,
+        }
+    }
+
+    /// Set how many points go around each end, consuming the parent.
+    pub fn with_segments(mut self, segments: usize) -> Self {
+        self.segments = segments;
+        self
+    }
+}
+
+impl CustomShape for Capsule {
+    fn to_paths(&self) -> Vec<Vec<Vec2>> {
+        let segments = self.segments.max(2);
+        let direction = (self.end - self.start).try_normalize().unwrap_or(Vec2::X);
+        let heading = direction.y.atan2(direction.x);
+
+        // Sweep around the end from one side to the other, then around the start.
+        let cap = |center: Vec2, from: f32| {
+            (0..=segments).map(move |i| {
+                let angle = from + PI * i as f32 / segments as f32;
+                center + Vec2::new(angle.cos(), angle.sin()) * self.radius
+            })
+        };
+        let mut outline: Vec<Vec2> = cap(self.end, heading - PI / 2.0)
+            .chain(cap(self.start, heading + PI / 2.0))
+            .collect();
+        outline.push(outline[0]);
+        vec![outline]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn radii(path: &[Vec2], center: Vec2) -> (f32, f32) {
+        path.iter()
+            .map(|point| point.distance(center))
+            .fold((f32::MAX, 0.0), |(min, max), radius| {
+                (min.min(radius), max.max(radius))
+            })
+    }
+
+    /// Verify that the primitives close their outlines and stay within their sizes.
+    #[test]
+    fn primitive_outlines() {
+        let center = Vec2::new(1.0, 2.0);
+
+        let ellipse = Superellipse::new(center, Vec2::new(2.0, 1.0), 2.0).to_paths();
+        assert_eq!(ellipse[0].len(), 65);
+        assert!(ellipse[0].iter().all(|point| {
+            let unit = (*point - center) / Vec2::new(2.0, 1.0);
+            (unit.length() - 1.0).abs() < 1e-5
+        }));
+        let squircle = Superellipse::squircle(center, 1.0).to_paths();
+        let corner = squircle[0][8] - center;
+        assert!((corner.x.powi(4) + corner.y.powi(4) - 1.0).abs() < 1e-5);
+        assert!(corner.length() > 1.1);
+
+        let gear = Gear::new(center, 12, 2.0).with_bore(3.0);
+        let paths = gear.to_paths();
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[0].first(), paths[0].last());
+        let (inner, outer) = radii(&paths[0], center);
+        assert!((inner - gear.root_radius()).abs() < 1e-4);
+        assert!((outer - gear.outer_radius()).abs() < 1e-4);
+        let (inner, outer) = radii(&paths[1], center);
+        assert!((inner - 3.0).abs() < 1e-4 && (outer - 3.0).abs() < 1e-4);
+
+        let capsule = Capsule::new(Vec2::ZERO, Vec2::new(4.0, 0.0), 1.0).to_paths();
+        let (min, max) = capsule[0].iter().fold(
+            (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+            |(min, max), point| (min.min(*point), max.max(*point)),
+        );
+        assert!(min.abs_diff_eq(Vec2::new(-1.0, -1.0), 1e-5));
+        assert!(max.abs_diff_eq(Vec2::new(5.0, 1.0), 1e-5));
+        assert_eq!(capsule[0].first(), capsule[0].last());
+    }
+}
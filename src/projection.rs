@@ -0,0 +1,354 @@
+use glam::Vec2;
+
+use crate::geometry::distance_to_segment;
+
+/// The furthest latitude, in degrees, that [WebMercator] can show. Beyond it the projection runs off to infinity.
+pub const MAX_MERCATOR_LATITUDE: f64 = 85.051_128_779_806_59;
+
+/// A way of flattening longitude/latitude coordinates onto the canvas.
+///
+/// Any `Fn(f64, f64) -> Vec2` closure taking `(longitude, latitude)` in degrees is also a [Projection].
+pub trait Projection {
+    /// Project a longitude and latitude, in degrees, into World Space.
+    fn project(&self, longitude: f64, latitude: f64) -> Vec2;
+
+    /// Whether a longitude and latitude, in degrees, can be seen at all, such as being on the near side of a globe.
+    ///
+    /// Everything is visible unless a projection says otherwise.
+    fn is_visible(&self, _longitude: f64, _latitude: f64) -> bool {
+        true
+    }
+}
+
+impl<F: Fn(f64, f64) -> Vec2> Projection for F {
+    fn project(&self, longitude: f64, latitude: f64) -> Vec2 {
+        self(longitude, latitude)
+    }
+}
+
+/// The Web Mercator projection used by most online maps.
+///
+/// The whole world fits in `-1..=1` on both axes, with north pointing up.
+/// Latitudes are clamped to [MAX_MERCATOR_LATITUDE].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WebMercator;
+
+impl Projection for WebMercator {
+    fn project(&self, longitude: f64, latitude: f64) -> Vec2 {
+        let latitude = latitude
+            .clamp(-MAX_MERCATOR_LATITUDE, MAX_MERCATOR_LATITUDE)
+            .to_radians();
+        let y = (std::f64::consts::FRAC_PI_4 + latitude / 2.0).tan().ln();
+
+        Vec2::new(
+            (longitude / 180.0) as f32,
+            (y / std::f64::consts::PI) as f32,
+        )
+    }
+}
+
+/// The equirectangular (plate carrée) projection, which maps longitude and latitude straight to x and y.
+///
+/// Longitude spans `-1..=1` on the x axis, and latitude is scaled equally, so the world is twice as wide as it is tall.
+/// Shapes keep their true proportions along the [standard parallel](Self::standard_parallel).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Equirectangular {
+    /// The latitude, in degrees, at which the projection is free of distortion.
+    pub standard_parallel: f64,
+}
+
+impl Equirectangular {
+    /// Create a new [Equirectangular] projection that is true to scale at `standard_parallel` degrees of latitude.
+    pub fn new(standard_parallel: f64) -> Self {
+        Self { standard_parallel }
+    }
+}
+
+impl Projection for Equirectangular {
+    fn project(&self, longitude: f64, latitude: f64) -> Vec2 {
+        let x = longitude * self.standard_parallel.to_radians().cos();
+
+        Vec2::new((x / 180.0) as f32, (latitude / 180.0) as f32)
+    }
+}
+
+/// The orthographic projection, showing the globe as a ball seen from far away, centered on a point of the Earth.
+///
+/// The visible hemisphere fills the unit circle, with north pointing up. The far side of the globe is not [visible](Projection::is_visible).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Orthographic {
+    /// The longitude, in degrees, at the center of the view.
+    pub center_longitude: f64,
+    /// The latitude, in degrees, at the center of the view.
+    pub center_latitude: f64,
+}
+
+impl Orthographic {
+    /// Create a new [Orthographic] projection centered on a longitude and latitude, in degrees.
+    pub fn new(center_longitude: f64, center_latitude: f64) -> Self {
+        Self {
+            center_longitude,
+            center_latitude,
+        }
+    }
+}
+
+impl Projection for Orthographic {
+    fn project(&self, longitude: f64, latitude: f64) -> Vec2 {
+        let (x, y, _) = azimuthal(
+            self.center_longitude,
+            self.center_latitude,
+            longitude,
+            latitude,
+        );
+        Vec2::new(x as f32, y as f32)
+    }
+
+    fn is_visible(&self, longitude: f64, latitude: f64) -> bool {
+        azimuthal(
+            self.center_longitude,
+            self.center_latitude,
+            longitude,
+            latitude,
+        )
+        .2 >= 0.0
+    }
+}
+
+/// The stereographic projection, which keeps angles true and draws every circle on the globe as a circle, centered on a point of the Earth.
+///
+/// The hemisphere around the center fills the unit circle, with north pointing up. Further out, everything grows without limit,
+/// so points more than [max_distance](Self::max_distance) from the center are not [visible](Projection::is_visible).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stereographic {
+    /// The longitude, in degrees, at the center of the view.
+    pub center_longitude: f64,
+    /// The latitude, in degrees, at the center of the view.
+    pub center_latitude: f64,
+    /// How far from the center points are still visible, in degrees around the globe.
+    pub max_distance: f64,
+}
+
+impl Stereographic {
+    /// Create a new [Stereographic] projection centered on a longitude and latitude, in degrees, showing everything within 150°.
+    pub fn new(center_longitude: f64, center_latitude: f64) -> Self {
+        Self {
+            center_longitude,
+            center_latitude,
+            max_distance: 150.0,
+        }
+    }
+}
+
+impl Default for Stereographic {
+    fn default() -> Self {
+        Self::new(0.0, 0.0)
+    }
+}
+
+impl Projection for Stereographic {
+    fn project(&self, longitude: f64, latitude: f64) -> Vec2 {
+        let (x, y, cos_distance) = azimuthal(
+            self.center_longitude,
+            self.center_latitude,
+            longitude,
+            latitude,
+        );
+        let scale = 1.0 / (1.0 + cos_distance);
+        Vec2::new((x * scale) as f32, (y * scale) as f32)
+    }
+
+    fn is_visible(&self, longitude: f64, latitude: f64) -> bool {
+        let cos_distance = azimuthal(
+            self.center_longitude,
+            self.center_latitude,
+            longitude,
+            latitude,
+        )
+        .2;
+        cos_distance >= self.max_distance.min(179.0).to_radians().cos()
+    }
+}
+
+/// The Mollweide projection, an oval map of the whole world that keeps areas true.
+///
+/// The world spans `-1..=1` on the x axis and `-0.5..=0.5` on the y axis, with north pointing up.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Mollweide {
+    /// The longitude, in degrees, down the middle of the map.
+    pub center_longitude: f64,
+}
+
+impl Mollweide {
+    /// Create a new [Mollweide] projection with `center_longitude` degrees down the middle.
+    pub fn new(center_longitude: f64) -> Self {
+        Self { center_longitude }
+    }
+}
+
+impl Projection for Mollweide {
+    fn project(&self, longitude: f64, latitude: f64) -> Vec2 {
+        use std::f64::consts::{FRAC_PI_2, PI};
+
+        let latitude = latitude.clamp(-90.0, 90.0).to_radians();
+        let mut longitude = longitude - self.center_longitude;
+        if longitude.abs() > 180.0 {
+            longitude = (longitude + 180.0).rem_euclid(360.0) - 180.0;
+        }
+
+        // Solve 2θ + sin 2θ = π sin φ for the auxiliary angle θ, which Newton's method finds quickly except right at the poles.
+        let target = PI * latitude.sin();
+        let mut theta = latitude;
+        for _ in 0..32 {
+            let step =
+                (2.0 * theta + (2.0 * theta).sin() - target) / (2.0 + 2.0 * (2.0 * theta).cos());
+            if !step.is_finite() || step.abs() < 1e-12 {
+                break;
+            }
+            theta -= step;
+        }
+        let theta = theta.clamp(-FRAC_PI_2, FRAC_PI_2);
+
+        Vec2::new(
+            (longitude / 180.0 * theta.cos()) as f32,
+            (theta.sin() / 2.0) as f32,
+        )
+    }
+}
+
+/// Project a longitude and latitude onto the plane touching the globe at a center, returning x, y and the cosine of the angle from the center.
+fn azimuthal(
+    center_longitude: f64,
+    center_latitude: f64,
+    longitude: f64,
+    latitude: f64,
+) -> (f64, f64, f64) {
+    let (sin_center, cos_center) = center_latitude.to_radians().sin_cos();
+    let (sin_latitude, cos_latitude) = latitude.to_radians().sin_cos();
+    let (sin_delta, cos_delta) = (longitude - center_longitude).to_radians().sin_cos();
+
+    (
+        cos_latitude * sin_delta,
+        cos_center * sin_latitude - sin_center * cos_latitude * cos_delta,
+        sin_center * sin_latitude + cos_center * cos_latitude * cos_delta,
+    )
+}
+
+/// Project a line of `(longitude, latitude)` points, in degrees, adding points wherever the projection bends it by more than `tolerance`.
+/// A `tolerance` of zero adds no points for bending.
+///
+/// The line is broken into separate pieces wherever it passes out of [view](Projection::is_visible), such as over the horizon of an [Orthographic] globe,
+/// and each piece ends as close to the edge of the view as it can. Segments with both ends out of view are left out.
+pub fn project_line<P: Projection + ?Sized>(
+    projection: &P,
+    line: &[(f64, f64)],
+    tolerance: f32,
+) -> Vec<Vec<Vec2>> {
+    let mut pieces = vec![Vec::new()];
+
+    if let Some(&(longitude, latitude)) = line.first() {
+        if projection.is_visible(longitude, latitude) {
+            pieces[0].push(projection.project(longitude, latitude));
+        }
+    }
+    for pair in line.windows(2) {
+        subdivide(projection, pair[0], pair[1], tolerance, 16, &mut pieces);
+    }
+
+    pieces.retain(|piece| piece.len() > 1);
+    pieces
+}
+
+/// Continue the last piece from `a` to `b`, splitting the segment in half until it is flat enough, or to find where it leaves or enters the view.
+fn subdivide<P: Projection + ?Sized>(
+    projection: &P,
+    a: (f64, f64),
+    b: (f64, f64),
+    tolerance: f32,
+    depth: u32,
+    pieces: &mut Vec<Vec<Vec2>>,
+) {
+    let visible = |(longitude, latitude): (f64, f64)| projection.is_visible(longitude, latitude);
+    let project = |(longitude, latitude): (f64, f64)| projection.project(longitude, latitude);
+    let middle = ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+
+    match (visible(a), visible(b)) {
+        (true, true) => {
+            let bent = !visible(middle)
+                || (tolerance > 0.0
+                    && distance_to_segment(project(middle), project(a), project(b)) > tolerance);
+            if depth > 0 && bent {
+                subdivide(projection, a, middle, tolerance, depth - 1, pieces);
+                subdivide(projection, middle, b, tolerance, depth - 1, pieces);
+            } else if let Some(piece) = pieces.last_mut() {
+                piece.push(project(b));
+            }
+        }
+        (false, false) => {}
+        _ if depth > 0 => {
+            subdivide(projection, a, middle, tolerance, depth - 1, pieces);
+            subdivide(projection, middle, b, tolerance, depth - 1, pieces);
+        }
+        (true, false) => pieces.push(Vec::new()),
+        (false, true) => pieces.push(vec![project(b)]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that Web Mercator maps the corners of the world onto the corners of the unit square.
+    #[test]
+    fn web_mercator_bounds() {
+        assert!(WebMercator.project(0.0, 0.0).abs().max_element() < 1e-6);
+
+        let corner = WebMercator.project(180.0, MAX_MERCATOR_LATITUDE);
+        assert!((corner - Vec2::ONE).abs().max_element() < 1e-5);
+
+        // Latitudes past the limit are clamped rather than running off to infinity.
+        assert_eq!(
+            WebMercator.project(0.0, 90.0),
+            WebMercator.project(0.0, MAX_MERCATOR_LATITUDE)
+        );
+    }
+
+    /// Verify that the equirectangular projection is linear, and narrows with its standard parallel.
+    #[test]
+    fn equirectangular_scale() {
+        let plain = Equirectangular::default();
+        assert_eq!(plain.project(90.0, 45.0), Vec2::new(0.5, 0.25));
+
+        let narrowed = Equirectangular::new(60.0);
+        assert!((narrowed.project(180.0, 0.0).x - 0.5).abs() < 1e-6);
+    }
+
+    /// Verify that globe projections hide the far side, and that lines crossing the horizon are cut there and bent smoothly.
+    #[test]
+    fn globe_projections() {
+        let globe = Orthographic::new(0.0, 0.0);
+        assert!(globe.project(90.0, 0.0).abs_diff_eq(Vec2::X, 1e-6));
+        assert!(!globe.is_visible(180.0, 0.0));
+
+        let stereographic = Stereographic::default();
+        assert!(stereographic.project(90.0, 0.0).abs_diff_eq(Vec2::X, 1e-6));
+        assert!(!stereographic.is_visible(170.0, 0.0));
+
+        let mollweide = Mollweide::default();
+        assert!(mollweide.project(180.0, 0.0).abs_diff_eq(Vec2::X, 1e-6));
+        assert!(mollweide
+            .project(0.0, 90.0)
+            .abs_diff_eq(Vec2::new(0.0, 0.5), 1e-6));
+
+        // The equator, seen from above, from the middle of the view around to the far side.
+        let tilted = Orthographic::new(0.0, 30.0);
+        let pieces = project_line(&tilted, &[(0.0, 0.0), (120.0, 0.0), (200.0, 0.0)], 0.001);
+        assert_eq!(pieces.len(), 1);
+        assert!(pieces[0].len() > 10);
+        assert!(pieces[0].last().unwrap().abs_diff_eq(Vec2::X, 1e-3));
+
+        // A line that dips behind the globe and comes back is split in two.
+        let pieces = project_line(&globe, &[(-60.0, 0.0), (180.0, 0.0), (60.0, 0.0)], 0.01);
+        assert_eq!(pieces.len(), 2);
+    }
+}
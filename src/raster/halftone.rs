@@ -0,0 +1,259 @@
+use glam::{Mat2, Vec2};
+use image::RgbaImage;
+
+use super::{luminance, to_shape_space};
+use crate::{geometry::optimize_travel, math::portable_sin_cos, packing::PackedCircle, rng::Rng};
+
+/// A grid of dots whose size follows the darkness of an image, like newspaper print.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Halftone {
+    /// Distance between neighbouring dots, in pixels.
+    pub spacing: f32,
+    /// Angle of the grid, in radians counter-clockwise from the x axis.
+    pub angle: f32,
+}
+
+impl Halftone {
+    /// Create a new [Halftone].
+    pub fn new(spacing: f32, angle: f32) -> Self {
+        Self { spacing, angle }
+    }
+
+    /// Place a dot on every grid point inside the image, with an area proportional to the average darkness of its cell.
+    ///
+    /// Cells that are pure white get no dot. One unit is one pixel, the image is centered on the origin, and the y axis points up.
+    pub fn dots(&self, image: &RgbaImage) -> Vec<PackedCircle> {
+        if self.spacing.is_nan() || self.spacing <= 0.0 {
+            return Vec::new();
+        }
+
+        let (sin, cos) = portable_sin_cos(self.angle);
+        let rotation = Mat2::from_cols(Vec2::new(cos, -sin), Vec2::new(sin, cos));
+        let center = image_size(image) / 2.0;
+        let reach = (center.length() / self.spacing).ceil() as i32;
+
+        let mut dots = Vec::new();
+        for row in -reach..=reach {
+            for column in -reach..=reach {
+                let position =
+                    center + rotation * Vec2::new(column as f32, row as f32) * self.spacing;
+                if !contains(image, position) {
+                    continue;
+                }
+
+                let darkness = average_darkness(image, position, self.spacing / 2.0);
+                if darkness > 0.0 {
+                    dots.push(PackedCircle {
+                        center: to_shape_space(image, position),
+                        radius: self.spacing * (darkness / std::f32::consts::PI).sqrt(),
+                    });
+                }
+            }
+        }
+
+        dots
+    }
+}
+
+/// Points scattered with a density that follows the darkness of an image.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stipple {
+    /// How many points to place.
+    pub count: usize,
+    /// Seed for placing the points.
+    pub seed: u64,
+}
+
+impl Stipple {
+    /// Create a new [Stipple].
+    pub fn new(count: usize, seed: u64) -> Self {
+        Self { count, seed }
+    }
+
+    /// Scatter up to [count](Self::count) points, each pixel being as likely to get one as it is dark.
+    ///
+    /// Fewer points may be returned if the image is mostly light. One unit is one pixel, the image is centered on the origin, and the y axis points up.
+    pub fn points(&self, image: &RgbaImage) -> Vec<Vec2> {
+        let size = image_size(image);
+        let mut rng = Rng::new(self.seed);
+        let mut points = Vec::with_capacity(self.count);
+
+        for _ in 0..self.count.saturating_mul(100) {
+            if points.len() == self.count {
+                break;
+            }
+
+            let position = Vec2::new(rng.range(0.0, size.x), rng.range(0.0, size.y));
+            if contains(image, position) && rng.next_f32() < darkness(image, position) {
+                points.push(to_shape_space(image, position));
+            }
+        }
+
+        points
+    }
+
+    /// Join the [points](Self::points) into a single path that visits each once, for drawing in one stroke of the pen.
+    pub fn path(&self, image: &RgbaImage) -> Vec<Vec2> {
+        let points = self.points(image).into_iter().map(|point| vec![point]);
+
+        // Ordering the points as single-point paths gives the tour, which is then drawn as one line.
+        optimize_travel(points.collect(), 0.0)
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+}
+
+/// Layers of hatching that build up in the darker parts of an image, like an engraving.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ToneHatch {
+    /// Distance between neighbouring lines in each layer, in pixels.
+    pub spacing: f32,
+    /// Angle of the first layer, in radians counter-clockwise from the x axis.
+    pub angle: f32,
+    /// How many layers to draw. Each is drawn only where the image is darker than the last, at a different angle.
+    pub layers: usize,
+}
+
+impl ToneHatch {
+    /// Create a new [ToneHatch].
+    pub fn new(spacing: f32, angle: f32, layers: usize) -> Self {
+        Self {
+            spacing,
+            angle,
+            layers,
+        }
+    }
+
+    /// The hatching lines, as open polylines. Neighbouring lines in each layer alternate direction to keep pen travel short.
+    ///
+    /// Layer `n` of `layers` covers areas darker than `(n + 1) / (layers + 1)`, and is turned a further `1 / layers` of a half turn.
+    /// One unit is one pixel, the image is centered on the origin, and the y axis points up.
+    pub fn lines(&self, image: &RgbaImage) -> Vec<Vec<Vec2>> {
+        if self.spacing.is_nan() || self.spacing <= 0.0 {
+            return Vec::new();
+        }
+
+        let center = image_size(image) / 2.0;
+        let reach = center.length();
+        let mut lines = Vec::new();
+
+        for layer in 0..self.layers {
+            let threshold = (layer + 1) as f32 / (self.layers + 1) as f32;
+            let angle = self.angle + std::f32::consts::PI * layer as f32 / self.layers as f32;
+
+            // Pixels count down the image, so flip the angle to match the y axis pointing up.
+            let (sin, cos) = portable_sin_cos(angle);
+            let direction = Vec2::new(cos, -sin);
+            let normal = direction.perp();
+
+            let mut offset = -reach;
+            while offset <= reach {
+                let mut runs = Vec::new();
+                let mut run: Vec<Vec2> = Vec::new();
+
+                let mut along = -reach;
+                while along <= reach {
+                    let position = center + normal * offset + direction * along;
+                    if contains(image, position) && darkness(image, position) > threshold {
+                        if run.len() < 2 {
+                            run.push(position);
+                        } else {
+                            run[1] = position;
+                        }
+                    } else if run.len() == 2 {
+                        runs.push(std::mem::take(&mut run));
+                    } else {
+                        run.clear();
+                    }
+                    along += 0.5;
+                }
+                if run.len() == 2 {
+                    runs.push(run);
+                }
+
+                if lines.len() % 2 == 1 {
+                    runs.reverse();
+                    runs.iter_mut().for_each(|run| run.reverse());
+                }
+                lines.extend(runs.into_iter().map(|run| {
+                    run.into_iter()
+                        .map(|position| to_shape_space(image, position))
+                        .collect()
+                }));
+
+                offset += self.spacing;
+            }
+        }
+
+        lines
+    }
+}
+
+fn image_size(image: &RgbaImage) -> Vec2 {
+    Vec2::new(image.width() as f32, image.height() as f32)
+}
+
+/// Checks if a position, in pixels from the top left corner, lands on the image.
+fn contains(image: &RgbaImage, position: Vec2) -> bool {
+    let size = image_size(image);
+    position.x >= 0.0 && position.y >= 0.0 && position.x < size.x && position.y < size.y
+}
+
+/// The darkness of the pixel under a position, from 0.0 (white) to 1.0 (black).
+fn darkness(image: &RgbaImage, position: Vec2) -> f32 {
+    1.0 - luminance(image, position.x as u32, position.y as u32)
+}
+
+/// The average darkness of the pixels within `radius` of a position, horizontally and vertically.
+fn average_darkness(image: &RgbaImage, position: Vec2, radius: f32) -> f32 {
+    let min = (position - radius).max(Vec2::ZERO).as_uvec2();
+    let max = (position + radius)
+        .min(image_size(image) - 1.0)
+        .max(Vec2::ZERO)
+        .as_uvec2();
+
+    let mut total = 0.0;
+    let mut count = 0;
+    for y in min.y..=max.y {
+        for x in min.x..=max.x {
+            total += 1.0 - luminance(image, x, y);
+            count += 1;
+        }
+    }
+
+    total / count as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    /// Verify that dots, stipples and hatching only land on the dark half of an image.
+    #[test]
+    fn follow_darkness() {
+        let image = RgbaImage::from_fn(20, 10, |x, _| {
+            if x < 10 {
+                Rgba([0, 0, 0, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        });
+
+        let dots = Halftone::new(2.0, 0.0).dots(&image);
+        assert!(!dots.is_empty());
+        assert!(dots.iter().all(|dot| dot.center.x < 0.5));
+        let full = 2.0 / std::f32::consts::PI.sqrt();
+        assert!(dots.iter().any(|dot| (dot.radius - full).abs() < 1e-4));
+
+        let stipple = Stipple::new(50, 3);
+        assert_eq!(stipple.points(&image).len(), 50);
+        assert!(stipple.points(&image).iter().all(|point| point.x < 0.0));
+        assert_eq!(stipple.path(&image).len(), 50);
+
+        let lines = ToneHatch::new(2.0, 0.0, 2).lines(&image);
+        assert!(!lines.is_empty());
+        assert!(lines.iter().flatten().all(|point| point.x < 0.0));
+    }
+}
@@ -0,0 +1,25 @@
+mod halftone;
+mod trace;
+
+pub use halftone::{Halftone, Stipple, ToneHatch};
+pub use trace::Trace;
+
+use glam::Vec2;
+use image::RgbaImage;
+
+/// The brightness of a pixel, from 0.0 (black) to 1.0 (white), as if the image were laid over a white background.
+fn luminance(image: &RgbaImage, x: u32, y: u32) -> f32 {
+    let [r, g, b, a] = image.get_pixel(x, y).0;
+    let luminance = (0.2126 * r as f32 + 0.7152 * g as f32 + 0.0722 * b as f32) / 255.0;
+    let alpha = a as f32 / 255.0;
+
+    1.0 - alpha * (1.0 - luminance)
+}
+
+/// Convert a position in the image, measured in pixels from its top left corner, to the space shapes are returned in.
+///
+/// One unit is one pixel, the image is centered on the origin, and the y axis points up.
+fn to_shape_space(image: &RgbaImage, position: Vec2) -> Vec2 {
+    let half = Vec2::new(image.width() as f32, image.height() as f32) / 2.0;
+    Vec2::new(position.x - half.x, half.y - position.y)
+}
@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+
+use glam::Vec2;
+use image::RgbaImage;
+
+use super::{luminance, to_shape_space};
+use crate::{
+    geometry::{contains_point, signed_area, simplify_polyline},
+    Color, FillRule, Shape,
+};
+
+/// Settings for tracing the dark areas of an image into filled shapes.
+///
+/// Outlines are found with marching squares, interpolated between pixel centers, then simplified.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Trace {
+    /// Pixels darker than this luminance, from 0.0 to 1.0, are traced.
+    pub threshold: f32,
+    /// Trace the pixels lighter than the threshold instead.
+    pub invert: bool,
+    /// How far, in pixels, a simplified outline may stray from the traced one.
+    pub tolerance: f32,
+    /// Outlines enclosing less than this many square pixels are dropped, which removes specks of noise.
+    pub min_area: f32,
+}
+
+impl Trace {
+    /// Create a new [Trace], simplifying to within a quarter of a pixel and dropping anything smaller than 2 square pixels.
+    pub fn new(threshold: f32) -> Self {
+        Self {
+            threshold,
+            invert: false,
+            tolerance: 0.25,
+            min_area: 2.0,
+        }
+    }
+
+    /// Trace `image` into filled shapes, one for each separate area, with any holes in it as [subpaths](Shape::subpaths).
+    ///
+    /// One unit is one pixel, the image is centered on the origin, and the y axis points up.
+    pub fn trace(&self, image: &RgbaImage, fill: Color) -> Vec<Shape> {
+        let loops: Vec<Vec<Vec2>> = self
+            .contours(image)
+            .into_iter()
+            .map(|outline| simplify_polyline(&outline, self.tolerance))
+            .filter(|outline| outline.len() > 3 && signed_area(outline).abs() >= self.min_area)
+            .map(|outline| {
+                outline
+                    .into_iter()
+                    .map(|point| to_shape_space(image, point))
+                    .collect()
+            })
+            .collect();
+
+        // Outlines nested an even number of times are solid areas, and the ones directly inside them are their holes.
+        let depths: Vec<usize> = loops
+            .iter()
+            .enumerate()
+            .map(|(i, outline)| {
+                loops
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, other)| j != i && contains_point(other, outline[0]))
+                    .count()
+            })
+            .collect();
+
+        let mut shapes: Vec<(usize, Shape)> = loops
+            .iter()
+            .zip(&depths)
+            .enumerate()
+            .filter(|(_, (_, depth))| *depth % 2 == 0)
+            .map(|(i, (outline, _))| {
+                let shape = Shape {
+                    points: outline.clone(),
+                    fill: Some(fill),
+                    fill_rule: FillRule::EvenOdd,
+                    ..Default::default()
+                };
+                (i, shape)
+            })
+            .collect();
+
+        for (hole, depth) in loops.iter().zip(&depths) {
+            if depth % 2 == 0 {
+                continue;
+            }
+
+            let parent = shapes
+                .iter_mut()
+                .find(|(i, _)| depths[*i] + 1 == *depth && contains_point(&loops[*i], hole[0]));
+            if let Some((_, shape)) = parent {
+                shape.subpaths.push(hole.clone());
+            }
+        }
+
+        shapes.into_iter().map(|(_, shape)| shape).collect()
+    }
+
+    /// Closed outlines around the traced areas, in pixels from the top left corner of the image.
+    fn contours(&self, image: &RgbaImage) -> Vec<Vec<Vec2>> {
+        let (width, height) = (image.width() as usize + 2, image.height() as usize + 2);
+
+        // Sample at pixel centers, with a border of background around the edge so every outline closes.
+        let darkness = |luminance: f32| {
+            if self.invert {
+                luminance - self.threshold
+            } else {
+                self.threshold - luminance
+            }
+        };
+        let background = darkness(if self.invert { 0.0 } else { 1.0 });
+
+        let mut samples = vec![background; width * height];
+        for y in 0..image.height() {
+            for x in 0..image.width() {
+                samples[(y as usize + 1) * width + x as usize + 1] =
+                    darkness(luminance(image, x, y));
+            }
+        }
+
+        let sample = |x: usize, y: usize| samples[y * width + x];
+
+        // Where an edge between two samples crosses the threshold, identified by its first sample and whether it runs horizontally.
+        let crossing = |(x, y, horizontal): Edge| {
+            let (a, b) = if horizontal {
+                (sample(x, y), sample(x + 1, y))
+            } else {
+                (sample(x, y), sample(x, y + 1))
+            };
+            let t = a / (a - b);
+            let offset = if horizontal {
+                Vec2::new(t, 0.0)
+            } else {
+                Vec2::new(0.0, t)
+            };
+
+            Vec2::new(x as f32 - 0.5, y as f32 - 0.5) + offset
+        };
+
+        let mut segments: Vec<(Edge, Edge)> = Vec::new();
+        for y in 0..height - 1 {
+            for x in 0..width - 1 {
+                let inside = |x, y| (sample(x, y) > 0.0) as u8;
+                let case = inside(x, y) * 8
+                    + inside(x + 1, y) * 4
+                    + inside(x + 1, y + 1) * 2
+                    + inside(x, y + 1);
+
+                let top = (x, y, true);
+                let right = (x + 1, y, false);
+                let bottom = (x, y + 1, true);
+                let left = (x, y, false);
+
+                let pairs: &[(Edge, Edge)] = match case {
+                    1 | 14 => &[(left, bottom)],
+                    2 | 13 => &[(bottom, right)],
+                    3 | 12 => &[(left, right)],
+                    4 | 11 => &[(top, right)],
+                    5 => &[(top, right), (left, bottom)],
+                    6 | 9 => &[(top, bottom)],
+                    7 | 8 => &[(top, left)],
+                    10 => &[(top, left), (bottom, right)],
+                    _ => &[],
+                };
+                segments.extend_from_slice(pairs);
+            }
+        }
+
+        // Every crossed edge is shared by exactly two segments, so walking from segment to segment always closes a loop.
+        let mut by_edge: HashMap<Edge, Vec<usize>> = HashMap::new();
+        for (i, (a, b)) in segments.iter().enumerate() {
+            by_edge.entry(*a).or_default().push(i);
+            by_edge.entry(*b).or_default().push(i);
+        }
+
+        let mut used = vec![false; segments.len()];
+        let mut outlines = Vec::new();
+
+        for first in 0..segments.len() {
+            if used[first] {
+                continue;
+            }
+
+            let (start, mut edge) = segments[first];
+            let mut outline = vec![crossing(start)];
+            let mut current = first;
+            used[first] = true;
+
+            while edge != start {
+                outline.push(crossing(edge));
+
+                let next = by_edge[&edge]
+                    .iter()
+                    .copied()
+                    .find(|&i| i != current && !used[i]);
+                let Some(next) = next else { break };
+
+                used[next] = true;
+                current = next;
+                let (a, b) = segments[next];
+                edge = if a == edge { b } else { a };
+            }
+
+            outline.push(outline[0]);
+            outlines.push(outline);
+        }
+
+        outlines
+    }
+}
+
+type Edge = (usize, usize, bool);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    /// Verify that a dark square with a light hole traces to one shape with one hole, close to the pixel edges.
+    #[test]
+    fn trace_square_with_hole() {
+        let image = RgbaImage::from_fn(10, 10, |x, y| {
+            let in_square = (2..8).contains(&x) && (2..8).contains(&y);
+            let in_hole = (4..6).contains(&x) && (4..6).contains(&y);
+            if in_square && !in_hole {
+                Rgba([0, 0, 0, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        });
+
+        let shapes = Trace::new(0.5).trace(&image, Color::black());
+        assert_eq!(shapes.len(), 1);
+        assert_eq!(shapes[0].subpaths.len(), 1);
+
+        let outer = signed_area(&shapes[0].points).abs();
+        let hole = signed_area(&shapes[0].subpaths[0]).abs();
+        assert!((35.0..=36.0).contains(&outer), "{}", outer);
+        assert!((3.0..=4.0).contains(&hole), "{}", hole);
+        assert!(shapes[0]
+            .points
+            .iter()
+            .all(|point| point.abs().max_element() <= 3.0));
+    }
+}
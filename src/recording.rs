@@ -0,0 +1,381 @@
+use glam::{Affine2, Vec2};
+
+use crate::{
+    geometry::{arc_lengths, ClipRegion, Distortion, Hatch, Warp},
+    Color, FillRule, Shape, Stroke, Style,
+};
+
+/// A single call made on a [Canvas](crate::Canvas) while it was recording, along with its arguments.
+///
+/// Start recording with [Canvas::start_recording](crate::Canvas::start_recording), and play calls back onto any canvas with [Canvas::replay](crate::Canvas::replay).
+/// Calls made by other calls, such as the [draw_shape](crate::Canvas::draw_shape) behind every [draw_rect](crate::Canvas::draw_rect), are not recorded separately.
+/// Calls that take closures or traits, such as [draw_path](crate::Canvas::draw_path), are recorded as the simpler calls they make.
+///
+/// `absolute` is `true` for the `_absolute` version of a call.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DrawCall {
+    /// [draw_shape](crate::Canvas::draw_shape).
+    Shape {
+        /// Points of the shape.
+        points: Vec<Vec2>,
+        /// Stroke of the shape.
+        stroke: Option<Stroke>,
+        /// Fill of the shape.
+        fill: Option<Color>,
+        /// Whether the call was absolute.
+        absolute: bool,
+    },
+    /// [draw_compound_shape](crate::Canvas::draw_compound_shape).
+    CompoundShape {
+        /// Every path of the shape.
+        paths: Vec<Vec<Vec2>>,
+        /// Stroke of the shape.
+        stroke: Option<Stroke>,
+        /// Fill of the shape.
+        fill: Option<Color>,
+        /// Fill rule of the shape.
+        fill_rule: FillRule,
+        /// Whether the call was absolute.
+        absolute: bool,
+    },
+    /// [draw_polygon_with_holes](crate::Canvas::draw_polygon_with_holes).
+    PolygonWithHoles {
+        /// The outer ring.
+        outer: Vec<Vec2>,
+        /// The holes cut out of it.
+        holes: Vec<Vec<Vec2>>,
+        /// Stroke of the polygon.
+        stroke: Option<Stroke>,
+        /// Fill of the polygon.
+        fill: Option<Color>,
+        /// Whether the call was absolute.
+        absolute: bool,
+    },
+    /// [draw_rect](crate::Canvas::draw_rect).
+    Rect {
+        /// One corner of the rectangle.
+        top_left: Vec2,
+        /// The opposite corner.
+        bottom_right: Vec2,
+        /// Stroke of the rectangle.
+        stroke: Option<Stroke>,
+        /// Fill of the rectangle.
+        fill: Option<Color>,
+        /// Whether the call was absolute.
+        absolute: bool,
+    },
+    /// [draw_regular_polygon](crate::Canvas::draw_regular_polygon).
+    RegularPolygon {
+        /// Center of the polygon.
+        center: Vec2,
+        /// Number of sides.
+        sides: usize,
+        /// Distance from the center to each corner.
+        radius: f32,
+        /// Rotation in radians.
+        rotation: f32,
+        /// Stroke of the polygon.
+        stroke: Option<Stroke>,
+        /// Fill of the polygon.
+        fill: Option<Color>,
+        /// Whether the call was absolute.
+        absolute: bool,
+    },
+    /// [draw_circle](crate::Canvas::draw_circle).
+    Circle {
+        /// Center of the circle.
+        center: Vec2,
+        /// Radius of the circle.
+        radius: f32,
+        /// Stroke of the circle.
+        stroke: Option<Stroke>,
+        /// Fill of the circle.
+        fill: Option<Color>,
+        /// Whether the call was absolute.
+        absolute: bool,
+    },
+    /// [draw_triangle](crate::Canvas::draw_triangle).
+    Triangle {
+        /// Corners of the triangle.
+        points: [Vec2; 3],
+        /// Stroke of the triangle.
+        stroke: Option<Stroke>,
+        /// Fill of the triangle.
+        fill: Option<Color>,
+        /// Whether the call was absolute.
+        absolute: bool,
+    },
+    /// [draw_quad](crate::Canvas::draw_quad).
+    Quad {
+        /// Corners of the quad.
+        points: [Vec2; 4],
+        /// Stroke of the quad.
+        stroke: Option<Stroke>,
+        /// Fill of the quad.
+        fill: Option<Color>,
+        /// Whether the call was absolute.
+        absolute: bool,
+    },
+    /// [draw_quadratic_bezier](crate::Canvas::draw_quadratic_bezier).
+    QuadraticBezier {
+        /// Start of the curve.
+        start_point: Vec2,
+        /// Control point of the curve.
+        control_point: Vec2,
+        /// End of the curve.
+        end_point: Vec2,
+        /// Stroke of the curve.
+        stroke: Option<Stroke>,
+        /// Fill of the curve.
+        fill: Option<Color>,
+        /// Whether the call was absolute.
+        absolute: bool,
+    },
+    /// [draw_cubic_bezier](crate::Canvas::draw_cubic_bezier).
+    CubicBezier {
+        /// Start of the curve.
+        start_point: Vec2,
+        /// First control point of the curve.
+        control_point_0: Vec2,
+        /// Second control point of the curve.
+        control_point_1: Vec2,
+        /// End of the curve.
+        end_point: Vec2,
+        /// Stroke of the curve.
+        stroke: Option<Stroke>,
+        /// Fill of the curve.
+        fill: Option<Color>,
+        /// Whether the call was absolute.
+        absolute: bool,
+    },
+    /// [draw_line](crate::Canvas::draw_line).
+    Line {
+        /// Start of the line.
+        p0: Vec2,
+        /// End of the line.
+        p1: Vec2,
+        /// Stroke of the line.
+        stroke: Option<Stroke>,
+        /// Fill of the line.
+        fill: Option<Color>,
+        /// Whether the call was absolute.
+        absolute: bool,
+    },
+    /// [draw_polyline](crate::Canvas::draw_polyline).
+    Polyline {
+        /// Points along the line.
+        points: Vec<Vec2>,
+        /// Stroke of the line.
+        stroke: Stroke,
+        /// Whether the call was absolute.
+        absolute: bool,
+    },
+    /// [draw_polygon](crate::Canvas::draw_polygon).
+    Polygon {
+        /// Corners of the polygon.
+        points: Vec<Vec2>,
+        /// Fill of the polygon.
+        fill: Color,
+        /// Whether the call was absolute.
+        absolute: bool,
+    },
+    /// [draw_instances](crate::Canvas::draw_instances).
+    Instances {
+        /// The shape that is copied.
+        shape: Shape,
+        /// Where each copy is placed.
+        transforms: Vec<Affine2>,
+        /// Whether the call was absolute.
+        absolute: bool,
+    },
+    /// [draw_raw_shape](crate::Canvas::draw_raw_shape).
+    RawShape(Shape),
+    /// Shapes added exactly as they are, bypassing any [Symmetry](crate::Symmetry), for calls such as [draw_brush_stroke](crate::Canvas::draw_brush_stroke) that cannot be replayed from their arguments.
+    Shapes(Vec<Shape>),
+    /// [rotate_camera](crate::Canvas::rotate_camera).
+    RotateCamera(f32),
+    /// [move_camera](crate::Canvas::move_camera).
+    MoveCamera(Vec2),
+    /// [zoom_camera](crate::Canvas::zoom_camera).
+    ZoomCamera(f32),
+    /// [set_points_per_unit](crate::Canvas::set_points_per_unit).
+    SetPointsPerUnit(usize),
+    /// [set_deterministic](crate::Canvas::set_deterministic).
+    SetDeterministic(bool),
+    /// The transforms of [set_symmetry](crate::Canvas::set_symmetry) or [with_symmetry](crate::Canvas::with_symmetry) changed.
+    ///
+    /// Empty when there is no symmetry.
+    Symmetry(Vec<Affine2>),
+    /// The link of [with_link](crate::Canvas::with_link) changed.
+    Link(Option<String>),
+    /// The tooltip of [with_tooltip](crate::Canvas::with_tooltip) changed.
+    Tooltip(Option<String>),
+    /// The tag of [with_tag](crate::Canvas::with_tag) changed.
+    Tag(Option<String>),
+    /// The style of [set_style](crate::Canvas::set_style) or [with_style](crate::Canvas::with_style) changed.
+    Style(Style),
+    /// [clear](crate::Canvas::clear).
+    Clear,
+    /// [hatch_fills](crate::Canvas::hatch_fills).
+    HatchFills {
+        /// The hatching.
+        hatch: Hatch,
+        /// Width of the pen.
+        pen_width: f32,
+    },
+    /// [optimize_pen_travel](crate::Canvas::optimize_pen_travel).
+    OptimizePenTravel {
+        /// How close ends must be to be joined.
+        merge_tolerance: f32,
+    },
+    /// [remove_hidden_lines](crate::Canvas::remove_hidden_lines).
+    RemoveHiddenLines,
+    /// [flatten](crate::Canvas::flatten).
+    Flatten,
+    /// [bake_camera](crate::Canvas::bake_camera).
+    BakeCamera,
+    /// [clip_to](crate::Canvas::clip_to).
+    ClipTo(ClipRegion),
+    /// [distort](crate::Canvas::distort).
+    Distort {
+        /// The distortion applied.
+        distortion: Distortion,
+        /// How closely bent edges follow the true curve.
+        tolerance: f32,
+    },
+    /// [warp](crate::Canvas::warp).
+    Warp {
+        /// The warp applied.
+        warp: Warp,
+        /// How closely bent edges follow the true curve.
+        tolerance: f32,
+    },
+}
+
+/// The calls recorded so far, and how deeply nested the current call is.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Recording {
+    pub(crate) calls: Vec<DrawCall>,
+    pub(crate) depth: usize,
+}
+
+/// How each shape appears in a [build animation](crate::Canvas::render_build_animation).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Reveal {
+    /// Shapes appear all at once, at the end of their share of the animation.
+    #[default]
+    Appear,
+    /// Strokes are drawn from start to end, path by path, and fills appear once the stroke is finished.
+    ///
+    /// Shapes without a stroke appear all at once.
+    Draw,
+}
+
+impl Reveal {
+    /// What `shape` looks like `progress` of the way through being revealed, from 0 to 1.
+    pub(crate) fn partial(self, shape: &Shape, progress: f32) -> Option<Shape> {
+        if self == Reveal::Appear || shape.stroke.is_none() {
+            return None;
+        }
+        let shape: &Shape = &shape.flattened();
+
+        let lengths: Vec<Vec<f32>> = shape.paths().map(arc_lengths).collect();
+        let total: f32 = lengths.iter().filter_map(|path| path.last()).sum();
+        let mut remaining = total * progress;
+        let mut paths = Vec::new();
+
+        for (path, lengths) in shape.paths().zip(&lengths) {
+            if remaining <= 0.0 {
+                break;
+            }
+
+            let length = lengths.last().copied().unwrap_or(0.0);
+            if length <= remaining {
+                paths.push(path.to_vec());
+                remaining -= length;
+                continue;
+            }
+
+            let mut cut = vec![path[0]];
+            for (i, pair) in path.windows(2).enumerate() {
+                if lengths[i + 1] < remaining {
+                    cut.push(pair[1]);
+                } else {
+                    let t = (remaining - lengths[i]) / (lengths[i + 1] - lengths[i]);
+                    cut.push(pair[0].lerp(pair[1], t));
+                    break;
+                }
+            }
+            paths.push(cut);
+            remaining = 0.0;
+        }
+
+        let mut paths = paths.into_iter();
+        Some(Shape {
+            points: paths.next()?,
+            subpaths: paths.collect(),
+            fill: None,
+            ..shape.clone()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{custom_shape::custom_capsule, Canvas, Error, LineEnd, Renderer};
+    use glam::Vec2;
+
+    /// Collects the shapes of each frame.
+    struct Collect(Vec<Shape>);
+
+    impl Renderer for Collect {
+        type Output = Vec<Shape>;
+
+        fn render(&mut self, shape: &Shape) -> Result<(), Error> {
+            self.0.push(shape.clone());
+            Ok(())
+        }
+
+        fn finalize(self) -> Result<Self::Output, Error> {
+            Ok(self.0)
+        }
+    }
+
+    /// Verify that a build animation draws strokes along their length, and holds back fills until their turn is over.
+    #[test]
+    fn build_animation() {
+        let mut canvas = Canvas::default();
+        canvas.start_recording();
+        canvas.draw_line(
+            Vec2::ZERO,
+            Vec2::new(2.0, 0.0),
+            Some(Stroke::new(Color::black(), 0.1, LineEnd::Butt)),
+            None,
+        );
+        canvas.draw_rect(Vec2::ZERO, Vec2::ONE, None, Some(Color::red()));
+        let calls = canvas.stop_recording();
+
+        let frames = Canvas::default()
+            .render_build_animation(&calls, 4, Reveal::Draw, || Ok(Collect(Vec::new())))
+            .unwrap();
+
+        assert_eq!(frames.len(), 4);
+        assert_eq!(frames[0].len(), 1);
+        assert_eq!(frames[0][0].points, vec![Vec2::ZERO, Vec2::X]);
+        assert_eq!(frames[1].len(), 1);
+        assert_eq!(frames[2].len(), 1);
+        assert_eq!(frames[3], canvas.as_raw());
+    }
+
+    /// Verify that custom shapes are flattened before their strokes are partly drawn.
+    #[test]
+    fn reveal_custom_shape() {
+        let custom = custom_capsule();
+
+        let partial = Reveal::Draw.partial(&custom, 0.5);
+        assert_eq!(partial, Reveal::Draw.partial(&custom.flattened(), 0.5));
+        assert!(partial.is_some_and(|shape| !shape.points.is_empty()));
+    }
+}
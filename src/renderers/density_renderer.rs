@@ -0,0 +1,405 @@
+use glam::UVec2;
+use image::RgbaImage;
+use tiny_skia::{Path, Pixmap, Transform};
+
+use super::resolve::Dither;
+use super::skia_renderer::{paint_path, ImageSpace};
+use crate::canvas::Shape;
+use crate::{Color, Error, Renderer, Stroke};
+
+/// How a [DensityRenderer] turns the opacity piled up in each pixel into how much of its color shows.
+///
+/// Density is the sum of the opacity of everything covering a pixel, so ten strokes at 10% make a density of 1.
+#[derive(Debug, Clone, Copy)]
+pub enum ToneMap {
+    /// Fully shown at this density, and in proportion below it.
+    Linear(f32),
+    /// Shown by the logarithm of the density, scaled so the densest pixel in the image is fully shown.
+    ///
+    /// Brings out faint detail without the densest areas clipping, as in flow-field and attractor pieces.
+    Log,
+    /// Shown as if exposing film to the density for this long: `1 - e^(-exposure × density)`.
+    Exposure(f32),
+    /// Any curve from density to how much is shown, from 0 to 1.
+    Custom(fn(f32) -> f32),
+}
+
+impl ToneMap {
+    /// How much of a pixel's color shows at `density`, given the greatest density in the image.
+    fn apply(self, density: f32, max_density: f32) -> f32 {
+        let shown = match self {
+            ToneMap::Linear(full) => density / full,
+            ToneMap::Log => (1.0 + density).ln() / (1.0 + max_density).ln(),
+            ToneMap::Exposure(exposure) => 1.0 - (-exposure * density).exp(),
+            ToneMap::Custom(curve) => curve(density),
+        };
+        if shown.is_nan() {
+            0.0
+        } else {
+            shown.clamp(0.0, 1.0)
+        }
+    }
+}
+
+/// How two [DensityBuffer]s are combined, pixel by pixel, before tone mapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Composite {
+    /// Everything piles up, just as if it had been drawn into the same buffer. Like light painting.
+    Add,
+    /// The greater of the two, channel by channel, so overlaps are no denser than their densest layer.
+    Max,
+    /// The average colors multiply, mixing like overlapping inks, and so do the densities, so only where both layers have something shows.
+    Multiply,
+}
+
+/// A floating point image where overlapping shapes pile up, rather than covering each other.
+///
+/// Render a [Canvas](crate::Canvas) into one to get the buffer itself, such as to [combine](Self::combine) it with
+/// other layers, then [tone map](Self::tone_map) the result. To go straight to an image, use a [DensityRenderer].
+///
+/// Every shape adds its color, weighted by its opacity, into the buffer, along with the opacity itself: the density.
+/// [Blend modes](crate::BlendMode) and [gradient fills](crate::GradientFill) are ignored, since everything is added together.
+pub struct DensityBuffer {
+    size: UVec2,
+    antialias: bool,
+    image_space: ImageSpace,
+    /// Scratch space where each shape's coverage is drawn, then read back and cleared.
+    coverage: Pixmap,
+    /// The colors piled onto each pixel, weighted by opacity, followed by the density.
+    pixels: Vec<[f32; 4]>,
+}
+
+impl DensityBuffer {
+    /// Create a new, empty [DensityBuffer].
+    ///
+    /// `preserve_height` works the same way as in [SkiaRenderer::new](super::SkiaRenderer::new).
+    ///
+    /// Returns [Error::InvalidSize] if either dimension of `size` is zero.
+    pub fn new(size: UVec2, antialias: bool, preserve_height: bool) -> Result<Self, Error> {
+        let coverage = Pixmap::new(size.x, size.y).ok_or(Error::InvalidSize {
+            width: size.x,
+            height: size.y,
+        })?;
+
+        Ok(Self {
+            size,
+            antialias,
+            image_space: ImageSpace::new(size, preserve_height),
+            coverage,
+            pixels: vec![[0.0; 4]; (size.x * size.y) as usize],
+        })
+    }
+
+    /// The width and height of the buffer, in pixels.
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    /// Every pixel, row by row from the top left: the red, green and blue piled onto it, each weighted by opacity, then the density.
+    pub fn pixels(&self) -> &[[f32; 4]] {
+        &self.pixels
+    }
+
+    /// The sum of the opacity of everything covering the pixel at `x` and `y`.
+    pub fn density(&self, x: u32, y: u32) -> f32 {
+        self.pixels[(y * self.size.x + x) as usize][3]
+    }
+
+    /// Combine another layer into this one, pixel by pixel.
+    ///
+    /// Returns [Error::InvalidSize] if the layers are not the same size.
+    pub fn combine(&mut self, other: &DensityBuffer, composite: Composite) -> Result<(), Error> {
+        if other.size != self.size {
+            return Err(Error::InvalidSize {
+                width: other.size.x,
+                height: other.size.y,
+            });
+        }
+
+        for (pixel, other) in self.pixels.iter_mut().zip(&other.pixels) {
+            match composite {
+                Composite::Add => {
+                    for (channel, other) in pixel.iter_mut().zip(other) {
+                        *channel += other;
+                    }
+                }
+                Composite::Max => {
+                    for (channel, other) in pixel.iter_mut().zip(other) {
+                        *channel = channel.max(*other);
+                    }
+                }
+                // The colors are weighted by density, so multiplying them multiplies both the average colors and the densities.
+                Composite::Multiply => {
+                    for (channel, other) in pixel.iter_mut().zip(other) {
+                        *channel *= other;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Turn the buffer into an image: each pixel shows the average of the colors covering it, over `background`,
+    /// as strongly as `tone_map` says for how much was piled on.
+    pub fn tone_map(
+        &self,
+        tone_map: ToneMap,
+        background: Option<Color>,
+    ) -> Result<RgbaImage, Error> {
+        let (width, height) = (self.size.x, self.size.y);
+        let max_density = self
+            .pixels
+            .iter()
+            .fold(0.0f32, |max, pixel| max.max(pixel[3]));
+        let background = background.unwrap_or_else(Color::transparent);
+
+        let mut data = Vec::with_capacity(self.pixels.len() * 4);
+        for &[r, g, b, density] in &self.pixels {
+            let shown = if density > 0.0 {
+                tone_map.apply(density, max_density)
+            } else {
+                0.0
+            };
+            let average = |channel: f32| {
+                if density > 0.0 {
+                    channel / density
+                } else {
+                    0.0
+                }
+            };
+
+            // Lay the average color over the background, as strongly as it is shown, keeping the alpha premultiplied like a Pixmap.
+            let under = background.a() * (1.0 - shown);
+            let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+            data.extend([
+                channel(average(r) * shown + background.r() * under),
+                channel(average(g) * shown + background.g() * under),
+                channel(average(b) * shown + background.b() * under),
+                channel(shown + under),
+            ]);
+        }
+
+        RgbaImage::from_raw(width, height, data).ok_or(Error::InvalidSize { width, height })
+    }
+
+    /// Draw the coverage of `style` along `path`, then add it to the buffer in `color` and clear it again.
+    fn accumulate(
+        &mut self,
+        path: &Path,
+        style: &Shape,
+        color: Color,
+        margin: f32,
+    ) -> Result<(), Error> {
+        paint_path(
+            &mut self.coverage,
+            path,
+            style,
+            &self.image_space,
+            self.antialias,
+            Dither::None,
+            Transform::identity(),
+        )?;
+
+        // Only the pixels around the path can have been touched, so there's no need to visit the whole image.
+        let bounds = path.bounds();
+        let clamp = |value: f32, max: u32| (value.max(0.0) as u32).min(max);
+        let (left, right) = (
+            clamp((bounds.left() - margin).floor(), self.size.x),
+            clamp((bounds.right() + margin).ceil(), self.size.x),
+        );
+        let (top, bottom) = (
+            clamp((bounds.top() - margin).floor(), self.size.y),
+            clamp((bounds.bottom() + margin).ceil(), self.size.y),
+        );
+
+        let weight = color.a();
+        let coverage = self.coverage.data_mut();
+        for y in top..bottom {
+            for x in left..right {
+                let i = (y * self.size.x + x) as usize;
+                let covered = coverage[i * 4 + 3];
+                if covered == 0 {
+                    continue;
+                }
+                coverage[i * 4..i * 4 + 4].fill(0);
+
+                let amount = weight * covered as f32 / 255.0;
+                let pixel = &mut self.pixels[i];
+                pixel[0] += color.r() * amount;
+                pixel[1] += color.g() * amount;
+                pixel[2] += color.b() * amount;
+                pixel[3] += amount;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Renderer for DensityBuffer {
+    type Output = DensityBuffer;
+
+    fn render(&mut self, shape: &Shape) -> Result<(), Error> {
+        if !shape.is_drawable() {
+            return Ok(());
+        }
+
+        let path = self.image_space.build_path(shape)?;
+
+        // The coverage is drawn in opaque white, so its alpha is exactly how much of each pixel is covered.
+        if let Some(fill) = shape.fill {
+            let style = Shape {
+                fill: Some(Color::white()),
+                fill_rule: shape.fill_rule,
+                ..Default::default()
+            };
+            self.accumulate(&path, &style, fill, 1.0)?;
+        }
+
+        if let Some(stroke) = shape.stroke {
+            let style = Shape {
+                stroke: Some(Stroke {
+                    color: Color::white(),
+                    ..stroke
+                }),
+                ..Default::default()
+            };
+            // Leave room for miter joins, which can reach twice the stroke width past the path, and a pixel of anti-aliasing.
+            let margin = stroke.width * self.image_space.scale * 2.0 + 1.0;
+            self.accumulate(&path, &style, stroke.color, margin)?;
+        }
+
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Self::Output, Error> {
+        Ok(self)
+    }
+}
+
+/// Renderer where overlapping shapes pile up, rather than covering each other.
+///
+/// Shapes are piled into a [DensityBuffer], then once finished, each pixel shows the average of the colors covering it,
+/// as strongly as its [ToneMap] says for how much was piled on.
+/// So a million faint strokes build up smooth tones instead of clipping to solid color, as they would with a [SkiaRenderer](super::SkiaRenderer).
+pub struct DensityRenderer {
+    buffer: DensityBuffer,
+    background: Option<Color>,
+    tone_map: ToneMap,
+}
+
+impl DensityRenderer {
+    /// Create a new [DensityRenderer], tone mapped with [ToneMap::Log].
+    ///
+    /// `preserve_height` works the same way as in [SkiaRenderer::new](super::SkiaRenderer::new).
+    ///
+    /// Returns [Error::InvalidSize] if either dimension of `size` is zero.
+    pub fn new(
+        size: UVec2,
+        background: Option<Color>,
+        antialias: bool,
+        preserve_height: bool,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            buffer: DensityBuffer::new(size, antialias, preserve_height)?,
+            background,
+            tone_map: ToneMap::Log,
+        })
+    }
+
+    /// Set how the piled up density is shown, consuming the parent.
+    pub fn with_tone_map(mut self, tone_map: ToneMap) -> Self {
+        self.tone_map = tone_map;
+        self
+    }
+}
+
+impl Renderer for DensityRenderer {
+    type Output = RgbaImage;
+
+    fn render(&mut self, shape: &Shape) -> Result<(), Error> {
+        self.buffer.render(shape)
+    }
+
+    fn finalize(self) -> Result<Self::Output, Error> {
+        self.buffer.tone_map(self.tone_map, self.background)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Canvas, LineEnd};
+
+    /// Verify that overlapping translucent strokes pile up instead of clipping, and are shown through the tone map.
+    #[test]
+    fn accumulate_density() {
+        let mut canvas = Canvas::new(1);
+        let stroke = Stroke::new(Color::black().with_a(0.125), 0.5, LineEnd::Butt);
+        for _ in 0..16 {
+            canvas.draw_polyline([(-1.0, 0.0), (1.0, 0.0)], stroke);
+        }
+        canvas.draw_polyline([(0.0, -1.0), (0.0, 1.0)], stroke);
+
+        let render = |tone_map| {
+            let renderer =
+                DensityRenderer::new(UVec2::new(8, 8), Some(Color::white()), false, true)
+                    .unwrap()
+                    .with_tone_map(tone_map);
+            canvas.render(renderer).unwrap()
+        };
+
+        // Sixteen strokes pile up to a density of 2, and the crossing stroke adds another 0.125.
+        let linear = render(ToneMap::Linear(4.0));
+        assert_eq!(linear.get_pixel(1, 4).0, [128, 128, 128, 255]);
+        assert_eq!(linear.get_pixel(4, 4).0, [120, 120, 120, 255]);
+        assert_eq!(linear.get_pixel(4, 1).0, [247, 247, 247, 255]);
+        assert_eq!(linear.get_pixel(1, 1).0, [255, 255, 255, 255]);
+
+        let log = render(ToneMap::Log);
+        assert_eq!(log.get_pixel(4, 4).0, [0, 0, 0, 255]);
+
+        let custom = render(ToneMap::Custom(|density| density / 8.0));
+        assert_eq!(custom.get_pixel(1, 4).0, [191, 191, 191, 255]);
+    }
+
+    /// Verify that layers rendered into separate buffers are combined pixel by pixel before tone mapping.
+    #[test]
+    fn combine_layers() {
+        let layer = |color: Color, points: [(f32, f32); 2]| {
+            let mut canvas = Canvas::new(1);
+            canvas.draw_polyline(points, Stroke::new(color, 0.5, LineEnd::Butt));
+            let buffer = DensityBuffer::new(UVec2::new(8, 8), false, true).unwrap();
+            canvas.render(buffer).unwrap()
+        };
+        let across = [(-1.0, 0.0), (1.0, 0.0)];
+        let cyan = Color::new(0.0, 1.0, 1.0, 0.5);
+        let yellow = Color::new(1.0, 1.0, 0.0, 1.0);
+
+        let mut added = layer(cyan, across);
+        added.combine(&layer(cyan, across), Composite::Add).unwrap();
+        assert_eq!(added.pixels()[4 * 8 + 1], [0.0, 1.0, 1.0, 1.0]);
+
+        let mut most = layer(cyan, across);
+        most.combine(&layer(yellow, [(0.0, -1.0), (0.0, 1.0)]), Composite::Max)
+            .unwrap();
+        assert_eq!(most.density(1, 4), 0.5);
+        assert_eq!(most.density(4, 4), 1.0);
+
+        // Cyan and yellow inks overlap to make green, only where they cross.
+        let mut mixed = layer(cyan, across);
+        mixed
+            .combine(
+                &layer(yellow, [(0.0, -1.0), (0.0, 1.0)]),
+                Composite::Multiply,
+            )
+            .unwrap();
+        let image = mixed.tone_map(ToneMap::Linear(0.5), None).unwrap();
+        assert_eq!(image.get_pixel(4, 4).0, [0, 255, 0, 255]);
+        assert_eq!(image.get_pixel(1, 4).0, [0, 0, 0, 0]);
+
+        let small = DensityBuffer::new(UVec2::new(4, 4), false, true).unwrap();
+        assert!(mixed.combine(&small, Composite::Add).is_err());
+    }
+}
@@ -0,0 +1,353 @@
+use glam::Vec2;
+
+use crate::{Color, Error, FillRule, LineEnd, Renderer, Reveal, Shape, Stroke};
+use std::fmt::Write;
+
+/// A renderer for Lottie (bodymovin) JSON, which plays back in web and mobile apps.
+///
+/// Each shape becomes its own shape layer, visible for the whole duration of the composition.
+/// [with_reveal](Self::with_reveal) instead reveals the shapes one after another, like [Canvas::render_build_animation](crate::Canvas::render_build_animation),
+/// using layer in points and keyframes. Shapes have no transforms or colors that change over time, so nothing else is animated.
+/// Blend modes are ignored.
+#[derive(Clone)]
+pub struct LottieRenderer {
+    size: Vec2,
+    scale: f32,
+    center_offset: Vec2,
+    frame_rate: f32,
+    frames: f32,
+    reveal: Option<Reveal>,
+    background: Option<String>,
+    shapes: Vec<ShapeItems>,
+}
+
+/// The shape items of a shape, kept apart so [finalize](Renderer::finalize) can reveal its stroke and fill at different times.
+#[derive(Clone)]
+struct ShapeItems {
+    paths: String,
+    stroke: Option<String>,
+    fill: Option<String>,
+}
+
+impl LottieRenderer {
+    /// Creates a new [LottieRenderer], lasting `duration` seconds at `frame_rate` frames per second.
+    ///
+    /// `preserve_height` allows you to decide which axis to preserve.
+    /// If `true`, then the rendered composition will map `-1..=1` in the y axis in camera space to `size.y..=0`.
+    /// If `false` then the rendered composition will be mapped for the x axis.
+    pub fn new(
+        size: Vec2,
+        background: Option<Color>,
+        preserve_height: bool,
+        frame_rate: f32,
+        duration: f32,
+    ) -> Result<Self, Error> {
+        let (scale, center_offset) = if preserve_height {
+            let scale = size.y / 2.0;
+            (scale, Vec2::new(size.x / 2.0 / scale, 1.0))
+        } else {
+            let scale = size.x / 2.0;
+            (scale, Vec2::new(1.0, size.y / 2.0 / scale))
+        };
+
+        let mut renderer = Self {
+            size,
+            scale,
+            center_offset,
+            frame_rate,
+            frames: (frame_rate * duration).max(1.0).round(),
+            reveal: None,
+            background: None,
+            shapes: Vec::new(),
+        };
+
+        if let Some(background) = background {
+            let mut items = String::new();
+            write!(
+                items,
+                "{{\"ty\":\"rc\",\"d\":1,\"p\":{{\"a\":0,\"k\":[{},{}]}},\"s\":{{\"a\":0,\"k\":[{},{}]}},\"r\":{{\"a\":0,\"k\":0}}}},",
+                size.x / 2.0,
+                size.y / 2.0,
+                size.x,
+                size.y
+            )?;
+            write_fill(&mut items, background, FillRule::NonZero)?;
+            renderer.background = Some(items);
+        }
+
+        Ok(renderer)
+    }
+
+    /// Reveal the shapes one after another instead of showing them all at once, consuming the parent.
+    ///
+    /// Each shape gets an equal share of the composition, and the last frame shows every shape.
+    /// [Reveal::Draw] trims strokes from start to end with keyframes, and shows fills once their stroke is finished.
+    pub fn with_reveal(mut self, reveal: Reveal) -> Self {
+        self.reveal = Some(reveal);
+        self
+    }
+}
+
+impl LottieRenderer {
+    /// Write a shape layer holding `items`, which must end with a trailing comma, visible from frame `in_point` to the end.
+    fn write_layer(
+        &self,
+        document: &mut String,
+        name: &str,
+        items: &str,
+        in_point: f32,
+    ) -> Result<(), Error> {
+        write!(
+            document,
+            "{{\"ddd\":0,\"ty\":4,\"nm\":\"{}\",\"sr\":1,\"ks\":{},\"ao\":0,\"shapes\":[{{\"ty\":\"gr\",\"it\":[{}{}]}}],\"ip\":{},\"op\":{},\"st\":0,\"bm\":0}}",
+            name,
+            IDENTITY_TRANSFORM,
+            items,
+            GROUP_TRANSFORM,
+            in_point,
+            self.frames
+        )?;
+
+        Ok(())
+    }
+
+    /// The layers of a shape, from top to bottom, as their name, items and in point.
+    fn shape_layers(&self, index: usize, shape: &ShapeItems) -> Vec<(String, String, f32)> {
+        let name = format!("Shape {}", index);
+        let stroke = shape.stroke.as_deref().unwrap_or("");
+        let fill = shape.fill.as_deref().unwrap_or("");
+        let all = format!("{}{}{}", shape.paths, stroke, fill);
+
+        // The share of the composition the shape is revealed in, ending on the last frame for the last shape.
+        let share = (self.frames - 1.0).max(0.0) / self.shapes.len() as f32;
+        let (start, end) = (index as f32 * share, (index + 1) as f32 * share);
+
+        match (self.reveal, &shape.stroke) {
+            (None, _) => vec![(name, all, 0.0)],
+            (Some(Reveal::Appear), _) | (Some(Reveal::Draw), None) => vec![(name, all, end)],
+            (Some(Reveal::Draw), Some(stroke)) => {
+                // Paths are drawn one after another, the trimmed end running from 0% to 100% across the share.
+                let trim = format!(
+                    "{{\"ty\":\"tm\",\"s\":{{\"a\":0,\"k\":0}},\"e\":{{\"a\":1,\"k\":[{{\"t\":{},\"s\":[0],\"i\":{{\"x\":[1],\"y\":[1]}},\"o\":{{\"x\":[0],\"y\":[0]}}}},{{\"t\":{},\"s\":[100]}}]}},\"o\":{{\"a\":0,\"k\":0}},\"m\":2}},",
+                    start, end
+                );
+                let mut layers = vec![(
+                    name.clone(),
+                    format!("{}{}{}", shape.paths, trim, stroke),
+                    start,
+                )];
+                if !fill.is_empty() {
+                    layers.push((
+                        format!("{} Fill", name),
+                        format!("{}{}", shape.paths, fill),
+                        end,
+                    ));
+                }
+                layers
+            }
+        }
+    }
+
+    /// Write a path as a Lottie bezier with straight edges, transformed into Image Space.
+    fn write_path(&self, items: &mut String, points: &[Vec2]) -> Result<(), Error> {
+        let closed = points.len() >= 3 && points[0] == points[points.len() - 1];
+        let points = if closed {
+            &points[..points.len() - 1]
+        } else {
+            points
+        };
+
+        // Straight edges have every tangent at zero.
+        let tangents = vec!["[0,0]"; points.len()].join(",");
+
+        write!(
+            items,
+            "{{\"ty\":\"sh\",\"ks\":{{\"a\":0,\"k\":{{\"c\":{},\"v\":[",
+            closed
+        )?;
+        for (i, point) in points.iter().enumerate() {
+            // Transform from Camera Space (range from (-1, -1) to (1, 1)) to Image Space (range from (0, 0) to image size).
+            let point = (Vec2::new(point.x, -point.y) + self.center_offset) * self.scale;
+            let separator = if i == 0 { "" } else { "," };
+            write!(items, "{}[{},{}]", separator, point.x, point.y)?;
+        }
+        write!(items, "],\"i\":[{}],\"o\":[{}]}}}}}},", tangents, tangents)?;
+
+        Ok(())
+    }
+}
+
+impl Renderer for LottieRenderer {
+    type Output = String;
+
+    fn render(&mut self, shape: &Shape) -> Result<(), Error> {
+        if !shape.is_drawable() {
+            return Ok(());
+        }
+
+        let mut paths = String::new();
+        for points in shape.paths().filter(|points| points.len() > 1) {
+            self.write_path(&mut paths, points)?;
+        }
+
+        let stroke = match shape.stroke {
+            Some(stroke) => {
+                let mut items = String::new();
+                write_stroke(&mut items, stroke, self.scale)?;
+                Some(items)
+            }
+            None => None,
+        };
+
+        let fill = match shape.fill {
+            Some(fill) => {
+                let mut items = String::new();
+                write_fill(&mut items, fill, shape.fill_rule)?;
+                Some(items)
+            }
+            None => None,
+        };
+
+        self.shapes.push(ShapeItems {
+            paths,
+            stroke,
+            fill,
+        });
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Self::Output, Error> {
+        let mut document = String::new();
+        write!(
+            document,
+            "{{\"v\":\"5.7.0\",\"fr\":{},\"ip\":0,\"op\":{},\"w\":{},\"h\":{},\"ddd\":0,\"assets\":[],\"layers\":[",
+            self.frame_rate,
+            self.frames,
+            self.size.x.round(),
+            self.size.y.round()
+        )?;
+
+        // Lottie draws the first layer on top, so the last shape drawn comes first.
+        let mut layers: Vec<(String, String, f32)> = Vec::new();
+        for (index, shape) in self.shapes.iter().enumerate().rev() {
+            layers.extend(self.shape_layers(index, shape));
+        }
+        if let Some(background) = &self.background {
+            layers.push(("Background".to_string(), background.clone(), 0.0));
+        }
+
+        for (i, (name, items, in_point)) in layers.iter().enumerate() {
+            if i > 0 {
+                document.push(',');
+            }
+            self.write_layer(&mut document, name, items, *in_point)?;
+        }
+
+        write!(document, "]}}")?;
+
+        Ok(document)
+    }
+}
+
+/// The transform of a layer that leaves its contents where they are.
+const IDENTITY_TRANSFORM: &str = "{\"o\":{\"a\":0,\"k\":100},\"r\":{\"a\":0,\"k\":0},\"p\":{\"a\":0,\"k\":[0,0,0]},\"a\":{\"a\":0,\"k\":[0,0,0]},\"s\":{\"a\":0,\"k\":[100,100,100]}}";
+
+/// The transform that must end every group of shape items.
+const GROUP_TRANSFORM: &str = "{\"ty\":\"tr\",\"p\":{\"a\":0,\"k\":[0,0]},\"a\":{\"a\":0,\"k\":[0,0]},\"s\":{\"a\":0,\"k\":[100,100]},\"r\":{\"a\":0,\"k\":0},\"o\":{\"a\":0,\"k\":100}}";
+
+/// Write a stroke, with its width scaled from Camera Space into Image Space by `scale`.
+fn write_stroke(items: &mut String, stroke: Stroke, scale: f32) -> Result<(), Error> {
+    let line_cap = match stroke.line_end {
+        LineEnd::Butt => 1,
+        LineEnd::Round => 2,
+    };
+    write!(
+        items,
+        "{{\"ty\":\"st\",\"c\":{{\"a\":0,\"k\":{}}},\"o\":{{\"a\":0,\"k\":{}}},\"w\":{{\"a\":0,\"k\":{}}},\"lc\":{},\"lj\":2}},",
+        rgb(stroke.color),
+        stroke.color.a() * 100.0,
+        stroke.width * scale,
+        line_cap
+    )?;
+
+    Ok(())
+}
+
+fn write_fill(items: &mut String, fill: Color, fill_rule: FillRule) -> Result<(), Error> {
+    let rule = match fill_rule {
+        FillRule::NonZero => 1,
+        FillRule::EvenOdd => 2,
+    };
+    write!(
+        items,
+        "{{\"ty\":\"fl\",\"c\":{{\"a\":0,\"k\":{}}},\"o\":{{\"a\":0,\"k\":{}}},\"r\":{}}},",
+        rgb(fill),
+        fill.a() * 100.0,
+        rule
+    )?;
+
+    Ok(())
+}
+
+/// A color as a Lottie array, with each channel from 0 to 1. Opacity is set separately.
+fn rgb(color: Color) -> String {
+    format!("[{},{},{},1]", color.r(), color.g(), color.b())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Canvas, Stroke};
+
+    /// Verify that every shape becomes a layer, with the last shape drawn on top.
+    #[test]
+    fn layers_in_draw_order() {
+        let mut canvas = Canvas::new(16);
+        canvas.draw_rect(Vec2::ZERO, Vec2::ONE, None, Some(Color::red()));
+        canvas.draw_line(
+            Vec2::ZERO,
+            Vec2::ONE,
+            Some(Stroke::new(Color::blue(), 0.1, LineEnd::Round)),
+            None,
+        );
+
+        let renderer = LottieRenderer::new(Vec2::splat(100.0), None, true, 30.0, 2.0).unwrap();
+        let document = canvas.render(renderer).unwrap();
+
+        assert!(document.starts_with("{\"v\":\"5.7.0\",\"fr\":30,\"ip\":0,\"op\":60,"));
+        assert_eq!(document.matches("\"ty\":4").count(), 2);
+        assert!(document.find("\"Shape 1\"") < document.find("\"Shape 0\""));
+        assert!(document.contains("\"v\":[[50,50],[100,0]]"));
+    }
+
+    /// Verify that revealed shapes come in one after another, with strokes trimmed by keyframes and fills shown once their stroke is done.
+    #[test]
+    fn reveal_shapes() {
+        let stroke = Stroke::new(Color::blue(), 0.1, LineEnd::Round);
+        let mut canvas = Canvas::new(16);
+        canvas.draw_rect(Vec2::ZERO, Vec2::ONE, None, Some(Color::red()));
+        canvas.draw_rect(Vec2::ZERO, Vec2::ONE, Some(stroke), Some(Color::red()));
+
+        // 21 frames, so each of the two shapes gets a share of 10.
+        let renderer = || LottieRenderer::new(Vec2::splat(100.0), None, true, 21.0, 1.0).unwrap();
+
+        let appear = canvas
+            .render(renderer().with_reveal(Reveal::Appear))
+            .unwrap();
+        assert!(appear.contains("\"nm\":\"Shape 0\""));
+        assert_eq!(appear.matches("\"ip\":10,").count(), 1);
+        assert_eq!(appear.matches("\"ip\":20,").count(), 1);
+        assert!(!appear.contains("\"ty\":\"tm\""));
+
+        let draw = canvas.render(renderer().with_reveal(Reveal::Draw)).unwrap();
+        assert_eq!(draw.matches("\"ty\":4").count(), 3);
+        assert!(draw.contains("\"e\":{\"a\":1,\"k\":[{\"t\":10,\"s\":[0]"));
+        assert!(draw.contains("{\"t\":20,\"s\":[100]}"));
+        assert!(draw.find("\"Shape 1\"") < draw.find("\"Shape 1 Fill\""));
+        assert_eq!(draw.matches("\"ip\":10,").count(), 2);
+        assert_eq!(draw.matches("\"ip\":20,").count(), 1);
+
+        let still = canvas.render(renderer()).unwrap();
+        assert_eq!(still.matches("\"ip\":0,\"op\":21,\"st\"").count(), 2);
+    }
+}
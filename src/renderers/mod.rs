@@ -1,10 +1,37 @@
 #[cfg(feature = "tiny_skia_renderer")]
+mod density_renderer;
+#[cfg(feature = "lottie_renderer")]
+mod lottie_renderer;
+#[cfg(feature = "tiny_skia_renderer")]
+mod resolve;
+#[cfg(feature = "tiny_skia_renderer")]
 mod skia_renderer;
 #[cfg(feature = "svg_renderer")]
 mod svg_renderer;
+#[cfg(feature = "tikz_renderer")]
+mod tikz_renderer;
+#[cfg(feature = "tiny_skia_renderer")]
+mod tiled_skia_renderer;
+#[cfg(feature = "typst_renderer")]
+mod typst_renderer;
 
 #[cfg(feature = "svg_renderer")]
-pub use svg_renderer::SvgRenderer;
+pub use svg_renderer::{PrintMarks, StrokeAnimation, StrokeTiming, SvgRenderer};
+
+#[cfg(feature = "lottie_renderer")]
+pub use lottie_renderer::LottieRenderer;
+
+#[cfg(feature = "tikz_renderer")]
+pub use tikz_renderer::TikzRenderer;
 
+#[cfg(feature = "typst_renderer")]
+pub use typst_renderer::TypstRenderer;
+
+#[cfg(feature = "tiny_skia_renderer")]
+pub use density_renderer::{Composite, DensityBuffer, DensityRenderer, ToneMap};
+#[cfg(feature = "tiny_skia_renderer")]
+pub use resolve::{Dither, Downfilter};
+#[cfg(feature = "tiny_skia_renderer")]
+pub use skia_renderer::{write_png, Antialiasing, SkiaRenderer};
 #[cfg(feature = "tiny_skia_renderer")]
-pub use skia_renderer::SkiaRenderer;
+pub use tiled_skia_renderer::TiledSkiaRenderer;
@@ -0,0 +1,209 @@
+use glam::UVec2;
+
+/// How a supersampled image is shrunk back down to its final size.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Downfilter {
+    /// Average each block of samples. Fast, but can leave faint stair-stepping behind.
+    #[default]
+    Box,
+    /// Weight samples by distance, blending a little with neighbouring pixels for smoother edges.
+    Triangle,
+    /// A windowed sinc filter that stays sharp, at the cost of a slight halo around high-contrast edges.
+    Lanczos3,
+}
+
+impl Downfilter {
+    /// How far the filter reaches, in output pixels.
+    fn support(self) -> f32 {
+        match self {
+            Downfilter::Box => 0.5,
+            Downfilter::Triangle => 1.0,
+            Downfilter::Lanczos3 => 3.0,
+        }
+    }
+
+    /// The weight of a sample `distance` output pixels away from the center of the output pixel.
+    fn weight(self, distance: f32) -> f32 {
+        let distance = distance.abs();
+
+        match self {
+            Downfilter::Box if distance < 0.5 => 1.0,
+            Downfilter::Box => 0.0,
+            Downfilter::Triangle => (1.0 - distance).max(0.0),
+            Downfilter::Lanczos3 if distance < 3.0 => sinc(distance) * sinc(distance / 3.0),
+            Downfilter::Lanczos3 => 0.0,
+        }
+    }
+}
+
+/// A pattern used to hide banding when samples are rounded to 8 bits per channel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Dither {
+    /// Round every sample to the nearest value.
+    #[default]
+    None,
+    /// An 8x8 Bayer matrix. Cheap and stable, but the cross-hatched pattern can be visible on flat ramps.
+    Ordered,
+    /// A pattern without visible structure, approximating blue noise with the R2 low-discrepancy sequence.
+    BlueNoise,
+}
+
+impl Dither {
+    /// The offset, from `-0.5` to `0.5`, added to the pixel at `(x, y)` before rounding.
+    pub(super) fn offset(self, x: i64, y: i64) -> f32 {
+        match self {
+            Dither::None => 0.0,
+            Dither::Ordered => {
+                (BAYER[y.rem_euclid(8) as usize][x.rem_euclid(8) as usize] as f32 + 0.5) / 64.0
+                    - 0.5
+            }
+            Dither::BlueNoise => {
+                // Roberts' R2 sequence: the inverse of the plastic number and its square.
+                let value = x as f32 * 0.754_877_7 + y as f32 * 0.569_840_3;
+                value - value.floor() - 0.5
+            }
+        }
+    }
+}
+
+const BAYER: [[u8; 8]; 8] = [
+    [0, 32, 8, 40, 2, 34, 10, 42],
+    [48, 16, 56, 24, 50, 18, 58, 26],
+    [12, 44, 4, 36, 14, 46, 6, 38],
+    [60, 28, 52, 20, 62, 30, 54, 22],
+    [3, 35, 11, 43, 1, 33, 9, 41],
+    [51, 19, 59, 27, 49, 17, 57, 25],
+    [15, 47, 7, 39, 13, 45, 5, 37],
+    [63, 31, 55, 23, 61, 29, 53, 21],
+];
+
+fn sinc(x: f32) -> f32 {
+    if x == 0.0 {
+        1.0
+    } else {
+        let x = x * std::f32::consts::PI;
+        x.sin() / x
+    }
+}
+
+/// The source samples, and their normalized weights, that make up each output pixel along one axis.
+fn axis_weights(output_len: u32, factor: u32, filter: Downfilter) -> Vec<Vec<(usize, f32)>> {
+    let source_len = (output_len * factor) as i64;
+    let factor = factor as f32;
+    let reach = filter.support() * factor;
+
+    (0..output_len)
+        .map(|output| {
+            let center = (output as f32 + 0.5) * factor;
+            let start = (center - reach).floor() as i64;
+            let end = (center + reach).ceil() as i64;
+
+            let mut weights: Vec<(usize, f32)> = (start..end)
+                .map(|source| {
+                    let distance = (source as f32 + 0.5 - center) / factor;
+                    let clamped = source.clamp(0, source_len - 1) as usize;
+                    (clamped, filter.weight(distance))
+                })
+                .filter(|(_, weight)| *weight != 0.0)
+                .collect();
+
+            let total: f32 = weights.iter().map(|(_, weight)| weight).sum();
+            for (_, weight) in &mut weights {
+                *weight /= total;
+            }
+
+            weights
+        })
+        .collect()
+}
+
+/// Shrink premultiplied RGBA pixels that were rendered `factor` times larger than `size` on both axes,
+/// then round them back to 8 bits per channel with `dither`.
+pub(super) fn resolve(
+    data: &[u8],
+    size: UVec2,
+    factor: u32,
+    filter: Downfilter,
+    dither: Dither,
+) -> Vec<u8> {
+    let source_width = (size.x * factor) as usize;
+    let source_height = (size.y * factor) as usize;
+    let (width, height) = (size.x as usize, size.y as usize);
+
+    // Shrink each row first, then each column of the result.
+    let columns = axis_weights(size.x, factor, filter);
+    let mut narrow = vec![0.0; width * source_height * 4];
+    for y in 0..source_height {
+        for (x, weights) in columns.iter().enumerate() {
+            for &(source_x, weight) in weights {
+                let source = (y * source_width + source_x) * 4;
+                let target = (y * width + x) * 4;
+                for channel in 0..4 {
+                    narrow[target + channel] += data[source + channel] as f32 * weight;
+                }
+            }
+        }
+    }
+
+    let rows = axis_weights(size.y, factor, filter);
+    let mut output = vec![0; width * height * 4];
+    for (y, weights) in rows.iter().enumerate() {
+        for x in 0..width {
+            let mut pixel = [0.0; 4];
+            for &(source_y, weight) in weights {
+                let source = (source_y * width + x) * 4;
+                for (channel, value) in pixel.iter_mut().enumerate() {
+                    *value += narrow[source + channel] * weight;
+                }
+            }
+
+            // Filters with negative lobes can overshoot, so keep the result a valid premultiplied color.
+            let offset = dither.offset(x as i64, y as i64);
+            let alpha = (pixel[3] + offset).round().clamp(0.0, 255.0);
+            let target = (y * width + x) * 4;
+            for (channel, value) in pixel.iter().enumerate() {
+                output[target + channel] = (value + offset).round().clamp(0.0, alpha) as u8;
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that flat areas are left alone, and a block of mixed samples averages out.
+    #[test]
+    fn downsample_blocks() {
+        let flat = [100, 50, 25, 200].repeat(36);
+        for filter in [Downfilter::Box, Downfilter::Triangle, Downfilter::Lanczos3] {
+            let output = resolve(&flat, UVec2::new(3, 3), 2, filter, Dither::None);
+            assert_eq!(output, [100, 50, 25, 200].repeat(9), "{:?}", filter);
+        }
+
+        let white = [255, 255, 255, 255];
+        let black = [0, 0, 0, 255];
+        let checkers: Vec<u8> = [white, black, black, white].concat();
+
+        let output = resolve(&checkers, UVec2::ONE, 2, Downfilter::Box, Dither::None);
+        assert_eq!(output, [128, 128, 128, 255]);
+    }
+
+    /// Verify that dithering a value halfway between two levels rounds about half the pixels each way.
+    #[test]
+    fn dither_mixes_levels() {
+        // Each 2x2 block averages to 127.5.
+        let block = [[127, 127, 127, 255], [128, 128, 128, 255]].concat();
+        let data = block.repeat(8 * 8 * 2);
+
+        for dither in [Dither::Ordered, Dither::BlueNoise] {
+            let output = resolve(&data, UVec2::new(8, 8), 2, Downfilter::Box, dither);
+            let high = output.chunks(4).filter(|pixel| pixel[0] == 128).count();
+
+            assert!((24..=40).contains(&high), "{:?}: {}", dither, high);
+            assert!(output.chunks(4).all(|pixel| pixel[3] == 255));
+        }
+    }
+}
@@ -1,17 +1,53 @@
+use std::io::Write;
+
 use glam::{UVec2, Vec2};
 use image::RgbaImage;
-use tiny_skia::{FillRule, LineCap, Paint, PathBuilder, Pixmap, Transform};
+use tiny_skia::{
+    ColorU8, FilterQuality, LineCap, Paint, Path, PathBuilder, Pattern, Pixmap, SpreadMode,
+    Transform,
+};
 
+use super::resolve::{resolve, Dither, Downfilter};
 use crate::canvas::Shape;
-use crate::{Color, LineEnd, Renderer};
+use crate::rng::Rng;
+use crate::{BlendMode, Color, ColorProfile, Error, FillRule, GradientFill, LineEnd, Renderer};
+
+/// How a [SkiaRenderer] smooths the edges of shapes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Antialiasing {
+    /// No smoothing at all, leaving hard, pixel-aligned edges.
+    None,
+    /// tiny-skia's analytic anti-aliasing, which estimates how much of each pixel a shape covers.
+    Analytic,
+    /// Render at twice the size on both axes, then shrink the image down with a [Downfilter].
+    Supersample2x(Downfilter),
+    /// Render at four times the size on both axes, then shrink the image down with a [Downfilter].
+    ///
+    /// Uses sixteen times the memory, but fixes stair-stepping on thin, nearly horizontal lines.
+    Supersample4x(Downfilter),
+}
+
+impl Antialiasing {
+    /// How many times larger than the output image the shapes are drawn.
+    fn factor(self) -> u32 {
+        match self {
+            Antialiasing::None | Antialiasing::Analytic => 1,
+            Antialiasing::Supersample2x(_) => 2,
+            Antialiasing::Supersample4x(_) => 4,
+        }
+    }
+}
 
 /// Renderer that uses the [tiny_skia](https://github.com/RazrFalcon/tiny-skia) crate.
 /// This is NOT actual Skia, but a Rust port.
 #[derive(Clone)]
 pub struct SkiaRenderer {
-    antialias: bool,
-    scale: f32,
-    center_offset: Vec2,
+    antialiasing: Antialiasing,
+    dither: Dither,
+    size: UVec2,
+    background: Option<Color>,
+    preserve_height: bool,
+    image_space: ImageSpace,
     canvas: Pixmap,
 }
 
@@ -21,18 +57,147 @@ impl SkiaRenderer {
     /// `preserve_height` allows you to decide which axis to preserve.
     /// If `true`, then the rendered image will map `-1..=1` in the y axis in camera space to `size.y..=0`.
     /// If `false` then the rendered image will be mapped for the x axis.
+    ///
+    /// `antialias` picks between [Antialiasing::Analytic] and [Antialiasing::None]. For supersampling, use [with_antialiasing](Self::with_antialiasing).
+    ///
+    /// Returns [Error::InvalidSize] if either dimension of `size` is zero.
     pub fn new(
         size: UVec2,
         background: Option<Color>,
         antialias: bool,
         preserve_height: bool,
-    ) -> Self {
-        let mut canvas = Pixmap::new(size.x, size.y).unwrap();
+    ) -> Result<Self, Error> {
+        let antialiasing = if antialias {
+            Antialiasing::Analytic
+        } else {
+            Antialiasing::None
+        };
+
+        Ok(Self {
+            antialiasing,
+            dither: Dither::None,
+            size,
+            background,
+            preserve_height,
+            image_space: ImageSpace::new(size, preserve_height),
+            canvas: new_pixmap(size, background)?,
+        })
+    }
+
+    /// Change how the edges of shapes are smoothed, consuming the parent.
+    ///
+    /// This clears anything already rendered.
+    /// Returns [Error::InvalidSize] if the supersampled image would be too large.
+    pub fn with_antialiasing(mut self, antialiasing: Antialiasing) -> Result<Self, Error> {
+        let factor = antialiasing.factor();
+        let supersampled = UVec2::new(
+            self.size.x.saturating_mul(factor),
+            self.size.y.saturating_mul(factor),
+        );
+
+        self.canvas = new_pixmap(supersampled, self.background)?;
+        self.image_space = ImageSpace::new(supersampled, self.preserve_height);
+        self.antialiasing = antialiasing;
+
+        Ok(self)
+    }
+
+    /// Set the pattern used to hide banding where colors are rounded down to 8 bits per channel, consuming the parent.
+    ///
+    /// A [supersampled](Self::with_antialiasing) image is dithered as it is shrunk down.
+    /// Otherwise, the color of every shape is dithered as it is drawn, and [gradient fills](Shape::gradient_fill) use this pattern in place of their own noise.
+    pub fn with_dither(mut self, dither: Dither) -> Self {
+        self.dither = dither;
+        self
+    }
+}
+
+/// Encode an image rendered by a [SkiaRenderer] as a PNG tagged with a [ColorProfile], handing `writer` back once it is written.
+pub fn write_png<W: Write>(
+    image: &RgbaImage,
+    mut writer: W,
+    color_profile: &ColorProfile,
+) -> Result<W, Error> {
+    {
+        let info = color_profile.png_info(image.width(), image.height());
+        let encoder = png::Encoder::with_info(&mut writer, info).map_err(|_| Error::Format)?;
+        let mut png = encoder.write_header().map_err(|_| Error::Format)?;
+        png.write_image_data(image.as_raw())
+            .map_err(|_| Error::Format)?;
+        png.finish().map_err(|_| Error::Format)?;
+    }
+
+    Ok(writer)
+}
+
+fn new_pixmap(size: UVec2, background: Option<Color>) -> Result<Pixmap, Error> {
+    let mut pixmap = Pixmap::new(size.x, size.y).ok_or(Error::InvalidSize {
+        width: size.x,
+        height: size.y,
+    })?;
 
-        if let Some(background) = background {
-            canvas.fill(background.into());
+    if let Some(background) = background {
+        pixmap.fill(background.try_into()?);
+    }
+
+    Ok(pixmap)
+}
+
+impl Renderer for SkiaRenderer {
+    type Output = RgbaImage;
+
+    fn render(&mut self, shape: &Shape) -> Result<(), Error> {
+        if !shape.is_drawable() {
+            return Ok(());
         }
 
+        let path = self.image_space.build_path(shape)?;
+
+        // Supersampled images are dithered once they are shrunk down instead.
+        let dither = if self.antialiasing.factor() == 1 {
+            self.dither
+        } else {
+            Dither::None
+        };
+
+        paint_path(
+            &mut self.canvas,
+            &path,
+            shape,
+            &self.image_space,
+            self.antialiasing != Antialiasing::None,
+            dither,
+            Transform::identity(),
+        )
+    }
+
+    fn finalize(self) -> Result<Self::Output, Error> {
+        let (width, height) = (self.size.x, self.size.y);
+
+        let data = match self.antialiasing {
+            Antialiasing::Supersample2x(filter) => {
+                resolve(self.canvas.data(), self.size, 2, filter, self.dither)
+            }
+            Antialiasing::Supersample4x(filter) => {
+                resolve(self.canvas.data(), self.size, 4, filter, self.dither)
+            }
+            Antialiasing::None | Antialiasing::Analytic => self.canvas.take(),
+        };
+
+        RgbaImage::from_raw(width, height, data).ok_or(Error::InvalidSize { width, height })
+    }
+}
+
+/// Maps Camera Space (range from (-1, -1) to (1, 1)) onto Image Space (range from (0, 0) to image size).
+#[derive(Debug, Clone, Copy)]
+pub(super) struct ImageSpace {
+    pub(super) scale: f32,
+    center_offset: Vec2,
+}
+
+impl ImageSpace {
+    /// See [SkiaRenderer::new] for the meaning of `preserve_height`.
+    pub(super) fn new(size: UVec2, preserve_height: bool) -> Self {
         let (scale, center_offset) = if preserve_height {
             let scale = size.y as f32 / 2.0;
             (scale, Vec2::new(size.x as f32 / 2.0 / scale, 1.0))
@@ -42,30 +207,37 @@ impl SkiaRenderer {
         };
 
         Self {
-            antialias,
             scale,
             center_offset,
-            canvas,
         }
     }
-}
 
-impl Renderer for SkiaRenderer {
-    type Output = RgbaImage;
+    /// Transform a point in Camera Space into Image Space.
+    pub(super) fn image_point(&self, point: Vec2) -> Vec2 {
+        (Vec2::new(point.x, -point.y) + self.center_offset) * self.scale
+    }
 
-    fn render(&mut self, shape: &Shape) {
-        if !shape.is_drawable(){
+    /// Turn every path in a shape into a single tiny-skia [Path] in Image Space.
+    pub(super) fn build_path(&self, shape: &Shape) -> Result<Path, Error> {
+        let mut path = PathBuilder::new();
+        for points in shape.paths() {
+            self.append_subpath(&mut path, points);
+        }
+
+        path.finish().ok_or(Error::InvalidPath)
+    }
+
+    /// Add a path in Camera Space to a [PathBuilder], transformed into Image Space.
+    fn append_subpath(&self, path: &mut PathBuilder, points: &[Vec2]) {
+        if points.len() <= 1 {
             return;
         }
 
-        // Transform from Camera Space (range from (-1, -1) to (1, 1)) to Image Space (range from (0, 0) to image size).
-        let mut points = shape.points.iter().map(|p| {
-            let p = Vec2::new(p.x, -p.y) + self.center_offset;
-            p * self.scale
-        });
+        let is_polygon = points.len() >= 3 && points[0] == points[points.len() - 1];
+
+        let mut points = points.iter().map(|p| self.image_point(*p));
 
         if let Some(first) = points.next() {
-            let mut path = PathBuilder::new();
             path.move_to(first.x, first.y);
 
             // Grab second point in case we need to complete a polygon properly.
@@ -79,57 +251,279 @@ impl Renderer for SkiaRenderer {
             }
 
             // Fix ends of polygon
-            if shape.is_polygon() {
-                let second = second.unwrap();
-
-                path.line_to(second.x, second.y);
+            if is_polygon {
+                if let Some(second) = second {
+                    path.line_to(second.x, second.y);
+                }
             }
+        }
+    }
+}
 
-            let path = path.finish().unwrap();
-
-            if let Some(stroke) = shape.stroke {
-                let mut paint = Paint::default();
-                paint.set_color(stroke.color.into());
-                paint.anti_alias = self.antialias;
-
-                self.canvas.stroke_path(
-                    &path,
-                    &paint,
-                    &tiny_skia::Stroke {
-                        width: stroke.width * self.scale,
-                        line_cap: match stroke.line_end {
-                            LineEnd::Butt => LineCap::Butt,
-                            LineEnd::Round => LineCap::Round,
-                        },
-                        ..Default::default()
-                    },
-                    Transform::identity(),
-                    None,
-                );
-            }
+/// Stroke and fill a path with the style of `shape`. The points of `shape` are not used.
+///
+/// `image_space` converts stroke widths and gradients into pixels, and `transform` is applied to the path as it is drawn.
+/// Colors are rounded to 8 bits per channel with `dither`.
+pub(super) fn paint_path(
+    pixmap: &mut Pixmap,
+    path: &Path,
+    shape: &Shape,
+    image_space: &ImageSpace,
+    antialias: bool,
+    dither: Dither,
+    transform: Transform,
+) -> Result<(), Error> {
+    if let Some(stroke) = shape.stroke {
+        let tile = dither_tile(stroke.color, dither)?;
+        let mut paint = solid_paint(stroke.color, tile.as_ref())?;
+        paint.anti_alias = antialias;
+        paint.blend_mode = skia_blend_mode(shape.blend_mode);
 
-            if let Some(fill) = shape.fill {
-                let mut paint = Paint::default();
-                paint.set_color(fill.into());
-                paint.anti_alias = self.antialias;
-
-                self.canvas.fill_path(
-                    &path,
-                    &paint,
-                    FillRule::Winding,
-                    Transform::identity(),
-                    None,
-                );
-            }
+        pixmap.stroke_path(
+            path,
+            &paint,
+            &tiny_skia::Stroke {
+                width: stroke.width * image_space.scale,
+                line_cap: match stroke.line_end {
+                    LineEnd::Butt => LineCap::Butt,
+                    LineEnd::Round => LineCap::Round,
+                },
+                ..Default::default()
+            },
+            transform,
+            None,
+        );
+    }
+
+    if let Some(fill) = shape.fill {
+        let tile = match shape.gradient_fill {
+            Some(_) => None,
+            None => dither_tile(fill, dither)?,
+        };
+        let mut paint = solid_paint(fill, tile.as_ref())?;
+        paint.anti_alias = antialias;
+        paint.blend_mode = skia_blend_mode(shape.blend_mode);
+
+        // Keep the gradient alive until the path is filled, since the pattern borrows it.
+        let gradient = match &shape.gradient_fill {
+            Some(gradient_fill) => Some(rasterize_gradient(
+                gradient_fill,
+                path,
+                image_space,
+                dither,
+                pixmap,
+                transform,
+            )?),
+            None => None,
+        };
+        if let Some((gradient, left, top)) = &gradient {
+            // Filling applies `transform` to the pattern along with the path, so it is placed in the same space.
+            paint.shader = Pattern::new(
+                gradient.as_ref(),
+                SpreadMode::Pad,
+                FilterQuality::Nearest,
+                1.0,
+                Transform::from_translate(*left, *top),
+            );
         }
+
+        pixmap.fill_path(
+            path,
+            &paint,
+            match shape.fill_rule {
+                FillRule::NonZero => tiny_skia::FillRule::Winding,
+                FillRule::EvenOdd => tiny_skia::FillRule::EvenOdd,
+            },
+            transform,
+            None,
+        );
     }
 
-    fn finalize(self) -> Self::Output {
-        RgbaImage::from_raw(
-            self.canvas.width(),
-            self.canvas.height(),
-            self.canvas.take(),
+    Ok(())
+}
+
+/// A [Paint] of `color`, repeating `tile` across the image instead when there is one.
+fn solid_paint(color: Color, tile: Option<&Pixmap>) -> Result<Paint<'_>, Error> {
+    let mut paint = Paint::default();
+    paint.set_color(color.try_into()?);
+
+    if let Some(tile) = tile {
+        paint.shader = Pattern::new(
+            tile.as_ref(),
+            SpreadMode::Repeat,
+            FilterQuality::Nearest,
+            1.0,
+            Transform::identity(),
+        );
+    }
+
+    Ok(paint)
+}
+
+/// A tile of `color` rounded to 8 bits per channel with `dither`, to repeat across the image, or `None` without dithering.
+fn dither_tile(color: Color, dither: Dither) -> Result<Option<Pixmap>, Error> {
+    if dither == Dither::None {
+        return Ok(None);
+    }
+
+    // The Bayer matrix repeats every 8 pixels. Blue noise doesn't repeat, but a seam every 64 pixels can't be seen.
+    let size = if dither == Dither::Ordered { 8 } else { 64 };
+    let mut tile = Pixmap::new(size, size).ok_or(Error::InvalidSize {
+        width: size,
+        height: size,
+    })?;
+
+    for (i, pixel) in tile.pixels_mut().iter_mut().enumerate() {
+        let offset = dither.offset(i as i64 % size as i64, i as i64 / size as i64);
+        let channel = |value: f32, offset: f32| {
+            (value.clamp(0.0, 1.0) * 255.0 + offset)
+                .round()
+                .clamp(0.0, 255.0) as u8
+        };
+        *pixel = ColorU8::from_rgba(
+            channel(color.r(), offset),
+            channel(color.g(), offset),
+            channel(color.b(), offset),
+            channel(color.a(), 0.0),
         )
-        .unwrap()
+        .premultiply();
+    }
+
+    Ok(Some(tile))
+}
+
+/// Draw a [GradientFill] into a pixmap covering the bounds of `path`, with its dither and grain applied to every pixel.
+///
+/// Its dither follows the pattern of `dither`, unless that is [Dither::None].
+/// Only the part of the bounds that `transform` puts on `target` is drawn, so huge paths don't need huge pixmaps.
+/// Returns the pixmap, along with the position of its top left corner in Image Space.
+fn rasterize_gradient(
+    gradient_fill: &GradientFill,
+    path: &Path,
+    image_space: &ImageSpace,
+    dither: Dither,
+    target: &Pixmap,
+    transform: Transform,
+) -> Result<(Pixmap, f32, f32), Error> {
+    let bounds = path.bounds();
+    let (mut left, mut top) = (bounds.left(), bounds.top());
+    let (mut right, mut bottom) = (bounds.right(), bounds.bottom());
+
+    // Map the edges of the target back through the transform, when it has no rotation or skew to make that hard.
+    if !transform.has_skew() && transform.sx != 0.0 && transform.sy != 0.0 {
+        let unmap = |edge: f32, scale: f32, offset: f32| (edge - offset) / scale;
+        let (x0, x1) = (
+            unmap(0.0, transform.sx, transform.tx),
+            unmap(target.width() as f32, transform.sx, transform.tx),
+        );
+        let (y0, y1) = (
+            unmap(0.0, transform.sy, transform.ty),
+            unmap(target.height() as f32, transform.sy, transform.ty),
+        );
+        left = left.max(x0.min(x1));
+        right = right.min(x0.max(x1));
+        top = top.max(y0.min(y1));
+        bottom = bottom.min(y0.max(y1));
+    }
+
+    let (left, top) = (left.floor(), top.floor());
+    let width = (right.ceil() - left).max(1.0) as u32;
+    let height = (bottom.ceil() - top).max(1.0) as u32;
+    let mut pixmap = Pixmap::new(width, height).ok_or(Error::InvalidSize { width, height })?;
+
+    let start = image_space.image_point(gradient_fill.start);
+    let direction = image_space.image_point(gradient_fill.end) - start;
+    let length_squared = direction.length_squared();
+
+    for (i, pixel) in pixmap.pixels_mut().iter_mut().enumerate() {
+        let (x, y) = (i as u32 % width, i as u32 / width);
+        let center = Vec2::new(left + x as f32 + 0.5, top + y as f32 + 0.5);
+
+        let t = if length_squared == 0.0 {
+            0.0
+        } else {
+            (center - start).dot(direction) / length_squared
+        };
+        let mut color = gradient_fill.gradient.sample(t);
+
+        // Interleaved gradient noise, which spreads its energy evenly like blue noise, tied to the position in the image.
+        let (image_x, image_y) = (center.x.floor(), center.y.floor());
+        let blue = match dither {
+            Dither::None => {
+                (52.982_918 * (0.067_110_56 * image_x + 0.005_837_15 * image_y).fract()).fract()
+                    - 0.5
+            }
+            dither => dither.offset(image_x as i64, image_y as i64),
+        };
+        let white =
+            Rng::new(((image_x as i64 as u64) << 32) ^ image_y as i64 as u64).next_f32() - 0.5;
+        let noise = blue * gradient_fill.dither / 255.0 + white * gradient_fill.grain;
+
+        color = Color::new(
+            color.r() + noise,
+            color.g() + noise,
+            color.b() + noise,
+            color.a(),
+        );
+
+        let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        *pixel = ColorU8::from_rgba(
+            channel(color.r()),
+            channel(color.g()),
+            channel(color.b()),
+            channel(color.a()),
+        )
+        .premultiply();
+    }
+
+    Ok((pixmap, left, top))
+}
+
+fn skia_blend_mode(blend_mode: BlendMode) -> tiny_skia::BlendMode {
+    match blend_mode {
+        BlendMode::Normal => tiny_skia::BlendMode::SourceOver,
+        BlendMode::Multiply => tiny_skia::BlendMode::Multiply,
+        BlendMode::Screen => tiny_skia::BlendMode::Screen,
+        BlendMode::Overlay => tiny_skia::BlendMode::Overlay,
+        BlendMode::Darken => tiny_skia::BlendMode::Darken,
+        BlendMode::Lighten => tiny_skia::BlendMode::Lighten,
+        BlendMode::Add => tiny_skia::BlendMode::Plus,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Canvas;
+
+    /// Verify that a color halfway between two levels is dithered without supersampling, and that the order the renderer is built in doesn't matter.
+    #[test]
+    fn dither_flat_color() {
+        let mut canvas = Canvas::new(4);
+        let gray = Color::new(0.5, 0.5, 0.5, 1.0);
+        canvas.draw_rect((-2.0, -2.0), (2.0, 2.0), None, Some(gray));
+        canvas.draw_circle((0.0, 0.0), 0.5, None, Some(Color::white()));
+
+        let plain = || SkiaRenderer::new(UVec2::splat(16), None, false, true).unwrap();
+        let high = |renderer: SkiaRenderer| {
+            let image = canvas.render(renderer).unwrap();
+            image.pixels().filter(|pixel| pixel[0] == 128).count()
+        };
+        assert!((80..=128).contains(&high(plain().with_dither(Dither::Ordered))));
+        assert!((80..=128).contains(&high(plain().with_dither(Dither::BlueNoise))));
+
+        let supersampled = Antialiasing::Supersample2x(Downfilter::Triangle);
+        let before = plain()
+            .with_dither(Dither::Ordered)
+            .with_antialiasing(supersampled)
+            .unwrap();
+        let after = plain()
+            .with_antialiasing(supersampled)
+            .unwrap()
+            .with_dither(Dither::Ordered);
+        assert_eq!(
+            canvas.render(before).unwrap(),
+            canvas.render(after).unwrap()
+        );
     }
 }
@@ -1,8 +1,91 @@
 use glam::Vec2;
 
-use crate::{Color, LineEnd, Renderer, Shape};
+use crate::{
+    color_profile::base64, BlendMode, Color, ColorProfile, Error, FillRule, GradientFill, LineEnd,
+    Renderer, Shape,
+};
 use std::fmt::Write;
 
+/// When each stroke starts being drawn in a [StrokeAnimation].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StrokeTiming {
+    /// One stroke after another, in the order they are rendered.
+    #[default]
+    PerShape,
+    /// Every stroke of the same color starts together, one color after another, like a plotter changing pens.
+    PerPen,
+}
+
+/// Draws each stroke of an [SvgRenderer] along its length as the document loads, with CSS animations of `stroke-dashoffset`.
+///
+/// Fills are not animated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StrokeAnimation {
+    /// How long drawing each stroke takes, in seconds.
+    pub duration: f32,
+    /// How long to wait between starting one shape or pen and the next, in seconds.
+    pub stagger: f32,
+    /// How long to wait before the first stroke starts, in seconds.
+    pub delay: f32,
+    /// Whether strokes are staggered by shape or by pen.
+    pub timing: StrokeTiming,
+}
+
+impl StrokeAnimation {
+    /// Creates a new [StrokeAnimation], drawing strokes one after another, each taking `duration` seconds.
+    pub fn new(duration: f32) -> Self {
+        Self {
+            duration,
+            stagger: duration,
+            delay: 0.0,
+            timing: StrokeTiming::PerShape,
+        }
+    }
+}
+
+/// Bleed, crop marks and registration targets an [SvgRenderer] draws around the page, so the file is ready for a print shop.
+///
+/// The size of the image becomes the trim box, where the print is cut. Artwork runs on past it into the bleed,
+/// so no paper shows if the cut is slightly off, and the marks are drawn in a margin beyond that.
+/// The document grows by that margin on every side.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrintMarks {
+    /// How far artwork runs on past the trim box, in the same units as the size of the image.
+    pub bleed: f32,
+    /// The length of the crop marks, which is also the width of the margin they are drawn in, beyond the bleed.
+    pub mark_length: f32,
+    /// The width of the lines of the marks.
+    pub mark_width: f32,
+    /// Whether to draw crop marks in line with each edge of the trim box, at its corners.
+    pub crop_marks: bool,
+    /// Whether to draw registration targets halfway along each side, for lining up the inks of each plate.
+    pub registration: bool,
+}
+
+impl PrintMarks {
+    /// Creates new [PrintMarks], with both crop marks and registration targets.
+    pub fn new(bleed: f32, mark_length: f32) -> Self {
+        Self {
+            bleed,
+            mark_length,
+            mark_width: mark_length / 24.0,
+            crop_marks: true,
+            registration: true,
+        }
+    }
+
+    /// Set the width of the lines of the marks, consuming the parent.
+    pub fn with_mark_width(mut self, mark_width: f32) -> Self {
+        self.mark_width = mark_width;
+        self
+    }
+
+    /// How far the document reaches past the trim box on each side.
+    fn margin(&self) -> f32 {
+        self.bleed + self.mark_length
+    }
+}
+
 /// A renderer for Scalable Vector Graphics.
 ///
 /// Unless a shape approximates a circle, it will be drawn as either a polygon or a polyline.
@@ -13,6 +96,14 @@ pub struct SvgRenderer {
     center_offset: Vec2,
     ints_only: bool,
     circle_vertex_threshold: usize,
+    stroke_animation: Option<StrokeAnimation>,
+    animated_strokes: usize,
+    pens: Vec<Color>,
+    gradients: usize,
+    size: Vec2,
+    background: Option<Color>,
+    color_profile: Option<ColorProfile>,
+    print_marks: Option<PrintMarks>,
     document: String,
 }
 
@@ -28,29 +119,15 @@ impl SvgRenderer {
         ints_only: bool,
         preserve_height: bool,
         circle_vertex_threshold: usize,
-    ) -> Self {
-        let mut document = format!(
-            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">",
-            size.x, size.y
-        );
-
-        if let Some(background) = background {
-            write!(
-                document,
-                "<rect fill=\"{}\" width=\"{}\" height=\"{}\"/>",
-                background.as_hex(false),
-                size.x,
-                size.y
-            )
-            .unwrap();
-        }
+    ) -> Result<Self, Error> {
+        let document = start_document(size, background, None, None)?;
 
         let (scale, center_offset) = if preserve_height {
-            let scale = size.y as f32 / 2.0;
-            (scale, Vec2::new(size.x as f32 / 2.0 / scale, 1.0))
+            let scale = size.y / 2.0;
+            (scale, Vec2::new(size.x / 2.0 / scale, 1.0))
         } else {
-            let scale = size.x as f32 / 2.0;
-            (scale, Vec2::new(1.0, size.y as f32 / 2.0 / scale))
+            let scale = size.x / 2.0;
+            (scale, Vec2::new(1.0, size.y / 2.0 / scale))
         };
 
         let circle_vertex_threshold = if circle_vertex_threshold < 3 {
@@ -59,26 +136,158 @@ impl SvgRenderer {
             circle_vertex_threshold
         };
 
-        Self {
+        Ok(Self {
             scale,
             center_offset,
             ints_only,
             circle_vertex_threshold,
+            stroke_animation: None,
+            animated_strokes: 0,
+            pens: Vec::new(),
+            gradients: 0,
+            size,
+            background,
+            color_profile: None,
+            print_marks: None,
             document,
+        })
+    }
+
+    /// Tag the document with the color space its colors are meant for, consuming the parent.
+    ///
+    /// Colors are written with the CSS `color()` function, so they are not clamped to sRGB. ICC profiles are embedded with `@color-profile`.
+    /// This clears anything already rendered.
+    pub fn with_color_profile(mut self, color_profile: ColorProfile) -> Result<Self, Error> {
+        self.document = start_document(
+            self.size,
+            self.background,
+            Some(&color_profile),
+            self.print_marks.as_ref(),
+        )?;
+        self.color_profile = Some(color_profile);
+
+        Ok(self)
+    }
+
+    /// Draw a bleed, crop marks and registration targets around the page, consuming the parent.
+    ///
+    /// The background fills the bleed, and artwork is cut off at its edge. This clears anything already rendered.
+    pub fn with_print_marks(mut self, print_marks: PrintMarks) -> Result<Self, Error> {
+        self.document = start_document(
+            self.size,
+            self.background,
+            self.color_profile.as_ref(),
+            Some(&print_marks),
+        )?;
+        self.print_marks = Some(print_marks);
+
+        Ok(self)
+    }
+
+    /// Animate strokes being drawn, consuming the parent.
+    pub fn with_stroke_animation(mut self, animation: StrokeAnimation) -> Self {
+        self.stroke_animation = Some(animation);
+        self
+    }
+}
+
+impl SvgRenderer {
+    /// Transform a point in Camera Space (range from (-1, -1) to (1, 1)) to Image Space (range from (0, 0) to image size).
+    fn image_point(&self, point: Vec2) -> Vec2 {
+        (Vec2::new(point.x, -point.y) + self.center_offset) * self.scale
+    }
+
+    /// Write a point in Camera Space to the document, transformed into Image Space.
+    fn write_point(&mut self, point: Vec2) -> Result<(), Error> {
+        let point = self.image_point(point);
+
+        if self.ints_only {
+            write!(self.document, "{},{} ", point.x.round(), point.y.round())?;
+        } else {
+            write!(self.document, "{},{} ", point.x, point.y)?;
+        }
+
+        Ok(())
+    }
+
+    /// Seconds before a stroke of `color` starts being drawn, counting it as the next stroke.
+    fn stroke_delay(&mut self, animation: StrokeAnimation, color: Color) -> f32 {
+        let index = match animation.timing {
+            StrokeTiming::PerShape => self.animated_strokes,
+            StrokeTiming::PerPen => match self.pens.iter().position(|pen| *pen == color) {
+                Some(index) => index,
+                None => {
+                    self.pens.push(color);
+                    self.pens.len() - 1
+                }
+            },
+        };
+        self.animated_strokes += 1;
+
+        animation.delay + animation.stagger * index as f32
+    }
+
+    /// Define a linear gradient for `gradient_fill`, and a grain filter if it has any noise, returning the number in their ids.
+    ///
+    /// SVG cannot dither per pixel, so dither and grain are approximated together with `feTurbulence`.
+    fn define_gradient(&mut self, gradient_fill: &GradientFill) -> Result<usize, Error> {
+        let id = self.gradients;
+        self.gradients += 1;
+
+        let (start, end) = (
+            self.image_point(gradient_fill.start),
+            self.image_point(gradient_fill.end),
+        );
+        write!(
+            self.document,
+            "<defs><linearGradient id=\"barium-gradient-{}\" gradientUnits=\"userSpaceOnUse\" x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\">",
+            id, start.x, start.y, end.x, end.y
+        )?;
+        for (position, color) in gradient_fill.gradient.stops() {
+            write!(
+                self.document,
+                "<stop offset=\"{}\" stop-color=\"{}\" stop-opacity=\"{}\"/>",
+                position,
+                paint(*color, self.color_profile.as_ref()),
+                color.a()
+            )?;
+        }
+        write!(self.document, "</linearGradient>")?;
+
+        let amount = gradient_fill.grain + gradient_fill.dither / 255.0;
+        if amount > 0.0 {
+            // Gray noise, centered on zero, added to the shape and clipped back to its outline.
+            write!(
+                self.document,
+                "<filter id=\"barium-grain-{}\" x=\"0\" y=\"0\" width=\"1\" height=\"1\">\
+                <feTurbulence type=\"fractalNoise\" baseFrequency=\"0.8\" numOctaves=\"2\" stitchTiles=\"stitch\"/>\
+                <feColorMatrix type=\"saturate\" values=\"0\"/>\
+                <feComposite in2=\"SourceGraphic\" operator=\"arithmetic\" k2=\"{}\" k3=\"1\" k4=\"{}\"/>\
+                <feComposite in2=\"SourceGraphic\" operator=\"in\"/>\
+                </filter>",
+                id,
+                amount,
+                -amount / 2.0
+            )?;
         }
+        write!(self.document, "</defs>")?;
+
+        Ok(id)
     }
 }
 
 impl Renderer for SvgRenderer {
     type Output = String;
 
-    fn render(&mut self, shape: &Shape) {
-        if !shape.is_drawable(){
-            return;
+    fn render(&mut self, shape: &Shape) -> Result<(), Error> {
+        if !shape.is_drawable() {
+            return Ok(());
         }
 
         // Check if shape approximates a circle, if so, render it as such.
-        let is_circle = if shape.points.len() >= self.circle_vertex_threshold && shape.is_polygon()
+        let is_circle = if shape.subpaths.is_empty()
+            && shape.points.len() >= self.circle_vertex_threshold
+            && shape.is_polygon()
         {
             let center = shape.points.iter().sum::<Vec2>() / shape.points.len() as f32;
             let d = center.distance(shape.points[0]);
@@ -98,72 +307,426 @@ impl Renderer for SvgRenderer {
             None
         };
 
-        if shape.points.len() > 3 && shape.is_polygon() {
+        let gradient = match (&shape.gradient_fill, shape.fill) {
+            (Some(gradient_fill), Some(_)) => Some((
+                self.define_gradient(gradient_fill)?,
+                gradient_fill.grain + gradient_fill.dither > 0.0,
+            )),
+            _ => None,
+        };
+
+        if let Some(link) = &shape.link {
+            write!(self.document, "<a href=\"{}\">", escape(link))?;
+        }
+
+        let element = if !shape.subpaths.is_empty() {
+            "path"
+        } else if shape.points.len() > 3 && shape.is_polygon() {
+            if is_circle.is_some() {
+                "circle"
+            } else {
+                "polygon"
+            }
+        } else {
+            "polyline"
+        };
+
+        if !shape.subpaths.is_empty() {
+            // Only paths can hold several subpaths.
+            write!(self.document, "<path d=\"")?;
+
+            for points in shape.paths().filter(|points| points.len() > 1) {
+                for (i, point) in points.iter().enumerate() {
+                    write!(self.document, "{}", if i == 0 { "M" } else { "L" })?;
+                    self.write_point(*point)?;
+                }
+
+                if points.len() >= 3 && points[0] == points[points.len() - 1] {
+                    write!(self.document, "Z ")?;
+                }
+            }
+        } else if shape.points.len() > 3 && shape.is_polygon() {
             if let Some((circle_center, circle_radius)) = is_circle {
                 write!(
                     self.document,
                     "<circle cx=\"{}\" cy=\"{}\" r=\"{}",
                     circle_center.x, circle_center.y, circle_radius
-                )
-                .unwrap();
+                )?;
             } else {
-                write!(self.document, "<polygon points=\"").unwrap();
+                write!(self.document, "<polygon points=\"")?;
             }
         } else {
-            write!(self.document, "<polyline points=\"").unwrap();
-        }
-
-        if is_circle.is_none() {
-            for point in shape.points.iter().map(|p| {
-                // Transform from Camera Space (range from (-1, -1) to (1, 1)) to Image Space (range from (0, 0) to image size).
-                let p = Vec2::new(p.x, -p.y) + self.center_offset;
-                p * self.scale
-            }) {
-                if self.ints_only {
-                    write!(self.document, "{},{} ", point.x.round(), point.y.round()).unwrap();
-                } else {
-                    write!(self.document, "{},{} ", point.x, point.y).unwrap();
-                }
+            write!(self.document, "<polyline points=\"")?;
+        }
+
+        if is_circle.is_none() && shape.subpaths.is_empty() {
+            for point in &shape.points {
+                self.write_point(*point)?;
             }
         }
 
-        write!(self.document, "\" style=\"").unwrap();
+        let animation = shape.stroke.zip(self.stroke_animation);
+        if animation.is_some() {
+            // Measure the path as one unit long, so the same dash hides any stroke.
+            write!(self.document, "\" pathLength=\"1")?;
+        }
+
+        write!(self.document, "\" style=\"")?;
+
+        if let Some((stroke, animation)) = animation {
+            let delay = self.stroke_delay(animation, stroke.color);
+            write!(
+                self.document,
+                "stroke-dasharray:1;stroke-dashoffset:1;animation:barium-draw {}s linear {}s forwards;",
+                animation.duration, delay
+            )?;
+        }
 
         if let Some(stroke) = shape.stroke {
             write!(
                 self.document,
                 "stroke:{};stroke-width:{};",
-                stroke.color.as_hex(false),
+                paint(stroke.color, self.color_profile.as_ref()),
                 stroke.width * self.scale
-            )
-            .unwrap();
+            )?;
 
             if stroke.color.a() != 1.0 {
-                write!(self.document, "stroke-opacity:{};", stroke.color.a()).unwrap();
+                write!(self.document, "stroke-opacity:{};", stroke.color.a())?;
             }
 
             match stroke.line_end {
-                LineEnd::Butt => write!(self.document, "stroke-linecap:butt;").unwrap(),
-                LineEnd::Round => write!(self.document, "stroke-linecap:round;").unwrap(),
+                LineEnd::Butt => write!(self.document, "stroke-linecap:butt;")?,
+                LineEnd::Round => write!(self.document, "stroke-linecap:round;")?,
             }
         }
 
-        if let Some(fill) = shape.fill {
-            write!(self.document, "fill:{};", fill.as_hex(false)).unwrap();
+        if let Some((id, grain)) = gradient {
+            write!(self.document, "fill:url(#barium-gradient-{});", id)?;
+
+            if grain {
+                write!(self.document, "filter:url(#barium-grain-{});", id)?;
+            }
+
+            if shape.fill_rule == FillRule::EvenOdd {
+                write!(self.document, "fill-rule:evenodd;")?;
+            }
+        } else if let Some(fill) = shape.fill {
+            write!(
+                self.document,
+                "fill:{};",
+                paint(fill, self.color_profile.as_ref())
+            )?;
 
             if fill.a() != 1.0 {
-                write!(self.document, "fill-opacity:{};", fill.a()).unwrap();
+                write!(self.document, "fill-opacity:{};", fill.a())?;
+            }
+
+            if shape.fill_rule == FillRule::EvenOdd {
+                write!(self.document, "fill-rule:evenodd;")?;
             }
         } else {
-            write!(self.document, "fill:none;").unwrap();
+            write!(self.document, "fill:none;")?;
+        }
+
+        let blend_mode = match shape.blend_mode {
+            BlendMode::Normal => None,
+            BlendMode::Multiply => Some("multiply"),
+            BlendMode::Screen => Some("screen"),
+            BlendMode::Overlay => Some("overlay"),
+            BlendMode::Darken => Some("darken"),
+            BlendMode::Lighten => Some("lighten"),
+            BlendMode::Add => Some("plus-lighter"),
+        };
+        if let Some(blend_mode) = blend_mode {
+            write!(self.document, "mix-blend-mode:{};", blend_mode)?;
+        }
+
+        match &shape.tooltip {
+            // Browsers show the title of an element when hovering over it.
+            Some(tooltip) => write!(
+                self.document,
+                "\"><title>{}</title></{}>",
+                escape(tooltip),
+                element
+            )?,
+            None => write!(self.document, "\"/>")?,
+        }
+
+        if shape.link.is_some() {
+            write!(self.document, "</a>")?;
+        }
+
+        Ok(())
+    }
+
+    fn finalize(mut self) -> Result<Self::Output, Error> {
+        if self.stroke_animation.is_some() {
+            write!(
+                self.document,
+                "<style>@keyframes barium-draw{{to{{stroke-dashoffset:0}}}}</style>"
+            )?;
+        }
+
+        if let Some(print_marks) = self.print_marks {
+            write!(self.document, "</g>")?;
+            write_print_marks(
+                &mut self.document,
+                self.size,
+                &print_marks,
+                self.color_profile.as_ref(),
+            )?;
+        }
+
+        write!(self.document, "</svg>")?;
+
+        Ok(self.document)
+    }
+}
+
+/// Open the document, and draw the background.
+///
+/// With print marks, the page is moved inside the margin, and everything drawn on it is clipped to the bleed.
+fn start_document(
+    size: Vec2,
+    background: Option<Color>,
+    color_profile: Option<&ColorProfile>,
+    print_marks: Option<&PrintMarks>,
+) -> Result<String, Error> {
+    let margin = print_marks.map_or(0.0, PrintMarks::margin);
+    let mut document = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">",
+        size.x + margin * 2.0,
+        size.y + margin * 2.0
+    );
+
+    if let Some(ColorProfile::Icc(profile)) = color_profile {
+        write!(
+            document,
+            "<style>@color-profile --barium{{src:url(\"data:application/vnd.iccprofile;base64,{}\")}}</style>",
+            base64(profile)
+        )?;
+    }
+
+    let bleed = print_marks.map_or(0.0, |print_marks| print_marks.bleed);
+    if print_marks.is_some() {
+        write!(
+            document,
+            "<defs><clipPath id=\"barium-bleed\"><rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/></clipPath></defs>\
+            <g transform=\"translate({},{})\" clip-path=\"url(#barium-bleed)\">",
+            -bleed,
+            -bleed,
+            size.x + bleed * 2.0,
+            size.y + bleed * 2.0,
+            margin,
+            margin
+        )?;
+    }
+
+    if let Some(background) = background {
+        if print_marks.is_some() {
+            write!(
+                document,
+                "<rect fill=\"{}\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>",
+                paint(background, color_profile),
+                -bleed,
+                -bleed,
+                size.x + bleed * 2.0,
+                size.y + bleed * 2.0
+            )?;
+        } else {
+            write!(
+                document,
+                "<rect fill=\"{}\" width=\"{}\" height=\"{}\"/>",
+                paint(background, color_profile),
+                size.x,
+                size.y
+            )?;
+        }
+    }
+
+    Ok(document)
+}
+
+/// Draw the crop marks and registration targets in the margin around a page of `size`.
+///
+/// Marks are drawn in registration black, which prints on every plate.
+fn write_print_marks(
+    document: &mut String,
+    size: Vec2,
+    print_marks: &PrintMarks,
+    color_profile: Option<&ColorProfile>,
+) -> Result<(), Error> {
+    let PrintMarks {
+        bleed,
+        mark_length,
+        mark_width,
+        ..
+    } = *print_marks;
+    let margin = print_marks.margin();
+    let (min, max) = (Vec2::splat(margin), Vec2::splat(margin) + size);
+
+    write!(
+        document,
+        "<g style=\"stroke:{};stroke-width:{};fill:none;\">",
+        paint(Color::black(), color_profile),
+        mark_width
+    )?;
+
+    let line = |document: &mut String, from: Vec2, to: Vec2| {
+        write!(
+            document,
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>",
+            from.x, from.y, to.x, to.y
+        )
+    };
+
+    if print_marks.crop_marks {
+        // Each corner gets a mark in line with both of its edges, pointing away from the page and clear of the bleed.
+        for (x, outward_x) in [(min.x, -1.0), (max.x, 1.0)] {
+            for (y, outward_y) in [(min.y, -1.0), (max.y, 1.0)] {
+                let corner = Vec2::new(x, y);
+                for outward in [Vec2::new(outward_x, 0.0), Vec2::new(0.0, outward_y)] {
+                    line(
+                        document,
+                        corner + outward * bleed,
+                        corner + outward * (bleed + mark_length),
+                    )?;
+                }
+            }
+        }
+    }
+
+    if print_marks.registration {
+        // A circle with crosshairs, centered in the margin halfway along each side.
+        let middle = (min + max) / 2.0;
+        let offset = bleed + mark_length / 2.0;
+        let radius = mark_length * 0.3;
+        for center in [
+            Vec2::new(middle.x, min.y - offset),
+            Vec2::new(middle.x, max.y + offset),
+            Vec2::new(min.x - offset, middle.y),
+            Vec2::new(max.x + offset, middle.y),
+        ] {
+            let reach = mark_length * 0.45;
+            line(document, center - Vec2::X * reach, center + Vec2::X * reach)?;
+            line(document, center - Vec2::Y * reach, center + Vec2::Y * reach)?;
+            write!(
+                document,
+                "<circle cx=\"{}\" cy=\"{}\" r=\"{}\"/>",
+                center.x, center.y, radius
+            )?;
+        }
+    }
+
+    write!(document, "</g>")?;
+
+    Ok(())
+}
+
+/// A color as CSS, ignoring alpha. Colors in sRGB are written as hex, which every viewer understands.
+fn paint(color: Color, color_profile: Option<&ColorProfile>) -> String {
+    match color_profile {
+        None | Some(ColorProfile::Srgb) => color.as_hex(false),
+        Some(color_profile) => format!(
+            "color({} {} {} {})",
+            color_profile.css_name(),
+            color.r(),
+            color.g(),
+            color.b()
+        ),
+    }
+}
+
+/// Escape text for use in XML content and attributes.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Canvas, Stroke};
+
+    /// Verify that animated strokes of the same pen start together, and each pen starts after the last.
+    #[test]
+    fn stroke_animation_per_pen() {
+        let mut canvas = Canvas::new(16);
+        for (color, y) in [
+            (Color::red(), 0.0),
+            (Color::blue(), 0.5),
+            (Color::red(), 1.0),
+        ] {
+            canvas.draw_line(
+                Vec2::new(0.0, y),
+                Vec2::new(1.0, y),
+                Some(Stroke::new(color, 0.1, LineEnd::Butt)),
+                None,
+            );
         }
 
-        write!(self.document, "\"/>").unwrap();
+        let animation = StrokeAnimation {
+            timing: StrokeTiming::PerPen,
+            ..StrokeAnimation::new(2.0)
+        };
+        let renderer = SvgRenderer::new(Vec2::splat(100.0), None, true, true, 16)
+            .unwrap()
+            .with_stroke_animation(animation);
+        let document = canvas.render(renderer).unwrap();
+
+        assert_eq!(document.matches("pathLength=\"1\"").count(), 3);
+        assert_eq!(document.matches("barium-draw 2s linear 0s").count(), 2);
+        assert_eq!(document.matches("barium-draw 2s linear 2s").count(), 1);
+        assert!(document.ends_with("@keyframes barium-draw{to{stroke-dashoffset:0}}</style></svg>"));
     }
 
-    fn finalize(mut self) -> Self::Output {
-        write!(self.document, "</svg>").unwrap();
+    /// Verify that links wrap shapes in anchors, and tooltips become escaped titles.
+    #[test]
+    fn links_and_tooltips() {
+        let mut canvas = Canvas::new(16);
+        canvas.with_link("https://example.com/?a=1&b=2", |canvas| {
+            canvas.with_tooltip("<42>", |canvas| {
+                canvas.draw_rect(Vec2::ZERO, Vec2::ONE, None, Some(Color::red()));
+            });
+        });
+        canvas.draw_rect(Vec2::ZERO, Vec2::ONE, None, Some(Color::red()));
+
+        let renderer = SvgRenderer::new(Vec2::splat(100.0), None, true, true, 16).unwrap();
+        let document = canvas.render(renderer).unwrap();
+
+        assert!(document.contains("<a href=\"https://example.com/?a=1&amp;b=2\"><polygon "));
+        assert!(document.contains("\"><title>&lt;42&gt;</title></polygon></a><polygon "));
+        assert_eq!(document.matches("<a ").count(), 1);
+    }
+
+    /// Verify that print marks grow the document around the page, clip artwork to the bleed, and draw marks in the margin.
+    #[test]
+    fn print_marks() {
+        let mut canvas = Canvas::new(16);
+        canvas.draw_rect(
+            Vec2::splat(-2.0),
+            Vec2::splat(2.0),
+            None,
+            Some(Color::red()),
+        );
+
+        let renderer =
+            SvgRenderer::new(Vec2::new(100.0, 50.0), Some(Color::white()), true, true, 16)
+                .unwrap()
+                .with_print_marks(PrintMarks::new(3.0, 12.0))
+                .unwrap();
+        let document = canvas.render(renderer).unwrap();
 
-        self.document
+        assert!(document
+            .starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"130\" height=\"80\">"));
+        assert!(document.contains("<clipPath id=\"barium-bleed\"><rect x=\"-3\" y=\"-3\" width=\"106\" height=\"56\"/></clipPath>"));
+        assert!(document.contains("<g transform=\"translate(15,15)\" clip-path=\"url(#barium-bleed)\"><rect fill=\"#FFFFFF\" x=\"-3\""));
+        // Eight crop marks, and a target with two crosshairs on each side.
+        assert_eq!(document.matches("<line ").count(), 16);
+        assert_eq!(document.matches("<circle ").count(), 4);
+        assert!(document.contains("<line x1=\"12\" y1=\"15\" x2=\"0\" y2=\"15\"/>"));
+        assert!(document.ends_with("</g></svg>"));
     }
 }
@@ -0,0 +1,212 @@
+use glam::Vec2;
+
+use crate::{BlendMode, Color, Error, FillRule, LineEnd, Renderer, Shape};
+use std::fmt::Write;
+
+/// A renderer for TikZ, which draws figures natively inside LaTeX documents.
+///
+/// The output is a complete `tikzpicture` environment, with every color defined through `xcolor` at the top.
+/// Lengths are in centimeters.
+#[derive(Clone)]
+pub struct TikzRenderer {
+    scale: f32,
+    center_offset: Vec2,
+    colors: Vec<String>,
+    commands: String,
+}
+
+impl TikzRenderer {
+    /// Creates a new [TikzRenderer], drawing a figure `size` centimeters large.
+    ///
+    /// `preserve_height` allows you to decide which axis to preserve.
+    /// If `true`, then the figure will map `-1..=1` in the y axis in camera space to `0..=size.y`.
+    /// If `false` then the figure will be mapped for the x axis.
+    pub fn new(
+        size: Vec2,
+        background: Option<Color>,
+        preserve_height: bool,
+    ) -> Result<Self, Error> {
+        let (scale, center_offset) = if preserve_height {
+            let scale = size.y / 2.0;
+            (scale, Vec2::new(size.x / 2.0 / scale, 1.0))
+        } else {
+            let scale = size.x / 2.0;
+            (scale, Vec2::new(1.0, size.y / 2.0 / scale))
+        };
+
+        let mut renderer = Self {
+            scale,
+            center_offset,
+            colors: Vec::new(),
+            commands: String::new(),
+        };
+
+        if let Some(background) = background {
+            let name = renderer.color_name(background);
+            writeln!(
+                renderer.commands,
+                "\\fill[{}] (0,0) rectangle ({},{});",
+                name, size.x, size.y
+            )?;
+        }
+
+        Ok(renderer)
+    }
+}
+
+impl TikzRenderer {
+    /// The name of an `xcolor` definition matching `color`, ignoring alpha. New colors are defined as they are needed.
+    fn color_name(&mut self, color: Color) -> String {
+        let hex = color.as_hex(false);
+        let hex = hex.trim_start_matches('#').to_uppercase();
+
+        let index = match self.colors.iter().position(|defined| *defined == hex) {
+            Some(index) => index,
+            None => {
+                self.colors.push(hex);
+                self.colors.len() - 1
+            }
+        };
+
+        format!("barium{}", index)
+    }
+
+    /// Write a point in Camera Space to the document, transformed into the figure's centimeters.
+    fn write_point(&mut self, point: Vec2) -> Result<(), Error> {
+        // TikZ's y axis points up, just like Camera Space, so only scale and offset.
+        let point = (point + self.center_offset) * self.scale;
+        write!(self.commands, "({},{})", point.x, point.y)?;
+
+        Ok(())
+    }
+}
+
+impl Renderer for TikzRenderer {
+    type Output = String;
+
+    fn render(&mut self, shape: &Shape) -> Result<(), Error> {
+        if !shape.is_drawable() {
+            return Ok(());
+        }
+
+        let mut options = Vec::new();
+
+        if let Some(fill) = shape.fill {
+            options.push(format!("fill={}", self.color_name(fill)));
+            if fill.a() != 1.0 {
+                options.push(format!("fill opacity={}", fill.a()));
+            }
+            if shape.fill_rule == FillRule::EvenOdd {
+                options.push("even odd rule".to_string());
+            }
+        }
+
+        if let Some(stroke) = shape.stroke {
+            options.push(format!("draw={}", self.color_name(stroke.color)));
+            options.push(format!("line width={}cm", stroke.width * self.scale));
+            if stroke.color.a() != 1.0 {
+                options.push(format!("draw opacity={}", stroke.color.a()));
+            }
+            options.push(
+                match stroke.line_end {
+                    LineEnd::Butt => "line cap=butt",
+                    LineEnd::Round => "line cap=round",
+                }
+                .to_string(),
+            );
+            options.push("line join=round".to_string());
+        }
+
+        let blend_mode = match shape.blend_mode {
+            BlendMode::Normal => None,
+            BlendMode::Multiply => Some("multiply"),
+            BlendMode::Screen => Some("screen"),
+            BlendMode::Overlay => Some("overlay"),
+            BlendMode::Darken => Some("darken"),
+            BlendMode::Lighten => Some("lighten"),
+            // PDF has no additive blending, and screen is the closest match.
+            BlendMode::Add => Some("screen"),
+        };
+        if let Some(blend_mode) = blend_mode {
+            options.push(format!("blend mode={}", blend_mode));
+        }
+
+        if options.is_empty() {
+            return Ok(());
+        }
+
+        write!(self.commands, "\\path[{}]", options.join(", "))?;
+
+        let paths: Vec<Vec<Vec2>> = shape
+            .paths()
+            .filter(|points| points.len() > 1)
+            .map(<[Vec2]>::to_vec)
+            .collect();
+        for points in paths {
+            let closed = points.len() >= 3 && points[0] == points[points.len() - 1];
+            let open = if closed {
+                &points[..points.len() - 1]
+            } else {
+                &points[..]
+            };
+
+            for (i, point) in open.iter().enumerate() {
+                write!(self.commands, "{}", if i == 0 { " " } else { " -- " })?;
+                self.write_point(*point)?;
+            }
+
+            if closed {
+                write!(self.commands, " -- cycle")?;
+            }
+        }
+
+        writeln!(self.commands, ";")?;
+
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Self::Output, Error> {
+        let mut document = String::from("\\begin{tikzpicture}\n");
+
+        for (i, hex) in self.colors.iter().enumerate() {
+            writeln!(document, "\\definecolor{{barium{}}}{{HTML}}{{{}}}", i, hex)?;
+        }
+
+        document.push_str(&self.commands);
+        document.push_str("\\end{tikzpicture}\n");
+
+        Ok(document)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Canvas, Stroke};
+
+    /// Verify that shapes become TikZ paths, sharing color definitions.
+    #[test]
+    fn draw_and_fill() {
+        let mut canvas = Canvas::new(16);
+        canvas.draw_rect(
+            Vec2::new(-1.0, -1.0),
+            Vec2::ZERO,
+            Some(Stroke::new(Color::red(), 0.1, LineEnd::Butt)),
+            Some(Color::red()),
+        );
+
+        let renderer = TikzRenderer::new(Vec2::splat(10.0), Some(Color::white()), true).unwrap();
+        let document = canvas.render(renderer).unwrap();
+
+        assert_eq!(
+            document,
+            "\\begin{tikzpicture}\n\
+            \\definecolor{barium0}{HTML}{FFFFFF}\n\
+            \\definecolor{barium1}{HTML}{FF0000}\n\
+            \\fill[barium0] (0,0) rectangle (10,10);\n\
+            \\path[fill=barium1, draw=barium1, line width=0.5cm, line cap=butt, line join=round] \
+            (0,0) -- (5,0) -- (5,5) -- (0,5) -- cycle;\n\
+            \\end{tikzpicture}\n"
+        );
+    }
+}
@@ -0,0 +1,280 @@
+use std::io::Write;
+
+use glam::UVec2;
+use tiny_skia::{Path, Pixmap, Transform};
+
+use super::resolve::Dither;
+use super::skia_renderer::{paint_path, ImageSpace};
+use crate::canvas::Shape;
+use crate::{Color, ColorProfile, Error, Renderer};
+
+/// Renderer that draws very large images in horizontal strips, streaming each one into a PNG as soon as it is done.
+///
+/// Only a single strip of `size.x` by `tile_height` pixels is held in memory at a time, so posters far larger than a [SkiaRenderer](super::SkiaRenderer) could fit can still be rendered.
+/// The result matches a [SkiaRenderer](super::SkiaRenderer) with the same settings, give or take the rounding of anti-aliased edges.
+///
+/// The writer is handed back once the image is finished.
+pub struct TiledSkiaRenderer<W: Write> {
+    writer: W,
+    color_profile: Option<ColorProfile>,
+    strips: Strips,
+}
+
+/// Everything needed to draw a single strip of the image.
+struct Strips {
+    size: UVec2,
+    tile_height: u32,
+    background: Option<tiny_skia::Color>,
+    antialias: bool,
+    image_space: ImageSpace,
+    shapes: Vec<TiledShape>,
+    /// The indices of the shapes reaching into each strip, in the order they were drawn.
+    buckets: Vec<Vec<usize>>,
+}
+
+/// A shape that has already been turned into a path, waiting for the strips it covers to be drawn.
+struct TiledShape {
+    path: Path,
+    style: Shape,
+}
+
+impl<W: Write> TiledSkiaRenderer<W> {
+    /// Create a new [TiledSkiaRenderer] that writes a PNG to `writer`.
+    ///
+    /// `preserve_height` works the same way as in [SkiaRenderer::new](super::SkiaRenderer::new).
+    /// Each strip only draws the shapes reaching into it, and larger values of `tile_height` trade memory for drawing shapes across fewer strips.
+    ///
+    /// Returns [Error::InvalidSize] if either dimension of `size`, or `tile_height`, is zero.
+    pub fn new(
+        writer: W,
+        size: UVec2,
+        background: Option<Color>,
+        antialias: bool,
+        preserve_height: bool,
+        tile_height: u32,
+    ) -> Result<Self, Error> {
+        if size.x == 0 || size.y == 0 || tile_height == 0 {
+            return Err(Error::InvalidSize {
+                width: size.x,
+                height: size.y,
+            });
+        }
+
+        let tile_height = tile_height.min(size.y);
+
+        Ok(Self {
+            writer,
+            color_profile: None,
+            strips: Strips {
+                size,
+                tile_height,
+                background: background.map(TryInto::try_into).transpose()?,
+                antialias,
+                image_space: ImageSpace::new(size, preserve_height),
+                shapes: Vec::new(),
+                buckets: vec![Vec::new(); size.y.div_ceil(tile_height) as usize],
+            },
+        })
+    }
+
+    /// Tag the PNG with the color space its colors are meant for, consuming the parent.
+    pub fn with_color_profile(mut self, color_profile: ColorProfile) -> Self {
+        self.color_profile = Some(color_profile);
+        self
+    }
+}
+
+impl Strips {
+    /// Draw every shape that reaches into the strip starting at row `top`.
+    fn render(&self, top: u32, height: u32) -> Result<Pixmap, Error> {
+        let bucket = &self.buckets[(top / self.tile_height) as usize];
+        let mut strip = Pixmap::new(self.size.x, height).ok_or(Error::InvalidSize {
+            width: self.size.x,
+            height,
+        })?;
+
+        if let Some(background) = self.background {
+            strip.fill(background);
+        }
+
+        let transform = Transform::from_translate(0.0, -(top as f32));
+
+        for shape in bucket.iter().map(|&index| &self.shapes[index]) {
+            paint_path(
+                &mut strip,
+                &shape.path,
+                &shape.style,
+                &self.image_space,
+                self.antialias,
+                Dither::None,
+                transform,
+            )?;
+        }
+
+        Ok(strip)
+    }
+}
+
+impl<W: Write> Renderer for TiledSkiaRenderer<W> {
+    type Output = W;
+
+    fn render(&mut self, shape: &Shape) -> Result<(), Error> {
+        if !shape.is_drawable() {
+            return Ok(());
+        }
+
+        let strips = &mut self.strips;
+        let path = strips.image_space.build_path(shape)?;
+
+        // Leave room for miter joins, which can reach twice the stroke width past the path, and a pixel of anti-aliasing.
+        let margin = shape
+            .stroke
+            .map_or(0.0, |stroke| stroke.width * strips.image_space.scale * 2.0)
+            + 1.0;
+        let bounds = path.bounds();
+
+        // File the shape under every strip it reaches into, so each strip only visits its own shapes.
+        let tile_height = strips.tile_height as f32;
+        let first = ((bounds.top() - margin) / tile_height).floor().max(0.0) as usize;
+        let last = ((bounds.bottom() + margin) / tile_height).floor();
+        if last < 0.0 || first >= strips.buckets.len() {
+            return Ok(());
+        }
+        let last = (last as usize).min(strips.buckets.len() - 1);
+        for bucket in &mut strips.buckets[first..=last] {
+            bucket.push(strips.shapes.len());
+        }
+
+        strips.shapes.push(TiledShape {
+            path,
+            style: Shape {
+                stroke: shape.stroke,
+                fill: shape.fill,
+                fill_rule: shape.fill_rule,
+                blend_mode: shape.blend_mode,
+                gradient_fill: shape.gradient_fill.clone(),
+                ..Default::default()
+            },
+        });
+
+        Ok(())
+    }
+
+    fn finalize(mut self) -> Result<Self::Output, Error> {
+        {
+            let strips = &self.strips;
+            let encoder = match &self.color_profile {
+                Some(color_profile) => {
+                    let info = color_profile.png_info(strips.size.x, strips.size.y);
+                    png::Encoder::with_info(&mut self.writer, info).map_err(|_| Error::Format)?
+                }
+                None => {
+                    let mut encoder =
+                        png::Encoder::new(&mut self.writer, strips.size.x, strips.size.y);
+                    encoder.set_color(png::ColorType::Rgba);
+                    encoder.set_depth(png::BitDepth::Eight);
+                    encoder
+                }
+            };
+
+            let mut png = encoder.write_header().map_err(|_| Error::Format)?;
+            let mut rows = png.stream_writer().map_err(|_| Error::Format)?;
+
+            for top in (0..strips.size.y).step_by(strips.tile_height as usize) {
+                let height = strips.tile_height.min(strips.size.y - top);
+                let strip = strips.render(top, height)?;
+
+                rows.write_all(strip.data()).map_err(|_| Error::Format)?;
+            }
+
+            rows.finish().map_err(|_| Error::Format)?;
+            png.finish().map_err(|_| Error::Format)?;
+        }
+
+        Ok(self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{renderers::SkiaRenderer, Canvas, Gradient, GradientFill, LineEnd, Stroke, Vec2};
+
+    /// Verify that rendering in strips, gradient fills included, produces the same image as rendering all at once.
+    #[test]
+    fn matches_single_pass() {
+        let mut canvas = Canvas::new(100);
+        let gradient = Gradient::new(Color::black(), Color::white());
+        canvas.draw_gradient_shape(
+            [
+                (-1.5, -1.0),
+                (1.5, -1.0),
+                (1.5, 1.0),
+                (-1.5, 1.0),
+                (-1.5, -1.0),
+            ],
+            None,
+            GradientFill::new(gradient, Vec2::new(0.0, -1.0), Vec2::new(0.0, 1.0)),
+        );
+        canvas.draw_circle(Vec2::ZERO, 0.8, None, Some(Color::new(1.0, 0.5, 0.0, 1.0)));
+        canvas.draw_line(
+            (-1.0, -0.9),
+            (1.0, 0.7),
+            Some(Stroke::new(Color::black(), 0.05, LineEnd::Round)),
+            None,
+        );
+
+        let size = UVec2::new(120, 90);
+        let expected = canvas
+            .render(SkiaRenderer::new(size, Some(Color::white()), true, true).unwrap())
+            .unwrap();
+
+        let png = canvas
+            .render(
+                TiledSkiaRenderer::new(Vec::new(), size, Some(Color::white()), true, true, 16)
+                    .unwrap(),
+            )
+            .unwrap();
+        let actual = image::load_from_memory(&png).unwrap().to_rgba8();
+
+        // A few anti-aliased pixels can round differently once shifted into a strip, but only by a sliver of coverage.
+        assert_eq!(actual.dimensions(), expected.dimensions());
+        let differences: Vec<u8> = actual
+            .pixels()
+            .zip(expected.pixels())
+            .map(|(actual, expected)| {
+                let channels = actual.0.iter().zip(expected.0);
+                channels.map(|(a, e)| a.abs_diff(e)).max().unwrap_or(0)
+            })
+            .filter(|&difference| difference > 0)
+            .collect();
+        assert!(
+            differences.len() <= 8,
+            "{} pixels differ",
+            differences.len()
+        );
+        assert!(differences.iter().all(|&difference| difference <= 16));
+    }
+
+    /// Verify that each strip only holds the shapes that reach into it.
+    #[test]
+    fn bucket_shapes_by_strip() {
+        let mut canvas = Canvas::new(100);
+        let fill = Some(Color::black());
+        canvas.draw_rect((-1.0, 0.9), (1.0, 0.95), None, fill);
+        canvas.draw_rect((-1.0, -0.95), (1.0, 0.95), None, fill);
+        canvas.draw_rect((-1.0, 5.0), (1.0, 6.0), None, fill);
+
+        let mut renderer =
+            TiledSkiaRenderer::new(Vec::new(), UVec2::new(100, 100), None, true, true, 10).unwrap();
+        for shape in canvas.as_raw() {
+            renderer.render(shape).unwrap();
+        }
+
+        let buckets = &renderer.strips.buckets;
+        assert_eq!(buckets.len(), 10);
+        assert_eq!(buckets[0], [0, 1]);
+        assert_eq!(buckets[5], [1]);
+        assert_eq!(buckets[9], [1]);
+    }
+}
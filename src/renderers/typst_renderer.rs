@@ -0,0 +1,170 @@
+use glam::Vec2;
+
+use crate::{Color, Error, FillRule, LineEnd, Renderer, Shape};
+use std::fmt::Write;
+
+/// A renderer for Typst markup, which embeds figures into documents as source rather than images.
+///
+/// The output is a `box` of placed `curve`s, which needs Typst 0.13 or later. Lengths are in centimeters.
+/// Blend modes are ignored.
+#[derive(Clone)]
+pub struct TypstRenderer {
+    scale: f32,
+    center_offset: Vec2,
+    document: String,
+}
+
+impl TypstRenderer {
+    /// Creates a new [TypstRenderer], drawing a figure `size` centimeters large.
+    ///
+    /// `preserve_height` allows you to decide which axis to preserve.
+    /// If `true`, then the figure will map `-1..=1` in the y axis in camera space to `size.y..=0`.
+    /// If `false` then the figure will be mapped for the x axis.
+    pub fn new(
+        size: Vec2,
+        background: Option<Color>,
+        preserve_height: bool,
+    ) -> Result<Self, Error> {
+        let mut document = format!("#box(width: {}cm, height: {}cm", size.x, size.y);
+        if let Some(background) = background {
+            write!(document, ", fill: {}", paint(background))?;
+        }
+        writeln!(document, ")[")?;
+
+        let (scale, center_offset) = if preserve_height {
+            let scale = size.y / 2.0;
+            (scale, Vec2::new(size.x / 2.0 / scale, 1.0))
+        } else {
+            let scale = size.x / 2.0;
+            (scale, Vec2::new(1.0, size.y / 2.0 / scale))
+        };
+
+        Ok(Self {
+            scale,
+            center_offset,
+            document,
+        })
+    }
+}
+
+impl TypstRenderer {
+    /// Write a point in Camera Space to the document, transformed into the figure's centimeters.
+    fn write_point(&mut self, point: Vec2) -> Result<(), Error> {
+        // Transform from Camera Space (range from (-1, -1) to (1, 1)) to the figure (range from (0, 0) to its size).
+        let point = (Vec2::new(point.x, -point.y) + self.center_offset) * self.scale;
+        write!(self.document, "({}cm, {}cm)", point.x, point.y)?;
+
+        Ok(())
+    }
+}
+
+impl Renderer for TypstRenderer {
+    type Output = String;
+
+    fn render(&mut self, shape: &Shape) -> Result<(), Error> {
+        if !shape.is_drawable() || (shape.fill.is_none() && shape.stroke.is_none()) {
+            return Ok(());
+        }
+
+        write!(self.document, "  #place(curve(")?;
+
+        match shape.fill {
+            Some(fill) => {
+                write!(self.document, "fill: {}, ", paint(fill))?;
+                if shape.fill_rule == FillRule::EvenOdd {
+                    write!(self.document, "fill-rule: \"even-odd\", ")?;
+                }
+            }
+            None => write!(self.document, "fill: none, ")?,
+        }
+
+        match shape.stroke {
+            Some(stroke) => {
+                let cap = match stroke.line_end {
+                    LineEnd::Butt => "butt",
+                    LineEnd::Round => "round",
+                };
+                write!(
+                    self.document,
+                    "stroke: (paint: {}, thickness: {}cm, cap: \"{}\", join: \"round\")",
+                    paint(stroke.color),
+                    stroke.width * self.scale,
+                    cap
+                )?;
+            }
+            None => write!(self.document, "stroke: none")?,
+        }
+
+        let paths: Vec<Vec<Vec2>> = shape
+            .paths()
+            .filter(|points| points.len() > 1)
+            .map(<[Vec2]>::to_vec)
+            .collect();
+        for points in paths {
+            let closed = points.len() >= 3 && points[0] == points[points.len() - 1];
+            let open = if closed {
+                &points[..points.len() - 1]
+            } else {
+                &points[..]
+            };
+
+            for (i, point) in open.iter().enumerate() {
+                let command = if i == 0 { "move" } else { "line" };
+                write!(self.document, ", curve.{}(", command)?;
+                self.write_point(*point)?;
+                write!(self.document, ")")?;
+            }
+
+            if closed {
+                write!(self.document, ", curve.close()")?;
+            }
+        }
+
+        writeln!(self.document, "))")?;
+
+        Ok(())
+    }
+
+    fn finalize(mut self) -> Result<Self::Output, Error> {
+        writeln!(self.document, "]")?;
+
+        Ok(self.document)
+    }
+}
+
+/// A color as a Typst `rgb`, including alpha only when it is not opaque.
+fn paint(color: Color) -> String {
+    format!("rgb(\"{}\")", color.as_hex(color.a() != 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Canvas, Stroke};
+
+    /// Verify that shapes become placed curves inside a box of the right size.
+    #[test]
+    fn place_curves() {
+        let mut canvas = Canvas::new(16);
+        canvas.draw_rect(Vec2::new(-1.0, -1.0), Vec2::ZERO, None, Some(Color::red()));
+        canvas.draw_line(
+            Vec2::ZERO,
+            Vec2::ONE,
+            Some(Stroke::new(Color::blue(), 0.1, LineEnd::Round)),
+            None,
+        );
+
+        let renderer = TypstRenderer::new(Vec2::splat(10.0), None, true).unwrap();
+        let document = canvas.render(renderer).unwrap();
+
+        assert_eq!(
+            document,
+            "#box(width: 10cm, height: 10cm)[\n  \
+            #place(curve(fill: rgb(\"#FF0000\"), stroke: none, \
+            curve.move((0cm, 10cm)), curve.line((5cm, 10cm)), curve.line((5cm, 5cm)), curve.line((0cm, 5cm)), curve.close()))\n  \
+            #place(curve(fill: none, stroke: (paint: rgb(\"#0000FF\"), thickness: 0.5cm, cap: \"round\", join: \"round\"), \
+            curve.move((5cm, 5cm)), curve.line((10cm, 0cm))))\n\
+            ]\n"
+        );
+    }
+}
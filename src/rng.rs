@@ -0,0 +1,45 @@
+use glam::Vec2;
+
+use crate::math::portable_sin_cos;
+
+/// A small, seedable pseudo-random number generator ([SplitMix64](https://prng.di.unimi.it/splitmix64.c)).
+///
+/// Used wherever `barium` needs randomness, so that the same seed always produces the same drawing on every platform.
+#[derive(Debug, Clone)]
+pub(crate) struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Create a new [Rng] from a seed.
+    pub(crate) fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Get the next random [u64].
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Get a random [f32] in `0.0..1.0`.
+    pub(crate) fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Get a random [f32] in `min..max`.
+    pub(crate) fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+
+    /// Get a random point inside a circle of the given radius, centered on the origin.
+    pub(crate) fn in_circle(&mut self, radius: f32) -> Vec2 {
+        let angle = self.range(0.0, std::f32::consts::TAU);
+        let distance = radius * self.next_f32().sqrt();
+        let (sin, cos) = portable_sin_cos(angle);
+        Vec2::new(cos, sin) * distance
+    }
+}
@@ -0,0 +1,402 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use glam::Vec2;
+
+use crate::geometry::{clip_segment, segment_intersection};
+
+/// The kind of path a [Router] draws between two shapes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Routing {
+    /// The shortest path of straight lines, bending only around obstacles.
+    #[default]
+    Straight,
+    /// Only horizontal and vertical lines, with as few bends as it can, like a circuit diagram.
+    Orthogonal,
+    /// The shortest path, with its bends rounded off. A path that needs no bends stays straight.
+    Curved,
+}
+
+/// Routes connectors between shapes, such as the wires of a node diagram, around a set of obstacles.
+///
+/// Obstacles are avoided by their bounding boxes, grown by the clearance. Connectors start and end where they cross the outlines of the two shapes,
+/// so they meet each shape on the side facing the way they go.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Router {
+    /// The outlines of shapes to route around. Any that cover the middle of a shape being connected are ignored for that connector,
+    /// so every shape in a diagram can be given here.
+    pub obstacles: Vec<Vec<Vec2>>,
+    /// The smallest distance kept between a connector and an obstacle.
+    pub clearance: f32,
+    /// The kind of path drawn.
+    pub routing: Routing,
+    /// For [Orthogonal](Routing::Orthogonal) routes, how much length one fewer bend is worth.
+    pub bend_penalty: f32,
+    /// For [Curved](Routing::Curved) routes, how far from each bend the rounding starts.
+    pub corner_radius: f32,
+}
+
+impl Router {
+    /// Create a new [Router] for straight connectors, rounding curved ones as widely as the clearance.
+    pub fn new(obstacles: Vec<Vec<Vec2>>, clearance: f32) -> Self {
+        Self {
+            obstacles,
+            clearance,
+            routing: Routing::Straight,
+            bend_penalty: clearance * 4.0,
+            corner_radius: clearance,
+        }
+    }
+
+    /// Set the kind of path drawn, consuming the parent.
+    pub fn with_routing(mut self, routing: Routing) -> Self {
+        self.routing = routing;
+        self
+    }
+
+    /// Route a connector from the outline of one shape to the outline of another.
+    ///
+    /// If there's no way around the obstacles, the connector goes straight through them.
+    pub fn route(&self, from: &[Vec2], to: &[Vec2]) -> Vec<Vec2> {
+        let (start, end) = (middle(from), middle(to));
+
+        let boxes: Vec<(Vec2, Vec2)> = self
+            .obstacles
+            .iter()
+            .filter_map(|obstacle| bounds(obstacle))
+            .map(|(min, max)| (min - self.clearance, max + self.clearance))
+            .filter(|&(min, max)| !inside(start, min, max) && !inside(end, min, max))
+            .collect();
+
+        let path = match self.routing {
+            Routing::Straight | Routing::Curved => visibility_path(start, end, &boxes),
+            Routing::Orthogonal => orthogonal_path(start, end, &boxes, self.bend_penalty),
+        }
+        .unwrap_or_else(|| vec![start, end]);
+
+        let path = trim(&path, from, to);
+        match self.routing {
+            Routing::Curved => round_corners(&path, self.corner_radius),
+            _ => path,
+        }
+    }
+}
+
+/// A point and the cost of reaching it, ordered so that a [BinaryHeap] pops the cheapest first.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Candidate {
+    cost: f32,
+    state: usize,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The cheapest path through a graph of `states` from `start` to any state `done` accepts, with `edges` listing each state's neighbours and costs.
+fn cheapest_path<E, D>(states: usize, start: usize, edges: E, done: D) -> Option<Vec<usize>>
+where
+    E: Fn(usize) -> Vec<(usize, f32)>,
+    D: Fn(usize) -> bool,
+{
+    let mut costs = vec![f32::INFINITY; states];
+    let mut previous = vec![usize::MAX; states];
+    let mut queue = BinaryHeap::new();
+    costs[start] = 0.0;
+    queue.push(Candidate {
+        cost: 0.0,
+        state: start,
+    });
+
+    while let Some(Candidate { cost, state }) = queue.pop() {
+        if cost > costs[state] {
+            continue;
+        }
+        if done(state) {
+            let mut path = vec![state];
+            while previous[path[path.len() - 1]] != usize::MAX {
+                path.push(previous[path[path.len() - 1]]);
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        for (next, step) in edges(state) {
+            let cost = cost + step;
+            if cost < costs[next] {
+                costs[next] = cost;
+                previous[next] = state;
+                queue.push(Candidate { cost, state: next });
+            }
+        }
+    }
+    None
+}
+
+/// The shortest path from `start` to `end` that doesn't pass through any box, bending only at their corners.
+fn visibility_path(start: Vec2, end: Vec2, boxes: &[(Vec2, Vec2)]) -> Option<Vec<Vec2>> {
+    let mut points = vec![start, end];
+    for &(min, max) in boxes {
+        for corner in [min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)] {
+            if !boxes.iter().any(|&(min, max)| inside(corner, min, max)) {
+                points.push(corner);
+            }
+        }
+    }
+
+    let path = cheapest_path(
+        points.len(),
+        0,
+        |from| {
+            (0..points.len())
+                .filter(|&to| to != from && !blocked(points[from], points[to], boxes))
+                .map(|to| (to, points[from].distance(points[to])))
+                .collect()
+        },
+        |state| state == 1,
+    )?;
+    Some(path.into_iter().map(|index| points[index]).collect())
+}
+
+/// The path from `start` to `end` along horizontal and vertical lines that doesn't pass through any box,
+/// as short as it can be once each bend counts for `bend_penalty`.
+fn orthogonal_path(
+    start: Vec2,
+    end: Vec2,
+    boxes: &[(Vec2, Vec2)],
+    bend_penalty: f32,
+) -> Option<Vec<Vec2>> {
+    // A grid through the two ends and every edge of every box. Each state is a crossing of the grid and the direction it was reached from.
+    let lines = |along: fn(Vec2) -> f32| {
+        let mut lines: Vec<f32> = [start, end]
+            .into_iter()
+            .chain(boxes.iter().flat_map(|&(min, max)| [min, max]))
+            .map(along)
+            .collect();
+        lines.sort_by(f32::total_cmp);
+        lines.dedup();
+        lines
+    };
+    let (xs, ys) = (lines(|point| point.x), lines(|point| point.y));
+    let point = |node: usize| Vec2::new(xs[node % xs.len()], ys[node / xs.len()]);
+    let find = |point: Vec2| {
+        let x = xs.iter().position(|&x| x == point.x)?;
+        let y = ys.iter().position(|&y| y == point.y)?;
+        Some(y * xs.len() + x)
+    };
+    let (first, last) = (find(start)?, find(end)?);
+
+    const DIRECTIONS: usize = 5;
+    let path = cheapest_path(
+        xs.len() * ys.len() * DIRECTIONS,
+        first * DIRECTIONS + 4,
+        |state| {
+            let (node, heading) = (state / DIRECTIONS, state % DIRECTIONS);
+            let (x, y) = (node % xs.len(), node / xs.len());
+            let neighbours = [
+                (x + 1 < xs.len()).then(|| node + 1),
+                (y + 1 < ys.len()).then(|| node + xs.len()),
+                (x > 0).then(|| node - 1),
+                (y > 0).then(|| node - xs.len()),
+            ];
+
+            neighbours
+                .into_iter()
+                .enumerate()
+                .filter_map(|(direction, next)| {
+                    let next = next?;
+                    if blocked(point(node), point(next), boxes) {
+                        return None;
+                    }
+                    let bend = if heading != 4 && heading != direction {
+                        bend_penalty
+                    } else {
+                        0.0
+                    };
+                    let cost = point(node).distance(point(next)) + bend;
+                    Some((next * DIRECTIONS + direction, cost))
+                })
+                .collect()
+        },
+        |state| state / DIRECTIONS == last,
+    )?;
+
+    // Keep only the ends and the bends.
+    let mut points: Vec<Vec2> = Vec::new();
+    for state in path {
+        let next = point(state / DIRECTIONS);
+        if points.len() >= 2 {
+            let (a, b) = (points[points.len() - 2], points[points.len() - 1]);
+            if (b - a).perp_dot(next - b).abs() <= f32::EPSILON {
+                points.pop();
+            }
+        }
+        points.push(next);
+    }
+    Some(points)
+}
+
+/// Checks if the segment from `a` to `b` passes through the inside of any box. Running along an edge or through a corner is allowed.
+fn blocked(a: Vec2, b: Vec2, boxes: &[(Vec2, Vec2)]) -> bool {
+    const EPSILON: f32 = 1e-3;
+    boxes.iter().any(|&(min, max)| {
+        let (min, max) = (min + EPSILON, max - EPSILON);
+        min.cmplt(max).all()
+            && clip_segment(a, b, min, max)
+                .is_some_and(|(enter, leave)| enter.distance(leave) > 0.0)
+    })
+}
+
+/// Checks if `point` lies strictly inside the box from `min` to `max`.
+fn inside(point: Vec2, min: Vec2, max: Vec2) -> bool {
+    point.cmpgt(min).all() && point.cmplt(max).all()
+}
+
+/// The bottom left and top right corners of the box around `points`.
+fn bounds(points: &[Vec2]) -> Option<(Vec2, Vec2)> {
+    let first = *points.first()?;
+    Some(points.iter().fold((first, first), |(min, max), &point| {
+        (min.min(point), max.max(point))
+    }))
+}
+
+/// The middle of the box around `points`.
+fn middle(points: &[Vec2]) -> Vec2 {
+    bounds(points).map_or(Vec2::ZERO, |(min, max)| (min + max) / 2.0)
+}
+
+/// Cut a path between the middles of two shapes down to the part between their outlines:
+/// from the last place it leaves `from` to the first place after that where it meets `to`.
+fn trim(path: &[Vec2], from: &[Vec2], to: &[Vec2]) -> Vec<Vec2> {
+    // Where along the path, as a segment and how far along it, the path meets an outline.
+    let meetings = |outline: &[Vec2]| {
+        let mut found = Vec::new();
+        for (segment, pair) in path.windows(2).enumerate() {
+            let length = pair[0].distance(pair[1]);
+            for edge in outline.windows(2).chain(std::iter::once(
+                [outline[outline.len() - 1], outline[0]].as_slice(),
+            )) {
+                if let Some(point) = segment_intersection(pair[0], pair[1], edge[0], edge[1]) {
+                    let along = if length > 0.0 {
+                        pair[0].distance(point) / length
+                    } else {
+                        0.0
+                    };
+                    found.push((segment as f32 + along, segment, point));
+                }
+            }
+        }
+        found
+    };
+
+    let (mut start, mut start_segment, mut start_point) = (0.0, 0, path[0]);
+    if from.len() >= 2 {
+        if let Some(&last) = meetings(from).iter().max_by(|a, b| a.0.total_cmp(&b.0)) {
+            (start, start_segment, start_point) = last;
+        }
+    }
+
+    let (mut end_segment, mut end_point) = (path.len() - 2, path[path.len() - 1]);
+    if to.len() >= 2 {
+        if let Some(&(_, segment, point)) = meetings(to)
+            .iter()
+            .filter(|meeting| meeting.0 >= start)
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+        {
+            (end_segment, end_point) = (segment, point);
+        }
+    }
+
+    let mut trimmed = vec![start_point];
+    trimmed.extend(&path[start_segment + 1..=end_segment]);
+    trimmed.push(end_point);
+    trimmed.dedup();
+    trimmed
+}
+
+/// Round off each bend of a path with a curve starting up to `radius` before it.
+fn round_corners(path: &[Vec2], radius: f32) -> Vec<Vec2> {
+    const STEPS: usize = 8;
+    if path.len() < 3 || radius <= 0.0 {
+        return path.to_vec();
+    }
+
+    let mut rounded = vec![path[0]];
+    for corner in path.windows(3) {
+        let (before, point, after) = (corner[0], corner[1], corner[2]);
+        let cut = radius
+            .min(point.distance(before) / 2.0)
+            .min(point.distance(after) / 2.0);
+        let enter = point + (before - point).normalize_or_zero() * cut;
+        let leave = point + (after - point).normalize_or_zero() * cut;
+
+        rounded.extend((0..=STEPS).map(|i| {
+            let t = i as f32 / STEPS as f32;
+            enter.lerp(point, t).lerp(point.lerp(leave, t), t)
+        }));
+    }
+    rounded.push(path[path.len() - 1]);
+    rounded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::distance_to_segment;
+
+    /// Verify that each kind of connector starts and ends on the shapes' outlines and goes around an obstacle between them.
+    #[test]
+    fn route_around_obstacle() {
+        let square = |center: Vec2| {
+            vec![
+                center + Vec2::new(-1.0, -1.0),
+                center + Vec2::new(1.0, -1.0),
+                center + Vec2::new(1.0, 1.0),
+                center + Vec2::new(-1.0, 1.0),
+                center + Vec2::new(-1.0, -1.0),
+            ]
+        };
+        let from = square(Vec2::new(-5.0, 0.0));
+        let to = square(Vec2::new(5.0, 0.0));
+        let wall = vec![Vec2::new(-0.5, -3.0), Vec2::new(0.5, 3.0)];
+        let obstacles = vec![from.clone(), to.clone(), wall];
+
+        for routing in [Routing::Straight, Routing::Orthogonal, Routing::Curved] {
+            let router = Router::new(obstacles.clone(), 0.5).with_routing(routing);
+            let path = router.route(&from, &to);
+
+            let on_outline = |point: Vec2, outline: &[Vec2]| {
+                outline
+                    .windows(2)
+                    .any(|edge| distance_to_segment(point, edge[0], edge[1]) < 1e-3)
+            };
+            assert!(on_outline(path[0], &from));
+            assert!(on_outline(path[path.len() - 1], &to));
+            assert!(path.len() > 2);
+            for pair in path.windows(2) {
+                assert!(
+                    clip_segment(pair[0], pair[1], Vec2::new(-0.5, -3.0), Vec2::new(0.5, 3.0))
+                        .is_none()
+                );
+                if routing == Routing::Orthogonal {
+                    assert!(pair[0].x == pair[1].x || pair[0].y == pair[1].y);
+                }
+            }
+        }
+
+        // With nothing in the way, a connector is a straight line between the facing sides.
+        let path = Router::new(Vec::new(), 0.5).route(&from, &to);
+        assert_eq!(path, vec![Vec2::new(-4.0, 0.0), Vec2::new(4.0, 0.0)]);
+    }
+}
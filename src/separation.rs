@@ -0,0 +1,208 @@
+use glam::Vec3;
+
+use crate::{BlendMode, Canvas, CmykProfile, Color, Shape, Stroke};
+
+/// An ink printed from its own [Plate], such as one drum of a risograph or one screen of a screen print.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ink {
+    /// The name of the ink, matched against [Shape::tag] by spot color separations.
+    pub name: String,
+    /// The color the ink prints.
+    pub color: Color,
+}
+
+impl Ink {
+    /// Create a new [Ink].
+    pub fn new<S: Into<String>>(name: S, color: Color) -> Self {
+        Self {
+            name: name.into(),
+            color,
+        }
+    }
+}
+
+/// How [Canvas::separate] splits a canvas between inks, each printed from its own [Plate].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Separation {
+    /// The cyan, magenta, yellow and black process inks, with every color split between them by [Color::to_cmyk].
+    Cmyk(CmykProfile),
+    /// Spot inks, each printing whole shapes: those [tagged](Shape::tag) with its name, and otherwise those closest to its color.
+    ///
+    /// Shapes lighter than their ink are printed as a tint of it.
+    Spot(Vec<Ink>),
+}
+
+impl Separation {
+    /// The inks, in the order of their plates.
+    pub fn inks(&self) -> Vec<Ink> {
+        match self {
+            Separation::Cmyk(_) => vec![
+                Ink::new("Cyan", Color::from_cmyk(1.0, 0.0, 0.0, 0.0)),
+                Ink::new("Magenta", Color::from_cmyk(0.0, 1.0, 0.0, 0.0)),
+                Ink::new("Yellow", Color::from_cmyk(0.0, 0.0, 1.0, 0.0)),
+                Ink::new("Black", Color::from_cmyk(0.0, 0.0, 0.0, 1.0)),
+            ],
+            Separation::Spot(inks) => inks.clone(),
+        }
+    }
+
+    /// How much of each ink prints `color` on a shape tagged with `tag`, each from 0 to 1.
+    fn amounts(&self, color: Color, tag: Option<&str>) -> Vec<f32> {
+        match self {
+            Separation::Cmyk(profile) => color.to_cmyk(profile).to_vec(),
+            Separation::Spot(inks) => {
+                let mut amounts = vec![0.0; inks.len()];
+                let tagged = tag.and_then(|tag| inks.iter().position(|ink| ink.name == tag));
+                let nearest = || {
+                    (0..inks.len()).min_by(|a, b| {
+                        let distance = |i: &usize| color.perceptual_distance(&inks[*i].color);
+                        distance(a).total_cmp(&distance(b))
+                    })
+                };
+
+                if let Some(index) = tagged.or_else(nearest) {
+                    // The tint of the ink over white paper that comes closest to the shape's color.
+                    let absorbed =
+                        |color: Color| Vec3::new(1.0 - color.r(), 1.0 - color.g(), 1.0 - color.b());
+                    let ink = absorbed(inks[index].color);
+                    amounts[index] = if ink.length_squared() > 0.0 {
+                        (absorbed(color).dot(ink) / ink.length_squared()).clamp(0.0, 1.0)
+                    } else {
+                        1.0
+                    };
+                }
+                amounts
+            }
+        }
+    }
+}
+
+/// The part of a [Canvas] printed with one [Ink], made by [Canvas::separate].
+#[derive(Debug, Clone)]
+pub struct Plate {
+    /// The ink the plate prints.
+    pub ink: Ink,
+    /// The shapes on the plate, in black as dark as the ink is heavy, ready to print as a film or master over white.
+    pub canvas: Canvas,
+}
+
+impl Canvas {
+    /// Split the canvas into a [Plate] for each ink of `separation`, for printing one ink at a time, such as with a risograph or screen print.
+    ///
+    /// Render each plate with any renderer, over a white background, to get its own image or SVG.
+    /// Every shape is kept on every plate, white where it has none of that plate's ink, so it knocks out the inks under it just as it covers them on screen.
+    /// [Gradient fills](Shape::gradient_fill) and [blend modes](Shape::blend_mode) are dropped.
+    pub fn separate(&self, separation: &Separation) -> Vec<Plate> {
+        let inks = separation.inks();
+        let mut plates: Vec<Vec<Shape>> = vec![Vec::new(); inks.len()];
+
+        // How dark the plate is where `amount` of its ink prints, through `alpha`.
+        let tone =
+            |amount: f32, alpha: f32| Color::new(1.0 - amount, 1.0 - amount, 1.0 - amount, alpha);
+
+        for shape in self.as_raw() {
+            let tag = shape.tag.as_deref();
+            let fill = shape
+                .fill
+                .map(|fill| (fill.a(), separation.amounts(fill, tag)));
+            let stroke = shape
+                .stroke
+                .map(|stroke| (stroke, separation.amounts(stroke.color, tag)));
+
+            for (i, plate) in plates.iter_mut().enumerate() {
+                plate.push(Shape {
+                    fill: fill
+                        .as_ref()
+                        .map(|(alpha, amounts)| tone(amounts[i], *alpha)),
+                    stroke: stroke.as_ref().map(|(stroke, amounts)| Stroke {
+                        color: tone(amounts[i], stroke.color.a()),
+                        ..*stroke
+                    }),
+                    gradient_fill: None,
+                    blend_mode: BlendMode::Normal,
+                    ..shape.clone()
+                });
+            }
+        }
+
+        // Each plate keeps the canvas's camera, so it is framed just like the original.
+        inks.into_iter()
+            .zip(plates)
+            .map(|(ink, shapes)| Plate {
+                ink,
+                canvas: self.with_shapes(shapes),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LineEnd, Vec2};
+
+    /// Verify that colors are split between process inks, and shapes go to the spot ink they are tagged with or closest to.
+    #[test]
+    fn separate_inks() {
+        let square = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 0.0)];
+        let mut canvas = Canvas::new(1);
+        canvas.draw_polygon(square, Color::red());
+        canvas.draw_polyline(
+            square,
+            Stroke::new(Color::new(0.0, 0.0, 1.0, 0.5), 0.1, LineEnd::Butt),
+        );
+        canvas.with_tag("Red", |canvas| canvas.draw_polygon(square, Color::black()));
+
+        let cmyk = canvas.separate(&Separation::Cmyk(CmykProfile::default()));
+        let names: Vec<&str> = cmyk.iter().map(|plate| plate.ink.name.as_str()).collect();
+        assert_eq!(names, ["Cyan", "Magenta", "Yellow", "Black"]);
+        let cyan = cmyk[0].canvas.as_raw();
+        assert_eq!(cyan[0].fill, Some(Color::white()));
+        assert_eq!(cyan[1].stroke.unwrap().color, Color::black().with_a(0.5));
+        assert_eq!(cmyk[1].canvas.as_raw()[0].fill, Some(Color::black()));
+
+        let spot = canvas.separate(&Separation::Spot(vec![
+            Ink::new("Red", Color::red()),
+            Ink::new("Blue", Color::blue()),
+        ]));
+        let (red, blue) = (spot[0].canvas.as_raw(), spot[1].canvas.as_raw());
+        assert_eq!(red[0].fill, Some(Color::black()));
+        assert_eq!(blue[0].fill, Some(Color::white()));
+        assert_eq!(blue[1].stroke.unwrap().color, Color::black().with_a(0.5));
+        assert_eq!(red[2].fill, Some(Color::black()));
+        assert_eq!(blue[2].fill, Some(Color::white()));
+
+        // Pink is a tint of red, lighter than the ink itself.
+        let mut tint = Canvas::new(1);
+        tint.draw_polygon(square, Color::new(1.0, 0.5, 0.5, 1.0));
+        let red = tint.separate(&Separation::Spot(vec![Ink::new("Red", Color::red())]));
+        assert_eq!(
+            red[0].canvas.as_raw()[0].fill,
+            Some(Color::new(0.5, 0.5, 0.5, 1.0))
+        );
+    }
+
+    /// Verify that plates keep the camera of the canvas they are separated from.
+    #[test]
+    fn plates_keep_camera() {
+        let mut canvas = Canvas::new(1);
+        canvas.move_camera((5.0, 0.0));
+        canvas.zoom_camera(2.0);
+        canvas.draw_polygon(
+            [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 0.0)],
+            Color::red(),
+        );
+
+        let plates = canvas.separate(&Separation::Cmyk(CmykProfile::default()));
+        let point = Vec2::new(4.0, 0.0);
+        for plate in &plates {
+            assert_eq!(
+                plate.canvas.to_camera_space(point),
+                canvas.to_camera_space(point)
+            );
+            assert_eq!(plate.canvas.as_raw()[0].points, canvas.as_raw()[0].points);
+        }
+    }
+}
@@ -0,0 +1,264 @@
+use std::f32::consts::PI;
+
+use glam::{Mat2, Vec2};
+
+use crate::{Canvas, Color, IntoPoint, IntoPoints, Stroke};
+
+/// A builder to style, transform and then draw a shape, as an alternative to the positional arguments of the `draw_*` methods.
+///
+/// Start one from [Canvas::circle], [Canvas::rect], [Canvas::regular_polygon], [Canvas::polygon], [Canvas::polyline] or [Canvas::line],
+/// and finish it with [draw](Self::draw). Nothing is drawn until then.
+///
+/// A shape given neither a stroke nor a fill takes the canvas's [Style](crate::Style).
+#[derive(Debug)]
+#[must_use = "nothing is drawn until `draw` is called"]
+pub struct ShapeBuilder<'a> {
+    canvas: &'a mut Canvas,
+    points: Vec<Vec2>,
+    pivot: Vec2,
+    stroke: Option<Stroke>,
+    fill: Option<Color>,
+    absolute: bool,
+}
+
+impl<'a> ShapeBuilder<'a> {
+    fn new(canvas: &'a mut Canvas, points: Vec<Vec2>, pivot: Vec2) -> Self {
+        Self {
+            canvas,
+            points,
+            pivot,
+            stroke: None,
+            fill: None,
+            absolute: false,
+        }
+    }
+
+    /// Outline the shape with `stroke`.
+    pub fn stroke(mut self, stroke: Stroke) -> Self {
+        self.stroke = Some(stroke);
+        self
+    }
+
+    /// Fill the shape with `fill`.
+    pub fn fill(mut self, fill: Color) -> Self {
+        self.fill = Some(fill);
+        self
+    }
+
+    /// Rotate the shape counter-clockwise about its center, in radians.
+    pub fn rotate(mut self, radians: f32) -> Self {
+        let (sin, cos) = self.canvas.sin_cos(radians);
+        let rotation = Mat2::from_cols(Vec2::new(cos, sin), Vec2::new(-sin, cos));
+        let pivot = self.pivot;
+        for point in &mut self.points {
+            *point = pivot + rotation * (*point - pivot);
+        }
+        self
+    }
+
+    /// Scale the shape about its center.
+    pub fn scale(mut self, factor: f32) -> Self {
+        let pivot = self.pivot;
+        for point in &mut self.points {
+            *point = pivot + (*point - pivot) * factor;
+        }
+        self
+    }
+
+    /// Move the shape, and its center with it.
+    pub fn translate<P: IntoPoint>(mut self, offset: P) -> Self {
+        let offset = offset.into_point();
+        for point in &mut self.points {
+            *point += offset;
+        }
+        self.pivot += offset;
+        self
+    }
+
+    /// Draw the shape directly onto the canvas, rather than projecting it from the camera.
+    pub fn absolute(mut self) -> Self {
+        self.absolute = true;
+        self
+    }
+
+    /// Draw the shape onto the canvas.
+    pub fn draw(self) {
+        if self.absolute {
+            self.canvas
+                .draw_shape_absolute(self.points, self.stroke, self.fill);
+        } else {
+            self.canvas.draw_shape(self.points, self.stroke, self.fill);
+        }
+    }
+}
+
+impl Canvas {
+    /// Start building a circle. Like [draw_circle](Self::draw_circle), it has as many sides as [points_per_unit](Self::points_per_unit) calls for.
+    pub fn circle<P: IntoPoint>(&mut self, center: P, radius: f32) -> ShapeBuilder<'_> {
+        let sides = (2.0 * PI * radius * self.points_per_unit() as f32) as usize;
+        let center = center.into_point();
+        let points = if sides > 2 {
+            self.regular_polygon_points(center, sides, radius)
+        } else {
+            Vec::new()
+        };
+        ShapeBuilder::new(self, points, center)
+    }
+
+    /// Start building a rectangle between two opposite corners.
+    pub fn rect<P: IntoPoint>(&mut self, corner: P, opposite: P) -> ShapeBuilder<'_> {
+        let (a, b) = (corner.into_point(), opposite.into_point());
+        let points = vec![a, Vec2::new(b.x, a.y), b, Vec2::new(a.x, b.y), a];
+        ShapeBuilder::new(self, points, (a + b) / 2.0)
+    }
+
+    /// Start building a regular polygon, with its first corner to the right of its center.
+    ///
+    /// With fewer than 3 sides, there is no polygon and nothing is drawn.
+    pub fn regular_polygon<P: IntoPoint>(
+        &mut self,
+        center: P,
+        sides: usize,
+        radius: f32,
+    ) -> ShapeBuilder<'_> {
+        let center = center.into_point();
+        let points = if sides > 2 {
+            self.regular_polygon_points(center, sides, radius)
+        } else {
+            Vec::new()
+        };
+        ShapeBuilder::new(self, points, center)
+    }
+
+    /// Start building a closed polygon through `points`, joining the last back to the first.
+    pub fn polygon<C: IntoPoints>(&mut self, points: C) -> ShapeBuilder<'_> {
+        let mut points = points.into_points();
+
+        // A closing point repeats the first corner, so it is left out of the center.
+        let corners = match (points.first(), points.last()) {
+            (Some(first), Some(last)) if points.len() > 1 && first == last => {
+                &points[..points.len() - 1]
+            }
+            _ => &points[..],
+        };
+        let pivot = centroid(corners);
+
+        if let (Some(&first), Some(&last)) = (points.first(), points.last()) {
+            if first != last {
+                points.push(first);
+            }
+        }
+        ShapeBuilder::new(self, points, pivot)
+    }
+
+    /// Start building an open line through `points`.
+    pub fn polyline<C: IntoPoints>(&mut self, points: C) -> ShapeBuilder<'_> {
+        let points = points.into_points();
+        let pivot = centroid(&points);
+        ShapeBuilder::new(self, points, pivot)
+    }
+
+    /// Start building a straight line between two points.
+    pub fn line<P: IntoPoint>(&mut self, from: P, to: P) -> ShapeBuilder<'_> {
+        let (from, to) = (from.into_point(), to.into_point());
+        ShapeBuilder::new(self, vec![from, to], (from + to) / 2.0)
+    }
+
+    /// The corners of a regular polygon, with the first repeated at the end.
+    fn regular_polygon_points(&self, center: Vec2, sides: usize, radius: f32) -> Vec<Vec2> {
+        let mut points: Vec<Vec2> = (0..sides)
+            .map(|n| {
+                let (sin, cos) = self.sin_cos(2.0 * PI * n as f32 / sides as f32);
+                center + Vec2::new(cos, sin) * radius
+            })
+            .collect();
+        points.push(points[0]);
+        points
+    }
+}
+
+/// The average of `points`, or the origin if there are none.
+fn centroid(points: &[Vec2]) -> Vec2 {
+    if points.is_empty() {
+        return Vec2::ZERO;
+    }
+    points.iter().fold(Vec2::ZERO, |sum, point| sum + *point) / points.len() as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LineEnd;
+
+    /// Verify that built shapes match their positional counterparts, and are transformed about their centers.
+    #[test]
+    fn build_shapes() {
+        let stroke = Stroke::new(Color::black(), 0.1, LineEnd::Butt);
+
+        let mut expected = Canvas::new(10);
+        expected.draw_circle(Vec2::ONE, 2.0, Some(stroke), Some(Color::red()));
+        let mut canvas = Canvas::new(10);
+        canvas
+            .circle(Vec2::ONE, 2.0)
+            .fill(Color::red())
+            .stroke(stroke)
+            .draw();
+        assert_eq!(canvas.as_raw(), expected.as_raw());
+
+        canvas
+            .rect(Vec2::ZERO, Vec2::new(2.0, 1.0))
+            .rotate(PI / 2.0)
+            .fill(Color::blue())
+            .draw();
+        let rotated = &canvas.as_raw()[1];
+        assert!(rotated.points[0].abs_diff_eq(Vec2::new(1.5, -0.5), 1e-5));
+        assert!(rotated.points[2].abs_diff_eq(Vec2::new(0.5, 1.5), 1e-5));
+        assert_eq!(rotated.stroke, None);
+
+        canvas
+            .line((0.0, 0.0), (2.0, 0.0))
+            .scale(0.5)
+            .translate((0.0, 1.0))
+            .stroke(stroke)
+            .absolute()
+            .draw();
+        assert_eq!(
+            canvas.as_raw()[2].points,
+            [Vec2::new(0.5, 1.0), Vec2::new(1.5, 1.0)]
+        );
+
+        canvas
+            .polygon(vec![Vec2::ZERO, Vec2::X, Vec2::Y])
+            .fill(Color::white())
+            .draw();
+        assert_eq!(canvas.as_raw()[3].points.len(), 4);
+    }
+
+    /// Verify that polygons turn about the average of their corners, whether or not they are given closed, and that too few sides draw nothing.
+    #[test]
+    fn polygon_pivot() {
+        let triangle = [Vec2::ZERO, Vec2::new(3.0, 0.0), Vec2::new(0.0, 3.0)];
+        let expected = [
+            Vec2::new(2.0, 2.0),
+            Vec2::new(-1.0, 2.0),
+            Vec2::new(2.0, -1.0),
+            Vec2::new(2.0, 2.0),
+        ];
+
+        let mut canvas = Canvas::new(10);
+        canvas.polygon(triangle.to_vec()).rotate(PI).draw();
+        let mut closed = triangle.to_vec();
+        closed.push(Vec2::ZERO);
+        canvas.polygon(closed).rotate(PI).draw();
+
+        for shape in canvas.as_raw() {
+            assert_eq!(shape.points.len(), 4);
+            for (point, expected) in shape.points.iter().zip(expected) {
+                assert!(point.abs_diff_eq(expected, 1e-5), "{}", point);
+            }
+        }
+
+        canvas.regular_polygon(Vec2::ZERO, 2, 1.0).draw();
+        assert_eq!(canvas.as_raw().len(), 2);
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use glam::Vec2;
+
+/// The most cells a single box is stored in. Larger boxes are kept aside and returned by every query instead.
+const MAX_CELLS_PER_BOX: i64 = 256;
+
+/// A uniform grid of boxes, for quickly finding which boxes might overlap a given area.
+///
+/// Each box is stored in every cell it touches, so cells should be around the size of a typical box.
+/// Boxes spanning too many cells are kept in a separate list, and boxes with non-finite corners are ignored.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SpatialIndex {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<usize>>,
+    large: Vec<usize>,
+}
+
+impl SpatialIndex {
+    /// Create an empty index with square cells `cell_size` wide.
+    pub(crate) fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size: if cell_size > 0.0 && cell_size.is_finite() {
+                cell_size
+            } else {
+                1.0
+            },
+            cells: HashMap::new(),
+            large: Vec::new(),
+        }
+    }
+
+    /// Add the box from `min` to `max`, identified by `index`.
+    pub(crate) fn insert(&mut self, index: usize, min: Vec2, max: Vec2) {
+        match self.cells_between(min, max) {
+            Some(cells) => {
+                for cell in cells {
+                    self.cells.entry(cell).or_default().push(index);
+                }
+            }
+            None if min.is_finite() && max.is_finite() => self.large.push(index),
+            None => {}
+        }
+    }
+
+    /// The indices of every box that shares a cell with the box from `min` to `max`, sorted and without duplicates.
+    ///
+    /// This may include boxes that do not actually overlap, but never misses one that does.
+    pub(crate) fn query(&self, min: Vec2, max: Vec2) -> Vec<usize> {
+        let mut found = self.large.clone();
+        match self.cells_between(min, max) {
+            Some(cells) => found.extend(
+                cells
+                    .filter_map(|cell| self.cells.get(&cell))
+                    .flatten()
+                    .copied(),
+            ),
+            None => found.extend(self.cells.values().flatten().copied()),
+        }
+
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+
+    /// Every cell touched by the box from `min` to `max`, or `None` if it is not finite or touches too many cells to list.
+    fn cells_between(&self, min: Vec2, max: Vec2) -> Option<impl Iterator<Item = (i32, i32)>> {
+        let first = (min / self.cell_size).floor();
+        let last = (max / self.cell_size).floor();
+        if !first.is_finite() || !last.is_finite() {
+            return None;
+        }
+
+        let (x0, y0) = (first.x as i64, first.y as i64);
+        let (x1, y1) = (last.x as i64, last.y as i64);
+        let count = (x1 - x0 + 1).max(0) * (y1 - y0 + 1).max(0);
+        let range = i32::MIN as i64..=i32::MAX as i64;
+        if count > MAX_CELLS_PER_BOX || ![x0, y0, x1, y1].iter().all(|c| range.contains(c)) {
+            return None;
+        }
+
+        let (x0, y0, x1, y1) = (x0 as i32, y0 as i32, x1 as i32, y1 as i32);
+        Some((x0..=x1).flat_map(move |x| (y0..=y1).map(move |y| (x, y))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that huge boxes are still found without being stored in every cell, and non-finite boxes are ignored.
+    #[test]
+    fn huge_and_non_finite_boxes() {
+        let mut index = SpatialIndex::new(1.0);
+        index.insert(0, Vec2::ZERO, Vec2::ONE);
+        index.insert(1, Vec2::splat(-1e6), Vec2::splat(1e6));
+        index.insert(2, Vec2::ZERO, Vec2::new(f32::INFINITY, 1.0));
+        index.insert(3, Vec2::new(f32::NAN, 0.0), Vec2::ONE);
+
+        assert!(index.cells.len() <= 4);
+        assert_eq!(index.query(Vec2::splat(0.5), Vec2::splat(0.5)), [0, 1]);
+        assert_eq!(index.query(Vec2::splat(50.0), Vec2::splat(51.0)), [1]);
+        assert_eq!(index.query(Vec2::splat(-1e9), Vec2::splat(1e9)), [0, 1]);
+    }
+}
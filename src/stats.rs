@@ -0,0 +1,33 @@
+use std::time::Duration;
+
+/// Statistics collected while rendering a [Canvas](crate::Canvas).
+///
+/// Returned by [Canvas::render_with_stats](crate::Canvas::render_with_stats).
+/// Useful for tracking down slow scenes without reaching for an external profiler.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RenderStats {
+    /// Number of shapes handed to the renderer.
+    pub shapes_rendered: usize,
+    /// Number of shapes skipped because they could not be drawn (see [Shape::is_drawable](crate::Shape::is_drawable)).
+    ///
+    /// Nothing is culled against the view, so shapes outside it are still rendered and counted in [shapes_rendered](Self::shapes_rendered).
+    pub shapes_skipped: usize,
+    /// Total number of points handed to the renderer.
+    pub points_rendered: usize,
+    /// Time spent turning shapes into polygons before they reach the renderer:
+    /// [flattening](crate::Shape::flatten) custom geometry, and offsetting the outlines of strokes that aren't [centered](crate::StrokeAlignment::Center).
+    pub tessellation_time: Duration,
+    /// Time spent projecting the points of each shape from World Space into Camera Space and scaling its stroke with the zoom.
+    pub transform_time: Duration,
+    /// Time spent inside [Renderer::render](crate::Renderer::render), including any tessellation the renderer does itself.
+    pub raster_time: Duration,
+    /// Time spent inside [Renderer::finalize](crate::Renderer::finalize), such as encoding the output.
+    pub finalize_time: Duration,
+}
+
+impl RenderStats {
+    /// The total time spent rendering, across all phases.
+    pub fn total_time(&self) -> Duration {
+        self.tessellation_time + self.transform_time + self.raster_time + self.finalize_time
+    }
+}
@@ -0,0 +1,171 @@
+use glam::{Affine2, IVec2, Mat2, UVec2, Vec2};
+
+/// A symmetry group that a [Canvas](crate::Canvas) can replicate every shape under.
+///
+/// Symmetries are centered on the origin of World Space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Symmetry {
+    /// `n` copies, evenly rotated around the origin.
+    Radial(usize),
+    /// The shape and its reflection across a line through the origin, running in the given direction.
+    Mirror(Vec2),
+    /// `n` rotated copies, each paired with its reflection, like looking through a kaleidoscope.
+    Kaleidoscope(usize),
+    /// Copies repeated across the plane in a grid of cells.
+    Wallpaper(Wallpaper),
+}
+
+/// A repeating pattern of cells, each containing copies of a shape arranged by a [WallpaperGroup].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Wallpaper {
+    /// How the copies are arranged inside each cell.
+    pub group: WallpaperGroup,
+    /// The width and height of each cell. The cell containing the origin is centered on it.
+    pub cell: Vec2,
+    /// How many cells to repeat in each direction away from the center cell.
+    pub repeat: UVec2,
+}
+
+impl Wallpaper {
+    /// Create a new [Wallpaper].
+    pub fn new(group: WallpaperGroup, cell: Vec2, repeat: UVec2) -> Self {
+        Self {
+            group,
+            cell,
+            repeat,
+        }
+    }
+}
+
+/// The wallpaper groups with rectangular or square cells, named in crystallographic notation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WallpaperGroup {
+    /// Translation only.
+    P1,
+    /// Half turns around the center of each cell.
+    P2,
+    /// Reflections across the vertical line through each cell.
+    Pm,
+    /// Reflections across both the vertical and horizontal lines through each cell.
+    Pmm,
+    /// Quarter turns around the center of each cell. Works best with square cells.
+    P4,
+    /// Quarter turns and reflections, the full symmetry of a square. Works best with square cells.
+    P4m,
+}
+
+impl Symmetry {
+    /// Every transform in the group, starting with the identity.
+    ///
+    /// `sin_cos` is used for rotations, so that deterministic canvases stay deterministic.
+    pub(crate) fn transforms<F: Fn(f32) -> (f32, f32)>(&self, sin_cos: F) -> Vec<Affine2> {
+        // Zero copies is treated as one, the shape itself, rather than dividing by zero.
+        let rotations = |n: usize| {
+            let n = n.max(1);
+            (0..n).map(move |i| rotation(&sin_cos, std::f32::consts::TAU * i as f32 / n as f32))
+        };
+
+        match *self {
+            Symmetry::Radial(n) => rotations(n).collect(),
+            Symmetry::Mirror(axis) => vec![Affine2::IDENTITY, reflection(axis)],
+            Symmetry::Kaleidoscope(n) => {
+                let mirror = reflection(Vec2::X);
+                rotations(n)
+                    .flat_map(|rotation| [rotation, rotation * mirror])
+                    .collect()
+            }
+            Symmetry::Wallpaper(wallpaper) => {
+                let cell: Vec<Affine2> = match wallpaper.group {
+                    WallpaperGroup::P1 => rotations(1).collect(),
+                    WallpaperGroup::P2 => rotations(2).collect(),
+                    WallpaperGroup::Pm => vec![Affine2::IDENTITY, reflection(Vec2::Y)],
+                    WallpaperGroup::Pmm => rotations(2)
+                        .flat_map(|rotation| [rotation, rotation * reflection(Vec2::Y)])
+                        .collect(),
+                    WallpaperGroup::P4 => rotations(4).collect(),
+                    WallpaperGroup::P4m => rotations(4)
+                        .flat_map(|rotation| [rotation, rotation * reflection(Vec2::Y)])
+                        .collect(),
+                };
+
+                let repeat = wallpaper.repeat.as_ivec2();
+                let mut offsets: Vec<IVec2> = (-repeat.y..=repeat.y)
+                    .flat_map(|row| {
+                        (-repeat.x..=repeat.x).map(move |column| IVec2::new(column, row))
+                    })
+                    .collect();
+
+                // Keep the original shape first, where it was drawn.
+                offsets.sort_by_key(|offset| *offset != IVec2::ZERO);
+
+                offsets
+                    .into_iter()
+                    .flat_map(|offset| {
+                        let translation =
+                            Affine2::from_translation(offset.as_vec2() * wallpaper.cell);
+                        cell.iter().map(move |&transform| translation * transform)
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+fn rotation<F: Fn(f32) -> (f32, f32)>(sin_cos: &F, radians: f32) -> Affine2 {
+    let (sin, cos) = sin_cos(radians);
+    Affine2::from_mat2(Mat2::from_cols(Vec2::new(cos, sin), Vec2::new(-sin, cos)))
+}
+
+/// A reflection across the line through the origin running along `axis`.
+fn reflection(axis: Vec2) -> Affine2 {
+    let axis = axis.try_normalize().unwrap_or(Vec2::Y);
+    let (x, y) = (axis.x, axis.y);
+
+    Affine2::from_mat2(Mat2::from_cols(
+        Vec2::new(x * x - y * y, 2.0 * x * y),
+        Vec2::new(2.0 * x * y, y * y - x * x),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transform_all(symmetry: Symmetry, point: Vec2) -> Vec<Vec2> {
+        symmetry
+            .transforms(f32::sin_cos)
+            .iter()
+            .map(|transform| transform.transform_point2(point))
+            .collect()
+    }
+
+    /// Verify that each symmetry produces the right copies, with the original first.
+    #[test]
+    fn symmetry_copies() {
+        let point = Vec2::new(1.0, 0.5);
+
+        let mirrored = transform_all(Symmetry::Mirror(Vec2::Y), point);
+        assert_eq!(mirrored, vec![point, Vec2::new(-1.0, 0.5)]);
+
+        let radial = transform_all(Symmetry::Radial(4), point);
+        assert_eq!(radial.len(), 4);
+        assert!(radial[1].abs_diff_eq(Vec2::new(-0.5, 1.0), 1e-6));
+
+        assert_eq!(transform_all(Symmetry::Radial(0), point), vec![point]);
+        assert_eq!(
+            transform_all(Symmetry::Kaleidoscope(0), point),
+            vec![point, Vec2::new(1.0, -0.5)]
+        );
+
+        let kaleidoscope = transform_all(Symmetry::Kaleidoscope(3), point);
+        assert_eq!(kaleidoscope.len(), 6);
+        assert!(kaleidoscope[1].abs_diff_eq(Vec2::new(1.0, -0.5), 1e-6));
+
+        let wallpaper = Wallpaper::new(WallpaperGroup::Pm, Vec2::splat(4.0), UVec2::new(1, 0));
+        let tiled = transform_all(Symmetry::Wallpaper(wallpaper), point);
+        assert_eq!(tiled.len(), 6);
+        assert_eq!(tiled[0], point);
+        assert!(tiled.contains(&Vec2::new(5.0, 0.5)));
+        assert!(tiled.contains(&Vec2::new(-5.0, 0.5)));
+    }
+}
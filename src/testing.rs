@@ -0,0 +1,220 @@
+use std::path::{Path, PathBuf};
+
+use image::{Rgba, RgbaImage};
+
+/// How different two images may be before they are considered a mismatch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    /// The largest perceptual difference (from 0.0 to 1.0) a pixel may have before it counts as differing.
+    pub per_pixel: f32,
+    /// The fraction of pixels (from 0.0 to 1.0) that may differ before the images are considered a mismatch.
+    pub differing_pixels: f32,
+}
+
+impl Default for Tolerance {
+    /// Allows small anti-aliasing differences, but nothing else.
+    fn default() -> Self {
+        Self {
+            per_pixel: 0.02,
+            differing_pixels: 0.001,
+        }
+    }
+}
+
+impl Tolerance {
+    /// Require the images to be identical.
+    pub fn exact() -> Self {
+        Self {
+            per_pixel: 0.0,
+            differing_pixels: 0.0,
+        }
+    }
+}
+
+/// The result of [comparing](compare_images) two images.
+#[derive(Debug, Clone)]
+pub struct ImageDiff {
+    /// The largest perceptual difference between any two pixels.
+    pub max_difference: f32,
+    /// The number of pixels whose difference exceeded [Tolerance::per_pixel].
+    pub differing_pixels: usize,
+    /// The total number of pixels compared.
+    pub total_pixels: usize,
+    /// A visualization of the difference. Matching pixels are faded, differing pixels are red.
+    pub diff_image: RgbaImage,
+}
+
+impl ImageDiff {
+    /// Checks if the difference is within the given tolerance.
+    pub fn is_within(&self, tolerance: Tolerance) -> bool {
+        self.differing_pixels as f32 <= tolerance.differing_pixels * self.total_pixels as f32
+    }
+}
+
+/// Compare two images perceptually.
+///
+/// Pixels are compared in YIQ space, which weighs brightness differences more than hue differences, similar to how people see them.
+/// Returns [None] if the images are not the same size.
+pub fn compare_images(
+    actual: &RgbaImage,
+    expected: &RgbaImage,
+    tolerance: Tolerance,
+) -> Option<ImageDiff> {
+    if actual.dimensions() != expected.dimensions() {
+        return None;
+    }
+
+    let mut diff_image = RgbaImage::new(actual.width(), actual.height());
+    let mut max_difference: f32 = 0.0;
+    let mut differing_pixels = 0;
+
+    for ((a, e), d) in actual
+        .pixels()
+        .zip(expected.pixels())
+        .zip(diff_image.pixels_mut())
+    {
+        let difference = pixel_difference(a, e);
+        max_difference = max_difference.max(difference);
+
+        *d = if difference > tolerance.per_pixel {
+            differing_pixels += 1;
+            Rgba([255, 0, 0, 255])
+        } else {
+            let (y, _, _) = yiq(e);
+            let faded = (255.0 - (255.0 - y) * 0.1) as u8;
+            Rgba([faded, faded, faded, 255])
+        };
+    }
+
+    Some(ImageDiff {
+        max_difference,
+        differing_pixels,
+        total_pixels: (actual.width() * actual.height()) as usize,
+        diff_image,
+    })
+}
+
+/// Assert that an image matches a golden image stored on disk.
+///
+/// If the golden image does not exist, or the `BARIUM_UPDATE_GOLDEN` environment variable is set, `actual` is written to `golden_path` and the assertion passes.
+/// On failure, the actual image and a diff image are written next to the golden image (as `<name>.actual.png` and `<name>.diff.png`) before panicking.
+pub fn assert_matches_golden<P: AsRef<Path>>(
+    actual: &RgbaImage,
+    golden_path: P,
+    tolerance: Tolerance,
+) {
+    let golden_path = golden_path.as_ref();
+
+    if !golden_path.exists() || std::env::var_os("BARIUM_UPDATE_GOLDEN").is_some() {
+        if let Some(parent) = golden_path.parent() {
+            std::fs::create_dir_all(parent).expect("failed to create golden image directory");
+        }
+        actual
+            .save(golden_path)
+            .expect("failed to write golden image");
+        return;
+    }
+
+    let expected = image::open(golden_path)
+        .expect("failed to read golden image")
+        .to_rgba8();
+
+    let actual_path = sibling_path(golden_path, "actual");
+    let failure = match compare_images(actual, &expected, tolerance) {
+        None => Some(format!(
+            "image size {:?} does not match golden image size {:?}",
+            actual.dimensions(),
+            expected.dimensions()
+        )),
+        Some(diff) if !diff.is_within(tolerance) => {
+            let diff_path = sibling_path(golden_path, "diff");
+            diff.diff_image
+                .save(&diff_path)
+                .expect("failed to write diff image");
+            Some(format!(
+                "{} of {} pixels differ (max difference {}), diff written to {}",
+                diff.differing_pixels,
+                diff.total_pixels,
+                diff.max_difference,
+                diff_path.display()
+            ))
+        }
+        Some(_) => None,
+    };
+
+    if let Some(failure) = failure {
+        actual
+            .save(&actual_path)
+            .expect("failed to write actual image");
+        panic!(
+            "image does not match golden image {}: {}, actual image written to {}",
+            golden_path.display(),
+            failure,
+            actual_path.display()
+        );
+    }
+}
+
+/// `dir/name.png` -> `dir/name.<suffix>.png`
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{}.{}.png", stem, suffix))
+}
+
+/// The perceptual difference between two pixels, from 0.0 to 1.0.
+///
+/// Both pixels are blended onto white before comparing.
+fn pixel_difference(a: &Rgba<u8>, b: &Rgba<u8>) -> f32 {
+    let (ay, ai, aq) = yiq(a);
+    let (by, bi, bq) = yiq(b);
+
+    let (dy, di, dq) = (ay - by, ai - bi, aq - bq);
+
+    // Weights from "Measuring perceived color difference using YIQ NTSC transmission color space in mobile applications" by Kotsarenko and Ramos.
+    // The maximum possible value is 35215.
+    (0.5053 * dy * dy + 0.299 * di * di + 0.1957 * dq * dq) / 35215.0
+}
+
+/// Blend a pixel onto white and convert it into YIQ.
+fn yiq(pixel: &Rgba<u8>) -> (f32, f32, f32) {
+    let alpha = pixel.0[3] as f32 / 255.0;
+    let blend = |c: u8| 255.0 + (c as f32 - 255.0) * alpha;
+    let (r, g, b) = (blend(pixel.0[0]), blend(pixel.0[1]), blend(pixel.0[2]));
+
+    (
+        0.298_895 * r + 0.586_622 * g + 0.114_482 * b,
+        0.595_978 * r - 0.274_176 * g - 0.321_802 * b,
+        0.211_470 * r - 0.522_617 * g + 0.311_147 * b,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that identical images match exactly and a changed pixel is reported.
+    #[test]
+    fn compare_identical_and_changed() {
+        let expected = RgbaImage::from_pixel(4, 4, Rgba([10, 20, 30, 255]));
+        let mut actual = expected.clone();
+
+        let diff = compare_images(&actual, &expected, Tolerance::exact()).unwrap();
+        assert_eq!(diff.differing_pixels, 0);
+        assert!(diff.is_within(Tolerance::exact()));
+
+        actual.put_pixel(1, 1, Rgba([255, 255, 255, 255]));
+        let diff = compare_images(&actual, &expected, Tolerance::default()).unwrap();
+        assert_eq!(diff.differing_pixels, 1);
+        assert!(!diff.is_within(Tolerance::default()));
+        assert_eq!(diff.diff_image.get_pixel(1, 1), &Rgba([255, 0, 0, 255]));
+    }
+
+    /// Verify that images of different sizes never match.
+    #[test]
+    fn compare_mismatched_sizes() {
+        let a = RgbaImage::new(2, 2);
+        let b = RgbaImage::new(2, 3);
+
+        assert!(compare_images(&a, &b, Tolerance::default()).is_none());
+    }
+}
@@ -0,0 +1,394 @@
+use crate::chart::{Axes, Colorbar, Legend, OrdinalScale};
+#[cfg(feature = "toml")]
+use crate::Error;
+use crate::{Canvas, Color, IntoPoint, LineEnd, Stroke, Style};
+
+/// The typeface text is set in, read by whatever draws [chart text](crate::chart::ChartText) and other laid out text.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Font {
+    /// The name of the font family, such as `"Helvetica"` or `"sans-serif"`.
+    pub family: String,
+    /// The height of the text, in World Space.
+    pub size: f32,
+    /// The color of the text.
+    pub color: Color,
+}
+
+/// The colors of chart axes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AxisStyle {
+    /// The color of the axis lines and ticks.
+    pub color: Color,
+    /// The color of the grid lines, or `None` for no grid.
+    pub grid: Option<Color>,
+}
+
+/// A consistent look for a set of figures: the background, default stroke and fill, text, a palette for data series, and axes.
+///
+/// A [Canvas] doesn't keep a theme: [Canvas::set_theme] gives it the theme's [Style], [Canvas::draw_background] fills with its background,
+/// and chart builders take their colors from one with `with_theme`. Passing the same theme to each restyles a whole set of figures in one place.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Theme {
+    /// The color behind everything.
+    pub background: Color,
+    /// The stroke lines and outlines are drawn with by default.
+    pub stroke: Stroke,
+    /// The color shapes are filled with by default.
+    pub fill: Color,
+    /// The font text is set in.
+    pub font: Font,
+    /// The colors given to each data series in turn.
+    pub palette: Vec<Color>,
+    /// The colors of chart axes.
+    pub axis: AxisStyle,
+}
+
+/// A color from its hex code, such as `0xff8800`.
+fn rgb(hex: u32) -> Color {
+    let channel = |shift: u32| ((hex >> shift) & 0xff) as f32 / 255.0;
+    Color::new(channel(16), channel(8), channel(0), 1.0)
+}
+
+impl Theme {
+    /// Dark lines on white, with the Tableau 10 palette.
+    pub fn light() -> Self {
+        Self::new(
+            rgb(0xffffff),
+            rgb(0x222222),
+            rgb(0xdddddd),
+            &[
+                0x4e79a7, 0xf28e2b, 0xe15759, 0x76b7b2, 0x59a14f, 0xedc948, 0xb07aa1, 0xff9da7,
+                0x9c755f, 0xbab0ac,
+            ],
+        )
+    }
+
+    /// Light lines on near-black, with a palette bright enough to stand out against it.
+    pub fn dark() -> Self {
+        Self::new(
+            rgb(0x1e1e1e),
+            rgb(0xe0e0e0),
+            rgb(0x3a3a3a),
+            &[
+                0x8ab4f8, 0xfdae61, 0xf28b82, 0x81c995, 0xfde293, 0xc58af9, 0x78d9ec, 0xff8bcb,
+                0xcbb08f, 0xbdc1c6,
+            ],
+        )
+    }
+
+    /// Ethan Schoonover's Solarized, on its cream background.
+    pub fn solarized_light() -> Self {
+        Self::new(rgb(0xfdf6e3), rgb(0x657b83), rgb(0xeee8d5), &SOLARIZED)
+    }
+
+    /// Ethan Schoonover's Solarized, on its deep blue background.
+    pub fn solarized_dark() -> Self {
+        Self::new(rgb(0x002b36), rgb(0x839496), rgb(0x073642), &SOLARIZED)
+    }
+
+    /// A theme with thin lines in `foreground`, filled with the first color of the palette.
+    fn new(background: Color, foreground: Color, grid: Color, palette: &[u32]) -> Self {
+        let palette: Vec<Color> = palette.iter().copied().map(rgb).collect();
+        Self {
+            background,
+            stroke: Stroke::new(foreground, 0.01, LineEnd::Round),
+            fill: palette[0],
+            font: Font {
+                family: "sans-serif".to_string(),
+                size: 0.04,
+                color: foreground,
+            },
+            palette,
+            axis: AxisStyle {
+                color: foreground,
+                grid: Some(grid),
+            },
+        }
+    }
+
+    /// The built-in theme called `name`: `"light"`, `"dark"`, `"solarized_light"` or `"solarized_dark"`.
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "light" => Some(Self::light()),
+            "dark" => Some(Self::dark()),
+            "solarized_light" => Some(Self::solarized_light()),
+            "solarized_dark" => Some(Self::solarized_dark()),
+            _ => None,
+        }
+    }
+
+    /// The color of the `index`th data series, starting again from the first when the palette runs out.
+    pub fn color(&self, index: usize) -> Color {
+        if self.palette.is_empty() {
+            return self.fill;
+        }
+        self.palette[index % self.palette.len()]
+    }
+
+    /// The theme's default stroke and fill, for [Canvas::set_style].
+    pub fn style(&self) -> Style {
+        Style::new(Some(self.stroke), Some(self.fill))
+    }
+
+    /// A scale giving each category its color from the palette.
+    pub fn palette_scale<S: Into<String>, I: IntoIterator<Item = S>>(
+        &self,
+        domain: I,
+    ) -> OrdinalScale<Color> {
+        OrdinalScale::new(domain, self.palette.clone())
+    }
+
+    /// Read a theme from TOML. Every key is optional, and anything left out is taken from the built-in theme named by `base`,
+    /// or [light](Self::light) if there isn't one. Colors are hex codes.
+    ///
+    /// ```toml
+    /// base = "dark"
+    /// background = "#101820"
+    /// palette = ["#fee715", "#00a6fb", "#ef476f"]
+    ///
+    /// [stroke]
+    /// width = 0.02
+    ///
+    /// [font]
+    /// family = "Inter"
+    ///
+    /// [axis]
+    /// grid = "none"
+    /// ```
+    ///
+    /// Requires the `toml` feature.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(text: &str) -> Result<Self, Error> {
+        let file: toml_file::ThemeFile = toml::from_str(text)
+            .map_err(|error| Error::InvalidTheme(error.message().to_string()))?;
+        file.apply()
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::light()
+    }
+}
+
+/// Solarized's eight accent colors.
+const SOLARIZED: [u32; 8] = [
+    0x268bd2, 0xdc322f, 0x859900, 0xb58900, 0x6c71c4, 0x2aa198, 0xcb4b16, 0xd33682,
+];
+
+/// The shape of a theme written in TOML, where every key is optional.
+#[cfg(feature = "toml")]
+mod toml_file {
+    use super::*;
+
+    #[derive(serde::Deserialize)]
+    #[serde(deny_unknown_fields)]
+    pub(super) struct ThemeFile {
+        base: Option<String>,
+        background: Option<String>,
+        fill: Option<String>,
+        palette: Option<Vec<String>>,
+        #[serde(default)]
+        stroke: StrokeFile,
+        #[serde(default)]
+        font: FontFile,
+        #[serde(default)]
+        axis: AxisFile,
+    }
+
+    #[derive(Default, serde::Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct StrokeFile {
+        color: Option<String>,
+        width: Option<f32>,
+        round: Option<bool>,
+    }
+
+    #[derive(Default, serde::Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct FontFile {
+        family: Option<String>,
+        size: Option<f32>,
+        color: Option<String>,
+    }
+
+    #[derive(Default, serde::Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct AxisFile {
+        color: Option<String>,
+        /// A hex code, or `"none"` for no grid.
+        grid: Option<String>,
+    }
+
+    impl ThemeFile {
+        /// Lay the keys that were given over the base theme.
+        pub(super) fn apply(self) -> Result<Theme, Error> {
+            let base = self.base.as_deref().unwrap_or("light");
+            let mut theme = Theme::named(base).ok_or_else(|| {
+                Error::InvalidTheme(format!("no built-in theme named {:?}", base))
+            })?;
+
+            if let Some(background) = self.background {
+                theme.background = Color::from_hex(&background)?;
+            }
+            if let Some(fill) = self.fill {
+                theme.fill = Color::from_hex(&fill)?;
+            }
+            if let Some(palette) = self.palette {
+                theme.palette = palette
+                    .iter()
+                    .map(|hex| Color::from_hex(hex))
+                    .collect::<Result<_, _>>()?;
+            }
+
+            if let Some(color) = self.stroke.color {
+                theme.stroke.color = Color::from_hex(&color)?;
+            }
+            if let Some(width) = self.stroke.width {
+                theme.stroke.width = width;
+            }
+            if let Some(round) = self.stroke.round {
+                theme.stroke.line_end = if round { LineEnd::Round } else { LineEnd::Butt };
+            }
+
+            if let Some(family) = self.font.family {
+                theme.font.family = family;
+            }
+            if let Some(size) = self.font.size {
+                theme.font.size = size;
+            }
+            if let Some(color) = self.font.color {
+                theme.font.color = Color::from_hex(&color)?;
+            }
+
+            if let Some(color) = self.axis.color {
+                theme.axis.color = Color::from_hex(&color)?;
+            }
+            match self.axis.grid.as_deref() {
+                Some("none") => theme.axis.grid = None,
+                Some(grid) => theme.axis.grid = Some(Color::from_hex(grid)?),
+                None => (),
+            }
+
+            Ok(theme)
+        }
+    }
+}
+
+impl Axes {
+    /// Take the colors of the axis lines, ticks and grid from `theme`, keeping their widths, consuming the parent.
+    pub fn with_theme(mut self, theme: &Theme) -> Self {
+        self.stroke.color = theme.axis.color;
+        self.grid = theme.axis.grid.map(|color| Stroke {
+            color,
+            ..self.stroke
+        });
+        self
+    }
+}
+
+impl Legend {
+    /// Set the background to the theme's, and the border to its axis color, consuming the parent.
+    pub fn with_theme(mut self, theme: &Theme) -> Self {
+        self.background = Some(theme.background);
+        self.border = Some(Stroke {
+            color: theme.axis.color,
+            ..self.border.unwrap_or(theme.stroke)
+        });
+        self
+    }
+}
+
+impl Colorbar {
+    /// Take the color of the outline and ticks from `theme`, keeping their width, consuming the parent.
+    pub fn with_theme(mut self, theme: &Theme) -> Self {
+        self.stroke.color = theme.axis.color;
+        self
+    }
+}
+
+impl Canvas {
+    /// [Set the style](Self::set_style) to the theme's [stroke and fill](Theme::style), so shapes drawn with neither take them on.
+    ///
+    /// The canvas doesn't keep the theme itself. Chart builders only take its colors through their own `with_theme`.
+    pub fn set_theme(&mut self, theme: &Theme) {
+        self.set_style(theme.style());
+    }
+
+    /// Fill the rectangle from `min` to `max` with the background of `theme`, projected from the camera.
+    /// Draw this first, so everything else sits on top.
+    pub fn draw_background<P: IntoPoint>(&mut self, theme: &Theme, min: P, max: P) {
+        self.draw_rect(min, max, None, Some(theme.background));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chart::Axis;
+    use glam::Vec2;
+
+    /// Verify that built-in themes restyle the canvas and charts, and TOML themes build on them.
+    #[test]
+    fn restyle() {
+        let dark = Theme::named("dark").unwrap();
+        assert_eq!(dark.color(1), dark.color(11));
+        assert_eq!(
+            dark.palette_scale(["a", "b"]).map("b"),
+            Some(dark.palette[1])
+        );
+
+        let axes = Axes::new(
+            Vec2::ZERO,
+            Vec2::ONE,
+            Axis::new((0.0, 1.0)),
+            Axis::new((0.0, 1.0)),
+        )
+        .with_theme(&dark);
+        assert_eq!(axes.stroke.color, dark.axis.color);
+        assert_eq!(axes.grid.unwrap().width, axes.stroke.width);
+
+        let mut canvas = Canvas::new(1);
+        let solarized = Theme::solarized_dark();
+        canvas.set_theme(&solarized);
+        assert_eq!(canvas.style(), solarized.style());
+        canvas.draw_background(&solarized, Vec2::ZERO, Vec2::ONE);
+        assert_eq!(canvas.as_raw()[0].fill, Some(rgb(0x002b36)));
+        canvas.draw_line((0.0, 0.0), (1.0, 1.0), None, None);
+        assert_eq!(
+            canvas.as_raw()[1].stroke,
+            Some(Theme::solarized_dark().stroke)
+        );
+
+        #[cfg(feature = "toml")]
+        {
+            let theme = Theme::from_toml(
+                r##"
+                base = "solarized_light"
+                palette = ["#ff0000", "#0000ff"]
+
+                [stroke]
+                width = 0.5
+
+                [axis]
+                grid = "none"
+                "##,
+            )
+            .unwrap();
+            assert_eq!(theme.background, Theme::solarized_light().background);
+            assert_eq!(theme.color(3), Color::blue());
+            assert_eq!(theme.stroke.width, 0.5);
+            assert_eq!(theme.axis.grid, None);
+
+            assert!(Theme::from_toml("base = \"sepia\"").is_err());
+            assert!(Theme::from_toml("backdrop = \"#ffffff\"").is_err());
+            assert_eq!(
+                Theme::from_toml("fill = \"orange\""),
+                Err(Error::InvalidHex("orange".to_string()))
+            );
+        }
+    }
+}
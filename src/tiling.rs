@@ -0,0 +1,430 @@
+use glam::{Affine2, Vec2};
+
+use crate::{rng::Rng, Canvas, Color, Shape, Stroke};
+
+/// A square tile for a [Tiling], drawn in the unit square from `(0, 0)` to `(1, 1)`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Tile {
+    /// The shapes making up the tile, in its own unit square.
+    pub shapes: Vec<Shape>,
+    /// Labels for the right, top, left and bottom edges. Neighbouring tiles must have the same label on the edge they share.
+    pub edges: [u32; 4],
+}
+
+impl Tile {
+    /// Create a new [Tile] from shapes drawn in the unit square.
+    pub fn new(shapes: Vec<Shape>, edges: [u32; 4]) -> Self {
+        Self { shapes, edges }
+    }
+
+    /// Create a new [Tile] from everything drawn onto `canvas`, which should cover the unit square.
+    pub fn from_canvas(canvas: &Canvas, edges: [u32; 4]) -> Self {
+        Self::new(canvas.as_raw().to_vec(), edges)
+    }
+
+    /// The label of one edge, counting counter-clockwise from the right, after turning the tile `rotation` quarter turns counter-clockwise.
+    fn edge(&self, side: usize, rotation: u8) -> u32 {
+        self.edges[(side + 4 - rotation as usize % 4) % 4]
+    }
+}
+
+/// Where [Tiling::layout] put a tile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlacedTile {
+    /// Index of the tile in [Tiling::tiles].
+    pub tile: usize,
+    /// Column of the cell, counting from the left.
+    pub column: usize,
+    /// Row of the cell, counting from the bottom.
+    pub row: usize,
+    /// Quarter turns counter-clockwise, from 0 to 3.
+    pub rotation: u8,
+}
+
+impl PlacedTile {
+    /// A transform that moves the tile's unit square onto its cell, for a grid with its bottom left corner at `min`.
+    ///
+    /// Hand these to [Canvas::draw_instances_absolute] to draw the tile's shapes in place.
+    pub fn transform(&self, min: Vec2, cell_size: f32) -> Affine2 {
+        let center = min + (Vec2::new(self.column as f32, self.row as f32) + 0.5) * cell_size;
+        let angle = std::f32::consts::FRAC_PI_2 * self.rotation as f32;
+
+        Affine2::from_scale_angle_translation(Vec2::splat(cell_size), angle, center)
+            * Affine2::from_translation(Vec2::splat(-0.5))
+    }
+}
+
+/// Filling a grid with Truchet or Wang tiles, so that every pair of neighbouring tiles agrees on the label of their shared edge.
+///
+/// Truchet tiles share one label on every edge and are placed with random rotations. Wang tiles use their edge labels to decide which tiles may sit next to each other.
+/// Cells are filled row by row with random tiles that fit, backtracking when a cell has no tile that fits. The same seed always produces the same tiling.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tiling {
+    /// The tiles to choose from.
+    pub tiles: Vec<Tile>,
+    /// Whether tiles may be turned in quarter turns.
+    pub rotate: bool,
+    /// How often [collapse](Self::collapse) picks each tile relative to the others, in the same order as [tiles](Self::tiles).
+    /// Tiles without a weight have a weight of one.
+    pub weights: Vec<f32>,
+    /// Seed for choosing tiles.
+    pub seed: u64,
+}
+
+impl Tiling {
+    /// Create a new [Tiling] from a set of tiles, without rotating them.
+    pub fn new(tiles: Vec<Tile>) -> Self {
+        Self {
+            tiles,
+            rotate: false,
+            weights: Vec::new(),
+            seed: 0,
+        }
+    }
+
+    /// The classic Truchet tile of Smith: two quarter circles joining the middles of neighbouring edges, placed with random rotations to form meandering curves.
+    pub fn truchet_arcs(stroke: Stroke) -> Self {
+        const SEGMENTS: usize = 16;
+        let arc = |center: Vec2, start: f32| -> Shape {
+            let points = (0..=SEGMENTS)
+                .map(|i| {
+                    let angle = start + std::f32::consts::FRAC_PI_2 * i as f32 / SEGMENTS as f32;
+                    center + Vec2::new(angle.cos(), angle.sin()) * 0.5
+                })
+                .collect();
+
+            Shape {
+                points,
+                stroke: Some(stroke),
+                ..Default::default()
+            }
+        };
+
+        let tile = Tile::new(
+            vec![arc(Vec2::ZERO, 0.0), arc(Vec2::ONE, std::f32::consts::PI)],
+            [0; 4],
+        );
+
+        Self {
+            rotate: true,
+            ..Self::new(vec![tile])
+        }
+    }
+
+    /// The original Truchet tile: a square split along its diagonal into a filled and an empty triangle, placed with random rotations.
+    pub fn truchet_triangles(fill: Color) -> Self {
+        let triangle = Shape {
+            points: vec![Vec2::ZERO, Vec2::X, Vec2::Y, Vec2::ZERO],
+            fill: Some(fill),
+            ..Default::default()
+        };
+
+        Self {
+            rotate: true,
+            ..Self::new(vec![Tile::new(vec![triangle], [0; 4])])
+        }
+    }
+
+    /// A complete set of sixteen Wang tiles, one for each combination of edges with a line running to the middle of the tile.
+    /// Matching edges join the lines into a connected network of pipes, with no loose ends inside the grid.
+    pub fn wang_pipes(stroke: Stroke) -> Self {
+        let middles = [
+            Vec2::new(1.0, 0.5),
+            Vec2::new(0.5, 1.0),
+            Vec2::new(0.0, 0.5),
+            Vec2::new(0.5, 0.0),
+        ];
+
+        let tiles = (0..16u32)
+            .map(|mask| {
+                let edges = [0, 1, 2, 3].map(|side| (mask >> side) & 1);
+                let shapes = (0..4)
+                    .filter(|&side| edges[side] == 1)
+                    .map(|side| Shape {
+                        points: vec![Vec2::splat(0.5), middles[side]],
+                        stroke: Some(stroke),
+                        ..Default::default()
+                    })
+                    .collect();
+
+                Tile::new(shapes, edges)
+            })
+            .collect();
+
+        Self::new(tiles)
+    }
+
+    /// Pick a tile for every cell of a grid `columns` wide and `rows` tall, in order from the bottom left, row by row.
+    ///
+    /// Returns `None` if the tiles cannot be fitted together, or no fit was found after a generous amount of backtracking.
+    pub fn layout(&self, columns: usize, rows: usize) -> Option<Vec<PlacedTile>> {
+        let options = self.options();
+        let cells = columns * rows;
+        let mut rng = Rng::new(self.seed);
+        let mut chosen: Vec<(usize, u8)> = Vec::with_capacity(cells);
+        // The shuffled tiles that fit each cell so far, and how many of them have been tried.
+        let mut candidates: Vec<(Vec<(usize, u8)>, usize)> = Vec::with_capacity(cells);
+        let mut steps = 0;
+
+        while chosen.len() < cells {
+            steps += 1;
+            if steps > 64 * cells + 1024 {
+                return None;
+            }
+
+            if candidates.len() == chosen.len() {
+                let cell = chosen.len();
+                let left = (!cell.is_multiple_of(columns)).then(|| chosen[cell - 1]);
+                let below = (cell >= columns).then(|| chosen[cell - columns]);
+
+                let mut fitting: Vec<(usize, u8)> = options
+                    .iter()
+                    .copied()
+                    .filter(|&(tile, rotation)| {
+                        let tile = &self.tiles[tile];
+                        let fits_left = left.is_none_or(|(other, other_rotation)| {
+                            self.tiles[other].edge(0, other_rotation) == tile.edge(2, rotation)
+                        });
+                        let fits_below = below.is_none_or(|(other, other_rotation)| {
+                            self.tiles[other].edge(1, other_rotation) == tile.edge(3, rotation)
+                        });
+                        fits_left && fits_below
+                    })
+                    .collect();
+
+                for i in (1..fitting.len()).rev() {
+                    let j = (rng.next_u64() % (i as u64 + 1)) as usize;
+                    fitting.swap(i, j);
+                }
+                candidates.push((fitting, 0));
+            }
+
+            let (fitting, tried) = candidates.last_mut()?;
+            if let Some(&option) = fitting.get(*tried) {
+                *tried += 1;
+                chosen.push(option);
+            } else {
+                // Nothing fits here, so try the next option for the previous cell.
+                candidates.pop();
+                chosen.pop()?;
+            }
+        }
+
+        Some(placements(&chosen, columns))
+    }
+
+    /// Pick a tile for every cell of a grid `columns` wide and `rows` tall with the simple tiled model of Wave Function Collapse.
+    ///
+    /// Every cell starts out able to hold any tile. The cell with the fewest choices left, weighted by [weights](Self::weights), is collapsed to one
+    /// random tile, and tiles that no longer fit are ruled out of the cells around it, spreading outwards until nothing changes.
+    /// This repeats until every cell holds one tile. Unlike [layout](Self::layout), which fills rows in order, the result has no bias towards any corner,
+    /// and weights control how common each tile is.
+    ///
+    /// If some cell runs out of tiles, the grid starts over, up to ten times. Returns tiles in the same order as [layout](Self::layout), or `None` if every try failed.
+    pub fn collapse(&self, columns: usize, rows: usize) -> Option<Vec<PlacedTile>> {
+        const ATTEMPTS: usize = 10;
+
+        let options = self.options();
+        let weights: Vec<f32> = options
+            .iter()
+            .map(|&(tile, _)| self.weights.get(tile).copied().unwrap_or(1.0).max(0.0))
+            .collect();
+        if !weights.iter().any(|&weight| weight > 0.0) {
+            return None;
+        }
+
+        // Which options may sit next to each other, by the side of the first that the second is on.
+        let allowed: Vec<Vec<Vec<bool>>> = (0..4)
+            .map(|side| {
+                options
+                    .iter()
+                    .map(|&(a, a_rotation)| {
+                        options
+                            .iter()
+                            .map(|&(b, b_rotation)| {
+                                self.tiles[a].edge(side, a_rotation)
+                                    == self.tiles[b].edge((side + 2) % 4, b_rotation)
+                            })
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let cells = columns * rows;
+        let neighbor = |cell: usize, side: usize| -> Option<usize> {
+            let (column, row) = (cell % columns, cell / columns);
+            match side {
+                0 => (column + 1 < columns).then(|| cell + 1),
+                1 => (row + 1 < rows).then(|| cell + columns),
+                2 => (column > 0).then(|| cell - 1),
+                _ => (row > 0).then(|| cell - columns),
+            }
+        };
+
+        let mut rng = Rng::new(self.seed);
+        'attempts: for _ in 0..ATTEMPTS {
+            let mut wave: Vec<Vec<bool>> = vec![weights.iter().map(|&w| w > 0.0).collect(); cells];
+            let mut pending: Vec<usize> = (0..cells).collect();
+
+            loop {
+                // Rule out options that nothing next to them allows.
+                while let Some(cell) = pending.pop() {
+                    for (side, allowed) in allowed.iter().enumerate() {
+                        let Some(other) = neighbor(cell, side) else {
+                            continue;
+                        };
+
+                        let mut changed = false;
+                        for b in 0..options.len() {
+                            let supported =
+                                (0..options.len()).any(|a| wave[cell][a] && allowed[a][b]);
+                            if wave[other][b] && !supported {
+                                wave[other][b] = false;
+                                changed = true;
+                            }
+                        }
+
+                        if changed {
+                            if !wave[other].contains(&true) {
+                                continue 'attempts;
+                            }
+                            pending.push(other);
+                        }
+                    }
+                }
+
+                // Collapse the undecided cell with the lowest entropy, with a little noise to break ties.
+                let entropy = |cell: &Vec<bool>| -> f32 {
+                    let (sum, sum_log) = cell
+                        .iter()
+                        .zip(&weights)
+                        .filter(|(possible, _)| **possible)
+                        .fold((0.0, 0.0), |(sum, sum_log), (_, &w)| {
+                            (sum + w, sum_log + w * w.ln())
+                        });
+                    sum.ln() - sum_log / sum
+                };
+                let undecided = (0..cells)
+                    .filter(|&cell| wave[cell].iter().filter(|&&possible| possible).count() > 1)
+                    .map(|cell| (cell, entropy(&wave[cell]) + rng.range(0.0, 1e-3)))
+                    .min_by(|a, b| a.1.total_cmp(&b.1));
+
+                let Some((cell, _)) = undecided else {
+                    let chosen: Vec<(usize, u8)> = wave
+                        .iter()
+                        .filter_map(|cell| cell.iter().position(|&possible| possible))
+                        .map(|option| options[option])
+                        .collect();
+                    return Some(placements(&chosen, columns));
+                };
+
+                let total: f32 = (0..options.len())
+                    .filter(|&option| wave[cell][option])
+                    .map(|option| weights[option])
+                    .sum();
+                let mut pick = rng.range(0.0, total);
+                let choice = (0..options.len())
+                    .filter(|&option| wave[cell][option])
+                    .find(|&option| {
+                        pick -= weights[option];
+                        pick <= 0.0
+                    })
+                    .or_else(|| wave[cell].iter().rposition(|&possible| possible))?;
+
+                for (option, possible) in wave[cell].iter_mut().enumerate() {
+                    *possible = option == choice;
+                }
+                pending.push(cell);
+            }
+        }
+
+        None
+    }
+
+    /// Every tile, in every rotation it may be placed in.
+    fn options(&self) -> Vec<(usize, u8)> {
+        let rotations: u8 = if self.rotate { 4 } else { 1 };
+        (0..self.tiles.len())
+            .flat_map(|tile| (0..rotations).map(move |rotation| (tile, rotation)))
+            .collect()
+    }
+}
+
+/// Turn the tile chosen for each cell, row by row from the bottom left, into [PlacedTile]s.
+fn placements(chosen: &[(usize, u8)], columns: usize) -> Vec<PlacedTile> {
+    chosen
+        .iter()
+        .enumerate()
+        .map(|(cell, &(tile, rotation))| PlacedTile {
+            tile,
+            column: cell % columns,
+            row: cell / columns,
+            rotation,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LineEnd;
+
+    /// Verify that Wang tiles always match their neighbours whether laid out in order or collapsed, and that tiles that cannot match give no layout.
+    #[test]
+    fn matching_layouts() {
+        let stroke = Stroke::new(Color::black(), 0.05, LineEnd::Round);
+        let tiling = Tiling {
+            seed: 7,
+            ..Tiling::wang_pipes(stroke)
+        };
+
+        let (columns, rows) = (6, 5);
+        let placed = tiling.layout(columns, rows).unwrap();
+        assert_eq!(placed.len(), columns * rows);
+        for tile in &placed {
+            let edge =
+                |placed: &PlacedTile, side| tiling.tiles[placed.tile].edge(side, placed.rotation);
+            let cell = tile.row * columns + tile.column;
+            if tile.column + 1 < columns {
+                assert_eq!(edge(tile, 0), edge(&placed[cell + 1], 2));
+            }
+            if tile.row + 1 < rows {
+                assert_eq!(edge(tile, 1), edge(&placed[cell + columns], 3));
+            }
+        }
+
+        let truchet = Tiling::truchet_arcs(stroke).layout(4, 4).unwrap();
+        assert!(truchet
+            .iter()
+            .any(|tile| tile.rotation != truchet[0].rotation));
+        let corner = truchet[5].transform(Vec2::new(1.0, 2.0), 0.5);
+        assert!(corner
+            .transform_point2(Vec2::splat(0.5))
+            .abs_diff_eq(Vec2::new(1.75, 2.75), 1e-6));
+
+        let sparse = Tiling {
+            weights: [vec![20.0], vec![1.0; 15]].concat(),
+            ..tiling.clone()
+        };
+        let collapsed = sparse.collapse(columns, rows).unwrap();
+        assert_eq!(collapsed.len(), columns * rows);
+        assert!(collapsed.iter().filter(|tile| tile.tile == 0).count() > columns * rows / 3);
+        for tile in &collapsed {
+            let edge =
+                |placed: &PlacedTile, side| tiling.tiles[placed.tile].edge(side, placed.rotation);
+            let cell = tile.row * columns + tile.column;
+            if tile.column + 1 < columns {
+                assert_eq!(edge(tile, 0), edge(&collapsed[cell + 1], 2));
+            }
+            if tile.row + 1 < rows {
+                assert_eq!(edge(tile, 1), edge(&collapsed[cell + columns], 3));
+            }
+        }
+
+        let one_way = Tiling::new(vec![Tile::new(Vec::new(), [1, 0, 0, 0])]);
+        assert!(one_way.layout(1, 3).is_some());
+        assert!(one_way.layout(2, 1).is_none());
+        assert!(one_way.collapse(2, 1).is_none());
+    }
+}
@@ -0,0 +1,98 @@
+use glam::Vec2;
+
+use crate::{geometry::segments_cross, Shape};
+
+/// A problem found by [Canvas::validate](crate::Canvas::validate).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValidationIssue {
+    /// Index of the offending shape, as found in [Canvas::as_raw](crate::Canvas::as_raw).
+    pub shape_index: usize,
+    /// What is wrong with the shape.
+    pub kind: IssueKind,
+}
+
+/// The kinds of degenerate geometry detected by [Canvas::validate](crate::Canvas::validate).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IssueKind {
+    /// A point has a `NaN` or infinite coordinate.
+    NonFiniteCoordinate {
+        /// Index of the offending point, counting through the [subpaths](crate::Shape::subpaths) after the main points.
+        point_index: usize,
+    },
+    /// The shape has a stroke with a width that is zero, negative, or not finite.
+    ZeroWidthStroke,
+    /// Two non-adjacent edges of a polygon cross each other.
+    SelfIntersection {
+        /// Index of the first point of the first edge.
+        first_edge: usize,
+        /// Index of the first point of the second edge.
+        second_edge: usize,
+    },
+    /// The shape has one or fewer points and will not be drawn.
+    EmptyPath,
+}
+
+/// Collect every issue with a single shape.
+pub(crate) fn validate_shape(shape_index: usize, shape: &Shape, issues: &mut Vec<ValidationIssue>) {
+    let mut push = |kind| issues.push(ValidationIssue { shape_index, kind });
+    let shape: &Shape = &shape.flattened();
+
+    if !shape.is_drawable() {
+        push(IssueKind::EmptyPath);
+    }
+
+    let mut finite = true;
+    for (point_index, point) in shape.paths().flatten().enumerate() {
+        if !point.is_finite() {
+            finite = false;
+            push(IssueKind::NonFiniteCoordinate { point_index });
+        }
+    }
+
+    if let Some(stroke) = shape.stroke {
+        if !(stroke.width.is_finite() && stroke.width > 0.0) {
+            push(IssueKind::ZeroWidthStroke);
+        }
+    }
+
+    // Intersection tests are meaningless with non-finite points.
+    if finite && shape.is_polygon() {
+        if let Some((first_edge, second_edge)) = find_self_intersection(&shape.points) {
+            push(IssueKind::SelfIntersection {
+                first_edge,
+                second_edge,
+            });
+        }
+    }
+}
+
+/// Find the first pair of non-adjacent edges that cross in a closed polygon.
+///
+/// The polygon's first and last points must be equal.
+/// This is a brute force `O(n^2)` search, with a bounding box check to skip most pairs.
+fn find_self_intersection(points: &[Vec2]) -> Option<(usize, usize)> {
+    let edge_count = points.len() - 1;
+
+    for i in 0..edge_count {
+        let (a0, a1) = (points[i], points[i + 1]);
+        let (a_min, a_max) = (a0.min(a1), a0.max(a1));
+
+        for j in i + 2..edge_count {
+            // The first and last edges share the closing point.
+            if i == 0 && j == edge_count - 1 {
+                continue;
+            }
+
+            let (b0, b1) = (points[j], points[j + 1]);
+            if b0.max(b1).cmplt(a_min).any() || b0.min(b1).cmpgt(a_max).any() {
+                continue;
+            }
+
+            if segments_cross(a0, a1, b0, b1) {
+                return Some((i, j));
+            }
+        }
+    }
+
+    None
+}
@@ -0,0 +1,116 @@
+use crate::{
+    color::{linear_to_srgb, srgb_to_linear},
+    Color, Gradient,
+};
+
+/// A kind of color blindness, for checking that a figure still reads well without full color vision.
+///
+/// Simulation uses the model of Machado, Oliveira and Fernandes (2009), at full severity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorVisionDeficiency {
+    /// No working red cones, so reds look dark and are confused with greens.
+    Protanopia,
+    /// No working green cones, the most common kind, so reds and greens are confused.
+    Deuteranopia,
+    /// No working blue cones, so blues are confused with greens, and yellows with pinks.
+    Tritanopia,
+}
+
+impl ColorVisionDeficiency {
+    /// Every kind of deficiency, for checking against all of them at once.
+    pub const ALL: [ColorVisionDeficiency; 3] = [
+        ColorVisionDeficiency::Protanopia,
+        ColorVisionDeficiency::Deuteranopia,
+        ColorVisionDeficiency::Tritanopia,
+    ];
+
+    /// The matrix applied to linear RGB, one row per output channel.
+    fn matrix(self) -> [[f32; 3]; 3] {
+        match self {
+            ColorVisionDeficiency::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ],
+            ColorVisionDeficiency::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ],
+            ColorVisionDeficiency::Tritanopia => [
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ],
+        }
+    }
+
+    /// How `color` looks to someone with this deficiency. Alpha is kept.
+    pub fn simulate(self, color: Color) -> Color {
+        let color = color.clamped();
+        let linear = [color.r(), color.g(), color.b()].map(srgb_to_linear);
+
+        let [r, g, b] = self.matrix().map(|row| {
+            let value = row.iter().zip(linear).map(|(m, c)| m * c).sum::<f32>();
+            linear_to_srgb(value.clamp(0.0, 1.0))
+        });
+
+        Color::new(r, g, b, color.a())
+    }
+
+    /// How `gradient` looks to someone with this deficiency.
+    pub fn simulate_gradient(self, gradient: &Gradient) -> Gradient {
+        Gradient::from_stops(
+            gradient
+                .stops()
+                .iter()
+                .map(|(position, color)| (*position, self.simulate(*color))),
+        )
+    }
+
+    /// Every pair of colors in `palette`, by index, that are closer than `min_distance` once simulated.
+    ///
+    /// Distance is measured with [Color::perceptual_distance]. Around 0.1 keeps categories in a chart easy to tell apart.
+    pub fn confusable_pairs(self, palette: &[Color], min_distance: f32) -> Vec<(usize, usize)> {
+        let simulated: Vec<Color> = palette.iter().map(|color| self.simulate(*color)).collect();
+        let mut pairs = Vec::new();
+
+        for i in 0..simulated.len() {
+            for j in i + 1..simulated.len() {
+                if simulated[i].perceptual_distance(&simulated[j]) < min_distance {
+                    pairs.push((i, j));
+                }
+            }
+        }
+
+        pairs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that red and green blur together without green cones, but not blue and yellow, and that contrast follows WCAG.
+    #[test]
+    fn check_palette() {
+        let palette = [
+            Color::new(0.8, 0.2, 0.1, 1.0),
+            Color::new(0.3, 0.55, 0.1, 1.0),
+            Color::new(0.1, 0.2, 0.9, 1.0),
+            Color::new(0.95, 0.9, 0.1, 1.0),
+        ];
+
+        assert!(palette[0].perceptual_distance(&palette[1]) > 0.1);
+        assert_eq!(
+            ColorVisionDeficiency::Deuteranopia.confusable_pairs(&palette, 0.1),
+            vec![(0, 1)]
+        );
+
+        let white = ColorVisionDeficiency::Tritanopia.simulate(Color::white());
+        assert!(white.perceptual_distance(&Color::white()) < 1e-3);
+
+        assert!((Color::black().contrast_ratio(&Color::white()) - 21.0).abs() < 1e-3);
+        assert_eq!(Color::red().contrast_ratio(&Color::red()), 1.0);
+    }
+}
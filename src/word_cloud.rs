@@ -0,0 +1,243 @@
+use glam::Vec2;
+
+use crate::{rng::Rng, spatial::SpatialIndex};
+
+/// The most turns the spiral makes on its way out to [WordCloud::max_radius], which bounds how small a step is used.
+const MAX_TURNS: f32 = 500.0;
+
+/// A word to place in a [WordCloud], sized by its weight.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Word {
+    /// The text of the word.
+    pub text: String,
+    /// How important the word is, such as how often it appears. Heavier words are drawn larger and placed first.
+    pub weight: f32,
+}
+
+impl Word {
+    /// Create a new [Word].
+    pub fn new(text: impl Into<String>, weight: f32) -> Self {
+        Self {
+            text: text.into(),
+            weight,
+        }
+    }
+}
+
+/// Where [WordCloud] put a [Word].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlacedWord {
+    /// The font size to draw the word at.
+    pub font_size: f32,
+    /// The center of the word's box.
+    pub center: Vec2,
+    /// Whether the word is turned a quarter turn counter-clockwise, to read upwards.
+    pub vertical: bool,
+    /// The bottom left corner of the word's box, after turning.
+    pub min: Vec2,
+    /// The top right corner of the word's box, after turning.
+    pub max: Vec2,
+}
+
+/// A word cloud layout, which places words from heaviest to lightest along a spiral out from the center,
+/// each at the first spot where its box doesn't overlap a word already placed.
+///
+/// Words are measured and placed the same way as [chart text](crate::chart::ChartText).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WordCloud {
+    /// Where the spiral starts.
+    pub center: Vec2,
+    /// How far from the center a word may be placed. Words that don't fit within it are left out, and nothing is placed if it is not finite.
+    pub max_radius: f32,
+    /// The font size of the lightest word.
+    pub min_font_size: f32,
+    /// The font size of the heaviest word.
+    pub max_font_size: f32,
+    /// Space kept between neighbouring words' boxes.
+    pub padding: f32,
+    /// The share of words turned to read upwards, from 0.0 (none) to 1.0 (all).
+    pub vertical: f32,
+    /// Distance between turns of the spiral, and between the spots tried along it.
+    ///
+    /// Smaller steps are raised to 1/500 of [max_radius](Self::max_radius), so the search always ends.
+    pub step: f32,
+    /// The seed for choosing which words turn and where the spiral starts.
+    pub seed: u64,
+}
+
+impl WordCloud {
+    /// Create a new [WordCloud] with every word horizontal, stepping out a tenth of the smallest font size at a time.
+    pub fn new(center: Vec2, max_radius: f32, min_font_size: f32, max_font_size: f32) -> Self {
+        Self {
+            center,
+            max_radius,
+            min_font_size,
+            max_font_size,
+            padding: 0.0,
+            vertical: 0.0,
+            step: min_font_size / 10.0,
+            seed: 0,
+        }
+    }
+
+    /// Place every word, returning where each went in the same order as `words`.
+    ///
+    /// `measure` gives the width and height of a word's text at a font size of 1.0.
+    pub fn place<M: Fn(&str) -> Vec2>(
+        &self,
+        words: &[Word],
+        measure: M,
+    ) -> Vec<Option<PlacedWord>> {
+        self.place_within(words, measure, |_| true)
+    }
+
+    /// Place every word like [place](Self::place), but only where its box lies within a mask, such as the outline of a shape.
+    ///
+    /// `inside` checks whether a point is in the mask. It is tried at the corners, edges and middle of each box,
+    /// so the mask should not have gaps narrower than the smallest words.
+    pub fn place_within<M, I>(
+        &self,
+        words: &[Word],
+        measure: M,
+        inside: I,
+    ) -> Vec<Option<PlacedWord>>
+    where
+        M: Fn(&str) -> Vec2,
+        I: Fn(Vec2) -> bool,
+    {
+        let mut rng = Rng::new(self.seed);
+        let (lightest, heaviest) = words
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), word| {
+                (min.min(word.weight), max.max(word.weight))
+            });
+
+        let mut order: Vec<usize> = (0..words.len()).collect();
+        order.sort_by(|&a, &b| words[b].weight.total_cmp(&words[a].weight));
+
+        let mut placed: Vec<Option<PlacedWord>> = vec![None; words.len()];
+        let mut index = SpatialIndex::new(self.max_font_size.max(self.min_font_size));
+        let step = self.step.max(self.max_radius / MAX_TURNS);
+        if !(step > 0.0 && step.is_finite()) {
+            return placed;
+        }
+
+        for word_index in order {
+            let word = &words[word_index];
+            let t = if heaviest > lightest {
+                (word.weight - lightest) / (heaviest - lightest)
+            } else {
+                1.0
+            };
+            let font_size = self.min_font_size + (self.max_font_size - self.min_font_size) * t;
+            let vertical = rng.next_f32() < self.vertical;
+            let size = measure(&word.text) * font_size;
+            let size = if vertical {
+                Vec2::new(size.y, size.x)
+            } else {
+                size
+            };
+            let half = size / 2.0;
+
+            // An Archimedean spiral, with the spots along it spaced about a step apart.
+            let mut angle = rng.range(0.0, std::f32::consts::TAU);
+            let mut radius = 0.0;
+            while radius <= self.max_radius {
+                let center = self.center + Vec2::new(angle.cos(), angle.sin()) * radius;
+                let (min, max) = (center - half, center + half);
+
+                let fits = index
+                    .query(min - self.padding, max + self.padding)
+                    .iter()
+                    .all(|&other| {
+                        let other: &PlacedWord = placed[other].as_ref().unwrap();
+                        other.min.cmpge(max + self.padding).any()
+                            || other.max.cmple(min - self.padding).any()
+                    })
+                    && (0..3).all(|i| {
+                        (0..3).all(|j| inside(min + size * Vec2::new(i as f32, j as f32) / 2.0))
+                    });
+
+                if fits {
+                    index.insert(word_index, min, max);
+                    placed[word_index] = Some(PlacedWord {
+                        font_size,
+                        center,
+                        vertical,
+                        min,
+                        max,
+                    });
+                    break;
+                }
+
+                let turn = step / radius.max(step);
+                angle += turn;
+                radius += step * turn / std::f32::consts::TAU;
+            }
+        }
+
+        placed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that words are sized by weight, never overlap, and stay within the mask.
+    #[test]
+    fn place_words() {
+        let words: Vec<Word> = (0..30)
+            .map(|i| Word::new("word".repeat(1 + i % 3), 30.0 - i as f32))
+            .collect();
+        let measure = |text: &str| Vec2::new(text.len() as f32 * 0.6, 1.0);
+
+        let mut cloud = WordCloud::new(Vec2::ZERO, 200.0, 4.0, 20.0);
+        cloud.padding = 1.0;
+        cloud.vertical = 0.3;
+        let placed = cloud.place(&words, measure);
+
+        let first = placed[0].unwrap();
+        assert_eq!(first.font_size, 20.0);
+        assert_eq!(placed[29].unwrap().font_size, 4.0);
+        assert!(first.center.length() < 1e-4);
+        assert!(placed.iter().flatten().any(|word| word.vertical));
+
+        let boxes: Vec<PlacedWord> = placed.iter().flatten().copied().collect();
+        assert_eq!(boxes.len(), 30);
+        for (i, a) in boxes.iter().enumerate() {
+            for b in &boxes[i + 1..] {
+                assert!(
+                    a.min.cmpge(b.max + 1.0 - 1e-3).any() || a.max.cmple(b.min - 1.0 + 1e-3).any()
+                );
+            }
+        }
+
+        // Only the right half is allowed.
+        let placed = cloud.place_within(&words, measure, |point| point.x >= 0.0);
+        assert!(placed.iter().flatten().all(|word| word.min.x >= 0.0));
+        assert!(placed.iter().flatten().count() > 10);
+    }
+
+    /// Verify that a step of zero or less is raised enough for the search to end.
+    #[test]
+    fn zero_step() {
+        let words = [Word::new("a", 1.0), Word::new("b", 2.0)];
+        let measure = |_: &str| Vec2::splat(1.0);
+
+        let cloud = WordCloud::new(Vec2::ZERO, 50.0, 0.0, 10.0);
+        assert_eq!(cloud.step, 0.0);
+        let placed = cloud.place(&words, measure);
+        assert!(placed[1].is_some());
+
+        // Words that fit nowhere are left out once the spiral reaches the edge.
+        assert_eq!(cloud.place_within(&words, measure, |_| false), [None, None]);
+
+        let mut unbounded = cloud;
+        unbounded.max_radius = f32::INFINITY;
+        assert_eq!(unbounded.place(&words, measure), [None, None]);
+    }
+}